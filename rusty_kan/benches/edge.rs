@@ -0,0 +1,21 @@
+//! Benchmark of the batched forward pass of an edge against evaluating it one input at a time.
+//! Run with `cargo bench --bench edge`.
+
+use criterion::{criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion, Throughput};
+use rusty_kan::bench_utils;
+use rusty_kan::data_structures::{edge::Edge, vector::Vector};
+
+fn edge(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("edge");
+    for samples in [64, 1024, 16384] {
+        let inputs: Vector = bench_utils::grid(samples);
+        let mut edge: Edge = bench_utils::edge(32, 3, 42);
+        group.throughput(Throughput::Elements(samples as u64));
+        group.bench_function(BenchmarkId::new("forward_batch", samples), |b| b.iter(|| edge.forward_batch(inputs.clone()).0));
+        group.bench_function(BenchmarkId::new("forward", samples), |b| b.iter(|| inputs.iter().map(|&t| edge.forward(t)).sum::<f64>()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, edge);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+//! Benchmark of the forward pass of a single layer and of a whole KAN of increasing widths.
+//! Run with `cargo bench --bench layer`.
+
+use criterion::{criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion, Throughput};
+use rusty_kan::bench_utils;
+use rusty_kan::data_structures::matrix::Matrix;
+use rusty_kan::kan::KAN;
+
+const SAMPLES: usize = 256;
+
+fn layer(c: &mut Criterion) {
+    let inputs: Matrix = bench_utils::regression(SAMPLES, 42).inputs;
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("layer");
+    group.throughput(Throughput::Elements(SAMPLES as u64));
+    for width in [4, 16, 64] {
+        let kan: KAN = bench_utils::kan(&[4, width, 1], 42);
+        let layer_inputs: Vec<Matrix> = (0..SAMPLES).map(|i| bench_utils::layer_input(&kan, &inputs.row(i))).collect();
+        group.bench_function(BenchmarkId::new("layer forward", width), |b| b.iter(|| layer_inputs.iter().map(|input| kan.layers[0].borrow().forward(input.clone()).unwrap()).collect::<Vec<Matrix>>()));
+        group.bench_function(BenchmarkId::new("kan predict", width), |b| b.iter(|| kan.predict(&inputs).unwrap()));
+        group.bench_function(BenchmarkId::new("kan forward_batch", width), |b| b.iter(|| kan.forward_batch(&inputs)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, layer);
+criterion_main!(benches);
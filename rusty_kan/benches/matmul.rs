@@ -0,0 +1,36 @@
+//! Benchmark of the blocked matrix multiplication against a naive triple loop on 512 x 512 matrices.
+//! Run with `cargo bench --bench matmul`.
+
+use criterion::{criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion};
+use rusty_kan::data_structures::{matrix::Matrix, vector::Vector};
+
+const SIZE: usize = 512;
+
+/// The textbook i-j-k triple loop, as a baseline.
+fn naive(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result: Matrix = Matrix::zeros(a.rows, b.cols);
+    for i in 0..a.rows {
+        for j in 0..b.cols {
+            let mut sum: f64 = 0.0;
+            for k in 0..a.cols {
+                sum += a[i][k] * b[k][j];
+            }
+            result[i][j] = sum;
+        }
+    }
+    result
+}
+
+fn matmul(c: &mut Criterion) {
+    let a: Matrix = Matrix::new((0..SIZE).map(|i| Vector::new((0..SIZE).map(|j| ((i * 31 + j * 17) % 97) as f64 / 97.0).collect())).collect());
+    let b: Matrix = Matrix::new((0..SIZE).map(|i| Vector::new((0..SIZE).map(|j| ((i * 13 + j * 7) % 89) as f64 / 89.0 - 0.5).collect())).collect());
+
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("matmul 512 x 512");
+    group.sample_size(10);
+    group.bench_function("naive", |bencher| bencher.iter(|| naive(&a, &b)));
+    group.bench_function("blocked", |bencher| bencher.iter(|| &a * &b));
+    group.finish();
+}
+
+criterion_group!(benches, matmul);
+criterion_main!(benches);
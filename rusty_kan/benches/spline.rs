@@ -0,0 +1,28 @@
+//! Benchmark of evaluating B-splines of increasing grid sizes, which exercises the memoized basis functions.
+//! Run with `cargo bench --bench spline`.
+
+use criterion::{criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion, Throughput};
+use rusty_kan::bench_utils;
+use rusty_kan::data_structures::{spline::BSpline, vector::Vector};
+
+const POINTS: usize = 1024;
+
+fn spline(c: &mut Criterion) {
+    let ts: Vector = bench_utils::grid(POINTS);
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("spline");
+    group.throughput(Throughput::Elements(POINTS as u64));
+    for num_control_points in [8, 32, 128] {
+        let mut spline: BSpline = bench_utils::spline(num_control_points, 3, 42);
+        // The memo is cleared before every iteration, so the first evaluation at every point is timed
+        group.bench_function(BenchmarkId::new("eval cold", num_control_points), |b| b.iter(|| {
+            spline.clear_memo();
+            ts.iter().map(|&t| spline.eval(t)).sum::<f64>()
+        }));
+        group.bench_function(BenchmarkId::new("eval memoized", num_control_points), |b| b.iter(|| ts.iter().map(|&t| spline.eval(t)).sum::<f64>()));
+        group.bench_function(BenchmarkId::new("basis_matrix", num_control_points), |b| b.iter(|| spline.basis_matrix(&ts)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, spline);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+//! Benchmark of one training epoch of a KAN on a regression dataset, the end-to-end cost of the forward and backward passes and the updates.
+//! Run with `cargo bench --bench training`.
+
+use criterion::{criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion, Throughput};
+use rusty_kan::bench_utils;
+use rusty_kan::datasets::dataset::Dataset;
+use rusty_kan::loss::Loss;
+use rusty_kan::trainer::Trainer;
+
+const SAMPLES: usize = 256;
+
+fn training(c: &mut Criterion) {
+    let dataset: Dataset = bench_utils::regression(SAMPLES, 42);
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("training");
+    group.throughput(Throughput::Elements(SAMPLES as u64));
+    group.sample_size(10);
+    for width in [4, 16] {
+        let mut trainer: Trainer = Trainer::new(bench_utils::kan(&[4, width, 1], 42), 0.01, Loss::MeanSquaredError, 42);
+        group.bench_function(BenchmarkId::new("epoch", width), |b| b.iter(|| trainer.fit(&dataset.inputs, &dataset.targets, 1).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, training);
+criterion_main!(benches);
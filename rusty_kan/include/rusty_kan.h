@@ -0,0 +1,67 @@
+#ifndef RUSTY_KAN_H
+#define RUSTY_KAN_H
+
+/* Generated with cbindgen from src/ffi.rs. Do not edit. */
+
+#include <stddef.h>
+
+// An opaque handle to a KAN loaded for inference through the C interface of the `ffi` feature.
+// The C header is `include/rusty_kan.h`, generated with `cbindgen --config cbindgen.toml --output include/rusty_kan.h`.
+typedef struct KanModel KanModel;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Load a KAN from a JSON file written by `KAN::save_json`.
+//
+// # Safety
+//
+// `path` must be a valid pointer to a NUL-terminated string.
+//
+// # Returns
+//
+// * A handle to the model, to be released with `kan_free`, or NULL if the file cannot be read or does not describe a KAN.
+struct KanModel *kan_load(const char *path);
+
+// The number of input values of a model, or 0 if `model` is NULL.
+//
+// # Safety
+//
+// `model` must be NULL or a handle returned by `kan_load` that has not been freed.
+size_t kan_num_inputs(const struct KanModel *model);
+
+// The number of output values of a model, or 0 if `model` is NULL.
+//
+// # Safety
+//
+// `model` must be NULL or a handle returned by `kan_load` that has not been freed.
+size_t kan_num_outputs(const struct KanModel *model);
+
+// Compute the output values of a model for one sample. A model can be used from several threads at once.
+//
+// # Safety
+//
+// `model` must be a handle returned by `kan_load` that has not been freed, `input` must point to `input_len` doubles, and `output` must point to `output_len` writable doubles.
+//
+// # Returns
+//
+// * 0 on success, or -1 if a pointer is NULL, the lengths do not match the model, or the evaluation fails.
+int kan_forward(const struct KanModel *model,
+                const double *input,
+                size_t input_len,
+                double *output,
+                size_t output_len);
+
+// Release a model. Does nothing if `model` is NULL.
+//
+// # Safety
+//
+// `model` must be NULL or a handle returned by `kan_load` that has not been freed.
+void kan_free(struct KanModel *model);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUSTY_KAN_H */
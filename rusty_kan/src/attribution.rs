@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A report of the attribution score of every edge of a KAN, the mean absolute value of the edge over the samples of the last `KAN::update_edge_scores`.
+/// The scores are keyed by (layer, start, end), where layer is the index of the layer in the KAN whose nodes the edge feeds into, and start and end are the indices of the nodes the edge connects.
+///
+/// An edge with a small score contributes little to the outputs, so it is a candidate for pruning.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EdgeScores {
+    pub scores: BTreeMap<(usize, usize, usize), f64>,
+}
+
+impl EdgeScores {
+    /// Returns the score of the edge from node `start` to node `end` of the layer, if it has been scored.
+    pub fn get(&self, layer: usize, start: usize, end: usize) -> Option<f64> {
+        self.scores.get(&(layer, start, end)).copied()
+    }
+
+    /// Returns the number of scored edges.
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Returns true if no edges have been scored.
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Returns an iterator over the keys (layer, start, end) and scores of the edges, in order of layer, start, and end.
+    pub fn iter(&self) -> impl Iterator<Item = (&(usize, usize, usize), &f64)> {
+        self.scores.iter()
+    }
+
+    /// Find the edges whose score falls below a threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - A scalar threshold.
+    ///
+    /// # Returns
+    ///
+    /// * The keys (layer, start, end) of the edges with a score smaller than the threshold, in order of layer, start, and end.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let weak = kan.edge_scores().below(1e-2);
+    /// ```
+    pub fn below(&self, threshold: f64) -> Vec<(usize, usize, usize)> {
+        self.scores.iter().filter(|(_, &score)| score < threshold).map(|(&key, _)| key).collect()
+    }
+}
+
+impl fmt::Display for EdgeScores {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "layer start end score")?;
+        for (&(layer, start, end), score) in self.scores.iter() {
+            writeln!(f, "{} {} {} {}", layer, start, end, score)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix, spline::BSpline, edge::Edge};
+use crate::builder::KANBuilder;
+use crate::datasets::{dataset::Dataset, toy::Toy};
+use crate::kan::KAN;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Generate equally spaced inputs over [0, 1], the default domain of the splines.
+pub fn grid(n: usize) -> Vector {
+    Vector::new((0..n).map(|i| i as f64 / (n.max(2) - 1) as f64).collect())
+}
+
+/// Generate a random B-spline with uniform knots over [0, 1].
+///
+/// # Arguments
+///
+/// * `num_control_points` - The number of control points, which sets the size of the grid.
+///
+/// * `degree` - The degree of the spline, smaller than the number of control points.
+///
+/// * `seed` - A seed for the random control points.
+///
+/// # Example
+///
+/// ```
+/// let spline = bench_utils::spline(64, 3, 42);
+/// ```
+pub fn spline(num_control_points: usize, degree: usize, seed: u64) -> BSpline {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(seed);
+    BSpline::new(Vector::random_uniform_with_rng(num_control_points, -1.0, 1.0, &mut rng), degree)
+}
+
+/// Generate an edge with a random spline activation of the given size.
+///
+/// # Example
+///
+/// ```
+/// let mut edge = bench_utils::edge(64, 3, 42);
+/// ```
+pub fn edge(num_control_points: usize, degree: usize, seed: u64) -> Edge {
+    Edge::new(0, 0, spline(num_control_points, degree, seed), 0)
+}
+
+/// Generate a KAN with the given widths, from the number of inputs to the number of outputs.
+///
+/// # Example
+///
+/// ```
+/// let kan = bench_utils::kan(&[4, 16, 16, 1], 42);
+/// ```
+pub fn kan(widths: &[usize], seed: u64) -> KAN {
+    if widths.len() < 2 {
+        panic!("A KAN must have at least an input and an output width.");
+    }
+    widths[1..].iter().fold(KANBuilder::new(widths[0]), |builder, &width| builder.add_layer(width)).seed(seed).build()
+}
+
+/// The input of the first layer of a KAN for one sample, which `Layer::forward` takes.
+pub fn layer_input(kan: &KAN, input: &Vector) -> Matrix {
+    kan.layers[0].borrow().route(input)
+}
+
+/// Generate a regression dataset of `Toy::ExpSinFour`, whose inputs are scaled onto [0, 1], as a training workload for a KAN with 4 inputs and 1 output.
+///
+/// # Example
+///
+/// ```
+/// let dataset = bench_utils::regression(1024, 42);
+/// ```
+pub fn regression(samples: usize, seed: u64) -> Dataset {
+    let dataset: Dataset = Toy::ExpSinFour.generate(samples, 0.0, seed);
+    let inputs: Matrix = Matrix::from_elements(dataset.inputs.rows, dataset.inputs.cols, dataset.inputs.elements.iter().map(|x| 0.5 * (x + 1.0)).collect());
+    Dataset::new(inputs, dataset.targets)
+}
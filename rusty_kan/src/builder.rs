@@ -0,0 +1,456 @@
+use crate::data_structures::{edge::{Edge, BaseFn}, node::{Node, Aggregation}, layer::Layer, norm::{Norm, NormKind}, activation::Basis};
+use crate::init::Init;
+use crate::kan::KAN;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The edges of a skip connection, which feed the values two layers back into a layer alongside the values of the previous layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Skip {
+    /// Every node two layers back is connected to every node of the layer with a trainable edge of the basis family of the layer.
+    Spline,
+    /// The i-th node two layers back is connected to the i-th node of the layer with a frozen identity edge, as in a residual network.
+    Identity,
+}
+
+/// A builder for fully connected KANs of arbitrary depth.
+/// It is represented as the widths of the layers (starting with the number of inputs), the basis family and the grid of the edges of every layer, the number of product nodes, the normalization, the skip connection, and the dropout probability of every layer, the default grid and the base function of the edges, the initialisation scheme of the control points, and an optional seed for reproducible initialisation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KANBuilder {
+    pub widths: Vec<usize>,
+    pub bases: Vec<Basis>, // Basis family of the incoming edges of every layer
+    pub grids: Vec<Option<(usize, usize)>>, // Degree and grid size of the incoming edges of every layer, if they differ from those of the builder
+    pub products: Vec<usize>, // Number of product nodes at the end of every layer
+    pub norms: Vec<Option<NormKind>>, // Normalization of the values of the nodes of every layer
+    pub skips: Vec<Option<Skip>>, // Skip connection from two layers back into every layer
+    pub dropouts: Vec<f64>, // Dropout probability of the incoming edges of every layer
+    pub degree: usize, // Degree of the B-spline edges of the layers without their own grid
+    pub grid_size: usize, // Number of control points or Gaussians of the edges of the layers without their own grid
+    pub base: BaseFn, // Base function of every edge
+    pub init: Init,
+    pub seed: Option<u64>,
+}
+
+impl KANBuilder {
+    /// Create a new builder for a KAN with a given number of inputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A scalar representing the number of inputs.
+    ///
+    /// # Returns
+    ///
+    /// * A builder without layers, using B-splines of degree 2 with 5 control points, the SiLU base function, the default initialisation, and no seed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let kan = KANBuilder::new(2).add_layer(3).add_layer(1).build();
+    /// ```
+    pub fn new(inputs: usize) -> KANBuilder {
+        KANBuilder { widths: vec![inputs], bases: Vec::new(), grids: Vec::new(), products: Vec::new(), norms: Vec::new(), skips: Vec::new(), dropouts: Vec::new(), degree: 2, grid_size: 5, base: BaseFn::Silu, init: Init::default(), seed: None }
+    }
+
+    /// Add a layer that is fully connected to the previous layer with B-spline edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - A scalar representing the number of nodes in the layer.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the added layer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(4).add_layer(8).add_layer(3);
+    /// ```
+    pub fn add_layer(self, width: usize) -> KANBuilder {
+        self.add_layer_with_basis(width, Basis::BSpline)
+    }
+
+    /// Add a layer that is fully connected to the previous layer with edges of a given basis family.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - A scalar representing the number of nodes in the layer.
+    ///
+    /// * `basis` - The basis family of the incoming edges of the layer.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the added layer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(4).add_layer_with_basis(8, Basis::Rbf { trainable_centers: false }).add_layer(3);
+    /// ```
+    pub fn add_layer_with_basis(mut self, width: usize, basis: Basis) -> KANBuilder {
+        self.widths.push(width);
+        self.bases.push(basis);
+        self.grids.push(None);
+        self.products.push(0);
+        self.norms.push(None);
+        self.skips.push(None);
+        self.dropouts.push(0.0);
+        self
+    }
+
+    /// Add a layer of sum and product nodes that is fully connected to the previous layer with B-spline edges, as in MultKAN.
+    /// The sum nodes come first, followed by the product nodes, which multiply the values of their incoming edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `sums` - A scalar representing the number of sum nodes in the layer.
+    ///
+    /// * `products` - A scalar representing the number of product nodes in the layer.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the added layer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_mult_layer(2, 1).add_layer(1);
+    /// ```
+    pub fn add_mult_layer(mut self, sums: usize, products: usize) -> KANBuilder {
+        self = self.add_layer(sums + products);
+        *self.products.last_mut().unwrap() = products;
+        self
+    }
+
+    /// Normalize the values of the nodes of the last added layer before they are passed to the next layer.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of normalization.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the normalization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_layer(8).norm(NormKind::Layer).add_layer(1);
+    /// ```
+    pub fn norm(mut self, kind: NormKind) -> KANBuilder {
+        match self.norms.last_mut() {
+            Some(norm) => *norm = Some(kind),
+            None => panic!("A normalization must follow a layer."),
+        }
+        self
+    }
+
+    /// Add skip edges into the last added layer from the layer two levels back, or from the inputs if it is the second layer, so the signal reaches deep layers directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Whether the skip edges are trainable edges or identities.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the skip connection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_layer(4).add_layer(2).skip(Skip::Identity).add_layer(1);
+    /// ```
+    pub fn skip(mut self, kind: Skip) -> KANBuilder {
+        if self.skips.len() < 2 {
+            panic!("A skip connection must follow at least two layers.");
+        }
+        *self.skips.last_mut().unwrap() = Some(kind);
+        self
+    }
+
+    /// Mask every incoming edge of the last added layer, including its skip edges, with a given probability in the training steps, see `KAN::sample_dropout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `probability` - A scalar in [0, 1).
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the dropout probability.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_layer(8).dropout(0.1).add_layer(1);
+    /// ```
+    pub fn dropout(mut self, probability: f64) -> KANBuilder {
+        if !(0.0..1.0).contains(&probability) {
+            panic!("The dropout probability must be at least 0 and smaller than 1.");
+        }
+        match self.dropouts.last_mut() {
+            Some(dropout) => *dropout = probability,
+            None => panic!("A dropout probability must follow a layer."),
+        }
+        self
+    }
+
+    /// Give the incoming edges of the last added layer, including its skip edges, their own grid instead of that of the builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `degree` - The degree of the B-splines, which a Gaussian RBF ignores.
+    ///
+    /// * `grid_size` - The number of control points of the B-splines, at least `degree + 1`, or the number of Gaussians of a Gaussian RBF, at least 2.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the grid of the layer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_layer(8).layer_grid(3, 10).add_layer(1);
+    /// ```
+    pub fn layer_grid(mut self, degree: usize, grid_size: usize) -> KANBuilder {
+        match self.grids.last_mut() {
+            Some(grid) => *grid = Some((degree, grid_size)),
+            None => panic!("A grid must follow a layer."),
+        }
+        self
+    }
+
+    /// Set the degree of the B-spline edges of every layer without its own grid, see `KANBuilder::layer_grid`.
+    ///
+    /// # Arguments
+    ///
+    /// * `degree` - The degree of the B-splines.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the given degree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_layer(1).degree(3).grid_size(8);
+    /// ```
+    pub fn degree(mut self, degree: usize) -> KANBuilder {
+        self.degree = degree;
+        self
+    }
+
+    /// Set the number of control points, or of Gaussians for a Gaussian RBF, of the edges of every layer without its own grid, see `KANBuilder::layer_grid`.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid_size` - The number of control points or Gaussians.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the given grid size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_layer(1).grid_size(10);
+    /// ```
+    pub fn grid_size(mut self, grid_size: usize) -> KANBuilder {
+        self.grid_size = grid_size;
+        self
+    }
+
+    /// The degree and grid size of the incoming edges of a layer, indexed as in `KAN::layers`.
+    pub fn grid_of(&self, layer: usize) -> (usize, usize) {
+        self.grids[layer].unwrap_or((self.degree, self.grid_size))
+    }
+
+    /// Set the base function of every edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - A base function.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the given base function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_layer(1).base(BaseFn::Tanh);
+    /// ```
+    pub fn base(mut self, base: BaseFn) -> KANBuilder {
+        self.base = base;
+        self
+    }
+
+    /// Set the initialisation scheme of the control points.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - An initialisation scheme.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the given initialisation scheme.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_layer(1).init(Init::Xavier);
+    /// ```
+    pub fn init(mut self, init: Init) -> KANBuilder {
+        self.init = init;
+        self
+    }
+
+    /// Set the seed of the random number generator used to initialise the control points.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - A seed for the random number generator.
+    ///
+    /// # Returns
+    ///
+    /// * The builder with the given seed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let builder = KANBuilder::new(2).add_layer(1).seed(42);
+    /// ```
+    pub fn seed(mut self, seed: u64) -> KANBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Build the KAN. Every edge has a B-spline, or a Gaussian RBF in layers with an RBF basis, with the grid of its layer, by default of degree 2 with 5 control points or Gaussians.
+    /// Without a seed, the control points are drawn from the system random number generator, so a seed is required on wasm32.
+    ///
+    /// # Returns
+    ///
+    /// * A KAN with the configured layers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let kan = KANBuilder::new(2).add_layer(3).add_layer(1).seed(42).build();
+    /// ```
+    pub fn build(&self) -> KAN {
+        match self.seed {
+            Some(seed) => self.build_with_rng(&mut ChaCha8Rng::seed_from_u64(seed)),
+            #[cfg(not(target_arch = "wasm32"))]
+            None => self.build_with_rng(&mut rand::thread_rng()),
+            #[cfg(target_arch = "wasm32")]
+            None => panic!("A seed is required to build a KAN on wasm32, which has no system random number generator."),
+        }
+    }
+
+    /// Build the KAN, drawing the control points from the given random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A random number generator.
+    ///
+    /// # Returns
+    ///
+    /// * A KAN with the configured layers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let kan = KANBuilder::new(2).add_layer(1).build_with_rng(&mut rng);
+    /// ```
+    pub fn build_with_rng(&self, rng: &mut impl Rng) -> KAN {
+        if self.widths.len() < 2 {
+            panic!("The KAN must have at least one layer.");
+        }
+        if self.bases.len() != self.widths.len() - 1 {
+            panic!("Every layer must have a basis family.");
+        }
+        if self.grids.len() != self.bases.len() {
+            panic!("Every layer must have an optional grid.");
+        }
+        if self.products.len() != self.bases.len() || self.products.iter().zip(self.widths[1..].iter()).any(|(products, width)| products > width) {
+            panic!("Every layer must have at most as many product nodes as nodes.");
+        }
+        if self.norms.len() != self.bases.len() || self.norms.last().is_some_and(Option::is_some) {
+            panic!("Every normalization must be followed by another layer.");
+        }
+        if self.skips.len() != self.bases.len() || self.skips.iter().take(1).any(Option::is_some) {
+            panic!("Every skip connection must follow at least two layers.");
+        }
+        if (2..self.widths.len()).any(|l| self.skips[l - 1] == Some(Skip::Identity) && self.widths[l] != self.widths[l - 2]) {
+            panic!("A layer with identity skip edges must be as wide as the layer two levels back.");
+        }
+        if self.dropouts.len() != self.bases.len() {
+            panic!("Every layer must have a dropout probability.");
+        }
+        if self.widths.contains(&0) {
+            panic!("The number of inputs and the width of every layer must be greater than 0.");
+        }
+
+        let mut layers: Vec<Rc<RefCell<Layer>>> = Vec::with_capacity(self.widths.len() - 1);
+        // The nodes of every level, where the inputs have no nodes
+        let mut levels: Vec<Vec<Rc<RefCell<Node>>>> = vec![Vec::new()];
+        for l in 1..self.widths.len() {
+            let skip: Option<Skip> = self.skips[l - 1];
+            let skip_width: usize = if skip == Some(Skip::Spline) { self.widths[l - 2] } else { 0 };
+            let fan_in: usize = self.widths[l - 1] + skip_width;
+            let (degree, grid_size): (usize, usize) = self.grid_of(l - 1);
+            let mut nodes: Vec<Rc<RefCell<Node>>> = Vec::with_capacity(self.widths[l]);
+            for j in 0..self.widths[l] {
+                // Incoming edges from every node of the previous layer
+                let mut incoming_edges: Vec<Rc<RefCell<Edge>>> = Vec::with_capacity(fan_in);
+                for i in 0..self.widths[l - 1] {
+                    let mut edge: Edge = Edge::with_activation(i, j, self.bases[l - 1].sample_grid(degree, grid_size, self.init, fan_in, rng), l);
+                    edge.base = self.base;
+                    let edge: Rc<RefCell<Edge>> = Rc::new(RefCell::new(edge));
+                    if let Some(previous_node) = levels[l - 1].get(i) {
+                        previous_node.borrow_mut().add_outgoing(edge.clone());
+                    }
+                    incoming_edges.push(edge);
+                }
+                // Skip edges from the nodes two layers back
+                let starts: Vec<usize> = match skip {
+                    Some(Skip::Spline) => (0..skip_width).collect(),
+                    Some(Skip::Identity) => vec![j],
+                    None => vec![],
+                };
+                for i in starts {
+                    let mut edge: Edge = match skip {
+                        Some(Skip::Identity) => Edge::identity(i, j, l),
+                        _ => {
+                            let mut edge: Edge = Edge::with_activation(i, j, self.bases[l - 1].sample_grid(degree, grid_size, self.init, fan_in, rng), l);
+                            edge.base = self.base;
+                            edge
+                        }
+                    };
+                    edge.skip = true;
+                    let edge: Rc<RefCell<Edge>> = Rc::new(RefCell::new(edge));
+                    if let Some(skip_node) = levels[l - 2].get(i) {
+                        skip_node.borrow_mut().add_outgoing(edge.clone());
+                    }
+                    incoming_edges.push(edge);
+                }
+                let mut node: Node = Node::new(incoming_edges, Vec::new(), l);
+                if j >= self.widths[l] - self.products[l - 1] {
+                    node.aggregation = Aggregation::Product;
+                }
+                nodes.push(Rc::new(RefCell::new(node)));
+            }
+            let mut layer: Layer = Layer::new(nodes.clone());
+            layer.norm = self.norms[l - 1].map(|kind| Norm::new(kind, self.widths[l]));
+            layer.set_dropout(self.dropouts[l - 1]);
+            layers.push(Rc::new(RefCell::new(layer)));
+            levels.push(nodes);
+        }
+
+        let mut kan: KAN = KAN::new(layers);
+        kan.inputs = self.widths[0];
+        kan
+    }
+}
@@ -0,0 +1,303 @@
+use crate::kan::KAN;
+use crate::error::KanError;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// The state of a training run that is shared with the callbacks.
+/// Callbacks can read the progress of the run and change the learning rate or request an early stop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingState {
+    pub epoch: usize, // Index of the current epoch
+    pub learning_rate: f64, // Learning rate used for the next update
+    pub train_loss: Option<f64>, // Mean training loss of the last completed epoch
+    pub validation_loss: Option<f64>, // Validation loss of the last completed epoch, if a validation set is used
+    pub gradient_norm: Option<f64>, // Euclidean norm of the gradient of the last update, before the optimizer step
+    pub stop: bool, // Set to true to stop training after the current epoch
+}
+
+impl TrainingState {
+    /// Create a new training state at the first epoch with a given learning rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - A scalar representing the initial learning rate.
+    ///
+    /// # Returns
+    ///
+    /// * A training state at the first epoch with no recorded losses.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let state = TrainingState::new(0.01);
+    /// ```
+    pub fn new(learning_rate: f64) -> TrainingState {
+        TrainingState { epoch: 0, learning_rate, train_loss: None, validation_loss: None, gradient_norm: None, stop: false }
+    }
+}
+
+/// A callback is a hook into the training loop of `Trainer::fit`.
+/// All methods have empty default implementations, so a callback only implements the events it needs.
+///
+/// # Example
+///
+/// ```
+/// struct Logger;
+///
+/// impl Callback for Logger {
+///     fn on_epoch_end(&mut self, _kan: &KAN, state: &mut TrainingState) {
+///         println!("Epoch {}: {:?}", state.epoch, state.train_loss);
+///     }
+/// }
+/// ```
+pub trait Callback {
+    /// Called before the first sample of every epoch.
+    fn on_epoch_start(&mut self, _kan: &KAN, _state: &mut TrainingState) {}
+
+    /// Called after every epoch, once the training and validation losses are recorded in the state.
+    fn on_epoch_end(&mut self, _kan: &KAN, _state: &mut TrainingState) {}
+
+    /// Called after every update of the edges with the index of the batch within the epoch and its loss.
+    fn on_batch_end(&mut self, _kan: &KAN, _batch: usize, _loss: f64, _state: &mut TrainingState) {}
+}
+
+/// The file format of a `TrainingLogger`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Comma-separated values with a header line, where missing values are empty fields.
+    Csv,
+    /// One JSON object per line, where missing values are null.
+    Jsonl,
+}
+
+/// One line of a training log.
+#[derive(Serialize)]
+struct LogRecord {
+    epoch: usize,
+    train_loss: Option<f64>,
+    validation_loss: Option<f64>,
+    learning_rate: f64,
+    gradient_norm: Option<f64>,
+    wall_time: f64,
+}
+
+/// A training logger is a callback that writes one record per epoch with the epoch, the training and validation loss, the learning rate, the norm of the gradient of the last update, and the seconds since the first epoch started.
+/// It is represented as the writer of the log, its format, the start time of the first epoch, and the first error that occurred while writing.
+///
+/// Every record is flushed at the end of its epoch, so the log of a long run can be followed while it trains. Since callbacks cannot fail, a write error stops the logging and is kept for `error`.
+pub struct TrainingLogger {
+    writer: Box<dyn Write>,
+    format: LogFormat,
+    start: Option<Instant>, // Start of the first epoch
+    error: Option<KanError>,
+}
+
+impl TrainingLogger {
+    /// Create a new training logger that writes to any writer, e.g. standard output.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer of the log.
+    ///
+    /// * `format` - The format of the log.
+    ///
+    /// # Returns
+    ///
+    /// * A training logger that writes the header of a CSV log immediately and a record at the end of every epoch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// trainer.add_callback(Box::new(TrainingLogger::new(Box::new(std::io::stdout()), LogFormat::Jsonl)));
+    /// ```
+    pub fn new(writer: Box<dyn Write>, format: LogFormat) -> TrainingLogger {
+        let mut logger: TrainingLogger = TrainingLogger { writer, format, start: None, error: None };
+        if format == LogFormat::Csv {
+            logger.write_line("epoch,train_loss,validation_loss,learning_rate,gradient_norm,wall_time".to_string());
+        }
+        logger
+    }
+
+    /// Create a new training logger that writes to a file, replacing an existing file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the log file.
+    ///
+    /// * `format` - The format of the log.
+    ///
+    /// # Returns
+    ///
+    /// * A result containing the training logger, or an error if the file cannot be created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// trainer.add_callback(Box::new(TrainingLogger::create("run.csv", LogFormat::Csv).unwrap()));
+    /// ```
+    pub fn create<P: AsRef<Path>>(path: P, format: LogFormat) -> Result<TrainingLogger, KanError> {
+        let file: File = File::create(path).map_err(|_| KanError::Io("Failed to create the log file."))?;
+        let logger: TrainingLogger = TrainingLogger::new(Box::new(BufWriter::new(file)), format);
+        match logger.error {
+            Some(err) => Err(err),
+            None => Ok(logger),
+        }
+    }
+
+    /// Returns the first error that occurred while writing the log, after which nothing more is written.
+    pub fn error(&self) -> Option<&KanError> {
+        self.error.as_ref()
+    }
+
+    /// Write and flush one line, unless an earlier write failed.
+    fn write_line(&mut self, line: String) {
+        if self.error.is_none() && writeln!(self.writer, "{}", line).and_then(|_| self.writer.flush()).is_err() {
+            self.error = Some(KanError::Io("Failed to write the training log."));
+        }
+    }
+}
+
+impl Callback for TrainingLogger {
+    fn on_epoch_start(&mut self, _kan: &KAN, _state: &mut TrainingState) {
+        self.start.get_or_insert_with(Instant::now);
+    }
+
+    fn on_epoch_end(&mut self, _kan: &KAN, state: &mut TrainingState) {
+        let record: LogRecord = LogRecord {
+            epoch: state.epoch,
+            train_loss: state.train_loss,
+            validation_loss: state.validation_loss,
+            learning_rate: state.learning_rate,
+            gradient_norm: state.gradient_norm,
+            wall_time: self.start.map_or(0.0, |start| start.elapsed().as_secs_f64()),
+        };
+        let line: String = match self.format {
+            LogFormat::Csv => {
+                let field = |value: Option<f64>| value.map_or(String::new(), |value| value.to_string());
+                format!("{},{},{},{},{},{}", record.epoch, field(record.train_loss), field(record.validation_loss), record.learning_rate, field(record.gradient_norm), record.wall_time)
+            }
+            LogFormat::Jsonl => serde_json::to_string(&record).expect("A log record is always serializable."),
+        };
+        self.write_line(line);
+    }
+}
+
+/// A progress bar is a callback that redraws one line after every batch with the progress of a run, the loss of the last batch, and the estimated time until the run ends.
+/// It is represented as the number of epochs of the run, the width of the bar, the writer of the line, the start of the run, and the number of batches per epoch, which is known after the first epoch.
+///
+/// The line is redrawn in place with a carriage return, so it should be written to a terminal. It ends after the last epoch or when the run stops early.
+pub struct ProgressBar {
+    epochs: usize,
+    width: usize, // Number of characters of the bar
+    writer: Box<dyn Write>,
+    start: Option<(Instant, usize)>, // Start time and epoch counter of the trainer at the start of the run
+    batches: Option<usize>, // Number of batches per epoch, counted in the first epoch
+    batches_done: usize, // Number of batches of the current epoch
+}
+
+impl ProgressBar {
+    /// Create a new progress bar for a run of a given number of epochs that writes to standard error.
+    ///
+    /// # Arguments
+    ///
+    /// * `epochs` - The number of epochs passed to `Trainer::fit`.
+    ///
+    /// # Returns
+    ///
+    /// * A progress bar with a bar of 30 characters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// trainer.add_callback(Box::new(ProgressBar::new(100)));
+    /// trainer.fit(&inputs, &targets, 100).unwrap();
+    /// ```
+    pub fn new(epochs: usize) -> ProgressBar {
+        ProgressBar::with_writer(epochs, Box::new(std::io::stderr()))
+    }
+
+    /// Create a new progress bar that writes to any writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `epochs` - The number of epochs passed to `Trainer::fit`.
+    ///
+    /// * `writer` - The writer of the line.
+    ///
+    /// # Returns
+    ///
+    /// * A progress bar with a bar of 30 characters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let progress = ProgressBar::with_writer(100, Box::new(std::io::stdout()));
+    /// ```
+    pub fn with_writer(epochs: usize, writer: Box<dyn Write>) -> ProgressBar {
+        ProgressBar { epochs, width: 30, writer, start: None, batches: None, batches_done: 0 }
+    }
+
+    /// The line of the progress bar for the current batch of an epoch of the trainer.
+    fn line(&self, epoch: usize, loss: Option<f64>) -> String {
+        let (start, first_epoch): (Instant, usize) = self.start.unwrap_or((Instant::now(), epoch));
+        let run_epoch: usize = epoch - first_epoch;
+        // Until the number of batches per epoch is known, only completed epochs count as progress
+        let fraction: f64 = match self.batches {
+            Some(batches) => (run_epoch as f64 + self.batches_done as f64 / batches.max(1) as f64) / self.epochs.max(1) as f64,
+            None => run_epoch as f64 / self.epochs.max(1) as f64,
+        }.min(1.0);
+        let filled: usize = (fraction * self.width as f64).round() as usize;
+        let mut line: String = format!("epoch {}/{} [{}{}] {:>3.0}%", run_epoch + 1, self.epochs, "#".repeat(filled), "-".repeat(self.width - filled), 100.0 * fraction);
+        line += &match self.batches {
+            Some(batches) => format!(" batch {}/{}", self.batches_done, batches),
+            None => format!(" batch {}", self.batches_done),
+        };
+        if let Some(loss) = loss {
+            line += &format!(" loss {:.4e}", loss);
+        }
+        if fraction > 0.0 {
+            let elapsed: f64 = start.elapsed().as_secs_f64();
+            line += &format!(" eta {}", format_duration(Duration::from_secs_f64(elapsed / fraction - elapsed)));
+        }
+        line
+    }
+
+    /// Redraw the line in place, ignoring write errors since the progress is only informative.
+    fn draw(&mut self, line: &str, end: bool) {
+        let _ = write!(self.writer, "\r{}{}", line, if end { "\n" } else { "" });
+        let _ = self.writer.flush();
+    }
+}
+
+/// Format a duration as minutes and seconds, with hours for long durations.
+fn format_duration(duration: Duration) -> String {
+    let seconds: u64 = duration.as_secs();
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+impl Callback for ProgressBar {
+    fn on_epoch_start(&mut self, _kan: &KAN, state: &mut TrainingState) {
+        self.start.get_or_insert((Instant::now(), state.epoch));
+        self.batches_done = 0;
+    }
+
+    fn on_batch_end(&mut self, _kan: &KAN, batch: usize, loss: f64, state: &mut TrainingState) {
+        self.batches_done = batch + 1;
+        let line: String = self.line(state.epoch, Some(loss));
+        self.draw(&line, false);
+    }
+
+    fn on_epoch_end(&mut self, _kan: &KAN, state: &mut TrainingState) {
+        self.batches.get_or_insert(self.batches_done);
+        let line: String = self.line(state.epoch, state.train_loss);
+        let first_epoch: usize = self.start.map_or(state.epoch, |(_, first_epoch)| first_epoch);
+        self.draw(&line, state.stop || state.epoch + 1 - first_epoch >= self.epochs);
+    }
+}
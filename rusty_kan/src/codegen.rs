@@ -0,0 +1,292 @@
+use crate::data_structures::{edge::Edge, node::Aggregation, spline::BSpline, rbf::GaussianRbf, symbolic::{Symbolic, LOG_FLOOR, RECIPROCAL_FLOOR}};
+use crate::error::KanError;
+use crate::kan::KAN;
+use crate::compiled::CompiledKAN;
+
+/// The evaluation code of the generated file, which mirrors `Edge::value` for every kind of activation.
+/// `{LOG_FLOOR}` and `{RECIPROCAL_FLOOR}` are replaced by the floors of the logarithm and reciprocal of the symbolic activations.
+const RUNTIME: &str = r#"
+/// Compute the values of all output nodes for the given input values.
+pub fn forward(input: &[f64]) -> [f64; OUTPUTS] {
+    assert_eq!(input.len(), INPUTS, "The number of inputs must match the model.");
+    let mut activations: [f64; MAX_WIDTH] = [0.0; MAX_WIDTH];
+    for (i, &x) in input.iter().enumerate() {
+        activations[i] = (x - OFFSET[i]) / SCALE[i];
+    }
+    for (layer, products) in LAYERS.iter().zip(PRODUCTS) {
+        let mut next: [f64; MAX_WIDTH] = [0.0; MAX_WIDTH];
+        for (j, node) in layer.iter().enumerate() {
+            let values = node.iter().map(|edge| edge.value(activations[edge.start]));
+            next[j] = if products[j] { values.product() } else { values.sum() };
+        }
+        activations = next;
+    }
+    let mut output: [f64; OUTPUTS] = [0.0; OUTPUTS];
+    output.copy_from_slice(&activations[..OUTPUTS]);
+    output
+}
+
+/// Compute the value of the first output node for the given input values.
+pub fn predict(input: &[f64]) -> f64 {
+    forward(input)[0]
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy)]
+enum InputMode { Raw, Clamp, Squash }
+
+#[allow(dead_code)]
+#[derive(Clone, Copy)]
+enum BaseFn { Silu, Identity, Tanh, None }
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq)]
+enum Extrapolation { Zero, Panic, Clamp, Linear }
+
+#[allow(dead_code)]
+#[derive(Clone, Copy)]
+enum SymbolicFn { Identity, Square, Cube, Quartic, Reciprocal, Sqrt, Exp, Log, Sin, Tanh, Abs, Gaussian, Sigmoid, Arctan }
+
+#[allow(dead_code)]
+enum Activation {
+    Spline { knots: &'static [f64], control_points: &'static [f64], weights: &'static [f64], degree: usize, domain: (f64, f64), extrapolation: Extrapolation },
+    Rbf { amplitudes: &'static [f64], centers: &'static [f64], widths: &'static [f64], domain: (f64, f64) },
+    Symbolic { function: SymbolicFn, params: [f64; 4], domain: (f64, f64) },
+}
+
+struct Edge {
+    start: usize,
+    base: BaseFn,
+    base_weight: f64,
+    spline_weight: f64,
+    input_mode: InputMode,
+    activation: Activation,
+}
+
+impl Edge {
+    fn value(&self, t: f64) -> f64 {
+        let domain: (f64, f64) = match self.activation {
+            Activation::Spline { domain, .. } | Activation::Rbf { domain, .. } | Activation::Symbolic { domain, .. } => domain,
+        };
+        let spline_input: f64 = match self.input_mode {
+            InputMode::Raw => t,
+            InputMode::Clamp => t.clamp(domain.0, domain.1),
+            InputMode::Squash => domain.0 + (domain.1 - domain.0) / (1.0 + (-t).exp()),
+        };
+        let base: f64 = match self.base {
+            BaseFn::Silu => t / (1.0 + (-t).exp()),
+            BaseFn::Identity => t,
+            BaseFn::Tanh => t.tanh(),
+            BaseFn::None => 0.0,
+        };
+        self.base_weight * base + self.spline_weight * self.activation.value(spline_input)
+    }
+}
+
+impl Activation {
+    fn value(&self, t: f64) -> f64 {
+        match *self {
+            Activation::Spline { knots, control_points, weights, degree, domain, extrapolation } => {
+                let u: f64 = (t - domain.0) / (domain.1 - domain.0);
+                let first: f64 = knots[0];
+                let last: f64 = knots[knots.len() - 1];
+                let bound: Option<f64> = if u < first { Some(first) } else if u > last { Some(last) } else { None };
+                if bound.is_some() && extrapolation == Extrapolation::Panic {
+                    panic!("The parameter value lies outside the range of the knots.");
+                }
+                let n: usize = control_points.len();
+                let mut row: [f64; MAX_KNOTS] = [0.0; MAX_KNOTS];
+                match (bound, extrapolation) {
+                    (Some(bound), Extrapolation::Clamp) => row = rational_row(knots, weights, degree, n, bound).0,
+                    (Some(bound), Extrapolation::Linear) => {
+                        let (values, slopes) = rational_row(knots, weights, degree, n, bound);
+                        for i in 0..n {
+                            row[i] = values[i] + slopes[i] * (u - bound);
+                        }
+                    }
+                    _ => row = rational_row(knots, weights, degree, n, u).0,
+                }
+                (0..n).map(|i| row[i] * control_points[i]).sum()
+            }
+            Activation::Rbf { amplitudes, centers, widths, .. } => (0..amplitudes.len()).map(|i| {
+                let z: f64 = (t - centers[i]) / widths[i];
+                amplitudes[i] * (-z * z).exp()
+            }).sum(),
+            Activation::Symbolic { function, params, .. } => {
+                let x: f64 = params[0] * t + params[1];
+                let y: f64 = match function {
+                    SymbolicFn::Identity => x,
+                    SymbolicFn::Square => x * x,
+                    SymbolicFn::Cube => x * x * x,
+                    SymbolicFn::Quartic => x * x * x * x,
+                    SymbolicFn::Reciprocal => 1.0 / if x.abs() < {RECIPROCAL_FLOOR} { f64::copysign({RECIPROCAL_FLOOR}, x) } else { x },
+                    SymbolicFn::Sqrt => x.max(0.0).sqrt(),
+                    SymbolicFn::Exp => x.exp(),
+                    SymbolicFn::Log => x.max({LOG_FLOOR}).ln(),
+                    SymbolicFn::Sin => x.sin(),
+                    SymbolicFn::Tanh => x.tanh(),
+                    SymbolicFn::Abs => x.abs(),
+                    SymbolicFn::Gaussian => (-x * x).exp(),
+                    SymbolicFn::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+                    SymbolicFn::Arctan => x.atan(),
+                };
+                params[2] * y + params[3]
+            }
+        }
+    }
+}
+
+/// The values and derivatives of the n basis functions at a parameter value t, divided by their weighted sum for a rational B-spline.
+fn rational_row(knots: &[f64], weights: &[f64], degree: usize, n: usize, t: f64) -> ([f64; MAX_KNOTS], [f64; MAX_KNOTS]) {
+    let values: [f64; MAX_KNOTS] = basis_row(knots, t, degree);
+    let mut slopes: [f64; MAX_KNOTS] = [0.0; MAX_KNOTS];
+    if degree > 0 {
+        let lower: [f64; MAX_KNOTS] = basis_row(knots, t, degree - 1);
+        for i in 0..n {
+            let left_width: f64 = knots[i + degree] - knots[i];
+            let right_width: f64 = knots[i + degree + 1] - knots[i + 1];
+            let left: f64 = if left_width != 0.0 { lower[i] / left_width } else { 0.0 };
+            let right: f64 = if right_width != 0.0 { lower[i + 1] / right_width } else { 0.0 };
+            slopes[i] = degree as f64 * (left - right);
+        }
+    }
+    if weights.is_empty() {
+        return (values, slopes);
+    }
+    let denominator: f64 = (0..n).map(|i| weights[i] * values[i]).sum();
+    if denominator == 0.0 {
+        return ([0.0; MAX_KNOTS], [0.0; MAX_KNOTS]);
+    }
+    let denominator_slope: f64 = (0..n).map(|i| weights[i] * slopes[i]).sum();
+    let mut rational: [f64; MAX_KNOTS] = [0.0; MAX_KNOTS];
+    let mut rational_slopes: [f64; MAX_KNOTS] = [0.0; MAX_KNOTS];
+    for i in 0..n {
+        rational[i] = weights[i] * values[i] / denominator;
+        rational_slopes[i] = weights[i] * (slopes[i] * denominator - values[i] * denominator_slope) / (denominator * denominator);
+    }
+    (rational, rational_slopes)
+}
+
+/// The values of the basis functions of a given degree at a parameter value t, by the Cox-de Boor recursion evaluated bottom-up.
+fn basis_row(knots: &[f64], t: f64, degree: usize) -> [f64; MAX_KNOTS] {
+    let m: usize = knots.len();
+    let last: f64 = knots[m - 1];
+    let mut values: [f64; MAX_KNOTS] = [0.0; MAX_KNOTS];
+    for i in 0..m - 1 {
+        let in_span: bool = knots[i] <= t && t < knots[i + 1];
+        let at_end: bool = t == last && knots[i] < knots[i + 1] && knots[i + 1] == last;
+        values[i] = if in_span || at_end { 1.0 } else { 0.0 };
+    }
+    for d in 1..=degree {
+        for i in 0..m - d - 1 {
+            let left: f64 = if knots[i + d] != knots[i] { (t - knots[i]) / (knots[i + d] - knots[i]) * values[i] } else { 0.0 };
+            let right: f64 = if knots[i + d + 1] != knots[i + 1] { (knots[i + d + 1] - t) / (knots[i + d + 1] - knots[i + 1]) * values[i + 1] } else { 0.0 };
+            values[i] = left + right;
+        }
+    }
+    values
+}
+"#;
+
+/// Generate the source of a dependency-free Rust module that evaluates a KAN, for embedding a trained model without the training machinery.
+/// The knots, control points, and other parameters are hardcoded, and the module exposes `INPUTS`, `OUTPUTS`, `forward(&[f64]) -> [f64; OUTPUTS]`, and `predict(&[f64]) -> f64`, the value of the first output.
+/// The generated code evaluates the edges like `Edge::value`, so it gives the same outputs as `CompiledKAN::forward`.
+///
+/// # Arguments
+///
+/// * `kan` - A KAN with at least one layer.
+///
+/// # Returns
+///
+/// * The source of the module, or an error if the KAN has no layers, a layer has a normalization, an edge is a skip edge, or an edge has an activation other than a B-spline, Gaussian RBF, or symbolic activation.
+///
+/// # Example
+///
+/// ```
+/// let source = codegen::rust(&kan).unwrap();
+/// fs::write("model.rs", source).unwrap();
+/// ```
+pub fn rust(kan: &KAN) -> Result<String, KanError> {
+    let compiled: CompiledKAN = kan.compile();
+    if compiled.layers.is_empty() {
+        return Err(KanError::InvalidConfig("The KAN has no layers."));
+    }
+    if compiled.norms.iter().any(Option::is_some) {
+        return Err(KanError::Unsupported("Layers with a normalization cannot be generated."));
+    }
+    if compiled.layers.iter().flatten().flatten().any(|edge| edge.skip) {
+        return Err(KanError::Unsupported("Skip edges cannot be generated."));
+    }
+    let (inputs, outputs): (usize, usize) = (compiled.num_inputs(), compiled.num_outputs());
+    let (offsets, scales): (Vec<f64>, Vec<f64>) = match &compiled.scaler {
+        Some(scaler) => (0..inputs).map(|i| scaler.affine(i)).unzip(),
+        None => (vec![0.0; inputs], vec![1.0; inputs]),
+    };
+    let layers: Vec<Vec<Vec<Edge>>> = compiled.layers;
+    let products: Vec<String> = compiled.aggregations.iter().map(|nodes| {
+        format!("    &[{}],", nodes.iter().map(|&aggregation| (aggregation == Aggregation::Product).to_string()).collect::<Vec<String>>().join(", "))
+    }).collect();
+    let max_width: usize = layers.iter().map(|nodes| nodes.len()).chain(std::iter::once(inputs)).max().unwrap_or(0);
+    let max_knots: usize = layers.iter().flatten().flatten().filter_map(|edge| edge.activation.downcast_ref::<BSpline>().map(|spline| spline.knots.len())).max().unwrap_or(1);
+
+    let mut layer_sources: Vec<String> = Vec::with_capacity(layers.len());
+    for nodes in layers.iter() {
+        let mut node_sources: Vec<String> = Vec::with_capacity(nodes.len());
+        for node in nodes.iter() {
+            let edge_sources: Vec<String> = node.iter().map(edge).collect::<Result<Vec<String>, KanError>>()?;
+            node_sources.push(format!("        &[\n{}\n        ],", edge_sources.join("\n")));
+        }
+        layer_sources.push(format!("    &[\n{}\n    ],", node_sources.join("\n")));
+    }
+
+    let mut source: String = String::from("// Generated by rusty_kan from a trained KAN. The module has no dependencies.\n\n");
+    source += &format!("/// The number of input values.\npub const INPUTS: usize = {};\n\n", inputs);
+    source += &format!("/// The number of output values.\npub const OUTPUTS: usize = {};\n\n", outputs);
+    source += &format!("/// The offset and scale of the map x' = (x - offset) / scale of every input value.\nconst OFFSET: [f64; INPUTS] = [{}];\n\nconst SCALE: [f64; INPUTS] = [{}];\n\n", list(&offsets), list(&scales));
+    source += &format!("const MAX_WIDTH: usize = {};\n\n", max_width);
+    source += &format!("const MAX_KNOTS: usize = {};\n\n", max_knots);
+    source += &format!("/// The incoming edges of every node in every layer.\nstatic LAYERS: &[&[&[Edge]]] = &[\n{}\n];\n", layer_sources.join("\n"));
+    source += &format!("\n/// Whether every node in every layer multiplies the values of its incoming edges instead of adding them.\nstatic PRODUCTS: &[&[bool]] = &[\n{}\n];\n", products.join("\n"));
+    source += &RUNTIME.replace("{LOG_FLOOR}", &literal(LOG_FLOOR)).replace("{RECIPROCAL_FLOOR}", &literal(RECIPROCAL_FLOOR));
+    Ok(source)
+}
+
+/// The source of the constructor of an edge.
+fn edge(edge: &Edge) -> Result<String, KanError> {
+    let activation: String = if let Some(spline) = edge.activation.downcast_ref::<BSpline>() {
+        let weights: String = spline.weights.as_ref().map_or("&[]".to_string(), |weights| slice(&weights.elements));
+        format!("Activation::Spline {{ knots: {}, control_points: {}, weights: {}, degree: {}, domain: {}, extrapolation: Extrapolation::{:?} }}",
+            slice(&spline.knots.elements), slice(&spline.control_points.elements), weights, spline.degree, pair(spline.domain), spline.extrapolation)
+    } else if let Some(rbf) = edge.activation.downcast_ref::<GaussianRbf>() {
+        format!("Activation::Rbf {{ amplitudes: {}, centers: {}, widths: {}, domain: {} }}", slice(rbf.amplitudes()), slice(rbf.centers()), slice(rbf.widths()), pair(rbf.domain))
+    } else if let Some(symbolic) = edge.activation.downcast_ref::<Symbolic>() {
+        format!("Activation::Symbolic {{ function: SymbolicFn::{:?}, params: [{}], domain: {} }}", symbolic.function, list(&symbolic.params.elements), pair(symbolic.domain))
+    } else {
+        return Err(KanError::Unsupported("The activation of an edge cannot be generated as code."));
+    };
+    Ok(format!("            Edge {{ start: {}, base: BaseFn::{:?}, base_weight: {}, spline_weight: {}, input_mode: InputMode::{:?}, activation: {} }},",
+        edge.start, edge.base, literal(edge.base_weight), literal(edge.spline_weight), edge.input_mode, activation))
+}
+
+/// A Rust literal that evaluates to exactly the given number.
+fn literal(x: f64) -> String {
+    if x.is_nan() {
+        "f64::NAN".to_string()
+    } else if x.is_infinite() {
+        if x > 0.0 { "f64::INFINITY".to_string() } else { "f64::NEG_INFINITY".to_string() }
+    } else {
+        format!("{:?}", x)
+    }
+}
+
+fn list(values: &[f64]) -> String {
+    values.iter().map(|&x| literal(x)).collect::<Vec<String>>().join(", ")
+}
+
+fn slice(values: &[f64]) -> String {
+    format!("&[{}]", list(values))
+}
+
+fn pair(values: (f64, f64)) -> String {
+    format!("({}, {})", literal(values.0), literal(values.1))
+}
@@ -0,0 +1,254 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::Aggregation, norm::Norm};
+use crate::error::KanError;
+use crate::loss::softmax;
+use crate::scaler::Scaler;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A CompiledKAN is a frozen copy of a trained KAN for inference.
+/// It holds the edges by value instead of behind `Rc<RefCell<...>>` and evaluates them through shared references without touching their caches, so it is `Send` and `Sync` and can serve `forward` calls from many threads at once.
+/// It is created with `KAN::compile` and is not updated when the KAN is trained further.
+#[derive(Debug, Clone)]
+pub struct CompiledKAN {
+    pub layers: Vec<Vec<Vec<Edge>>>, // The incoming edges of every node in every layer
+    pub aggregations: Vec<Vec<Aggregation>>, // How every node in every layer combines the values of its incoming edges
+    pub norms: Vec<Option<Norm>>, // The normalization of the values of the nodes in every layer
+    pub scaler: Option<Scaler>, // Applied to the input values before the first layer
+    pub luts: Vec<Vec<Vec<Lut>>>, // Lookup tables of the incoming edges of every node in every layer, empty unless created with `with_luts`
+}
+
+/// A lookup table of the values of an edge at equally spaced inputs over the domain of its activation.
+/// It is represented as the range of the inputs and the value of the edge at every input, and is evaluated by linear interpolation between the two nearest entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut {
+    pub min: f64, // Input of the first entry
+    pub max: f64, // Input of the last entry
+    pub values: Vec<f64>,
+}
+
+impl Lut {
+    /// Create a lookup table of an edge by evaluating it at equally spaced inputs over the domain of its activation.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - The edge.
+    ///
+    /// * `resolution` - The number of entries, at least 2.
+    ///
+    /// # Returns
+    ///
+    /// * A lookup table with the values of the edge.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let lut = Lut::new(&edge, 1024);
+    /// ```
+    pub fn new(edge: &Edge, resolution: usize) -> Lut {
+        if resolution < 2 {
+            panic!("A lookup table must have at least 2 entries.");
+        }
+        let (min, max): (f64, f64) = edge.activation.domain();
+        let step: f64 = (max - min) / (resolution - 1) as f64;
+        Lut { min, max, values: (0..resolution).map(|i| edge.value(min + step * i as f64)).collect() }
+    }
+
+    /// Interpolate the value of the edge at an input linearly between the two nearest entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The input of the edge.
+    ///
+    /// # Returns
+    ///
+    /// * The interpolated value, or None if the input is outside of the range of the table.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let value = lut.value(0.5).unwrap_or_else(|| edge.value(0.5));
+    /// ```
+    pub fn value(&self, t: f64) -> Option<f64> {
+        if !(self.min..=self.max).contains(&t) {
+            return None;
+        }
+        let last: usize = self.values.len() - 1;
+        let position: f64 = if self.max > self.min { (t - self.min) / (self.max - self.min) * last as f64 } else { 0.0 };
+        let i: usize = (position.floor() as usize).min(last - 1);
+        let fraction: f64 = position - i as f64;
+        Some(self.values[i] * (1.0 - fraction) + self.values[i + 1] * fraction)
+    }
+}
+
+impl CompiledKAN {
+    /// The forward pass computes the values of all output nodes for the given input values.
+    /// Each incoming edge of a node receives the value of the node at its `start` index in the previous layer, or the input value at its `start` index in the first layer, mapped by the scaler if there is one.
+    /// A skip edge receives the value two layers back instead, which is the input value for the second layer.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector representing the input values to the first layer.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a vector where the i-th entry is the value of the i-th node in the last layer, or an error if an edge starts at a value that does not exist, e.g. because the input is too short.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let compiled = kan.compile();
+    /// let input = Vector::new(vec![1.0, 2.0]);
+    ///
+    /// let output = compiled.forward(&input).unwrap();
+    /// ```
+    pub fn forward(&self, input: &Vector) -> Result<Vector, KanError> {
+        let mut activations: Vector = match &self.scaler {
+            Some(scaler) => scaler.transform(input),
+            None => input.clone(),
+        };
+        // The values of the nodes two layers back, which the skip edges start at
+        let mut skip_activations: Vector = Vector::new(vec![]);
+        for (l, ((layer, aggregations), norm)) in self.layers.iter().zip(self.aggregations.iter()).zip(self.norms.iter()).enumerate() {
+            let values: Vec<f64> = layer.iter().zip(aggregations.iter()).enumerate().map(|(j, (node, aggregation))| {
+                let values: Vec<f64> = node.iter().enumerate().map(|(k, edge)| {
+                    let source: &Vector = if edge.skip { &skip_activations } else { &activations };
+                    let t: f64 = match source.elements.get(edge.start) {
+                        Some(&t) => t,
+                        None => return Err(KanError::mismatch("values of the layer an edge starts at", edge.start + 1, source.len()).at_layer(l).at_node(j).at_edge(k)),
+                    };
+                    // Inputs outside of the lookup table are evaluated exactly
+                    let lut: Option<&Lut> = self.luts.get(l).and_then(|layer| layer.get(j)).and_then(|node| node.get(k));
+                    Ok(lut.and_then(|lut| lut.value(t)).unwrap_or_else(|| edge.value(t)))
+                }).collect::<Result<Vec<f64>, KanError>>()?;
+                Ok(match aggregation {
+                    Aggregation::Sum => values.into_iter().sum(),
+                    Aggregation::Product => values.into_iter().product(),
+                })
+            }).collect::<Result<Vec<f64>, KanError>>()?;
+            let mut next: Vector = Vector::new(values);
+            if let Some(norm) = norm {
+                next = norm.forward(&next);
+            }
+            skip_activations = std::mem::replace(&mut activations, next);
+        }
+        Ok(activations)
+    }
+
+    /// Precompute the activation of every edge into a lookup table, trading memory for faster inference of a frozen model.
+    /// Every edge is evaluated at equally spaced inputs over the domain of its activation, and `forward` interpolates linearly between the entries, falling back to the exact value of the edge for inputs outside of the domain.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolution` - The number of entries of the table of every edge, at least 2.
+    ///
+    /// # Returns
+    ///
+    /// * The compiled KAN with a lookup table for every edge.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let compiled = kan.compile().with_luts(1024);
+    /// let output = compiled.forward(&Vector::new(vec![1.0, 2.0])).unwrap();
+    /// ```
+    pub fn with_luts(mut self, resolution: usize) -> CompiledKAN {
+        self.luts = self.layers.iter().map(|layer| layer.iter().map(|node| node.iter().map(|edge| Lut::new(edge, resolution)).collect()).collect()).collect();
+        self
+    }
+
+    /// The batched forward pass computes the values of all output nodes for many samples at once.
+    /// With the `parallel` feature the samples are spread over the threads of rayon.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith sample.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a matrix where the ith row holds the values of the nodes in the last layer for the ith sample, or an error as in `forward`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let inputs = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
+    ///
+    /// let outputs = compiled.forward_batch(&inputs).unwrap();
+    /// ```
+    pub fn forward_batch(&self, inputs: &Matrix) -> Result<Matrix, KanError> {
+        let forward = |i: usize| self.forward(&inputs.row(i));
+        #[cfg(feature = "parallel")]
+        let outputs: Vec<Vector> = (0..inputs.rows).into_par_iter().map(forward).collect::<Result<Vec<Vector>, KanError>>()?;
+        #[cfg(not(feature = "parallel"))]
+        let outputs: Vec<Vector> = (0..inputs.rows).map(forward).collect::<Result<Vec<Vector>, KanError>>()?;
+        Ok(Matrix::new(outputs))
+    }
+
+    /// The number of input values, one more than the largest start index of the incoming edges of the first layer and the skip edges of the second layer.
+    ///
+    /// # Returns
+    ///
+    /// * The number of inputs of the first layer, or 0 if there are no layers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let inputs = kan.compile().num_inputs();
+    /// ```
+    pub fn num_inputs(&self) -> usize {
+        let skip_edges = self.layers.get(1).into_iter().flatten().flatten().filter(|edge| edge.skip);
+        self.layers.first().into_iter().flatten().flatten().chain(skip_edges).map(|edge| edge.start + 1).max().unwrap_or(0)
+    }
+
+    /// The number of output values.
+    ///
+    /// # Returns
+    ///
+    /// * The number of nodes in the last layer, or 0 if there are no layers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let outputs = kan.compile().num_outputs();
+    /// ```
+    pub fn num_outputs(&self) -> usize {
+        self.layers.last().map_or(0, |nodes| nodes.len())
+    }
+
+    /// Computes the class probabilities for the given input values by applying the softmax to the output logits.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector representing the input values to the first layer.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a vector where the i-th entry is the probability of the i-th class, or an error as in `forward`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let probabilities = compiled.predict_proba(&Vector::new(vec![1.0, 2.0])).unwrap();
+    /// ```
+    pub fn predict_proba(&self, input: &Vector) -> Result<Vector, KanError> {
+        Ok(softmax(&self.forward(input)?))
+    }
+
+    /// Predict the class of the given input values as the index of the largest output logit.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector representing the input values to the first layer.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the index of the predicted class, or an error as in `forward`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let class = compiled.predict_class(&Vector::new(vec![1.0, 2.0])).unwrap();
+    /// ```
+    pub fn predict_class(&self, input: &Vector) -> Result<usize, KanError> {
+        Ok(self.forward(input)?.argmax())
+    }
+}
@@ -0,0 +1,417 @@
+use crate::builder::KANBuilder;
+use crate::data_structures::{activation::Basis, edge::BaseFn};
+use crate::error::KanError;
+use crate::init::Init;
+use crate::kan::KAN;
+use crate::loss::Loss;
+use crate::optimizer::Optimizer;
+use crate::scheduler::Scheduler;
+use crate::trainer::{Trainer, EarlyStopping, Regularization};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// An experiment defined by a configuration file: the architecture of a KAN and the hyperparameters of its training.
+/// It is read from JSON, or from TOML if the file has the extension `.toml`, with the same keys in both formats:
+///
+/// ```toml
+/// [model]
+/// widths = [2, 5, 1]   # The number of inputs followed by the width of every layer
+/// basis = "bspline"    # "bspline" or "rbf", with `trainable_centers = true` for trainable centers
+/// degree = 2           # The degree of the B-splines, or an array with the degree of every layer
+/// grid_size = 5        # The number of control points or Gaussians of every activation, or an array with that of every layer
+/// base = "silu"        # "silu", "identity", "tanh", or "none"
+/// seed = 42            # Optional, for a reproducible initialisation
+///
+/// [model.init]
+/// kind = "normal"      # "zeros", "uniform" with `low` and `high`, "normal" with `mean` and `std`, or "xavier"
+/// mean = 0.0
+/// std = 1.0
+///
+/// [training]
+/// learning_rate = 0.01
+/// loss = "mean_squared_error"   # or "cross_entropy"
+/// epochs = 100
+/// batch_size = 8
+/// shuffle = true
+/// drop_last = false
+/// seed = 0
+///
+/// [training.optimizer]
+/// kind = "adam"        # "sgd", "momentum" with `beta`, or "adam" with `beta1`, `beta2`, and `epsilon`
+///
+/// [training.scheduler]
+/// kind = "step_decay"  # "constant", "step_decay" with `step_size` and `gamma`, or "exponential_decay" with `gamma`
+/// step_size = 10
+/// gamma = 0.5
+///
+/// [training.early_stopping]
+/// validation_split = 0.2
+/// patience = 5
+///
+/// [training.regularization]
+/// smoothness = 0.0
+/// l1 = 0.001
+/// entropy = 0.0
+/// ```
+///
+/// Only `model.widths` is required; every other key has the default of `KANBuilder` and `Trainer::new`, and the parameters of an optimizer default to those of `Optimizer::adam` and 0.9 for momentum.
+/// Unknown keys are errors, so a misspelt hyperparameter is not silently replaced by its default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub builder: KANBuilder,
+    pub training: TrainingConfig,
+}
+
+/// The hyperparameters of the training of a configured KAN, see `Config`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainingConfig {
+    pub learning_rate: f64,
+    pub loss: Loss,
+    pub optimizer: Optimizer,
+    pub scheduler: Scheduler,
+    pub epochs: usize, // Number of epochs to pass to `Trainer::fit`
+    pub batch_size: usize,
+    pub shuffle: bool,
+    pub drop_last: bool,
+    pub seed: u64, // Seed of the shuffle of the data loader
+    pub early_stopping: Option<EarlyStopping>,
+    pub regularization: Regularization,
+}
+
+impl Default for TrainingConfig {
+    /// The settings of `Trainer::new` with a learning rate of 0.01, the mean squared error, seed 0, and 100 epochs.
+    fn default() -> TrainingConfig {
+        TrainingConfig {
+            learning_rate: 0.01,
+            loss: Loss::MeanSquaredError,
+            optimizer: Optimizer::Sgd,
+            scheduler: Scheduler::Constant,
+            epochs: 100,
+            batch_size: 1,
+            shuffle: true,
+            drop_last: false,
+            seed: 0,
+            early_stopping: None,
+            regularization: Regularization::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Read a configuration from a file, as TOML if its extension is `.toml` and as JSON otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the configuration file.
+    ///
+    /// # Returns
+    ///
+    /// * The configuration, or an error if the file cannot be read or does not describe a valid configuration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let config = Config::load("experiment.toml").unwrap();
+    /// let mut trainer = config.trainer();
+    /// trainer.fit(&inputs, &targets, config.training.epochs).unwrap();
+    /// ```
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, KanError> {
+        let source: String = fs::read_to_string(&path).map_err(|_| KanError::Io("Failed to read the configuration file."))?;
+        if path.as_ref().extension().is_some_and(|extension| extension == "toml") {
+            Config::from_toml(&source)
+        } else {
+            Config::from_json(&source)
+        }
+    }
+
+    /// Read a configuration from a JSON string, see `Config` for the keys.
+    pub fn from_json(source: &str) -> Result<Config, KanError> {
+        let value: Value = serde_json::from_str(source).map_err(|_| KanError::Format("The configuration is not valid JSON."))?;
+        Config::from_value(value)
+    }
+
+    /// Read a configuration from a TOML string, see `Config` for the keys.
+    pub fn from_toml(source: &str) -> Result<Config, KanError> {
+        let value: Value = toml::from_str(source).map_err(|_| KanError::Format("The configuration is not valid TOML."))?;
+        Config::from_value(value)
+    }
+
+    /// Build the configured KAN, see `KANBuilder::build`.
+    pub fn build(&self) -> KAN {
+        self.builder.build()
+    }
+
+    /// Build the configured KAN and a trainer with the configured hyperparameters.
+    ///
+    /// # Returns
+    ///
+    /// * A trainer for a new KAN, which has not completed any epochs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let config = Config::load("experiment.json").unwrap();
+    /// let mut trainer = config.trainer();
+    /// let history = trainer.fit(&inputs, &targets, config.training.epochs).unwrap();
+    /// ```
+    pub fn trainer(&self) -> Trainer {
+        let training: TrainingConfig = self.training;
+        let mut trainer: Trainer = Trainer::new(self.build(), training.learning_rate, training.loss, training.seed);
+        trainer.optimizer = training.optimizer;
+        trainer.scheduler = training.scheduler;
+        trainer.loader.batch_size = training.batch_size;
+        trainer.loader.shuffle = training.shuffle;
+        trainer.loader.drop_last = training.drop_last;
+        trainer.early_stopping = training.early_stopping;
+        trainer.regularization = training.regularization;
+        trainer
+    }
+
+    /// Read a configuration from its tree of values, which is parsed from JSON or TOML into the same `ConfigData`.
+    fn from_value(value: Value) -> Result<Config, KanError> {
+        let data: ConfigData = serde_json::from_value(value).map_err(|_| KanError::InvalidConfig("The configuration has an unknown key, lacks a required key, or has a value of the wrong type."))?;
+        let model: ModelData = data.model.ok_or(KanError::InvalidConfig("The configuration must have a model section."))?;
+        let builder: KANBuilder = model.builder()?;
+        let training: TrainingConfig = match data.training {
+            Some(training) => training.config()?,
+            None => TrainingConfig::default(),
+        };
+        Ok(Config { builder, training })
+    }
+}
+
+/// The sections of a configuration file.
+/// Unknown keys are rejected, and absent keys are `None` until they are replaced by their defaults.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigData {
+    model: Option<ModelData>,
+    training: Option<TrainingData>,
+}
+
+/// The model section of a configuration.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ModelData {
+    widths: Vec<usize>,
+    basis: Option<BasisData>,
+    trainable_centers: Option<bool>,
+    degree: Option<PerLayer>,
+    grid_size: Option<PerLayer>,
+    base: Option<BaseData>,
+    seed: Option<u64>,
+    init: Option<InitData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BasisData {
+    BSpline,
+    Rbf,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BaseData {
+    Silu,
+    Identity,
+    Tanh,
+    None,
+}
+
+/// An initialisation scheme, whose parameters default to the standard uniform and normal distributions.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum InitData {
+    Zeros {},
+    Uniform { low: Option<f64>, high: Option<f64> },
+    Normal { mean: Option<f64>, std: Option<f64> },
+    Xavier {},
+}
+
+/// A setting of the model that is either shared by every layer or given for every layer.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PerLayer {
+    Shared(usize),
+    Layers(Vec<usize>),
+}
+
+/// The training section of a configuration.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TrainingData {
+    learning_rate: Option<f64>,
+    loss: Option<LossData>,
+    epochs: Option<usize>,
+    batch_size: Option<usize>,
+    shuffle: Option<bool>,
+    drop_last: Option<bool>,
+    seed: Option<u64>,
+    optimizer: Option<OptimizerData>,
+    scheduler: Option<SchedulerData>,
+    early_stopping: Option<EarlyStoppingData>,
+    regularization: Option<RegularizationData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LossData {
+    MeanSquaredError,
+    CrossEntropy,
+}
+
+/// An optimizer, whose parameters default to those of `Optimizer::adam` and 0.9 for momentum.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum OptimizerData {
+    Sgd {},
+    Momentum { beta: Option<f64> },
+    Adam { beta1: Option<f64>, beta2: Option<f64>, epsilon: Option<f64> },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+enum SchedulerData {
+    Constant {},
+    StepDecay { step_size: usize, gamma: f64 },
+    ExponentialDecay { gamma: f64 },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EarlyStoppingData {
+    validation_split: f64,
+    patience: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RegularizationData {
+    smoothness: f64,
+    l1: f64,
+    entropy: f64,
+}
+
+impl Default for RegularizationData {
+    fn default() -> RegularizationData {
+        let Regularization { smoothness, l1, entropy } = Regularization::default();
+        RegularizationData { smoothness, l1, entropy }
+    }
+}
+
+impl PerLayer {
+    /// The value of every layer, or an error if the setting does not have one value per layer.
+    fn of_layers(setting: &Option<PerLayer>, default: usize, layers: usize, message: &'static str) -> Result<Vec<usize>, KanError> {
+        match setting {
+            None => Ok(vec![default; layers]),
+            Some(PerLayer::Shared(value)) => Ok(vec![*value; layers]),
+            Some(PerLayer::Layers(values)) if values.len() == layers => Ok(values.clone()),
+            Some(PerLayer::Layers(_)) => Err(KanError::InvalidConfig(message)),
+        }
+    }
+
+    /// The value that is shared by every layer, or the default if the setting is given for every layer.
+    fn shared(setting: &Option<PerLayer>, default: usize) -> usize {
+        match setting {
+            Some(PerLayer::Shared(value)) => *value,
+            _ => default,
+        }
+    }
+}
+
+impl ModelData {
+    /// The builder of the configured architecture.
+    fn builder(self) -> Result<KANBuilder, KanError> {
+        let widths: Vec<usize> = self.widths;
+        if widths.len() < 2 {
+            return Err(KanError::InvalidConfig("The widths of the model must have the number of inputs and the width of at least one layer."));
+        }
+        if widths.contains(&0) {
+            return Err(KanError::InvalidConfig("The number of inputs and the width of every layer must be greater than 0."));
+        }
+        let trainable_centers: bool = self.trainable_centers.unwrap_or(false);
+        let basis: Basis = match self.basis {
+            Some(BasisData::Rbf) => Basis::Rbf { trainable_centers },
+            None | Some(BasisData::BSpline) if !trainable_centers => Basis::BSpline,
+            None | Some(BasisData::BSpline) => return Err(KanError::InvalidConfig("Only a Gaussian RBF basis has trainable centers.")),
+        };
+        let layers: usize = widths.len() - 1;
+        let degrees: Vec<usize> = PerLayer::of_layers(&self.degree, 2, layers, "The degree of the model must be an integer or an array with an integer for every layer.")?;
+        let grid_sizes: Vec<usize> = PerLayer::of_layers(&self.grid_size, 5, layers, "The grid size of the model must be an integer or an array with an integer for every layer.")?;
+        for l in 0..layers {
+            basis.check_grid(degrees[l], grid_sizes[l])?;
+        }
+        let base: BaseFn = match self.base {
+            None | Some(BaseData::Silu) => BaseFn::Silu,
+            Some(BaseData::Identity) => BaseFn::Identity,
+            Some(BaseData::Tanh) => BaseFn::Tanh,
+            Some(BaseData::None) => BaseFn::None,
+        };
+        let init: Init = match self.init {
+            None => Init::default(),
+            Some(InitData::Zeros {}) => Init::Zeros,
+            Some(InitData::Uniform { low, high }) => Init::Uniform { low: low.unwrap_or(-1.0), high: high.unwrap_or(1.0) },
+            Some(InitData::Normal { mean, std }) => Init::Normal { mean: mean.unwrap_or(0.0), std: std.unwrap_or(1.0) },
+            Some(InitData::Xavier {}) => Init::Xavier,
+        };
+
+        let mut builder: KANBuilder = KANBuilder::new(widths[0]);
+        for &width in widths[1..].iter() {
+            builder = builder.add_layer_with_basis(width, basis);
+        }
+        builder = builder.degree(PerLayer::shared(&self.degree, 2)).grid_size(PerLayer::shared(&self.grid_size, 5)).base(base).init(init);
+        if matches!(self.degree, Some(PerLayer::Layers(_))) || matches!(self.grid_size, Some(PerLayer::Layers(_))) {
+            for l in 0..layers {
+                builder.grids[l] = Some((degrees[l], grid_sizes[l]));
+            }
+        }
+        if let Some(seed) = self.seed {
+            builder = builder.seed(seed);
+        }
+        Ok(builder)
+    }
+}
+
+impl TrainingData {
+    /// The configured hyperparameters, with the defaults of `TrainingConfig` for the absent keys.
+    fn config(self) -> Result<TrainingConfig, KanError> {
+        let defaults: TrainingConfig = TrainingConfig::default();
+        let batch_size: usize = self.batch_size.unwrap_or(defaults.batch_size);
+        if batch_size == 0 {
+            return Err(KanError::InvalidConfig("The batch size must be greater than 0."));
+        }
+        let Optimizer::Adam { beta1, beta2, epsilon } = Optimizer::adam() else { unreachable!() };
+        let optimizer: Optimizer = match self.optimizer {
+            None => defaults.optimizer,
+            Some(OptimizerData::Sgd {}) => Optimizer::Sgd,
+            Some(OptimizerData::Momentum { beta }) => Optimizer::Momentum { beta: beta.unwrap_or(0.9) },
+            Some(OptimizerData::Adam { beta1: b1, beta2: b2, epsilon: e }) => Optimizer::Adam { beta1: b1.unwrap_or(beta1), beta2: b2.unwrap_or(beta2), epsilon: e.unwrap_or(epsilon) },
+        };
+        let scheduler: Scheduler = match self.scheduler {
+            None => defaults.scheduler,
+            Some(SchedulerData::Constant {}) => Scheduler::Constant,
+            Some(SchedulerData::StepDecay { step_size, gamma }) => Scheduler::StepDecay { step_size, gamma },
+            Some(SchedulerData::ExponentialDecay { gamma }) => Scheduler::ExponentialDecay { gamma },
+        };
+        Ok(TrainingConfig {
+            learning_rate: self.learning_rate.unwrap_or(defaults.learning_rate),
+            loss: match self.loss {
+                None => defaults.loss,
+                Some(LossData::MeanSquaredError) => Loss::MeanSquaredError,
+                Some(LossData::CrossEntropy) => Loss::CrossEntropy,
+            },
+            optimizer,
+            scheduler,
+            epochs: self.epochs.unwrap_or(defaults.epochs),
+            batch_size,
+            shuffle: self.shuffle.unwrap_or(defaults.shuffle),
+            drop_last: self.drop_last.unwrap_or(defaults.drop_last),
+            seed: self.seed.unwrap_or(defaults.seed),
+            early_stopping: self.early_stopping.map(|early_stopping| EarlyStopping { validation_split: early_stopping.validation_split, patience: early_stopping.patience }),
+            regularization: self.regularization.map_or(defaults.regularization, |regularization| Regularization { smoothness: regularization.smoothness, l1: regularization.l1, entropy: regularization.entropy }),
+        })
+    }
+}
@@ -0,0 +1,227 @@
+use crate::data_structures::{vector::Vector, spline::BSpline, rbf::GaussianRbf, symbolic::Symbolic};
+use crate::error::KanError;
+use crate::init::Init;
+use rand::Rng;
+use serde::{Serialize, Serializer, Deserialize, Deserializer, ser::Error};
+use std::any::Any;
+
+/// A learnable univariate function of an edge, such as a B-spline or a Gaussian RBF.
+/// An edge evaluates and trains its activation only through this trait, so new basis families plug in without changes to the nodes, layers, or KAN.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Debug, Clone)]
+/// struct Linear { params: Vector }
+///
+/// impl ActivationFn for Linear {
+///     fn eval(&mut self, t: f64) -> f64 { self.params[0] * t }
+///     fn grad_wrt_params(&mut self, t: f64) -> Vector { Vector::new(vec![t]) }
+///     fn grad_wrt_input(&self, _t: f64) -> f64 { self.params[0] }
+///     fn params(&self) -> &Vector { &self.params }
+///     fn params_mut(&mut self) -> &mut Vector { &mut self.params }
+///     fn domain(&self) -> (f64, f64) { (0.0, 1.0) }
+///     fn clone_box(&self) -> Box<dyn ActivationFn> { Box::new(self.clone()) }
+///     fn as_any(&self) -> &dyn Any { self }
+///     fn as_any_mut(&mut self) -> &mut dyn Any { self }
+/// }
+///
+/// impl std::fmt::Display for Linear {
+///     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+///         write!(f, "Linear {{ slope: {} }}", self.params[0])
+///     }
+/// }
+/// ```
+pub trait ActivationFn: std::fmt::Debug + std::fmt::Display + Send + Sync {
+    /// Evaluate the activation at a given point t.
+    fn eval(&mut self, t: f64) -> f64;
+
+    /// Evaluate the activation at a given point t without updating any cache, so it can be shared between threads. Evaluates a clone by default.
+    fn value(&self, t: f64) -> f64 {
+        self.clone_box().eval(t)
+    }
+
+    /// Compute the gradient of the activation at a given point t with respect to its trainable parameters, in the layout of `params`.
+    fn grad_wrt_params(&mut self, t: f64) -> Vector;
+
+    /// Compute the derivative of the activation with respect to its input at a given point t.
+    fn grad_wrt_input(&self, t: f64) -> f64;
+
+    /// The trainable parameters of the activation.
+    fn params(&self) -> &Vector;
+
+    /// A mutable reference to the trainable parameters of the activation.
+    fn params_mut(&mut self) -> &mut Vector;
+
+    /// The domain of the activation, which the input mode of an edge maps inputs into.
+    fn domain(&self) -> (f64, f64);
+
+    /// Project the parameters onto the constraints of the activation; called after every update. Does nothing by default.
+    fn project(&mut self) {}
+
+    /// Clone the activation into a new box, so edges can be cloned.
+    fn clone_box(&self) -> Box<dyn ActivationFn>;
+
+    /// The activation as `Any`, to recover its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// The activation as mutable `Any`, to recover its concrete type.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl dyn ActivationFn {
+    /// Recover a reference to the concrete type of the activation.
+    ///
+    /// # Returns
+    ///
+    /// * The activation as a `T`, or `None` if it is of another type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let spline: Option<&BSpline> = edge.activation.downcast_ref::<BSpline>();
+    /// ```
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+
+    /// Recover a mutable reference to the concrete type of the activation.
+    ///
+    /// # Returns
+    ///
+    /// * The activation as a `T`, or `None` if it is of another type.
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut::<T>()
+    }
+}
+
+impl Clone for Box<dyn ActivationFn> {
+    fn clone(&self) -> Box<dyn ActivationFn> {
+        self.clone_box()
+    }
+}
+
+/// A borrowed activation of a known type, tagged with its type when serialized.
+#[derive(Serialize)]
+enum ActivationRef<'a> {
+    BSpline(&'a BSpline),
+    Rbf(&'a GaussianRbf),
+    Symbolic(&'a Symbolic),
+}
+
+/// An owned activation of a known type, read back from the tagged form of `ActivationRef`.
+#[derive(Deserialize)]
+enum ActivationData {
+    BSpline(BSpline),
+    Rbf(GaussianRbf),
+    Symbolic(Symbolic),
+}
+
+/// Only B-spline, Gaussian RBF, and symbolic activations can be serialized; other activations give an error.
+impl Serialize for Box<dyn ActivationFn> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let activation: ActivationRef = if let Some(spline) = self.downcast_ref::<BSpline>() {
+            ActivationRef::BSpline(spline)
+        } else if let Some(rbf) = self.downcast_ref::<GaussianRbf>() {
+            ActivationRef::Rbf(rbf)
+        } else if let Some(symbolic) = self.downcast_ref::<Symbolic>() {
+            ActivationRef::Symbolic(symbolic)
+        } else {
+            return Err(S::Error::custom("Only B-spline, Gaussian RBF, and symbolic activations can be serialized."));
+        };
+        activation.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<dyn ActivationFn> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Box<dyn ActivationFn>, D::Error> {
+        Ok(match ActivationData::deserialize(deserializer)? {
+            ActivationData::BSpline(spline) => Box::new(spline),
+            ActivationData::Rbf(rbf) => Box::new(rbf),
+            ActivationData::Symbolic(symbolic) => Box::new(symbolic),
+        })
+    }
+}
+
+/// The family of activations of the edges of a layer, used by `KANBuilder`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Basis {
+    /// A B-spline, by default of degree 2 with 5 control points.
+    BSpline,
+    /// A Gaussian RBF with Gaussians spaced evenly over [0, 1], by default 5.
+    Rbf { trainable_centers: bool },
+}
+
+impl Basis {
+    /// Create an activation of the basis family with the default grid, a B-spline of degree 2 with 5 control points or a Gaussian RBF with 5 Gaussians, sampling its coefficients from an initialisation scheme.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - An initialisation scheme for the control points or amplitudes.
+    ///
+    /// * `fan_in` - The number of incoming edges of the destination node.
+    ///
+    /// * `rng` - A random number generator.
+    ///
+    /// # Returns
+    ///
+    /// * An activation of the basis family.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let activation = Basis::Rbf { trainable_centers: true }.sample(Init::Xavier, 4, &mut rng);
+    /// ```
+    pub fn sample(&self, init: Init, fan_in: usize, rng: &mut impl Rng) -> Box<dyn ActivationFn> {
+        self.sample_grid(2, 5, init, fan_in, rng)
+    }
+
+    /// Create an activation of the basis family with a given grid, sampling its coefficients from an initialisation scheme.
+    ///
+    /// # Arguments
+    ///
+    /// * `degree` - The degree of a B-spline, which a Gaussian RBF ignores.
+    ///
+    /// * `grid_size` - The number of control points of a B-spline, at least `degree + 1`, or the number of Gaussians of a Gaussian RBF, at least 2.
+    ///
+    /// * `init` - An initialisation scheme for the control points or amplitudes.
+    ///
+    /// * `fan_in` - The number of incoming edges of the destination node.
+    ///
+    /// * `rng` - A random number generator.
+    ///
+    /// # Returns
+    ///
+    /// * An activation of the basis family.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let activation = Basis::BSpline.sample_grid(3, 10, Init::Xavier, 4, &mut rng);
+    /// ```
+    pub fn sample_grid(&self, degree: usize, grid_size: usize, init: Init, fan_in: usize, rng: &mut impl Rng) -> Box<dyn ActivationFn> {
+        if let Err(err) = self.check_grid(degree, grid_size) {
+            panic!("{}", err);
+        }
+        let coefficients: Vector = init.sample(grid_size, fan_in, rng);
+        match *self {
+            Basis::BSpline => Box::new(BSpline::new(coefficients, degree)),
+            Basis::Rbf { trainable_centers } => {
+                let mut rbf: GaussianRbf = GaussianRbf::uniform(coefficients, (0.0, 1.0));
+                rbf.trainable_centers = trainable_centers;
+                Box::new(rbf)
+            }
+        }
+    }
+
+    /// Check that the basis family can have a grid of the given degree and size, see `Basis::sample_grid`.
+    pub fn check_grid(&self, degree: usize, grid_size: usize) -> Result<(), KanError> {
+        match *self {
+            Basis::BSpline if grid_size < degree + 1 => Err(KanError::InvalidConfig("A B-spline must have more control points than its degree.")),
+            Basis::Rbf { .. } if grid_size < 2 => Err(KanError::InvalidConfig("A Gaussian RBF needs at least two Gaussians.")),
+            _ => Ok(()),
+        }
+    }
+}
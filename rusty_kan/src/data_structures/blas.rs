@@ -0,0 +1,150 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix, float::Float};
+use crate::error::KanError;
+use std::any::Any;
+use std::os::raw::{c_char, c_int};
+
+// The Fortran interfaces of the reference BLAS and LAPACK, which OpenBLAS, MKL, and Accelerate also export.
+// All arrays are in column-major order, and all arguments are passed by pointer.
+#[link(name = "blas")]
+extern "C" {
+    fn dgemm_(transa: *const c_char, transb: *const c_char, m: *const c_int, n: *const c_int, k: *const c_int, alpha: *const f64, a: *const f64, lda: *const c_int, b: *const f64, ldb: *const c_int, beta: *const f64, c: *mut f64, ldc: *const c_int);
+    fn sgemm_(transa: *const c_char, transb: *const c_char, m: *const c_int, n: *const c_int, k: *const c_int, alpha: *const f32, a: *const f32, lda: *const c_int, b: *const f32, ldb: *const c_int, beta: *const f32, c: *mut f32, ldc: *const c_int);
+}
+
+#[link(name = "lapack")]
+extern "C" {
+    fn dgesv_(n: *const c_int, nrhs: *const c_int, a: *mut f64, lda: *const c_int, ipiv: *mut c_int, b: *mut f64, ldb: *const c_int, info: *mut c_int);
+    fn sgesv_(n: *const c_int, nrhs: *const c_int, a: *mut f32, lda: *const c_int, ipiv: *mut c_int, b: *mut f32, ldb: *const c_int, info: *mut c_int);
+    fn dposv_(uplo: *const c_char, n: *const c_int, nrhs: *const c_int, a: *mut f64, lda: *const c_int, b: *mut f64, ldb: *const c_int, info: *mut c_int);
+    fn sposv_(uplo: *const c_char, n: *const c_int, nrhs: *const c_int, a: *mut f32, lda: *const c_int, b: *mut f32, ldb: *const c_int, info: *mut c_int);
+}
+
+/// The routines of one precision.
+trait Lapack: Float {
+    /// C = A B for column-major A (m x k), B (k x n), and C (m x n).
+    fn gemm(m: usize, n: usize, k: usize, a: &[Self], b: &[Self], c: &mut [Self]);
+
+    /// Solve A X = B in place for a column-major square A with LU decomposition, returning the LAPACK status.
+    fn gesv(n: usize, a: &mut [Self], b: &mut [Self]) -> c_int;
+
+    /// Solve A X = B in place for a symmetric positive definite A with Cholesky decomposition, returning the LAPACK status.
+    fn posv(n: usize, a: &mut [Self], b: &mut [Self]) -> c_int;
+}
+
+macro_rules! impl_lapack {
+    ($t:ty, $gemm:ident, $gesv:ident, $posv:ident) => {
+        impl Lapack for $t {
+            fn gemm(m: usize, n: usize, k: usize, a: &[$t], b: &[$t], c: &mut [$t]) {
+                let (m, n, k): (c_int, c_int, c_int) = (dimension(m), dimension(n), dimension(k));
+                // The leading dimensions must be at least 1, even for empty matrices
+                let (lda, ldb): (c_int, c_int) = (m.max(1), k.max(1));
+                let (alpha, beta): ($t, $t) = (1.0, 0.0);
+                let no: c_char = b'N' as c_char;
+                // SAFETY: the slices hold m * k, k * n, and m * n elements in column-major order, as checked by the callers
+                unsafe { $gemm(&no, &no, &m, &n, &k, &alpha, a.as_ptr(), &lda, b.as_ptr(), &ldb, &beta, c.as_mut_ptr(), &lda) }
+            }
+
+            fn gesv(n: usize, a: &mut [$t], b: &mut [$t]) -> c_int {
+                let (n, nrhs, ld): (c_int, c_int, c_int) = (dimension(n), 1, dimension(n).max(1));
+                let mut pivots: Vec<c_int> = vec![0; n as usize];
+                let mut info: c_int = 0;
+                // SAFETY: a holds n * n elements, b holds n elements, and there is one pivot per row
+                unsafe { $gesv(&n, &nrhs, a.as_mut_ptr(), &ld, pivots.as_mut_ptr(), b.as_mut_ptr(), &ld, &mut info) };
+                info
+            }
+
+            fn posv(n: usize, a: &mut [$t], b: &mut [$t]) -> c_int {
+                let (n, nrhs, ld): (c_int, c_int, c_int) = (dimension(n), 1, dimension(n).max(1));
+                let lower: c_char = b'L' as c_char;
+                let mut info: c_int = 0;
+                // SAFETY: a holds n * n elements and b holds n elements
+                unsafe { $posv(&lower, &n, &nrhs, a.as_mut_ptr(), &ld, b.as_mut_ptr(), &ld, &mut info) };
+                info
+            }
+        }
+    };
+}
+
+impl_lapack!(f64, dgemm_, dgesv_, dposv_);
+impl_lapack!(f32, sgemm_, sgesv_, sposv_);
+
+/// A dimension as the integer type of the Fortran interface.
+fn dimension(n: usize) -> c_int {
+    c_int::try_from(n).expect("The dimensions of a matrix must fit in the integers of BLAS.")
+}
+
+/// Compute the product of two matrices with gemm, or return None if the precision has no BLAS routines.
+pub(crate) fn matmul<T: Float>(a: &Matrix<T>, b: &Matrix<T>) -> Option<Matrix<T>> {
+    if let (Some(a), Some(b)) = (downcast::<_, Matrix<f64>>(a), downcast::<_, Matrix<f64>>(b)) {
+        return cast(matmul_typed(a, b));
+    }
+    if let (Some(a), Some(b)) = (downcast::<_, Matrix<f32>>(a), downcast::<_, Matrix<f32>>(b)) {
+        return cast(matmul_typed(a, b));
+    }
+    None
+}
+
+/// Solve the linear system A x = b of a square matrix with gesv, or return None if the precision has no LAPACK routines.
+pub(crate) fn solve<T: Float>(a: &Matrix<T>, b: &Vector<T>) -> Option<Result<Vector<T>, KanError>> {
+    if let (Some(a), Some(b)) = (downcast::<_, Matrix<f64>>(a), downcast::<_, Vector<f64>>(b)) {
+        return cast(solve_typed(a, b));
+    }
+    if let (Some(a), Some(b)) = (downcast::<_, Matrix<f32>>(a), downcast::<_, Vector<f32>>(b)) {
+        return cast(solve_typed(a, b));
+    }
+    None
+}
+
+/// Solve the linear system A x = b of a symmetric positive definite matrix with posv, or return None if the precision has no LAPACK routines.
+pub(crate) fn solve_cholesky<T: Float>(a: &Matrix<T>, b: &Vector<T>) -> Option<Result<Vector<T>, KanError>> {
+    if let (Some(a), Some(b)) = (downcast::<_, Matrix<f64>>(a), downcast::<_, Vector<f64>>(b)) {
+        return cast(solve_cholesky_typed(a, b));
+    }
+    if let (Some(a), Some(b)) = (downcast::<_, Matrix<f32>>(a), downcast::<_, Vector<f32>>(b)) {
+        return cast(solve_cholesky_typed(a, b));
+    }
+    None
+}
+
+fn matmul_typed<L: Lapack>(a: &Matrix<L>, b: &Matrix<L>) -> Matrix<L> {
+    let (n, m, p) = (a.rows, a.cols, b.cols);
+    let mut result: Matrix<L> = Matrix::zeros(n, p);
+    if n > 0 && p > 0 && m > 0 {
+        // The row-major product C = A B is the column-major product C^T = B^T A^T
+        L::gemm(p, n, m, &b.elements, &a.elements, &mut result.elements);
+    }
+    result
+}
+
+fn solve_typed<L: Lapack>(a: &Matrix<L>, b: &Vector<L>) -> Result<Vector<L>, KanError> {
+    let n: usize = a.rows;
+    // LAPACK expects column-major order
+    let mut transposed: Vec<L> = (0..n * n).map(|k| a.elements[(k % n) * n + k / n]).collect();
+    let mut x: Vec<L> = b.elements.clone();
+    match L::gesv(n, &mut transposed, &mut x) {
+        0 => Ok(Vector::new(x)),
+        info if info > 0 => Err(KanError::SingularMatrix),
+        _ => panic!("The arguments of the LAPACK solver must be valid."),
+    }
+}
+
+fn solve_cholesky_typed<L: Lapack>(a: &Matrix<L>, b: &Vector<L>) -> Result<Vector<L>, KanError> {
+    // A symmetric matrix is the same in row-major and column-major order
+    let mut factor: Vec<L> = a.elements.clone();
+    let mut x: Vec<L> = b.elements.clone();
+    match L::posv(a.rows, &mut factor, &mut x) {
+        0 => Ok(Vector::new(x)),
+        info if info > 0 => Err(KanError::NotPositiveDefinite),
+        _ => panic!("The arguments of the LAPACK solver must be valid."),
+    }
+}
+
+/// A reference to a value of type T as a reference of type U, if T is U.
+fn downcast<T: 'static, U: 'static>(value: &T) -> Option<&U> {
+    (value as &dyn Any).downcast_ref::<U>()
+}
+
+/// A value of type T as type U, if T is U.
+fn cast<T: 'static, U: 'static>(value: T) -> Option<U> {
+    (Box::new(value) as Box<dyn Any>).downcast::<U>().ok().map(|value| *value)
+}
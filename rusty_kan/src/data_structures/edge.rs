@@ -1,14 +1,250 @@
-use crate::data_structures::{vector::Vector, spline::BSpline};
+use crate::data_structures::{float::Float, vector::Vector, matrix::Matrix, spline::BSpline, activation::{ActivationFn, Basis}, symbolic::{Symbolic, SymbolicFn, suggest}};
+use crate::error::KanError;
+use crate::init::Init;
+use crate::expr::Expr;
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+/// How an edge treats inputs outside the domain of its spline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InputMode {
+    /// Inputs are passed to the spline unchanged, so the spline vanishes outside its knots.
+    Raw,
+    /// Inputs are clamped into the domain of the spline.
+    Clamp,
+    /// Inputs are squashed into the domain of the spline with the logistic function, so every real input lies inside the knots.
+    Squash,
+}
+
+impl InputMode {
+    /// Map an input to the value that is passed to a spline with a given domain.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - An input value.
+    /// 
+    /// * `domain` - The domain (lower, upper) of the spline.
+    /// 
+    /// # Returns
+    /// 
+    /// * The input value to the spline.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let t = InputMode::Clamp.apply(1.5, (0.0, 1.0)); // 1.0
+    /// ```
+    pub fn apply<T: Float>(&self, t: T, domain: (T, T)) -> T {
+        match self {
+            InputMode::Raw => t,
+            InputMode::Clamp => if t < domain.0 { domain.0 } else if t > domain.1 { domain.1 } else { t },
+            InputMode::Squash => domain.0 + (domain.1 - domain.0) / (T::ONE + (-t).exp()),
+        }
+    }
+
+    /// Evaluate the derivative of the map of an input onto a spline with a given domain.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - An input value.
+    /// 
+    /// * `domain` - The domain (lower, upper) of the spline.
+    /// 
+    /// # Returns
+    /// 
+    /// * The derivative of `apply` at t, which is 0 where the input is clamped.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let slope = InputMode::Squash.derivative(0.0, (0.0, 1.0)); // 0.25
+    /// ```
+    pub fn derivative<T: Float>(&self, t: T, domain: (T, T)) -> T {
+        match self {
+            InputMode::Raw => T::ONE,
+            InputMode::Clamp => if t < domain.0 || t > domain.1 { T::ZERO } else { T::ONE },
+            InputMode::Squash => {
+                let s: T = T::ONE / (T::ONE + (-t).exp());
+                (domain.1 - domain.0) * s * (T::ONE - s)
+            }
+        }
+    }
+}
+
+/// The fixed base function of an edge, which is added to its learnable activation as a residual.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BaseFn {
+    /// The Sigmoid Linear Unit x / (1 + e^(-x)).
+    Silu,
+    /// The identity x.
+    Identity,
+    /// The hyperbolic tangent.
+    Tanh,
+    /// No base function.
+    None,
+}
+
+impl BaseFn {
+    /// Evaluate the base function at a given input.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - A scalar.
+    /// 
+    /// # Returns
+    /// 
+    /// * The value of the base function at x.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let y = BaseFn::Tanh.eval(0.5);
+    /// ```
+    pub fn eval<T: Float>(&self, x: T) -> T {
+        match self {
+            BaseFn::Silu => silu(x),
+            BaseFn::Identity => x,
+            BaseFn::Tanh => x.tanh(),
+            BaseFn::None => T::ZERO,
+        }
+    }
+
+    /// Evaluate the derivative of the base function at a given input.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - A scalar.
+    /// 
+    /// # Returns
+    /// 
+    /// * The derivative of the base function at x.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let slope = BaseFn::Silu.derivative(0.5);
+    /// ```
+    pub fn derivative<T: Float>(&self, x: T) -> T {
+        match self {
+            BaseFn::Silu => {
+                let sigmoid: T = T::ONE / (T::ONE + (-x).exp());
+                sigmoid * (T::ONE + x * (T::ONE - sigmoid))
+            }
+            BaseFn::Identity => T::ONE,
+            BaseFn::Tanh => {
+                let tanh: T = x.tanh();
+                T::ONE - tanh * tanh
+            }
+            BaseFn::None => T::ZERO,
+        }
+    }
+}
+
+/// The parts of an edge around its activation in a given precision: the base function with its scale w_b, the scale w_s of the activation, the input mode, and the domain of the activation.
+/// `Edge` and the single-precision `single::SingleEdge` compute their values and gradients from those of their activations through it, so both treat their inputs and weights alike.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Residual<T: Float> {
+    pub base: BaseFn,
+    pub base_weight: T, // Scale w_b of the base function
+    pub spline_weight: T, // Scale w_s of the activation
+    pub input_mode: InputMode,
+    pub domain: (T, T), // Domain of the activation
+}
+
+impl<T: Float> Residual<T> {
+    /// Map an input of the edge to the input of its activation according to the input mode.
+    pub fn spline_input(&self, t: T) -> T {
+        self.input_mode.apply(t, self.domain)
+    }
+
+    /// The value w_b * base(t) + w_s * activation of the edge at an input t, given the value of the activation at `spline_input(t)`.
+    pub fn value(&self, t: T, activation: T) -> T {
+        self.base_weight * self.base.eval(t) + self.spline_weight * activation
+    }
+
+    /// The gradient of the loss with respect to the weights of the edge at an input t, in the layout of the parameters of the activation followed by w_b and w_s.
+    /// It is computed from the value of the activation at `spline_input(t)` and its gradient with respect to its parameters.
+    pub fn gradient(&self, t: T, activation: T, activation_gradient: &Vector<T>, upstream_gradient: T) -> Vector<T> {
+        let mut gradient: Vector<T> = activation_gradient * (self.spline_weight * upstream_gradient);
+        gradient.push(self.base.eval(t) * upstream_gradient);
+        gradient.push(activation * upstream_gradient);
+        gradient
+    }
+
+    /// The derivative of the value of the edge with respect to its input t, given the derivative of the activation at `spline_input(t)`.
+    pub fn input_gradient(&self, t: T, activation_slope: T) -> T {
+        self.base_weight * self.base.derivative(t) + self.spline_weight * activation_slope * self.input_mode.derivative(t, self.domain)
+    }
+}
+
+/// Take a step of gradient descent on the weights of an edge, the parameters of its activation followed by w_b and w_s, in place.
+///
+/// # Arguments
+///
+/// * `params` - The parameters of the activation.
+///
+/// * `base_weight` - The scale w_b of the base function.
+///
+/// * `spline_weight` - The scale w_s of the activation.
+///
+/// * `gradient` - The gradient of the loss with respect to the weights, one entry per weight.
+///
+/// * `step` - The factor of the gradient that is added to the weights, the negative learning rate.
+///
+/// # Returns
+///
+/// * A result indicating whether the step was taken, or an error if the gradient does not have one entry per weight.
+pub(crate) fn descend<T: Float>(params: &mut Vector<T>, base_weight: &mut T, spline_weight: &mut T, gradient: &Vector<T>, step: T) -> Result<(), KanError> {
+    let n: usize = params.len();
+    if gradient.len() != n + 2 {
+        return Err(KanError::mismatch("gradient entries, one per weight", n + 2, gradient.len()));
+    }
+    for (param, &gradient) in params.iter_mut().zip(gradient.iter()) {
+        *param += step * gradient;
+    }
+    *base_weight += step * gradient[n];
+    *spline_weight += step * gradient[n + 1];
+    Ok(())
+}
+
+/// The values of a forward batch pass of an edge that the backward batch pass reuses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchCache {
+    pub inputs: Vector, // Inputs of the edge
+    pub basis: Option<Matrix>, // Basis matrix at the inputs of the activation, if it is a B-spline
+    pub activations: Vector, // Values of the activation at the inputs
+}
 
 /// An edge is a connection between two nodes in a graph.
-/// It is represented as an index in the origin layer, an index in the destination layer, a layer index corresponding to the origin layer, and a spline.
-#[derive(Debug, Clone)]
+/// It is represented as an index in the origin layer, an index in the destination layer, a layer index corresponding to the origin layer, an activation (a B-spline or a Gaussian RBF), a base function with its scale w_b and the scale w_s of the activation, a learning rate multiplier, whether it is frozen, how inputs outside the domain of the activation are treated, whether it is a skip edge, and its dropout probability.
+/// 
+/// The value of the edge is w_b * base(t) + w_s * activation(t), as in the KAN paper.
+/// The weights of the edge are the parameters of the activation followed by w_b and w_s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
     pub start: usize,
     pub end: usize,
-    pub spline: BSpline,
+    pub activation: Box<dyn ActivationFn>,
     pub layer: usize,
-    pub gradient: Vector, // To store gradients for control points
+    pub base: BaseFn,
+    pub base_weight: f64, // Scale w_b of the base function
+    pub spline_weight: f64, // Scale w_s of the activation
+    pub gradient: Vector, // To store gradients for the weights (the parameters of the activation, w_b, and w_s)
+    pub lr_scale: f64, // Multiplier of the learning rate for the weights
+    pub frozen: bool, // Whether the weights are excluded from updates
+    pub input_mode: InputMode, // How inputs outside the domain of the activation are treated
+    #[serde(default)] // Files written before edges were scored have no scores
+    pub score: Option<f64>, // Mean absolute value of the edge over the samples of the last `KAN::update_edge_scores`
+    #[serde(default)]
+    pub skip: bool, // Whether the edge starts at a node two layers back, skipping the previous layer
+    #[serde(default)] // Files written before dropout was added have none
+    pub dropout: f64, // Probability that the edge is masked in a training step
+    #[serde(default)]
+    pub tie: Option<usize>, // Group of edges whose activations share their parameters, see `KAN::tie`
+    #[serde(skip)]
+    pub masked: Option<bool>, // Whether the edge is masked in the current training step, or None outside of training steps
+    #[serde(skip)]
+    pub training: bool, // Whether the edge is in training mode, where it can be masked by dropout
 }
 
 impl Edge {
@@ -38,8 +274,34 @@ impl Edge {
     /// let edge = Edge::new(start, end, spline, layer);
     /// ```
     pub fn new(start: usize, end: usize, spline: BSpline, layer: usize) -> Edge {
-        let gradient: Vector = Vector { elements: vec![0.0; spline.control_points.len()] };
-        Edge { start, end, spline, gradient, layer }
+        Edge::with_activation(start, end, Box::new(spline), layer)
+    }
+
+    /// Create a new edge with a given start index, end index, and activation, e.g. a Gaussian RBF.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `start` - An index in the origin layer.
+    /// 
+    /// * `end` - An index in the destination layer.
+    /// 
+    /// * `activation` - An activation that represents the edge.
+    /// 
+    /// * `layer` - A layer index corresponding to the origin layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * An edge with the given start index, end index, and activation.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let rbf = GaussianRbf::uniform(Vector::new(vec![0.0, 1.0, 0.0]), (0.0, 1.0));
+    /// let edge = Edge::with_activation(0, 1, Box::new(rbf), 0);
+    /// ```
+    pub fn with_activation(start: usize, end: usize, activation: Box<dyn ActivationFn>, layer: usize) -> Edge {
+        let gradient: Vector = Vector { elements: vec![0.0; activation.params().len() + 2] };
+        Edge { start, end, activation, layer, base: BaseFn::Silu, base_weight: 1.0, spline_weight: 1.0, gradient, lr_scale: 1.0, frozen: false, input_mode: InputMode::Raw, score: None, skip: false, dropout: 0.0, tie: None, masked: None, training: true }
     }
 
     /// Create a new edge for the standard layer with a given start index and end index, where the spline is initialised with degree 2 and 5 normally distributed control points with mean 0 and standard deviation 1.
@@ -64,19 +326,122 @@ impl Edge {
     /// let layer = 0;
     /// let edge = Edge::standard(start, end, layer);
     /// ```
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn standard(start: usize, end: usize, layer: usize) -> Edge {
-        let control_points: Vector = Vector::random(5);
-        let degree: usize = 2;
-        let spline: BSpline = BSpline::new(control_points, degree);
-        Edge::new(start, end, spline, layer)
+        Edge::standard_with_rng(start, end, layer, &mut rand::thread_rng())
     }
 
+    /// Create a new edge for the standard layer like `Edge::standard`, drawing the control points from the given random number generator.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `start` - An index in the origin layer.
+    /// 
+    /// * `end` - An index in the destination layer.
+    ///
+    /// * `layer` - A layer index corresponding to the origin layer.
+    /// 
+    /// * `rng` - A random number generator.
+    /// 
+    /// # Returns
+    /// 
+    /// * An edge with the given start index, end index, and spline.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let edge = Edge::standard_with_rng(0, 1, 0, &mut rng);
+    /// ```
+    pub fn standard_with_rng(start: usize, end: usize, layer: usize, rng: &mut impl Rng) -> Edge {
+        Edge::standard_with_init(start, end, layer, Init::default(), 1, rng)
+    }
 
-    /// The forward pass computes the value of the spline at the given parameter value t and adds the value of the basis function (sigmoid linear unit).
+    /// Create a new edge for the standard layer with a spline of degree 2 and 5 control points sampled from the given initialisation scheme.
     /// 
     /// # Arguments
     /// 
-    /// * `t` - A parameter value between 0 and 1.
+    /// * `start` - An index in the origin layer.
+    /// 
+    /// * `end` - An index in the destination layer.
+    ///
+    /// * `layer` - A layer index corresponding to the origin layer.
+    /// 
+    /// * `init` - An initialisation scheme for the control points.
+    /// 
+    /// * `fan_in` - The number of incoming edges of the destination node.
+    /// 
+    /// * `rng` - A random number generator.
+    /// 
+    /// # Returns
+    /// 
+    /// * An edge with the given start index, end index, and spline.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let edge = Edge::standard_with_init(0, 1, 0, Init::Xavier, 4, &mut rng);
+    /// ```
+    pub fn standard_with_init(start: usize, end: usize, layer: usize, init: Init, fan_in: usize, rng: &mut impl Rng) -> Edge {
+        Edge::with_activation(start, end, Basis::BSpline.sample(init, fan_in, rng), layer)
+    }
+
+    /// Create a new edge that passes its input through unchanged, e.g. for a residual skip connection.
+    /// The activation is the symbolic identity with no base function, and the edge is frozen so that it stays the identity during training.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `start` - An index in the origin layer.
+    /// 
+    /// * `end` - An index in the destination layer.
+    ///
+    /// * `layer` - A layer index corresponding to the origin layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * An edge whose value is its input.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut edge = Edge::identity(0, 0, 2);
+    /// edge.skip = true;
+    /// ```
+    pub fn identity(start: usize, end: usize, layer: usize) -> Edge {
+        let mut edge: Edge = Edge::with_activation(start, end, Box::new(Symbolic::new(SymbolicFn::Identity, Vector::new(vec![1.0, 0.0, 1.0, 0.0]), (-1.0, 1.0))), layer);
+        edge.base = BaseFn::None;
+        edge.base_weight = 0.0;
+        edge.frozen = true;
+        edge
+    }
+
+    /// The B-spline of the edge.
+    /// 
+    /// # Returns
+    /// 
+    /// * A reference to the B-spline; panics if the activation of the edge is not a B-spline.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let degree = edge.spline().degree;
+    /// ```
+    pub fn spline(&self) -> &BSpline {
+        self.activation.downcast_ref::<BSpline>().expect("The activation of the edge is not a B-spline.")
+    }
+
+    /// A mutable reference to the B-spline of the edge; panics if the activation of the edge is not a B-spline.
+    pub fn spline_mut(&mut self) -> &mut BSpline {
+        self.activation.downcast_mut::<BSpline>().expect("The activation of the edge is not a B-spline.")
+    }
+
+
+    /// The forward pass computes the value of the activation at the given parameter value t and adds the value of the base function, each scaled by its weight.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - An input value, mapped into the domain of the spline according to the input mode.
     /// 
     /// # Returns
     /// 
@@ -90,37 +455,151 @@ impl Edge {
     /// let value = edge.forward(t);
     /// ```
     pub fn forward(&mut self, t: f64) -> f64 {
-        self.spline.eval(t) + silu(t)
+        let residual: Residual<f64> = self.residual();
+        residual.value(t, self.activation.eval(residual.spline_input(t)))
+    }
+
+    /// Computes the value of the edge like `forward`, but through a shared reference without updating the cache of the activation, so many threads can evaluate the edge at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - An input value, mapped into the domain of the spline according to the input mode.
+    ///
+    /// # Returns
+    ///
+    /// * A scalar representing the value of the edge at the given input value t.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let edge = Edge::new(start, end, spline, layer);
+    /// let value = edge.value(0.5);
+    /// ```
+    pub fn value(&self, t: f64) -> f64 {
+        let residual: Residual<f64> = self.residual();
+        residual.value(t, self.activation.value(residual.spline_input(t)))
     }
 
-    /// The forward batch pass computes the value of the spline at the given parameter values.
+    /// The forward batch pass computes the value of the edge at many inputs at once.
+    /// A B-spline activation is evaluated through its basis matrix in a single pass instead of the recursion per input.
     /// 
     /// # Arguments
     /// 
-    /// * `inputs` - A vector of parameter values between 0 and 1.
+    /// * `inputs` - A vector of input values, mapped into the domain of the spline according to the input mode.
     /// 
     /// # Returns
     /// 
-    /// * A vector representing the values of the spline at the given parameter values.
+    /// * A vector representing the values of the edge at the given input values, in the same order.
+    /// 
+    /// * The cached values of the pass, which `backward_batch_cached` reuses.
     /// 
     /// # Example
     /// 
     /// ```
     /// let edge = Edge::new(start, end, spline, layer);
     /// let inputs = Vector::new(vec![0.0, 0.5, 1.0]);
-    /// let values = edge.forward_batch(inputs);
+    /// let (values, cache) = edge.forward_batch(inputs);
     /// ```
-    pub fn forward_batch(&mut self, inputs: Vector) -> Vector {
-        let mut result: Vec<f64> = inputs.map(|t| self.spline.eval(t) + silu(t)).collect();
-        result.reverse();
-        Vector::new(result)
+    pub fn forward_batch(&mut self, inputs: Vector) -> (Vector, BatchCache) {
+        let cache: BatchCache = self.batch_cache(inputs);
+        let residual: Residual<f64> = self.residual();
+        let values: Vector = Vector::new(cache.inputs.elements.iter().zip(cache.activations.elements.iter()).map(|(&t, &a)| residual.value(t, a)).collect());
+        (values, cache)
     }
 
-    /// The backward pass computes the gradient of the spline with respect to the control points.
+    /// The backward batch pass adds the gradient of the edge with respect to its weights, summed over many inputs, to the stored gradient.
+    /// Unlike `backward`, which overwrites the stored gradient, repeated calls accumulate, so gradients of several batches can be combined before an update; call `zero_grad` to start over.
     /// 
     /// # Arguments
     /// 
-    /// * `t` - A parameter value between 0 and 1.
+    /// * `inputs` - A vector of input values, mapped into the domain of the spline according to the input mode.
+    /// 
+    /// * `upstream_gradients` - A vector with the gradient of the loss with respect to the value of the edge, one per input.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the backward pass was successful, which fails if the number of upstream gradients differs from the number of inputs or the stored gradient does not match the weights of the edge.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let inputs = Vector::new(vec![0.1, 0.5, 0.9]);
+    /// let upstream_gradients = Vector::new(vec![0.25, -0.5, 1.0]);
+    /// edge.zero_grad();
+    /// edge.backward_batch(&inputs, &upstream_gradients).unwrap();
+    /// let mean: Vector = &edge.gradient * (1.0 / inputs.len() as f64);
+    /// ```
+    pub fn backward_batch(&mut self, inputs: &Vector, upstream_gradients: &Vector) -> Result<(), KanError> {
+        let cache: BatchCache = self.batch_cache(inputs.clone());
+        self.backward_batch_cached(&cache, upstream_gradients)
+    }
+
+    /// The backward batch pass with the cached values of a preceding forward batch pass, which avoids evaluating the basis again.
+    /// The gradient summed over the inputs is added to the stored gradient.
+    /// A B-spline activation uses the cached basis matrix, so the gradient of the control points is a single product with the upstream gradients.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `cache` - The cached values returned by `forward_batch`.
+    /// 
+    /// * `upstream_gradients` - A vector with the gradient of the loss with respect to the value of the edge, one per input of the cache.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the backward pass was successful, which fails if the number of upstream gradients differs from the number of inputs or the stored gradient does not match the weights of the edge.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let (values, cache) = edge.forward_batch(inputs);
+    /// edge.backward_batch_cached(&cache, &upstream_gradients).unwrap();
+    /// ```
+    pub fn backward_batch_cached(&mut self, cache: &BatchCache, upstream_gradients: &Vector) -> Result<(), KanError> {
+        let inputs: &Vector = &cache.inputs;
+        if inputs.len() != upstream_gradients.len() {
+            return Err(KanError::mismatch("upstream gradients, one per input", inputs.len(), upstream_gradients.len()));
+        }
+        if self.gradient.len() != self.num_weights() {
+            return Err(KanError::mismatch("entries in the stored gradient, one per weight (call zero_grad first)", self.num_weights(), self.gradient.len()));
+        }
+        let spline_weight: f64 = self.spline_weight;
+        let mut gradient: Vector = match &cache.basis {
+            Some(basis) => &(&basis.transpose() * upstream_gradients) * spline_weight,
+            None => {
+                let mut params_gradient: Vector = Vector::zeros(self.activation.params().len());
+                for k in 0..inputs.len() {
+                    let spline_input: f64 = self.spline_input(inputs[k]);
+                    params_gradient = &params_gradient + &(&self.activation.grad_wrt_params(spline_input) * (spline_weight * upstream_gradients[k]));
+                }
+                params_gradient
+            }
+        };
+        gradient.push((0..inputs.len()).map(|k| self.base.eval(inputs[k]) * upstream_gradients[k]).sum());
+        gradient.push((0..inputs.len()).map(|k| cache.activations[k] * upstream_gradients[k]).sum());
+        self.gradient = &self.gradient + &gradient;
+
+        Ok(())
+    }
+
+    /// Evaluate the activation at many inputs, through the basis matrix for a B-spline.
+    fn batch_cache(&mut self, inputs: Vector) -> BatchCache {
+        let spline_inputs: Vector = inputs.map(|t| self.spline_input(t));
+        let (basis, activations): (Option<Matrix>, Vector) = match self.activation.downcast_ref::<BSpline>() {
+            Some(spline) => {
+                let basis: Matrix = spline.basis_matrix(&spline_inputs);
+                let activations: Vector = &basis * &spline.control_points;
+                (Some(basis), activations)
+            }
+            None => (None, spline_inputs.map(|u| self.activation.eval(u))),
+        };
+        BatchCache { inputs, basis, activations }
+    }
+
+    /// The backward pass computes the gradient of the edge with respect to its weights: the parameters of the activation, w_b, and w_s.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - An input value, mapped into the domain of the spline according to the input mode.
     /// 
     /// * `upstream_gradient` - A scalar representing the gradient of the loss with respect to the value of the spline at the given parameter value t.
     /// 
@@ -132,16 +611,305 @@ impl Edge {
     /// let upstream_gradient = 0.25;
     /// edge.backward(t, upstream_gradient);
     /// ```
-    pub fn backward(&mut self, t: f64, upstream_gradient: f64) -> Result<(), &'static str> {
-        let n: usize = self.spline.control_points.len();
-        for i in 0..n {
-            self.gradient[i] = self.spline.basis(i, self.spline.degree, t) * upstream_gradient;
-        }
+    pub fn backward(&mut self, t: f64, upstream_gradient: f64) -> Result<(), KanError> {
+        let residual: Residual<f64> = self.residual();
+        let spline_input: f64 = residual.spline_input(t);
+        let activation_gradient: Vector = self.activation.grad_wrt_params(spline_input);
+        self.gradient = residual.gradient(t, self.activation.eval(spline_input), &activation_gradient, upstream_gradient);
         
         Ok(())
     }
 
-    /// Uses the stored gradient of the spline with respect to the control points to update the control points.
+    /// Compute the derivative of the value of the edge, w_b * base(t) + w_s * activation(t), with respect to its input.
+    /// The derivative of the activation is analytic and includes the input mode, so it is 0 where the input is clamped.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - An input value.
+    /// 
+    /// # Returns
+    /// 
+    /// * The derivative of the edge at the given input value t, e.g. for backpropagation through the edge or the sensitivity of the output to the input.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let edge = Edge::new(start, end, spline, layer);
+    /// let slope = edge.input_gradient(0.5);
+    /// ```
+    pub fn input_gradient(&self, t: f64) -> f64 {
+        let residual: Residual<f64> = self.residual();
+        residual.input_gradient(t, self.activation.grad_wrt_input(residual.spline_input(t)))
+    }
+
+    /// Map an input of the edge to the input of its spline according to the input mode.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - An input value.
+    /// 
+    /// # Returns
+    /// 
+    /// * The input value to the spline.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// edge.input_mode = InputMode::Clamp;
+    /// let t = edge.spline_input(1.5); // 1.0
+    /// ```
+    pub fn spline_input(&self, t: f64) -> f64 {
+        self.input_mode.apply(t, self.activation.domain())
+    }
+
+    /// The base function, weights, input mode, and domain of the activation of the edge, which compute its value and gradients.
+    fn residual(&self) -> Residual<f64> {
+        Residual { base: self.base, base_weight: self.base_weight, spline_weight: self.spline_weight, input_mode: self.input_mode, domain: self.activation.domain() }
+    }
+
+    /// Fit every candidate c * f(a * x + b) + d to the activation of the edge on its domain and rank the fits by R², without changing the edge.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `candidates` - A list of candidate functions, e.g. `SymbolicFn::ALL`.
+    /// 
+    /// # Returns
+    /// 
+    /// * The fitted symbolic activation of every candidate with its R², from the best to the worst fit, see `symbolic::suggest`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// for (symbolic, r2) in edge.suggest_symbolic(&SymbolicFn::ALL).iter().take(3) {
+    ///     println!("{} (R² = {})", symbolic, r2);
+    /// }
+    /// ```
+    pub fn suggest_symbolic(&self, candidates: &[SymbolicFn]) -> Vec<(Symbolic, f64)> {
+        let domain: (f64, f64) = self.activation.domain();
+        let num_samples: usize = 101;
+        let xs: Vector = Vector::new((0..num_samples).map(|i| domain.0 + (domain.1 - domain.0) * i as f64 / (num_samples - 1) as f64).collect());
+        let ys: Vector = xs.map(|x| self.activation.value(x));
+        suggest(candidates, &xs, &ys, domain)
+    }
+
+    /// Snap the activation of the edge to the analytic candidate c * f(a * x + b) + d that best fits it on its domain, measured by R², the first entry of `suggest_symbolic`.
+    /// The edge becomes symbolic and uses the analytic form in the forward and backward passes, where a, b, c, and d remain trainable.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `candidates` - A non-empty list of candidate functions, e.g. `SymbolicFn::ALL`.
+    /// 
+    /// # Returns
+    /// 
+    /// * The chosen function and the R² of its fit.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let (function, r2) = edge.symbolify(&SymbolicFn::ALL);
+    /// ```
+    pub fn symbolify(&mut self, candidates: &[SymbolicFn]) -> (SymbolicFn, f64) {
+        if candidates.is_empty() {
+            panic!("Symbolify needs at least one candidate function.");
+        }
+        let (symbolic, r2) = self.suggest_symbolic(candidates).swap_remove(0);
+        let function: SymbolicFn = symbolic.function;
+        self.activation = Box::new(symbolic);
+        self.zero_grad();
+        (function, r2)
+    }
+
+    /// Compose the edge with an expression of its input, as a term of the formula of a KAN.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - The expression of the input of the edge.
+    /// 
+    /// # Returns
+    /// 
+    /// * The expression of the value of the edge scaled by its keep probability, or an error if the edge has a nonzero spline weight and an activation that is not symbolic, or clamps its inputs.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// edge.symbolify(&SymbolicFn::ALL);
+    /// let expr = edge.expr(Expr::Var(edge.start)).unwrap();
+    /// ```
+    pub fn expr(&self, input: Expr) -> Result<Expr, KanError> {
+        let mut terms: Vec<Expr> = Vec::with_capacity(2);
+        let base: Option<Expr> = match self.base {
+            BaseFn::Silu => Some(Expr::Product(vec![input.clone(), Expr::Apply(SymbolicFn::Sigmoid, Box::new(input.clone()))])),
+            BaseFn::Identity => Some(input.clone()),
+            BaseFn::Tanh => Some(Expr::Apply(SymbolicFn::Tanh, Box::new(input.clone()))),
+            BaseFn::None => None,
+        };
+        if let Some(base) = base.filter(|_| self.base_weight != 0.0) {
+            terms.push(Expr::Product(vec![Expr::Const(self.base_weight), base]));
+        }
+        if self.spline_weight != 0.0 {
+            let Some(symbolic) = self.activation.downcast_ref::<Symbolic>() else {
+                return Err(KanError::Unsupported("Only symbolic activations can be expressed as a formula."));
+            };
+            let (lower, upper) = symbolic.domain;
+            let spline_input: Expr = match self.input_mode {
+                InputMode::Raw => input,
+                InputMode::Squash => Expr::Sum(vec![Expr::Const(lower), Expr::Product(vec![Expr::Const(upper - lower), Expr::Apply(SymbolicFn::Sigmoid, Box::new(input))])]),
+                InputMode::Clamp => return Err(KanError::Unsupported("Clamped inputs cannot be expressed as a formula.")),
+            };
+            terms.push(Expr::Product(vec![Expr::Const(self.spline_weight), symbolic.expr(spline_input)]));
+        }
+        Ok(Expr::Product(vec![Expr::Const(self.dropout_scale()), Expr::Sum(terms)]))
+    }
+
+    /// Whether the edge has been snapped to an analytic function with `symbolify`.
+    pub fn is_symbolic(&self) -> bool {
+        self.activation.downcast_ref::<Symbolic>().is_some()
+    }
+
+    /// Set the multiplier of the learning rate for the weights of the edge, e.g. to train some edges more slowly than others.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scale` - A non-negative scalar that multiplies the learning rate in `update_weights`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut edge = Edge::standard(0, 1, 0);
+    /// edge.set_lr_scale(0.1);
+    /// ```
+    pub fn set_lr_scale(&mut self, scale: f64) {
+        if !(scale >= 0.0 && scale.is_finite()) {
+            panic!("The learning rate scale must be a finite non-negative number.");
+        }
+        self.lr_scale = scale;
+    }
+
+    /// Set the dropout probability of the edge, the probability that its value is masked in a training step of a KAN in training mode.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `probability` - A scalar in [0, 1).
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut edge = Edge::standard(0, 1, 0);
+    /// edge.set_dropout(0.2);
+    /// ```
+    pub fn set_dropout(&mut self, probability: f64) {
+        if !(0.0..1.0).contains(&probability) {
+            panic!("The dropout probability must be at least 0 and smaller than 1.");
+        }
+        self.dropout = probability;
+    }
+
+    /// The factor that the value of the edge is multiplied with in the node it feeds into.
+    /// 
+    /// # Returns
+    /// 
+    /// * 0 if the edge is masked and 1 if it is kept in the current training step, or the keep probability 1 - p outside of training steps, so the expected value of the node is the same.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let contribution = edge.dropout_scale() * edge.value(t);
+    /// ```
+    pub fn dropout_scale(&self) -> f64 {
+        match self.masked {
+            Some(true) => 0.0,
+            Some(false) => 1.0,
+            None => 1.0 - self.dropout,
+        }
+    }
+
+    /// Switch the edge to training or evaluation mode. An edge in evaluation mode is never masked by dropout, so its mask is cleared.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `training` - Whether the edge is in training mode.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// edge.set_training(false);
+    /// ```
+    pub fn set_training(&mut self, training: bool) {
+        self.training = training;
+        if !training {
+            self.masked = None;
+        }
+    }
+
+    /// Freeze the edge, so `update_weights` leaves its weights unchanged.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut edge = Edge::standard(0, 1, 0);
+    /// edge.freeze();
+    /// ```
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Unfreeze the edge, so `update_weights` updates its weights again.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// edge.unfreeze();
+    /// ```
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    /// The number of trainable weights of the edge: the parameters of the activation, w_b, and w_s.
+    pub fn num_weights(&self) -> usize {
+        self.activation.params().len() + 2
+    }
+
+    /// The trainable weights of the edge, in the layout of its gradient.
+    /// 
+    /// # Returns
+    /// 
+    /// * A vector with the parameters of the activation followed by w_b and w_s.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let weights = edge.weights();
+    /// ```
+    pub fn weights(&self) -> Vector {
+        let mut weights: Vector = self.activation.params().clone();
+        weights.push(self.base_weight);
+        weights.push(self.spline_weight);
+        weights
+    }
+
+    /// Set the trainable weights of the edge.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `weights` - A vector with the parameters of the activation followed by w_b and w_s.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let weights = edge.weights();
+    /// edge.set_weights(&weights);
+    /// ```
+    pub fn set_weights(&mut self, weights: &Vector) {
+        let n: usize = self.activation.params().len();
+        if weights.len() != n + 2 {
+            panic!("The number of weights must match the parameters of the activation plus the base and spline weights.");
+        }
+        *self.activation.params_mut() = Vector::new(weights.elements[..n].to_vec());
+        self.base_weight = weights[n];
+        self.spline_weight = weights[n + 1];
+    }
+
+    /// Uses the stored gradient of the edge with respect to its weights to update the weights.
     /// 
     /// # Arguments
     /// 
@@ -158,16 +926,33 @@ impl Edge {
     /// let learning_rate = 0.01;
     /// edge.update_weights(learning_rate);
     /// ```
-    pub fn update_weights(&mut self, learning_rate: f64) -> Result<(), &'static str> {
+    pub fn update_weights(&mut self, learning_rate: f64) -> Result<(), KanError> {
         if learning_rate <= 0.0 {
             panic!("The learning rate must be greater than 0.");
         }
-        // control points = control points - learning_rate * gradient
-        self.spline.control_points = &self.spline.control_points - &(&self.gradient * learning_rate);
+        // weights = weights - learning_rate * lr_scale * gradient in place, unless the edge is frozen
+        if !self.frozen {
+            descend(self.activation.params_mut(), &mut self.base_weight, &mut self.spline_weight, &self.gradient, -learning_rate * self.lr_scale)?;
+            self.activation.project();
+        }
         // Reset gradient
-        self.gradient = Vector { elements: vec![0.0; self.spline.control_points.len()] };
+        self.zero_grad();
         Ok(())
     }
+
+    /// Reset the stored gradient to zeros with one entry per weight, e.g. before accumulating gradients with `backward_batch` or after the number of weights changed.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// edge.zero_grad();
+    /// ```
+    pub fn zero_grad(&mut self) {
+        let n: usize = self.num_weights();
+        // Reuse the allocation of the gradient
+        self.gradient.elements.clear();
+        self.gradient.elements.resize(n, 0.0);
+    }
 }
 
 /// The Sigmoid Linear Unit (SiLU) activation function.
@@ -186,12 +971,12 @@ impl Edge {
 /// let x = 0.5;
 /// let silu = silu(x);
 /// ```
-fn silu(x: f64) -> f64 {
-    x / (1.0 + (-x).exp())
+fn silu<T: Float>(x: T) -> T {
+    x / (T::ONE + (-x).exp())
 }
 
 impl std::fmt::Display for Edge {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Edge(start: {}, end: {}, layer: {}, spline: {})", self.start, self.end, self.layer, self.spline)
+        write!(f, "Edge(start: {}, end: {}, layer: {}, activation: {})", self.start, self.end, self.layer, self.activation)
     }
 }
\ No newline at end of file
@@ -0,0 +1,134 @@
+use std::{cmp::Ordering, fmt::{Debug, Display}, iter::Sum, ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign}};
+
+/// A floating-point number type that vectors and matrices can hold, implemented for `f32` and `f64`.
+/// Single precision halves the memory of the elements, while double precision is the default everywhere.
+///
+/// # Example
+///
+/// ```
+/// fn mean<T: Float>(values: &[T]) -> T {
+///     values.iter().copied().sum::<T>() / T::from_usize(values.len())
+/// }
+/// ```
+pub trait Float:
+    Copy + Debug + Display + Default + PartialEq + PartialOrd + Send + Sync + 'static
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+    + AddAssign + SubAssign + MulAssign + DivAssign + Sum + for<'a> Sum<&'a Self>
+{
+    /// The additive identity 0.
+    const ZERO: Self;
+
+    /// The multiplicative identity 1.
+    const ONE: Self;
+
+    /// The difference between 1 and the next larger representable number.
+    const EPSILON: Self;
+
+    /// Positive infinity.
+    const INFINITY: Self;
+
+    /// Convert from a double precision number, rounding to the nearest representable value.
+    fn from_f64(x: f64) -> Self;
+
+    /// Convert to a double precision number.
+    fn to_f64(self) -> f64;
+
+    /// Convert from an unsigned integer, rounding to the nearest representable value.
+    fn from_usize(n: usize) -> Self;
+
+    /// The absolute value.
+    fn abs(self) -> Self;
+
+    /// The square root.
+    fn sqrt(self) -> Self;
+
+    /// The exponential function e^x.
+    fn exp(self) -> Self;
+
+    /// The number raised to a floating-point power.
+    fn powf(self, n: Self) -> Self;
+
+    /// The hyperbolic tangent.
+    fn tanh(self) -> Self;
+
+    /// The larger of two numbers, ignoring NaN.
+    fn max(self, other: Self) -> Self;
+
+    /// The smaller of two numbers, ignoring NaN.
+    fn min(self, other: Self) -> Self;
+
+    /// Whether the number is neither infinite nor NaN.
+    fn is_finite(self) -> bool;
+
+    /// Whether the number is NaN.
+    fn is_nan(self) -> bool;
+
+    /// A total ordering of the numbers, including NaN.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! impl_float {
+    ($t:ty) => {
+        impl Float for $t {
+            const ZERO: $t = 0.0;
+            const ONE: $t = 1.0;
+            const EPSILON: $t = <$t>::EPSILON;
+            const INFINITY: $t = <$t>::INFINITY;
+
+            fn from_f64(x: f64) -> $t {
+                x as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_usize(n: usize) -> $t {
+                n as $t
+            }
+
+            fn abs(self) -> $t {
+                <$t>::abs(self)
+            }
+
+            fn sqrt(self) -> $t {
+                <$t>::sqrt(self)
+            }
+
+            fn exp(self) -> $t {
+                <$t>::exp(self)
+            }
+
+            fn powf(self, n: $t) -> $t {
+                <$t>::powf(self, n)
+            }
+
+            fn tanh(self) -> $t {
+                <$t>::tanh(self)
+            }
+
+            fn max(self, other: $t) -> $t {
+                <$t>::max(self, other)
+            }
+
+            fn min(self, other: $t) -> $t {
+                <$t>::min(self, other)
+            }
+
+            fn is_finite(self) -> bool {
+                <$t>::is_finite(self)
+            }
+
+            fn is_nan(self) -> bool {
+                <$t>::is_nan(self)
+            }
+
+            fn total_cmp(&self, other: &$t) -> Ordering {
+                <$t>::total_cmp(self, other)
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
@@ -1,13 +1,17 @@
-use crate::data_structures::{node::Node, vector::Vector, matrix::Matrix};
+use crate::data_structures::{edge::Edge, node::{Node, Aggregation}, norm::Norm, vector::Vector, matrix::Matrix, sparse::SparseMatrix};
+use crate::error::KanError;
 use std::rc::Rc;
-use std::cell::{RefCell, RefMut};
+use std::cell::{Ref, RefCell, RefMut};
+use serde::{Serialize, Deserialize};
 
 /// A layer is a collection of nodes and its associated edges in a KAN.
-/// It is represented as a list of nodes.
+/// It is represented as a list of nodes and an optional normalization of the values of the nodes, which is applied before they are passed to the next layer.
 /// The layer struct provides methods to compute the value of the layer and update the gradients of the nodes.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Layer {
     pub nodes: Vec<Rc<RefCell<Node>>>,
+    #[serde(default)] // Files written before normalizations were added have none
+    pub norm: Option<Norm>, // Must have one entry per node
 }
 
 impl Layer {
@@ -19,7 +23,7 @@ impl Layer {
     /// 
     /// # Returns
     /// 
-    /// * A layer with the given list of nodes.
+    /// * A layer with the given list of nodes and no normalization.
     /// 
     /// # Example
     /// 
@@ -29,7 +33,7 @@ impl Layer {
     /// let layer = Layer::new(nodes);
     /// ```
     pub fn new(nodes: Vec<Rc<RefCell<Node>>>) -> Layer {
-        Layer { nodes }
+        Layer { nodes, norm: None }
     }
 
     /// Add a node to the layer.
@@ -56,29 +60,301 @@ impl Layer {
     /// 
     /// # Returns
     /// 
-    /// * A matrix representing the value of the layer given the input values, where the entry (i, j) is the value of the j-th outgoing edge for the i-th node.
+    /// * A matrix representing the value of the layer given the input values, where the entry (i, j) is the value of the j-th outgoing edge for the i-th node, or an error if the shape of the input does not match the layer.
     /// 
     /// # Example
     ///  
     /// ```
     /// let layer = Layer::new(nodes);
     /// let input = Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
-    /// let value = layer.forward(input);
+    /// let value = layer.forward(input).unwrap();
     /// ```
-    pub fn forward(&self, input: Matrix) -> Matrix {
-        if input.shape().0 != self.nodes.len() {
-            panic!("The number of rows in the input matrix must be equal to the number of nodes in the layer.");
-        }
+    pub fn forward(&self, input: Matrix) -> Result<Matrix, KanError> {
+        let activations: Vector = self.activations(input)?;
         let mut result: Matrix = Matrix::new(vec![]);
-        for i in 0..self.nodes.len() {
-            let mut node: RefMut<Node> = self.nodes[i].borrow_mut();
-            let sum: f64 = node.forward(&input[i]);
-            println!("Node {}: {}", i, sum);
-            let result_vector: Vector = Vector::new(vec![sum; node.outgoing.len()]);
+        for (i, node) in self.nodes.iter().enumerate() {
+            let result_vector: Vector = Vector::new(vec![activations[i]; node.borrow().outgoing.len()]);
             result.push(result_vector);
         }
+        Ok(result)
+    }
+
+    /// Computes the value of each node in the layer given the input values.
+    /// Unlike `forward`, the values are not replicated over the outgoing edges, so this also works for output layers whose nodes have no outgoing edges.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A matrix where the entry (i, j) is the input to the j-th incoming edge for the i-th node.
+    /// 
+    /// # Returns
+    /// 
+    /// * A vector where the i-th entry is the value of the i-th node, or an error if the shape of the input does not match the layer.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let layer = Layer::new(nodes);
+    /// let input = Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+    /// let activations = layer.activations(input).unwrap();
+    /// ```
+    pub fn activations(&self, input: Matrix) -> Result<Vector, KanError> {
+        if input.rows != self.nodes.len() {
+            return Err(KanError::mismatch("input rows, one per node", self.nodes.len(), input.rows));
+        }
+        let mut result: Vector = Vector::new(vec![]);
+        for i in 0..self.nodes.len() {
+            let node_input: Vector = self.node_input(&input, i)?;
+            result.push(self.nodes[i].borrow_mut().forward(&node_input).map_err(|err| err.at_node(i))?);
+        }
+        Ok(result)
+    }
+
+    /// Applies the normalization of the layer, if any, to the values of its nodes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `values` - A vector where the i-th entry is the value of the i-th node.
+    /// 
+    /// # Returns
+    /// 
+    /// * The normalized values, or the given values if the layer has no normalization.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let output = layer.normalize(layer.activations(input).unwrap());
+    /// ```
+    pub fn normalize(&self, values: Vector) -> Vector {
+        match &self.norm {
+            Some(norm) => norm.forward(&values),
+            None => values,
+        }
+    }
+
+    /// Builds the input matrix of the layer from the values of the nodes in the previous layer.
+    /// Each incoming edge receives the value of the node at its `start` index.
+    /// The rows of nodes with fewer incoming edges than the widest node, e.g. after pruning, are padded with zeros, which `node_input` strips.
+    /// Layers with skip edges are routed with `route_with_skip`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `activations` - A vector where the i-th entry is the value of the i-th node in the previous layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * A matrix where the entry (i, j) is the input to the j-th incoming edge for the i-th node.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let layer = Layer::new(nodes);
+    /// let activations = Vector::new(vec![0.25, 0.75]);
+    /// let input = layer.route(&activations);
+    /// ```
+    pub fn route(&self, activations: &Vector) -> Matrix {
+        self.route_with_skip(activations, &Vector::new(vec![]))
+    }
+
+    /// Builds the input matrix of the layer like `route`, where the skip edges receive the values of the nodes two layers back.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `activations` - A vector where the i-th entry is the value of the i-th node in the previous layer.
+    /// 
+    /// * `skip_activations` - A vector where the i-th entry is the value of the i-th node two layers back, or the i-th input for the second layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * A matrix where the entry (i, j) is the input to the j-th incoming edge for the i-th node.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let input = layer.route_with_skip(&hidden, &kan_input);
+    /// ```
+    pub fn route_with_skip(&self, activations: &Vector, skip_activations: &Vector) -> Matrix {
+        let width: usize = self.nodes.iter().map(|node| node.borrow().incoming.len()).max().unwrap_or(0);
+        let mut result: Matrix = Matrix::new(vec![]);
+        for node in self.nodes.iter() {
+            let mut row: Vector = Vector::zeros(width);
+            for (j, edge) in node.borrow().incoming.iter().enumerate() {
+                let edge: Ref<Edge> = edge.borrow();
+                let values: &Vector = if edge.skip { skip_activations } else { activations };
+                if edge.start >= values.len() {
+                    if edge.skip {
+                        panic!("The start index of a skip edge must be smaller than the number of nodes two layers back.");
+                    }
+                    panic!("The start index of an incoming edge must be smaller than the number of nodes in the previous layer.");
+                }
+                row[j] = values[edge.start];
+            }
+            result.push(row);
+        }
         result
     }
+
+    /// The inputs of the incoming edges of a node, without the padding of `route`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A matrix where the entry (i, j) is the input to the j-th incoming edge for the i-th node.
+    /// 
+    /// * `i` - The index of the node.
+    /// 
+    /// # Returns
+    /// 
+    /// * A vector with one entry per incoming edge of the node, or an error if the row of the node has neither one entry per incoming edge nor the padded width.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let t = layer.node_input(&layer.route(&activations), 0).unwrap();
+    /// ```
+    pub fn node_input(&self, input: &Matrix, i: usize) -> Result<Vector, KanError> {
+        let row: Vector = input.row(i);
+        let count: usize = self.nodes[i].borrow().incoming.len();
+        // Only the padding of `route` up to the widest node may follow the inputs
+        let width: usize = self.nodes.iter().map(|node| node.borrow().incoming.len()).max().unwrap_or(0);
+        if row.len() != count && row.len() != width {
+            return Err(KanError::mismatch("inputs, one per incoming edge", count, row.len()).at_node(i));
+        }
+        Ok(Vector::new(row.elements[..count].to_vec()))
+    }
+
+    /// Propagates the upstream gradients of the nodes in the layer to the nodes in the previous layer.
+    /// Each incoming edge is treated as having unit slope with respect to its input, so the gradient of a node in the previous layer is the sum of the upstream gradients of the nodes it feeds into.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `upstream_gradient` - A vector where the i-th entry is the gradient of the loss with respect to the value of the i-th node in the layer.
+    /// 
+    /// * `previous_width` - The number of nodes in the previous layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a vector where the j-th entry is the gradient of the loss with respect to the value of the j-th node in the previous layer, or an error if there is not one upstream gradient per node or as in `adjacency`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let layer = Layer::new(nodes);
+    /// let upstream_gradient = Vector::new(vec![0.5, 0.25]);
+    /// let previous_gradient = layer.propagate(&upstream_gradient, 3).unwrap();
+    /// ```
+    pub fn propagate(&self, upstream_gradient: &Vector, previous_width: usize) -> Result<Vector, KanError> {
+        if upstream_gradient.len() != self.nodes.len() {
+            return Err(KanError::mismatch("upstream gradients, one per node", self.nodes.len(), upstream_gradient.len()));
+        }
+        Ok(self.adjacency(previous_width)?.mul_transpose(upstream_gradient))
+    }
+
+    /// Propagates the upstream gradients of the nodes in the layer to the nodes in the previous layer like `propagate`, taking the aggregation of every node into account.
+    /// Each incoming edge still has unit slope, but the gradient reaching an incoming edge of a product node is scaled by the product of the values of the other incoming edges, see `Node::edge_gradients`.
+    /// The gradients are scaled by the dropout scale of every edge, and skip edges are left out, see `propagate_skip`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A matrix where the entry (i, j) is the input to the j-th incoming edge for the i-th node.
+    /// 
+    /// * `upstream_gradient` - A vector where the i-th entry is the gradient of the loss with respect to the value of the i-th node in the layer.
+    /// 
+    /// * `previous_width` - The number of nodes in the previous layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a vector where the j-th entry is the gradient of the loss with respect to the value of the j-th node in the previous layer, or an error if there is not one upstream gradient per node, a row of the input does not fit its node, or an edge starts at a node that does not exist.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let previous_gradient = layer.propagate_through(&input, &upstream_gradient, 3).unwrap();
+    /// ```
+    pub fn propagate_through(&self, input: &Matrix, upstream_gradient: &Vector, previous_width: usize) -> Result<Vector, KanError> {
+        // The adjacency has unit entries, which only holds for sum nodes whose edges are not scaled by dropout
+        let unscaled = |node: &Rc<RefCell<Node>>| node.borrow().incoming.iter().all(|edge| edge.borrow().dropout_scale() == 1.0);
+        if self.nodes.iter().all(|node| node.borrow().aggregation == Aggregation::Sum && unscaled(node)) {
+            return self.propagate(upstream_gradient, previous_width);
+        }
+        self.propagate_edges(input, upstream_gradient, previous_width, false)
+    }
+
+    /// Propagates the upstream gradients of the nodes in the layer through the skip edges to the nodes two layers back, like `propagate_through`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A matrix where the entry (i, j) is the input to the j-th incoming edge for the i-th node.
+    /// 
+    /// * `upstream_gradient` - A vector where the i-th entry is the gradient of the loss with respect to the value of the i-th node in the layer.
+    /// 
+    /// * `skip_width` - The number of nodes two layers back.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a vector where the j-th entry is the gradient of the loss through the skip edges with respect to the value of the j-th node two layers back, or an error as in `propagate_through`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let skip_gradient = layer.propagate_skip(&input, &upstream_gradient, 2).unwrap();
+    /// ```
+    pub fn propagate_skip(&self, input: &Matrix, upstream_gradient: &Vector, skip_width: usize) -> Result<Vector, KanError> {
+        self.propagate_edges(input, upstream_gradient, skip_width, true)
+    }
+
+    /// Sums the gradients of the incoming edges that are, or are not, skip edges at their start indices.
+    fn propagate_edges(&self, input: &Matrix, upstream_gradient: &Vector, width: usize, skip: bool) -> Result<Vector, KanError> {
+        if upstream_gradient.len() != self.nodes.len() {
+            return Err(KanError::mismatch("upstream gradients, one per node", self.nodes.len(), upstream_gradient.len()));
+        }
+        let mut result: Vector = Vector::zeros(width);
+        for (i, node) in self.nodes.iter().enumerate() {
+            let node: Ref<Node> = node.borrow();
+            let gradients: Vector = node.edge_gradients(&self.node_input(input, i)?, upstream_gradient[i]);
+            for (k, (edge, &gradient)) in node.incoming.iter().zip(gradients.iter()).enumerate() {
+                let edge: Ref<Edge> = edge.borrow();
+                if edge.skip != skip {
+                    continue;
+                }
+                if edge.start >= width {
+                    return Err(start_mismatch(edge.start, width).at_node(i).at_edge(k));
+                }
+                result[edge.start] += gradient;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Builds the sparse adjacency matrix between the nodes in the previous layer and the nodes in the layer.
+    /// Only connected pairs of nodes are stored, so the cost of a pruned layer grows with its number of edges rather than the product of the widths.
+    /// Skip edges do not start in the previous layer, so they are left out.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `previous_width` - The number of nodes in the previous layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a sparse matrix where the entry (i, j) is the number of incoming edges of the i-th node with start index j, or an error if an edge starts at a node that does not exist in the previous layer.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let layer = Layer::new(nodes);
+    /// let adjacency = layer.adjacency(3).unwrap();
+    /// ```
+    pub fn adjacency(&self, previous_width: usize) -> Result<SparseMatrix, KanError> {
+        let mut triplets: Vec<(usize, usize, f64)> = Vec::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            for (k, edge) in node.borrow().incoming.iter().enumerate().filter(|(_, edge)| !edge.borrow().skip) {
+                let start: usize = edge.borrow().start;
+                if start >= previous_width {
+                    return Err(start_mismatch(start, previous_width).at_node(i).at_edge(k));
+                }
+                triplets.push((i, start, 1.0));
+            }
+        }
+        Ok(SparseMatrix::from_triplets(self.nodes.len(), previous_width, &triplets))
+    }
     
     /// The backward pass computes the gradients of the edges in the incoming layer given the upstream gradients and the input values.
     /// 
@@ -100,19 +376,18 @@ impl Layer {
     /// let upstream_gradient = Vector::new(vec![0.5, 0.25]);
     /// layer.backward(input, upstream_gradient);
     /// ```
-    pub fn backward(&self, input: Matrix, upstream_gradient: &Vector) -> Result<(), &str> {
+    pub fn backward(&self, input: Matrix, upstream_gradient: &Vector) -> Result<(), KanError> {
         if input.shape().0 != self.nodes.len() {
-            panic!("The number of rows in the input matrix must be equal to the number of nodes in the layer.");
+            return Err(KanError::mismatch("input rows, one per node", self.nodes.len(), input.shape().0));
         }
         if upstream_gradient.len() != self.nodes.len() {
-            panic!("The number of elements in the upstream gradient vector must be equal to the number of nodes in the layer.");
+            return Err(KanError::mismatch("upstream gradients, one per node", self.nodes.len(), upstream_gradient.len()));
         }
 
         for (i, node) in self.nodes.iter().enumerate() {
+            let node_input: Vector = self.node_input(&input, i)?;
             let mut node: RefMut<Node> = node.borrow_mut();
-            node.backward(input[i].clone(), upstream_gradient[i]).unwrap_or_else(|err| {
-                panic!("{}", err)
-            });
+            node.backward(node_input, upstream_gradient[i]).map_err(|err| err.at_node(i))?;
         }
         Ok(())
     }
@@ -134,12 +409,108 @@ impl Layer {
     /// let learning_rate = 0.01;
     /// layer.update_weights(learning_rate);
     /// ```
-    pub fn update_weights(&self, learning_rate: f64) -> Result<(), &str> {
-        for node in self.nodes.iter() {
-            node.borrow_mut().update_weights(learning_rate).unwrap_or_else(|err| {
-                panic!("{}", err)
-            });
+    pub fn update_weights(&self, learning_rate: f64) -> Result<(), KanError> {
+        for (i, node) in self.nodes.iter().enumerate() {
+            node.borrow_mut().update_weights(learning_rate).map_err(|err| err.at_node(i))?;
         }
         Ok(())
     }
+
+    /// Switch the incoming edges of the nodes in the layer and its normalization to training or evaluation mode.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `training` - Whether the layer is in training mode.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// layer.set_training(false);
+    /// ```
+    pub fn set_training(&mut self, training: bool) {
+        for node in self.nodes.iter() {
+            for edge in node.borrow().incoming.iter() {
+                edge.borrow_mut().set_training(training);
+            }
+        }
+        if let Some(norm) = &mut self.norm {
+            norm.training = training;
+        }
+    }
+
+    /// Set the dropout probability of all incoming edges of the nodes in the layer, see `Edge::set_dropout`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `probability` - A scalar in [0, 1).
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let layer = Layer::new(nodes);
+    /// layer.set_dropout(0.1);
+    /// ```
+    pub fn set_dropout(&self, probability: f64) {
+        for node in self.nodes.iter() {
+            for edge in node.borrow().incoming.iter() {
+                edge.borrow_mut().set_dropout(probability);
+            }
+        }
+    }
+
+    /// Set the multiplier of the learning rate for all incoming edges of the nodes in the layer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scale` - A non-negative scalar that multiplies the learning rate of the edges.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let layer = Layer::new(nodes);
+    /// layer.set_lr_scale(0.1);
+    /// ```
+    pub fn set_lr_scale(&self, scale: f64) {
+        for node in self.nodes.iter() {
+            for edge in node.borrow().incoming.iter() {
+                edge.borrow_mut().set_lr_scale(scale);
+            }
+        }
+    }
+
+    /// Freeze all incoming edges of the nodes in the layer, so their control points are not updated during training.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let layer = Layer::new(nodes);
+    /// layer.freeze();
+    /// ```
+    pub fn freeze(&self) {
+        for node in self.nodes.iter() {
+            for edge in node.borrow().incoming.iter() {
+                edge.borrow_mut().freeze();
+            }
+        }
+    }
+
+    /// Unfreeze all incoming edges of the nodes in the layer.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// layer.unfreeze();
+    /// ```
+    pub fn unfreeze(&self) {
+        for node in self.nodes.iter() {
+            for edge in node.borrow().incoming.iter() {
+                edge.borrow_mut().unfreeze();
+            }
+        }
+    }
+}
+
+/// The error of an incoming edge whose start index is not smaller than the number of nodes in the layer it starts at.
+fn start_mismatch(start: usize, width: usize) -> KanError {
+    KanError::mismatch("values of the layer an edge starts at", start + 1, width)
 }
@@ -1,272 +1,705 @@
-use std::ops::{Add, Sub, Mul, Index, IndexMut};
-use crate::data_structures::vector::Vector;
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Index, IndexMut};
+use crate::data_structures::{vector::Vector, float::Float};
+use crate::error::KanError;
+#[cfg(feature = "blas")]
+use crate::data_structures::blas;
 use rand::Rng;
+use serde::{Serialize, Deserialize};
 
-/// A matrix is a vector of vectors.
-/// It is represented as a two-dimensional array of numbers.
+/// A matrix is a two-dimensional array of numbers.
+/// It is represented as its elements in a single contiguous vector in row-major order together with its number of rows and columns.
+/// Indexing a matrix gives a row as a slice, so `matrix[i][j]` is the element in row i and column j.
 /// The matrix struct implements basic operations such as addition, subtraction, multiplication, and division.
-#[derive(Debug, Clone, PartialEq)]
-pub struct Matrix {
-    pub rows: Vec<Vector>,
+/// The elements are `f64` by default and can be any `Float`, e.g. `Matrix<f32>` for single precision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Matrix<T: Float = f64> {
+    pub elements: Vec<T>, // Elements in row-major order
+    pub rows: usize,
+    pub cols: usize,
 }
 
-impl Add<Matrix> for Matrix {
-    type Output = Matrix;
+impl<T: Float> Add<Matrix<T>> for Matrix<T> {
+    type Output = Matrix<T>;
 
-    fn add(self, other: Matrix) -> Matrix {
-        if self.shape() != other.shape() {
-            panic!("Matrices must have the same shape for addition.");
-        }
-        Matrix { rows: self.rows.iter().zip(other.rows.iter()).map(|(a, b)| a + b).collect() }
+    fn add(self, other: Matrix<T>) -> Matrix<T> {
+        &self + &other
     }
 }
 
-impl Add<&Matrix> for &Matrix {
-    type Output = Matrix;
+impl<T: Float> Add<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
 
-    fn add(self, other: &Matrix) -> Matrix {
-        if self.shape() != other.shape() {
-            panic!("Matrices must have the same shape for addition.");
-        }
-        Matrix { rows: self.rows.iter().zip(other.rows.iter()).map(|(a, b)| a + b).collect() }
+    fn add(self, other: &Matrix<T>) -> Matrix<T> {
+        self.try_add(other).unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
-impl Sub<Matrix> for Matrix {
-    type Output = Matrix;
+impl<T: Float> Sub<Matrix<T>> for Matrix<T> {
+    type Output = Matrix<T>;
 
-    fn sub(self, other: Matrix) -> Matrix {
-        if self.shape() != other.shape() {
-            panic!("Matrices must have the same shape for subtraction.");
-        }
-        Matrix { rows: self.rows.iter().zip(other.rows.iter()).map(|(a, b)| a - b).collect() }
+    fn sub(self, other: Matrix<T>) -> Matrix<T> {
+        &self - &other
     }
 }
 
-impl Sub<&Matrix> for &Matrix {
-    type Output = Matrix;
+impl<T: Float> Sub<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
 
-    fn sub(self, other: &Matrix) -> Matrix {
-        if self.shape() != other.shape() {
-            panic!("Matrices must have the same shape for subtraction.");
-        }
-        Matrix { rows: self.rows.iter().zip(other.rows.iter()).map(|(a, b)| a - b).collect() }
+    fn sub(self, other: &Matrix<T>) -> Matrix<T> {
+        self.try_sub(other).unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
-impl Mul<f64> for Matrix {
-    type Output = Matrix;
+impl<T: Float> Mul<T> for Matrix<T> {
+    type Output = Matrix<T>;
 
-    fn mul(self, scalar: f64) -> Matrix {
-        Matrix { rows: self.rows.iter().map(|x| x * scalar).collect() }
+    fn mul(self, scalar: T) -> Matrix<T> {
+        &self * scalar
     }
 }
 
-impl Mul<f64> for &Matrix {
-    type Output = Matrix;
+impl<T: Float> Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
 
-    fn mul(self, scalar: f64) -> Matrix {
-        Matrix { rows: self.rows.iter().map(|x| x * scalar).collect() }
+    fn mul(self, scalar: T) -> Matrix<T> {
+        Matrix { elements: self.elements.iter().map(|&x| x * scalar).collect(), rows: self.rows, cols: self.cols }
     }
 }
 
-impl Mul<Vector> for Matrix {
-    type Output = Vector;
+impl<T: Float> Mul<Vector<T>> for Matrix<T> {
+    type Output = Vector<T>;
 
-    fn mul(self, other: Vector) -> Vector {
-        if self.rows[0].elements.len() != other.elements.len() {
-            panic!("The number of columns in the first matrix must be equal to the number of elements in the vector for multiplication.");
-        }
-        Vector { elements: self.rows.iter().map(|row| row.elements.iter().zip(other.elements.iter()).map(|(a, b)| a * b).sum()).collect() }
+    fn mul(self, other: Vector<T>) -> Vector<T> {
+        &self * &other
     }
 }
 
-impl Mul<&Vector> for &Matrix {
-    type Output = Vector;
+impl<T: Float> Mul<&Vector<T>> for &Matrix<T> {
+    type Output = Vector<T>;
 
-    fn mul(self, other: &Vector) -> Vector {
-        if self.rows[0].elements.len() != other.elements.len() {
-            panic!("The number of columns in the first matrix must be equal to the number of elements in the vector for multiplication.");
-        }
-        Vector { elements: self.rows.iter().map(|row| row.elements.iter().zip(other.elements.iter()).map(|(a, b)| a * b).sum()).collect() }
+    fn mul(self, other: &Vector<T>) -> Vector<T> {
+        self.try_mul_vector(other).unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
-impl Mul<Matrix> for Matrix {
-    type Output = Matrix;
+impl<T: Float> Mul<Matrix<T>> for Matrix<T> {
+    type Output = Matrix<T>;
 
-    fn mul(self, other: Matrix) -> Matrix {
-        if self.rows[0].elements.len() != other.rows.len() {
-            panic!("The number of columns in the first matrix must be equal to the number of rows in the second matrix for multiplication.");
-        }
-        let mut result = vec![];
-        for i in 0..self.rows.len() {
-            let mut row = vec![];
-            for j in 0..other.rows[0].elements.len() {
-                let mut sum = 0.0;
-                for k in 0..self.rows[0].elements.len() {
-                    sum += self.rows[i].elements[k] * other.rows[k].elements[j];
-                }
-                row.push(sum);
-            }
-            result.push(Vector { elements: row });
-        }
-        Matrix { rows: result }
+    fn mul(self, other: Matrix<T>) -> Matrix<T> {
+        &self * &other
     }
 }
 
-impl Mul<&Matrix> for &Matrix {
-    type Output = Matrix;
+impl<T: Float> Mul<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+        self.try_mul(other).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
 
-    fn mul(self, other: &Matrix) -> Matrix {
-        if self.rows[0].elements.len() != other.rows.len() {
-            panic!("The number of columns in the first matrix must be equal to the number of rows in the second matrix for multiplication.");
+impl<T: Float> AddAssign<&Matrix<T>> for Matrix<T> {
+    fn add_assign(&mut self, other: &Matrix<T>) {
+        self.axpy(T::ONE, other);
+    }
+}
+
+impl<T: Float> SubAssign<&Matrix<T>> for Matrix<T> {
+    fn sub_assign(&mut self, other: &Matrix<T>) {
+        if self.shape() != other.shape() {
+            panic!("Matrices must have the same shape for subtraction.");
         }
-        let mut result = vec![];
-        for i in 0..self.rows.len() {
-            let mut row = vec![];
-            for j in 0..other.rows[0].elements.len() {
-                let mut sum = 0.0;
-                for k in 0..self.rows[0].elements.len() {
-                    sum += self.rows[i].elements[k] * other.rows[k].elements[j];
-                }
-                row.push(sum);
-            }
-            result.push(Vector { elements: row });
+        for (a, &b) in self.elements.iter_mut().zip(other.elements.iter()) {
+            *a -= b;
+        }
+    }
+}
+
+impl<T: Float> MulAssign<T> for Matrix<T> {
+    fn mul_assign(&mut self, scalar: T) {
+        for a in self.elements.iter_mut() {
+            *a *= scalar;
         }
-        Matrix { rows: result }
     }
 }
 
-impl Index<usize> for Matrix {
-    type Output = Vector;
+// Side length of the square tiles of the blocked matrix multiplication
+const BLOCK_SIZE: usize = 64;
 
-    fn index(&self, index: usize) -> &Vector {
-        &self.rows[index]
+impl<T: Float> Index<usize> for Matrix<T> {
+    type Output = [T];
+
+    fn index(&self, index: usize) -> &[T] {
+        &self.elements[index * self.cols..(index + 1) * self.cols]
     }
 }
 
-impl Index<usize> for &Matrix {
-    type Output = Vector;
+impl<T: Float> Index<usize> for &Matrix<T> {
+    type Output = [T];
 
-    fn index(&self, index: usize) -> &Vector {
-        &self.rows[index]
+    fn index(&self, index: usize) -> &[T] {
+        &self.elements[index * self.cols..(index + 1) * self.cols]
     }
 }
 
-impl Index<usize> for &mut Matrix {
-    type Output = Vector;
+impl<T: Float> Index<usize> for &mut Matrix<T> {
+    type Output = [T];
 
-    fn index(&self, index: usize) -> &Vector {
-        &self.rows[index]
+    fn index(&self, index: usize) -> &[T] {
+        &self.elements[index * self.cols..(index + 1) * self.cols]
     }
 }
 
-impl IndexMut<usize> for Matrix {
-    fn index_mut(&mut self, index: usize) -> &mut Vector {
-        &mut self.rows[index]
+impl<T: Float> IndexMut<usize> for Matrix<T> {
+    fn index_mut(&mut self, index: usize) -> &mut [T] {
+        &mut self.elements[index * self.cols..(index + 1) * self.cols]
     }
 }
 
-impl IndexMut<usize> for &mut Matrix {
-    fn index_mut(&mut self, index: usize) -> &mut Vector {
-        &mut self.rows[index]
+impl<T: Float> IndexMut<usize> for &mut Matrix<T> {
+    fn index_mut(&mut self, index: usize) -> &mut [T] {
+        &mut self.elements[index * self.cols..(index + 1) * self.cols]
     }
 }
 
-impl Matrix {
-    /// Create a new matrix with the given elements.
-    pub fn new(elements: Vec<Vector>) -> Matrix {
-        Matrix { rows: elements }
+impl<T: Float> Matrix<T> {
+    /// Create a new matrix with the given rows, which must all have the same length.
+    pub fn new(rows: Vec<Vector<T>>) -> Matrix<T> {
+        let cols: usize = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != cols) {
+            panic!("All rows of a matrix must have the same number of elements.");
+        }
+        Matrix { rows: rows.len(), cols, elements: rows.into_iter().flat_map(|row| row.elements).collect() }
+    }
+
+    /// Create a new matrix with the given size from elements in row-major order.
+    pub fn from_elements(rows: usize, cols: usize, elements: Vec<T>) -> Matrix<T> {
+        if elements.len() != rows * cols {
+            panic!("The number of elements must be equal to the number of rows times the number of columns.");
+        }
+        Matrix { elements, rows, cols }
+    }
+
+    /// Create a new matrix by stacking the rows of the given matrices, which must all have the same number of columns.
+    pub fn vstack(matrices: &[Matrix<T>]) -> Matrix<T> {
+        let cols: usize = matrices.first().map_or(0, |matrix| matrix.cols);
+        if matrices.iter().any(|matrix| matrix.cols != cols) {
+            panic!("All matrices must have the same number of columns to be stacked vertically.");
+        }
+        Matrix { elements: matrices.iter().flat_map(|matrix| matrix.elements.iter().copied()).collect(), rows: matrices.iter().map(|matrix| matrix.rows).sum(), cols }
+    }
+
+    /// Create a new matrix by placing the given matrices side by side, which must all have the same number of rows.
+    pub fn hstack(matrices: &[Matrix<T>]) -> Matrix<T> {
+        let rows: usize = matrices.first().map_or(0, |matrix| matrix.rows);
+        if matrices.iter().any(|matrix| matrix.rows != rows) {
+            panic!("All matrices must have the same number of rows to be stacked horizontally.");
+        }
+        let elements: Vec<T> = (0..rows).flat_map(|i| matrices.iter().flat_map(move |matrix| matrix.elements[i * matrix.cols..(i + 1) * matrix.cols].iter().copied())).collect();
+        Matrix { elements, rows, cols: matrices.iter().map(|matrix| matrix.cols).sum() }
     }
 
     /// Create a new matrix with the given size and all elements set to zero.
-    pub fn zeros(rows: usize, cols: usize) -> Matrix {
-        Matrix { rows: vec![Vector { elements: vec![0.0; cols] }; rows] }
+    pub fn zeros(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix { elements: vec![T::ZERO; rows * cols], rows, cols }
     }
 
     /// Create a new matrix with the given size and all elements set to random values.
-    pub fn random(rows: usize, cols: usize) -> Matrix {
-        let mut rng = rand::thread_rng();
-        Matrix { rows: vec![Vector { elements: (0..cols).map(|_| rng.gen_range(0.0..1.0)).collect() }; rows] }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn random(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix::random_with_rng(rows, cols, &mut rand::thread_rng())
+    }
+
+    /// Create a new matrix with the given size and all elements drawn from the given random number generator, so the result is reproducible for a seeded generator.
+    pub fn random_with_rng(rows: usize, cols: usize, rng: &mut impl Rng) -> Matrix<T> {
+        // One random row is repeated for every row
+        let row: Vec<T> = (0..cols).map(|_| T::from_f64(rng.gen_range(0.0..1.0))).collect();
+        Matrix { elements: row.repeat(rows), rows, cols }
+    }
+
+    /// Create a new matrix with the given size and all elements uniformly distributed between `low` and `high`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn random_uniform(rows: usize, cols: usize, low: T, high: T) -> Matrix<T> {
+        Matrix::random_uniform_with_rng(rows, cols, low, high, &mut rand::thread_rng())
+    }
+
+    /// Create a new matrix like `Matrix::random_uniform`, drawing the elements from the given random number generator.
+    pub fn random_uniform_with_rng(rows: usize, cols: usize, low: T, high: T, rng: &mut impl Rng) -> Matrix<T> {
+        Matrix { elements: Vector::random_uniform_with_rng(rows * cols, low, high, rng).elements, rows, cols }
+    }
+
+    /// Create a new matrix with the given size and all elements normally distributed with mean `mean` and standard deviation `std`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn random_normal(rows: usize, cols: usize, mean: T, std: T) -> Matrix<T> {
+        Matrix::random_normal_with_rng(rows, cols, mean, std, &mut rand::thread_rng())
+    }
+
+    /// Create a new matrix like `Matrix::random_normal`, drawing the elements from the given random number generator.
+    pub fn random_normal_with_rng(rows: usize, cols: usize, mean: T, std: T, rng: &mut impl Rng) -> Matrix<T> {
+        Matrix { elements: Vector::random_normal_with_rng(rows * cols, mean, std, rng).elements, rows, cols }
     }
 
     /// Create a new matrix with the given size and all elements set to one.
-    pub fn ones(rows: usize, cols: usize) -> Matrix {
-        Matrix { rows: vec![Vector { elements: vec![1.0; cols] }; rows
-        ] }
+    pub fn ones(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix { elements: vec![T::ONE; rows * cols], rows, cols }
     }
 
     /// Create a new identity matrix with the given size.
-    pub fn identity(size: usize) -> Matrix {
-        let mut elements = vec![];
+    pub fn identity(size: usize) -> Matrix<T> {
+        let mut result: Matrix<T> = Matrix::zeros(size, size);
         for i in 0..size {
-            let mut row = vec![];
-            for j in 0..size {
-                if i == j {
-                    row.push(1.0);
-                } else {
-                    row.push(0.0);
-                }
-            }
-            elements.push(Vector { elements: row });
+            result[i][i] = T::ONE;
         }
-        Matrix { rows: elements }
+        result
     }
 
     /// Transpose the matrix.
-    pub fn transpose(&self) -> Matrix {
-        let mut result: Vec<Vector> = vec![];
-        for j in 0..self.rows[0].elements.len() {
-            let mut row: Vec<f64> = vec![];
-            for i in 0..self.rows.len() {
-                row.push(self.rows[i].elements[j]);
+    pub fn transpose(&self) -> Matrix<T> {
+        let mut elements: Vec<T> = Vec::with_capacity(self.elements.len());
+        for j in 0..self.cols {
+            for i in 0..self.rows {
+                elements.push(self.elements[i * self.cols + j]);
             }
-            result.push(Vector { elements: row });
         }
-        Matrix { rows: result }
+        Matrix { elements, rows: self.cols, cols: self.rows }
     }
 
     /// Returns the shape of the matrix.
     pub fn shape(&self) -> (usize, usize) {
-        (self.rows.len(), self.rows[0].elements.len())
+        (self.rows, self.cols)
+    }
+
+    /// Returns a row in the matrix.
+    pub fn row(&self, row: usize) -> Vector<T> {
+        Vector::new(self[row].to_vec())
+    }
+
+    /// Returns an iterator over the rows of the matrix as slices.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.rows).map(move |i| &self.elements[i * self.cols..(i + 1) * self.cols])
+    }
+
+    /// Returns a new matrix with the rows in the given range.
+    pub fn slice_rows(&self, range: std::ops::Range<usize>) -> Matrix<T> {
+        if range.start > range.end || range.end > self.rows {
+            panic!("The range of rows must lie within the matrix.");
+        }
+        Matrix { elements: self.elements[range.start * self.cols..range.end * self.cols].to_vec(), rows: range.end - range.start, cols: self.cols }
     }
 
     /// Sets the elements in the given row to the given vector.
-    pub fn set_row(&mut self, row: usize, vector: Vector) {
-        if vector.elements.len() != self.rows[row].elements.len() {
+    pub fn set_row(&mut self, row: usize, vector: Vector<T>) {
+        if vector.elements.len() != self.cols {
             panic!("The number of elements in the vector must be equal to the number of columns in the matrix.");
         }
-        self.rows[row] = vector;
+        let cols: usize = self.cols;
+        self.elements[row * cols..(row + 1) * cols].copy_from_slice(&vector.elements);
     }
 
     /// Sets the elements in the given column to the given vector.
-    pub fn set_col(&mut self, col: usize, vector: Vector) {
-        if vector.elements.len() != self.rows.len() {
+    pub fn set_col(&mut self, col: usize, vector: Vector<T>) {
+        if vector.elements.len() != self.rows {
             panic!("The number of elements in the vector must be equal to the number of rows in the matrix.");
         }
-        for i in 0..self.rows.len() {
-            self.rows[i].elements[col] = vector.elements[i];
+        for i in 0..self.rows {
+            self.elements[i * self.cols + col] = vector.elements[i];
         }
     }
 
     /// Returns a column in the matrix.
-    pub fn get_col(&self, col: usize) -> Vector {
-        Vector { elements: self.rows.iter().map(|row| row.elements[col]).collect() }
+    pub fn get_col(&self, col: usize) -> Vector<T> {
+        Vector { elements: (0..self.rows).map(|i| self[i][col]).collect() }
     }
 
     /// Adds a row to the matrix.
-    pub fn push(&mut self, row: Vector) {
-        self.rows.push(row);
+    pub fn push(&mut self, row: Vector<T>) {
+        if self.rows == 0 {
+            self.cols = row.len();
+        } else if row.len() != self.cols {
+            panic!("The number of elements in the row must be equal to the number of columns in the matrix.");
+        }
+        self.elements.extend(row.elements);
+        self.rows += 1;
+    }
+
+    /// Returns the sum of two matrices like `+`, or an error instead of a panic if their shapes differ.
+    pub fn try_add(&self, other: &Matrix<T>) -> Result<Matrix<T>, KanError> {
+        if self.shape() != other.shape() {
+            return Err(KanError::shape_mismatch("the second matrix of the addition", self.shape(), other.shape()));
+        }
+        Ok(Matrix { elements: self.elements.iter().zip(other.elements.iter()).map(|(&a, &b)| a + b).collect(), rows: self.rows, cols: self.cols })
+    }
+
+    /// Returns the difference of two matrices like `-`, or an error instead of a panic if their shapes differ.
+    pub fn try_sub(&self, other: &Matrix<T>) -> Result<Matrix<T>, KanError> {
+        if self.shape() != other.shape() {
+            return Err(KanError::shape_mismatch("the second matrix of the subtraction", self.shape(), other.shape()));
+        }
+        Ok(Matrix { elements: self.elements.iter().zip(other.elements.iter()).map(|(&a, &b)| a - b).collect(), rows: self.rows, cols: self.cols })
+    }
+
+    /// Returns the product of two matrices like `*`, or an error instead of a panic if the number of columns of the first matrix differs from the number of rows of the second.
+    pub fn try_mul(&self, other: &Matrix<T>) -> Result<Matrix<T>, KanError> {
+        if self.cols != other.rows {
+            return Err(KanError::mismatch("rows in the second matrix of the product, one per column of the first", self.cols, other.rows));
+        }
+        #[cfg(feature = "blas")]
+        if let Some(result) = blas::matmul(self, other) {
+            return Ok(result);
+        }
+        let (n, m, p) = (self.rows, self.cols, other.cols);
+        let mut result: Matrix<T> = Matrix::zeros(n, p);
+        // Multiply in tiles that fit in the cache, streaming rows of the second matrix in the innermost loop
+        for ii in (0..n).step_by(BLOCK_SIZE) {
+            let i_end: usize = (ii + BLOCK_SIZE).min(n);
+            for kk in (0..m).step_by(BLOCK_SIZE) {
+                let k_end: usize = (kk + BLOCK_SIZE).min(m);
+                for jj in (0..p).step_by(BLOCK_SIZE) {
+                    let j_end: usize = (jj + BLOCK_SIZE).min(p);
+                    for i in ii..i_end {
+                        let result_row: &mut [T] = &mut result.elements[i * p + jj..i * p + j_end];
+                        for k in kk..k_end {
+                            let a: T = self.elements[i * m + k];
+                            if a == T::ZERO {
+                                continue;
+                            }
+                            let other_row: &[T] = &other.elements[k * p + jj..k * p + j_end];
+                            for (value, b) in result_row.iter_mut().zip(other_row.iter()) {
+                                *value += a * *b;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns the product of the matrix and a vector like `*`, or an error instead of a panic if the number of columns differs from the length of the vector.
+    pub fn try_mul_vector(&self, other: &Vector<T>) -> Result<Vector<T>, KanError> {
+        if self.cols != other.elements.len() {
+            return Err(KanError::mismatch("elements in the vector of the product, one per column of the matrix", self.cols, other.elements.len()));
+        }
+        Ok(Vector { elements: (0..self.rows).map(|i| self[i].iter().zip(other.elements.iter()).map(|(&a, &b)| a * b).sum()).collect() })
+    }
+
+    /// Adds a multiple of another matrix to the matrix in place, i.e. self = self + alpha * x, without allocating.
+    pub fn axpy(&mut self, alpha: T, x: &Matrix<T>) {
+        if self.shape() != x.shape() {
+            panic!("Matrices must have the same shape for addition.");
+        }
+        for (a, &b) in self.elements.iter_mut().zip(x.elements.iter()) {
+            *a += alpha * b;
+        }
+    }
+
+    /// Add the scaled outer product `scale * u v^T` to the matrix in place without forming the outer product.
+    pub fn rank1_update(&mut self, u: &Vector<T>, v: &Vector<T>, scale: T) {
+        if self.rows != u.len() || self.cols != v.len() {
+            panic!("The lengths of the vectors must match the number of rows and columns of the matrix for a rank-1 update.");
+        }
+        for (row, &a) in self.elements.chunks_mut(self.cols.max(1)).zip(u.elements.iter()) {
+            let factor: T = scale * a;
+            for (element, &b) in row.iter_mut().zip(v.elements.iter()) {
+                *element += factor * b;
+            }
+        }
+    }
+
+    /// Computes the LU decomposition P A = L U of a square matrix with partial pivoting, or an error if the matrix is singular.
+    pub fn lu_decompose(&self) -> Result<LuDecomposition<T>, KanError> {
+        let (n, cols) = self.shape();
+        if n != cols {
+            panic!("The matrix must be square.");
+        }
+        // Pivots below this size are rounding errors of a singular matrix
+        let scale: T = self.elements.iter().fold(T::ZERO, |max: T, x| max.max(x.abs()));
+        let tolerance: T = T::from_usize(n) * T::EPSILON * scale;
+
+        let mut upper: Vec<Vec<T>> = (0..n).map(|i| self[i].to_vec()).collect();
+        let mut lower: Vec<Vec<T>> = vec![vec![T::ZERO; n]; n];
+        let mut permutation: Vec<usize> = (0..n).collect();
+        for k in 0..n {
+            let pivot: usize = (k..n).max_by(|&x, &y| upper[x][k].abs().total_cmp(&upper[y][k].abs())).unwrap();
+            if upper[pivot][k].abs() <= tolerance || !upper[pivot][k].is_finite() {
+                return Err(KanError::SingularMatrix);
+            }
+            upper.swap(k, pivot);
+            lower.swap(k, pivot);
+            permutation.swap(k, pivot);
+            let pivot_row: Vec<T> = upper[k].clone();
+            for i in k + 1..n {
+                let factor: T = upper[i][k] / pivot_row[k];
+                lower[i][k] = factor;
+                for (value, pivot_value) in upper[i].iter_mut().zip(pivot_row.iter()).skip(k) {
+                    *value -= factor * *pivot_value;
+                }
+            }
+        }
+        for (i, row) in lower.iter_mut().enumerate() {
+            row[i] = T::ONE;
+        }
+        Ok(LuDecomposition {
+            lower: Matrix::new(lower.into_iter().map(Vector::new).collect()),
+            upper: Matrix::new(upper.into_iter().map(Vector::new).collect()),
+            permutation,
+        })
+    }
+
+    /// Solves the linear system A x = b of a square matrix, or returns an error if the matrix is singular.
+    pub fn solve(&self, b: &Vector<T>) -> Result<Vector<T>, KanError> {
+        if b.len() != self.rows {
+            panic!("The number of elements in the vector must be equal to the number of rows in the matrix.");
+        }
+        #[cfg(feature = "blas")]
+        if self.rows == self.cols {
+            if let Some(result) = blas::solve(self, b) {
+                return result;
+            }
+        }
+        Ok(self.lu_decompose()?.solve(b))
+    }
+
+    /// Checks that a square matrix is symmetric up to rounding errors, returning the size below which values are rounding errors.
+    fn check_symmetric(&self) -> Result<T, KanError> {
+        let (n, cols) = self.shape();
+        if n != cols {
+            panic!("The matrix must be square.");
+        }
+        let scale: T = self.elements.iter().fold(T::ZERO, |max: T, x| max.max(x.abs()));
+        let tolerance: T = T::from_usize(n) * T::EPSILON * scale;
+        for i in 0..n {
+            for j in 0..i {
+                if (self[i][j] - self[j][i]).abs() > tolerance {
+                    return Err(KanError::DomainError("The matrix is not symmetric."));
+                }
+            }
+        }
+        Ok(tolerance)
+    }
+
+    /// Computes the Cholesky factor L of a symmetric positive definite matrix A = L L^T, or returns an error if the matrix is not symmetric positive definite.
+    pub fn cholesky(&self) -> Result<Matrix<T>, KanError> {
+        let tolerance: T = self.check_symmetric()?;
+        let n: usize = self.rows;
+        let mut lower: Matrix<T> = Matrix::zeros(n, n);
+        for j in 0..n {
+            let diagonal: T = self[j][j] - (0..j).map(|k| lower[j][k] * lower[j][k]).sum::<T>();
+            if diagonal <= tolerance || !diagonal.is_finite() {
+                return Err(KanError::NotPositiveDefinite);
+            }
+            lower[j][j] = diagonal.sqrt();
+            for i in j + 1..n {
+                let sum: T = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+                lower[i][j] = (self[i][j] - sum) / lower[j][j];
+            }
+        }
+        Ok(lower)
+    }
+
+    /// Solves the linear system A x = b of a symmetric positive definite matrix with its Cholesky factor, or returns an error if the matrix is not symmetric positive definite.
+    pub fn solve_cholesky(&self, b: &Vector<T>) -> Result<Vector<T>, KanError> {
+        if b.len() != self.rows {
+            panic!("The number of elements in the vector must be equal to the number of rows in the matrix.");
+        }
+        #[cfg(feature = "blas")]
+        {
+            self.check_symmetric()?;
+            if let Some(result) = blas::solve_cholesky(self, b) {
+                return result;
+            }
+        }
+        let lower: Matrix<T> = self.cholesky()?;
+        let n: usize = b.len();
+        // Forward substitution with L, then back substitution with L^T
+        let mut y: Vec<T> = vec![T::ZERO; n];
+        for i in 0..n {
+            let sum: T = (0..i).map(|j| lower[i][j] * y[j]).sum();
+            y[i] = (b[i] - sum) / lower[i][i];
+        }
+        let mut x: Vec<T> = vec![T::ZERO; n];
+        for i in (0..n).rev() {
+            let sum: T = (i + 1..n).map(|j| lower[j][i] * x[j]).sum();
+            x[i] = (y[i] - sum) / lower[i][i];
+        }
+        Ok(Vector::new(x))
+    }
+
+    /// Computes the inverse of a square matrix, or returns an error if the matrix is singular.
+    pub fn inverse(&self) -> Result<Matrix<T>, KanError> {
+        let lu: LuDecomposition<T> = self.lu_decompose()?;
+        let n: usize = self.rows;
+        let mut inverse: Matrix<T> = Matrix::zeros(n, n);
+        for j in 0..n {
+            let mut unit: Vector<T> = Vector::zeros(n);
+            unit[j] = T::ONE;
+            inverse.set_col(j, lu.solve(&unit));
+        }
+        Ok(inverse)
+    }
+
+    /// Adds the given vector to every row, which must have one element per column.
+    pub fn add_row_vector(&self, vector: &Vector<T>) -> Matrix<T> {
+        self.broadcast_row(vector, |a, b| a + b)
+    }
+
+    /// Subtracts the given vector from every row, which must have one element per column.
+    pub fn sub_row_vector(&self, vector: &Vector<T>) -> Matrix<T> {
+        self.broadcast_row(vector, |a, b| a - b)
+    }
+
+    /// Multiplies every row element-wise by the given vector, which must have one element per column.
+    pub fn mul_row_vector(&self, vector: &Vector<T>) -> Matrix<T> {
+        self.broadcast_row(vector, |a, b| a * b)
+    }
+
+    /// Divides every row element-wise by the given vector, which must have one element per column.
+    pub fn div_row_vector(&self, vector: &Vector<T>) -> Matrix<T> {
+        self.broadcast_row(vector, |a, b| a / b)
+    }
+
+    /// Adds the given vector to every column, which must have one element per row.
+    pub fn add_col_vector(&self, vector: &Vector<T>) -> Matrix<T> {
+        self.broadcast_col(vector, |a, b| a + b)
+    }
+
+    /// Subtracts the given vector from every column, which must have one element per row.
+    pub fn sub_col_vector(&self, vector: &Vector<T>) -> Matrix<T> {
+        self.broadcast_col(vector, |a, b| a - b)
+    }
+
+    /// Multiplies every column element-wise by the given vector, which must have one element per row.
+    pub fn mul_col_vector(&self, vector: &Vector<T>) -> Matrix<T> {
+        self.broadcast_col(vector, |a, b| a * b)
+    }
+
+    /// Divides every column element-wise by the given vector, which must have one element per row.
+    pub fn div_col_vector(&self, vector: &Vector<T>) -> Matrix<T> {
+        self.broadcast_col(vector, |a, b| a / b)
+    }
+
+    /// Combines every row element-wise with a vector of one element per column.
+    fn broadcast_row(&self, vector: &Vector<T>, op: impl Fn(T, T) -> T) -> Matrix<T> {
+        if vector.len() != self.cols {
+            panic!("The number of elements in the vector must be equal to the number of columns in the matrix.");
+        }
+        let elements: Vec<T> = self.iter_rows().flat_map(|row| row.iter().zip(vector.elements.iter()).map(|(&a, &b)| op(a, b))).collect();
+        Matrix { elements, rows: self.rows, cols: self.cols }
+    }
+
+    /// Combines every column element-wise with a vector of one element per row.
+    fn broadcast_col(&self, vector: &Vector<T>, op: impl Fn(T, T) -> T) -> Matrix<T> {
+        if vector.len() != self.rows {
+            panic!("The number of elements in the vector must be equal to the number of rows in the matrix.");
+        }
+        let elements: Vec<T> = self.iter_rows().zip(vector.elements.iter()).flat_map(|(row, &b)| row.iter().map(|&a| op(a, b)).collect::<Vec<T>>()).collect();
+        Matrix { elements, rows: self.rows, cols: self.cols }
+    }
+
+    /// Returns the sums of the rows or of the columns.
+    pub fn sum(&self, axis: Axis) -> Vector<T> {
+        Vector::new(self.lanes(axis).iter().map(Vector::sum).collect())
+    }
+
+    /// Returns the sums of the rows or of the columns with compensated summation.
+    pub fn sum_stable(&self, axis: Axis) -> Vector<T> {
+        Vector::new(self.lanes(axis).iter().map(Vector::sum_stable).collect())
+    }
+
+    /// Returns the means of the rows or of the columns.
+    pub fn mean(&self, axis: Axis) -> Vector<T> {
+        Vector::new(self.lanes(axis).iter().map(Vector::mean).collect())
+    }
+
+    /// Returns the population standard deviations of the rows or of the columns.
+    pub fn std(&self, axis: Axis) -> Vector<T> {
+        Vector::new(self.lanes(axis).iter().map(Vector::std).collect())
+    }
+
+    /// Returns the smallest elements of the rows or of the columns, ignoring NaN.
+    pub fn min(&self, axis: Axis) -> Vector<T> {
+        Vector::new(self.lanes(axis).iter().map(Vector::min).collect())
+    }
+
+    /// Returns the largest elements of the rows or of the columns, ignoring NaN.
+    pub fn max(&self, axis: Axis) -> Vector<T> {
+        Vector::new(self.lanes(axis).iter().map(Vector::max).collect())
+    }
+
+    /// Returns the indices of the first smallest elements of the rows or of the columns, ignoring NaN.
+    pub fn argmin(&self, axis: Axis) -> Vec<usize> {
+        self.lanes(axis).iter().map(Vector::argmin).collect()
+    }
+
+    /// Returns the indices of the first largest elements of the rows or of the columns, ignoring NaN.
+    pub fn argmax(&self, axis: Axis) -> Vec<usize> {
+        self.lanes(axis).iter().map(Vector::argmax).collect()
+    }
+
+    /// Returns the p-norms of the rows or of the columns for p >= 1, where `T::INFINITY` gives the largest absolute values.
+    pub fn norm(&self, axis: Axis, p: T) -> Vector<T> {
+        Vector::new(self.lanes(axis).iter().map(|lane| lane.norm(p)).collect())
+    }
+
+    /// Returns the rows or the columns of the matrix as vectors.
+    fn lanes(&self, axis: Axis) -> Vec<Vector<T>> {
+        match axis {
+            Axis::Rows => (0..self.rows).map(|i| self.row(i)).collect(),
+            Axis::Cols => (0..self.cols).map(|j| self.get_col(j)).collect(),
+        }
+    }
+
+    /// Convert the elements of the matrix to another precision, rounding to the nearest representable value.
+    pub fn cast<U: Float>(&self) -> Matrix<U> {
+        Matrix { elements: self.elements.iter().map(|&x| U::from_f64(x.to_f64())).collect(), rows: self.rows, cols: self.cols }
     }
 }
 
-impl std::fmt::Display for Matrix {
+/// The direction in which the reductions of a matrix, such as `Matrix::sum`, are taken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Axis {
+    /// Reduce every row to one value, giving one value per row.
+    Rows,
+    /// Reduce every column to one value, giving one value per column.
+    Cols,
+}
+
+/// The LU decomposition P A = L U of a square matrix A with partial pivoting.
+/// L is unit lower triangular, U is upper triangular, and P is the permutation of the rows chosen for the pivots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LuDecomposition<T: Float = f64> {
+    pub lower: Matrix<T>, // Unit lower triangular factor L
+    pub upper: Matrix<T>, // Upper triangular factor U with non-zero diagonal
+    pub permutation: Vec<usize>, // Row i of P A is row permutation[i] of A
+}
+
+impl<T: Float> LuDecomposition<T> {
+    /// Solves the linear system A x = b by forward substitution with L and back substitution with U.
+    pub fn solve(&self, b: &Vector<T>) -> Vector<T> {
+        let n: usize = self.permutation.len();
+        if b.len() != n {
+            panic!("The number of elements in the vector must be equal to the size of the matrix.");
+        }
+        let mut y: Vec<T> = vec![T::ZERO; n];
+        for i in 0..n {
+            let sum: T = (0..i).map(|j| self.lower[i][j] * y[j]).sum();
+            y[i] = b[self.permutation[i]] - sum;
+        }
+        let mut x: Vec<T> = vec![T::ZERO; n];
+        for i in (0..n).rev() {
+            let sum: T = (i + 1..n).map(|j| self.upper[i][j] * x[j]).sum();
+            x[i] = (y[i] - sum) / self.upper[i][i];
+        }
+        Vector::new(x)
+    }
+}
+
+impl<T: Float> std::fmt::Display for Matrix<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let mut result = String::new();
-        for row in &self.rows {
-            result.push_str(&format!("{}\n", row));
+        for row in self.iter_rows() {
+            result.push_str(&format!("{}\n", Vector::new(row.to_vec())));
         }
         write!(f, "{}", result)
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,14 @@
+pub mod float;
 pub mod vector;
 pub mod matrix;
+#[cfg(feature = "blas")]
+mod blas;
+pub mod sparse;
 pub mod spline;
+pub mod rbf;
+pub mod activation;
+pub mod symbolic;
 pub mod edge;
 pub mod node;
-pub mod layer;
\ No newline at end of file
+pub mod layer;
+pub mod norm;
\ No newline at end of file
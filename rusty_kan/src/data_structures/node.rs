@@ -1,14 +1,29 @@
 use std::{cell::RefCell, rc::Rc, vec};
 use crate::data_structures::{vector::Vector, edge::Edge, matrix::Matrix};
+use crate::error::KanError;
+use serde::{Serialize, Deserialize};
+
+/// How a node combines the values of its incoming edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Aggregation {
+    /// The sum of the values, as in the KAN paper.
+    #[default]
+    Sum,
+    /// The product of the values, as in the multiplication nodes of MultKAN (KAN 2.0).
+    Product,
+}
 
 /// A node is an intersection of edges in the network.
-/// It is represented as a list of incoming edges, a list of outgoing edges, and a layer index.
+/// It is represented as a list of incoming edges, a list of outgoing edges, a layer index, and how the values of the incoming edges are combined.
 /// The node struct provides methods to compute the value of the node and update the gradients of the incoming edges.
-#[derive(Debug, Clone)]
+/// A serialized node holds copies of its edges; `KAN` serializes every edge once and reconnects the nodes when it is loaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Node {
     pub incoming: Vec<Rc<RefCell<Edge>>>,
     pub outgoing: Vec<Rc<RefCell<Edge>>>,
     pub layer: usize,
+    #[serde(default)] // Files written before product nodes were added only have sum nodes
+    pub aggregation: Aggregation, // Sum or product of the values of the incoming edges
 }
 
 impl Node {
@@ -37,7 +52,7 @@ impl Node {
     /// 
     /// ```
     pub fn new(incoming_edges: Vec<Rc<RefCell<Edge>>>, outgoing_edges: Vec<Rc<RefCell<Edge>>>, layer: usize) -> Node {
-        Node { incoming: incoming_edges, outgoing: outgoing_edges, layer }
+        Node { incoming: incoming_edges, outgoing: outgoing_edges, layer, aggregation: Aggregation::Sum }
     }
 
     /// Add an incoming edge to the node.
@@ -79,23 +94,70 @@ impl Node {
     /// 
     /// # Returns
     /// 
-    /// * The sum of the incoming activations.
+    /// * The sum of the incoming activations, or their product for a product node, where every activation is scaled by the dropout scale of its edge, or an error if the number of inputs does not match the number of incoming edges.
     /// 
     /// # Example
     /// 
     /// ```
     /// let inputs = Vector { elements: vec![0.0, 1.0, 2.0] };
-    /// let value = node.forward(inputs);
+    /// let value = node.forward(&inputs).unwrap();
     /// ```
-    pub fn forward(&mut self, inputs: &Vector) -> f64 {
+    pub fn forward(&mut self, inputs: &Vector) -> Result<f64, KanError> {
         if inputs.len() != self.incoming.len() {
+            return Err(KanError::mismatch("inputs, one per incoming edge", self.incoming.len(), inputs.len()));
+        }
+        let values = self.incoming.iter().enumerate().map(|(i, edge)| {
+            let mut edge = edge.borrow_mut();
+            edge.forward(inputs[i]) * edge.dropout_scale()
+        });
+        match self.aggregation {
+            Aggregation::Sum => Ok(values.sum()),
+            Aggregation::Product => Ok(values.product()),
+        }
+    }
+
+    /// Compute the gradient of the loss with respect to the value of every incoming edge, given the gradient with respect to the value of the node.
+    /// For a sum node this is the upstream gradient itself; for a product node it is the upstream gradient times the product of the values of the other incoming edges.
+    /// Either is scaled by the dropout scale of the edge, so a masked edge receives no gradient.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - A vector of inputs to the incoming edges.
+    /// 
+    /// * `upstream_gradient` - A scalar representing the gradient of the loss with respect to the value of the node.
+    /// 
+    /// # Returns
+    /// 
+    /// * A vector where the i-th entry is the gradient of the loss with respect to the value of the i-th incoming edge.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let gradients = node.edge_gradients(&Vector::new(vec![0.2, 0.7]), 1.0);
+    /// ```
+    pub fn edge_gradients(&self, t: &Vector, upstream_gradient: f64) -> Vector {
+        if t.len() != self.incoming.len() {
             panic!("The number of inputs must match the number of incoming edges.");
         }
-        let mut result: f64 = 0.0;
-        for (i, edge) in self.incoming.iter().enumerate() {
-            result += edge.borrow_mut().forward(inputs[i]);
+        match self.aggregation {
+            Aggregation::Sum => Vector::new(self.incoming.iter().map(|edge| upstream_gradient * edge.borrow().dropout_scale()).collect()),
+            Aggregation::Product => {
+                let values: Vec<f64> = self.incoming.iter().enumerate().map(|(i, edge)| edge.borrow().dropout_scale() * edge.borrow().value(t[i])).collect();
+                // Products of the values before and after every edge, so no value is divided out
+                let mut gradients: Vec<f64> = self.incoming.iter().map(|edge| upstream_gradient * edge.borrow().dropout_scale()).collect();
+                let mut before: f64 = 1.0;
+                for (gradient, &value) in gradients.iter_mut().zip(values.iter()) {
+                    *gradient *= before;
+                    before *= value;
+                }
+                let mut after: f64 = 1.0;
+                for (gradient, &value) in gradients.iter_mut().zip(values.iter()).rev() {
+                    *gradient *= after;
+                    after *= value;
+                }
+                Vector::new(gradients)
+            }
         }
-        result
     }
 
     /// Compute the gradients of the incoming edges for their inputs.
@@ -117,9 +179,13 @@ impl Node {
     /// let upstream_gradient = 0.25;
     /// node.backward(t, upstream_gradient);
     /// ```
-    pub fn backward(&mut self, t: Vector, upstream_gradient: f64) -> Result<(), &'static str> {
+    pub fn backward(&mut self, t: Vector, upstream_gradient: f64) -> Result<(), KanError> {
+        if t.len() != self.incoming.len() {
+            return Err(KanError::mismatch("inputs, one per incoming edge", self.incoming.len(), t.len()));
+        }
+        let gradients: Vector = self.edge_gradients(&t, upstream_gradient);
         for (i, edge) in self.incoming.iter().enumerate() {
-            edge.borrow_mut().backward(t[i], upstream_gradient).unwrap();
+            edge.borrow_mut().backward(t[i], gradients[i]).map_err(|err| err.at_edge(i))?;
         }
         Ok(())
     }
@@ -140,11 +206,9 @@ impl Node {
     /// let learning_rate = 0.01;
     /// node.update_weights(learning_rate);
     /// ```
-    pub fn update_weights(&mut self, learning_rate: f64) -> Result<(), &'static str> {
-        for edge in self.incoming.iter() {
-            edge.borrow_mut().update_weights(learning_rate).unwrap_or_else(|err| {
-                panic!("{}", err)
-            });
+    pub fn update_weights(&mut self, learning_rate: f64) -> Result<(), KanError> {
+        for (i, edge) in self.incoming.iter().enumerate() {
+            edge.borrow_mut().update_weights(learning_rate).map_err(|err| err.at_edge(i))?;
         }
         Ok(())
     }
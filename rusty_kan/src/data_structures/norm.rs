@@ -0,0 +1,243 @@
+use crate::data_structures::{vector::Vector, matrix::{Matrix, Axis}};
+use serde::{Serialize, Deserialize};
+
+/// How a normalization computes the mean and variance of the values of the nodes in a layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NormKind {
+    /// Running statistics of every node over the training batches, as in batch normalization.
+    Batch,
+    /// Statistics over the nodes of the layer for every sample, as in layer normalization.
+    Layer,
+}
+
+/// A normalization standardizes the values of the nodes in a layer before they are passed to the next layer, then applies a trainable scale and shift to every node.
+/// It is represented as the kind of statistics, the scale and shift of every node, the running statistics of batch normalization, and the gradients of the scale and shift.
+///
+/// The value of the i-th node is mapped to scale_i * (x_i - mean_i) / sqrt(var_i + epsilon) + shift_i, which keeps the inputs of the next layer inside the domain of its splines.
+/// The running statistics are treated as constants in the backward pass, and are only changed by `update_statistics`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Norm {
+    pub kind: NormKind,
+    pub scale: Vector, // Trainable scale of every node
+    pub shift: Vector, // Trainable shift of every node
+    pub running_mean: Vector, // Mean of every node over the training batches, used by batch normalization
+    pub running_var: Vector, // Variance of every node over the training batches, used by batch normalization
+    pub momentum: f64, // Weight of the statistics of a new batch in the running statistics
+    pub epsilon: f64, // Added to the variance to avoid dividing by zero
+    pub scale_gradient: Vector,
+    pub shift_gradient: Vector,
+    #[serde(skip)]
+    pub training: bool, // Whether the running statistics are updated, which they are not in evaluation mode
+}
+
+impl Norm {
+    /// Create a new normalization for a layer with a given number of nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of statistics.
+    ///
+    /// * `width` - A scalar representing the number of nodes in the layer.
+    ///
+    /// # Returns
+    ///
+    /// * A normalization in training mode with unit scale, zero shift, running statistics of a standard normal distribution, a momentum of 0.1, and an epsilon of 1e-5.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// layer.norm = Some(Norm::new(NormKind::Layer, layer.nodes.len()));
+    /// ```
+    pub fn new(kind: NormKind, width: usize) -> Norm {
+        if width == 0 {
+            panic!("A normalization must have at least one node.");
+        }
+        Norm {
+            kind,
+            scale: Vector::new(vec![1.0; width]),
+            shift: Vector::zeros(width),
+            running_mean: Vector::zeros(width),
+            running_var: Vector::new(vec![1.0; width]),
+            momentum: 0.1,
+            epsilon: 1e-5,
+            scale_gradient: Vector::zeros(width),
+            shift_gradient: Vector::zeros(width),
+            training: true,
+        }
+    }
+
+    /// Returns the number of nodes the normalization applies to.
+    pub fn len(&self) -> usize {
+        self.scale.len()
+    }
+
+    /// Returns true if the normalization applies to no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Standardize the values of the nodes of a layer and apply the scale and shift.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - A vector with the value of every node in the layer.
+    ///
+    /// # Returns
+    ///
+    /// * A vector with the normalized value of every node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let normalized = norm.forward(&Vector::new(vec![0.5, 2.0, -1.0]));
+    /// ```
+    pub fn forward(&self, x: &Vector) -> Vector {
+        let (standardized, _) = self.standardize(x);
+        Vector::new(standardized.iter().enumerate().map(|(i, &z)| self.scale[i] * z + self.shift[i]).collect())
+    }
+
+    /// Compute the gradient of the loss with respect to the values of the nodes before the normalization, without changing the gradients of the scale and shift.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - A vector with the value of every node in the layer before the normalization.
+    ///
+    /// * `upstream_gradient` - A vector with the gradient of the loss with respect to the normalized value of every node.
+    ///
+    /// # Returns
+    ///
+    /// * A vector with the gradient of the loss with respect to the value of every node before the normalization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let gradient = norm.input_gradient(&x, &upstream_gradient);
+    /// ```
+    pub fn input_gradient(&self, x: &Vector, upstream_gradient: &Vector) -> Vector {
+        if upstream_gradient.len() != self.len() {
+            panic!("The number of elements in the upstream gradient vector must be equal to the number of nodes of the normalization.");
+        }
+        let (standardized, std) = self.standardize(x);
+        let scaled: Vec<f64> = upstream_gradient.iter().enumerate().map(|(i, &g)| g * self.scale[i]).collect();
+        match self.kind {
+            NormKind::Batch => Vector::new(scaled.iter().enumerate().map(|(i, &g)| g / std[i]).collect()),
+            NormKind::Layer => {
+                // The mean and variance depend on every node, which couples the gradients of the nodes
+                let n: f64 = self.len() as f64;
+                let mean: f64 = scaled.iter().sum::<f64>() / n;
+                let projection: f64 = scaled.iter().zip(standardized.iter()).map(|(g, z)| g * z).sum::<f64>() / n;
+                Vector::new(scaled.iter().zip(standardized.iter()).map(|(g, z)| (g - mean - z * projection) / std[0]).collect())
+            }
+        }
+    }
+
+    /// The backward pass computes the gradients of the scale and shift, overwriting the previous gradients, and the gradient of the loss with respect to the values of the nodes before the normalization.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - A vector with the value of every node in the layer before the normalization.
+    ///
+    /// * `upstream_gradient` - A vector with the gradient of the loss with respect to the normalized value of every node.
+    ///
+    /// # Returns
+    ///
+    /// * A vector with the gradient of the loss with respect to the value of every node before the normalization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let gradient = norm.backward(&x, &upstream_gradient);
+    /// ```
+    pub fn backward(&mut self, x: &Vector, upstream_gradient: &Vector) -> Vector {
+        let gradient: Vector = self.input_gradient(x, upstream_gradient);
+        let (standardized, _) = self.standardize(x);
+        self.scale_gradient = Vector::new(upstream_gradient.iter().zip(standardized.iter()).map(|(g, z)| g * z).collect());
+        self.shift_gradient = upstream_gradient.clone();
+        gradient
+    }
+
+    /// Blend the statistics of a batch of node values into the running statistics with the momentum. Layer normalization has no running statistics and a normalization in evaluation mode keeps its statistics, so both are left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - A matrix with at least one row, where the ith row holds the values of the nodes for the ith sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// norm.update_statistics(&values);
+    /// ```
+    pub fn update_statistics(&mut self, values: &Matrix) {
+        if values.rows == 0 {
+            panic!("The statistics of a normalization require at least one sample.");
+        }
+        if values.cols != self.len() {
+            panic!("The number of values of every sample must be equal to the number of nodes of the normalization.");
+        }
+        if self.kind == NormKind::Layer || !self.training {
+            return;
+        }
+        let mean: Vector = values.mean(Axis::Cols);
+        let std: Vector = values.std(Axis::Cols);
+        for i in 0..self.len() {
+            self.running_mean[i] = (1.0 - self.momentum) * self.running_mean[i] + self.momentum * mean[i];
+            self.running_var[i] = (1.0 - self.momentum) * self.running_var[i] + self.momentum * std[i] * std[i];
+        }
+    }
+
+    /// Update the scale and shift with one step of gradient descent.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale_gradient` - A vector with the gradient of the loss with respect to the scale of every node.
+    ///
+    /// * `shift_gradient` - A vector with the gradient of the loss with respect to the shift of every node.
+    ///
+    /// * `learning_rate` - A learning rate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// norm.step(&norm.scale_gradient.clone(), &norm.shift_gradient.clone(), 0.01);
+    /// ```
+    pub fn step(&mut self, scale_gradient: &Vector, shift_gradient: &Vector, learning_rate: f64) {
+        if scale_gradient.len() != self.len() || shift_gradient.len() != self.len() {
+            panic!("The number of gradients must be equal to the number of nodes of the normalization.");
+        }
+        self.scale.axpy(-learning_rate, scale_gradient);
+        self.shift.axpy(-learning_rate, shift_gradient);
+    }
+
+    /// Reset the gradients of the scale and shift to zero.
+    pub fn zero_grad(&mut self) {
+        self.scale_gradient = Vector::zeros(self.len());
+        self.shift_gradient = Vector::zeros(self.len());
+    }
+
+    /// Remove the parameters and statistics of a node, when the node is removed from its layer.
+    pub fn remove(&mut self, index: usize) {
+        if index >= self.len() {
+            panic!("The index of the node must be smaller than the number of nodes of the normalization.");
+        }
+        for vector in [&mut self.scale, &mut self.shift, &mut self.running_mean, &mut self.running_var, &mut self.scale_gradient, &mut self.shift_gradient] {
+            vector.elements.remove(index);
+        }
+    }
+
+    /// The standardized values of the nodes and the standard deviation used for every node.
+    fn standardize(&self, x: &Vector) -> (Vector, Vector) {
+        if x.len() != self.len() {
+            panic!("The number of values must be equal to the number of nodes of the normalization.");
+        }
+        let n: usize = self.len();
+        let (mean, std): (Vector, Vector) = match self.kind {
+            NormKind::Batch => (self.running_mean.clone(), Vector::new(self.running_var.iter().map(|var| (var + self.epsilon).sqrt()).collect())),
+            NormKind::Layer => {
+                let mean: f64 = x.iter().sum::<f64>() / n as f64;
+                let var: f64 = x.iter().map(|value| (value - mean) * (value - mean)).sum::<f64>() / n as f64;
+                (Vector::new(vec![mean; n]), Vector::new(vec![(var + self.epsilon).sqrt(); n]))
+            }
+        };
+        (Vector::new((0..n).map(|i| (x[i] - mean[i]) / std[i]).collect()), std)
+    }
+}
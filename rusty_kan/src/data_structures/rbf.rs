@@ -0,0 +1,243 @@
+use crate::data_structures::{vector::Vector, activation::ActivationFn};
+use std::any::Any;
+use serde::{Serialize, Deserialize};
+
+/// A Gaussian radial basis function (RBF) expansion is a sum of Gaussians that is used as a faster approximation of a B-spline in a KAN (as in FastKAN).
+/// It is represented as the amplitudes, centers, and widths of the Gaussians, whether the centers and widths are trained, and the domain of inputs covered by the centers.
+///
+/// The value at a point t is the sum of a_i * exp(-((t - c_i) / w_i)^2) over the Gaussians.
+/// The parameters are stored in a single vector, so the RBF can be trained like the control points of a B-spline.
+/// The gradient with respect to the centers and widths is zero unless they are trainable.
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GaussianRbf {
+    pub params: Vector, // Amplitudes, centers, and widths of the Gaussians, in that order
+    pub trainable_centers: bool, // Whether the centers and widths are trained along with the amplitudes
+    pub domain: (f64, f64), // Range of inputs that is covered by the centers
+}
+
+impl GaussianRbf {
+    /// Create a new Gaussian RBF with given amplitudes, centers, and widths.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitudes` - A vector of amplitudes.
+    ///
+    /// * `centers` - A vector of centers, one for every amplitude.
+    ///
+    /// * `widths` - A vector of positive widths, one for every amplitude.
+    ///
+    /// * `trainable_centers` - Whether the centers and widths are trained along with the amplitudes.
+    ///
+    /// # Returns
+    ///
+    /// * A Gaussian RBF whose domain ranges from the smallest to the largest center.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let amplitudes = Vector::new(vec![1.0, -1.0]);
+    /// let centers = Vector::new(vec![0.0, 1.0]);
+    /// let widths = Vector::new(vec![0.5, 0.5]);
+    /// let rbf = GaussianRbf::new(amplitudes, centers, widths, false);
+    /// ```
+    pub fn new(amplitudes: Vector, centers: Vector, widths: Vector, trainable_centers: bool) -> GaussianRbf {
+        let m: usize = amplitudes.len();
+        if m < 2 || centers.len() != m || widths.len() != m {
+            panic!("A Gaussian RBF needs at least two Gaussians with one amplitude, center, and width each.");
+        }
+        if widths.elements.iter().any(|w| !(*w > 0.0 && w.is_finite())) {
+            panic!("The widths of a Gaussian RBF must be finite positive numbers.");
+        }
+        let lower: f64 = centers.min();
+        let upper: f64 = centers.max();
+        if !(lower.is_finite() && upper.is_finite() && lower < upper) {
+            panic!("The centers of a Gaussian RBF must be finite and not all equal.");
+        }
+        let params: Vector = Vector::new([amplitudes.elements, centers.elements, widths.elements].concat());
+        GaussianRbf { params, trainable_centers, domain: (lower, upper) }
+    }
+
+    /// Create a new Gaussian RBF with given amplitudes and centers spaced evenly over a domain, where every width equals the spacing of the centers.
+    ///
+    /// # Arguments
+    ///
+    /// * `amplitudes` - A vector of at least two amplitudes.
+    ///
+    /// * `domain` - A pair of finite bounds (lower, upper) with lower < upper.
+    ///
+    /// # Returns
+    ///
+    /// * A Gaussian RBF with fixed centers and widths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let rbf = GaussianRbf::uniform(Vector::new(vec![0.0, 1.0, 0.0]), (-2.0, 2.0));
+    /// ```
+    pub fn uniform(amplitudes: Vector, domain: (f64, f64)) -> GaussianRbf {
+        let m: usize = amplitudes.len();
+        if m < 2 {
+            panic!("A Gaussian RBF needs at least two Gaussians.");
+        }
+        if !(domain.0.is_finite() && domain.1.is_finite() && domain.0 < domain.1) {
+            panic!("The domain must be a finite range with a lower bound smaller than the upper bound.");
+        }
+        let spacing: f64 = (domain.1 - domain.0) / (m - 1) as f64;
+        let centers: Vector = Vector::new((0..m).map(|i| domain.0 + i as f64 * spacing).collect());
+        let widths: Vector = Vector::new(vec![spacing; m]);
+        GaussianRbf::new(amplitudes, centers, widths, false)
+    }
+
+    /// The number of Gaussians of the RBF.
+    pub fn len(&self) -> usize {
+        self.params.len() / 3
+    }
+
+    /// Whether the RBF has no Gaussians.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The amplitudes of the Gaussians.
+    pub fn amplitudes(&self) -> &[f64] {
+        &self.params.elements[..self.len()]
+    }
+
+    /// The centers of the Gaussians.
+    pub fn centers(&self) -> &[f64] {
+        &self.params.elements[self.len()..2 * self.len()]
+    }
+
+    /// The widths of the Gaussians.
+    pub fn widths(&self) -> &[f64] {
+        &self.params.elements[2 * self.len()..]
+    }
+
+    /// Evaluate the RBF at a given point t.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - An input value.
+    ///
+    /// # Returns
+    ///
+    /// * The value of the RBF at the given point t.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let value = rbf.eval(0.5);
+    /// ```
+    pub fn eval(&self, t: f64) -> f64 {
+        (0..self.len()).map(|i| self.amplitudes()[i] * self.gaussian(i, t)).sum()
+    }
+
+    /// Compute the gradient of the RBF at a given point t with respect to its parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - An input value.
+    ///
+    /// # Returns
+    ///
+    /// * A vector with the partial derivatives in the layout of the parameters, where the entries of the centers and widths are zero unless they are trainable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let gradient = rbf.grad_wrt_params(0.5);
+    /// ```
+    pub fn grad_wrt_params(&self, t: f64) -> Vector {
+        let m: usize = self.len();
+        let mut gradient: Vector = Vector::zeros(3 * m);
+        for i in 0..m {
+            let phi: f64 = self.gaussian(i, t);
+            gradient[i] = phi;
+            if self.trainable_centers {
+                let a: f64 = self.amplitudes()[i];
+                let d: f64 = t - self.centers()[i];
+                let w: f64 = self.widths()[i];
+                gradient[m + i] = a * phi * 2.0 * d / (w * w);
+                gradient[2 * m + i] = a * phi * 2.0 * d * d / (w * w * w);
+            }
+        }
+        gradient
+    }
+
+    /// Compute the derivative of the RBF with respect to its input at a given point t.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - An input value.
+    ///
+    /// # Returns
+    ///
+    /// * The derivative of the RBF at the given point t.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let slope = rbf.grad_wrt_input(0.5);
+    /// ```
+    pub fn grad_wrt_input(&self, t: f64) -> f64 {
+        (0..self.len()).map(|i| {
+            let w: f64 = self.widths()[i];
+            -self.amplitudes()[i] * self.gaussian(i, t) * 2.0 * (t - self.centers()[i]) / (w * w)
+        }).sum()
+    }
+
+    // The i-th Gaussian at the point t.
+    fn gaussian(&self, i: usize, t: f64) -> f64 {
+        let z: f64 = (t - self.centers()[i]) / self.widths()[i];
+        (-z * z).exp()
+    }
+}
+
+impl std::fmt::Display for GaussianRbf {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "GaussianRbf {{ amplitudes: {:?}, centers: {:?}, widths: {:?} }}", self.amplitudes(), self.centers(), self.widths())
+    }
+}
+
+impl ActivationFn for GaussianRbf {
+    fn eval(&mut self, t: f64) -> f64 {
+        GaussianRbf::eval(self, t)
+    }
+
+    fn value(&self, t: f64) -> f64 {
+        GaussianRbf::eval(self, t)
+    }
+
+    fn grad_wrt_params(&mut self, t: f64) -> Vector {
+        GaussianRbf::grad_wrt_params(self, t)
+    }
+
+    fn grad_wrt_input(&self, t: f64) -> f64 {
+        GaussianRbf::grad_wrt_input(self, t)
+    }
+
+    fn params(&self) -> &Vector {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut Vector {
+        &mut self.params
+    }
+
+    fn domain(&self) -> (f64, f64) {
+        self.domain
+    }
+
+    fn clone_box(&self) -> Box<dyn ActivationFn> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
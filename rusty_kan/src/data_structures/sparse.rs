@@ -0,0 +1,142 @@
+use std::ops::Mul;
+use crate::data_structures::{vector::Vector, matrix::Matrix, float::Float};
+
+/// A sparse matrix stores only its non-zero elements, in compressed sparse row (CSR) form.
+/// The column indices and values of row i are `col_indices[row_offsets[i]..row_offsets[i + 1]]` and `values[row_offsets[i]..row_offsets[i + 1]]`, sorted by column.
+/// It is used for the connections of pruned networks, where most pairs of nodes are not connected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix<T: Float = f64> {
+    pub values: Vec<T>, // Non-zero elements in row-major order
+    pub col_indices: Vec<usize>, // Column of every non-zero element
+    pub row_offsets: Vec<usize>, // Start of every row in `values`, followed by the number of non-zero elements
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl<T: Float> Mul<&Vector<T>> for &SparseMatrix<T> {
+    type Output = Vector<T>;
+
+    fn mul(self, other: &Vector<T>) -> Vector<T> {
+        if self.cols != other.len() {
+            panic!("The number of columns in the matrix must be equal to the number of elements in the vector for multiplication.");
+        }
+        Vector::new((0..self.rows).map(|i| self.row_entries(i).map(|(j, value)| value * other[j]).sum()).collect())
+    }
+}
+
+impl<T: Float> Mul<Vector<T>> for SparseMatrix<T> {
+    type Output = Vector<T>;
+
+    fn mul(self, other: Vector<T>) -> Vector<T> {
+        &self * &other
+    }
+}
+
+impl<T: Float> SparseMatrix<T> {
+    /// Create a new sparse matrix with the given size and no non-zero elements.
+    pub fn zeros(rows: usize, cols: usize) -> SparseMatrix<T> {
+        SparseMatrix { values: vec![], col_indices: vec![], row_offsets: vec![0; rows + 1], rows, cols }
+    }
+
+    /// Create a new sparse matrix with the given size from (row, column, value) triplets in any order, where the values of repeated positions are added.
+    pub fn from_triplets(rows: usize, cols: usize, triplets: &[(usize, usize, T)]) -> SparseMatrix<T> {
+        if triplets.iter().any(|&(i, j, _)| i >= rows || j >= cols) {
+            panic!("The positions of the elements must lie within the matrix.");
+        }
+        let mut sorted: Vec<(usize, usize, T)> = triplets.to_vec();
+        sorted.sort_by_key(|&(i, j, _)| (i, j));
+
+        let mut result: SparseMatrix<T> = SparseMatrix::zeros(rows, cols);
+        let mut previous: Option<(usize, usize)> = None;
+        for (i, j, value) in sorted {
+            if previous == Some((i, j)) {
+                *result.values.last_mut().unwrap() += value;
+            } else {
+                result.values.push(value);
+                result.col_indices.push(j);
+                result.row_offsets[i + 1] += 1;
+            }
+            previous = Some((i, j));
+        }
+        // Turn the number of elements of every row into the offsets of the rows
+        for i in 0..rows {
+            result.row_offsets[i + 1] += result.row_offsets[i];
+        }
+        result
+    }
+
+    /// Create a new sparse matrix from the non-zero elements of a dense matrix.
+    pub fn from_dense(matrix: &Matrix<T>) -> SparseMatrix<T> {
+        let mut result: SparseMatrix<T> = SparseMatrix::zeros(matrix.rows, matrix.cols);
+        for (i, row) in matrix.iter_rows().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                if value != T::ZERO {
+                    result.values.push(value);
+                    result.col_indices.push(j);
+                }
+            }
+            result.row_offsets[i + 1] = result.values.len();
+        }
+        result
+    }
+
+    /// Convert the sparse matrix to a dense matrix.
+    pub fn to_dense(&self) -> Matrix<T> {
+        let mut result: Matrix<T> = Matrix::zeros(self.rows, self.cols);
+        for i in 0..self.rows {
+            for (j, value) in self.row_entries(i) {
+                result[i][j] = value;
+            }
+        }
+        result
+    }
+
+    /// Returns the number of stored non-zero elements.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the shape of the matrix.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Returns the element in the given row and column, which is 0 if it is not stored.
+    pub fn get(&self, row: usize, col: usize) -> T {
+        if row >= self.rows || col >= self.cols {
+            panic!("The position of the element must lie within the matrix.");
+        }
+        let start: usize = self.row_offsets[row];
+        let end: usize = self.row_offsets[row + 1];
+        match self.col_indices[start..end].binary_search(&col) {
+            Ok(k) => self.values[start + k],
+            Err(_) => T::ZERO,
+        }
+    }
+
+    /// Returns an iterator over the (column, value) pairs of the non-zero elements in the given row.
+    pub fn row_entries(&self, row: usize) -> impl Iterator<Item = (usize, T)> + '_ {
+        let range: std::ops::Range<usize> = self.row_offsets[row]..self.row_offsets[row + 1];
+        self.col_indices[range.clone()].iter().copied().zip(self.values[range].iter().copied())
+    }
+
+    /// Transpose the matrix.
+    pub fn transpose(&self) -> SparseMatrix<T> {
+        let triplets: Vec<(usize, usize, T)> = (0..self.rows).flat_map(|i| self.row_entries(i).map(move |(j, value)| (j, i, value))).collect();
+        SparseMatrix::from_triplets(self.cols, self.rows, &triplets)
+    }
+
+    /// Multiplies the transpose of the matrix with a vector without forming the transpose.
+    pub fn mul_transpose(&self, vector: &Vector<T>) -> Vector<T> {
+        if self.rows != vector.len() {
+            panic!("The number of rows in the matrix must be equal to the number of elements in the vector for multiplication with the transpose.");
+        }
+        let mut result: Vector<T> = Vector::zeros(self.cols);
+        for i in 0..self.rows {
+            for (j, value) in self.row_entries(i) {
+                result[j] += value * vector[i];
+            }
+        }
+        result
+    }
+}
@@ -1,19 +1,60 @@
-use std::collections::HashMap;
-use crate::data_structures::vector::Vector;
+use rustc_hash::FxHashMap;
+use crate::data_structures::{float::Float, vector::Vector, matrix::Matrix, activation::ActivationFn};
+use crate::error::KanError;
+use std::any::Any;
+use serde::{Serialize, Deserialize};
+
+/// A cache of basis function values keyed by the index, the degree, and the bits of the parameter value.
+pub type BasisMemo = FxHashMap<(usize, usize, u64), f64>;
+
+/// How a B-spline is evaluated outside the range of its knots.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Extrapolation {
+    /// The basis functions vanish outside the knots, so the B-spline is zero.
+    Zero,
+    /// Evaluating outside the knots panics.
+    Panic,
+    /// The value at the nearest boundary knot is used.
+    Clamp,
+    /// The B-spline continues linearly with the value and derivative at the nearest boundary knot.
+    Linear,
+}
+
+/// A shape constraint on a B-spline, enforced on the control points by `BSpline::project`.
+/// Monotone control points give a monotone B-spline, and control points with non-negative (non-positive) second differences give a convex (concave) B-spline on uniform knots.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    /// No constraint.
+    None,
+    /// The B-spline is non-decreasing.
+    Increasing,
+    /// The B-spline is non-increasing.
+    Decreasing,
+    /// The B-spline is convex.
+    Convex,
+    /// The B-spline is concave.
+    Concave,
+}
 
 /// A B-spline is a piecewise polynomial function that is used as a parameterised version of a univariate learnable activation function in a KAN.
-/// It is represented as a list of control points, a list of knots, and a degree.
+/// It is represented as a list of control points, a list of knots, a degree, a domain of inputs that is mapped onto the knots, an extrapolation policy, a shape constraint, and optional weights of the control points.
+/// With weights w, the B-spline is rational (a NURBS), and its value is the sum of w_i * c_i * N_i(t) divided by the sum of w_i * N_i(t).
 /// 
 /// The B_Spline struct implements methods to evaluate the function at a given point and calculate the basis function.
 /// The basis function is a recursive function that calculates the value of the B-spline at a given point.
 /// The eval method calculates the value of the B-spline at a given point by summing the control points multiplied by the basis function. 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BSpline {
     pub control_points: Vector, // Coefficients to be trained
     pub knots: Vector,
     pub degree: usize,
-    pub memo: HashMap<String, f64>,
+    #[serde(skip)]
+    pub memo: BasisMemo, // Cached basis values; clear with `clear_memo` after changing the knots
+    pub domain: (f64, f64), // Range of inputs that is mapped affinely onto the parameter range [0, 1] of the knots
+    pub extrapolation: Extrapolation, // How the B-spline is evaluated outside the range of the knots
+    pub shape: Shape, // Constraint on the control points, enforced after every update
+    pub weights: Option<Vector>, // Positive weights of the control points of a rational B-spline, or None for a polynomial one
 }
 
 impl BSpline {
@@ -40,25 +81,234 @@ impl BSpline {
     pub fn new(control_points: Vector, degree: usize) -> BSpline {
         let n: usize = control_points.elements.len();
         let knots: Vector = Vector { elements: (0..n + degree + 1).map(|i| i as f64 / (n + degree) as f64).collect() };
-        BSpline { control_points, knots, degree, memo: HashMap::new() }
+        BSpline { control_points, knots, degree, memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None }
+    }
+
+    /// Create a new 1D B-spline with a given list of control points, knots, and degree.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `control_points` - A vector of control points.
+    /// 
+    /// * `knots` - A non-decreasing vector of knots with `control_points.len() + degree + 1` elements.
+    /// 
+    /// * `degree` - The degree of the B-spline.
+    /// 
+    /// # Returns
+    /// 
+    /// * A B-spline with the given list of control points, knots, and degree.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let control_points: Vector = Vector::new(vec![0.0, 1.0, 2.0]);
+    /// let knots: Vector = Vector::new(vec![0.0, 0.1, 0.3, 0.6, 0.8, 1.0]);
+    /// 
+    /// let spline = BSpline::with_knots(control_points, knots, 2);
+    /// ```
+    pub fn with_knots(control_points: Vector, knots: Vector, degree: usize) -> BSpline {
+        if knots.len() != control_points.len() + degree + 1 {
+            panic!("The number of knots must be equal to the number of control points plus the degree plus one.");
+        }
+        if knots.elements.windows(2).any(|pair| pair[0] > pair[1] || pair[0].is_nan() || pair[1].is_nan()) {
+            panic!("The knots must be non-decreasing.");
+        }
+        BSpline { control_points, knots, degree, memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None }
+    }
+
+    /// Create a new 1D rational B-spline (NURBS) with a given list of control points, weights, and degree.
+    /// The weights are not trained with the control points by an edge; `grad_wrt_weights` gives their gradient.
+    /// Changing the knots with `refine`, `update_knots_from_samples`, or `remove_knot` refits a rational B-spline as a polynomial one.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `control_points` - A vector of control points.
+    /// 
+    /// * `weights` - A vector of positive finite weights, one per control point.
+    /// 
+    /// * `degree` - The degree of the B-spline.
+    /// 
+    /// # Returns
+    /// 
+    /// * A rational B-spline with the given list of control points, weights, specified degree, and uniform knots.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let control_points: Vector = Vector::new(vec![0.0, 1.0, 0.0]);
+    /// let weights: Vector = Vector::new(vec![1.0, 4.0, 1.0]);
+    /// 
+    /// let spline = BSpline::rational(control_points, weights, 2);
+    /// ```
+    pub fn rational(control_points: Vector, weights: Vector, degree: usize) -> BSpline {
+        if weights.len() != control_points.len() {
+            panic!("A rational B-spline needs one weight per control point.");
+        }
+        if weights.elements.iter().any(|w| !(*w > 0.0 && w.is_finite())) {
+            panic!("The weights of a rational B-spline must be finite positive numbers.");
+        }
+        let mut spline: BSpline = BSpline::new(control_points, degree);
+        spline.weights = Some(weights);
+        spline
+    }
+
+    /// Create a new 1D clamped B-spline over [0, 1] with a given list of control points and degree.
+    /// The boundary knots are repeated degree + 1 times, so the B-spline interpolates its first and last control points at 0 and 1.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `control_points` - A vector of at least degree + 1 control points.
+    /// 
+    /// * `degree` - The degree of the B-spline.
+    /// 
+    /// # Returns
+    /// 
+    /// * A B-spline with the given list of control points, specified degree, and clamped uniform knots.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let control_points: Vector = Vector::new(vec![0.0, 1.0, 2.0, 3.0]);
+    /// 
+    /// let spline = BSpline::clamped(control_points, 2);
+    /// ```
+    pub fn clamped(control_points: Vector, degree: usize) -> BSpline {
+        let n: usize = control_points.len();
+        if n <= degree {
+            panic!("A clamped B-spline needs more control points than its degree.");
+        }
+        let intervals: usize = n - degree;
+        let mut knots: Vector = Vector::zeros(degree);
+        for i in 0..=intervals {
+            knots.push(i as f64 / intervals as f64);
+        }
+        for _ in 0..degree {
+            knots.push(1.0);
+        }
+        BSpline::with_knots(control_points, knots, degree)
+    }
+
+    /// Fit a B-spline to samples of a function by linear least squares.
+    /// The domain is the range of the samples, and the knots are uniform with `degree` extra knots on each side, so every basis function is fully supported on the domain.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `xs` - A vector of sample positions with at least two distinct values.
+    /// 
+    /// * `ys` - A vector of sample values, one per position.
+    /// 
+    /// * `degree` - The degree of the B-spline.
+    /// 
+    /// * `num_control_points` - The number of control points, greater than the degree.
+    /// 
+    /// # Returns
+    /// 
+    /// * The B-spline whose control points minimise the squared error to the samples.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let xs = Vector::new((0..50).map(|i| i as f64 / 49.0).collect());
+    /// let ys = xs.map(|x| x.sin());
+    /// 
+    /// let spline = BSpline::fit(&xs, &ys, 3, 8);
+    /// ```
+    pub fn fit(xs: &Vector, ys: &Vector, degree: usize, num_control_points: usize) -> BSpline {
+        if xs.len() != ys.len() {
+            panic!("The number of sample positions must be equal to the number of sample values.");
+        }
+        if num_control_points <= degree {
+            panic!("The number of control points must be greater than the degree.");
+        }
+        let min: f64 = xs.elements.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max: f64 = xs.elements.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if min >= max || !min.is_finite() || !max.is_finite() {
+            panic!("At least two distinct sample positions are required to fit a B-spline.");
+        }
+
+        let intervals: usize = num_control_points - degree;
+        let knots: Vector = Vector::new((0..num_control_points + degree + 1).map(|j| (j as f64 - degree as f64) / intervals as f64).collect());
+        let mut spline: BSpline = BSpline::with_knots(Vector::zeros(num_control_points), knots, degree);
+        spline.set_domain((min, max));
+
+        let samples: Vector = xs.map(|x| spline.normalize(x));
+        spline.fit_control_points(&samples, ys);
+        spline
+    }
+
+    /// Set the domain of the B-spline, the range of inputs that is mapped affinely onto the parameter range [0, 1] of the knots.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `domain` - A pair of finite bounds (lower, upper) with lower < upper.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut spline = BSpline::new(control_points, 2);
+    /// spline.set_domain((-3.0, 3.0));
+    /// ```
+    pub fn set_domain(&mut self, domain: (f64, f64)) {
+        if !(domain.0.is_finite() && domain.1.is_finite() && domain.0 < domain.1) {
+            panic!("The domain must be a finite range with a lower bound smaller than the upper bound.");
+        }
+        self.domain = domain;
     }
 
     /// Evaluate the B-spline at a given parameter value t.
     /// 
     /// # Arguments
     /// 
-    /// * `t` - A parameter value between 0 and 1.
+    /// * `t` - A parameter value in the domain of the B-spline.
     /// 
     /// # Returns
     /// 
     /// * The value of the B-spline at the given parameter value t.
     pub fn eval(&mut self, t: f64) -> f64 {
+        let u: f64 = self.normalize(t);
+        let bound: Option<f64> = self.boundary(u);
+        self.eval_extrapolated(u, bound)
+    }
+
+    /// Evaluate the B-spline at a parameter value u, following the extrapolation policy beyond the nearest boundary knot if there is one.
+    fn eval_extrapolated(&mut self, u: f64, bound: Option<f64>) -> f64 {
+        match (bound, self.extrapolation) {
+            (Some(bound), Extrapolation::Clamp) => self.eval_normalized(bound),
+            (Some(bound), Extrapolation::Linear) => self.eval_normalized(bound) + self.derivative_normalized(bound, 1) * (u - bound),
+            _ => self.eval_normalized(u),
+        }
+    }
+
+    /// Evaluate the B-spline at a parameter value u in the parameter range of the knots.
+    fn eval_normalized(&mut self, u: f64) -> f64 {
         let n: usize = self.control_points.len();
         let mut result: f64 = 0.0;
+        let mut denominator: f64 = 0.0;
         for i in 0..n {
-            result += self.control_points.elements[i] * self.basis(i, self.degree, t);
+            let basis: f64 = self.basis_normalized(i, self.degree, u);
+            let weight: f64 = self.weights.as_ref().map_or(1.0, |weights| weights[i]);
+            result += weight * self.control_points.elements[i] * basis;
+            denominator += weight * basis;
+        }
+        match self.weights {
+            Some(_) if denominator == 0.0 => 0.0,
+            Some(_) => result / denominator,
+            None => result,
+        }
+    }
+
+    /// The nearest boundary knot if a parameter value u lies outside the range of the knots, panicking if the extrapolation policy forbids it.
+    fn boundary(&self, u: f64) -> Option<f64> {
+        let bound: Option<f64> = nearest_knot(&self.knots, u);
+        if bound.is_some() && self.extrapolation == Extrapolation::Panic {
+            panic!("The parameter value lies outside the range of the knots.");
         }
-        result
+        bound
+    }
+
+    /// Map a value from the domain of the B-spline onto the parameter range of the knots.
+    fn normalize(&self, t: f64) -> f64 {
+        (t - self.domain.0) / (self.domain.1 - self.domain.0)
     }
 
     /// Calculate the basis function at a given index, degree, and parameter value t.
@@ -69,7 +319,7 @@ impl BSpline {
     /// 
     /// * `degree` - The degree of the B-spline.
     /// 
-    /// * `t` - A parameter value between 0 and 1.
+    /// * `t` - A parameter value in the domain of the B-spline.
     /// 
     /// # Returns
     /// 
@@ -85,32 +335,819 @@ impl BSpline {
     /// let basis = spline.basis(i, degree, t);
     /// ```
     pub fn basis(&mut self, i: usize, degree: usize, t: f64) -> f64 {
-        let hashmap_key: String = i.to_string() + " " + &degree.to_string() + " " + &t.to_string();
-        if let Some(&result) = self.memo.get(hashmap_key.as_str()) {
+        let u: f64 = self.normalize(t);
+        match (self.boundary(u), self.extrapolation) {
+            (Some(bound), Extrapolation::Clamp) => self.basis_normalized(i, degree, bound),
+            (Some(bound), Extrapolation::Linear) => self.basis_normalized(i, degree, bound) + self.basis_derivative_normalized(i, degree, bound) * (u - bound),
+            _ => self.basis_normalized(i, degree, u),
+        }
+    }
+
+    /// Calculate the values of all basis functions at many parameter values at once.
+    /// Every row is built bottom-up from the degree-0 indicators in a single pass over the degrees, without recursion or the memo, and follows the extrapolation policy like `basis`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ts` - A vector of parameter values in the domain of the B-spline.
+    ///
+    /// # Returns
+    ///
+    /// * A matrix with one row per parameter value and one column per control point, so the values of the B-spline are the product with the control points.
+    ///   For a rational B-spline, the rows hold the rational basis w_i * N_i(t) divided by the sum of w_j * N_j(t).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let basis: Matrix = spline.basis_matrix(&Vector::new(vec![0.1, 0.5, 0.9]));
+    /// let values: Vector = &basis * &spline.control_points;
+    /// ```
+    pub fn basis_matrix(&self, ts: &Vector) -> Matrix {
+        Matrix::new(ts.elements.iter().map(|&t| self.basis_row(t).0).collect())
+    }
+
+    /// Calculate the values and derivatives with respect to t of all basis functions at a parameter value t, panicking outside the knots if the extrapolation policy forbids it.
+    fn basis_row(&self, t: f64) -> (Vector, Vector) {
+        basis_at(&self.knots, self.degree, self.weights.as_ref(), self.domain, self.extrapolation, t).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Calculate the derivative of the basis function with respect to a parameter value t in the parameter range of the knots.
+    fn basis_derivative_normalized(&mut self, i: usize, degree: usize, t: f64) -> f64 {
+        if degree == 0 {
+            return 0.0;
+        }
+        let left_width: f64 = self.knots[i + degree] - self.knots[i];
+        let right_width: f64 = self.knots[i + degree + 1] - self.knots[i + 1];
+        let left: f64 = if left_width != 0.0 { self.basis_normalized(i, degree - 1, t) / left_width } else { 0.0 };
+        let right: f64 = if right_width != 0.0 { self.basis_normalized(i + 1, degree - 1, t) / right_width } else { 0.0 };
+        degree as f64 * (left - right)
+    }
+
+    /// Calculate the basis function at a parameter value t in the parameter range of the knots.
+    fn basis_normalized(&mut self, i: usize, degree: usize, t: f64) -> f64 {
+        let key: (usize, usize, u64) = (i, degree, t.to_bits());
+        if let Some(&result) = self.memo.get(&key) {
             return result;
         }
 
         if degree == 0 {
-            return if self.knots[i] <= t && t < self.knots[i + 1] { 1.0 } else { 0.0 };
+            // Half-open spans, except that the last non-empty span also contains the last knot
+            let last: f64 = self.knots[self.knots.len() - 1];
+            let in_span: bool = self.knots[i] <= t && t < self.knots[i + 1];
+            let at_end: bool = t == last && self.knots[i] < self.knots[i + 1] && self.knots[i + 1] == last;
+            if in_span || at_end { 1.0 } else { 0.0 }
         } else {
             let left: f64 = if self.knots[i + degree] != self.knots[i] {
-                (t - self.knots[i]) / (self.knots[i + degree] - self.knots[i]) * self.basis(i, degree - 1, t)
+                (t - self.knots[i]) / (self.knots[i + degree] - self.knots[i]) * self.basis_normalized(i, degree - 1, t)
             } else {
                 0.0
             };
             let right: f64 = if self.knots[i + degree + 1] != self.knots[i + 1] {
-                (self.knots[i + degree + 1] - t) / (self.knots[i + degree + 1] - self.knots[i + 1]) * self.basis(i + 1, degree - 1, t)
+                (self.knots[i + degree + 1] - t) / (self.knots[i + degree + 1] - self.knots[i + 1]) * self.basis_normalized(i + 1, degree - 1, t)
             } else {
                 0.0
             };
-            self.memo.insert(hashmap_key, left + right);
+            self.memo.insert(key, left + right);
             left + right
         }
     }
+
+    /// Clear the cache of basis function values.
+    /// The cache grows with every distinct parameter value, and its entries are stale once the knots change.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// spline.knots = Vector::new(vec![0.0, 0.1, 0.3, 0.6, 0.8, 1.0]);
+    /// spline.clear_memo();
+    /// ```
+    pub fn clear_memo(&mut self) {
+        self.memo.clear();
+    }
+
+    /// Evaluate the first derivative of the B-spline with respect to t at a given parameter value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - A parameter value in the domain of the B-spline.
+    /// 
+    /// # Returns
+    /// 
+    /// * The derivative of the B-spline at the given parameter value t.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let slope = spline.derivative(0.5);
+    /// ```
+    pub fn derivative(&self, t: f64) -> f64 {
+        self.basis_row(t).1.dot(&self.control_points)
+    }
+
+    /// Evaluate the kth derivative of the B-spline with respect to t at a given parameter value.
+    /// The derivative of a B-spline of degree p is a B-spline of degree p - 1 on the same knots whose control points are the scaled differences p * (c[i] - c[i - 1]) / (knots[i + p] - knots[i]).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - A parameter value in the domain of the B-spline.
+    /// 
+    /// * `k` - The order of the derivative.
+    /// 
+    /// # Returns
+    /// 
+    /// * The kth derivative of the B-spline at the given parameter value t, which is zero if k exceeds the degree.
+    ///   Outside the knots, the derivatives follow the extrapolation policy.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let curvature = spline.nth_derivative(0.5, 2);
+    /// ```
+    pub fn nth_derivative(&self, t: f64, k: usize) -> f64 {
+        if k == 0 {
+            return self.clone().eval(t);
+        }
+        if k > self.degree && self.weights.is_none() {
+            return 0.0;
+        }
+        let mut u: f64 = self.normalize(t);
+        match (self.boundary(u), self.extrapolation) {
+            (Some(_), Extrapolation::Clamp) => return 0.0,
+            (Some(_), Extrapolation::Linear) if k > 1 => return 0.0,
+            (Some(bound), Extrapolation::Linear) => u = bound,
+            _ => {}
+        }
+        // Chain rule for the affine map from the domain onto the knots
+        self.derivative_normalized(u, k) / (self.domain.1 - self.domain.0).powi(k as i32)
+    }
+
+    /// Evaluate the kth derivative of the B-spline with respect to a parameter value u in the parameter range of the knots.
+    /// A rational B-spline A / W is differentiated through the polynomial B-splines A (control points w_i * c_i) and W (control points w_i) by the Leibniz rule
+    /// C^(k) = (A^(k) - sum over j = 1..k of binomial(k, j) * W^(j) * C^(k - j)) / W.
+    fn derivative_normalized(&self, u: f64, k: usize) -> f64 {
+        let weights: &Vector = match &self.weights {
+            Some(weights) => weights,
+            None => return self.polynomial_derivatives(u, k)[k],
+        };
+        let mut numerator: BSpline = self.clone();
+        numerator.weights = None;
+        numerator.control_points = Vector::new((0..weights.len()).map(|i| weights[i] * self.control_points[i]).collect());
+        let mut denominator: BSpline = numerator.clone();
+        denominator.control_points = weights.clone();
+        let a: Vec<f64> = numerator.polynomial_derivatives(u, k);
+        let w: Vec<f64> = denominator.polynomial_derivatives(u, k);
+        if w[0] == 0.0 {
+            return 0.0;
+        }
+        let mut c: Vec<f64> = Vec::with_capacity(k + 1);
+        for m in 0..=k {
+            let mut value: f64 = a[m];
+            let mut binomial: f64 = 1.0;
+            for j in 1..=m {
+                binomial = binomial * (m - j + 1) as f64 / j as f64;
+                value -= binomial * w[j] * c[m - j];
+            }
+            c.push(value / w[0]);
+        }
+        c[k]
+    }
+
+    /// The derivatives of orders 0 to k of the B-spline, ignoring its weights, at a parameter value u in the parameter range of the knots.
+    fn polynomial_derivatives(&self, u: f64, k: usize) -> Vec<f64> {
+        let mut spline: BSpline = self.clone();
+        spline.weights = None;
+        let mut derivatives: Vec<f64> = vec![0.0; k + 1];
+        for (order, derivative) in derivatives.iter_mut().enumerate() {
+            if order > 0 {
+                if spline.degree == 0 {
+                    break;
+                }
+                spline = spline.derivative_spline();
+            }
+            *derivative = spline.eval_normalized(u);
+        }
+        derivatives
+    }
+
+    /// Compute the gradient of a rational B-spline at a given parameter value t with respect to the weights of its control points.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - A parameter value in the domain of the B-spline.
+    /// 
+    /// # Returns
+    /// 
+    /// * A vector with the partial derivative w.r.t. every weight, (c_i - C(t)) * N_i(t) divided by the sum of w_j * N_j(t), or zeros for a polynomial B-spline.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let gradient = spline.grad_wrt_weights(0.5);
+    /// ```
+    pub fn grad_wrt_weights(&self, t: f64) -> Vector {
+        let weights: &Vector = match &self.weights {
+            Some(weights) => weights,
+            None => return Vector::zeros(self.control_points.len()),
+        };
+        let value: f64 = self.clone().eval(t);
+        let rational: Vector = self.basis_matrix(&Vector::new(vec![t])).row(0);
+        // The rational basis is w_i * N_i / W, so N_i / W is the rational basis divided by w_i
+        Vector::new((0..weights.len()).map(|i| (self.control_points[i] - value) * rational[i] / weights[i]).collect())
+    }
+
+    /// The B-spline of one degree lower on the same knots that represents the derivative of this B-spline.
+    /// The control points beyond both ends are taken as zero, so the derivative is also exact where the basis functions are only partially supported.
+    fn derivative_spline(&self) -> BSpline {
+        let p: usize = self.degree;
+        let n: usize = self.control_points.len();
+        let control_points: Vector = Vector::new((0..=n).map(|i| {
+            let current: f64 = if i < n { self.control_points[i] } else { 0.0 };
+            let previous: f64 = if i > 0 { self.control_points[i - 1] } else { 0.0 };
+            let width: f64 = self.knots[i + p] - self.knots[i];
+            if width == 0.0 { 0.0 } else { p as f64 * (current - previous) / width }
+        }).collect());
+        BSpline { control_points, knots: self.knots.clone(), degree: p - 1, memo: BasisMemo::default(), domain: self.domain, extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None }
+    }
+
+    /// Sample the B-spline at evenly spaced points across its domain, including both bounds, e.g. for plotting, symbolic fitting, or a lookup table for inference.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of samples, at least 2.
+    ///
+    /// # Returns
+    ///
+    /// * The sample positions and the values of the B-spline at them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (xs, ys) = spline.sample(100);
+    /// ```
+    pub fn sample(&self, n: usize) -> (Vector, Vector) {
+        if n < 2 {
+            panic!("At least two samples are required to cover the domain.");
+        }
+        let (lower, upper) = self.domain;
+        let xs: Vector = Vector::new((0..n).map(|i| lower + (upper - lower) * i as f64 / (n - 1) as f64).collect());
+        let ys: Vector = &self.basis_matrix(&xs) * &self.control_points;
+        (xs, ys)
+    }
+
+    /// Project the control points onto the shape constraint of the B-spline.
+    /// Monotonicity is enforced by isotonic regression of the control points (pool adjacent violators), which is the closest monotone sequence in the least-squares sense.
+    /// Convexity is enforced by isotonic regression of the first differences, with the offset chosen to keep the mean of the control points.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut spline = BSpline::new(Vector::new(vec![0.0, 2.0, 1.0, 3.0]), 2);
+    /// spline.shape = Shape::Increasing;
+    /// spline.project(); // control points [0.0, 1.5, 1.5, 3.0]
+    /// ```
+    pub fn project(&mut self) {
+        let c: &Vec<f64> = &self.control_points.elements;
+        let projected: Vec<f64> = match self.shape {
+            Shape::None => return,
+            Shape::Increasing => isotonic(c),
+            Shape::Decreasing => negate(&isotonic(&negate(c))),
+            Shape::Convex => convex(c),
+            Shape::Concave => negate(&convex(&negate(c))),
+        };
+        self.control_points = Vector::new(projected);
+        self.clear_memo();
+    }
+
+    /// Compute the roughness penalty of the B-spline, which approximates the integral of the squared second derivative (up to a constant factor for uniform knots) by the sum of the squared second differences of the control points.
+    /// 
+    /// # Returns
+    /// 
+    /// * The sum of (c[i] - 2 c[i+1] + c[i+2])^2 over the control points c, which is 0 for fewer than three control points.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let spline = BSpline::new(Vector::new(vec![0.0, 1.0, 4.0]), 2);
+    /// let roughness = spline.roughness(); // 4.0
+    /// ```
+    pub fn roughness(&self) -> f64 {
+        let c: &Vector = &self.control_points;
+        (2..c.len()).map(|i| (c[i - 2] - 2.0 * c[i - 1] + c[i]).powi(2)).sum()
+    }
+
+    /// Compute the gradient of the roughness penalty with respect to the control points.
+    /// 
+    /// # Returns
+    /// 
+    /// * A vector of partial derivatives, one per control point.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let gradient = spline.roughness_gradient();
+    /// ```
+    pub fn roughness_gradient(&self) -> Vector {
+        let c: &Vector = &self.control_points;
+        let mut gradient: Vector = Vector::zeros(c.len());
+        for i in 2..c.len() {
+            let difference: f64 = c[i - 2] - 2.0 * c[i - 1] + c[i];
+            gradient[i - 2] += 2.0 * difference;
+            gradient[i - 1] -= 4.0 * difference;
+            gradient[i] += 2.0 * difference;
+        }
+        gradient
+    }
+
+    /// Refine the grid of the B-spline to a given number of control points while preserving the function it represents (grid extension).
+    /// The knots are spread uniformly over the same range, and the new control points are the least-squares fit of the refined spline to the original one on a dense set of samples.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `new_num_points` - The number of control points of the refined B-spline, at least the current number.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut spline = BSpline::new(Vector::new(vec![0.0, 1.0, 2.0, 3.0, 4.0]), 2);
+    /// spline.refine(10);
+    /// ```
+    pub fn refine(&mut self, new_num_points: usize) {
+        if new_num_points < self.control_points.len() {
+            panic!("The refined B-spline must have at least as many control points as the original.");
+        }
+        let start: f64 = self.knots[0];
+        let end: f64 = self.knots[self.knots.len() - 1];
+        let num_knots: usize = new_num_points + self.degree + 1;
+        let knots: Vector = Vector::new((0..num_knots).map(|i| start + (end - start) * i as f64 / (num_knots - 1) as f64).collect());
+        self.refit(knots, start, end);
+    }
+
+    /// Reposition the knots to the empirical quantiles of observed inputs, so the resolution of the B-spline concentrates where the data lies.
+    /// The grid between the smallest and largest sample follows the quantiles (mixed with a small uniform share to keep the knots distinct) and is extended uniformly by `degree` knots on each side.
+    /// The control points are refitted so the function is preserved between the smallest and largest sample.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `samples` - A vector of observed inputs to the B-spline in its domain, with at least two distinct values.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut spline = BSpline::new(Vector::new(vec![0.0, 1.0, 2.0, 3.0, 4.0]), 2);
+    /// spline.update_knots_from_samples(&Vector::new(vec![0.1, 0.15, 0.2, 0.8]));
+    /// ```
+    pub fn update_knots_from_samples(&mut self, samples: &Vector) {
+        let mut sorted: Vec<f64> = samples.elements.iter().map(|&t| self.normalize(t)).collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        if sorted.len() < 2 || sorted[0] == sorted[sorted.len() - 1] {
+            panic!("At least two distinct samples are required to update the knots.");
+        }
+        let min: f64 = sorted[0];
+        let max: f64 = sorted[sorted.len() - 1];
+
+        // Number of intervals in the grid between the smallest and largest sample
+        let n: usize = self.control_points.len();
+        let intervals: usize = n - self.degree;
+        let spacing: f64 = (max - min) / intervals as f64;
+        let uniform_share: f64 = 0.02;
+
+        let mut knots: Vector = Vector::new(Vec::with_capacity(n + self.degree + 1));
+        for i in 0..self.degree {
+            knots.push(min - spacing * (self.degree - i) as f64);
+        }
+        for j in 0..=intervals {
+            let quantile: f64 = sorted[(j * (sorted.len() - 1) + intervals / 2) / intervals];
+            let uniform: f64 = min + spacing * j as f64;
+            knots.push(uniform_share * uniform + (1.0 - uniform_share) * quantile);
+        }
+        for i in 1..=self.degree {
+            knots.push(max + spacing * i as f64);
+        }
+        self.refit(knots, min, max);
+    }
+
+    /// Extend the knots to cover an input that lies outside their range, for inputs that drift over time.
+    /// Knots are appended on the side of the input at the average spacing of the current knots until the input is covered with `degree` knots to spare, so the resolution is kept and the number of control points grows with the range.
+    /// The control points are refitted so the function is preserved on the old range and follows the extrapolation policy beyond it, treating `Panic` like `Zero`.
+    /// The gradient of an edge with this B-spline must be resized to the new number of weights before the next update.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - A finite input to the B-spline in its domain.
+    /// 
+    /// # Returns
+    /// 
+    /// * Whether the knots changed, which is false if the input already lies within their range.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut spline = BSpline::new(Vector::new(vec![0.0, 1.0, 2.0, 3.0, 4.0]), 2);
+    /// assert!(spline.extend_to(1.4));
+    /// ```
+    pub fn extend_to(&mut self, t: f64) -> bool {
+        if !t.is_finite() {
+            panic!("The input must be finite to extend the knots.");
+        }
+        let u: f64 = self.normalize(t);
+        let first: f64 = self.knots[0];
+        let last: f64 = self.knots[self.knots.len() - 1];
+        if u >= first && u <= last {
+            return false;
+        }
+        let spacing: f64 = (last - first) / (self.knots.len() - 1) as f64;
+        let steps: usize = ((first - u).max(u - last) / spacing).ceil() as usize + self.degree;
+
+        let mut knots: Vector = Vector::new(Vec::with_capacity(self.knots.len() + steps));
+        if u < first {
+            for i in (1..=steps).rev() {
+                knots.push(first - spacing * i as f64);
+            }
+        }
+        for &knot in self.knots.iter() {
+            knots.push(knot);
+        }
+        if u > last {
+            for i in 1..=steps {
+                knots.push(last + spacing * i as f64);
+            }
+        }
+
+        // Sample the current spline, extrapolated beyond the old range, at midpoints of a grid that is much finer than the new knots
+        let start: f64 = knots[0];
+        let end: f64 = knots[knots.len() - 1];
+        let num_samples: usize = 8 * knots.len();
+        let samples: Vector = Vector::new((0..num_samples).map(|i| start + (end - start) * (i as f64 + 0.5) / num_samples as f64).collect());
+        let values: Vector = samples.map(|u| {
+            let bound: Option<f64> = if u < first { Some(first) } else if u > last { Some(last) } else { None };
+            self.eval_extrapolated(u, bound)
+        });
+        self.replace_knots(knots, &samples, &values);
+        true
+    }
+
+    /// Insert a knot with Boehm's algorithm, adding one control point without changing the function the B-spline represents.
+    /// Only the `degree` control points around the new knot change, so resolution is added locally without a refit.
+    /// The gradient of an edge with this B-spline must be resized to the new number of weights before the next update.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The position of the new knot in the domain of the B-spline, strictly inside the range of the knots.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut spline = BSpline::new(Vector::new(vec![0.0, 1.0, 2.0, 3.0, 4.0]), 2);
+    /// spline.insert_knot(0.55);
+    /// ```
+    pub fn insert_knot(&mut self, t: f64) {
+        let u: f64 = self.normalize(t);
+        let m: usize = self.knots.len();
+        if !(u > self.knots[0] && u < self.knots[m - 1]) {
+            panic!("The new knot must lie strictly inside the range of the knots.");
+        }
+        let p: usize = self.degree;
+        let n: usize = self.control_points.len();
+
+        // Span of the new knot: knots[k] <= u < knots[k + 1]
+        let k: usize = (0..m - 1).rev().find(|&i| self.knots[i] <= u).unwrap();
+
+        // Every new control point is a convex combination of two neighbouring old ones, where the points outside the B-spline count as zero
+        let boehm = |points: &Vector| -> Vector {
+            Vector::new((0..=n).map(|j| {
+                let alpha: f64 = if j + p <= k {
+                    1.0
+                } else if j > k {
+                    0.0
+                } else {
+                    (u - self.knots[j]) / (self.knots[j + p] - self.knots[j])
+                };
+                let current: f64 = if j < n { points[j] } else { 0.0 };
+                let previous: f64 = if j > 0 { points[j - 1] } else { 0.0 };
+                alpha * current + (1.0 - alpha) * previous
+            }).collect())
+        };
+        // A rational B-spline is refined in homogeneous coordinates (w_i * c_i, w_i)
+        let (control_points, weights): (Vector, Option<Vector>) = match &self.weights {
+            Some(weights) => {
+                let homogeneous: Vector = boehm(&Vector::new((0..n).map(|i| weights[i] * self.control_points[i]).collect()));
+                let weights: Vector = boehm(weights);
+                (Vector::new((0..=n).map(|j| homogeneous[j] / weights[j]).collect()), Some(weights))
+            }
+            None => (boehm(&self.control_points), None),
+        };
+
+        let mut knots: Vec<f64> = self.knots.elements.clone();
+        knots.insert(k + 1, u);
+        self.knots = Vector::new(knots);
+        self.control_points = control_points;
+        self.weights = weights;
+        self.clear_memo();
+    }
+
+    /// Remove an interior knot, dropping one control point, if the function the B-spline represents is preserved within a tolerance.
+    /// The control points are refitted by least squares on the range of the knots where the basis is complete, and the B-spline is left unchanged if the fit deviates by more than the tolerance anywhere on that range.
+    /// The gradient of an edge with this B-spline must be resized to the new number of weights before the next update.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the knot, strictly between `degree` and the number of control points.
+    ///
+    /// * `tolerance` - The largest deviation of the B-spline that is accepted.
+    ///
+    /// # Returns
+    ///
+    /// * Whether the knot was removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut spline = BSpline::new(Vector::new(vec![0.0, 1.0, 2.0, 3.0, 4.0]), 2);
+    /// let removed: bool = spline.remove_knot(3, 1e-9);
+    /// ```
+    pub fn remove_knot(&mut self, index: usize, tolerance: f64) -> bool {
+        let p: usize = self.degree;
+        let n: usize = self.control_points.len();
+        if index <= p || index >= n {
+            panic!("Only knots strictly inside the range where the basis is complete can be removed.");
+        }
+        if n <= p + 1 {
+            panic!("The B-spline has too few control points to remove a knot.");
+        }
+        let start: f64 = self.knots[p];
+        let end: f64 = self.knots[n];
+        let mut knots: Vec<f64> = self.knots.elements.clone();
+        knots.remove(index);
+
+        let mut reduced: BSpline = self.clone();
+        reduced.refit(Vector::new(knots), start, end);
+
+        // Compare on a grid that is finer than the one used for the fit
+        let num_checks: usize = 16 * (n + p);
+        let deviation: f64 = (0..=num_checks).map(|i| {
+            let u: f64 = start + (end - start) * i as f64 / num_checks as f64;
+            (reduced.eval_normalized(u) - self.eval_normalized(u)).abs()
+        }).fold(0.0, f64::max);
+        if deviation > tolerance {
+            return false;
+        }
+        *self = reduced;
+        true
+    }
+
+    /// Replace the knots and fit the control points by least squares so the new B-spline matches the current one on a dense set of samples in [start, end] of the parameter range of the knots.
+    fn refit(&mut self, knots: Vector, start: f64, end: f64) {
+        let num_points: usize = knots.len() - self.degree - 1;
+
+        // Sample the current spline at midpoints of a grid that is much finer than the new knots
+        let num_samples: usize = 8 * (num_points + self.degree);
+        let samples: Vector = Vector::new((0..num_samples).map(|i| start + (end - start) * (i as f64 + 0.5) / num_samples as f64).collect());
+        let values: Vector = samples.map(|t| self.eval_normalized(t));
+        self.replace_knots(knots, &samples, &values);
+    }
+
+    /// Replace the knots and fit the control points by least squares to values at parameter values in the parameter range of the new knots.
+    fn replace_knots(&mut self, knots: Vector, samples: &Vector, values: &Vector) {
+        let num_points: usize = knots.len() - self.degree - 1;
+        let mut refitted: BSpline = BSpline { control_points: Vector::zeros(num_points), knots, degree: self.degree, memo: BasisMemo::default(), domain: self.domain, extrapolation: self.extrapolation, shape: self.shape, weights: None };
+        refitted.fit_control_points(samples, values);
+        refitted.project();
+        *self = refitted;
+    }
+
+    /// Set the control points to the least-squares fit of the B-spline to values at parameter values in the parameter range of the knots.
+    fn fit_control_points(&mut self, samples: &Vector, values: &Vector) {
+        let num_points: usize = self.knots.len() - self.degree - 1;
+        let basis: Matrix = Matrix::new(samples.elements.iter().map(|&t| Vector::new((0..num_points).map(|i| self.basis_normalized(i, self.degree, t)).collect())).collect());
+        self.control_points = least_squares(&basis, values);
+        self.clear_memo();
+    }
+}
+
+/// Calculate the values of all basis functions of a B-spline and their derivatives with respect to t at a parameter value t in its domain, following the extrapolation policy like `BSpline::basis_matrix`.
+/// The evaluation is generic over the precision, so `BSpline` and the single-precision `single::SingleSpline` share it.
+///
+/// # Arguments
+///
+/// * `knots` - A non-decreasing vector of knots.
+///
+/// * `degree` - The degree of the B-spline.
+///
+/// * `weights` - The weights of the control points of a rational B-spline, or None for a polynomial one.
+///
+/// * `domain` - The range of inputs that is mapped affinely onto the parameter range [0, 1] of the knots.
+///
+/// * `extrapolation` - How the B-spline is evaluated outside the range of the knots.
+///
+/// * `t` - A parameter value in the domain of the B-spline.
+///
+/// # Returns
+///
+/// * A result with the values and the derivatives of the basis functions, whose products with the control points are the value and the derivative of the B-spline at t, or an error if t lies outside the knots and the extrapolation policy is `Extrapolation::Panic`.
+///   For a rational B-spline, the values are the rational basis w_i * N_i(t) divided by the sum of w_j * N_j(t).
+///
+/// # Example
+///
+/// ```
+/// let (basis, slopes) = basis_at(&spline.knots, spline.degree, None, spline.domain, spline.extrapolation, 0.5).unwrap();
+/// let value = basis.dot(&spline.control_points);
+/// ```
+pub(crate) fn basis_at<T: Float>(knots: &Vector<T>, degree: usize, weights: Option<&Vector<T>>, domain: (T, T), extrapolation: Extrapolation, t: T) -> Result<(Vector<T>, Vector<T>), KanError> {
+    let width: T = domain.1 - domain.0;
+    let u: T = (t - domain.0) / width;
+    let (values, slopes): (Vector<T>, Vector<T>) = match (nearest_knot(knots, u), extrapolation) {
+        (Some(_), Extrapolation::Panic) => return Err(KanError::DomainError("The parameter value lies outside the range of the knots.")),
+        (Some(bound), Extrapolation::Clamp) => (rational_row(knots, degree, weights, bound).0, Vector::zeros(knots.len() - degree - 1)),
+        (Some(bound), Extrapolation::Linear) => {
+            let (values, slopes) = rational_row(knots, degree, weights, bound);
+            (&values + &(&slopes * (u - bound)), slopes)
+        }
+        _ => rational_row(knots, degree, weights, u),
+    };
+    // Chain rule for the affine map from the domain onto the knots
+    Ok((values, &slopes / width))
+}
+
+/// The nearest boundary knot if a parameter value u lies outside the range of the knots.
+fn nearest_knot<T: Float>(knots: &Vector<T>, u: T) -> Option<T> {
+    let first: T = knots[0];
+    let last: T = knots[knots.len() - 1];
+    if u < first { Some(first) } else if u > last { Some(last) } else { None }
+}
+
+/// Calculate the values and derivatives of all basis functions, divided by the weighted sum of the basis for a rational B-spline, at a parameter value u in the parameter range of the knots.
+fn rational_row<T: Float>(knots: &Vector<T>, p: usize, weights: Option<&Vector<T>>, u: T) -> (Vector<T>, Vector<T>) {
+    let values: Vector<T> = polynomial_row(knots, u, p);
+    // Derivatives of the basis functions from the basis of one degree lower
+    let slopes: Vector<T> = if p == 0 {
+        Vector::zeros(values.len())
+    } else {
+        let lower: Vector<T> = polynomial_row(knots, u, p - 1);
+        let degree: T = T::from_usize(p);
+        Vector::new((0..values.len()).map(|i| {
+            let left_width: T = knots[i + p] - knots[i];
+            let right_width: T = knots[i + p + 1] - knots[i + 1];
+            let left: T = if left_width != T::ZERO { lower[i] / left_width } else { T::ZERO };
+            let right: T = if right_width != T::ZERO { lower[i + 1] / right_width } else { T::ZERO };
+            degree * (left - right)
+        }).collect())
+    };
+    let weights: &Vector<T> = match weights {
+        Some(weights) => weights,
+        None => return (values, slopes),
+    };
+    let denominator: T = (0..values.len()).map(|i| weights[i] * values[i]).sum();
+    if denominator == T::ZERO {
+        return (Vector::zeros(values.len()), Vector::zeros(values.len()));
+    }
+    let denominator_slope: T = (0..values.len()).map(|i| weights[i] * slopes[i]).sum();
+    let rational: Vector<T> = Vector::new((0..values.len()).map(|i| weights[i] * values[i] / denominator).collect());
+    let rational_slopes: Vector<T> = Vector::new((0..values.len()).map(|i| weights[i] * (slopes[i] * denominator - values[i] * denominator_slope) / (denominator * denominator)).collect());
+    (rational, rational_slopes)
+}
+
+/// Calculate the values of all basis functions of a given degree at a parameter value u in the parameter range of the knots, by the Cox-de Boor recursion evaluated bottom-up.
+fn polynomial_row<T: Float>(knots: &Vector<T>, u: T, degree: usize) -> Vector<T> {
+    let m: usize = knots.len();
+    let last: T = knots[m - 1];
+    // Degree 0 on half-open spans, except that the last non-empty span also contains the last knot
+    let mut values: Vec<T> = (0..m - 1).map(|i| {
+        let in_span: bool = knots[i] <= u && u < knots[i + 1];
+        let at_end: bool = u == last && knots[i] < knots[i + 1] && knots[i + 1] == last;
+        if in_span || at_end { T::ONE } else { T::ZERO }
+    }).collect();
+    for d in 1..=degree {
+        values = (0..m - d - 1).map(|i| {
+            let left: T = if knots[i + d] != knots[i] {
+                (u - knots[i]) / (knots[i + d] - knots[i]) * values[i]
+            } else {
+                T::ZERO
+            };
+            let right: T = if knots[i + d + 1] != knots[i + 1] {
+                (knots[i + d + 1] - u) / (knots[i + d + 1] - knots[i + 1]) * values[i + 1]
+            } else {
+                T::ZERO
+            };
+            left + right
+        }).collect();
+    }
+    Vector::new(values)
+}
+
+/// The closest non-decreasing sequence to the values in the least-squares sense, by pooling adjacent violators.
+fn isotonic(values: &[f64]) -> Vec<f64> {
+    // Blocks of pooled values as (mean, count)
+    let mut blocks: Vec<(f64, usize)> = Vec::with_capacity(values.len());
+    for &value in values.iter() {
+        blocks.push((value, 1));
+        while blocks.len() > 1 && blocks[blocks.len() - 2].0 > blocks[blocks.len() - 1].0 {
+            let (mean, count) = blocks.pop().unwrap();
+            let last: &mut (f64, usize) = blocks.last_mut().unwrap();
+            last.0 = (last.0 * last.1 as f64 + mean * count as f64) / (last.1 + count) as f64;
+            last.1 += count;
+        }
+    }
+    blocks.iter().flat_map(|&(mean, count)| std::iter::repeat_n(mean, count)).collect()
+}
+
+/// A sequence with non-decreasing first differences close to the values, with the same mean.
+fn convex(values: &[f64]) -> Vec<f64> {
+    if values.len() < 3 {
+        return values.to_vec();
+    }
+    let differences: Vec<f64> = isotonic(&values.windows(2).map(|w| w[1] - w[0]).collect::<Vec<f64>>());
+    let mut result: Vec<f64> = vec![0.0];
+    for difference in differences.iter() {
+        result.push(result[result.len() - 1] + difference);
+    }
+    let offset: f64 = values.iter().zip(result.iter()).map(|(v, r)| v - r).sum::<f64>() / values.len() as f64;
+    result.iter().map(|r| r + offset).collect()
+}
+
+fn negate(values: &[f64]) -> Vec<f64> {
+    values.iter().map(|v| -v).collect()
+}
+
+/// Solve the linear least-squares problem min ||A x - b|| through the normal equations.
+/// A tiny ridge term keeps the system solvable when some columns of A are zero.
+fn least_squares(a: &Matrix, b: &Vector) -> Vector {
+    let (rows, cols) = a.shape();
+    let mut normal: Matrix = Matrix::zeros(cols, cols);
+    let mut rhs: Vector = Vector::zeros(cols);
+    for r in 0..rows {
+        for i in 0..cols {
+            if a[r][i] == 0.0 {
+                continue;
+            }
+            for j in 0..cols {
+                normal[i][j] += a[r][i] * a[r][j];
+            }
+            rhs[i] += a[r][i] * b[r];
+        }
+    }
+    // Relative to the largest diagonal entry, so the ridge stays above the rounding errors of many samples
+    let ridge: f64 = 1e-10 * (1.0 + (0..cols).map(|i| normal[i][i]).fold(0.0, f64::max));
+    for i in 0..cols {
+        normal[i][i] += ridge;
+    }
+    // The regularised normal equations are symmetric positive definite
+    normal.solve_cholesky(&rhs).expect("The regularised normal equations of a least-squares fit are positive definite.")
 }
 
 impl std::fmt::Display for BSpline {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "BSpline {{ control_points: {:?}, knots: {:?}, degree: {} }}", self.control_points, self.knots, self.degree)
+        match &self.weights {
+            Some(weights) => write!(f, "BSpline {{ control_points: {:?}, weights: {:?}, knots: {:?}, degree: {} }}", self.control_points, weights, self.knots, self.degree),
+            None => write!(f, "BSpline {{ control_points: {:?}, knots: {:?}, degree: {} }}", self.control_points, self.knots, self.degree),
+        }
+    }
+}
+
+impl ActivationFn for BSpline {
+    fn eval(&mut self, t: f64) -> f64 {
+        BSpline::eval(self, t)
     }
-}
\ No newline at end of file
+
+    fn value(&self, t: f64) -> f64 {
+        self.basis_matrix(&Vector::new(vec![t])).row(0).dot(&self.control_points)
+    }
+
+    fn grad_wrt_params(&mut self, t: f64) -> Vector {
+        if self.weights.is_some() {
+            return self.basis_matrix(&Vector::new(vec![t])).row(0);
+        }
+        let degree: usize = self.degree;
+        Vector::new((0..self.control_points.len()).map(|i| self.basis(i, degree, t)).collect())
+    }
+
+    fn grad_wrt_input(&self, t: f64) -> f64 {
+        self.derivative(t)
+    }
+
+    fn params(&self) -> &Vector {
+        &self.control_points
+    }
+
+    fn params_mut(&mut self) -> &mut Vector {
+        &mut self.control_points
+    }
+
+    fn domain(&self) -> (f64, f64) {
+        self.domain
+    }
+
+    fn project(&mut self) {
+        BSpline::project(self)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActivationFn> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,330 @@
+use crate::data_structures::{vector::Vector, activation::ActivationFn};
+use crate::expr::Expr;
+use std::any::Any;
+use serde::{Serialize, Deserialize};
+
+/// An analytic function that a learned activation can be snapped to, one entry of the candidate library of `suggest`.
+/// The logarithm, square root, and reciprocal are clamped at the boundary of their domain, so they are defined for every real input.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SymbolicFn {
+    /// x
+    Identity,
+    /// x²
+    Square,
+    /// x³
+    Cube,
+    /// x⁴
+    Quartic,
+    /// 1/x, which is constant for |x| below 1e-2 on either side of 0.
+    Reciprocal,
+    /// √x, which is 0 for negative x.
+    Sqrt,
+    /// e^x
+    Exp,
+    /// ln x, which is constant below 1e-4.
+    Log,
+    /// sin x
+    Sin,
+    /// tanh x
+    Tanh,
+    /// |x|
+    Abs,
+    /// e^(-x²)
+    Gaussian,
+    /// 1 / (1 + e^(-x))
+    Sigmoid,
+    /// arctan x
+    Arctan,
+}
+
+// Smallest argument of the logarithm, below which it is constant.
+pub(crate) const LOG_FLOOR: f64 = 1e-4;
+
+// Smallest magnitude of the argument of the reciprocal, below which it is constant.
+pub(crate) const RECIPROCAL_FLOOR: f64 = 1e-2;
+
+impl SymbolicFn {
+    /// Every symbolic function, the default set of candidates for `Edge::symbolify`.
+    pub const ALL: [SymbolicFn; 14] = [
+        SymbolicFn::Identity, SymbolicFn::Square, SymbolicFn::Cube, SymbolicFn::Quartic, SymbolicFn::Reciprocal, SymbolicFn::Sqrt, SymbolicFn::Exp,
+        SymbolicFn::Log, SymbolicFn::Sin, SymbolicFn::Tanh, SymbolicFn::Abs, SymbolicFn::Gaussian, SymbolicFn::Sigmoid, SymbolicFn::Arctan,
+    ];
+
+    /// Evaluate the function at a given input.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - A scalar.
+    ///
+    /// # Returns
+    ///
+    /// * The value of the function at x.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let y = SymbolicFn::Sin.eval(0.5);
+    /// ```
+    pub fn eval(&self, x: f64) -> f64 {
+        match self {
+            SymbolicFn::Identity => x,
+            SymbolicFn::Square => x * x,
+            SymbolicFn::Cube => x * x * x,
+            SymbolicFn::Quartic => x * x * x * x,
+            SymbolicFn::Reciprocal => 1.0 / if x.abs() < RECIPROCAL_FLOOR { RECIPROCAL_FLOOR.copysign(x) } else { x },
+            SymbolicFn::Sqrt => x.max(0.0).sqrt(),
+            SymbolicFn::Exp => x.exp(),
+            SymbolicFn::Log => x.max(LOG_FLOOR).ln(),
+            SymbolicFn::Sin => x.sin(),
+            SymbolicFn::Tanh => x.tanh(),
+            SymbolicFn::Abs => x.abs(),
+            SymbolicFn::Gaussian => (-x * x).exp(),
+            SymbolicFn::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            SymbolicFn::Arctan => x.atan(),
+        }
+    }
+
+    /// Evaluate the derivative of the function at a given input.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - A scalar.
+    ///
+    /// # Returns
+    ///
+    /// * The derivative of the function at x, which is 0 where the function is clamped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let slope = SymbolicFn::Sin.derivative(0.5);
+    /// ```
+    pub fn derivative(&self, x: f64) -> f64 {
+        match self {
+            SymbolicFn::Identity => 1.0,
+            SymbolicFn::Square => 2.0 * x,
+            SymbolicFn::Cube => 3.0 * x * x,
+            SymbolicFn::Quartic => 4.0 * x * x * x,
+            SymbolicFn::Reciprocal => if x.abs() < RECIPROCAL_FLOOR { 0.0 } else { -1.0 / (x * x) },
+            SymbolicFn::Sqrt => if x > 0.0 { 0.5 / x.sqrt() } else { 0.0 },
+            SymbolicFn::Exp => x.exp(),
+            SymbolicFn::Log => if x > LOG_FLOOR { 1.0 / x } else { 0.0 },
+            SymbolicFn::Sin => x.cos(),
+            SymbolicFn::Tanh => 1.0 - x.tanh().powi(2),
+            SymbolicFn::Abs => if x > 0.0 { 1.0 } else if x < 0.0 { -1.0 } else { 0.0 },
+            SymbolicFn::Gaussian => -2.0 * x * (-x * x).exp(),
+            SymbolicFn::Sigmoid => {
+                let sigmoid: f64 = 1.0 / (1.0 + (-x).exp());
+                sigmoid * (1.0 - sigmoid)
+            }
+            SymbolicFn::Arctan => 1.0 / (1.0 + x * x),
+        }
+    }
+}
+
+/// A symbolic activation c * f(a * x + b) + d of an analytic function f with trainable affine parameters, the result of snapping an edge to an analytic function with `Edge::symbolify`.
+/// It is represented as the function, the parameters [a, b, c, d], and the domain of the activation it replaced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Symbolic {
+    pub function: SymbolicFn,
+    pub params: Vector, // Affine parameters [a, b, c, d]
+    pub domain: (f64, f64), // Range of inputs that the input mode of the edge maps into
+}
+
+impl Symbolic {
+    /// Create a new symbolic activation c * f(a * x + b) + d.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The analytic function f.
+    ///
+    /// * `params` - The affine parameters [a, b, c, d].
+    ///
+    /// * `domain` - The domain of the activation.
+    ///
+    /// # Returns
+    ///
+    /// * A symbolic activation with the given function and parameters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let symbolic = Symbolic::new(SymbolicFn::Sin, Vector::new(vec![3.0, 0.0, 1.0, 0.0]), (0.0, 1.0));
+    /// ```
+    pub fn new(function: SymbolicFn, params: Vector, domain: (f64, f64)) -> Symbolic {
+        if params.len() != 4 {
+            panic!("A symbolic activation has exactly four affine parameters.");
+        }
+        Symbolic { function, params, domain }
+    }
+
+    /// Fit c * f(a * x + b) + d to samples of a function by a grid search over a and b that is refined around the best point, with c and d from linear least squares.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The analytic function f.
+    ///
+    /// * `xs` - A vector of sample inputs.
+    ///
+    /// * `ys` - A vector of sample values, one per input.
+    ///
+    /// * `domain` - The domain of the fitted activation.
+    ///
+    /// # Returns
+    ///
+    /// * The fitted symbolic activation and its coefficient of determination R² on the samples.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let xs = Vector::new((0..50).map(|i| i as f64 / 49.0).collect());
+    /// let ys = xs.map(|x| (2.0 * x).sin());
+    /// let (symbolic, r2) = Symbolic::fit(SymbolicFn::Sin, &xs, &ys, (0.0, 1.0));
+    /// ```
+    pub fn fit(function: SymbolicFn, xs: &Vector, ys: &Vector, domain: (f64, f64)) -> (Symbolic, f64) {
+        if xs.len() != ys.len() || xs.len() < 2 {
+            panic!("Fitting a symbolic activation needs at least two samples with one value per input.");
+        }
+        let steps: usize = 41;
+        let mut best: (f64, f64, f64, f64, f64) = (0.0, 0.0, 0.0, 0.0, f64::NEG_INFINITY);
+        let mut center: (f64, f64) = (0.0, 0.0);
+        let mut half_width: f64 = 10.0;
+        for _ in 0..3 {
+            for i in 0..steps {
+                let a: f64 = center.0 - half_width + 2.0 * half_width * i as f64 / (steps - 1) as f64;
+                for j in 0..steps {
+                    let b: f64 = center.1 - half_width + 2.0 * half_width * j as f64 / (steps - 1) as f64;
+                    let features: Vec<f64> = xs.elements.iter().map(|x| function.eval(a * x + b)).collect();
+                    let (c, d, r2) = affine_fit(&features, &ys.elements);
+                    if r2 > best.4 {
+                        best = (a, b, c, d, r2);
+                    }
+                }
+            }
+            center = (best.0, best.1);
+            half_width /= 10.0;
+        }
+        (Symbolic::new(function, Vector::new(vec![best.0, best.1, best.2, best.3]), domain), best.4)
+    }
+
+    /// Compose the activation c * f(a * x + b) + d with an expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The expression x of the input of the activation.
+    ///
+    /// # Returns
+    ///
+    /// * The expression of the value of the activation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let expr = symbolic.expr(Expr::Var(0));
+    /// ```
+    pub fn expr(&self, input: Expr) -> Expr {
+        let argument: Expr = Expr::Sum(vec![Expr::Product(vec![Expr::Const(self.params[0]), input]), Expr::Const(self.params[1])]);
+        Expr::Sum(vec![Expr::Product(vec![Expr::Const(self.params[2]), Expr::Apply(self.function, Box::new(argument))]), Expr::Const(self.params[3])])
+    }
+}
+
+/// Fit c * f(a * x + b) + d to samples of a function for every candidate f with `Symbolic::fit`, and rank the fits by their R².
+/// This is the search behind `Edge::symbolify`, exposed on its own to inspect how well each candidate explains an activation before committing to one.
+///
+/// # Arguments
+///
+/// * `candidates` - A list of candidate functions, e.g. `SymbolicFn::ALL`.
+///
+/// * `xs` - A vector of sample inputs.
+///
+/// * `ys` - A vector of sample values, one per input.
+///
+/// * `domain` - The domain of the fitted activations.
+///
+/// # Returns
+///
+/// * The fitted symbolic activation of every candidate with its R², from the best to the worst fit. Candidates with the same R² keep their order.
+///
+/// # Example
+///
+/// ```
+/// let xs = Vector::new((0..50).map(|i| i as f64 / 49.0).collect());
+/// let ys = xs.map(|x| 1.0 / (1.0 + x));
+/// let ranking = suggest(&SymbolicFn::ALL, &xs, &ys, (0.0, 1.0));
+/// let (best, r2) = &ranking[0];
+/// ```
+pub fn suggest(candidates: &[SymbolicFn], xs: &Vector, ys: &Vector, domain: (f64, f64)) -> Vec<(Symbolic, f64)> {
+    let mut ranking: Vec<(Symbolic, f64)> = candidates.iter().map(|&function| Symbolic::fit(function, xs, ys, domain)).collect();
+    ranking.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    ranking
+}
+
+/// Fit ys ≈ c * features + d by least squares.
+/// Returns c, d, and the coefficient of determination R², which is negative infinity if the features are not finite.
+fn affine_fit(features: &[f64], ys: &[f64]) -> (f64, f64, f64) {
+    if features.iter().any(|f| !f.is_finite()) {
+        return (0.0, 0.0, f64::NEG_INFINITY);
+    }
+    let n: f64 = ys.len() as f64;
+    let mean_f: f64 = features.iter().sum::<f64>() / n;
+    let mean_y: f64 = ys.iter().sum::<f64>() / n;
+    let covariance: f64 = features.iter().zip(ys.iter()).map(|(f, y)| (f - mean_f) * (y - mean_y)).sum();
+    let variance: f64 = features.iter().map(|f| (f - mean_f).powi(2)).sum();
+    let c: f64 = if variance > 1e-12 { covariance / variance } else { 0.0 };
+    let d: f64 = mean_y - c * mean_f;
+
+    let ss_res: f64 = features.iter().zip(ys.iter()).map(|(f, y)| (y - c * f - d).powi(2)).sum();
+    let ss_tot: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+    let r2: f64 = if ss_tot > 0.0 { 1.0 - ss_res / ss_tot } else if ss_res == 0.0 { 1.0 } else { 0.0 };
+    (c, d, r2)
+}
+
+impl std::fmt::Display for Symbolic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} * {:?}({} * x + {}) + {}", self.params[2], self.function, self.params[0], self.params[1], self.params[3])
+    }
+}
+
+impl ActivationFn for Symbolic {
+    fn eval(&mut self, t: f64) -> f64 {
+        self.value(t)
+    }
+
+    fn value(&self, t: f64) -> f64 {
+        self.params[2] * self.function.eval(self.params[0] * t + self.params[1]) + self.params[3]
+    }
+
+    fn grad_wrt_params(&mut self, t: f64) -> Vector {
+        let z: f64 = self.params[0] * t + self.params[1];
+        let slope: f64 = self.params[2] * self.function.derivative(z);
+        Vector::new(vec![slope * t, slope, self.function.eval(z), 1.0])
+    }
+
+    fn grad_wrt_input(&self, t: f64) -> f64 {
+        self.params[2] * self.function.derivative(self.params[0] * t + self.params[1]) * self.params[0]
+    }
+
+    fn params(&self) -> &Vector {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut Vector {
+        &mut self.params
+    }
+
+    fn domain(&self) -> (f64, f64) {
+        self.domain
+    }
+
+    fn clone_box(&self) -> Box<dyn ActivationFn> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
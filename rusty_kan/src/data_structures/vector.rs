@@ -1,166 +1,235 @@
-use std::{ops::{Add, Div, Index, IndexMut, Mul, Sub}, iter::Iterator};
-use crate::data_structures::matrix::Matrix;
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Sub, SubAssign};
+use crate::data_structures::{matrix::Matrix, float::Float};
+use crate::error::KanError;
 use rand::Rng;
+use serde::{Serialize, Deserialize};
+use rand_distr::{Distribution, Normal, Uniform};
 
 /// A vector is a one-dimensional array of numbers.
 /// It is represented as a list of elements.
 /// The vector struct implements basic operations such as addition, subtraction, multiplication, and division.
 /// It also provides methods to calculate the dot product, element-wise product, and convert to a matrix.
-#[derive(Debug, Clone, PartialEq)]
-pub struct Vector {
-    pub elements: Vec<f64>,
+/// The elements are `f64` by default and can be any `Float`, e.g. `Vector<f32>` for single precision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Vector<T: Float = f64> {
+    pub elements: Vec<T>,
 }
 
-impl From<Vec<f64>> for Vector {
-    fn from(elements: Vec<f64>) -> Vector {
+impl<T: Float> From<Vec<T>> for Vector<T> {
+    fn from(elements: Vec<T>) -> Vector<T> {
         Vector { elements }
     }
 }
 
-impl From<&Vec<f64>> for Vector {
-    fn from(elements: &Vec<f64>) -> Vector {
+impl<T: Float> From<&Vec<T>> for Vector<T> {
+    fn from(elements: &Vec<T>) -> Vector<T> {
         Vector { elements: elements.clone() }
     }
 }
 
-impl Add<Vector> for Vector {
-    type Output = Vector;
+impl<T: Float> Add<Vector<T>> for Vector<T> {
+    type Output = Vector<T>;
 
-    fn add(self, other: Vector) -> Vector {
-        if self.len() != other.len() {
-            panic!("Vectors must have the same length for addition.");
-        }
-        Vector { elements: self.elements.iter().zip(other.elements.iter()).map(|(a, b)| a + b).collect() }
+    fn add(self, other: Vector<T>) -> Vector<T> {
+        &self + &other
     }
 }
 
-impl Add<&Vector> for &Vector {
-    type Output = Vector;
+impl<T: Float> Add<&Vector<T>> for &Vector<T> {
+    type Output = Vector<T>;
 
-    fn add(self, other: &Vector) -> Vector {
-        if self.len() != other.len() {
-            panic!("Vectors must have the same length for addition.");
-        }
-        Vector { elements: self.elements.iter().zip(other.elements.iter()).map(|(a, b)| a + b).collect() }
+    fn add(self, other: &Vector<T>) -> Vector<T> {
+        self.try_add(other).unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
-impl Sub<Vector> for Vector {
-    type Output = Vector;
+impl<T: Float> Sub<Vector<T>> for Vector<T> {
+    type Output = Vector<T>;
 
-    fn sub(self, other: Vector) -> Vector {
-        if self.len() != other.len() {
-            panic!("Vectors must have the same length for subtraction.");
-        }
-        Vector { elements: self.elements.iter().zip(other.elements.iter()).map(|(a, b)| a - b).collect() }
+    fn sub(self, other: Vector<T>) -> Vector<T> {
+        &self - &other
     }
 }
 
-impl Sub<&Vector> for &Vector {
-    type Output = Vector;
+impl<T: Float> Sub<&Vector<T>> for &Vector<T> {
+    type Output = Vector<T>;
 
-    fn sub(self, other: &Vector) -> Vector {
-        if self.len() != other.len() {
-            panic!("Vectors must have the same length for subtraction.");
-        }
-        Vector { elements: self.elements.iter().zip(other.elements.iter()).map(|(a, b)| a - b).collect() }
+    fn sub(self, other: &Vector<T>) -> Vector<T> {
+        self.try_sub(other).unwrap_or_else(|err| panic!("{}", err))
     }
 }
 
-impl Mul<f64> for Vector {
-    type Output = Vector;
+impl<T: Float> Mul<T> for Vector<T> {
+    type Output = Vector<T>;
 
-    fn mul(self, scalar: f64) -> Vector {
-        Vector { elements: self.elements.iter().map(|x| x * scalar).collect() }
+    fn mul(self, scalar: T) -> Vector<T> {
+        self.map(|x| x * scalar)
     }
 }
 
-impl Mul<f64> for &Vector {
-    type Output = Vector;
+impl<T: Float> Mul<T> for &Vector<T> {
+    type Output = Vector<T>;
 
-    fn mul(self, scalar: f64) -> Vector {
-        Vector { elements: self.elements.iter().map(|x| x * scalar).collect() }
+    fn mul(self, scalar: T) -> Vector<T> {
+        self.map(|x| x * scalar)
     }
 }
 
-impl Div<f64> for Vector {
-    type Output = Vector;
+impl<T: Float> Div<T> for Vector<T> {
+    type Output = Vector<T>;
 
-    fn div(self, scalar: f64) -> Vector {
-        if scalar == 0.0 {
+    fn div(self, scalar: T) -> Vector<T> {
+        if scalar == T::ZERO {
             panic!("Cannot divide by zero.");
         }
-        Vector { elements: self.elements.iter().map(|x| x / scalar).collect() }
+        self.map(|x| x / scalar)
     }
 }
 
-impl Div<f64> for &Vector {
-    type Output = Vector;
+impl<T: Float> Div<T> for &Vector<T> {
+    type Output = Vector<T>;
+
+    fn div(self, scalar: T) -> Vector<T> {
+        if scalar == T::ZERO {
+            panic!("Cannot divide by zero.");
+        }
+        self.map(|x| x / scalar)
+    }
+}
+
+impl<T: Float> AddAssign<&Vector<T>> for Vector<T> {
+    fn add_assign(&mut self, other: &Vector<T>) {
+        self.axpy(T::ONE, other);
+    }
+}
+
+impl<T: Float> SubAssign<&Vector<T>> for Vector<T> {
+    fn sub_assign(&mut self, other: &Vector<T>) {
+        if self.len() != other.len() {
+            panic!("Vectors must have the same length for subtraction.");
+        }
+        for (a, &b) in self.elements.iter_mut().zip(other.elements.iter()) {
+            *a -= b;
+        }
+    }
+}
+
+impl<T: Float> MulAssign<T> for Vector<T> {
+    fn mul_assign(&mut self, scalar: T) {
+        for a in self.elements.iter_mut() {
+            *a *= scalar;
+        }
+    }
+}
 
-    fn div(self, scalar: f64) -> Vector {
-        if scalar == 0.0 {
+impl<T: Float> DivAssign<T> for Vector<T> {
+    fn div_assign(&mut self, scalar: T) {
+        if scalar == T::ZERO {
             panic!("Cannot divide by zero.");
         }
-        Vector { elements: self.elements.iter().map(|x| x / scalar).collect() }
+        for a in self.elements.iter_mut() {
+            *a /= scalar;
+        }
     }
 }
 
-impl Index<usize> for Vector {
-    type Output = f64;
+impl<T: Float> Index<usize> for Vector<T> {
+    type Output = T;
 
-    fn index(&self, index: usize) -> &f64 {
+    fn index(&self, index: usize) -> &T {
         &self.elements[index]
     }
 }
 
-impl Index<usize> for &Vector {
-    type Output = f64;
+impl<T: Float> Index<usize> for &Vector<T> {
+    type Output = T;
 
-    fn index(&self, index: usize) -> &f64 {
+    fn index(&self, index: usize) -> &T {
         &self.elements[index]
     }
 }
 
-impl Index<usize> for &mut Vector {
-    type Output = f64;
+impl<T: Float> Index<usize> for &mut Vector<T> {
+    type Output = T;
 
-    fn index(&self, index: usize) -> &f64 {
+    fn index(&self, index: usize) -> &T {
         &self.elements[index]
     }
 }
 
-impl IndexMut<usize> for Vector {
-    fn index_mut(&mut self, index: usize) -> &mut f64 {
+impl<T: Float> IndexMut<usize> for Vector<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
         &mut self.elements[index]
     }
 }
 
-impl IndexMut<usize> for &mut Vector {
-    fn index_mut(&mut self, index: usize) -> &mut f64 {
+impl<T: Float> IndexMut<usize> for &mut Vector<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
         &mut self.elements[index]
     }
 }
 
-impl Vector {
+impl<T: Float> Vector<T> {
     /// Create a new vector with the given elements.
-    pub fn new(elements: Vec<f64>) -> Vector {
+    pub fn new(elements: Vec<T>) -> Vector<T> {
         Vector { elements }
     }
 
+    /// Create a new vector by joining the elements of the given vectors in order.
+    pub fn concat(vectors: &[Vector<T>]) -> Vector<T> {
+        Vector { elements: vectors.iter().flat_map(|vector| vector.elements.iter().copied()).collect() }
+    }
+
     /// Create a new vector with the given size and all elements set to zero.
-    pub fn zeros(size: usize) -> Vector {
-        Vector { elements: vec![0.0; size] }
+    pub fn zeros(size: usize) -> Vector<T> {
+        Vector { elements: vec![T::ZERO; size] }
     }
 
     /// Create a new vector with the given size and all elements set to one.
-    pub fn ones(size: usize) -> Vector {
-        Vector { elements: vec![1.0; size] }
+    pub fn ones(size: usize) -> Vector<T> {
+        Vector { elements: vec![T::ONE; size] }
+    }
+
+    /// Create a new vector with the given size and all elements uniformly distributed between 0 and 1.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn random(size: usize) -> Vector<T> {
+        Vector::random_with_rng(size, &mut rand::thread_rng())
     }
 
-    /// Create a new vector with the given size and all elements normally distributed with mean 0 and standard deviation 1.
-    pub fn random(size: usize) -> Vector {
-        let mut rng = rand::thread_rng();
-        Vector { elements: (0..size).map(|_| rng.gen::<f64>()).collect() }
+    /// Create a new vector with the given size and all elements drawn from the given random number generator, so the result is reproducible for a seeded generator.
+    pub fn random_with_rng(size: usize, rng: &mut impl Rng) -> Vector<T> {
+        Vector { elements: (0..size).map(|_| T::from_f64(rng.gen::<f64>())).collect() }
+    }
+
+    /// Create a new vector with the given size and all elements uniformly distributed between `low` and `high`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn random_uniform(size: usize, low: T, high: T) -> Vector<T> {
+        Vector::random_uniform_with_rng(size, low, high, &mut rand::thread_rng())
+    }
+
+    /// Create a new vector like `Vector::random_uniform`, drawing the elements from the given random number generator.
+    pub fn random_uniform_with_rng(size: usize, low: T, high: T, rng: &mut impl Rng) -> Vector<T> {
+        if low >= high {
+            panic!("The lower bound of the uniform distribution must be smaller than the upper bound.");
+        }
+        let distribution: Uniform<f64> = Uniform::new(low.to_f64(), high.to_f64());
+        Vector { elements: (0..size).map(|_| T::from_f64(distribution.sample(rng))).collect() }
+    }
+
+    /// Create a new vector with the given size and all elements normally distributed with mean `mean` and standard deviation `std`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn random_normal(size: usize, mean: T, std: T) -> Vector<T> {
+        Vector::random_normal_with_rng(size, mean, std, &mut rand::thread_rng())
+    }
+
+    /// Create a new vector like `Vector::random_normal`, drawing the elements from the given random number generator.
+    pub fn random_normal_with_rng(size: usize, mean: T, std: T, rng: &mut impl Rng) -> Vector<T> {
+        if !std.is_finite() || std < T::ZERO {
+            panic!("The standard deviation of the normal distribution must be finite and non-negative.");
+        }
+        let distribution: Normal<f64> = Normal::new(mean.to_f64(), std.to_f64()).unwrap();
+        Vector { elements: (0..size).map(|_| T::from_f64(distribution.sample(rng))).collect() }
     }
 
     /// Return the length of the vector.
@@ -168,42 +237,220 @@ impl Vector {
         self.elements.len()
     }
 
+    /// Return whether the vector has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Return an iterator over the elements of the vector.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.elements.iter()
+    }
+
+    /// Return an iterator that allows modifying the elements of the vector in place.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.elements.iter_mut()
+    }
+
+    /// Return a new vector with the given function applied to every element, leaving the vector unchanged.
+    pub fn map(&self, f: impl FnMut(T) -> T) -> Vector<T> {
+        Vector { elements: self.elements.iter().copied().map(f).collect() }
+    }
+
+    /// Add a multiple of another vector to the vector in place, i.e. self = self + alpha * x, without allocating.
+    pub fn axpy(&mut self, alpha: T, x: &Vector<T>) {
+        if self.len() != x.len() {
+            panic!("Vectors must have the same length for addition.");
+        }
+        for (a, &b) in self.elements.iter_mut().zip(x.elements.iter()) {
+            *a += alpha * b;
+        }
+    }
+
+    /// Return the outer product of two vectors, the matrix whose element (i, j) is `self[i] * other[j]`.
+    pub fn outer(&self, other: &Vector<T>) -> Matrix<T> {
+        Matrix { elements: self.elements.iter().flat_map(|&a| other.elements.iter().map(move |&b| a * b)).collect(), rows: self.len(), cols: other.len() }
+    }
+
     /// Return the dot product of two vectors.
-    pub fn dot(&self, other: &Vector) -> f64 {
+    pub fn dot(&self, other: &Vector<T>) -> T {
+        self.try_dot(other).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Return the sum of two vectors like `+`, or an error instead of a panic if their lengths differ.
+    pub fn try_add(&self, other: &Vector<T>) -> Result<Vector<T>, KanError> {
+        if self.len() != other.len() {
+            return Err(KanError::mismatch("elements in the second vector of the addition", self.len(), other.len()));
+        }
+        Ok(Vector { elements: self.elements.iter().zip(other.elements.iter()).map(|(&a, &b)| a + b).collect() })
+    }
+
+    /// Return the difference of two vectors like `-`, or an error instead of a panic if their lengths differ.
+    pub fn try_sub(&self, other: &Vector<T>) -> Result<Vector<T>, KanError> {
+        if self.len() != other.len() {
+            return Err(KanError::mismatch("elements in the second vector of the subtraction", self.len(), other.len()));
+        }
+        Ok(Vector { elements: self.elements.iter().zip(other.elements.iter()).map(|(&a, &b)| a - b).collect() })
+    }
+
+    /// Return the dot product of two vectors like `dot`, or an error instead of a panic if their lengths differ.
+    pub fn try_dot(&self, other: &Vector<T>) -> Result<T, KanError> {
+        if self.len() != other.len() {
+            return Err(KanError::mismatch("elements in the second vector of the dot product", self.len(), other.len()));
+        }
+        Ok(self.elements.iter().zip(other.elements.iter()).map(|(&a, &b)| a * b).sum())
+    }
+
+    /// Return the dot product of two vectors with compensated summation, which keeps the rounding error independent of the length of the vectors.
+    pub fn dot_stable(&self, other: &Vector<T>) -> T {
         if self.len() != other.len() {
             panic!("Vectors must have the same length for the dot product.");
         }
-        self.element_wise(other).elements.iter().sum()
+        compensated_sum(self.elements.iter().zip(other.elements.iter()).map(|(&a, &b)| a * b))
     }
 
     /// Return the element-wise product of two vectors.
-    pub fn element_wise(&self, other: &Vector) -> Vector {
+    pub fn element_wise(&self, other: &Vector<T>) -> Vector<T> {
         if self.len() != other.len() {
             panic!("Vectors must have the same length for element-wise multiplication.");
         }
-        Vector { elements: self.elements.iter().zip(other.elements.iter()).map(|(a, b)| a * b).collect() }
+        Vector { elements: self.elements.iter().zip(other.elements.iter()).map(|(&a, &b)| a * b).collect() }
     }
 
     /// Convert the vector to a matrix.
-    pub fn to_matrix(&self) -> Matrix {
+    pub fn to_matrix(&self) -> Matrix<T> {
         Matrix::new(vec![self.clone()])
     }
 
     /// Add an element to the vector.
-    pub fn push(&mut self, element: f64) {
+    pub fn push(&mut self, element: T) {
         self.elements.push(element);
     }
+
+    /// Return the sum of the elements, which is 0 for an empty vector.
+    pub fn sum(&self) -> T {
+        self.elements.iter().sum()
+    }
+
+    /// Return the sum of the elements with compensated summation, which keeps the rounding error independent of the length of the vector.
+    pub fn sum_stable(&self) -> T {
+        compensated_sum(self.elements.iter().copied())
+    }
+
+    /// Return the mean of the elements.
+    pub fn mean(&self) -> T {
+        if self.is_empty() {
+            panic!("Cannot compute the mean of an empty vector.");
+        }
+        self.sum() / T::from_usize(self.len())
+    }
+
+    /// Return the population standard deviation of the elements, i.e. the root of the mean squared deviation from the mean.
+    pub fn std(&self) -> T {
+        let mean: T = self.mean();
+        let variance: T = self.elements.iter().map(|&x| (x - mean) * (x - mean)).sum::<T>() / T::from_usize(self.len());
+        variance.sqrt()
+    }
+
+    /// Return the smallest element, ignoring NaN.
+    pub fn min(&self) -> T {
+        self[self.argmin()]
+    }
+
+    /// Return the largest element, ignoring NaN.
+    pub fn max(&self) -> T {
+        self[self.argmax()]
+    }
+
+    /// Return the index of the first smallest element, ignoring NaN.
+    pub fn argmin(&self) -> usize {
+        if self.is_empty() {
+            panic!("Cannot compute the minimum of an empty vector.");
+        }
+        let mut best: usize = 0;
+        for i in 1..self.len() {
+            if self[i] < self[best] || self[best].is_nan() {
+                best = i;
+            }
+        }
+        best
+    }
+
+    /// Return the index of the first largest element, ignoring NaN.
+    pub fn argmax(&self) -> usize {
+        if self.is_empty() {
+            panic!("Cannot compute the maximum of an empty vector.");
+        }
+        let mut best: usize = 0;
+        for i in 1..self.len() {
+            if self[i] > self[best] || self[best].is_nan() {
+                best = i;
+            }
+        }
+        best
+    }
+
+    /// Return the p-norm of the vector for p >= 1, where `T::INFINITY` gives the largest absolute value.
+    pub fn norm(&self, p: T) -> T {
+        if p < T::ONE || p.is_nan() {
+            panic!("The order of the norm must be at least 1.");
+        }
+        if p == T::INFINITY {
+            return self.elements.iter().fold(T::ZERO, |max: T, x| max.max(x.abs()));
+        }
+        self.elements.iter().map(|x| x.abs().powf(p)).sum::<T>().powf(T::ONE / p)
+    }
+
+    /// Convert the elements of the vector to another precision, rounding to the nearest representable value.
+    pub fn cast<U: Float>(&self) -> Vector<U> {
+        Vector { elements: self.elements.iter().map(|&x| U::from_f64(x.to_f64())).collect() }
+    }
+}
+
+/// Sum values with the compensated summation of Neumaier, which carries the low-order bits lost by every addition in a separate term.
+fn compensated_sum<T: Float>(values: impl Iterator<Item = T>) -> T {
+    let mut sum: T = T::ZERO;
+    let mut compensation: T = T::ZERO;
+    for value in values {
+        let total: T = sum + value;
+        if sum.abs() >= value.abs() {
+            compensation += (sum - total) + value;
+        } else {
+            compensation += (value - total) + sum;
+        }
+        sum = total;
+    }
+    sum + compensation
+}
+
+impl<T: Float> IntoIterator for Vector<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.into_iter()
+    }
+}
+
+impl<'a, T: Float> IntoIterator for &'a Vector<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.iter()
+    }
 }
 
-impl Iterator for Vector {
-    type Item = f64;
+impl<'a, T: Float> IntoIterator for &'a mut Vector<T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.elements.pop()
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.iter_mut()
     }
 }
 
-impl std::fmt::Display for Vector {
+impl<T: Float> std::fmt::Display for Vector<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         for (i, element) in self.elements.iter().enumerate() {
             if i == 0 {
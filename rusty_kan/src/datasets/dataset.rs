@@ -0,0 +1,262 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::error::KanError;
+use crate::builder::KANBuilder;
+use crate::kan::KAN;
+use crate::trainer::Trainer;
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+
+/// A dataset is a list of input-target pairs.
+/// It is represented as a matrix of inputs and a matrix of targets, where the ith rows of both matrices form the ith sample.
+/// The dataset struct provides methods to split the samples for model selection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dataset {
+    pub inputs: Matrix,
+    pub targets: Matrix,
+}
+
+impl Dataset {
+    /// Create a new dataset from a matrix of inputs and a matrix of targets with the same number of rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith sample.
+    ///
+    /// * `targets` - A matrix where the ith row represents the target values for the ith sample.
+    ///
+    /// # Returns
+    ///
+    /// * A dataset with the given samples.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let dataset = Dataset::new(inputs, targets);
+    /// ```
+    pub fn new(inputs: Matrix, targets: Matrix) -> Dataset {
+        if inputs.rows != targets.rows {
+            panic!("The number of inputs must be equal to the number of targets.");
+        }
+        Dataset { inputs, targets }
+    }
+
+    /// Returns the number of samples in the dataset.
+    pub fn len(&self) -> usize {
+        self.inputs.rows
+    }
+
+    /// Returns true if the dataset has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.inputs.rows == 0
+    }
+
+    /// Returns a new dataset with the samples at the given indices, in the given order.
+    pub fn select(&self, indices: &[usize]) -> Dataset {
+        let mut inputs: Matrix = Matrix::from_elements(0, self.inputs.cols, vec![]);
+        let mut targets: Matrix = Matrix::from_elements(0, self.targets.cols, vec![]);
+        for &i in indices.iter() {
+            inputs.push(self.inputs.row(i));
+            targets.push(self.targets.row(i));
+        }
+        Dataset { inputs, targets }
+    }
+
+    /// Split the samples in a random order into parts of the given relative sizes, e.g. into training, validation, and test sets.
+    /// The size of every part is rounded down and the remaining samples are added to the last part.
+    ///
+    /// # Arguments
+    ///
+    /// * `ratios` - A list of non-negative fractions of the samples, one per part, that sum to 1.
+    ///
+    /// * `seed` - A seed for the random number generator that shuffles the samples.
+    ///
+    /// # Returns
+    ///
+    /// * A list of datasets, one per ratio.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let parts = dataset.split(&[0.6, 0.2, 0.2], 42);
+    /// let (train, validation, test) = (&parts[0], &parts[1], &parts[2]);
+    /// ```
+    pub fn split(&self, ratios: &[f64], seed: u64) -> Vec<Dataset> {
+        if ratios.is_empty() || ratios.iter().any(|&ratio| ratio < 0.0) {
+            panic!("There must be at least one ratio and all ratios must be non-negative.");
+        }
+        if (ratios.iter().sum::<f64>() - 1.0).abs() > 1e-9 {
+            panic!("The ratios must sum to 1.");
+        }
+
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+
+        let mut parts: Vec<Dataset> = Vec::with_capacity(ratios.len());
+        let mut start: usize = 0;
+        for (i, &ratio) in ratios.iter().enumerate() {
+            let end: usize = if i == ratios.len() - 1 { self.len() } else { start + (self.len() as f64 * ratio).floor() as usize };
+            parts.push(self.select(&order[start..end]));
+            start = end;
+        }
+        parts
+    }
+
+    /// Split the samples into k contiguous folds for cross-validation. The sizes of the folds differ by at most one sample.
+    /// Shuffle the dataset first, e.g. with `split(&[1.0], seed)`, if its samples are ordered.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The number of folds, between 2 and the number of samples.
+    ///
+    /// # Returns
+    ///
+    /// * A list of k pairs, where the ith pair holds the samples outside the ith fold for training and the samples of the ith fold for validation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for (train, validation) in dataset.kfold(5) {
+    ///     trainer.fit(&train.inputs, &train.targets, 10).unwrap();
+    /// }
+    /// ```
+    pub fn kfold(&self, k: usize) -> Vec<(Dataset, Dataset)> {
+        if k < 2 || k > self.len() {
+            panic!("The number of folds must be at least 2 and at most the number of samples.");
+        }
+        let mut folds: Vec<(Dataset, Dataset)> = Vec::with_capacity(k);
+        for i in 0..k {
+            let start: usize = i * self.len() / k;
+            let end: usize = (i + 1) * self.len() / k;
+            let train: Vec<usize> = (0..start).chain(end..self.len()).collect();
+            folds.push((self.select(&train), self.slice(start..end)));
+        }
+        folds
+    }
+
+    /// Returns a new dataset with the samples in the given range.
+    fn slice(&self, range: std::ops::Range<usize>) -> Dataset {
+        Dataset { inputs: self.inputs.slice_rows(range.clone()), targets: self.targets.slice_rows(range) }
+    }
+}
+
+/// A data loader splits the samples of a dataset into mini-batches for every epoch.
+/// It is represented as the batch size, whether the samples are visited in a new random order every epoch, whether a last incomplete batch is dropped, and the random number generator of the shuffle.
+/// The same seed always gives the same sequence of batches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataLoader {
+    pub batch_size: usize, // Number of samples per batch
+    pub shuffle: bool, // Whether the samples are visited in a new random order every epoch
+    pub drop_last: bool, // Whether a last batch with fewer than `batch_size` samples is dropped
+    pub(crate) rng: ChaCha8Rng,
+}
+
+impl DataLoader {
+    /// Create a new data loader that shuffles the samples and keeps the last incomplete batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_size` - The number of samples per batch.
+    ///
+    /// * `seed` - A seed for the random number generator that shuffles the samples.
+    ///
+    /// # Returns
+    ///
+    /// * A data loader with the given batch size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut loader = DataLoader::new(16, 42);
+    /// loader.drop_last = true;
+    /// ```
+    pub fn new(batch_size: usize, seed: u64) -> DataLoader {
+        DataLoader { batch_size, shuffle: true, drop_last: false, rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+
+    /// Split the indices of the samples into the batches of one epoch, advancing the random number generator if the samples are shuffled.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The number of samples.
+    ///
+    /// # Returns
+    ///
+    /// * A list of batches, each a list of sample indices.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for indices in loader.indices(dataset.len()) {
+    ///     let batch = dataset.select(&indices);
+    /// }
+    /// ```
+    pub fn indices(&mut self, samples: usize) -> Vec<Vec<usize>> {
+        if self.batch_size == 0 {
+            panic!("The batch size must be positive.");
+        }
+        // Shuffle a fresh ordering so the order only depends on the state of the random number generator
+        let mut order: Vec<usize> = (0..samples).collect();
+        if self.shuffle {
+            order.shuffle(&mut self.rng);
+        }
+        order.chunks(self.batch_size).filter(|batch| !self.drop_last || batch.len() == self.batch_size).map(|batch| batch.to_vec()).collect()
+    }
+
+    /// Split a dataset into the mini-batches of one epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataset` - The samples.
+    ///
+    /// # Returns
+    ///
+    /// * A list of datasets, one per batch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for batch in loader.batches(&dataset) {
+    ///     let loss = kan.loss_with(batch.inputs, batch.targets, Loss::MeanSquaredError).unwrap();
+    /// }
+    /// ```
+    pub fn batches(&mut self, dataset: &Dataset) -> Vec<Dataset> {
+        self.indices(dataset.len()).iter().map(|indices| dataset.select(indices)).collect()
+    }
+}
+
+/// Estimate the generalisation loss of an architecture with k-fold cross-validation.
+/// For every fold, a fresh KAN is built, trained on the other folds, and evaluated on the fold with the loss function of its trainer.
+///
+/// # Arguments
+///
+/// * `builder` - The builder of the architecture; with a seed, every fold starts from the same control points.
+///
+/// * `dataset` - The samples, split into folds with `Dataset::kfold`.
+///
+/// * `k` - The number of folds.
+///
+/// * `trainer` - A function that creates the trainer of a freshly built KAN, e.g. to set the learning rate, loss, and optimizer.
+///
+/// * `epochs` - The number of epochs to train for in every fold.
+///
+/// # Returns
+///
+/// * A result with a vector where the ith element is the validation loss of the ith fold, or an error if the training or the evaluation fails.
+///
+/// # Example
+///
+/// ```
+/// let builder = KANBuilder::new(2).add_layer(3).add_layer(1).seed(42);
+/// let losses = cross_validate(&builder, &dataset, 5, |kan| Trainer::new(kan, 0.01, Loss::MeanSquaredError, 42), 20).unwrap();
+/// let loss = losses.mean();
+/// ```
+pub fn cross_validate(builder: &KANBuilder, dataset: &Dataset, k: usize, trainer: impl Fn(KAN) -> Trainer, epochs: usize) -> Result<Vector, KanError> {
+    let mut losses: Vec<f64> = Vec::with_capacity(k);
+    for (train, validation) in dataset.kfold(k) {
+        let mut trainer: Trainer = trainer(builder.build());
+        trainer.fit(&train.inputs, &train.targets, epochs)?;
+        losses.push(trainer.evaluate(&validation.inputs, &validation.targets)?);
+    }
+    Ok(Vector::new(losses))
+}
@@ -0,0 +1,2 @@
+pub mod dataset;
+pub mod toy;
\ No newline at end of file
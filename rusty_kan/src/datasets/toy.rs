@@ -0,0 +1,112 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::datasets::dataset::Dataset;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::PI;
+
+/// A toy function is one of the synthetic benchmark functions of the KAN paper and of the Feynman dataset.
+/// Every function has a fixed number of inputs, each sampled uniformly from its own range.
+///
+/// The Toy enum provides methods to evaluate a function and to generate a dataset of noisy samples from it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Toy {
+    /// exp(sin(πx) + y²) for x, y in [-1, 1], the running example of the KAN paper.
+    ExpSinSquare,
+    /// xy for x, y in [-1, 1].
+    Product,
+    /// exp((sin(π(x1² + x2²)) + sin(π(x3² + x4²))) / 2) for x1, ..., x4 in [-1, 1].
+    ExpSinFour,
+    /// Feynman I.6.2a, the standard normal density exp(-θ²/2) / sqrt(2π) for θ in [-3, 3].
+    GaussianDensity,
+    /// Feynman I.10.7, the relativistic mass m0 / sqrt(1 - v²) for m0 in [1, 5] and a speed v in [0, 0.9] in units of the speed of light.
+    RelativisticMass,
+    /// Feynman I.12.2, the Coulomb force q1 q2 / (4π r²) for q1, q2 in [1, 5] and r in [1, 5], in units where the vacuum permittivity is 1.
+    Coulomb,
+}
+
+impl Toy {
+    /// Returns the number of inputs of the function.
+    pub fn num_inputs(&self) -> usize {
+        self.ranges().len()
+    }
+
+    /// Returns the range (lower, upper) from which every input is sampled.
+    pub fn ranges(&self) -> Vec<(f64, f64)> {
+        match self {
+            Toy::ExpSinSquare | Toy::Product => vec![(-1.0, 1.0); 2],
+            Toy::ExpSinFour => vec![(-1.0, 1.0); 4],
+            Toy::GaussianDensity => vec![(-3.0, 3.0)],
+            Toy::RelativisticMass => vec![(1.0, 5.0), (0.0, 0.9)],
+            Toy::Coulomb => vec![(1.0, 5.0); 3],
+        }
+    }
+
+    /// Evaluate the function.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector with one value per input of the function.
+    ///
+    /// # Returns
+    ///
+    /// * The value of the function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let y = Toy::ExpSinSquare.eval(&Vector::new(vec![0.5, 0.0])); // e
+    /// ```
+    pub fn eval(&self, input: &Vector) -> f64 {
+        if input.len() != self.num_inputs() {
+            panic!("The number of input values must be equal to the number of inputs of the function.");
+        }
+        let x: &[f64] = &input.elements;
+        match self {
+            Toy::ExpSinSquare => ((PI * x[0]).sin() + x[1] * x[1]).exp(),
+            Toy::Product => x[0] * x[1],
+            Toy::ExpSinFour => (((PI * (x[0] * x[0] + x[1] * x[1])).sin() + (PI * (x[2] * x[2] + x[3] * x[3])).sin()) / 2.0).exp(),
+            Toy::GaussianDensity => (-x[0] * x[0] / 2.0).exp() / (2.0 * PI).sqrt(),
+            Toy::RelativisticMass => x[0] / (1.0 - x[1] * x[1]).sqrt(),
+            Toy::Coulomb => x[0] * x[1] / (4.0 * PI * x[2] * x[2]),
+        }
+    }
+
+    /// Generate a dataset of samples of the function, with the inputs drawn uniformly from their ranges.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The number of samples.
+    ///
+    /// * `noise` - The standard deviation of the Gaussian noise added to the targets, or 0 for exact targets.
+    ///
+    /// * `seed` - A seed for the random number generator, so the same seed always gives the same dataset.
+    ///
+    /// # Returns
+    ///
+    /// * A dataset with one input column per input of the function and a single target column.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let dataset = Toy::ExpSinSquare.generate(1000, 0.01, 42);
+    /// let parts = dataset.split(&[0.8, 0.2], 42);
+    /// ```
+    pub fn generate(&self, samples: usize, noise: f64, seed: u64) -> Dataset {
+        if noise < 0.0 {
+            panic!("The standard deviation of the noise must be non-negative.");
+        }
+        let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(seed);
+        let ranges: Vec<(f64, f64)> = self.ranges();
+        let mut inputs: Matrix = Matrix::zeros(samples, ranges.len());
+        let mut targets: Matrix = Matrix::zeros(samples, 1);
+        for i in 0..samples {
+            let input: Vector = Vector::new(ranges.iter().map(|&(low, high)| rng.gen_range(low..=high)).collect());
+            targets[i][0] = self.eval(&input);
+            inputs.set_row(i, input);
+        }
+        if noise > 0.0 {
+            targets.set_col(0, &targets.get_col(0) + &Vector::random_normal_with_rng(samples, 0.0, noise, &mut rng));
+        }
+        Dataset::new(inputs, targets)
+    }
+}
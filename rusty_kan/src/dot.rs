@@ -0,0 +1,69 @@
+use crate::data_structures::{edge::Edge, node::Aggregation};
+use crate::kan::KAN;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The width of the line of the edge with the largest score, relative to the default width of 1.
+const MAX_PENWIDTH: f64 = 5.0;
+
+/// The identifier of the node at a level of a KAN, where level 0 holds the inputs and level l + 1 holds the nodes of layer l.
+fn node_id(level: usize, index: usize) -> String {
+    if level == 0 { format!("x{}", index) } else { format!("n{}_{}", level - 1, index) }
+}
+
+/// Render the topology of a KAN as a Graphviz DOT graph, with one column of nodes per level from the inputs on the left to the outputs on the right.
+/// Product nodes are drawn as boxes and skip edges as dashed lines.
+/// If edges have been scored with `KAN::update_edge_scores`, the width of every edge is proportional to its score, so edges that contribute little are thin; unscored edges have the default width.
+///
+/// # Arguments
+///
+/// * `kan` - The KAN.
+///
+/// # Returns
+///
+/// * The source of the graph, which `dot -Tsvg` renders.
+///
+/// # Example
+///
+/// ```
+/// fs::write("kan.dot", dot::to_dot(&kan)).unwrap();
+/// ```
+pub fn to_dot(kan: &KAN) -> String {
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    let max_score: f64 = edges.iter().filter_map(|edge| edge.borrow().score).fold(0.0, f64::max);
+    let mut dot: String = String::from("digraph KAN {\n    rankdir=LR;\n    node [shape=circle, label=\"\", width=0.3];\n");
+    let inputs: Vec<String> = (0..kan.inputs).map(|i| format!("{} [label=\"x{}\", shape=plaintext]", node_id(0, i), i)).collect();
+    dot += &format!("    {{ rank=same; {}; }}\n", inputs.join("; "));
+    for (l, layer) in kan.layers.iter().enumerate() {
+        let nodes: Vec<String> = layer.borrow().nodes.iter().enumerate().map(|(j, node)| match node.borrow().aggregation {
+            Aggregation::Sum => node_id(l + 1, j),
+            Aggregation::Product => format!("{} [shape=box, label=\"×\"]", node_id(l + 1, j)),
+        }).collect();
+        dot += &format!("    {{ rank=same; {}; }}\n", nodes.join("; "));
+    }
+    for (l, layer) in kan.layers.iter().enumerate() {
+        for (j, node) in layer.borrow().nodes.iter().enumerate() {
+            for edge in node.borrow().incoming.iter() {
+                let edge = edge.borrow();
+                // A skip edge starts one level further back than the edges of its layer
+                let level: usize = if edge.skip { l - 1 } else { l };
+                let mut attributes: Vec<String> = Vec::new();
+                if let Some(score) = edge.score {
+                    let width: f64 = if max_score > 0.0 { 0.1 + (MAX_PENWIDTH - 0.1) * score / max_score } else { 0.1 };
+                    attributes.push(format!("penwidth={:.2}", width));
+                    attributes.push(format!("tooltip=\"score {:.3e}\"", score));
+                }
+                if edge.skip {
+                    attributes.push(String::from("style=dashed"));
+                }
+                if edge.frozen {
+                    attributes.push(String::from("color=gray"));
+                }
+                let attributes: String = if attributes.is_empty() { String::new() } else { format!(" [{}]", attributes.join(", ")) };
+                dot += &format!("    {} -> {}{};\n", node_id(level, edge.start), node_id(l + 1, j), attributes);
+            }
+        }
+    }
+    dot += "}\n";
+    dot
+}
@@ -0,0 +1,180 @@
+use crate::data_structures::matrix::Matrix;
+use crate::error::KanError;
+use crate::builder::KANBuilder;
+use crate::kan::KAN;
+use crate::trainer::{Trainer, History};
+
+/// How the predictions of the members of an ensemble are combined into one prediction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Consensus {
+    /// The mean of the predictions of the members.
+    Mean,
+    /// The median of the predictions of the members, which is robust to a single member that trained badly.
+    Median,
+}
+
+/// An ensemble of KANs with the same inputs and outputs, trained independently from different initialisations.
+/// It is represented as the list of member networks.
+///
+/// The KANEnsemble struct provides methods to train the members and to combine their predictions, where the spread of the predictions of the members is a cheap estimate of the uncertainty of the ensemble.
+#[derive(Debug)]
+pub struct KANEnsemble {
+    pub members: Vec<KAN>,
+}
+
+impl KANEnsemble {
+    /// Create a new ensemble from a list of KANs.
+    ///
+    /// # Arguments
+    ///
+    /// * `members` - A non-empty list of KANs with the same number of inputs and outputs.
+    ///
+    /// # Returns
+    ///
+    /// * An ensemble of the given KANs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ensemble = KANEnsemble::new(vec![KAN::standard_seeded(2, 3, 0), KAN::standard_seeded(2, 3, 1)]);
+    /// ```
+    pub fn new(members: Vec<KAN>) -> KANEnsemble {
+        if members.is_empty() {
+            panic!("An ensemble must have at least one member.");
+        }
+        KANEnsemble { members }
+    }
+
+    /// Build an ensemble of k KANs with the architecture of a builder, where the ith member is initialised with the seed `seed + i`.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - The builder of the members.
+    ///
+    /// * `k` - A positive scalar representing the number of members.
+    ///
+    /// * `seed` - The seed of the first member.
+    ///
+    /// # Returns
+    ///
+    /// * An ensemble of k KANs with different initialisations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ensemble = KANEnsemble::from_builder(&KANBuilder::new(2).add_layer(3).add_layer(1), 5, 42);
+    /// ```
+    pub fn from_builder(builder: &KANBuilder, k: usize, seed: u64) -> KANEnsemble {
+        KANEnsemble::new((0..k as u64).map(|i| builder.clone().seed(seed + i).build()).collect())
+    }
+
+    /// Returns the number of members of the ensemble.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns true if the ensemble has no members.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Train every member of the ensemble on the same samples with its own trainer.
+    /// Since a KAN shares its edges with its clones, the trainer of every member trains the member in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    ///
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    ///
+    /// * `epochs` - The maximum number of epochs to train every member for.
+    ///
+    /// * `trainer` - A function that creates the trainer of a member from the member and its index, e.g. to shuffle the samples with a different seed for every member.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the training was successful, with the history of every member.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let histories = ensemble.fit(&inputs, &targets, 100, |kan, i| Trainer::new(kan, 0.01, Loss::MeanSquaredError, i as u64)).unwrap();
+    /// ```
+    pub fn fit<F>(&self, inputs: &Matrix, targets: &Matrix, epochs: usize, mut trainer: F) -> Result<Vec<History>, KanError>
+    where
+        F: FnMut(KAN, usize) -> Trainer,
+    {
+        self.members.iter().enumerate().map(|(i, member)| trainer(member.clone(), i).fit(inputs, targets, epochs)).collect()
+    }
+
+    /// Predict the values of all output nodes for every sample of a dataset by combining the predictions of the members.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith sample.
+    ///
+    /// * `consensus` - How the predictions of the members are combined.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a matrix where the ith row holds the combined values of the output nodes for the ith sample, or an error if the members have different numbers of outputs or as in `KAN::forward_batch`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let predictions = ensemble.predict(&inputs, Consensus::Median).unwrap();
+    /// ```
+    pub fn predict(&self, inputs: &Matrix, consensus: Consensus) -> Result<Matrix, KanError> {
+        let outputs: Vec<Matrix> = self.member_outputs(inputs)?;
+        Ok(self.combine(&outputs, |values| match consensus {
+            Consensus::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Consensus::Median => {
+                values.sort_by(f64::total_cmp);
+                let n: usize = values.len();
+                if n % 2 == 1 { values[n / 2] } else { 0.5 * (values[n / 2 - 1] + values[n / 2]) }
+            }
+        }))
+    }
+
+    /// Predict the mean and the variance over the members of the values of all output nodes for every sample of a dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith sample.
+    ///
+    /// # Returns
+    ///
+    /// * A matrix with the mean prediction of every output node for every sample, as `predict` with `Consensus::Mean`.
+    ///
+    /// * A matrix with the population variance of the predictions of the members, which is 0 where all members agree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (mean, variance) = ensemble.predict_with_variance(&inputs).unwrap();
+    /// ```
+    pub fn predict_with_variance(&self, inputs: &Matrix) -> Result<(Matrix, Matrix), KanError> {
+        let outputs: Vec<Matrix> = self.member_outputs(inputs)?;
+        let mean: Matrix = self.combine(&outputs, |values| values.iter().sum::<f64>() / values.len() as f64);
+        let variance: Matrix = self.combine(&outputs, |values| {
+            let mean: f64 = values.iter().sum::<f64>() / values.len() as f64;
+            values.iter().map(|value| (value - mean) * (value - mean)).sum::<f64>() / values.len() as f64
+        });
+        Ok((mean, variance))
+    }
+
+    /// The outputs of every member for every sample, checking that the members have the same number of outputs.
+    fn member_outputs(&self, inputs: &Matrix) -> Result<Vec<Matrix>, KanError> {
+        let outputs: Vec<Matrix> = self.members.iter().map(|member| member.forward_batch(inputs)).collect::<Result<Vec<Matrix>, KanError>>()?;
+        if let Some(output) = outputs.iter().find(|output| output.cols != outputs[0].cols) {
+            return Err(KanError::mismatch("outputs of every member, as many as the first member", outputs[0].cols, output.cols));
+        }
+        Ok(outputs)
+    }
+
+    /// Combine the outputs of the members entry by entry.
+    fn combine<F: Fn(&mut [f64]) -> f64>(&self, outputs: &[Matrix], reduce: F) -> Matrix {
+        let (rows, cols): (usize, usize) = (outputs[0].rows, outputs[0].cols);
+        Matrix::from_elements(rows, cols, (0..rows * cols).map(|k| reduce(&mut outputs.iter().map(|output| output[k / cols][k % cols]).collect::<Vec<f64>>())).collect())
+    }
+}
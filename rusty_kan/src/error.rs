@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fmt;
+
+/// The part of a KAN where an error occurred, e.g. layer 1, node 3.
+/// Layers are indexed as in `KAN::layers`, nodes by their position in their layer, and edges by their position in the incoming edges of their node.
+/// Parts that are unknown, e.g. for the arithmetic of vectors outside a KAN, are `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Location {
+    pub layer: Option<usize>,
+    pub node: Option<usize>,
+    pub edge: Option<usize>,
+}
+
+impl Location {
+    /// Whether no part of the location is known.
+    pub fn is_empty(&self) -> bool {
+        self.layer.is_none() && self.node.is_none() && self.edge.is_none()
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = [("layer", self.layer), ("node", self.node), ("edge", self.edge)].iter().filter_map(|(name, index)| index.map(|index| format!("{} {}", name, index))).collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// The shape of the data an operation received or expected: the length of a vector, a number of items, or the rows and columns of a matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Length(usize),
+    Matrix(usize, usize),
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Shape::Length(length) => write!(f, "{}", length),
+            Shape::Matrix(rows, cols) => write!(f, "{}x{}", rows, cols),
+        }
+    }
+}
+
+/// The error of a fallible operation of the crate.
+/// The variants that carry a message describe the problem in a sentence, which `Display` prints.
+///
+/// # Example
+///
+/// ```
+/// match kan.backward_features(&features, &target, Loss::MeanSquaredError) {
+///     Err(KanError::DimensionMismatch { location, .. }) => eprintln!("Bad input at {}", location),
+///     Err(err) => eprintln!("{}", err),
+///     Ok(_) => {}
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KanError {
+    /// Two shapes that must agree do not, e.g. the number of inputs of a node and the number of its incoming edges.
+    /// `what` names the items that are counted, e.g. "inputs, one per incoming edge", and `location` the part of the KAN, if any, that received them.
+    DimensionMismatch { what: &'static str, expected: Shape, found: Shape, location: Location },
+    /// A value lies outside the domain of an operation, e.g. a negative sample weight.
+    DomainError(&'static str),
+    /// A system of equations has a singular matrix.
+    SingularMatrix,
+    /// A Cholesky factorization has a matrix that is not positive definite.
+    NotPositiveDefinite,
+    /// A setting of a model, a trainer, or one of their parts is invalid or inconsistent.
+    InvalidConfig(&'static str),
+    /// A model contains a part that the operation does not support, e.g. a normalization in code generation.
+    Unsupported(&'static str),
+    /// A file cannot be read, written, or created.
+    Io(&'static str),
+    /// The contents of a file or string are not in the expected format.
+    Format(&'static str),
+}
+
+impl fmt::Display for KanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KanError::SingularMatrix => write!(f, "The matrix is singular."),
+            KanError::NotPositiveDefinite => write!(f, "The matrix is not positive definite."),
+            KanError::DimensionMismatch { what, expected, found, location } => {
+                let sentence: String = match (expected, found) {
+                    (Shape::Length(_), Shape::Length(_)) => format!("expected {} {}, got {}.", expected, what, found),
+                    _ => format!("expected {} to have shape {}, got {}.", what, expected, found),
+                };
+                if location.is_empty() {
+                    write!(f, "{}", capitalize(&sentence))
+                } else {
+                    write!(f, "{}: {}", capitalize(&location.to_string()), sentence)
+                }
+            }
+            KanError::DomainError(message) | KanError::InvalidConfig(message) | KanError::Unsupported(message) | KanError::Io(message) | KanError::Format(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for KanError {}
+
+impl KanError {
+    /// A dimension mismatch between two numbers of items, at an unknown location.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// return Err(KanError::mismatch("inputs, one per incoming edge", self.incoming.len(), inputs.len()));
+    /// ```
+    pub fn mismatch(what: &'static str, expected: usize, found: usize) -> KanError {
+        KanError::DimensionMismatch { what, expected: Shape::Length(expected), found: Shape::Length(found), location: Location::default() }
+    }
+
+    /// A dimension mismatch between the shapes of two matrices, given as (rows, columns), at an unknown location.
+    pub fn shape_mismatch(what: &'static str, expected: (usize, usize), found: (usize, usize)) -> KanError {
+        KanError::DimensionMismatch { what, expected: Shape::Matrix(expected.0, expected.1), found: Shape::Matrix(found.0, found.1), location: Location::default() }
+    }
+
+    /// Record the layer of a dimension mismatch, unless it is already known; other errors are returned unchanged.
+    /// Layers pass errors of their nodes on with `at_node`, nodes those of their edges with `at_edge`, and the KAN those of its layers with `at_layer`, so an error raised deep in the graph names every part it passed through.
+    pub fn at_layer(mut self, layer: usize) -> KanError {
+        if let KanError::DimensionMismatch { location, .. } = &mut self {
+            location.layer = location.layer.or(Some(layer));
+        }
+        self
+    }
+
+    /// Record the node of a dimension mismatch, unless it is already known, like `at_layer`.
+    pub fn at_node(mut self, node: usize) -> KanError {
+        if let KanError::DimensionMismatch { location, .. } = &mut self {
+            location.node = location.node.or(Some(node));
+        }
+        self
+    }
+
+    /// Record the edge of a dimension mismatch, unless it is already known, like `at_layer`.
+    pub fn at_edge(mut self, edge: usize) -> KanError {
+        if let KanError::DimensionMismatch { location, .. } = &mut self {
+            location.edge = location.edge.or(Some(edge));
+        }
+        self
+    }
+}
+
+/// Capitalize the first letter of a sentence.
+fn capitalize(sentence: &str) -> String {
+    let mut chars = sentence.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,423 @@
+use crate::data_structures::{vector::Vector, node::Aggregation, norm::NormKind, symbolic::SymbolicFn};
+use crate::error::KanError;
+use crate::kan::KAN;
+use std::fmt;
+
+/// A symbolic expression of the input features, such as the formula of a KAN whose edges have been snapped to analytic functions.
+/// It is represented as a tree of constants, input features, sums, products, and known functions composed with a subexpression.
+///
+/// The Expr enum provides methods to evaluate, simplify, and print the expression. `Display` prints it in infix notation, with the precision of the formatter applied to the constants.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// A constant.
+    Const(f64),
+    /// The input feature with the given index.
+    Var(usize),
+    /// The sum of the terms, which is 0 without terms.
+    Sum(Vec<Expr>),
+    /// The product of the factors, which is 1 without factors.
+    Product(Vec<Expr>),
+    /// A known function composed with a subexpression.
+    Apply(SymbolicFn, Box<Expr>),
+}
+
+/// The notation an expression is printed in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Infix,
+    Latex,
+    Sympy,
+}
+
+impl Expr {
+    /// Evaluate the expression for given values of the input features.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A vector with the value of every input feature used by the expression.
+    ///
+    /// # Returns
+    ///
+    /// * The value of the expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let expr = Expr::Apply(SymbolicFn::Sin, Box::new(Expr::Var(0)));
+    /// let y = expr.eval(&Vector::new(vec![0.5]));
+    /// ```
+    pub fn eval(&self, inputs: &Vector) -> f64 {
+        match self {
+            Expr::Const(c) => *c,
+            Expr::Var(i) => {
+                if *i >= inputs.len() {
+                    panic!("The expression uses more input features than were given.");
+                }
+                inputs[*i]
+            }
+            Expr::Sum(terms) => terms.iter().map(|term| term.eval(inputs)).sum(),
+            Expr::Product(factors) => factors.iter().map(|factor| factor.eval(inputs)).product(),
+            Expr::Apply(function, argument) => function.eval(argument.eval(inputs)),
+        }
+    }
+
+    /// Simplify the expression without changing its value.
+    /// Nested sums and products are flattened, constants are folded, like terms of a sum are collected, terms of 0 and factors of 1 are dropped, and identity functions are removed.
+    ///
+    /// # Returns
+    ///
+    /// * The simplified expression, where the constant factor of a product comes first and the constant term of a sum comes last.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let expr = Expr::Sum(vec![Expr::Var(0), Expr::Product(vec![Expr::Const(2.0), Expr::Var(0)]), Expr::Const(0.0)]);
+    /// assert_eq!(expr.simplify(), Expr::Product(vec![Expr::Const(3.0), Expr::Var(0)]));
+    /// ```
+    pub fn simplify(&self) -> Expr {
+        match self {
+            Expr::Const(_) | Expr::Var(_) => self.clone(),
+            Expr::Apply(function, argument) => match (function, argument.simplify()) {
+                (SymbolicFn::Identity, argument) => argument,
+                (function, Expr::Const(c)) => Expr::Const(function.eval(c)),
+                (function, argument) => Expr::Apply(*function, Box::new(argument)),
+            },
+            Expr::Product(factors) => {
+                let mut constant: f64 = 1.0;
+                let mut rest: Vec<Expr> = Vec::new();
+                for factor in factors.iter().map(Expr::simplify) {
+                    let flattened: Vec<Expr> = match factor {
+                        Expr::Product(inner) => inner,
+                        factor => vec![factor],
+                    };
+                    for factor in flattened {
+                        match factor {
+                            Expr::Const(c) => constant *= c,
+                            factor => rest.push(factor),
+                        }
+                    }
+                }
+                scaled(constant, rest)
+            }
+            Expr::Sum(terms) => {
+                let mut constant: f64 = 0.0;
+                // The non-constant terms as a coefficient and the remaining factors, with like terms collected
+                let mut collected: Vec<(f64, Vec<Expr>)> = Vec::new();
+                for term in terms.iter().map(Expr::simplify) {
+                    let flattened: Vec<Expr> = match term {
+                        Expr::Sum(inner) => inner,
+                        term => vec![term],
+                    };
+                    for term in flattened {
+                        let (coefficient, factors) = term.split();
+                        if factors.is_empty() {
+                            constant += coefficient;
+                        } else if let Some(like) = collected.iter_mut().find(|(_, other)| *other == factors) {
+                            like.0 += coefficient;
+                        } else {
+                            collected.push((coefficient, factors));
+                        }
+                    }
+                }
+                let mut terms: Vec<Expr> = collected.into_iter().filter(|(coefficient, _)| *coefficient != 0.0).map(|(coefficient, factors)| scaled(coefficient, factors)).collect();
+                if constant != 0.0 {
+                    terms.push(Expr::Const(constant));
+                }
+                match terms.len() {
+                    0 => Expr::Const(0.0),
+                    1 => terms.pop().unwrap(),
+                    _ => Expr::Sum(terms),
+                }
+            }
+        }
+    }
+
+    /// Print the expression as LaTeX math, e.g. `2 \cdot \sin\left(x_{0}\right) + 1`.
+    ///
+    /// # Returns
+    ///
+    /// * The LaTeX source of the expression, without surrounding math delimiters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let latex = kan.extract_formula().unwrap()[0].to_latex();
+    /// ```
+    pub fn to_latex(&self) -> String {
+        self.render(Style::Latex, None)
+    }
+
+    /// Print the expression as a Python expression that `sympy.sympify` parses, e.g. `2*sin(x0) + 1`, to verify an extracted formula with SymPy.
+    /// The input features are the symbols x0, x1, and so on. The clamping of the logarithm, square root, and reciprocal near the boundary of their domain is not part of the printed expression.
+    ///
+    /// # Returns
+    ///
+    /// * The expression with the constants in their shortest exact form.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let source = format!("import sympy\nf = sympy.sympify(\"{}\")", kan.extract_formula().unwrap()[0].to_sympy_string());
+    /// ```
+    pub fn to_sympy_string(&self) -> String {
+        self.render(Style::Sympy, None)
+    }
+
+    /// The constant coefficient of a simplified term and its remaining factors.
+    fn split(self) -> (f64, Vec<Expr>) {
+        match self {
+            Expr::Const(c) => (c, vec![]),
+            Expr::Product(mut factors) => match factors.first() {
+                Some(&Expr::Const(c)) => {
+                    factors.remove(0);
+                    (c, factors)
+                }
+                _ => (1.0, factors),
+            },
+            term => (1.0, vec![term]),
+        }
+    }
+
+    /// The term with its sign flipped if it is printed with a leading minus sign.
+    fn negated(&self) -> Option<Expr> {
+        match self {
+            Expr::Const(c) if *c < 0.0 => Some(Expr::Const(-c)),
+            Expr::Product(factors) => match factors.first() {
+                Some(&Expr::Const(c)) if c < 0.0 => Some(scaled(-c, factors[1..].to_vec())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// How tightly the printed expression binds: 0 for sums, 1 for products and leading minus signs, 2 for powers, and 3 for atoms.
+    fn precedence(&self, style: Style) -> u8 {
+        match self {
+            Expr::Const(c) => if *c < 0.0 { 1 } else { 3 },
+            Expr::Var(_) => 3,
+            Expr::Sum(terms) => match terms.len() {
+                0 => 3,
+                1 => terms[0].precedence(style),
+                _ => 0,
+            },
+            Expr::Product(factors) => match factors.len() {
+                0 => 3,
+                1 => factors[0].precedence(style),
+                _ => 1,
+            },
+            Expr::Apply(function, argument) => match (function, style) {
+                (SymbolicFn::Identity, _) => argument.precedence(style),
+                (SymbolicFn::Square | SymbolicFn::Cube | SymbolicFn::Quartic, _) => 2,
+                (SymbolicFn::Reciprocal, Style::Infix | Style::Sympy) => 1,
+                (SymbolicFn::Sigmoid, Style::Sympy) => 1,
+                (SymbolicFn::Exp | SymbolicFn::Gaussian, Style::Latex) => 2,
+                _ => 3,
+            },
+        }
+    }
+
+    /// Print the expression, in parentheses if it binds less tightly than the given precedence.
+    fn render_at(&self, style: Style, precision: Option<usize>, precedence: u8) -> String {
+        let rendered: String = self.render(style, precision);
+        if self.precedence(style) >= precedence {
+            return rendered;
+        }
+        match style {
+            Style::Infix | Style::Sympy => format!("({})", rendered),
+            Style::Latex => format!("\\left({}\\right)", rendered),
+        }
+    }
+
+    /// Print the expression in a notation, with a number of decimal places for the constants or their shortest exact form.
+    fn render(&self, style: Style, precision: Option<usize>) -> String {
+        match self {
+            Expr::Const(c) if !c.is_finite() => match (style, c.is_nan()) {
+                (Style::Latex, true) => "\\mathrm{NaN}".to_string(),
+                (Style::Sympy, true) => "nan".to_string(),
+                (Style::Latex, false) => format!("{}\\infty", if *c < 0.0 { "-" } else { "" }),
+                (Style::Sympy, false) => format!("{}oo", if *c < 0.0 { "-" } else { "" }),
+                (Style::Infix, _) => format!("{}", c),
+            },
+            Expr::Const(c) => match precision {
+                Some(precision) => format!("{:.*}", precision, c),
+                None => format!("{}", c),
+            },
+            Expr::Var(i) => match style {
+                Style::Infix | Style::Sympy => format!("x{}", i),
+                Style::Latex => format!("x_{{{}}}", i),
+            },
+            Expr::Sum(terms) => {
+                if terms.is_empty() {
+                    return "0".to_string();
+                }
+                let mut rendered: String = terms[0].render(style, precision);
+                for term in terms[1..].iter() {
+                    match term.negated() {
+                        Some(negated) => rendered += &format!(" - {}", negated.render_at(style, precision, 1)),
+                        None => rendered += &format!(" + {}", term.render_at(style, precision, 1)),
+                    }
+                }
+                rendered
+            }
+            Expr::Product(factors) => {
+                if factors.is_empty() {
+                    return "1".to_string();
+                }
+                let separator: &str = match style {
+                    Style::Infix => " * ",
+                    Style::Sympy => "*",
+                    Style::Latex => " \\cdot ",
+                };
+                if factors.len() > 1 && factors[0] == Expr::Const(-1.0) {
+                    return format!("-{}", factors[1..].iter().map(|factor| factor.render_at(style, precision, 2)).collect::<Vec<String>>().join(separator));
+                }
+                let rest: Vec<String> = factors[1..].iter().map(|factor| factor.render_at(style, precision, 2)).collect();
+                std::iter::once(factors[0].render_at(style, precision, 1)).chain(rest).collect::<Vec<String>>().join(separator)
+            }
+            Expr::Apply(function, argument) => {
+                let inner: String = argument.render(style, precision);
+                let base: String = argument.render_at(style, precision, 3);
+                match style {
+                    Style::Infix => match function {
+                        SymbolicFn::Identity => inner,
+                        SymbolicFn::Square => format!("{}^2", base),
+                        SymbolicFn::Cube => format!("{}^3", base),
+                        SymbolicFn::Quartic => format!("{}^4", base),
+                        SymbolicFn::Reciprocal => format!("1 / {}", base),
+                        SymbolicFn::Sqrt => format!("sqrt({})", inner),
+                        SymbolicFn::Exp => format!("exp({})", inner),
+                        SymbolicFn::Log => format!("log({})", inner),
+                        SymbolicFn::Sin => format!("sin({})", inner),
+                        SymbolicFn::Tanh => format!("tanh({})", inner),
+                        SymbolicFn::Abs => format!("abs({})", inner),
+                        SymbolicFn::Gaussian => format!("exp(-{}^2)", base),
+                        SymbolicFn::Sigmoid => format!("sigmoid({})", inner),
+                        SymbolicFn::Arctan => format!("atan({})", inner),
+                    },
+                    Style::Latex => match function {
+                        SymbolicFn::Identity => inner,
+                        SymbolicFn::Square => format!("{}^{{2}}", base),
+                        SymbolicFn::Cube => format!("{}^{{3}}", base),
+                        SymbolicFn::Quartic => format!("{}^{{4}}", base),
+                        SymbolicFn::Reciprocal => format!("\\frac{{1}}{{{}}}", inner),
+                        SymbolicFn::Sqrt => format!("\\sqrt{{{}}}", inner),
+                        SymbolicFn::Exp => format!("e^{{{}}}", inner),
+                        SymbolicFn::Log => format!("\\log\\left({}\\right)", inner),
+                        SymbolicFn::Sin => format!("\\sin\\left({}\\right)", inner),
+                        SymbolicFn::Tanh => format!("\\tanh\\left({}\\right)", inner),
+                        SymbolicFn::Abs => format!("\\left|{}\\right|", inner),
+                        SymbolicFn::Gaussian => format!("e^{{-{}^{{2}}}}", base),
+                        SymbolicFn::Sigmoid => format!("\\frac{{1}}{{1 + e^{{-{}}}}}", argument.render_at(style, precision, 2)),
+                        SymbolicFn::Arctan => format!("\\arctan\\left({}\\right)", inner),
+                    },
+                    Style::Sympy => match function {
+                        SymbolicFn::Identity => inner,
+                        SymbolicFn::Square => format!("{}**2", base),
+                        SymbolicFn::Cube => format!("{}**3", base),
+                        SymbolicFn::Quartic => format!("{}**4", base),
+                        SymbolicFn::Reciprocal => format!("1/{}", base),
+                        SymbolicFn::Sqrt => format!("sqrt({})", inner),
+                        SymbolicFn::Exp => format!("exp({})", inner),
+                        SymbolicFn::Log => format!("log({})", inner),
+                        SymbolicFn::Sin => format!("sin({})", inner),
+                        SymbolicFn::Tanh => format!("tanh({})", inner),
+                        SymbolicFn::Abs => format!("Abs({})", inner),
+                        SymbolicFn::Gaussian => format!("exp(-{}**2)", base),
+                        SymbolicFn::Sigmoid => format!("1/(1 + exp(-{}))", argument.render_at(style, precision, 2)),
+                        SymbolicFn::Arctan => format!("atan({})", inner),
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// The product of a constant and factors without constants, leaving out a constant of 1.
+fn scaled(constant: f64, mut factors: Vec<Expr>) -> Expr {
+    if constant == 0.0 || factors.is_empty() {
+        return Expr::Const(constant);
+    }
+    if constant != 1.0 {
+        factors.insert(0, Expr::Const(constant));
+    }
+    match factors.len() {
+        1 => factors.pop().unwrap(),
+        _ => Expr::Product(factors),
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(Style::Infix, f.precision()))
+    }
+}
+
+/// Extract the formula of every output node of a KAN as an expression of the raw input features.
+/// The scaler, the base functions, the input modes, the keep probabilities of dropout, skip edges, product nodes, and batch normalizations are part of the formula.
+///
+/// # Arguments
+///
+/// * `kan` - A KAN whose edges with a nonzero spline weight are all symbolic, e.g. after `Edge::symbolify`.
+///
+/// # Returns
+///
+/// * The simplified formula of every output node, or an error if an edge is not symbolic, clamps its inputs, or a layer has a layer normalization.
+///
+/// # Example
+///
+/// ```
+/// let formulas = expr::extract(&kan).unwrap();
+/// ```
+pub fn extract(kan: &KAN) -> Result<Vec<Expr>, KanError> {
+    let Some(first) = kan.layers.first() else {
+        return Ok(vec![]);
+    };
+    // The inputs are read by the edges of the first layer and the skip edges of the second
+    let mut num_inputs: usize = first.borrow().nodes.iter().flat_map(|node| node.borrow().incoming.iter().map(|edge| edge.borrow().start + 1).collect::<Vec<usize>>()).max().unwrap_or(0);
+    if let Some(second) = kan.layers.get(1) {
+        for node in second.borrow().nodes.iter() {
+            for edge in node.borrow().incoming.iter().filter(|edge| edge.borrow().skip) {
+                num_inputs = num_inputs.max(edge.borrow().start + 1);
+            }
+        }
+    }
+    let inputs: Vec<Expr> = (0..num_inputs).map(|i| match &kan.scaler {
+        Some(scaler) => {
+            let (offset, scale) = scaler.affine(i);
+            Expr::Sum(vec![Expr::Product(vec![Expr::Const(1.0 / scale), Expr::Var(i)]), Expr::Const(-offset / scale)]).simplify()
+        }
+        None => Expr::Var(i),
+    }).collect();
+
+    // The expressions passed on by every level, the inputs followed by the normalized values of every layer
+    let mut levels: Vec<Vec<Expr>> = vec![inputs];
+    for (k, layer) in kan.layers.iter().enumerate() {
+        let layer = layer.borrow();
+        let mut values: Vec<Expr> = Vec::with_capacity(layer.nodes.len());
+        for node in layer.nodes.iter() {
+            let node = node.borrow();
+            let terms: Vec<Expr> = node.incoming.iter().map(|edge| {
+                let edge = edge.borrow();
+                let source: &Vec<Expr> = if edge.skip { &levels[k - 1] } else { &levels[k] };
+                edge.expr(source[edge.start].clone())
+            }).collect::<Result<Vec<Expr>, KanError>>()?;
+            let value: Expr = match node.aggregation {
+                Aggregation::Sum => Expr::Sum(terms),
+                Aggregation::Product => Expr::Product(terms),
+            };
+            values.push(value.simplify());
+        }
+        if let Some(norm) = &layer.norm {
+            if norm.kind == NormKind::Layer {
+                return Err(KanError::Unsupported("Layer normalizations cannot be expressed as a formula."));
+            }
+            // Batch normalization with its running statistics is an affine map of every node
+            values = values.into_iter().enumerate().map(|(i, value)| {
+                let std: f64 = (norm.running_var[i] + norm.epsilon).sqrt();
+                Expr::Sum(vec![Expr::Product(vec![Expr::Const(norm.scale[i] / std), value]), Expr::Const(norm.shift[i] - norm.scale[i] * norm.running_mean[i] / std)]).simplify()
+            }).collect();
+        }
+        levels.push(values);
+    }
+    Ok(levels.pop().unwrap())
+}
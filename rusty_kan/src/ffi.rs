@@ -0,0 +1,97 @@
+use crate::compiled::CompiledKAN;
+use crate::data_structures::vector::Vector;
+use crate::kan::KAN;
+use std::ffi::{c_char, c_int, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// An opaque handle to a KAN loaded for inference through the C interface of the `ffi` feature.
+/// The C header is `include/rusty_kan.h`, generated with `cbindgen --config cbindgen.toml --output include/rusty_kan.h`.
+pub struct KanModel {
+    compiled: CompiledKAN,
+}
+
+/// Load a KAN from a JSON file written by `KAN::save_json`.
+///
+/// # Safety
+///
+/// `path` must be a valid pointer to a NUL-terminated string.
+///
+/// # Returns
+///
+/// * A handle to the model, to be released with `kan_free`, or NULL if the file cannot be read or does not describe a KAN.
+#[no_mangle]
+pub unsafe extern "C" fn kan_load(path: *const c_char) -> *mut KanModel {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+    let path: &str = match CStr::from_ptr(path).to_str() {
+        Ok(path) => path,
+        Err(_) => return ptr::null_mut(),
+    };
+    match KAN::load_json(path) {
+        Ok(kan) => Box::into_raw(Box::new(KanModel { compiled: kan.compile() })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// The number of input values of a model, or 0 if `model` is NULL.
+///
+/// # Safety
+///
+/// `model` must be NULL or a handle returned by `kan_load` that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kan_num_inputs(model: *const KanModel) -> usize {
+    model.as_ref().map_or(0, |model| model.compiled.num_inputs())
+}
+
+/// The number of output values of a model, or 0 if `model` is NULL.
+///
+/// # Safety
+///
+/// `model` must be NULL or a handle returned by `kan_load` that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kan_num_outputs(model: *const KanModel) -> usize {
+    model.as_ref().map_or(0, |model| model.compiled.num_outputs())
+}
+
+/// Compute the output values of a model for one sample. A model can be used from several threads at once.
+///
+/// # Safety
+///
+/// `model` must be a handle returned by `kan_load` that has not been freed, `input` must point to `input_len` doubles, and `output` must point to `output_len` writable doubles.
+///
+/// # Returns
+///
+/// * 0 on success, or -1 if a pointer is NULL, the lengths do not match the model, or the evaluation fails.
+#[no_mangle]
+pub unsafe extern "C" fn kan_forward(model: *const KanModel, input: *const f64, input_len: usize, output: *mut f64, output_len: usize) -> c_int {
+    let model: &KanModel = match model.as_ref() {
+        Some(model) => model,
+        None => return -1,
+    };
+    if input.is_null() || output.is_null() || input_len != model.compiled.num_inputs() || output_len != model.compiled.num_outputs() {
+        return -1;
+    }
+    let input: Vector = Vector::new(std::slice::from_raw_parts(input, input_len).to_vec());
+    // A panic must not unwind into the caller
+    match panic::catch_unwind(AssertUnwindSafe(|| model.compiled.forward(&input))) {
+        Ok(Ok(values)) => {
+            std::slice::from_raw_parts_mut(output, output_len).copy_from_slice(&values.elements);
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// Release a model. Does nothing if `model` is NULL.
+///
+/// # Safety
+///
+/// `model` must be NULL or a handle returned by `kan_load` that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kan_free(model: *mut KanModel) {
+    if !model.is_null() {
+        drop(Box::from_raw(model));
+    }
+}
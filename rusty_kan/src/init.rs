@@ -0,0 +1,60 @@
+use crate::data_structures::vector::Vector;
+use rand::Rng;
+
+/// An initialisation scheme determines the initial control points of the splines on the edges.
+///
+/// The Init enum provides a method to sample the control points of an edge from a random number generator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Init {
+    /// All control points are zero.
+    Zeros,
+    /// Control points are uniformly distributed between `low` and `high`.
+    Uniform { low: f64, high: f64 },
+    /// Control points are normally distributed with mean `mean` and standard deviation `std`.
+    Normal { mean: f64, std: f64 },
+    /// Control points are normally distributed with mean 0 and standard deviation 1/sqrt(fan-in), so the sum at each node keeps a unit scale.
+    Xavier,
+}
+
+impl Default for Init {
+    /// The standard normal distribution.
+    fn default() -> Init {
+        Init::Normal { mean: 0.0, std: 1.0 }
+    }
+}
+
+impl Init {
+    /// Sample the control points of an edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The number of control points.
+    ///
+    /// * `fan_in` - The number of incoming edges of the destination node, used by `Init::Xavier`.
+    ///
+    /// * `rng` - A random number generator.
+    ///
+    /// # Returns
+    ///
+    /// * A vector of control points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let control_points = Init::Xavier.sample(5, 4, &mut rng);
+    /// ```
+    pub fn sample(&self, size: usize, fan_in: usize, rng: &mut impl Rng) -> Vector {
+        match *self {
+            Init::Zeros => Vector::zeros(size),
+            Init::Uniform { low, high } => Vector::random_uniform_with_rng(size, low, high, rng),
+            Init::Normal { mean, std } => Vector::random_normal_with_rng(size, mean, std, rng),
+            Init::Xavier => {
+                if fan_in == 0 {
+                    panic!("The fan-in must be greater than 0.");
+                }
+                Init::Normal { mean: 0.0, std: 1.0 / (fan_in as f64).sqrt() }.sample(size, fan_in, rng)
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// A phase of training or inference whose running time is measured by the `instrument` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// The forward pass of a KAN through all layers, on its own or as the first half of a backward pass.
+    Forward,
+    /// The propagation of the gradient of the loss back through the layers, without the forward pass.
+    Backward,
+    /// The update of the weights of the edges and normalizations by the optimizer after a batch.
+    Update,
+}
+
+impl Phase {
+    /// All phases, in the order of a training step.
+    pub const ALL: [Phase; 3] = [Phase::Forward, Phase::Backward, Phase::Update];
+
+    /// The lowercase name of the phase, e.g. for log messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Forward => "forward",
+            Phase::Backward => "backward",
+            Phase::Update => "update",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Phase::Forward => 0,
+            Phase::Backward => 1,
+            Phase::Update => 2,
+        }
+    }
+}
+
+/// The number of spans of a phase and their total running time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    pub count: usize, // Number of completed spans of the phase
+    pub total: Duration,
+}
+
+/// A function that receives every completed span, e.g. to forward it to a logging framework.
+pub type Subscriber = Box<dyn Fn(Phase, Duration)>;
+
+thread_local! {
+    static TOTALS: RefCell<[(usize, Duration); 3]> = const { RefCell::new([(0, Duration::ZERO); 3]) };
+    static SUBSCRIBER: RefCell<Option<Subscriber>> = const { RefCell::new(None) };
+}
+
+/// Set the function that receives every completed span on the current thread, replacing the previous one, or remove it with `None`.
+///
+/// # Arguments
+///
+/// * `subscriber` - The function, called with the phase and running time of every span after the span ends.
+///
+/// # Example
+///
+/// ```
+/// instrument::set_subscriber(Some(Box::new(|phase, elapsed| eprintln!("{} took {:?}", phase.name(), elapsed))));
+/// ```
+pub fn set_subscriber(subscriber: Option<Subscriber>) {
+    SUBSCRIBER.with(|current| *current.borrow_mut() = subscriber);
+}
+
+/// The number of spans and the total running time of every phase on the current thread since the last `reset`.
+///
+/// # Returns
+///
+/// * One timing per phase, in the order of `Phase::ALL`.
+///
+/// # Example
+///
+/// ```
+/// trainer.fit(&inputs, &targets, 10).unwrap();
+/// for timing in instrument::timings() {
+///     println!("{:<8} {:>6} spans {:>10.2?}", timing.phase.name(), timing.count, timing.total);
+/// }
+/// ```
+pub fn timings() -> Vec<PhaseTiming> {
+    TOTALS.with(|totals| {
+        let totals = totals.borrow();
+        Phase::ALL.iter().map(|&phase| PhaseTiming { phase, count: totals[phase.index()].0, total: totals[phase.index()].1 }).collect()
+    })
+}
+
+/// Reset the timings of all phases on the current thread to zero.
+pub fn reset() {
+    TOTALS.with(|totals| *totals.borrow_mut() = [(0, Duration::ZERO); 3]);
+}
+
+/// A running span of a phase, which is recorded when it is dropped.
+pub(crate) struct Span {
+    phase: Phase,
+    start: Instant,
+}
+
+/// Start a span of a phase that ends at the end of the scope of the returned guard.
+pub(crate) fn span(phase: Phase) -> Span {
+    Span { phase, start: Instant::now() }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let elapsed: Duration = self.start.elapsed();
+        TOTALS.with(|totals| {
+            let total = &mut totals.borrow_mut()[self.phase.index()];
+            total.0 += 1;
+            total.1 += elapsed;
+        });
+        SUBSCRIBER.with(|subscriber| {
+            if let Some(subscriber) = subscriber.borrow().as_ref() {
+                subscriber(self.phase, elapsed);
+            }
+        });
+    }
+}
@@ -1,18 +1,49 @@
-use crate::data_structures::{vector::Vector, matrix::Matrix, node::Node, layer::Layer, edge::Edge};
+use crate::data_structures::{vector::Vector, matrix::Matrix, node::{Node, Aggregation}, layer::Layer, norm::Norm, edge::Edge, spline::BSpline, activation::{ActivationFn, Basis}};
+use crate::init::Init;
+use crate::error::KanError;
+use crate::loss::{Loss, Reduction, softmax};
+use crate::compiled::CompiledKAN;
+use crate::quantized::{QuantizedKAN, QuantizedEdge, Precision};
+use crate::single::SingleKAN;
+use crate::onnx::OnnxModel;
+use crate::pykan;
+use crate::codegen;
+use crate::dot;
+use crate::plot;
+use crate::topology::{self, TopologyError};
+use crate::expr::{self, Expr};
+use crate::scaler::Scaler;
+use crate::attribution::EdgeScores;
+use crate::config::Config;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxHashMap;
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::Path;
 use std::rc::Rc;
 use std::cell::{RefCell, RefMut, Ref};
 use std::vec;
 
 /// A KAN is a collection of layers in a network.
-/// It is represented as a list of layers.
-/// The KAN struct provides methods to compute the value of the KAN and update the activation functions of the edges.
-#[derive(Debug, Clone)]
+/// It is represented as a list of layers, the number of inputs, and an optional scaler that maps the raw input values into the domain of the splines of the first layer.
+/// The inputs are not nodes: the forward pass routes the i-th input value to every incoming edge of the first layer, and every skip edge of the second layer, that starts at i.
+/// The KAN struct provides methods to compute the value of the KAN and the gradients of its edges; training is handled by the `Trainer`.
+/// It is serialized as the incoming edges of every node, so an edge shared by two layers is stored once and shared again when the KAN is deserialized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "KANData", try_from = "KANData")]
 pub struct KAN {
     pub layers: Vec<Rc<RefCell<Layer>>>,
+    pub inputs: usize, // Number of input values, which the edges of the first layer start at
+    pub scaler: Option<Scaler>, // Applied to the inputs of the first layer in every forward pass
+    pub training: bool, // Whether the KAN is in training mode, see `train_mode`
 }
 
 impl KAN {
     /// Create a new KAN with a given list of layers.
+    /// The number of inputs is one more than the largest start index of the incoming edges of the first layer and the skip edges of the second layer.
     /// 
     /// # Arguments
     /// 
@@ -20,7 +51,7 @@ impl KAN {
     /// 
     /// # Returns
     /// 
-    /// * A KAN with the given list of layers.
+    /// * A KAN with the given list of layers and no scaler, in training mode.
     /// 
     /// # Example
     /// 
@@ -30,7 +61,8 @@ impl KAN {
     /// let kan = KAN::new(layers);
     /// ```
     pub fn new(layers: Vec<Rc<RefCell<Layer>>>) -> KAN {
-        KAN { layers }
+        let inputs: usize = input_width(&layers);
+        KAN { layers, inputs, scaler: None, training: true }
     }
 
     /// Create a new KAN of standard shape (n inputs, 1 hidden layer with m nodes, 1 output).
@@ -54,58 +86,183 @@ impl KAN {
     /// 
     /// let kan = KAN::standard(n, m);
     /// ```
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn standard(n: usize, m: usize) -> KAN {
-        let mut layers: Vec<Rc<RefCell<Layer>>> = Vec::new();
-        
-        // Input layer nodes (nodes with one incoming edge and m outgoing edges to all nodes in the hidden layer)
-        let mut input_nodes: Vec<Rc<RefCell<Node>>> = Vec::with_capacity(n);
-        for i in 0..n {
-            // Incoming edge
-            let incoming_edge: Rc<RefCell<Edge>> = Rc::new(RefCell::new(Edge::standard(i, i, 0)));
+        KAN::classifier(n, m, 1)
+    }
 
-            // Outgoing edges
-            let mut outgoing_edges: Vec<Rc<RefCell<Edge>>> = Vec::with_capacity(m);
-            for j in 0..m {
-                let edge: Rc<RefCell<Edge>> = Rc::new(RefCell::new(Edge::standard(i, j, 1)));
-                outgoing_edges.push(edge);
-            }
+    /// Create a new standard KAN (n inputs, 1 hidden layer with m nodes, 1 output) whose control points are drawn from a seeded random number generator.
+    /// The same seed always produces the same KAN.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `n` - A scalar representing the number of inputs.
+    /// 
+    /// * `m` - A scalar representing the number of nodes in the hidden layer.
+    /// 
+    /// * `seed` - A seed for the random number generator.
+    /// 
+    /// # Returns
+    /// 
+    /// * A KAN with the given number of inputs and nodes in the hidden layer.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let kan = KAN::standard_seeded(2, 3, 42);
+    /// ```
+    pub fn standard_seeded(n: usize, m: usize, seed: u64) -> KAN {
+        KAN::classifier_with_rng(n, m, 1, &mut ChaCha8Rng::seed_from_u64(seed))
+    }
 
-            // Node
-            let node = Rc::new(RefCell::new(Node::new(vec![incoming_edge], outgoing_edges, 0)));
-            input_nodes.push(node);
-        }
-        
+    /// Create a new standard KAN like `KAN::standard_seeded`, with B-splines of a given degree and number of control points on every edge.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `n` - A scalar representing the number of inputs.
+    /// 
+    /// * `m` - A scalar representing the number of nodes in the hidden layer.
+    /// 
+    /// * `degree` - The degree of the B-splines.
+    /// 
+    /// * `grid_size` - The number of control points of the B-splines, at least `degree + 1`.
+    /// 
+    /// * `seed` - A seed for the random number generator.
+    /// 
+    /// # Returns
+    /// 
+    /// * A KAN with the given number of inputs and nodes in the hidden layer.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let kan = KAN::standard_with_grid(2, 3, 3, 10, 42);
+    /// ```
+    pub fn standard_with_grid(n: usize, m: usize, degree: usize, grid_size: usize, seed: u64) -> KAN {
+        KAN::classifier_with_grid(n, m, 1, degree, grid_size, &mut ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Create a new KAN for classification (n inputs, 1 hidden layer with m nodes, k outputs).
+    /// Each output node produces the logit of one class; use `predict_proba` to apply the softmax.
+    /// The control points of the edges are normally distributed with mean 0 and standard deviation 1.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `n` - A scalar representing the number of inputs.
+    /// 
+    /// * `m` - A scalar representing the number of nodes in the hidden layer.
+    /// 
+    /// * `k` - A scalar representing the number of classes.
+    /// 
+    /// # Returns
+    /// 
+    /// * A KAN with the given number of inputs, nodes in the hidden layer, and outputs.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let kan = KAN::classifier(4, 8, 3);
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn classifier(n: usize, m: usize, k: usize) -> KAN {
+        KAN::classifier_with_rng(n, m, k, &mut rand::thread_rng())
+    }
+
+    /// Create a new KAN for classification like `KAN::classifier`, drawing the control points from the given random number generator.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `n` - A scalar representing the number of inputs.
+    /// 
+    /// * `m` - A scalar representing the number of nodes in the hidden layer.
+    /// 
+    /// * `k` - A scalar representing the number of classes.
+    /// 
+    /// * `rng` - A random number generator.
+    /// 
+    /// # Returns
+    /// 
+    /// * A KAN with the given number of inputs, nodes in the hidden layer, and outputs.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let kan = KAN::classifier_with_rng(4, 8, 3, &mut rng);
+    /// ```
+    pub fn classifier_with_rng(n: usize, m: usize, k: usize, rng: &mut impl Rng) -> KAN {
+        KAN::classifier_with_grid(n, m, k, 2, 5, rng)
+    }
+
+    /// Create a new KAN for classification like `KAN::classifier_with_rng`, with B-splines of a given degree and number of control points on every edge.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `n` - A scalar representing the number of inputs.
+    /// 
+    /// * `m` - A scalar representing the number of nodes in the hidden layer.
+    /// 
+    /// * `k` - A scalar representing the number of classes.
+    /// 
+    /// * `degree` - The degree of the B-splines.
+    /// 
+    /// * `grid_size` - The number of control points of the B-splines, at least `degree + 1`.
+    /// 
+    /// * `rng` - A random number generator.
+    /// 
+    /// # Returns
+    /// 
+    /// * A KAN with the given number of inputs, nodes in the hidden layer, and outputs.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let kan = KAN::classifier_with_grid(4, 8, 3, 3, 10, &mut rng);
+    /// ```
+    pub fn classifier_with_grid(n: usize, m: usize, k: usize, degree: usize, grid_size: usize, rng: &mut impl Rng) -> KAN {
+        let mut layers: Vec<Rc<RefCell<Layer>>> = Vec::new();
+
+        // The inputs are not nodes: the edges from input i to every node in the hidden layer, which the forward pass routes the i-th feature to
+        let input_edges: Vec<Vec<Rc<RefCell<Edge>>>> = (0..n).map(|i| {
+            (0..m).map(|j| Rc::new(RefCell::new(Edge::with_activation(i, j, Basis::BSpline.sample_grid(degree, grid_size, Init::default(), 1, rng), 1)))).collect()
+        }).collect();
 
         // Nodes in the hidden layer
         let mut hidden_nodes: Vec<Rc<RefCell<Node>>> = Vec::with_capacity(m);
         for i in 0..m {
             // Incoming edges
             let mut incoming_edges: Vec<Rc<RefCell<Edge>>>= Vec::with_capacity(n);
-            for j in 0..n {
-                let incoming_edge: Rc<RefCell<Edge>> = input_nodes[j].borrow().outgoing[i].clone();
-                incoming_edges.push(incoming_edge);
+            for edges in input_edges.iter() {
+                incoming_edges.push(edges[i].clone());
             }
 
-            // Outgoing edge
-            let outgoing_edge = Rc::new(RefCell::new(Edge::standard(i, 0, 2)));
+            // Outgoing edges
+            let mut outgoing_edges: Vec<Rc<RefCell<Edge>>> = Vec::with_capacity(k);
+            for j in 0..k {
+                outgoing_edges.push(Rc::new(RefCell::new(Edge::with_activation(i, j, Basis::BSpline.sample_grid(degree, grid_size, Init::default(), 1, rng), 2))));
+            }
 
             // Node
-            let node = Rc::new(RefCell::new(Node::new(incoming_edges, vec![outgoing_edge], 1)));
+            let node = Rc::new(RefCell::new(Node::new(incoming_edges, outgoing_edges, 1)));
             hidden_nodes.push(node);
         }
 
         let hidden_layer = Rc::new(RefCell::new(Layer::new(hidden_nodes)));
 
-        // Output node
-        let mut incoming_edges: Vec<Rc<RefCell<Edge>>> = Vec::with_capacity(m);
-        for i in 0..m {
-            let hidden_node: Rc<RefCell<Node>> = hidden_layer.borrow().nodes[i].clone();
-            let outgoing_edge: Rc<RefCell<Edge>> = hidden_node.borrow().outgoing[0].clone();
-            incoming_edges.push(outgoing_edge);
+        // Output nodes
+        let mut output_nodes: Vec<Rc<RefCell<Node>>> = Vec::with_capacity(k);
+        for j in 0..k {
+            let mut incoming_edges: Vec<Rc<RefCell<Edge>>> = Vec::with_capacity(m);
+            for i in 0..m {
+                let hidden_node: Rc<RefCell<Node>> = hidden_layer.borrow().nodes[i].clone();
+                let outgoing_edge: Rc<RefCell<Edge>> = hidden_node.borrow().outgoing[j].clone();
+                incoming_edges.push(outgoing_edge);
+            }
+            output_nodes.push(Rc::new(RefCell::new(Node::new(incoming_edges, Vec::new(), 2))));
         }
-        let output_node = Rc::new(RefCell::new(Node::new(incoming_edges, Vec::new(), 2)));
         
-        let output_layer = Rc::new(RefCell::new(Layer::new(vec![output_node])));
+        let output_layer = Rc::new(RefCell::new(Layer::new(output_nodes)));
 
         layers.push(hidden_layer);
         layers.push(output_layer);
@@ -114,6 +271,7 @@ impl KAN {
     }
 
     /// Add a layer to the KAN.
+    /// If it is the first layer, the number of inputs is inferred from its edges as in `new`.
     /// 
     /// # Arguments
     /// 
@@ -127,219 +285,2154 @@ impl KAN {
     /// ```
     pub fn add_layer(&mut self, layer: Layer) {
         self.layers.push(Rc::new(RefCell::new(layer)));
+        if self.layers.len() == 1 {
+            self.inputs = input_width(&self.layers);
+        }
     }
 
-    /// The forward pass computes the value of the KAN given the input values.
+    /// Check the graph of the KAN for structural errors, such as edges with indices out of range or edges missing from the outgoing edges of their start, which hand-built graphs can have.
+    /// See `topology::validate` for the checks.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the graph is valid, or every structural error found.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// if let Err(errors) = kan.validate() {
+    ///     for error in errors.iter() {
+    ///         println!("{}", error);
+    ///     }
+    /// }
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<TopologyError>> {
+        let errors: Vec<TopologyError> = topology::validate(self);
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Whether the KAN has the same graph as another KAN, e.g. one loaded from a checkpoint.
+    /// See `topology::same_topology` for what is compared.
     /// 
     /// # Arguments
     /// 
-    /// * `input` - A matrix where the entry (i, j) is the input to the j-th incoming edge for the i-th node in the first layer.
+    /// * `other` - The other KAN.
     /// 
     /// # Returns
     /// 
-    /// * A scalar representing the value of the KAN given the input values.
+    /// * Whether the KANs have the same layers, nodes, and edges, regardless of the values of their parameters.
     /// 
     /// # Example
     /// 
     /// ```
-    /// let input = Matrix::new(1, 2, vec![1.0, 2.0]);
+    /// assert!(kan.same_topology(&kan.deep_clone()));
+    /// ```
+    pub fn same_topology(&self, other: &KAN) -> bool {
+        topology::same_topology(self, other)
+    }
+
+    /// Whether the KAN equals another KAN up to a tolerance on their parameters, e.g. to check that saving and loading preserves a model.
+    /// See `topology::approx_eq` for what is compared.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - The other KAN.
+    /// 
+    /// * `tol` - The largest absolute difference between two parameters that counts as equal.
+    /// 
+    /// # Returns
+    /// 
+    /// * Whether the KANs have the same topology and parameters that differ by at most `tol`.
+    /// 
+    /// # Example
     /// 
-    /// let output = kan.forward(input);
     /// ```
-    pub fn forward(&self, input: Matrix) -> f64 {
-        let mut output: Matrix = input.clone();
-        for (i, layer) in self.layers.iter().enumerate() {
-            println!("Layer {}", i);
-            let layer: Ref<Layer> = layer.borrow();
-            println!("Output: {:?}", output);
-            output = layer.forward(output);
-        }
-        output[0][0] // Return the scalar value of the output matrix.
+    /// kan.save_json("kan.json").unwrap();
+    /// assert!(kan.approx_eq(&KAN::load_json("kan.json").unwrap(), 1e-12));
+    /// ```
+    pub fn approx_eq(&self, other: &KAN, tol: f64) -> bool {
+        topology::approx_eq(self, other, tol)
     }
 
-    /// The backward pass computes the gradient of the loss with respect to the input values.
-    /// It uses mean squared error as the loss function.
+    /// The forward pass computes the value of the KAN given the input values.
+    /// The i-th input value is routed to every incoming edge of the first layer that starts at i, and scaled by the scaler if there is one.
     /// 
     /// # Arguments
     /// 
-    /// * `input` - A matrix where the entry (i, j) is the input to the jth incoming edge for the ith node in the first layer.
-    /// * `target` - A scalar representing the target value.
+    /// * `input` - A vector with one value per input of the KAN.
     /// 
     /// # Returns
     /// 
-    /// * A result indicating whether the backward pass was successful.
+    /// * A scalar representing the value of the first output node, or an error if the number of input values does not match the number of inputs.
     /// 
     /// # Example
     /// 
     /// ```
-    /// let input = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
-    /// let target = 0.5;
+    /// let input = Vector::new(vec![1.0, 2.0]);
     /// 
-    /// let result = kan.backward(input, target);
+    /// let output = kan.forward(&input).unwrap();
     /// ```
-    pub fn backward(&self, input: Matrix, target: f64) -> Result<(), &'static str> {
-        // Forward pass and save intermediate values
-        let mut layer_outputs: Vec<Matrix> = Vec::new();
-        let mut current_output: Matrix = input.clone();
-        for layer in self.layers.iter() {
-            let layer: Ref<Layer> = layer.borrow();
-            current_output = layer.forward(current_output.clone());
-            layer_outputs.push(current_output.clone());
-        }
-
-        // Calculate initial error gradient (using mean squared error)
-        let final_output: f64 = layer_outputs.last().unwrap()[0][0];
-        let mut upstream_gradient: Vector = Vector::new(vec![2.0 * (final_output - target)]);
-
-        // Backward pass
-        for (i, layer) in self.layers.iter().rev().enumerate() {
-            let layer: RefMut<Layer> = layer.borrow_mut();
-
-            // If it is not the first layer, use the output of the previous layer as input
-            let layer_input: Matrix = if i > 0 {
-                layer_outputs[i - 1].clone()
-            } else {
-                input.clone()
-            };
-
-            layer.backward(layer_input, &upstream_gradient).unwrap();
-
-            // Update the error gradient for the next layer
-            upstream_gradient = Vector::new(vec![upstream_gradient.elements.iter().fold(0.0, |acc, &x| acc + x); layer.nodes.len()]);
-            
-        }
+    pub fn forward(&self, input: &Vector) -> Result<f64, KanError> {
+        Ok(self.forward_logits(input)?[0]) // Return the value of the first output node.
+    }
 
-        Ok(())
+    /// The forward pass computes the values of all output nodes of the KAN given the values of its input features.
+    /// The i-th feature is routed to every incoming edge of the first layer, and every skip edge of the second layer, that starts at i, so the input matrix of the first layer, with the value of every edge, is never built by hand.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `features` - A vector with one value per input of the KAN.
+    /// 
+    /// # Returns
+    /// 
+    /// * A vector where the i-th entry is the value of the i-th node in the last layer, or an error if the number of features does not match the number of inputs.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let features = Vector::new(vec![1.0, 2.0]);
+    /// 
+    /// let outputs = kan.forward_features(&features).unwrap();
+    /// ```
+    pub fn forward_features(&self, features: &Vector) -> Result<Vector, KanError> {
+        let (_, activations) = self.layer_inputs(self.feature_matrix(features)?)?;
+        Ok(activations)
     }
 
-    /// Update the activation functions of the edges in the KAN.
+    /// The forward pass computes the values of all output nodes of the KAN given the input values, like `forward_features`.
+    /// For a classifier these are the logits of the classes.
     /// 
     /// # Arguments
     /// 
-    /// * `learning_rate` - A scalar representing the learning rate.
+    /// * `input` - A vector with one value per input of the KAN.
+    /// 
+    /// # Returns
+    /// 
+    /// * A vector where the i-th entry is the value of the i-th node in the last layer, or an error if the number of input values does not match the number of inputs.
     /// 
     /// # Example
     /// 
     /// ```
-    /// let learning_rate = 0.01;
+    /// let input = Vector::new(vec![1.0, 2.0]);
     /// 
-    /// kan.update_edges(learning_rate);
+    /// let logits = kan.forward_logits(&input).unwrap();
     /// ```
-    pub fn update_edges(&self, learning_rate: f64) {
-        if learning_rate <= 0.0 {
-            panic!("Learning rate must be positive.");
-        }
-        for layer in self.layers.iter() {
-            let layer: RefMut<Layer> = layer.borrow_mut();
-            layer.update_weights(learning_rate).unwrap();
-        }
+    pub fn forward_logits(&self, input: &Vector) -> Result<Vector, KanError> {
+        self.forward_features(input)
     }
 
-    /// Calculate the loss of the KAN given the input values and target value.
+    /// The forward pass computes the values of all output nodes of the KAN given the input matrix of the first layer, as built by `Layer::route`.
+    /// Unlike `forward_features`, the input values are not routed to the edges, so every edge of the first layer can receive a different value.
     /// 
     /// # Arguments
     /// 
-    /// * `input` - A vector representing the input values to the first layer.
+    /// * `input` - A matrix where the entry (i, j) is the input to the j-th incoming edge for the i-th node in the first layer.
     /// 
-    /// * `target` - A scalar representing the target value.
+    /// # Returns
+    /// 
+    /// * A vector where the i-th entry is the value of the i-th node in the last layer, or an error if the shape of the input does not match the first layer.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let input = kan.layers[0].borrow().route(&Vector::new(vec![1.0, 2.0]));
+    /// 
+    /// let logits = kan.forward_matrix(input).unwrap();
+    /// ```
+    #[deprecated(note = "Use `forward_features`, which routes the input values to the edges of the first layer.")]
+    pub fn forward_matrix(&self, input: Matrix) -> Result<Vector, KanError> {
+        let (_, activations) = self.layer_inputs(input)?;
+        Ok(activations)
+    }
+
+    /// Computes the class probabilities of the KAN given the input values by applying the softmax to the output logits.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A vector with one value per input of the KAN.
     /// 
     /// # Returns
     /// 
-    /// * A scalar representing the loss of the KAN given the input values and target value, calculated using mean squared error.
+    /// * A vector where the i-th entry is the probability of the i-th class, or an error if the number of input values does not match the number of inputs.
     /// 
     /// # Example
     /// 
     /// ```
     /// let input = Vector::new(vec![1.0, 2.0]);
-    /// let target = 0.5;
     /// 
-    /// let loss = kan.loss_single(input, target);
+    /// let probabilities = kan.predict_proba(&input).unwrap();
     /// ```
-    pub fn loss_single(&self, input: Vector, target: f64) -> f64 {
-        let input_matrix: Matrix = Matrix::new(vec![input]);
-        let output: f64 = self.forward(input_matrix);
-        (output - target).powi(2)
+    pub fn predict_proba(&self, input: &Vector) -> Result<Vector, KanError> {
+        Ok(softmax(&self.forward_logits(input)?))
     }
 
-    /// Calculate the loss of the KAN given a list of input-target pairs.
+    /// The batched forward pass computes the values of all output nodes of the KAN for many samples at once.
+    /// The samples are evaluated by a `CompiledKAN` without touching the caches of the edges, so with the `parallel` feature they are spread over the threads of rayon.
     /// 
     /// # Arguments
     /// 
-    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
-    /// * `targets` - A vector where the ith element represents the target value for the ith input-target pair.
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith sample.
     /// 
     /// # Returns
     /// 
-    /// * A scalar representing the loss of the KAN given the input values and target values, calculated using mean squared error.
+    /// * A result with a matrix where the ith row holds the values of the nodes in the last layer for the ith sample, or an error if a row does not have one value per input.
     /// 
     /// # Example
     /// 
     /// ```
     /// let inputs = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
-    /// let targets = Vector::new(vec![0.5, 0.75]);
     /// 
-    /// let loss = kan.loss(inputs, targets);
+    /// let outputs = kan.forward_batch(&inputs).unwrap();
     /// ```
-    pub fn loss(&self, inputs: Matrix, targets: Vector) -> f64 {
-        let mut loss: f64 = 0.0;
-        for (i, row) in inputs.rows.iter().enumerate() {
-            loss += self.loss_single(row.clone(), targets[i]);
+    pub fn forward_batch(&self, inputs: &Matrix) -> Result<Matrix, KanError> {
+        if inputs.rows > 0 && inputs.cols != self.inputs {
+            return Err(KanError::mismatch("input values, one per input of the KAN", self.inputs, inputs.cols));
         }
-        loss/(inputs.rows.len() as f64)
+        self.compile().forward_batch(inputs)
     }
 
-    /// Train the KAN on one input-target pair.
+    /// Predict the value of the first output node for every sample of a dataset, one sample after the other.
     /// 
     /// # Arguments
     /// 
-    /// * `input` - A vector representing the input values to the first layer.
-    /// * `target` - A scalar representing the target value.
-    /// * `learning_rate` - A scalar representing the learning rate.
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith sample.
     /// 
     /// # Returns
     /// 
-    /// * A result indicating whether the training was successful, with the loss of the KAN given the input values and target value.
+    /// * A result with a vector where the ith element is the prediction for the ith sample, or an error if a row does not have one value per input.
     /// 
     /// # Example
     /// 
     /// ```
-    /// let input = Vector::new(vec![1.0, 2.0]);
-    /// let target = 0.5;
-    /// let learning_rate = 0.01;
+    /// let inputs = Matrix::new(vec![Vector::new(vec![0.1, 0.2]), Vector::new(vec![0.3, 0.4])]);
     /// 
-    /// let result = kan.train(input, target, learning_rate).unwrap();
+    /// let predictions = kan.predict(&inputs).unwrap();
     /// ```
-    pub fn train(&self, input: Vector, target: f64, learning_rate: f64) -> Result<f64, &'static str> {
-        let input_matrix: Matrix = Matrix::new(vec![input.clone()]);
-        self.backward(input_matrix, target)?;
-        self.update_edges(learning_rate);
-        Ok(self.loss_single(input, target))
+    pub fn predict(&self, inputs: &Matrix) -> Result<Vector, KanError> {
+        let predictions: Vec<f64> = (0..inputs.rows).map(|i| self.forward(&inputs.row(i))).collect::<Result<Vec<f64>, KanError>>()?;
+        Ok(Vector::new(predictions))
     }
 
-    /// Train the KAN on a list of input-target pairs.
+    /// Predict the value of the first output node for every sample of a dataset like `predict`, evaluating the samples with `forward_batch`.
+    /// With the `parallel` feature the samples are spread over the threads of rayon, which pays off for large datasets.
     /// 
     /// # Arguments
     /// 
-    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
-    /// * `targets` - A vector where the ith element represents the target value for the ith input-target pair.
-    /// * `learning_rate` - A scalar representing the learning rate.
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith sample.
     /// 
     /// # Returns
     /// 
-    /// * A result indicating whether the training was successful, with the loss of the KAN given the input values and target values.
+    /// * A result with a vector where the ith element is the prediction for the ith sample, or an error as in `forward_batch`.
     /// 
     /// # Example
     /// 
     /// ```
-    /// let inputs = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
-    /// let targets = Vector::new(vec![0.5, 0.75]);
-    /// let learning_rate = 0.01;
+    /// let predictions = kan.predict_batch(&dataset.inputs).unwrap();
+    /// ```
+    pub fn predict_batch(&self, inputs: &Matrix) -> Result<Vector, KanError> {
+        Ok(self.forward_batch(inputs)?.get_col(0))
+    }
+
+    /// Compile the KAN into a frozen copy for inference, which can be shared between threads.
+    /// Later changes to the KAN, e.g. by training, do not affect the copy.
+    /// The weights of every edge with a dropout probability p are scaled by 1 - p, so the copy gives the values of the KAN outside of training steps.
+    /// 
+    /// # Returns
+    /// 
+    /// * A `CompiledKAN` with copies of the edges of every node in every layer.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let compiled: CompiledKAN = kan.compile();
+    /// let output = compiled.forward(&Vector::new(vec![1.0, 2.0])).unwrap();
+    /// ```
+    pub fn compile(&self) -> CompiledKAN {
+        // The weights are scaled by the keep probability, which is how dropout is applied at inference
+        self.compile_with(|edge| 1.0 - edge.dropout)
+    }
+
+    /// Compile the KAN with the weights of every edge scaled by a factor and without dropout, e.g. by the keep probability for inference or by the dropout mask of the current training step.
+    fn compile_with(&self, scale: impl Fn(&Edge) -> f64) -> CompiledKAN {
+        CompiledKAN {
+            scaler: self.scaler.clone(),
+            layers: self.layers.iter().map(|layer| {
+                layer.borrow().nodes.iter().map(|node| {
+                    node.borrow().incoming.iter().map(|edge| {
+                        let mut edge: Edge = edge.borrow().clone();
+                        let keep: f64 = scale(&edge);
+                        edge.base_weight *= keep;
+                        edge.spline_weight *= keep;
+                        edge.dropout = 0.0;
+                        edge.masked = None;
+                        edge
+                    }).collect()
+                }).collect()
+            }).collect(),
+            aggregations: self.layers.iter().map(|layer| layer.borrow().nodes.iter().map(|node| node.borrow().aggregation).collect()).collect(),
+            norms: self.layers.iter().map(|layer| layer.borrow().norm.clone()).collect(),
+            luts: Vec::new(),
+        }
+    }
+
+    /// Quantize the KAN for inference on small devices after training, see `QuantizedKAN`.
+    /// The range of the inputs of every edge is calibrated on a set of samples, and the edge is resampled at equally spaced inputs over that range and stored as 8 or 16 bit integers.
+    /// As in `compile`, the weights of every edge with a dropout probability p are scaled by 1 - p.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix with at least one row, where the ith row represents the input values to the first layer for the ith calibration sample.
+    /// 
+    /// * `resolution` - The number of entries of the table of every edge, at least 2.
+    /// 
+    /// * `precision` - The integer type of the entries.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a `QuantizedKAN` with a table for every edge, or an error if there are no calibration samples, the resolution is smaller than 2, or an edge receives a value that is not finite.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let quantized = kan.quantize(&train_inputs, 256, Precision::Int8).unwrap();
+    /// let error = quantized.compare(&kan.compile(), &test_inputs);
+    /// ```
+    pub fn quantize(&self, inputs: &Matrix, resolution: usize, precision: Precision) -> Result<QuantizedKAN, KanError> {
+        if inputs.rows == 0 {
+            return Err(KanError::InvalidConfig("The quantization requires at least one calibration sample."));
+        }
+        if resolution < 2 {
+            return Err(KanError::InvalidConfig("The table of a quantized edge must have at least 2 entries."));
+        }
+        // The smallest and largest input of every edge, in the order of `edges`
+        let mut ranges: Vec<(f64, f64)> = Vec::new();
+        for i in 0..inputs.rows {
+            let values: Vec<f64> = self.edge_inputs(self.feature_matrix(&inputs.row(i))?)?;
+            ranges.resize(values.len(), (f64::INFINITY, f64::NEG_INFINITY));
+            for (range, &value) in ranges.iter_mut().zip(values.iter()) {
+                if !value.is_finite() {
+                    return Err(KanError::DomainError("The calibration samples must give a finite input to every edge."));
+                }
+                *range = (range.0.min(value), range.1.max(value));
+            }
+        }
+        let compiled: CompiledKAN = self.compile();
+        let mut ranges = ranges.into_iter();
+        Ok(QuantizedKAN {
+            layers: compiled.layers.iter().map(|layer| {
+                layer.iter().map(|node| {
+                    node.iter().map(|edge| {
+                        let (min, max): (f64, f64) = ranges.next().unwrap();
+                        QuantizedEdge::new(edge, min, max, resolution, precision)
+                    }).collect()
+                }).collect()
+            }).collect(),
+            aggregations: compiled.aggregations,
+            norms: compiled.norms,
+            scaler: compiled.scaler,
+            precision,
+        })
+    }
+
+    /// Round the KAN to single precision for training and inference in `f32`, see `SingleKAN`.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a `SingleKAN` with the layers and edges of the KAN, or an error if the KAN has a scaler, normalizations, product nodes, skip edges, or edges that are not polynomial B-splines, or edges with dropout or ties.
+    /// 
+    /// # Example
     /// 
-    /// let result = kan.train_batch(inputs, targets, learning_rate).unwrap();
     /// ```
-    pub fn train_batch(&self, inputs: Matrix, targets: Vector, learning_rate: f64) -> Result<f64, &'static str> {
-        self.backward(inputs.clone(), targets[0])?;
-        self.update_edges(learning_rate);
-        Ok(self.loss(inputs, targets))
+    /// let mut single = kan.to_single().unwrap();
+    /// single.train_batch(&inputs, &targets, 0.05, Loss::MeanSquaredError).unwrap();
+    /// let kan = single.to_kan().unwrap();
+    /// ```
+    pub fn to_single(&self) -> Result<SingleKAN, KanError> {
+        SingleKAN::from_kan(self)
     }
-}
\ No newline at end of file
+
+    /// The backward pass computes the gradient of the loss with respect to the input values.
+    /// It uses mean squared error as the loss function.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A matrix where the entry (i, j) is the input to the jth incoming edge for the ith node in the first layer.
+    /// * `target` - A scalar representing the target value.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the backward pass was successful.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let input = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
+    /// let target = 0.5;
+    /// 
+    /// let result = kan.backward(input, target);
+    /// ```
+    #[deprecated(note = "Use `backward_features` with `Loss::MeanSquaredError`, which routes the input values to the edges of the first layer.")]
+    pub fn backward(&self, input: Matrix, target: f64) -> Result<(), KanError> {
+        self.backward_routed(input, &Vector::new(vec![target]), Loss::MeanSquaredError)?;
+        Ok(())
+    }
+
+    /// The backward pass computes the gradient of a given loss with respect to the input values.
+    /// The gradient is seeded with the derivative of the loss with respect to the output nodes, e.g. softmax minus one-hot target for cross-entropy.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A matrix where the entry (i, j) is the input to the jth incoming edge for the ith node in the first layer.
+    /// * `target` - A vector of target values for the output nodes (a one-hot vector for cross-entropy).
+    /// * `loss` - The loss function.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the backward pass was successful, with the loss of the KAN at the forward pass.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let input = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
+    /// let target = one_hot(2, 3).unwrap();
+    /// 
+    /// let result = kan.backward_loss(input, &target, Loss::CrossEntropy);
+    /// ```
+    #[deprecated(note = "Use `backward_features`, which routes the input values to the edges of the first layer.")]
+    pub fn backward_loss(&self, input: Matrix, target: &Vector, loss: Loss) -> Result<f64, KanError> {
+        self.backward_routed(input, target, loss)
+    }
+
+    /// The backward pass computes the gradient of a given loss with respect to the weights of every edge, given the values of the input features.
+    /// The features are routed to the edges of the first layer as in `forward_features`, and the gradient is seeded with the derivative of the loss with respect to the output nodes, e.g. softmax minus one-hot target for cross-entropy.
+    /// Each call replaces the gradients stored in the edges with those of this sample, so the gradients of several samples are not accumulated.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `features` - A vector with one value per input of the KAN.
+    /// * `target` - A vector of target values for the output nodes (a one-hot vector for cross-entropy).
+    /// * `loss` - The loss function.
+    /// 
+    /// # Returns
+    /// 
+    /// * The loss of the KAN at the forward pass, or an error if the number of features does not match the number of inputs or the number of targets does not match the number of outputs.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let features = Vector::new(vec![1.0, 2.0]);
+    /// 
+    /// let loss = kan.backward_features(&features, &one_hot(2, 3), Loss::CrossEntropy).unwrap();
+    /// ```
+    pub fn backward_features(&self, features: &Vector, target: &Vector, loss: Loss) -> Result<f64, KanError> {
+        self.backward_routed(self.feature_matrix(features)?, target, loss)
+    }
+
+    /// The backward pass given the input matrix of the first layer, see `backward_features`.
+    fn backward_routed(&self, input: Matrix, target: &Vector, loss: Loss) -> Result<f64, KanError> {
+        // Forward pass and save the inputs and node values of each layer
+        let (layer_inputs, values) = self.trace(input)?;
+        let activations: Vector = match self.layers.last() {
+            Some(layer) => layer.borrow().normalize(values[values.len() - 1].clone()),
+            None => Vector::new(vec![]),
+        };
+
+        if activations.len() != target.len() {
+            return Err(KanError::mismatch("target values, one per output node", activations.len(), target.len()));
+        }
+
+        #[cfg(feature = "instrument")]
+        let _span = crate::instrument::span(crate::instrument::Phase::Backward);
+
+        // Seed the error gradient with the derivative of the loss
+        let mut upstream_gradient: Vector = loss.gradient(&activations, target)?;
+
+        // The gradients that reach the values of every layer through the skip edges two layers further on
+        let widths: Vec<usize> = self.layers.iter().map(|layer| layer.borrow().nodes.len()).collect();
+        let mut skip_gradients: Vec<Vector> = widths.iter().map(|&width| Vector::zeros(width)).collect();
+
+        // Backward pass
+        for i in (0..self.layers.len()).rev() {
+            upstream_gradient += &skip_gradients[i];
+            // Only layers with a normalization are borrowed mutably, so callers may hold other borrows of the layers
+            if self.layers[i].borrow().norm.is_some() {
+                if let Some(norm) = &mut self.layers[i].borrow_mut().norm {
+                    upstream_gradient = norm.backward(&values[i], &upstream_gradient);
+                }
+            }
+            let layer: Ref<Layer> = self.layers[i].borrow();
+            layer.backward(layer_inputs[i].clone(), &upstream_gradient).map_err(|err| err.at_layer(i))?;
+
+            // The skip edges of the second layer start at the inputs, which have no gradient
+            if i > 1 {
+                skip_gradients[i - 2] = layer.propagate_skip(&layer_inputs[i], &upstream_gradient, widths[i - 2]).map_err(|err| err.at_layer(i))?;
+            }
+
+            // Update the error gradient for the previous layer
+            if i > 0 {
+                upstream_gradient = layer.propagate_through(&layer_inputs[i], &upstream_gradient, widths[i - 1]).map_err(|err| err.at_layer(i))?;
+            }
+        }
+
+        loss.compute(&activations, target)
+    }
+
+    /// Calculate the loss of the KAN given the input values and target value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A vector representing the input values to the first layer.
+    /// 
+    /// * `target` - A scalar representing the target value.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a scalar representing the loss of the KAN given the input values and target value, calculated using mean squared error, or an error if the input does not have one value per input of the KAN.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let input = Vector::new(vec![1.0, 2.0]);
+    /// let target = 0.5;
+    /// 
+    /// let loss = kan.loss_single(input, target).unwrap();
+    /// ```
+    pub fn loss_single(&self, input: Vector, target: f64) -> Result<f64, KanError> {
+        self.loss_single_with(input, &Vector::new(vec![target]), Loss::MeanSquaredError)
+    }
+
+    /// Calculate the loss of the KAN given the input values and target values using a given loss function.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A vector representing the input values to the first layer, where the i-th entry is routed to every incoming edge with start index i.
+    /// 
+    /// * `target` - A vector of target values for the output nodes (a one-hot vector for cross-entropy).
+    /// 
+    /// * `loss` - The loss function.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a scalar representing the loss of the KAN given the input values and target values, or an error if the input does not have one value per input of the KAN.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let input = Vector::new(vec![1.0, 2.0]);
+    /// let target = one_hot(1, 3).unwrap();
+    /// 
+    /// let loss = kan.loss_single_with(input, &target, Loss::CrossEntropy).unwrap();
+    /// ```
+    pub fn loss_single_with(&self, input: Vector, target: &Vector, loss: Loss) -> Result<f64, KanError> {
+        let output: Vector = self.forward_logits(&input)?;
+        loss.compute(&output, target)
+    }
+
+    /// Calculate the loss of the KAN given a list of input-target pairs.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    /// * `targets` - A vector where the ith element represents the target value for the ith input-target pair.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a scalar representing the loss of the KAN given the input values and target values, calculated using mean squared error, or an error if the numbers of inputs and targets differ or a row does not have one value per input.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let inputs = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
+    /// let targets = Vector::new(vec![0.5, 0.75]);
+    /// 
+    /// let loss = kan.loss(inputs, targets).unwrap();
+    /// ```
+    pub fn loss(&self, inputs: Matrix, targets: Vector) -> Result<f64, KanError> {
+        let targets: Matrix = Matrix::new((0..targets.len()).map(|i| Vector::new(vec![targets[i]])).collect());
+        // Compensated summation keeps the mean accurate for large datasets
+        let losses: Vector = self.sample_losses(&inputs, &targets, Loss::MeanSquaredError)?;
+        Ok(losses.sum_stable()/(inputs.rows as f64))
+    }
+
+    /// Calculate the mean loss of the KAN given a list of input-target pairs using a given loss function.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    /// * `loss` - The loss function.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a scalar representing the mean loss of the KAN given the input values and target values, or an error as in `loss_reduced`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let inputs = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
+    /// let targets = Matrix::new(vec![one_hot(0, 2).unwrap(), one_hot(1, 2).unwrap()]);
+    /// 
+    /// let loss = kan.loss_with(inputs, targets, Loss::CrossEntropy).unwrap();
+    /// ```
+    pub fn loss_with(&self, inputs: Matrix, targets: Matrix, loss: Loss) -> Result<f64, KanError> {
+        Ok(self.loss_reduced(inputs, targets, loss, Reduction::Mean)?[0])
+    }
+
+    /// Calculate the loss of the KAN given a list of input-target pairs using a given loss function and reduction.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    /// * `loss` - The loss function.
+    /// * `reduction` - How the per-sample losses are combined.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a vector with the mean or sum of the losses, or the loss of every input-target pair for `Reduction::None`, or an error if the numbers of inputs and targets differ or a row does not have one value per input.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let inputs = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
+    /// let targets = Matrix::new(vec![Vector::new(vec![0.5]), Vector::new(vec![0.75])]);
+    /// 
+    /// let losses = kan.loss_reduced(inputs, targets, Loss::MeanSquaredError, Reduction::None).unwrap();
+    /// ```
+    pub fn loss_reduced(&self, inputs: Matrix, targets: Matrix, loss: Loss, reduction: Reduction) -> Result<Vector, KanError> {
+        let losses: Vector = self.sample_losses(&inputs, &targets, loss)?;
+        reduction.reduce(losses)
+    }
+
+    /// The loss of every input-target pair, evaluated on the layers of the KAN in its current mode, so that the dropout masks of a training step apply, unlike in `forward_batch`.
+    /// With the `parallel` feature the samples are evaluated by a copy compiled with the current masks and spread over the threads of rayon.
+    fn sample_losses(&self, inputs: &Matrix, targets: &Matrix, loss: Loss) -> Result<Vector, KanError> {
+        if inputs.rows != targets.rows {
+            return Err(KanError::mismatch("targets, one per input", inputs.rows, targets.rows));
+        }
+        if inputs.rows > 0 && inputs.cols != self.inputs {
+            return Err(KanError::mismatch("input values, one per input of the KAN", self.inputs, inputs.cols));
+        }
+        #[cfg(feature = "parallel")]
+        let losses: Vec<f64> = {
+            let compiled: CompiledKAN = self.compile_with(Edge::dropout_scale);
+            (0..inputs.rows).into_par_iter().map(|i| loss.compute(&compiled.forward(&inputs.row(i))?, &targets.row(i))).collect::<Result<Vec<f64>, KanError>>()?
+        };
+        #[cfg(not(feature = "parallel"))]
+        let losses: Vec<f64> = (0..inputs.rows).map(|i| loss.compute(&self.forward_features(&inputs.row(i))?, &targets.row(i))).collect::<Result<Vec<f64>, KanError>>()?;
+        Ok(Vector::new(losses))
+    }
+
+    /// Calculate the weighted mean loss of the KAN given a list of input-target pairs and a weight per pair, e.g. to balance the classes of an imbalanced dataset or to correct for importance sampling.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    /// * `weights` - A vector of non-negative weights with a positive sum, where the ith element is the weight of the ith input-target pair.
+    /// * `loss` - The loss function.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a scalar representing the sum of the weighted losses divided by the sum of the weights, which is the mean loss for equal weights, or an error if there is not one valid weight per input-target pair or as in `loss_reduced`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let inputs = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
+    /// let targets = Matrix::new(vec![Vector::new(vec![0.5]), Vector::new(vec![0.75])]);
+    /// 
+    /// let loss = kan.loss_weighted(inputs, targets, &Vector::new(vec![1.0, 3.0]), Loss::MeanSquaredError).unwrap();
+    /// ```
+    pub fn loss_weighted(&self, inputs: Matrix, targets: Matrix, weights: &Vector, loss: Loss) -> Result<f64, KanError> {
+        if weights.len() != inputs.rows {
+            return Err(KanError::mismatch("weights, one per input", inputs.rows, weights.len()));
+        }
+        if weights.iter().any(|&weight| !(weight >= 0.0 && weight.is_finite())) || weights.sum_stable() <= 0.0 {
+            return Err(KanError::DomainError("The weights must be finite and non-negative with a positive sum."));
+        }
+        let losses: Vector = self.loss_reduced(inputs, targets, loss, Reduction::None)?;
+        Ok(Vector::new(losses.iter().zip(weights.iter()).map(|(loss, weight)| loss * weight).collect()).sum_stable() / weights.sum_stable())
+    }
+
+    /// Predict the class of the given input values as the index of the largest output logit.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A vector representing the input values to the first layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with the index of the predicted class, or an error if the input does not have one value per input of the KAN.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let input = Vector::new(vec![1.0, 2.0]);
+    /// 
+    /// let class = kan.predict_class(input).unwrap();
+    /// ```
+    pub fn predict_class(&self, input: Vector) -> Result<usize, KanError> {
+        Ok(self.forward_logits(&input)?.argmax())
+    }
+
+    /// Compute the gradient of the output with respect to the input values, the saliency of every input at a sample.
+    /// The derivative of every edge is analytic, so the gradient is exact up to rounding; for a KAN with several output nodes it is the gradient of the sum of the outputs.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A vector representing the input values to the first layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a vector where the i-th entry is the partial derivative of the output with respect to the i-th input value, or an error if the input does not have one value per input of the KAN.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let gradients = kan.input_gradients(&Vector::new(vec![0.3, 0.6])).unwrap();
+    /// ```
+    pub fn input_gradients(&self, input: &Vector) -> Result<Vector, KanError> {
+        let jacobian: Matrix = self.input_jacobian(input)?;
+        Ok(Vector::new((0..jacobian.cols).map(|j| (0..jacobian.rows).map(|k| jacobian[k][j]).sum()).collect()))
+    }
+
+    /// Rank the inputs by how much they matter, as the mean absolute partial derivative of the outputs with respect to every input over a dataset.
+    /// For a KAN with several output nodes the absolute partial derivatives of the outputs are summed, so opposite effects on different outputs do not cancel.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix with at least one row, where the ith row represents the input values to the first layer for the ith sample.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a vector where the i-th entry is the importance of the i-th input, 0 for an input the outputs do not depend on, or an error if there are no samples or a row does not have one value per input.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let importance = kan.feature_importance(&dataset.inputs).unwrap();
+    /// let most_important = importance.argmax();
+    /// ```
+    pub fn feature_importance(&self, inputs: &Matrix) -> Result<Vector, KanError> {
+        if inputs.rows == 0 {
+            return Err(KanError::InvalidConfig("The importance of the inputs requires at least one sample."));
+        }
+        let mut importance: Vector = Vector::zeros(inputs.cols);
+        for i in 0..inputs.rows {
+            let jacobian: Matrix = self.input_jacobian(&inputs.row(i))?;
+            for j in 0..inputs.cols {
+                importance[j] += (0..jacobian.rows).map(|k| jacobian[k][j].abs()).sum::<f64>();
+            }
+        }
+        Ok(&importance * (1.0 / inputs.rows as f64))
+    }
+
+    /// The matrix of partial derivatives of the output nodes (rows) with respect to the input values (columns), by the chain rule through the analytic derivative of every edge.
+    fn input_jacobian(&self, input: &Vector) -> Result<Matrix, KanError> {
+        let (layer_inputs, values) = self.trace(self.feature_matrix(input)?)?;
+        let activations: Vector = self.layers[self.layers.len() - 1].borrow().normalize(values[values.len() - 1].clone());
+        let mut jacobian: Matrix = Matrix::zeros(activations.len(), input.len());
+        // The number of values passed on by every level, the inputs followed by the layers
+        let widths: Vec<usize> = std::iter::once(input.len()).chain(self.layers.iter().map(|layer| layer.borrow().nodes.len())).collect();
+        for k in 0..activations.len() {
+            // Gradient of the kth output with respect to the values of every level, where skip edges feed into the level two layers back
+            let mut gradients: Vec<Vector> = widths.iter().map(|&width| Vector::zeros(width)).collect();
+            gradients[self.layers.len()][k] = 1.0;
+            for l in (0..self.layers.len()).rev() {
+                let mut gradient: Vector = gradients[l + 1].clone();
+                if let Some(norm) = &self.layers[l].borrow().norm {
+                    gradient = norm.input_gradient(&values[l], &gradient);
+                }
+                for (i, node) in self.layers[l].borrow().nodes.iter().enumerate() {
+                    let node: Ref<Node> = node.borrow();
+                    let edge_gradients: Vector = node.edge_gradients(&self.layers[l].borrow().node_input(&layer_inputs[l], i)?, gradient[i]);
+                    for (j, edge) in node.incoming.iter().enumerate() {
+                        let edge: Ref<Edge> = edge.borrow();
+                        let level: usize = if edge.skip { l - 1 } else { l };
+                        gradients[level][edge.start] += edge_gradients[j] * edge.input_gradient(layer_inputs[l][i][j]);
+                    }
+                }
+            }
+            let mut gradient: Vector = gradients.swap_remove(0);
+            // The scaler divides every feature by its scale before the first layer
+            if let Some(scaler) = &self.scaler {
+                gradient = Vector::new(gradient.iter().enumerate().map(|(j, &g)| g / scaler.affine(j).1).collect());
+            }
+            jacobian.set_row(k, gradient);
+        }
+        Ok(jacobian)
+    }
+
+    /// Score every edge by its mean absolute value over a dataset, the attribution that pruning decisions and interpretability plots are based on.
+    /// The scores are stored on the edges as `Edge::score` until the next call.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix with at least one row, where the ith row represents the input values to the first layer for the ith sample.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the edges were scored, or an error if there are no samples or a row does not have one value per input.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.update_edge_scores(&dataset.inputs).unwrap();
+    /// println!("{}", kan.edge_scores());
+    /// ```
+    pub fn update_edge_scores(&self, inputs: &Matrix) -> Result<(), KanError> {
+        if inputs.rows == 0 {
+            return Err(KanError::InvalidConfig("The scores of the edges require at least one sample."));
+        }
+        let edges: Vec<Rc<RefCell<Edge>>> = self.edges();
+        let mut totals: Vec<f64> = vec![0.0; edges.len()];
+        for i in 0..inputs.rows {
+            let values: Vec<f64> = self.edge_inputs(self.feature_matrix(&inputs.row(i))?)?;
+            for ((total, edge), &value) in totals.iter_mut().zip(edges.iter()).zip(values.iter()) {
+                *total += edge.borrow().value(value).abs();
+            }
+        }
+        for (edge, total) in edges.iter().zip(totals.iter()) {
+            edge.borrow_mut().score = Some(total / inputs.rows as f64);
+        }
+        Ok(())
+    }
+
+    /// Report the scores of the edges from the last `update_edge_scores`.
+    /// 
+    /// # Returns
+    /// 
+    /// * The scores keyed by (layer, start, end), where layer is the index of the layer the edge belongs to; edges that have not been scored and skip edges, whose scores are only stored on the edges, are left out.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.update_edge_scores(&inputs).unwrap();
+    /// let score = kan.edge_scores().get(0, 1, 0);
+    /// ```
+    pub fn edge_scores(&self) -> EdgeScores {
+        let mut scores: EdgeScores = EdgeScores::default();
+        for (l, layer) in self.layers.iter().enumerate() {
+            for (j, node) in layer.borrow().nodes.iter().enumerate() {
+                for edge in node.borrow().incoming.iter().filter(|edge| !edge.borrow().skip) {
+                    let edge: Ref<Edge> = edge.borrow();
+                    if let Some(score) = edge.score {
+                        scores.scores.insert((l, edge.start, j), score);
+                    }
+                }
+            }
+        }
+        scores
+    }
+
+    /// Prune the KAN by removing every edge whose score from the last `update_edge_scores` falls below a threshold.
+    /// A hidden node left without incoming or outgoing edges is removed with its remaining edges, repeatedly until every hidden node is connected on both sides, and the nodes after it in its layer are renumbered.
+    /// The inputs and the output nodes are kept, so the KAN still takes and returns vectors of the same lengths, and every forward and backward pass only visits the remaining edges.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `threshold` - A scalar threshold on the scores of the edges.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with the number of removed edges, or an error if an edge has not been scored.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.update_edge_scores(&dataset.inputs).unwrap();
+    /// let removed = kan.prune(1e-2).unwrap();
+    /// ```
+    pub fn prune(&self, threshold: f64) -> Result<usize, KanError> {
+        let edges: Vec<Rc<RefCell<Edge>>> = self.edges();
+        if edges.iter().any(|edge| edge.borrow().score.is_none()) {
+            return Err(KanError::InvalidConfig("Every edge must be scored with update_edge_scores before pruning."));
+        }
+        let weak: Vec<Rc<RefCell<Edge>>> = edges.into_iter().filter(|edge| edge.borrow().score.unwrap() < threshold).collect();
+        for edge in weak.iter() {
+            self.remove_edge(edge);
+        }
+        let mut removed: usize = weak.len();
+
+        // Remove hidden nodes without incoming or outgoing edges until none are left
+        let mut changed: bool = true;
+        while changed {
+            changed = false;
+            for l in 0..self.layers.len().saturating_sub(1) {
+                // The incoming edges of the next layers are authoritative for the outgoing edges of a node
+                let dead: Option<usize> = self.layers[l].borrow().nodes.iter().enumerate().position(|(i, node)| {
+                    node.borrow().incoming.is_empty() || self.consumers(l, i).is_empty()
+                });
+                if let Some(index) = dead {
+                    removed += self.remove_node(l, index);
+                    changed = true;
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Disconnect an edge from the node it feeds into and from the node it starts at, if that node is in a layer of the KAN.
+    fn remove_edge(&self, edge: &Rc<RefCell<Edge>>) {
+        for (l, layer) in self.layers.iter().enumerate() {
+            let layer: Ref<Layer> = layer.borrow();
+            for node in layer.nodes.iter() {
+                let position: Option<usize> = node.borrow().incoming.iter().position(|incoming| Rc::ptr_eq(incoming, edge));
+                if let Some(position) = position {
+                    node.borrow_mut().incoming.remove(position);
+                    // Skip edges start two layers back, and the skip edges of the second layer at the inputs
+                    let back: usize = if edge.borrow().skip { 2 } else { 1 };
+                    if l >= back {
+                        let start: usize = edge.borrow().start;
+                        self.layers[l - back].borrow().nodes[start].borrow_mut().outgoing.retain(|outgoing| !Rc::ptr_eq(outgoing, edge));
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Remove a hidden node with its incoming and outgoing edges, renumbering the nodes after it in its layer, and return the number of removed edges.
+    fn remove_node(&self, l: usize, index: usize) -> usize {
+        let node: Rc<RefCell<Node>> = self.layers[l].borrow().nodes[index].clone();
+        let mut edges: Vec<Rc<RefCell<Edge>>> = node.borrow().incoming.clone();
+        edges.extend(self.consumers(l, index));
+        for edge in edges.iter() {
+            self.remove_edge(edge);
+        }
+        self.layers[l].borrow_mut().nodes.remove(index);
+        if let Some(norm) = &mut self.layers[l].borrow_mut().norm {
+            norm.remove(index);
+        }
+        for node in self.layers[l].borrow().nodes.iter().skip(index) {
+            for edge in node.borrow().incoming.iter() {
+                edge.borrow_mut().end -= 1;
+            }
+        }
+        for (m, skip) in [(l + 1, false), (l + 2, true)] {
+            if let Some(layer) = self.layers.get(m) {
+                for node in layer.borrow().nodes.iter() {
+                    for edge in node.borrow().incoming.iter() {
+                        let mut edge: RefMut<Edge> = edge.borrow_mut();
+                        if edge.skip == skip && edge.start > index {
+                            edge.start -= 1;
+                        }
+                    }
+                }
+            }
+        }
+        edges.len()
+    }
+
+    /// The edges that start at a node, which are the regular edges of the next layer and the skip edges of the layer after it.
+    fn consumers(&self, l: usize, index: usize) -> Vec<Rc<RefCell<Edge>>> {
+        let mut edges: Vec<Rc<RefCell<Edge>>> = Vec::new();
+        for (m, skip) in [(l + 1, false), (l + 2, true)] {
+            if let Some(layer) = self.layers.get(m) {
+                for node in layer.borrow().nodes.iter() {
+                    edges.extend(node.borrow().incoming.iter().filter(|edge| edge.borrow().skip == skip && edge.borrow().start == index).cloned());
+                }
+            }
+        }
+        edges
+    }
+
+    /// Get all edges of the KAN as the incoming edges of the nodes in every layer, in order of layer, node, and incoming edge.
+    /// 
+    /// # Returns
+    /// 
+    /// * A list of the edges of the KAN.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let edges = kan.edges();
+    /// ```
+    pub fn edges(&self) -> Vec<Rc<RefCell<Edge>>> {
+        let mut edges: Vec<Rc<RefCell<Edge>>> = Vec::new();
+        for layer in self.layers.iter() {
+            for node in layer.borrow().nodes.iter() {
+                for edge in node.borrow().incoming.iter() {
+                    edges.push(edge.clone());
+                }
+            }
+        }
+        edges
+    }
+
+    /// Compute the total roughness penalty of the B-spline edges, the penalty of `Regularization::smoothness`.
+    /// 
+    /// # Returns
+    /// 
+    /// * The sum of `BSpline::roughness` over all edges whose activation is a B-spline.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let roughness = kan.roughness();
+    /// ```
+    pub fn roughness(&self) -> f64 {
+        self.edges().iter().filter_map(|edge| edge.borrow().activation.downcast_ref::<BSpline>().map(|spline| spline.roughness())).sum()
+    }
+
+    /// Compute the sparsity penalty of the KAN over a batch of samples, the penalty of `Regularization::l1` and `Regularization::entropy`.
+    /// As in the KAN paper, the magnitude of an edge is its mean absolute value over the samples, and every layer contributes the sum of the magnitudes of its edges (L1) and the entropy of the magnitudes normalised to sum to one.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix with at least one row, where the ith row represents the input values to the first layer for the ith sample.
+    /// 
+    /// * `l1` - The coefficient of the L1 term.
+    /// 
+    /// * `entropy` - The coefficient of the entropy term.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with the weighted sum of the L1 and entropy terms over all layers, or an error if there are no samples or a row does not have one value per input.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let penalty = kan.sparsity(&inputs, 1.0, 2.0).unwrap();
+    /// ```
+    pub fn sparsity(&self, inputs: &Matrix, l1: f64, entropy: f64) -> Result<f64, KanError> {
+        Ok(self.sparsity_with_gradients(inputs, l1, entropy, false)?.0)
+    }
+
+    /// Compute the gradient of `sparsity` with respect to the weights of every edge, in the order of `edges`.
+    /// The inputs of every edge are held fixed, so an edge is only penalised through its own weights.
+    pub(crate) fn sparsity_gradients(&self, inputs: &Matrix, l1: f64, entropy: f64) -> Result<Vec<Vector>, KanError> {
+        Ok(self.sparsity_with_gradients(inputs, l1, entropy, true)?.1)
+    }
+
+    fn sparsity_with_gradients(&self, inputs: &Matrix, l1: f64, entropy: f64, with_gradients: bool) -> Result<(f64, Vec<Vector>), KanError> {
+        if inputs.rows == 0 {
+            return Err(KanError::InvalidConfig("The sparsity penalty requires at least one sample."));
+        }
+        // Mean absolute value of every edge and its gradient with respect to the weights of the edge
+        let edges: Vec<Rc<RefCell<Edge>>> = self.edges();
+        let mut magnitudes: Vec<f64> = vec![0.0; edges.len()];
+        let mut magnitude_gradients: Vec<Vector> = edges.iter().map(|edge| Vector::zeros(if with_gradients { edge.borrow().num_weights() } else { 0 })).collect();
+        let scale: f64 = 1.0 / inputs.rows as f64;
+        for i in 0..inputs.rows {
+            let values: Vec<f64> = self.edge_inputs(self.feature_matrix(&inputs.row(i))?)?;
+            for (((magnitude, gradient), edge), &t) in magnitudes.iter_mut().zip(magnitude_gradients.iter_mut()).zip(edges.iter()).zip(values.iter()) {
+                let mut edge: RefMut<Edge> = edge.borrow_mut();
+                let value: f64 = edge.value(t);
+                *magnitude += value.abs() * scale;
+                if with_gradients && value != 0.0 {
+                    edge.backward(t, value.signum() * scale)?;
+                    gradient.axpy(1.0, &edge.gradient);
+                }
+            }
+        }
+
+        let mut penalty: f64 = 0.0;
+        let mut gradients: Vec<Vector> = Vec::with_capacity(edges.len());
+        let mut offset: usize = 0;
+        for layer in self.layers.iter() {
+            let count: usize = layer.borrow().nodes.iter().map(|node| node.borrow().incoming.len()).sum();
+            let layer_magnitudes: &[f64] = &magnitudes[offset..offset + count];
+            let total: f64 = layer_magnitudes.iter().sum();
+            let layer_entropy: f64 = if total > 0.0 {
+                -layer_magnitudes.iter().filter(|&&magnitude| magnitude > 0.0).map(|&magnitude| (magnitude / total) * (magnitude / total).ln()).sum::<f64>()
+            } else {
+                0.0
+            };
+            penalty += l1 * total + entropy * layer_entropy;
+            if with_gradients {
+                for (k, &magnitude) in layer_magnitudes.iter().enumerate() {
+                    // d(entropy)/d(magnitude) = -(ln p + entropy) / total for p = magnitude / total
+                    let slope: f64 = if magnitude > 0.0 { l1 - entropy * ((magnitude / total).ln() + layer_entropy) / total } else { l1 };
+                    gradients.push(&magnitude_gradients[offset + k] * slope);
+                }
+            }
+            offset += count;
+        }
+        Ok((penalty, gradients))
+    }
+
+    /// Copy the KAN with fresh edges, nodes, and layers, so training the copy does not change the KAN, e.g. for the members of an ensemble or the best model of an early stopped run.
+    /// `clone` only copies the pointers to the layers, so a cloned KAN shares its parameters with the original.
+    /// Every edge is copied once, so an edge that is an outgoing edge of one layer and an incoming edge of the next is shared the same way in the copy.
+    ///
+    /// # Returns
+    ///
+    /// * A KAN with copies of the layers, nodes, edges, normalizations, and scaler of the KAN, in the same mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let best: KAN = kan.deep_clone();
+    /// trainer.fit(&inputs, &targets, 10).unwrap();
+    /// ```
+    pub fn deep_clone(&self) -> KAN {
+        // The copy of every edge, keyed by the address of the original
+        let mut copies: FxHashMap<*const RefCell<Edge>, Rc<RefCell<Edge>>> = FxHashMap::default();
+        let mut copy_edge = |edge: &Rc<RefCell<Edge>>| -> Rc<RefCell<Edge>> {
+            copies.entry(Rc::as_ptr(edge)).or_insert_with(|| Rc::new(RefCell::new(edge.borrow().clone()))).clone()
+        };
+        let layers: Vec<Rc<RefCell<Layer>>> = self.layers.iter().map(|layer| {
+            let layer: Ref<Layer> = layer.borrow();
+            let nodes: Vec<Rc<RefCell<Node>>> = layer.nodes.iter().map(|node| {
+                let node: Ref<Node> = node.borrow();
+                let incoming: Vec<Rc<RefCell<Edge>>> = node.incoming.iter().map(&mut copy_edge).collect();
+                let outgoing: Vec<Rc<RefCell<Edge>>> = node.outgoing.iter().map(&mut copy_edge).collect();
+                Rc::new(RefCell::new(Node { incoming, outgoing, layer: node.layer, aggregation: node.aggregation }))
+            }).collect();
+            Rc::new(RefCell::new(Layer { nodes, norm: layer.norm.clone() }))
+        }).collect();
+        KAN { layers, inputs: self.inputs, scaler: self.scaler.clone(), training: self.training }
+    }
+
+    /// Take a snapshot of the weights of all edges, in the order of `edges`.
+    /// 
+    /// # Returns
+    /// 
+    /// * A list of weight vectors (the parameters of the activation followed by w_b and w_s), one per edge.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let snapshot = kan.snapshot();
+    /// ```
+    pub fn snapshot(&self) -> Vec<Vector> {
+        self.edges().iter().map(|edge| edge.borrow().weights()).collect()
+    }
+
+    /// Restore the weights of all edges from a snapshot taken with `snapshot`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `snapshot` - A list of weight vectors, one per edge.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the weights were restored, or an error if the snapshot does not match the edges, in which case no weights are changed.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let snapshot = kan.snapshot();
+    /// trainer.fit(&inputs, &targets, 10).unwrap();
+    /// kan.restore(&snapshot).unwrap();
+    /// ```
+    pub fn restore(&self, snapshot: &[Vector]) -> Result<(), KanError> {
+        let edges: Vec<Rc<RefCell<Edge>>> = self.edges();
+        if edges.len() != snapshot.len() {
+            return Err(KanError::mismatch("weight vectors in the snapshot, one per edge", edges.len(), snapshot.len()));
+        }
+        for (edge, weights) in edges.iter().zip(snapshot.iter()) {
+            if edge.borrow().num_weights() != weights.len() {
+                return Err(KanError::mismatch("weights of an edge in the snapshot", edge.borrow().num_weights(), weights.len()));
+            }
+        }
+        for (edge, weights) in edges.iter().zip(snapshot.iter()) {
+            edge.borrow_mut().set_weights(weights);
+        }
+        Ok(())
+    }
+
+    /// The number of trainable weights of the KAN, the sum of `Edge::num_weights` over all edges.
+    pub fn num_params(&self) -> usize {
+        self.edges().iter().map(|edge| edge.borrow().num_weights()).sum()
+    }
+
+    /// Get the weights of all edges as one flat vector, e.g. for external optimizers or averaging the weights of several KANs with the same architecture.
+    /// 
+    /// # Returns
+    /// 
+    /// * A vector with `num_params` elements: the weights of every edge (the parameters of the activation followed by w_b and w_s), concatenated in the order of `edges`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let params = kan.get_flat_params();
+    /// ```
+    pub fn get_flat_params(&self) -> Vector {
+        Vector::new(self.edges().iter().flat_map(|edge| edge.borrow().weights().elements).collect())
+    }
+
+    /// Set the weights of all edges from one flat vector in the layout of `get_flat_params`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - A vector with `num_params` elements.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the weights were set, or an error if the vector does not have `num_params` elements.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// // Average the weights of two KANs with the same architecture
+    /// let average = &(&a.get_flat_params() + &b.get_flat_params()) * 0.5;
+    /// a.set_flat_params(&average).unwrap();
+    /// ```
+    pub fn set_flat_params(&self, params: &Vector) -> Result<(), KanError> {
+        let edges: Vec<Rc<RefCell<Edge>>> = self.edges();
+        let num_params: usize = edges.iter().map(|edge| edge.borrow().num_weights()).sum();
+        if params.len() != num_params {
+            return Err(KanError::mismatch("parameters, one per weight of the KAN", num_params, params.len()));
+        }
+        let mut offset: usize = 0;
+        for edge in edges.iter() {
+            let mut edge: RefMut<Edge> = edge.borrow_mut();
+            let n: usize = edge.num_weights();
+            edge.set_weights(&Vector::new(params.elements[offset..offset + n].to_vec()));
+            offset += n;
+        }
+        Ok(())
+    }
+
+    /// Compute the product of the Hessian of the loss with respect to the weights and a direction, for curvature analysis and second-order methods on small KANs.
+    /// The product is approximated with central differences of the gradients of `backward_features` along the direction, which costs two backward passes, so it is as accurate as those gradients.
+    /// The weights and the gradients of the KAN are left unchanged.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `features` - A vector with one value per input of the KAN.
+    /// 
+    /// * `target` - A vector of target values for the output nodes (a one-hot vector for cross-entropy).
+    /// 
+    /// * `v` - A direction with `num_params` elements, in the layout of `get_flat_params`.
+    /// 
+    /// * `loss` - The loss function.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with the Hessian-vector product in the layout of `get_flat_params`, or an error if the features, the target, or the direction has the wrong length.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let features = Vector::new(vec![0.3, 0.6]);
+    /// let target = Vector::new(vec![0.4]);
+    /// let v = Vector::new(vec![1.0; kan.num_params()]);
+    /// let hv = kan.hvp(&features, &target, &v, Loss::MeanSquaredError).unwrap();
+    /// let curvature = v.dot(&hv) / v.dot(&v);
+    /// ```
+    pub fn hvp(&self, features: &Vector, target: &Vector, v: &Vector, loss: Loss) -> Result<Vector, KanError> {
+        let input: Matrix = self.feature_matrix(features)?;
+        let params: Vector = self.get_flat_params();
+        if v.len() != params.len() {
+            return Err(KanError::mismatch("elements in the direction, one per weight of the KAN", params.len(), v.len()));
+        }
+        let norm: f64 = v.norm(2.0);
+        if norm == 0.0 {
+            return Ok(Vector::zeros(v.len()));
+        }
+        // The step balances the truncation error of the differences against the rounding error of the gradients
+        let epsilon: f64 = 1e-5 * params.norm(f64::INFINITY).max(1.0) / norm;
+
+        let edges: Vec<Rc<RefCell<Edge>>> = self.edges();
+        let gradients: Vec<Vector> = edges.iter().map(|edge| edge.borrow().gradient.clone()).collect();
+        let flat_gradient = |params: &Vector| -> Result<Vector, KanError> {
+            self.set_flat_params(params)?;
+            self.backward_routed(input.clone(), target, loss)?;
+            Ok(Vector::new(edges.iter().flat_map(|edge| edge.borrow().gradient.elements.clone()).collect()))
+        };
+        let mut plus: Vector = params.clone();
+        plus.axpy(epsilon, v);
+        let mut minus: Vector = params.clone();
+        minus.axpy(-epsilon, v);
+        let result: Result<Vector, KanError> = match (flat_gradient(&plus), flat_gradient(&minus)) {
+            (Ok(gradient_plus), Ok(gradient_minus)) => Ok(&(&gradient_plus - &gradient_minus) * (0.5 / epsilon)),
+            (Err(err), _) | (_, Err(err)) => Err(err),
+        };
+
+        self.set_flat_params(&params)?;
+        for (edge, gradient) in edges.iter().zip(gradients) {
+            edge.borrow_mut().gradient = gradient;
+        }
+        result
+    }
+
+    /// Refine the grid of every B-spline edge to a given number of control points, preserving the learned functions (grid extension).
+    /// The gradients of the edges are reset, so the moments of an optimizer are reinitialised on the next step.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `k` - The number of control points of every refined spline.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let kan = KAN::standard(2, 3);
+    /// kan.refine_grid(10);
+    /// ```
+    pub fn refine_grid(&self, k: usize) {
+        for edge in self.edges().iter() {
+            let mut edge: RefMut<Edge> = edge.borrow_mut();
+            if let Some(spline) = edge.activation.downcast_mut::<BSpline>() {
+                spline.refine(k);
+                edge.zero_grad();
+            }
+        }
+    }
+
+    /// Reposition the knots of every B-spline edge to the empirical quantiles of the values it receives for a set of inputs, preserving the learned functions on the observed range.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith sample.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the knots were updated, or an error if a row does not have one value per input or a B-spline edge receives fewer than two distinct values, in which case no knots are changed.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let inputs = Matrix::new(vec![Vector::new(vec![0.1, 0.2]), Vector::new(vec![0.3, 0.9]), Vector::new(vec![0.4, 0.5])]);
+    /// kan.update_grids(&inputs).unwrap();
+    /// ```
+    pub fn update_grids(&self, inputs: &Matrix) -> Result<(), KanError> {
+        // Collect the inputs of every edge, in the order of `edges`, before any knots change
+        let edges: Vec<Rc<RefCell<Edge>>> = self.edges();
+        let mut samples: Vec<Vec<f64>> = vec![Vec::new(); edges.len()];
+        for i in 0..inputs.rows {
+            let values: Vec<f64> = self.edge_inputs(self.feature_matrix(&inputs.row(i))?)?;
+            for (edge_samples, &value) in samples.iter_mut().zip(values.iter()) {
+                edge_samples.push(value);
+            }
+        }
+
+        // Tied edges keep sharing their knots
+        let samples: Vec<Vec<f64>> = pool_tied(&edges, samples);
+        for (edge, edge_samples) in edges.iter().zip(samples.iter()) {
+            let distinct: bool = edge_samples.iter().any(|&value| value != edge_samples[0]);
+            if edge.borrow().activation.downcast_ref::<BSpline>().is_some() && !distinct {
+                return Err(KanError::DomainError("Every B-spline edge must receive at least two distinct values to update its knots."));
+            }
+        }
+        for (edge, edge_samples) in edges.iter().zip(samples) {
+            if let Some(spline) = edge.borrow_mut().activation.downcast_mut::<BSpline>() {
+                spline.update_knots_from_samples(&Vector::new(edge_samples));
+            }
+        }
+        Ok(())
+    }
+
+    /// Blend the statistics of the values of the nodes over a set of samples into the running statistics of every batch normalization, see `Norm::update_statistics`.
+    /// The values of every layer are computed with the running statistics from before the call.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - A matrix with at least one row, where the ith row represents the input values to the first layer for the ith sample.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the statistics were updated, or an error if there are no samples or a row does not have one value per input.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.update_norm_statistics(&batch.inputs).unwrap();
+    /// ```
+    pub fn update_norm_statistics(&self, inputs: &Matrix) -> Result<(), KanError> {
+        if inputs.rows == 0 {
+            return Err(KanError::InvalidConfig("The statistics of the normalizations require at least one sample."));
+        }
+        if self.layers.iter().all(|layer| layer.borrow().norm.is_none()) {
+            return Ok(());
+        }
+        let mut values: Vec<Vec<Vector>> = vec![Vec::with_capacity(inputs.rows); self.layers.len()];
+        for i in 0..inputs.rows {
+            let (_, sample_values) = self.trace(self.feature_matrix(&inputs.row(i))?)?;
+            for (layer_values, sample_values) in values.iter_mut().zip(sample_values) {
+                layer_values.push(sample_values);
+            }
+        }
+        for (layer, layer_values) in self.layers.iter().zip(values).filter(|(layer, _)| layer.borrow().norm.is_some()) {
+            if let Some(norm) = &mut layer.borrow_mut().norm {
+                norm.update_statistics(&Matrix::new(layer_values));
+            }
+        }
+        Ok(())
+    }
+
+    /// Switch the KAN to training mode, the mode of a new KAN, and propagate the mode to its layers, edges, and normalizations.
+    /// In training mode the trainer masks the edges with their dropout probabilities and the running statistics of the batch normalizations are updated.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.train_mode();
+    /// trainer.fit(&inputs, &targets, 10).unwrap();
+    /// ```
+    pub fn train_mode(&mut self) {
+        self.set_training(true);
+    }
+
+    /// Switch the KAN to evaluation mode and propagate the mode to its layers, edges, and normalizations.
+    /// In evaluation mode no edge is masked by dropout, every edge is scaled by its keep probability, and the running statistics of the batch normalizations are kept.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.eval_mode();
+    /// let predictions = kan.predict(&inputs).unwrap();
+    /// ```
+    pub fn eval_mode(&mut self) {
+        self.set_training(false);
+    }
+
+    /// Set the mode of the KAN and of its layers.
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+        for layer in self.layers.iter() {
+            layer.borrow_mut().set_training(training);
+        }
+    }
+
+    /// Draw a new dropout mask for a training step, masking every edge independently with its dropout probability.
+    /// Masked edges contribute nothing to the nodes they feed into and receive no gradient until the mask is cleared with `clear_dropout`.
+    /// Edges without a dropout probability and edges in evaluation mode are never masked and draw no random numbers.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `rng` - A random number generator.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// kan.sample_dropout(&mut rng);
+    /// kan.backward_features(&features, &target, Loss::MeanSquaredError).unwrap();
+    /// kan.clear_dropout();
+    /// ```
+    pub fn sample_dropout(&self, rng: &mut impl Rng) {
+        for edge in self.edges() {
+            let mut edge: RefMut<Edge> = edge.borrow_mut();
+            if edge.training && edge.dropout > 0.0 {
+                edge.masked = Some(rng.gen::<f64>() < edge.dropout);
+            }
+        }
+    }
+
+    /// Clear the dropout mask of every edge after a training step, so the value of every edge is scaled by its keep probability as at inference.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.clear_dropout();
+    /// ```
+    pub fn clear_dropout(&self) {
+        for edge in self.edges() {
+            edge.borrow_mut().masked = None;
+        }
+    }
+
+    /// Estimate the uncertainty of the prediction for one sample with Monte Carlo dropout.
+    /// The KAN is evaluated n times with a new dropout mask for every edge with a dropout probability, also in evaluation mode, and the masks are cleared afterwards.
+    /// The masks are drawn from the system random number generator, which wasm32 does not have, see `predict_with_uncertainty_with_rng` for reproducible estimates and for wasm32.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A vector with the input values to the first layer.
+    /// 
+    /// * `n_samples` - A positive scalar representing the number of stochastic forward passes.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a vector with the mean value of every output node over the passes and a vector with the standard deviation of every output node over the passes, which is 0 for a KAN without dropout, or an error if the number of passes is 0 or the input does not have one value per input of the KAN.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let (mean, std) = kan.predict_with_uncertainty(&Vector::new(vec![0.3, 0.6]), 100).unwrap();
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn predict_with_uncertainty(&self, input: &Vector, n_samples: usize) -> Result<(Vector, Vector), KanError> {
+        self.predict_with_uncertainty_with_rng(input, n_samples, &mut rand::thread_rng())
+    }
+
+    /// Estimate the uncertainty of the prediction for one sample with Monte Carlo dropout like `predict_with_uncertainty`, drawing the masks from the given random number generator.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A vector with the input values to the first layer.
+    /// 
+    /// * `n_samples` - A positive scalar representing the number of stochastic forward passes.
+    /// 
+    /// * `rng` - A random number generator.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with a vector with the mean value of every output node over the passes and a vector with the standard deviation of every output node over the passes, or an error as in `predict_with_uncertainty`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let mut rng = ChaCha8Rng::seed_from_u64(42);
+    /// let (mean, std) = kan.predict_with_uncertainty_with_rng(&Vector::new(vec![0.3, 0.6]), 100, &mut rng).unwrap();
+    /// ```
+    pub fn predict_with_uncertainty_with_rng(&self, input: &Vector, n_samples: usize, rng: &mut impl Rng) -> Result<(Vector, Vector), KanError> {
+        if n_samples == 0 {
+            return Err(KanError::InvalidConfig("The number of samples must be greater than 0."));
+        }
+        let edges: Vec<Rc<RefCell<Edge>>> = self.edges();
+        let outputs: Result<Vec<Vector>, KanError> = (0..n_samples).map(|_| {
+            // Unlike `sample_dropout`, the masks are drawn outside of training mode as well
+            for edge in edges.iter() {
+                let mut edge: RefMut<Edge> = edge.borrow_mut();
+                if edge.dropout > 0.0 {
+                    edge.masked = Some(rng.gen::<f64>() < edge.dropout);
+                }
+            }
+            self.forward_logits(input)
+        }).collect();
+        // The masks are cleared even if a pass failed
+        self.clear_dropout();
+        let outputs: Vec<Vector> = outputs?;
+
+        let n: f64 = n_samples as f64;
+        let mean: Vector = Vector::new((0..outputs[0].len()).map(|j| outputs.iter().map(|output| output[j]).sum::<f64>() / n).collect());
+        let std: Vector = Vector::new((0..mean.len()).map(|j| (outputs.iter().map(|output| (output[j] - mean[j]).powi(2)).sum::<f64>() / n).sqrt()).collect());
+        Ok((mean, std))
+    }
+
+    /// Tie the activations of edges so they share one set of parameters, e.g. to impose a symmetry f(x, y) = f(y, x) as in the physics examples of the KAN paper.
+    /// The activation of the first edge is copied to the others. From then on `Trainer::fit` and `partial_fit` sum the gradients of the parameters of the activations of all tied edges, so every update moves the shared parameters once with the combined gradient, and `update_grids` and `partial_fit` update the knots of tied edges from their pooled inputs.
+    /// Edges that are already tied join the new group together with the edges they are tied to. The base and spline weights of every edge stay separate, and tied edges should be frozen and scaled together.
+    /// Set `Edge::tie` to None to untie an edge.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `edges` - At least two edges of the KAN with activations of the same kind and number of parameters.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the edges were tied, or an error if there are fewer than two edges or their activations differ in kind or number of parameters, in which case no edge is changed.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// // The first hidden node treats both inputs alike
+    /// let edges = kan.edges();
+    /// kan.tie(&[edges[0].clone(), edges[1].clone()]).unwrap();
+    /// ```
+    pub fn tie(&self, edges: &[Rc<RefCell<Edge>>]) -> Result<(), KanError> {
+        if edges.len() < 2 {
+            return Err(KanError::InvalidConfig("At least two edges are needed to tie their activations."));
+        }
+        let all: Vec<Rc<RefCell<Edge>>> = self.edges();
+        let joined: Vec<usize> = edges.iter().filter_map(|edge| edge.borrow().tie).collect();
+        let mut members: Vec<Rc<RefCell<Edge>>> = all.iter().filter(|edge| edge.borrow().tie.is_some_and(|tie| joined.contains(&tie))).cloned().collect();
+        for edge in edges.iter() {
+            if !members.iter().any(|member| Rc::ptr_eq(member, edge)) {
+                members.push(edge.clone());
+            }
+        }
+
+        let activation: Box<dyn ActivationFn> = edges[0].borrow().activation.clone_box();
+        for member in members.iter() {
+            let member: Ref<Edge> = member.borrow();
+            if member.activation.as_any().type_id() != activation.as_any().type_id() || member.activation.params().len() != activation.params().len() {
+                return Err(KanError::InvalidConfig("Tied edges must have activations of the same kind with the same number of parameters."));
+            }
+        }
+        let group: usize = all.iter().filter_map(|edge| edge.borrow().tie).max().map_or(0, |tie| tie + 1);
+        for member in members.iter() {
+            let mut member: RefMut<Edge> = member.borrow_mut();
+            member.activation = activation.clone_box();
+            member.tie = Some(group);
+            member.zero_grad();
+        }
+        Ok(())
+    }
+
+    /// Train the KAN on a single sample from a data stream with one step of gradient descent on the mean squared error.
+    /// Before the step, the knots of every B-spline edge that receives a value outside their range are widened to cover it with `BSpline::extend_to`, so the KAN keeps adapting as the observed input range drifts without retraining on past samples.
+    /// Frozen edges keep their knots.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `input` - A vector representing the input values to the first layer.
+    /// 
+    /// * `target` - A vector of target values for the output nodes.
+    /// 
+    /// * `learning_rate` - A positive scalar representing the learning rate.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with the loss of the KAN on the sample before the step, or an error if the learning rate is not positive, the number of input or target values does not match the KAN, or an edge receives a value that is not finite, in which case the KAN is not changed.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// for (input, target) in stream {
+    ///     kan.partial_fit(&input, &target, 0.01).unwrap();
+    /// }
+    /// ```
+    pub fn partial_fit(&self, input: &Vector, target: &Vector, learning_rate: f64) -> Result<f64, KanError> {
+        if learning_rate.is_nan() || learning_rate <= 0.0 {
+            return Err(KanError::InvalidConfig("The learning rate must be greater than 0."));
+        }
+        // Widen copies of the grids for the values the edges receive, in the order of `edges`
+        let edges: Vec<Rc<RefCell<Edge>>> = self.edges();
+        let values: Vec<Vec<f64>> = pool_tied(&edges, self.edge_inputs(self.feature_matrix(input)?)?.into_iter().map(|value| vec![value]).collect());
+        let mut widened: Vec<(usize, Box<dyn ActivationFn>)> = Vec::new();
+        for (i, (edge, edge_values)) in edges.iter().zip(values.iter()).enumerate() {
+            let edge: Ref<Edge> = edge.borrow();
+            if edge.frozen || edge.activation.downcast_ref::<BSpline>().is_none() {
+                continue;
+            }
+            if edge_values.iter().any(|value| !value.is_finite()) {
+                return Err(KanError::DomainError("The values the edges receive must be finite to widen their grids."));
+            }
+            let mut activation: Box<dyn ActivationFn> = edge.activation.clone_box();
+            let mut extended: bool = false;
+            if let Some(spline) = activation.downcast_mut::<BSpline>() {
+                for &value in edge_values.iter() {
+                    extended |= spline.extend_to(edge.spline_input(value));
+                }
+            }
+            if extended {
+                widened.push((i, activation));
+            }
+        }
+
+        // Swap in the widened grids, and swap the old ones back if the backward pass fails
+        let originals: Vec<(usize, Box<dyn ActivationFn>, Vector)> = widened.into_iter().map(|(i, activation)| {
+            let mut edge: RefMut<Edge> = edges[i].borrow_mut();
+            let original: Box<dyn ActivationFn> = std::mem::replace(&mut edge.activation, activation);
+            let gradient: Vector = edge.gradient.clone();
+            edge.zero_grad();
+            (i, original, gradient)
+        }).collect();
+        let loss: f64 = match self.backward_features(input, target, Loss::MeanSquaredError) {
+            Ok(loss) => loss,
+            Err(err) => {
+                for (i, original, gradient) in originals {
+                    let mut edge: RefMut<Edge> = edges[i].borrow_mut();
+                    edge.activation = original;
+                    edge.gradient = gradient;
+                }
+                return Err(err);
+            }
+        };
+        let mut gradients: Vec<Vector> = edges.iter().map(|edge| edge.borrow().gradient.clone()).collect();
+        share_tied_gradients(&edges, &mut gradients);
+        for (edge, gradient) in edges.iter().zip(gradients) {
+            let mut edge: RefMut<Edge> = edge.borrow_mut();
+            edge.gradient = gradient;
+            edge.update_weights(learning_rate)?;
+        }
+        for layer in self.layers.iter().filter(|layer| layer.borrow().norm.is_some()) {
+            if let Some(norm) = &mut layer.borrow_mut().norm {
+                let (scale_gradient, shift_gradient): (Vector, Vector) = (norm.scale_gradient.clone(), norm.shift_gradient.clone());
+                norm.step(&scale_gradient, &shift_gradient, learning_rate);
+            }
+        }
+        Ok(loss)
+    }
+
+    /// Save the KAN to a JSON file.
+    /// Only B-spline, Gaussian RBF, and symbolic activations can be saved.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - The path of the JSON file.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the KAN was written successfully.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.save_json("kan.json").unwrap();
+    /// ```
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<(), KanError> {
+        let json: String = serde_json::to_string(self).map_err(|_| KanError::Unsupported("The activation of an edge cannot be serialized."))?;
+        fs::write(path, json).map_err(|_| KanError::Io("Failed to write the JSON file."))
+    }
+
+    /// Load a KAN from a JSON file written by `save_json`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - The path of the JSON file.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with the loaded KAN, or an error if the file cannot be read or does not describe a KAN.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let kan = KAN::load_json("kan.json").unwrap();
+    /// ```
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<KAN, KanError> {
+        let json: String = fs::read_to_string(path).map_err(|_| KanError::Io("Failed to read the JSON file."))?;
+        serde_json::from_str(&json).map_err(|_| KanError::Format("The JSON file does not describe a KAN."))
+    }
+
+    /// Build a KAN from a configuration file, see `Config` for its format.
+    /// The training section of the file is read and validated too, but only `Config::trainer` uses it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - The path of the configuration file, read as TOML if its extension is `.toml` and as JSON otherwise.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with the new KAN, or an error if the file cannot be read or does not describe a valid configuration.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let kan = KAN::from_config("experiment.toml").unwrap();
+    /// ```
+    pub fn from_config<P: AsRef<Path>>(path: P) -> Result<KAN, KanError> {
+        Ok(Config::load(path)?.build())
+    }
+
+    /// Generate the source of a dependency-free Rust module with the parameters of the KAN hardcoded and a `predict(&[f64]) -> f64` function, for embedding the trained model into firmware or other crates.
+    /// See `codegen::rust` for the generated interface.
+    /// 
+    /// # Returns
+    /// 
+    /// * The source of the module, or an error if an edge has an activation that cannot be generated as code.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// fs::write("model.rs", kan.codegen_rust().unwrap()).unwrap();
+    /// ```
+    pub fn codegen_rust(&self) -> Result<String, KanError> {
+        codegen::rust(self)
+    }
+
+    /// Render the topology of the KAN as a Graphviz DOT graph, where the width of every edge shows its score from the last `update_edge_scores`.
+    /// See `dot::to_dot` for the layout.
+    /// 
+    /// # Returns
+    /// 
+    /// * The source of the graph.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.update_edge_scores(&dataset.inputs).unwrap();
+    /// fs::write("kan.dot", kan.to_dot()).unwrap();
+    /// ```
+    pub fn to_dot(&self) -> String {
+        dot::to_dot(self)
+    }
+
+    /// Write a plot of the activation of every edge over its domain to a directory, one SVG file per edge.
+    /// See `plot::plot_all` for the names of the files and `plot::edge_svg` for the plots.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `dir` - The directory, which is created if it does not exist.
+    /// 
+    /// # Returns
+    /// 
+    /// * The number of files written, or an error if a file cannot be written.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.plot_all("plots").unwrap();
+    /// ```
+    pub fn plot_all<P: AsRef<Path>>(&self, dir: P) -> Result<usize, KanError> {
+        plot::plot_all(self, dir)
+    }
+
+    /// Extract the formula of every output node as a simplified expression of the raw input features, once every edge has been snapped to an analytic function with `Edge::symbolify`.
+    /// See `expr::extract` for what the formula covers.
+    /// 
+    /// # Returns
+    /// 
+    /// * The formula of every output node, or an error if the KAN has an edge or normalization that cannot be expressed as a formula.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// for edge in kan.edges().iter() {
+    ///     edge.borrow_mut().symbolify(&SymbolicFn::ALL);
+    /// }
+    /// println!("{:.3}", kan.extract_formula().unwrap()[0]);
+    /// ```
+    pub fn extract_formula(&self) -> Result<Vec<Expr>, KanError> {
+        expr::extract(self)
+    }
+
+    /// Load a KAN that is equivalent to a model trained with pykan from a JSON dump of its state dict.
+    /// The JSON file is written in Python with `json.dump({name: tensor.tolist() for name, tensor in model.state_dict().items()}, file)`; see `pykan::from_state_dict` for the supported models.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - The path of the JSON file.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result with the loaded KAN, or an error if the file cannot be read or the model cannot be represented.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// let kan = KAN::load_pykan_json("pykan_state.json").unwrap();
+    /// ```
+    pub fn load_pykan_json<P: AsRef<Path>>(path: P) -> Result<KAN, KanError> {
+        let json: String = fs::read_to_string(path).map_err(|_| KanError::Io("Failed to read the JSON file."))?;
+        pykan::from_state_dict(&pykan::parse_state_dict(&json)?)
+    }
+
+    /// Export the KAN to an ONNX file, approximating every edge by 32 linear segments over the domain of its activation.
+    /// The graph only uses the standard operators MatMul, Sub, Relu, and Add, so it runs on existing ONNX runtimes; see `OnnxModel` for the lowering.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - The path of the ONNX file.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the model was written successfully.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.export_onnx("kan.onnx").unwrap();
+    /// ```
+    pub fn export_onnx<P: AsRef<Path>>(&self, path: P) -> Result<(), KanError> {
+        self.export_onnx_with_segments(path, 32)
+    }
+
+    /// Export the KAN to an ONNX file like `export_onnx`, approximating every edge by the given number of linear segments.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - The path of the ONNX file.
+    /// 
+    /// * `segments` - The number of linear segments per edge; more segments are more accurate and give a larger model.
+    /// 
+    /// # Returns
+    /// 
+    /// * A result indicating whether the model was written successfully.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// kan.export_onnx_with_segments("kan.onnx", 128).unwrap();
+    /// ```
+    pub fn export_onnx_with_segments<P: AsRef<Path>>(&self, path: P, segments: usize) -> Result<(), KanError> {
+        OnnxModel::from_kan(self, segments).save(path)
+    }
+
+    /// Forward pass that returns the input matrix of every layer together with the output values.
+    fn layer_inputs(&self, input: Matrix) -> Result<(Vec<Matrix>, Vector), KanError> {
+        let (layer_inputs, values) = self.trace(input)?;
+        let activations: Vector = match self.layers.last() {
+            Some(layer) => layer.borrow().normalize(values[values.len() - 1].clone()),
+            None => Vector::new(vec![]),
+        };
+        Ok((layer_inputs, activations))
+    }
+
+    /// Run the forward pass and return the input of every edge, in the order of `edges`.
+    fn edge_inputs(&self, input: Matrix) -> Result<Vec<f64>, KanError> {
+        let (layer_inputs, _) = self.layer_inputs(input)?;
+        let mut values: Vec<f64> = Vec::new();
+        for (layer, layer_input) in self.layers.iter().zip(layer_inputs.iter()) {
+            for (i, node) in layer.borrow().nodes.iter().enumerate() {
+                values.extend((0..node.borrow().incoming.len()).map(|j| layer_input[i][j]));
+            }
+        }
+        Ok(values)
+    }
+
+    /// Run the forward pass and return the input matrix and the values of the nodes before the normalization of every layer.
+    fn trace(&self, input: Matrix) -> Result<(Vec<Matrix>, Vec<Vector>), KanError> {
+        #[cfg(feature = "instrument")]
+        let _span = crate::instrument::span(crate::instrument::Phase::Forward);
+        let mut layer_inputs: Vec<Matrix> = Vec::with_capacity(self.layers.len());
+        let mut values: Vec<Vector> = Vec::with_capacity(self.layers.len());
+        // The values passed on by every level, the inputs followed by the normalized values of every layer
+        let mut outputs: Vec<Vector> = Vec::with_capacity(self.layers.len() + 1);
+        for (i, layer) in self.layers.iter().enumerate() {
+            let layer: Ref<Layer> = layer.borrow();
+            let layer_input: Matrix = if i > 0 {
+                layer.route_with_skip(&outputs[i], &outputs[i - 1])
+            } else {
+                let scaled: Matrix = self.scale_input(&layer, input.clone());
+                outputs.push(self.input_values(&layer, &scaled));
+                scaled
+            };
+            let layer_values: Vector = layer.activations(layer_input.clone()).map_err(|err| err.at_layer(i))?;
+            outputs.push(layer.normalize(layer_values.clone()));
+            values.push(layer_values);
+            layer_inputs.push(layer_input);
+        }
+        Ok((layer_inputs, values))
+    }
+
+    /// Recover the input values, which the skip edges of the second layer start at, from the input matrix of the first layer.
+    /// An input that no edge of the first layer starts at, e.g. after pruning, is read as zero.
+    fn input_values(&self, first_layer: &Layer, input: &Matrix) -> Vector {
+        let skip_starts: Vec<usize> = match self.layers.get(1) {
+            Some(layer) => layer.borrow().nodes.iter().flat_map(|node| node.borrow().incoming.iter().filter(|edge| edge.borrow().skip).map(|edge| edge.borrow().start).collect::<Vec<usize>>()).collect(),
+            None => vec![],
+        };
+        if skip_starts.is_empty() {
+            return Vector::new(vec![]);
+        }
+        let mut result: Vector = Vector::zeros(skip_starts.into_iter().max().unwrap_or(0) + 1);
+        for (i, node) in first_layer.nodes.iter().enumerate() {
+            for (j, edge) in node.borrow().incoming.iter().enumerate() {
+                let start: usize = edge.borrow().start;
+                if start < result.len() {
+                    result[start] = input[i][j];
+                }
+            }
+        }
+        result
+    }
+
+    /// Map the input matrix of the first layer with the scaler, where the entry for an incoming edge is scaled as the feature at its start index.
+    fn scale_input(&self, first_layer: &Layer, mut input: Matrix) -> Matrix {
+        if let Some(scaler) = &self.scaler {
+            for (i, node) in first_layer.nodes.iter().enumerate() {
+                for (j, edge) in node.borrow().incoming.iter().enumerate() {
+                    input[i][j] = scaler.transform_value(input[i][j], edge.borrow().start);
+                }
+            }
+        }
+        input
+    }
+
+    /// Route the values of the input features to the incoming edges of the first layer, or give an error if there is not one value per input.
+    fn feature_matrix(&self, features: &Vector) -> Result<Matrix, KanError> {
+        if features.len() != self.inputs {
+            return Err(KanError::mismatch("input values, one per input of the KAN", self.inputs, features.len()));
+        }
+        Ok(self.input_matrix(features))
+    }
+
+    /// Route a vector of input values to the incoming edges of the first layer.
+    pub(crate) fn input_matrix(&self, input: &Vector) -> Matrix {
+        self.layers[0].borrow().route(input)
+    }
+}
+
+/// The serialized form of a KAN: the layer index and incoming edges of every node in every layer, the number of inputs, and the scaler.
+#[derive(Serialize, Deserialize)]
+struct KANData {
+    layers: Vec<Vec<NodeData>>,
+    #[serde(default)] // Files written before the number of inputs was stored infer it from the edges
+    inputs: Option<usize>,
+    #[serde(default)] // Files written before normalizations were added have none
+    norms: Vec<Option<Norm>>,
+    #[serde(default)] // Files written before scalers were added have none
+    scaler: Option<Scaler>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NodeData {
+    layer: usize,
+    incoming: Vec<Edge>,
+    #[serde(default)] // Files written before product nodes were added only have sum nodes
+    aggregation: Aggregation,
+}
+
+impl From<KAN> for KANData {
+    fn from(kan: KAN) -> KANData {
+        KANData {
+            layers: kan.layers.iter().map(|layer| layer.borrow().nodes.iter().map(|node| {
+                let node: Ref<Node> = node.borrow();
+                NodeData { layer: node.layer, incoming: node.incoming.iter().map(|edge| edge.borrow().clone()).collect(), aggregation: node.aggregation }
+            }).collect()).collect(),
+            norms: kan.layers.iter().map(|layer| layer.borrow().norm.clone()).collect(),
+            inputs: Some(kan.inputs),
+            scaler: kan.scaler,
+        }
+    }
+}
+
+/// The outgoing edges of a node are the incoming edges of the next layer, or the skip edges two layers on, that start at it, in order of appearance.
+impl TryFrom<KANData> for KAN {
+    type Error = KanError;
+
+    fn try_from(data: KANData) -> Result<KAN, KanError> {
+        if !data.norms.is_empty() && data.norms.len() != data.layers.len() {
+            return Err(KanError::Format("The number of normalizations must be equal to the number of layers."));
+        }
+        let mut norms: Vec<Option<Norm>> = data.norms;
+        norms.resize(data.layers.len(), None);
+        if data.layers.iter().zip(norms.iter()).any(|(nodes, norm)| norm.as_ref().is_some_and(|norm| norm.len() != nodes.len())) {
+            return Err(KanError::Format("A normalization must have one scale and shift per node of its layer."));
+        }
+        let layers: Vec<Rc<RefCell<Layer>>> = data.layers.into_iter().zip(norms).map(|(nodes, norm)| {
+            let nodes: Vec<Rc<RefCell<Node>>> = nodes.into_iter().map(|node| {
+                let incoming: Vec<Rc<RefCell<Edge>>> = node.incoming.into_iter().map(|edge| Rc::new(RefCell::new(edge))).collect();
+                let mut rebuilt: Node = Node::new(incoming, vec![], node.layer);
+                rebuilt.aggregation = node.aggregation;
+                Rc::new(RefCell::new(rebuilt))
+            }).collect();
+            let mut layer: Layer = Layer::new(nodes);
+            layer.norm = norm;
+            Rc::new(RefCell::new(layer))
+        }).collect();
+
+        connect(&layers)?;
+        let inputs: usize = data.inputs.unwrap_or_else(|| input_width(&layers));
+        let mut kan: KAN = KAN { layers, inputs, scaler: data.scaler, training: true };
+        // The modes of the edges and normalizations are not serialized
+        kan.train_mode();
+        Ok(kan)
+    }
+}
+
+/// Sum the gradients of the parameters of the activations of the tied edges within every group, so every tied edge takes the same step.
+/// The gradients are given in the order of the edges, with the parameters of the activation first, and the gradients of the base and spline weights are left unchanged.
+pub(crate) fn share_tied_gradients(edges: &[Rc<RefCell<Edge>>], gradients: &mut [Vector]) {
+    for members in tied_groups(edges) {
+        let n: usize = edges[members[0]].borrow().activation.params().len();
+        let mut shared: Vector = Vector::zeros(n);
+        for &i in members.iter() {
+            for (sum, &gradient) in shared.iter_mut().zip(gradients[i].iter()) {
+                *sum += gradient;
+            }
+        }
+        for &i in members.iter() {
+            gradients[i].elements[..n].copy_from_slice(&shared.elements);
+        }
+    }
+}
+
+/// Replace the values of every tied edge with the values of all edges of its group, given in the order of the edges.
+fn pool_tied(edges: &[Rc<RefCell<Edge>>], mut values: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    for members in tied_groups(edges) {
+        let pooled: Vec<f64> = members.iter().flat_map(|&i| values[i].clone()).collect();
+        for &i in members.iter() {
+            values[i] = pooled.clone();
+        }
+    }
+    values
+}
+
+/// The indices of the edges of every group of tied edges.
+fn tied_groups(edges: &[Rc<RefCell<Edge>>]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+    for (i, edge) in edges.iter().enumerate() {
+        if let Some(tie) = edge.borrow().tie {
+            match groups.iter_mut().find(|(group, _)| *group == tie) {
+                Some((_, members)) => members.push(i),
+                None => groups.push((tie, vec![i])),
+            }
+        }
+    }
+    groups.into_iter().map(|(_, members)| members).collect()
+}
+
+/// The number of inputs the edges of a KAN start at, one more than the largest start index of the incoming edges of the first layer and the skip edges of the second layer, or 0 if there are no layers.
+pub(crate) fn input_width(layers: &[Rc<RefCell<Layer>>]) -> usize {
+    let starts = |l: usize, skip: bool| -> Vec<usize> {
+        layers.get(l).map_or(vec![], |layer| layer.borrow().nodes.iter().flat_map(|node| node.borrow().incoming.iter().filter(|edge| edge.borrow().skip == skip).map(|edge| edge.borrow().start + 1).collect::<Vec<usize>>()).collect())
+    };
+    starts(0, false).into_iter().chain(starts(1, true)).max().unwrap_or(0)
+}
+
+/// Add every incoming edge of a layer as an outgoing edge of the node it starts at, in the previous layer or two layers back for a skip edge.
+pub(crate) fn connect(layers: &[Rc<RefCell<Layer>>]) -> Result<(), KanError> {
+    for k in 1..layers.len() {
+        for node in layers[k].borrow().nodes.iter() {
+            for edge in node.borrow().incoming.iter() {
+                let (start, skip): (usize, bool) = (edge.borrow().start, edge.borrow().skip);
+                if skip && k == 1 {
+                    // The skip edges of the second layer start at the inputs
+                    continue;
+                }
+                let previous: Ref<Layer> = layers[if skip { k - 2 } else { k - 1 }].borrow();
+                if start >= previous.nodes.len() {
+                    return Err(KanError::Format(if skip { "A skip edge starts at a node that does not exist two layers back." } else { "An edge starts at a node that does not exist in the previous layer." }));
+                }
+                previous.nodes[start].borrow_mut().add_outgoing(edge.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check the analytic gradient of `KAN::backward_features` against a numerical gradient.
+/// Each weight of every edge is perturbed by plus and minus epsilon and the gradient is estimated with central differences of the loss.
+/// The weights and the gradients of the KAN are left unchanged.
+/// 
+/// # Arguments
+/// 
+/// * `kan` - A KAN.
+/// 
+/// * `features` - A vector with one value per input of the KAN.
+/// 
+/// * `target` - A vector of target values for the output nodes (a one-hot vector for cross-entropy).
+/// 
+/// * `loss` - The loss function.
+/// 
+/// * `epsilon` - A positive scalar representing the size of the perturbation.
+/// 
+/// # Returns
+/// 
+/// * A result with a vector with the maximum relative error between the analytic and numerical gradients of every edge, in the order of `KAN::edges`, or an error if epsilon is not positive, the features do not have one value per input, or the target does not have one value per output node.
+/// 
+/// # Example
+/// 
+/// ```
+/// let kan = KAN::standard(2, 3);
+/// let features = Vector::new(vec![0.2, 0.7]);
+/// 
+/// let errors = grad_check(&kan, &features, &one_hot(1, 3), Loss::CrossEntropy, 1e-6).unwrap();
+/// ```
+pub fn grad_check(kan: &KAN, features: &Vector, target: &Vector, loss: Loss, epsilon: f64) -> Result<Vector, KanError> {
+    if epsilon.is_nan() || epsilon <= 0.0 {
+        return Err(KanError::InvalidConfig("The perturbation must be greater than 0."));
+    }
+    let input: Matrix = kan.feature_matrix(features)?;
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    let gradients: Vec<Vector> = edges.iter().map(|edge| edge.borrow().gradient.clone()).collect();
+
+    let backward: Result<f64, KanError> = kan.backward_routed(input.clone(), target, loss);
+    let analytic: Vec<Vector> = edges.iter().map(|edge| edge.borrow().gradient.clone()).collect();
+    // Restore the gradients accumulated before the check
+    for (edge, gradient) in edges.iter().zip(gradients) {
+        edge.borrow_mut().gradient = gradient;
+    }
+    backward?;
+
+    let loss = |kan: &KAN| -> Result<f64, KanError> {
+        let (_, output) = kan.layer_inputs(input.clone())?;
+        loss.compute(&output, target)
+    };
+
+    let mut errors: Vec<f64> = Vec::with_capacity(edges.len());
+    for (edge, analytic) in edges.iter().zip(analytic.iter()) {
+        let mut max_error: f64 = 0.0;
+        for j in 0..analytic.len() {
+            let mut weights: Vector = edge.borrow().weights();
+            let original: f64 = weights[j];
+
+            weights[j] = original + epsilon;
+            edge.borrow_mut().set_weights(&weights);
+            let loss_plus: Result<f64, KanError> = loss(kan);
+            weights[j] = original - epsilon;
+            edge.borrow_mut().set_weights(&weights);
+            let loss_minus: Result<f64, KanError> = loss(kan);
+            weights[j] = original;
+            edge.borrow_mut().set_weights(&weights);
+
+            let numerical: f64 = (loss_plus? - loss_minus?) / (2.0 * epsilon);
+            let scale: f64 = analytic[j].abs().max(numerical.abs());
+            if scale > 0.0 {
+                max_error = max_error.max((analytic[j] - numerical).abs() / scale);
+            }
+        }
+        errors.push(max_error);
+    }
+
+    Ok(Vector::new(errors))
+}
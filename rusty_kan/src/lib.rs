@@ -1,5 +1,35 @@
+pub mod error;
 pub mod data_structures;
 pub mod kan;
+pub mod compiled;
+pub mod quantized;
+pub mod single;
+pub mod onnx;
+pub mod pykan;
+pub mod codegen;
+pub mod dot;
+pub mod plot;
+pub mod topology;
+pub mod expr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "instrument")]
+pub mod instrument;
+pub mod loss;
+pub mod datasets;
+pub mod scaler;
+pub mod attribution;
+pub mod callback;
+pub mod trainer;
+pub mod ensemble;
+pub mod optimizer;
+pub mod scheduler;
+pub mod init;
+pub mod builder;
+pub mod config;
+pub mod bench_utils;
 
 #[cfg(test)]
 pub mod tests;
\ No newline at end of file
@@ -0,0 +1,183 @@
+use crate::data_structures::vector::Vector;
+use crate::error::KanError;
+use serde::{Serialize, Deserialize};
+
+/// A loss function measures how far the output values of a KAN are from the target values.
+/// It is used to seed the gradient of the backward pass.
+///
+/// The Loss enum provides methods to compute the loss and its gradient with respect to the output values of the KAN.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Loss {
+    /// Mean squared error, averaged over the output nodes. Used for regression.
+    MeanSquaredError,
+    /// Categorical cross-entropy between the softmax of the output values and a one-hot target. Used for classification.
+    CrossEntropy,
+}
+
+impl Loss {
+    /// Compute the loss for a given output and target.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - A vector of output values (logits for cross-entropy).
+    ///
+    /// * `target` - A vector of target values (a one-hot vector for cross-entropy).
+    ///
+    /// # Returns
+    ///
+    /// * A result with a scalar representing the loss, or an error if the target does not have one value per output value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let output = Vector::new(vec![2.0, 1.0, 0.1]);
+    /// let target = one_hot(0, 3).unwrap();
+    ///
+    /// let loss = Loss::CrossEntropy.compute(&output, &target).unwrap();
+    /// ```
+    pub fn compute(&self, output: &Vector, target: &Vector) -> Result<f64, KanError> {
+        check_target(output, target)?;
+        Ok(match self {
+            Loss::MeanSquaredError => {
+                let difference: Vector = output - target;
+                difference.dot(&difference) / output.len() as f64
+            }
+            Loss::CrossEntropy => {
+                let log_sum_exp: f64 = log_sum_exp(output);
+                -output.elements.iter().zip(target.elements.iter()).map(|(z, t)| t * (z - log_sum_exp)).sum::<f64>()
+            }
+        })
+    }
+
+    /// Compute the gradient of the loss with respect to the output values.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - A vector of output values (logits for cross-entropy).
+    ///
+    /// * `target` - A vector of target values (a one-hot vector for cross-entropy).
+    ///
+    /// # Returns
+    ///
+    /// * A result with a vector where the i-th entry is the gradient of the loss with respect to the i-th output value, or an error as in `compute`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let output = Vector::new(vec![2.0, 1.0, 0.1]);
+    /// let target = one_hot(0, 3).unwrap();
+    ///
+    /// let gradient = Loss::CrossEntropy.gradient(&output, &target).unwrap();
+    /// ```
+    pub fn gradient(&self, output: &Vector, target: &Vector) -> Result<Vector, KanError> {
+        check_target(output, target)?;
+        Ok(match self {
+            Loss::MeanSquaredError => &(output - target) * (2.0 / output.len() as f64),
+            // The softmax and the cross-entropy combine into probabilities minus targets.
+            Loss::CrossEntropy => &softmax(output) - target,
+        })
+    }
+}
+
+/// Check that a target has one value per output value.
+fn check_target(output: &Vector, target: &Vector) -> Result<(), KanError> {
+    if output.len() != target.len() {
+        return Err(KanError::mismatch("target values, one per output value", output.len(), target.len()));
+    }
+    Ok(())
+}
+
+/// A reduction determines how the losses of the samples in a batch are combined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reduction {
+    /// The mean of the per-sample losses.
+    Mean,
+    /// The sum of the per-sample losses.
+    Sum,
+    /// The per-sample losses without reduction.
+    None,
+}
+
+impl Reduction {
+    /// Reduce a vector of per-sample losses.
+    ///
+    /// # Arguments
+    ///
+    /// * `losses` - A vector where the ith element is the loss of the ith sample.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a vector with a single element for `Mean` and `Sum`, or the per-sample losses for `None`, or an error if the mean of no losses is taken.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let losses = Vector::new(vec![0.5, 1.5]);
+    /// let total = Reduction::Sum.reduce(losses).unwrap(); // [2.0]
+    /// ```
+    pub fn reduce(&self, losses: Vector) -> Result<Vector, KanError> {
+        match self {
+            Reduction::Mean => {
+                if losses.is_empty() {
+                    return Err(KanError::DomainError("The mean of an empty batch of losses is undefined."));
+                }
+                Ok(Vector::new(vec![losses.sum_stable() / losses.len() as f64]))
+            }
+            Reduction::Sum => Ok(Vector::new(vec![losses.sum_stable()])),
+            Reduction::None => Ok(losses),
+        }
+    }
+}
+
+/// The softmax function maps a vector of logits to a probability distribution.
+///
+/// # Arguments
+///
+/// * `logits` - A vector of logits.
+///
+/// # Returns
+///
+/// * A vector of probabilities that sum to one.
+///
+/// # Example
+///
+/// ```
+/// let logits = Vector::new(vec![2.0, 1.0, 0.1]);
+/// let probabilities = softmax(&logits);
+/// ```
+pub fn softmax(logits: &Vector) -> Vector {
+    let log_sum_exp: f64 = log_sum_exp(logits);
+    logits.map(|z| (z - log_sum_exp).exp())
+}
+
+/// Create a one-hot encoded vector for a given class.
+///
+/// # Arguments
+///
+/// * `class` - The index of the class.
+///
+/// * `classes` - The number of classes.
+///
+/// # Returns
+///
+/// * A result with a vector of length `classes` with a one at index `class` and zeros elsewhere, or an error if the class index is not smaller than the number of classes.
+///
+/// # Example
+///
+/// ```
+/// let target = one_hot(1, 3).unwrap(); // [0.0, 1.0, 0.0]
+/// ```
+pub fn one_hot(class: usize, classes: usize) -> Result<Vector, KanError> {
+    if class >= classes {
+        return Err(KanError::DomainError("The class index must be smaller than the number of classes."));
+    }
+    let mut result: Vector = Vector::zeros(classes);
+    result[class] = 1.0;
+    Ok(result)
+}
+
+/// Numerically stable log(sum(exp(x))).
+fn log_sum_exp(x: &Vector) -> f64 {
+    let max: f64 = x.elements.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    max + x.elements.iter().map(|z| (z - max).exp()).sum::<f64>().ln()
+}
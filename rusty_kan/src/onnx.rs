@@ -0,0 +1,331 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::Aggregation};
+use crate::error::KanError;
+use crate::kan::KAN;
+use crate::compiled::CompiledKAN;
+use std::fs;
+use std::path::Path;
+
+/// The version of the ONNX intermediate representation written by `OnnxModel::to_bytes`.
+const IR_VERSION: u64 = 7;
+
+/// The version of the default ONNX operator set used by the exported graph.
+const OPSET_VERSION: u64 = 13;
+
+/// The ONNX element type of single precision floats.
+const FLOAT: u64 = 1;
+
+/// A layer of a KAN lowered to the ONNX operators MatMul, Sub, Relu, and Add.
+/// Every edge is replaced by a piecewise-linear interpolation of its values at evenly spaced breakpoints over the domain of its activation, written as a sum of hinges relu(x - t_k).
+/// The value of the layer is y = relu(x P - t) W + b, so inputs outside the domain of an edge give the value at the nearest end of the domain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnnxLayer {
+    pub selection: Matrix, // Entry (i, h) is 1 if hinge h belongs to an edge that starts at input i
+    pub thresholds: Vector, // Breakpoint of every hinge
+    pub weights: Matrix, // Entry (h, j) is the change of slope at hinge h of an edge that ends at node j
+    pub bias: Vector, // Sum of the values at the start of the domain of the incoming edges of every node
+}
+
+impl OnnxLayer {
+    /// Lower the incoming edges of the nodes of a layer to hinges.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The incoming edges of every node in the layer.
+    ///
+    /// * `inputs` - The number of inputs of the layer.
+    ///
+    /// * `segments` - The number of linear segments per edge.
+    ///
+    /// # Returns
+    ///
+    /// * The lowered layer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let layer = OnnxLayer::from_edges(&kan.compile().layers[0], 2, 32);
+    /// ```
+    pub fn from_edges(nodes: &[Vec<Edge>], inputs: usize, segments: usize) -> OnnxLayer {
+        if segments == 0 {
+            panic!("The number of segments must be greater than 0.");
+        }
+        let hinges_per_edge: usize = segments + 1;
+        let num_edges: usize = nodes.iter().map(|node| node.len()).sum();
+        let mut layer: OnnxLayer = OnnxLayer {
+            selection: Matrix::zeros(inputs, num_edges * hinges_per_edge),
+            thresholds: Vector::zeros(num_edges * hinges_per_edge),
+            weights: Matrix::zeros(num_edges * hinges_per_edge, nodes.len()),
+            bias: Vector::zeros(nodes.len()),
+        };
+
+        let edges = nodes.iter().enumerate().flat_map(|(j, node)| node.iter().map(move |edge| (j, edge)));
+        for (e, (j, edge)) in edges.enumerate() {
+            if edge.start >= inputs {
+                panic!("The start index of an incoming edge must be smaller than the number of nodes in the previous layer.");
+            }
+            let (low, high): (f64, f64) = edge.activation.domain();
+            if low >= high {
+                panic!("The domain of an edge must have a positive width.");
+            }
+            let breakpoints: Vec<f64> = (0..=segments).map(|k| low + (high - low) * k as f64 / segments as f64).collect();
+            let values: Vec<f64> = breakpoints.iter().map(|&t| edge.value(t)).collect();
+            let slopes: Vec<f64> = (0..segments).map(|k| (values[k + 1] - values[k]) / (breakpoints[k + 1] - breakpoints[k])).collect();
+
+            layer.bias[j] += values[0];
+            for (k, &breakpoint) in breakpoints.iter().enumerate() {
+                let h: usize = e * hinges_per_edge + k;
+                // The slope starts at 0 before the first breakpoint and returns to 0 after the last one
+                let previous_slope: f64 = if k > 0 { slopes[k - 1] } else { 0.0 };
+                let slope: f64 = if k < segments { slopes[k] } else { 0.0 };
+                layer.selection[edge.start][h] = 1.0;
+                layer.thresholds[h] = breakpoint;
+                layer.weights[h][j] = slope - previous_slope;
+            }
+        }
+        layer
+    }
+
+    /// Compute the value of the lowered layer, as the exported graph does.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector of input values to the layer.
+    ///
+    /// # Returns
+    ///
+    /// * A vector where the j-th entry is the value of the j-th node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let output = layer.forward(&Vector::new(vec![0.2, 0.7]));
+    /// ```
+    pub fn forward(&self, input: &Vector) -> Vector {
+        let projected: Vector = (&input.to_matrix() * &self.selection).row(0);
+        let hinges: Vector = Vector::new(projected.iter().zip(self.thresholds.iter()).map(|(&x, &t)| (x - t).max(0.0)).collect());
+        let combined: Vector = (&hinges.to_matrix() * &self.weights).row(0);
+        combined + self.bias.clone()
+    }
+}
+
+/// A KAN lowered to a graph of ONNX operators, which can be served from ONNX runtimes.
+/// It is created with `OnnxModel::from_kan` and written with `OnnxModel::save`; the graph has one input "input" of shape [N, inputs] and one output "output", in single precision.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnnxModel {
+    pub inputs: usize, // Number of inputs of the first layer
+    pub layers: Vec<OnnxLayer>,
+}
+
+impl OnnxModel {
+    /// Lower a KAN to a graph of ONNX operators, approximating every edge by a given number of linear segments.
+    /// The scaler of the KAN is folded into the thresholds and weights of the first layer.
+    /// The number of inputs is one more than the largest start index of the edges of the first layer.
+    /// Product nodes, normalizations, and skip edges cannot be lowered, since the graph only adds the hinges of the incoming edges of the previous layer.
+    ///
+    /// # Arguments
+    ///
+    /// * `kan` - A KAN.
+    ///
+    /// * `segments` - The number of linear segments per edge.
+    ///
+    /// # Returns
+    ///
+    /// * The lowered model.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let model = OnnxModel::from_kan(&kan, 32);
+    /// ```
+    pub fn from_kan(kan: &KAN, segments: usize) -> OnnxModel {
+        let compiled: CompiledKAN = kan.compile();
+        if compiled.aggregations.iter().flatten().any(|&aggregation| aggregation != Aggregation::Sum) {
+            panic!("Product nodes cannot be lowered to ONNX.");
+        }
+        if compiled.norms.iter().any(Option::is_some) {
+            panic!("Layers with a normalization cannot be lowered to ONNX.");
+        }
+        if compiled.layers.iter().flatten().flatten().any(|edge| edge.skip) {
+            panic!("Skip edges cannot be lowered to ONNX.");
+        }
+        let inputs: usize = compiled.num_inputs();
+        let layers: Vec<Vec<Vec<Edge>>> = compiled.layers;
+        let mut width: usize = inputs;
+        let mut lowered: Vec<OnnxLayer> = Vec::with_capacity(layers.len());
+        for nodes in layers.iter() {
+            lowered.push(OnnxLayer::from_edges(nodes, width, segments));
+            width = nodes.len();
+        }
+        // A hinge relu((x - offset) / scale - t) of a scaled input is relu(x - offset - scale * t) / scale
+        if let (Some(scaler), Some(first)) = (&compiled.scaler, lowered.first_mut()) {
+            for i in 0..inputs {
+                let (offset, scale): (f64, f64) = scaler.affine(i);
+                for h in 0..first.thresholds.len() {
+                    if first.selection[i][h] == 1.0 {
+                        first.thresholds[h] = offset + scale * first.thresholds[h];
+                        for j in 0..first.weights.cols {
+                            first.weights[h][j] /= scale;
+                        }
+                    }
+                }
+            }
+        }
+        OnnxModel { inputs, layers: lowered }
+    }
+
+    /// Compute the values of the output nodes, as the exported graph does.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector representing the input values to the first layer.
+    ///
+    /// # Returns
+    ///
+    /// * A vector where the i-th entry is the value of the i-th node in the last layer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let output = model.forward(&Vector::new(vec![0.2, 0.7]));
+    /// ```
+    pub fn forward(&self, input: &Vector) -> Vector {
+        self.layers.iter().fold(input.clone(), |activations, layer| layer.forward(&activations))
+    }
+
+    /// Encode the model as an ONNX protobuf message.
+    ///
+    /// # Returns
+    ///
+    /// * The bytes of the ONNX model.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let bytes = model.to_bytes();
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut graph: Vec<u8> = Vec::new();
+        let mut current: String = "input".to_string();
+        for (l, layer) in self.layers.iter().enumerate() {
+            let output: String = if l + 1 == self.layers.len() { "output".to_string() } else { format!("layer_{}", l) };
+            write_message(&mut graph, 5, &tensor(&format!("selection_{}", l), &[layer.selection.rows, layer.selection.cols], &layer.selection.elements));
+            write_message(&mut graph, 5, &tensor(&format!("thresholds_{}", l), &[layer.thresholds.len()], &layer.thresholds.elements));
+            write_message(&mut graph, 5, &tensor(&format!("weights_{}", l), &[layer.weights.rows, layer.weights.cols], &layer.weights.elements));
+            write_message(&mut graph, 5, &tensor(&format!("bias_{}", l), &[layer.bias.len()], &layer.bias.elements));
+            write_message(&mut graph, 1, &node("MatMul", &[&current, &format!("selection_{}", l)], &format!("projected_{}", l)));
+            write_message(&mut graph, 1, &node("Sub", &[&format!("projected_{}", l), &format!("thresholds_{}", l)], &format!("shifted_{}", l)));
+            write_message(&mut graph, 1, &node("Relu", &[&format!("shifted_{}", l)], &format!("hinges_{}", l)));
+            write_message(&mut graph, 1, &node("MatMul", &[&format!("hinges_{}", l), &format!("weights_{}", l)], &format!("combined_{}", l)));
+            write_message(&mut graph, 1, &node("Add", &[&format!("combined_{}", l), &format!("bias_{}", l)], &output));
+            current = output;
+        }
+        let outputs: usize = self.layers.last().map_or(self.inputs, |layer| layer.bias.len());
+        write_bytes(&mut graph, 2, b"rusty_kan");
+        write_message(&mut graph, 11, &value_info("input", self.inputs));
+        write_message(&mut graph, 12, &value_info(&current, outputs));
+
+        let mut opset: Vec<u8> = Vec::new();
+        write_bytes(&mut opset, 1, b"");
+        write_varint_field(&mut opset, 2, OPSET_VERSION);
+
+        let mut model: Vec<u8> = Vec::new();
+        write_varint_field(&mut model, 1, IR_VERSION);
+        write_bytes(&mut model, 2, b"rusty_kan");
+        write_message(&mut model, 7, &graph);
+        write_message(&mut model, 8, &opset);
+        model
+    }
+
+    /// Write the model to an ONNX file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the ONNX file.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the model was written successfully.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// model.save("kan.onnx").unwrap();
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), KanError> {
+        fs::write(path, self.to_bytes()).map_err(|_| KanError::Io("Failed to write the ONNX file."))
+    }
+}
+
+/// A NodeProto applying an operator to named inputs.
+fn node(op_type: &str, inputs: &[&str], output: &str) -> Vec<u8> {
+    let mut message: Vec<u8> = Vec::new();
+    for input in inputs {
+        write_bytes(&mut message, 1, input.as_bytes());
+    }
+    write_bytes(&mut message, 2, output.as_bytes());
+    write_bytes(&mut message, 3, output.as_bytes());
+    write_bytes(&mut message, 4, op_type.as_bytes());
+    message
+}
+
+/// A TensorProto of single precision floats with the given name and shape.
+fn tensor(name: &str, dims: &[usize], values: &[f64]) -> Vec<u8> {
+    let mut message: Vec<u8> = Vec::new();
+    for &dim in dims {
+        write_varint_field(&mut message, 1, dim as u64);
+    }
+    write_varint_field(&mut message, 2, FLOAT);
+    write_bytes(&mut message, 8, name.as_bytes());
+    let data: Vec<u8> = values.iter().flat_map(|&x| (x as f32).to_le_bytes()).collect();
+    write_bytes(&mut message, 9, &data);
+    message
+}
+
+/// A ValueInfoProto of a single precision tensor of shape [N, width] with a symbolic batch size N.
+fn value_info(name: &str, width: usize) -> Vec<u8> {
+    let mut batch: Vec<u8> = Vec::new();
+    write_bytes(&mut batch, 2, b"N");
+    let mut features: Vec<u8> = Vec::new();
+    write_varint_field(&mut features, 1, width as u64);
+    let mut shape: Vec<u8> = Vec::new();
+    write_message(&mut shape, 1, &batch);
+    write_message(&mut shape, 1, &features);
+
+    let mut tensor_type: Vec<u8> = Vec::new();
+    write_varint_field(&mut tensor_type, 1, FLOAT);
+    write_message(&mut tensor_type, 2, &shape);
+    let mut type_proto: Vec<u8> = Vec::new();
+    write_message(&mut type_proto, 1, &tensor_type);
+
+    let mut message: Vec<u8> = Vec::new();
+    write_bytes(&mut message, 1, name.as_bytes());
+    write_message(&mut message, 2, &type_proto);
+    message
+}
+
+/// Append an unsigned integer in the variable-length encoding of protobuf.
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+/// Append an integer field.
+fn write_varint_field(buffer: &mut Vec<u8>, field: u64, value: u64) {
+    write_varint(buffer, field << 3);
+    write_varint(buffer, value);
+}
+
+/// Append a length-delimited field of strings or bytes.
+fn write_bytes(buffer: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    write_varint(buffer, (field << 3) | 2);
+    write_varint(buffer, bytes.len() as u64);
+    buffer.extend_from_slice(bytes);
+}
+
+/// Append an embedded message field.
+fn write_message(buffer: &mut Vec<u8>, field: u64, message: &[u8]) {
+    write_bytes(buffer, field, message);
+}
@@ -0,0 +1,248 @@
+use crate::data_structures::{vector::Vector, edge::Edge};
+use crate::error::KanError;
+use serde::{Serialize, Deserialize};
+use std::cell::{RefCell, RefMut};
+use std::rc::Rc;
+
+/// An optimizer turns the gradients of the control points into updates of the control points.
+///
+/// The Optimizer enum provides a method to apply one update step to a list of edges, keeping its moment estimates in an `OptimizerState`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Optimizer {
+    /// Stochastic gradient descent.
+    Sgd,
+    /// Stochastic gradient descent with momentum.
+    Momentum { beta: f64 },
+    /// Adam, with bias-corrected first and second moment estimates.
+    Adam { beta1: f64, beta2: f64, epsilon: f64 },
+}
+
+/// The state of an optimizer across update steps.
+/// It is represented as the number of steps taken and the first and second moment estimates of every edge, in the order of `KAN::edges`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct OptimizerState {
+    pub step: usize,
+    pub first_moments: Vec<Vector>,
+    pub second_moments: Vec<Vector>,
+}
+
+impl Optimizer {
+    /// Create an Adam optimizer with the default hyperparameters (beta1 = 0.9, beta2 = 0.999, epsilon = 1e-8).
+    ///
+    /// # Returns
+    ///
+    /// * An Adam optimizer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let optimizer = Optimizer::adam();
+    /// ```
+    pub fn adam() -> Optimizer {
+        Optimizer::Adam { beta1: 0.9, beta2: 0.999, epsilon: 1e-8 }
+    }
+
+    /// Apply one update step to the control points of the given edges.
+    /// The step direction of every edge is stored as its gradient and applied with `Edge::update_weights`.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state of the optimizer, initialised on the first step.
+    ///
+    /// * `edges` - A list of edges.
+    ///
+    /// * `gradients` - A list of gradients of the loss with respect to the control points, one per edge.
+    ///
+    /// * `learning_rate` - A scalar representing the learning rate.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the update was successful.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut state = OptimizerState::default();
+    /// Optimizer::adam().step(&mut state, &kan.edges(), &gradients, 0.01).unwrap();
+    /// ```
+    pub fn step(&self, state: &mut OptimizerState, edges: &[Rc<RefCell<Edge>>], gradients: &[Vector], learning_rate: f64) -> Result<(), KanError> {
+        if edges.len() != gradients.len() {
+            return Err(KanError::mismatch("gradients, one per edge", edges.len(), gradients.len()));
+        }
+        if state.first_moments.len() != edges.len() || state.first_moments.iter().zip(gradients.iter()).any(|(m, g)| m.len() != g.len()) {
+            state.step = 0;
+            state.first_moments = gradients.iter().map(|g| Vector::zeros(g.len())).collect();
+            state.second_moments = gradients.iter().map(|g| Vector::zeros(g.len())).collect();
+        }
+        state.step += 1;
+
+        // The moments and the step direction are updated in place to avoid allocating on every step
+        for (i, edge) in edges.iter().enumerate() {
+            let mut edge: RefMut<Edge> = edge.borrow_mut();
+            match *self {
+                Optimizer::Sgd => edge.gradient.elements.clone_from(&gradients[i].elements),
+                Optimizer::Momentum { beta } => {
+                    state.first_moments[i] *= beta;
+                    state.first_moments[i] += &gradients[i];
+                    edge.gradient.elements.clone_from(&state.first_moments[i].elements);
+                }
+                Optimizer::Adam { beta1, beta2, epsilon } => {
+                    state.first_moments[i] *= beta1;
+                    state.first_moments[i].axpy(1.0 - beta1, &gradients[i]);
+                    for (v, &g) in state.second_moments[i].iter_mut().zip(gradients[i].iter()) {
+                        *v = *v * beta2 + g * g * (1.0 - beta2);
+                    }
+                    let first_correction: f64 = 1.0 - beta1.powi(state.step as i32);
+                    let second_correction: f64 = 1.0 - beta2.powi(state.step as i32);
+                    edge.gradient.elements.clone_from(&state.first_moments[i].elements);
+                    for (direction, &v) in edge.gradient.iter_mut().zip(state.second_moments[i].iter()) {
+                        *direction = (*direction / first_correction) / ((v / second_correction).sqrt() + epsilon);
+                    }
+                }
+            }
+            edge.update_weights(learning_rate)?;
+        }
+        Ok(())
+    }
+}
+
+/// L-BFGS is a quasi-Newton optimizer for full-batch training of small networks, as used in the KAN paper.
+/// It approximates the inverse Hessian from the most recent steps and gradient changes, and chooses the step length with a backtracking line search, so it needs no learning rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lbfgs {
+    pub history_size: usize, // Number of recent steps and gradient changes that approximate the curvature
+    pub tolerance: f64, // An iteration leaves the parameters unchanged once the largest absolute entry of the gradient is below the tolerance
+    pub max_line_search: usize, // Maximum number of halvings of the step length in the line search
+    pub sufficient_decrease: f64, // Fraction of the decrease predicted by the gradient that a step must achieve (the Armijo condition)
+}
+
+/// The state of L-BFGS across iterations.
+/// It is represented as the recent steps and gradient changes, oldest first, and the parameters, loss, and gradient at the end of the last iteration, which the next iteration reuses instead of evaluating them again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LbfgsState {
+    pub steps: Vec<Vector>,
+    pub gradient_changes: Vec<Vector>,
+    pub params: Option<Vector>,
+    pub loss: f64,
+    pub gradient: Vector,
+}
+
+impl Default for LbfgsState {
+    fn default() -> LbfgsState {
+        LbfgsState { steps: Vec::new(), gradient_changes: Vec::new(), params: None, loss: f64::INFINITY, gradient: Vector::zeros(0) }
+    }
+}
+
+impl Default for Lbfgs {
+    fn default() -> Lbfgs {
+        Lbfgs { history_size: 10, tolerance: 1e-7, max_line_search: 20, sufficient_decrease: 1e-4 }
+    }
+}
+
+impl Lbfgs {
+    /// Run one iteration of L-BFGS on a flat vector of parameters.
+    /// The search direction is computed from the history with the two-loop recursion, and the step is halved until the loss decreases sufficiently.
+    /// If the direction does not decrease the loss, the history is cleared and the negative gradient is used instead. If no step length decreases the loss, the parameters are left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state of L-BFGS, cleared if the number of parameters changed.
+    ///
+    /// * `params` - A vector of parameters, updated in place.
+    ///
+    /// * `objective` - A function that returns the loss and its gradient with respect to the parameters at a given vector of parameters.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the loss at the updated parameters, or the first error of the objective.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut state = LbfgsState::default();
+    /// let loss = Lbfgs::default().step(&mut state, &mut params, |x| Ok((x.dot(x), x * 2.0))).unwrap();
+    /// ```
+    pub fn step<F>(&self, state: &mut LbfgsState, params: &mut Vector, mut objective: F) -> Result<f64, KanError>
+    where
+        F: FnMut(&Vector) -> Result<(f64, Vector), KanError>,
+    {
+        if self.history_size == 0 {
+            panic!("The history of L-BFGS must hold at least one step.");
+        }
+        if state.params.as_ref().is_some_and(|previous| previous.len() != params.len()) {
+            *state = LbfgsState::default();
+        }
+        if state.params.as_ref() != Some(params) {
+            let (loss, gradient) = objective(params)?;
+            state.params = Some(params.clone());
+            state.loss = loss;
+            state.gradient = gradient;
+        }
+        if state.gradient.len() != params.len() {
+            return Err(KanError::mismatch("gradient entries, one per parameter", params.len(), state.gradient.len()));
+        }
+        if state.gradient.norm(f64::INFINITY) < self.tolerance {
+            return Ok(state.loss);
+        }
+
+        let mut direction: Vector = self.direction(state);
+        let mut slope: f64 = state.gradient.dot(&direction);
+        if slope >= 0.0 || slope.is_nan() {
+            state.steps.clear();
+            state.gradient_changes.clear();
+            direction = &state.gradient * -1.0;
+            slope = state.gradient.dot(&direction);
+        }
+
+        // The first step has no curvature information, so its length is bounded by the size of the gradient
+        let mut step_length: f64 = if state.steps.is_empty() { (1.0 / state.gradient.norm(1.0)).min(1.0) } else { 1.0 };
+        for _ in 0..self.max_line_search {
+            let mut candidate: Vector = params.clone();
+            candidate.axpy(step_length, &direction);
+            let (loss, gradient) = objective(&candidate)?;
+            if loss.is_finite() && loss <= state.loss + self.sufficient_decrease * step_length * slope {
+                let step: Vector = &candidate - &*params;
+                let gradient_change: Vector = &gradient - &state.gradient;
+                // Pairs without positive curvature would make the inverse Hessian indefinite
+                if step.dot(&gradient_change) > 1e-10 {
+                    if state.steps.len() == self.history_size {
+                        state.steps.remove(0);
+                        state.gradient_changes.remove(0);
+                    }
+                    state.steps.push(step);
+                    state.gradient_changes.push(gradient_change);
+                }
+                *params = candidate;
+                state.params = Some(params.clone());
+                state.loss = loss;
+                state.gradient = gradient;
+                return Ok(loss);
+            }
+            step_length *= 0.5;
+        }
+        state.steps.clear();
+        state.gradient_changes.clear();
+        Ok(state.loss)
+    }
+
+    /// The search direction -H g of the two-loop recursion, where H is the inverse Hessian approximated from the history.
+    fn direction(&self, state: &LbfgsState) -> Vector {
+        let mut q: Vector = state.gradient.clone();
+        let rhos: Vec<f64> = state.steps.iter().zip(state.gradient_changes.iter()).map(|(s, y)| 1.0 / s.dot(y)).collect();
+        let mut alphas: Vec<f64> = vec![0.0; state.steps.len()];
+        for i in (0..state.steps.len()).rev() {
+            alphas[i] = rhos[i] * state.steps[i].dot(&q);
+            q.axpy(-alphas[i], &state.gradient_changes[i]);
+        }
+        // The initial inverse Hessian is the identity scaled to the curvature of the most recent pair
+        if let (Some(s), Some(y)) = (state.steps.last(), state.gradient_changes.last()) {
+            q *= s.dot(y) / y.dot(y);
+        }
+        for i in 0..state.steps.len() {
+            let beta: f64 = rhos[i] * state.gradient_changes[i].dot(&q);
+            q.axpy(alphas[i] - beta, &state.steps[i]);
+        }
+        q *= -1.0;
+        q
+    }
+}
@@ -0,0 +1,205 @@
+use crate::data_structures::edge::Edge;
+use crate::error::KanError;
+use crate::kan::KAN;
+use crate::trainer::History;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// The size of a chart in pixels.
+const WIDTH: f64 = 320.0;
+const HEIGHT: f64 = 240.0;
+
+/// The space around the plot area for the title and the axis labels.
+const MARGIN: f64 = 40.0;
+
+/// An SVG image rendered by the plot functions.
+/// `Display` prints its source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Svg {
+    pub source: String,
+}
+
+impl Svg {
+    /// Write the image to a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the SVG file.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the image was written successfully.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// plot::loss_curve(&history).save("loss.svg").unwrap();
+    /// ```
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), KanError> {
+        fs::write(path, &self.source).map_err(|_| KanError::Io("Failed to write the plot file."))
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// A line of a chart, drawn through its points in order.
+struct Series {
+    label: &'static str,
+    color: &'static str,
+    dashed: bool,
+    points: Vec<(f64, f64)>,
+}
+
+/// Format the label of an axis bound, in scientific notation for very large or small values.
+fn tick(value: f64) -> String {
+    if value != 0.0 && (value.abs() >= 1e4 || value.abs() < 1e-2) { format!("{:.2e}", value) } else { format!("{:.2}", value) }
+}
+
+/// The range of the values, widened if it is empty and (0, 1) if there are no finite values.
+fn range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (low, high): (f64, f64) = values.filter(|value| value.is_finite()).fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), value| (low.min(value), high.max(value)));
+    if low > high {
+        (0.0, 1.0)
+    } else if low == high {
+        (low - 0.5, high + 0.5)
+    } else {
+        (low, high)
+    }
+}
+
+/// Render a line chart of several series with a title, a frame labelled with the bounds of both axes, a line at y = 0 if it is in range, and a legend.
+/// On a logarithmic y axis the series are drawn by the base 10 logarithm of their values. Points with a value that is not finite are left out.
+fn chart(title: &str, series: &[Series], log_y: bool) -> Svg {
+    let scaled: Vec<Vec<(f64, f64)>> = series.iter().map(|series| series.points.iter().map(|&(x, y)| (x, if log_y { y.log10() } else { y })).collect()).collect();
+    let (x_min, x_max): (f64, f64) = range(scaled.iter().flat_map(|points| points.iter().map(|&(x, _)| x)));
+    let (y_min, y_max): (f64, f64) = range(scaled.iter().flat_map(|points| points.iter().map(|&(_, y)| y)));
+    let y_tick = |y: f64| if log_y { tick(10.0_f64.powf(y)) } else { tick(y) };
+    let (left, right, top, bottom): (f64, f64, f64, f64) = (MARGIN, WIDTH - MARGIN / 2.0, MARGIN, HEIGHT - MARGIN);
+    let x_pixel = |x: f64| left + (x - x_min) / (x_max - x_min) * (right - left);
+    let y_pixel = |y: f64| bottom - (y - y_min) / (y_max - y_min) * (bottom - top);
+
+    let mut svg: String = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"10\">\n", WIDTH, HEIGHT, WIDTH, HEIGHT);
+    svg += &format!("<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n", WIDTH, HEIGHT);
+    svg += &format!("<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" font-size=\"12\">{}</text>\n", WIDTH / 2.0, MARGIN / 2.0, title);
+    svg += &format!("<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"none\" stroke=\"black\"/>\n", left, top, right - left, bottom - top);
+    if !log_y && y_min < 0.0 && y_max > 0.0 {
+        svg += &format!("<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"lightgray\"/>\n", left, y_pixel(0.0), right, y_pixel(0.0));
+    }
+    svg += &format!("<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"start\">{}</text>\n", left, bottom + 14.0, tick(x_min));
+    svg += &format!("<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n", right, bottom + 14.0, tick(x_max));
+    svg += &format!("<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n", left - 4.0, bottom, y_tick(y_min));
+    svg += &format!("<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n", left - 4.0, top + 8.0, y_tick(y_max));
+    for (i, (series, points)) in series.iter().zip(scaled.iter()).enumerate() {
+        let points: Vec<String> = points.iter().filter(|(x, y)| x.is_finite() && y.is_finite()).map(|&(x, y)| format!("{:.2},{:.2}", x_pixel(x), y_pixel(y))).collect();
+        let dash: &str = if series.dashed { " stroke-dasharray=\"4 3\"" } else { "" };
+        svg += &format!("<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"{}/>\n", points.join(" "), series.color, dash);
+        let y: f64 = top + 12.0 + 12.0 * i as f64;
+        svg += &format!("<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"1.5\"{}/>\n", right - 70.0, y - 3.0, right - 55.0, y - 3.0, series.color, dash);
+        svg += &format!("<text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n", right - 50.0, y, series.label);
+    }
+    svg += "</svg>\n";
+    Svg { source: svg }
+}
+
+/// Render the activation of an edge over the domain of its activation as an SVG line chart, like the plots of the activations in pykan.
+/// The chart shows the value of the edge and its two terms, the weighted base function and the weighted activation, and its title names the edge and its score if it has one.
+///
+/// # Arguments
+///
+/// * `edge` - The edge.
+///
+/// * `samples` - The number of equally spaced inputs the lines are drawn through, at least 2.
+///
+/// # Returns
+///
+/// * The SVG image.
+///
+/// # Example
+///
+/// ```
+/// plot::edge_svg(&kan.edges()[0].borrow(), 200).save("edge.svg").unwrap();
+/// ```
+pub fn edge_svg(edge: &Edge, samples: usize) -> Svg {
+    if samples < 2 {
+        panic!("A plot requires at least 2 samples.");
+    }
+    let (low, high): (f64, f64) = edge.activation.domain();
+    let ts: Vec<f64> = (0..samples).map(|i| low + (high - low) * i as f64 / (samples - 1) as f64).collect();
+    let values: Vec<(f64, f64)> = ts.iter().map(|&t| (t, edge.value(t))).collect();
+    let base: Vec<(f64, f64)> = ts.iter().map(|&t| (t, edge.base_weight * edge.base.eval(t))).collect();
+    let activation: Vec<(f64, f64)> = values.iter().zip(base.iter()).map(|(&(t, value), &(_, base))| (t, value - base)).collect();
+    let mut title: String = format!("{} {} \u{2192} {}", if edge.skip { "skip edge" } else { "edge" }, edge.start, edge.end);
+    if let Some(score) = edge.score {
+        title += &format!(" (score {:.3})", score);
+    }
+    chart(&title, &[
+        Series { label: "edge", color: "#1f4e9c", dashed: false, points: values },
+        Series { label: "base", color: "#888888", dashed: true, points: base },
+        Series { label: "activation", color: "#e07b00", dashed: true, points: activation },
+    ], false)
+}
+
+/// Write the plot of every edge of a KAN to a directory as `layer{l}_{start}_{end}.svg`, or `layer{l}_skip{start}_{end}.svg` for a skip edge, where l is the index of the layer in `KAN::layers`.
+///
+/// # Arguments
+///
+/// * `kan` - The KAN.
+///
+/// * `dir` - The directory, which is created if it does not exist.
+///
+/// # Returns
+///
+/// * The number of files written, or an error if the directory or a file cannot be written.
+///
+/// # Example
+///
+/// ```
+/// plot::plot_all(&kan, "plots").unwrap();
+/// ```
+pub fn plot_all<P: AsRef<Path>>(kan: &KAN, dir: P) -> Result<usize, KanError> {
+    fs::create_dir_all(&dir).map_err(|_| KanError::Io("Failed to create the plot directory."))?;
+    let mut written: usize = 0;
+    for (l, layer) in kan.layers.iter().enumerate() {
+        for node in layer.borrow().nodes.iter() {
+            for edge in node.borrow().incoming.iter() {
+                let edge = edge.borrow();
+                let name: String = format!("layer{}_{}{}_{}.svg", l, if edge.skip { "skip" } else { "" }, edge.start, edge.end);
+                edge_svg(&edge, 200).save(dir.as_ref().join(name))?;
+                written += 1;
+            }
+        }
+    }
+    Ok(written)
+}
+
+/// Render the training loss and, if the run had a validation set, the validation loss of every epoch of a training run as an SVG line chart.
+/// The loss axis is logarithmic when all losses are positive, so the progress of late epochs stays visible.
+///
+/// # Arguments
+///
+/// * `history` - The history returned by `Trainer::fit` or another training method.
+///
+/// # Returns
+///
+/// * The SVG image, with the epochs counted from 1.
+///
+/// # Example
+///
+/// ```
+/// let history = trainer.fit(&inputs, &targets, 100).unwrap();
+/// plot::loss_curve(&history).save("loss.svg").unwrap();
+/// ```
+pub fn loss_curve(history: &History) -> Svg {
+    let curve = |losses: &[f64]| -> Vec<(f64, f64)> { losses.iter().enumerate().map(|(i, &loss)| ((i + 1) as f64, loss)).collect() };
+    let mut series: Vec<Series> = vec![Series { label: "train", color: "#1f4e9c", dashed: false, points: curve(&history.train_loss) }];
+    if !history.validation_loss.is_empty() {
+        series.push(Series { label: "validation", color: "#e07b00", dashed: false, points: curve(&history.validation_loss) });
+    }
+    let log_y: bool = history.train_loss.iter().chain(history.validation_loss.iter()).all(|&loss| loss > 0.0);
+    chart(if log_y { "loss (log scale)" } else { "loss" }, &series, log_y)
+}
@@ -0,0 +1,166 @@
+use crate::data_structures::{vector::Vector, spline::BSpline, edge::{Edge, BaseFn}, node::Node, layer::Layer};
+use crate::error::KanError;
+use crate::kan::KAN;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+/// A tensor of a pykan state dict, with its elements in row-major order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tensor {
+    pub shape: Vec<usize>,
+    pub values: Vec<f64>,
+}
+
+impl Tensor {
+    /// Read a tensor from nested JSON arrays of numbers.
+    fn from_json(value: &Value) -> Result<Tensor, KanError> {
+        match value {
+            Value::Number(number) => Ok(Tensor { shape: vec![], values: vec![number.as_f64().ok_or(KanError::Format("Malformed number in the pykan state."))?] }),
+            Value::Array(items) => {
+                let inner: Vec<Tensor> = items.iter().map(Tensor::from_json).collect::<Result<Vec<Tensor>, KanError>>()?;
+                let inner_shape: Vec<usize> = inner.first().map_or(vec![], |tensor| tensor.shape.clone());
+                if inner.iter().any(|tensor| tensor.shape != inner_shape) {
+                    return Err(KanError::Format("The tensors of the pykan state must not be ragged."));
+                }
+                let mut shape: Vec<usize> = vec![items.len()];
+                shape.extend(inner_shape);
+                Ok(Tensor { shape, values: inner.into_iter().flat_map(|tensor| tensor.values).collect() })
+            }
+            _ => Err(KanError::Format("The pykan state must map every name to nested arrays of numbers.")),
+        }
+    }
+}
+
+/// Parse the state dict of a pykan model from JSON, as written by
+/// `json.dump({name: tensor.tolist() for name, tensor in model.state_dict().items()}, file)`.
+///
+/// # Arguments
+///
+/// * `json` - A JSON object mapping the names of the tensors to nested arrays of numbers.
+///
+/// # Returns
+///
+/// * A map from the names of the tensors to the tensors, or an error if the JSON is malformed.
+///
+/// # Example
+///
+/// ```
+/// let state = parse_state_dict(&fs::read_to_string("state.json").unwrap()).unwrap();
+/// ```
+pub fn parse_state_dict(json: &str) -> Result<HashMap<String, Tensor>, KanError> {
+    let value: Value = serde_json::from_str(json).map_err(|_| KanError::Format("The pykan state is not valid JSON."))?;
+    let object = value.as_object().ok_or(KanError::Format("The pykan state must be a JSON object."))?;
+    object.iter().map(|(name, tensor)| Ok((name.clone(), Tensor::from_json(tensor)?))).collect()
+}
+
+/// Build a KAN that is equivalent to a pykan model (`MultKAN`) from its state dict.
+/// Layer l is read from `act_fun.{l}.grid`, `act_fun.{l}.coef`, `act_fun.{l}.scale_base`, `act_fun.{l}.scale_sp`, and `act_fun.{l}.mask`.
+/// The grid of every input becomes the knots of its B-splines, with the inner grid as the domain, and the base function is SiLU, pykan's default.
+/// The scales of the nodes and subnodes are folded into the weights of the incoming edges; biases, multiplication nodes, and symbolic functions have no counterpart and give an error.
+///
+/// # Arguments
+///
+/// * `state` - The state dict of a pykan model.
+///
+/// # Returns
+///
+/// * A KAN with one layer per layer of the pykan model, or an error if the state is incomplete or uses unsupported features.
+///
+/// # Example
+///
+/// ```
+/// let kan = from_state_dict(&parse_state_dict(&json).unwrap()).unwrap();
+/// ```
+pub fn from_state_dict(state: &HashMap<String, Tensor>) -> Result<KAN, KanError> {
+    let mut layers: Vec<Rc<RefCell<Layer>>> = Vec::new();
+    let mut previous_nodes: Vec<Rc<RefCell<Node>>> = Vec::new();
+    let mut l: usize = 0;
+    while let Some(coef) = state.get(&format!("act_fun.{}.coef", l)) {
+        if coef.shape.len() != 3 {
+            return Err(KanError::Format("The coefficients of a pykan layer must have the shape [inputs, outputs, coefficients]."));
+        }
+        let (inputs, outputs, num_coef): (usize, usize, usize) = (coef.shape[0], coef.shape[1], coef.shape[2]);
+        if l > 0 && inputs != previous_nodes.len() {
+            return Err(KanError::mismatch("inputs of the pykan layer, one per node of the previous layer", previous_nodes.len(), inputs).at_layer(l));
+        }
+        let grid: &Tensor = tensor(state, &format!("act_fun.{}.grid", l), &[inputs, 0])?;
+        let grid_len: usize = grid.shape[1];
+        // The extended grid has G + 2k + 1 points for G + k coefficients of degree k
+        if grid_len <= num_coef + 1 || 2 * num_coef < grid_len {
+            return Err(KanError::Format("The grid of a pykan layer does not match its coefficients."));
+        }
+        let degree: usize = grid_len - num_coef - 1;
+        let scale_base: &Tensor = tensor(state, &format!("act_fun.{}.scale_base", l), &[inputs, outputs])?;
+        let scale_sp: &Tensor = tensor(state, &format!("act_fun.{}.scale_sp", l), &[inputs, outputs])?;
+        let mask: Vec<f64> = optional(state, &format!("act_fun.{}.mask", l), &[inputs, outputs], 1.0)?;
+        if let Some(symbolic_mask) = state.get(&format!("symbolic_fun.{}.mask", l)) {
+            if symbolic_mask.values.iter().any(|&x| x != 0.0) {
+                return Err(KanError::Unsupported("Symbolic functions of pykan models are not supported."));
+            }
+        }
+        // Affine maps after the sum at every node: subnode_scale * x + subnode_bias, then node_scale * x + node_bias
+        let subnode_scale: Vec<f64> = optional(state, &format!("subnode_scale.{}", l), &[outputs], 1.0)?;
+        let node_scale: Vec<f64> = optional(state, &format!("node_scale.{}", l), &[outputs], 1.0)?;
+        let subnode_bias: Vec<f64> = optional(state, &format!("subnode_bias.{}", l), &[outputs], 0.0)?;
+        let node_bias: Vec<f64> = optional(state, &format!("node_bias.{}", l), &[outputs], 0.0)?;
+        if subnode_bias.iter().chain(node_bias.iter()).any(|&x| x != 0.0) {
+            return Err(KanError::Unsupported("Node biases of pykan models are not supported."));
+        }
+
+        let mut nodes: Vec<Rc<RefCell<Node>>> = Vec::with_capacity(outputs);
+        for j in 0..outputs {
+            let mut incoming_edges: Vec<Rc<RefCell<Edge>>> = Vec::with_capacity(inputs);
+            for i in 0..inputs {
+                let points: &[f64] = &grid.values[i * grid_len..(i + 1) * grid_len];
+                let (lower, upper): (f64, f64) = (points[degree], points[grid_len - 1 - degree]);
+                if !(lower.is_finite() && upper.is_finite()) || lower >= upper {
+                    return Err(KanError::Format("The grid of a pykan layer must be increasing."));
+                }
+                let knots: Vector = Vector::new(points.iter().map(|&x| (x - lower) / (upper - lower)).collect());
+                let start: usize = (i * outputs + j) * num_coef;
+                let mut spline: BSpline = BSpline::with_knots(Vector::new(coef.values[start..start + num_coef].to_vec()), knots, degree);
+                spline.set_domain((lower, upper));
+
+                let scale: f64 = mask[i * outputs + j] * subnode_scale[j] * node_scale[j];
+                let mut edge: Edge = Edge::new(i, j, spline, l + 1);
+                edge.base = BaseFn::Silu;
+                edge.base_weight = scale_base.values[i * outputs + j] * scale;
+                edge.spline_weight = scale_sp.values[i * outputs + j] * scale;
+                let edge: Rc<RefCell<Edge>> = Rc::new(RefCell::new(edge));
+                if let Some(previous_node) = previous_nodes.get(i) {
+                    previous_node.borrow_mut().add_outgoing(edge.clone());
+                }
+                incoming_edges.push(edge);
+            }
+            nodes.push(Rc::new(RefCell::new(Node::new(incoming_edges, Vec::new(), l + 1))));
+        }
+        layers.push(Rc::new(RefCell::new(Layer::new(nodes.clone()))));
+        previous_nodes = nodes;
+        l += 1;
+    }
+
+    if layers.is_empty() {
+        return Err(KanError::Format("The pykan state has no layers."));
+    }
+    Ok(KAN::new(layers))
+}
+
+/// A tensor of the state with the given shape, where a dimension of 0 matches any size.
+fn tensor<'a>(state: &'a HashMap<String, Tensor>, name: &str, shape: &[usize]) -> Result<&'a Tensor, KanError> {
+    let tensor: &Tensor = state.get(name).ok_or(KanError::Format("The pykan state is missing a tensor of a layer."))?;
+    if tensor.shape.len() != shape.len() || tensor.shape.iter().zip(shape.iter()).any(|(&size, &expected)| expected != 0 && size != expected) {
+        return Err(KanError::Format("A tensor of the pykan state has the wrong shape."));
+    }
+    Ok(tensor)
+}
+
+/// The elements of an optional tensor of the state with the given shape, or the default value everywhere if it is missing.
+fn optional(state: &HashMap<String, Tensor>, name: &str, shape: &[usize], default: f64) -> Result<Vec<f64>, KanError> {
+    if state.contains_key(name) {
+        Ok(tensor(state, name, shape)?.values.clone())
+    } else {
+        Ok(vec![default; shape.iter().product()])
+    }
+}
@@ -0,0 +1,267 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::Aggregation, norm::Norm};
+use crate::compiled::CompiledKAN;
+use crate::error::KanError;
+use crate::scaler::Scaler;
+
+/// The integer type of the tables of a quantized KAN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    /// 256 levels per edge, one byte per entry.
+    Int8,
+    /// 65536 levels per edge, two bytes per entry.
+    Int16,
+}
+
+/// The quantized entries of the table of an edge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Table {
+    Int8(Vec<i8>),
+    Int16(Vec<i16>),
+}
+
+/// A quantized edge is an edge resampled as a piecewise linear spline with equally spaced knots over the range of its inputs, whose control points are stored as integers.
+/// It is represented as the index of the node it starts at, whether it is a skip edge, the range of its inputs, the affine map from the integers to values, and the table of quantized control points.
+///
+/// Inputs outside of the range are clamped to the range, so the range should cover the inputs seen at inference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizedEdge {
+    pub start: usize,
+    pub skip: bool,
+    pub min: f64, // Input of the first entry of the table
+    pub max: f64, // Input of the last entry of the table
+    pub offset: f64, // Value of the smallest integer of the precision
+    pub scale: f64, // Difference in value between consecutive integers
+    pub table: Table,
+}
+
+impl QuantizedEdge {
+    /// Quantize an edge by sampling its value at equally spaced inputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - The edge, whose dropout is applied by scaling as in `KAN::compile`.
+    ///
+    /// * `min` - The smallest input of the table.
+    ///
+    /// * `max` - The largest input of the table, at least `min`.
+    ///
+    /// * `resolution` - The number of entries of the table, at least 2.
+    ///
+    /// * `precision` - The integer type of the entries.
+    ///
+    /// # Returns
+    ///
+    /// * A quantized edge whose entries are the values of the edge at the inputs, rounded to the nearest of the levels spanning the smallest and largest value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let quantized = QuantizedEdge::new(&edge, 0.0, 1.0, 256, Precision::Int8);
+    /// ```
+    pub fn new(edge: &Edge, min: f64, max: f64, resolution: usize, precision: Precision) -> QuantizedEdge {
+        if resolution < 2 {
+            panic!("The table of a quantized edge must have at least 2 entries.");
+        }
+        if !(min <= max && min.is_finite() && max.is_finite()) {
+            panic!("The range of a quantized edge must be finite with the minimum at most the maximum.");
+        }
+        let step: f64 = (max - min) / (resolution - 1) as f64;
+        let values: Vec<f64> = (0..resolution).map(|i| edge.value(min + step * i as f64) * edge.dropout_scale()).collect();
+        let lowest: f64 = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let highest: f64 = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let (smallest, levels): (f64, f64) = match precision {
+            Precision::Int8 => (i8::MIN as f64, u8::MAX as f64),
+            Precision::Int16 => (i16::MIN as f64, u16::MAX as f64),
+        };
+        // A constant edge has a single level, and any positive scale maps it exactly
+        let scale: f64 = if highest > lowest { (highest - lowest) / levels } else { 1.0 };
+        let quantize = |value: &f64| ((value - lowest) / scale).round() + smallest;
+        let table: Table = match precision {
+            Precision::Int8 => Table::Int8(values.iter().map(|value| quantize(value) as i8).collect()),
+            Precision::Int16 => Table::Int16(values.iter().map(|value| quantize(value) as i16).collect()),
+        };
+        QuantizedEdge { start: edge.start, skip: edge.skip, min, max, offset: lowest - smallest * scale, scale, table }
+    }
+
+    /// The number of entries of the table.
+    pub fn len(&self) -> usize {
+        match &self.table {
+            Table::Int8(table) => table.len(),
+            Table::Int16(table) => table.len(),
+        }
+    }
+
+    /// Returns true if the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Compute the value of the edge by linear interpolation between the two nearest entries of the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The input of the edge, clamped to the range of the table.
+    ///
+    /// # Returns
+    ///
+    /// * The dequantized value of the edge at the input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let value = quantized.value(0.5);
+    /// ```
+    pub fn value(&self, t: f64) -> f64 {
+        let last: usize = self.len() - 1;
+        let position: f64 = if self.max > self.min { (t.clamp(self.min, self.max) - self.min) / (self.max - self.min) * last as f64 } else { 0.0 };
+        let i: usize = (position.floor() as usize).min(last - 1);
+        let fraction: f64 = position - i as f64;
+        let entry = |i: usize| -> f64 {
+            let q: f64 = match &self.table {
+                Table::Int8(table) => table[i] as f64,
+                Table::Int16(table) => table[i] as f64,
+            };
+            self.offset + self.scale * q
+        };
+        entry(i) * (1.0 - fraction) + entry(i + 1) * fraction
+    }
+}
+
+/// The error of a quantized KAN with respect to the KAN it was quantized from, over a set of samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantizationError {
+    pub max_error: f64, // Largest absolute difference of an output value over all samples and outputs
+    pub mean_error: f64, // Mean absolute difference of the output values over all samples and outputs
+    pub agreement: f64, // Fraction of the samples for which both predict the same class, the index of the largest output
+}
+
+/// A QuantizedKAN is a frozen copy of a trained KAN for inference on small devices, where the activation of every edge is a table of 8 or 16 bit integers.
+/// It has the structure of a `CompiledKAN`, with a `QuantizedEdge` in place of every edge, and is created with `KAN::quantize`.
+///
+/// Evaluating an edge is one table lookup and a linear interpolation regardless of its activation, and the tables take a fraction of the memory of the control points as 64 bit floats.
+#[derive(Debug, Clone)]
+pub struct QuantizedKAN {
+    pub layers: Vec<Vec<Vec<QuantizedEdge>>>, // The incoming edges of every node in every layer
+    pub aggregations: Vec<Vec<Aggregation>>, // How every node in every layer combines the values of its incoming edges
+    pub norms: Vec<Option<Norm>>, // The normalization of the values of the nodes in every layer
+    pub scaler: Option<Scaler>, // Applied to the input values before the first layer
+    pub precision: Precision,
+}
+
+impl QuantizedKAN {
+    /// The forward pass computes the values of all output nodes for the given input values, routing the values between the layers like `CompiledKAN::forward`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector representing the input values to the first layer.
+    ///
+    /// # Returns
+    ///
+    /// * A vector where the i-th entry is the value of the i-th node in the last layer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let output = quantized.forward(&Vector::new(vec![1.0, 2.0]));
+    /// ```
+    pub fn forward(&self, input: &Vector) -> Vector {
+        let mut activations: Vector = match &self.scaler {
+            Some(scaler) => scaler.transform(input),
+            None => input.clone(),
+        };
+        // The values of the nodes two layers back, which the skip edges start at
+        let mut skip_activations: Vector = Vector::new(vec![]);
+        for ((layer, aggregations), norm) in self.layers.iter().zip(self.aggregations.iter()).zip(self.norms.iter()) {
+            let mut next: Vector = Vector::new(layer.iter().zip(aggregations.iter()).map(|(node, aggregation)| {
+                let values = node.iter().map(|edge| {
+                    let source: &Vector = if edge.skip { &skip_activations } else { &activations };
+                    if edge.start >= source.len() {
+                        panic!("The start index of an incoming edge must be smaller than the number of nodes in the layer it starts at.");
+                    }
+                    edge.value(source[edge.start])
+                });
+                match aggregation {
+                    Aggregation::Sum => values.sum(),
+                    Aggregation::Product => values.product(),
+                }
+            }).collect());
+            if let Some(norm) = norm {
+                next = norm.forward(&next);
+            }
+            skip_activations = std::mem::replace(&mut activations, next);
+        }
+        activations
+    }
+
+    /// The batched forward pass computes the values of all output nodes for many samples, one sample after the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith sample.
+    ///
+    /// # Returns
+    ///
+    /// * A matrix where the ith row holds the values of the nodes in the last layer for the ith sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let outputs = quantized.forward_batch(&inputs);
+    /// ```
+    pub fn forward_batch(&self, inputs: &Matrix) -> Matrix {
+        Matrix::new((0..inputs.rows).map(|i| self.forward(&inputs.row(i))).collect())
+    }
+
+    /// The memory taken by the tables of all edges in bytes.
+    pub fn table_bytes(&self) -> usize {
+        let bytes: usize = match self.precision {
+            Precision::Int8 => 1,
+            Precision::Int16 => 2,
+        };
+        self.layers.iter().flatten().flatten().map(|edge| edge.len() * bytes).sum()
+    }
+
+    /// Compare the outputs of the quantized KAN with the outputs of a reference, usually the compiled KAN it was quantized from.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - A compiled KAN with the same number of inputs and outputs.
+    ///
+    /// * `inputs` - A matrix with at least one row, where the ith row represents the input values to the first layer for the ith sample.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the largest and mean absolute difference of the outputs, and the fraction of samples with the same predicted class, or an error if there are no samples, the numbers of outputs differ, or as in `CompiledKAN::forward`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let error = quantized.compare(&kan.compile(), &test_inputs).unwrap();
+    /// println!("max error {:.2e}, class agreement {:.1}%", error.max_error, 100.0 * error.agreement);
+    /// ```
+    pub fn compare(&self, reference: &CompiledKAN, inputs: &Matrix) -> Result<QuantizationError, KanError> {
+        if inputs.rows == 0 {
+            return Err(KanError::InvalidConfig("The comparison requires at least one sample."));
+        }
+        let mut error: QuantizationError = QuantizationError { max_error: 0.0, mean_error: 0.0, agreement: 0.0 };
+        let mut count: usize = 0;
+        for i in 0..inputs.rows {
+            let expected: Vector = reference.forward(&inputs.row(i))?;
+            let output: Vector = self.forward(&inputs.row(i));
+            if output.len() != expected.len() {
+                return Err(KanError::mismatch("outputs of the quantized KAN, one per output of the reference", expected.len(), output.len()));
+            }
+            for (a, b) in output.iter().zip(expected.iter()) {
+                error.max_error = error.max_error.max((a - b).abs());
+                error.mean_error += (a - b).abs();
+            }
+            count += output.len();
+            if output.argmax() == expected.argmax() {
+                error.agreement += 1.0;
+            }
+        }
+        error.mean_error /= count.max(1) as f64;
+        error.agreement /= inputs.rows as f64;
+        Ok(error)
+    }
+}
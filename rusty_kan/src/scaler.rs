@@ -0,0 +1,187 @@
+use crate::data_structures::{vector::Vector, matrix::{Matrix, Axis}};
+use serde::{Serialize, Deserialize};
+
+/// A scaler maps every input feature with an affine function x' = (x - offset) / scale that is fitted to a set of samples.
+/// Attached to a KAN as `KAN::scaler`, it is applied to the inputs of the first layer in every forward pass, so raw features are mapped into the domain of the splines.
+///
+/// The Scaler enum provides methods to fit the map and to apply it and its inverse.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Scaler {
+    /// Every feature is mapped linearly from its observed range onto [0, 1], the default domain of the splines.
+    MinMax { min: Vector, max: Vector },
+    /// Every feature is standardised to mean 0 and standard deviation 1.
+    ZScore { mean: Vector, std: Vector },
+}
+
+impl Scaler {
+    /// Create a min-max scaler fitted to a set of samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values of the ith sample.
+    ///
+    /// # Returns
+    ///
+    /// * A scaler that maps the observed range of every feature onto [0, 1].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// kan.scaler = Some(Scaler::min_max(&inputs));
+    /// ```
+    pub fn min_max(inputs: &Matrix) -> Scaler {
+        let mut scaler: Scaler = Scaler::MinMax { min: Vector::new(vec![]), max: Vector::new(vec![]) };
+        scaler.fit(inputs);
+        scaler
+    }
+
+    /// Create a z-score scaler fitted to a set of samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values of the ith sample.
+    ///
+    /// # Returns
+    ///
+    /// * A scaler that standardises every feature to mean 0 and standard deviation 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scaler = Scaler::z_score(&inputs);
+    /// ```
+    pub fn z_score(inputs: &Matrix) -> Scaler {
+        let mut scaler: Scaler = Scaler::ZScore { mean: Vector::new(vec![]), std: Vector::new(vec![]) };
+        scaler.fit(inputs);
+        scaler
+    }
+
+    /// Fit the scaler to a set of samples, keeping its kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix with at least one row, where the ith row represents the input values of the ith sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// scaler.fit(&new_inputs);
+    /// ```
+    pub fn fit(&mut self, inputs: &Matrix) {
+        if inputs.rows == 0 {
+            panic!("A scaler must be fitted to at least one sample.");
+        }
+        *self = match self {
+            Scaler::MinMax { .. } => Scaler::MinMax { min: inputs.min(Axis::Cols), max: inputs.max(Axis::Cols) },
+            Scaler::ZScore { .. } => Scaler::ZScore { mean: inputs.mean(Axis::Cols), std: inputs.std(Axis::Cols) },
+        };
+    }
+
+    /// Returns the number of features the scaler was fitted to.
+    pub fn len(&self) -> usize {
+        match self {
+            Scaler::MinMax { min, .. } => min.len(),
+            Scaler::ZScore { mean, .. } => mean.len(),
+        }
+    }
+
+    /// Returns true if the scaler was fitted to no features.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Map a value of a feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - A value of the feature.
+    ///
+    /// * `feature` - The index of the feature.
+    ///
+    /// # Returns
+    ///
+    /// * The scaled value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let t = scaler.transform_value(12.5, 0);
+    /// ```
+    pub fn transform_value(&self, x: f64, feature: usize) -> f64 {
+        let (offset, scale) = self.affine(feature);
+        (x - offset) / scale
+    }
+
+    /// Map the values of all features of a sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector with one value per feature.
+    ///
+    /// # Returns
+    ///
+    /// * A vector with the scaled values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scaled = scaler.transform(&Vector::new(vec![12.5, -3.0]));
+    /// ```
+    pub fn transform(&self, input: &Vector) -> Vector {
+        self.check_len(input.len());
+        Vector::new(input.iter().enumerate().map(|(i, &x)| self.transform_value(x, i)).collect())
+    }
+
+    /// Map the values of all features of a sample back to the original scale, undoing `transform`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector with one scaled value per feature.
+    ///
+    /// # Returns
+    ///
+    /// * A vector with the values on the original scale.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let original = scaler.inverse_transform(&scaled);
+    /// ```
+    pub fn inverse_transform(&self, input: &Vector) -> Vector {
+        self.check_len(input.len());
+        Vector::new(input.iter().enumerate().map(|(i, &x)| {
+            let (offset, scale) = self.affine(i);
+            x * scale + offset
+        }).collect())
+    }
+
+    /// Map every row of a matrix of samples with `transform`.
+    pub fn transform_batch(&self, inputs: &Matrix) -> Matrix {
+        self.check_len(inputs.cols);
+        Matrix::new((0..inputs.rows).map(|i| self.transform(&inputs.row(i))).collect())
+    }
+
+    /// Map every row of a matrix of scaled samples with `inverse_transform`.
+    pub fn inverse_transform_batch(&self, inputs: &Matrix) -> Matrix {
+        self.check_len(inputs.cols);
+        Matrix::new((0..inputs.rows).map(|i| self.inverse_transform(&inputs.row(i))).collect())
+    }
+
+    /// The offset and the positive scale of the map of a feature. A constant feature has a scale of 1, so it is only shifted.
+    pub fn affine(&self, feature: usize) -> (f64, f64) {
+        if feature >= self.len() {
+            panic!("The scaler was fitted to fewer features than the index of the feature.");
+        }
+        let (offset, scale): (f64, f64) = match self {
+            Scaler::MinMax { min, max } => (min[feature], max[feature] - min[feature]),
+            Scaler::ZScore { mean, std } => (mean[feature], std[feature]),
+        };
+        (offset, if scale > 0.0 { scale } else { 1.0 })
+    }
+
+    fn check_len(&self, len: usize) {
+        if len != self.len() {
+            panic!("The number of features must be equal to the number of features the scaler was fitted to.");
+        }
+    }
+}
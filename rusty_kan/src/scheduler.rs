@@ -0,0 +1,47 @@
+use serde::{Serialize, Deserialize};
+
+/// A learning rate scheduler determines the learning rate of every epoch from the base learning rate.
+///
+/// The Scheduler enum provides a method to compute the learning rate for a given epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Scheduler {
+    /// The base learning rate in every epoch.
+    Constant,
+    /// The learning rate is multiplied by `gamma` every `step_size` epochs.
+    StepDecay { step_size: usize, gamma: f64 },
+    /// The learning rate is multiplied by `gamma` every epoch.
+    ExponentialDecay { gamma: f64 },
+}
+
+impl Scheduler {
+    /// Compute the learning rate for a given epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_learning_rate` - The learning rate of the first epoch.
+    ///
+    /// * `epoch` - The index of the epoch, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// * The learning rate for the given epoch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let scheduler = Scheduler::StepDecay { step_size: 10, gamma: 0.5 };
+    /// let learning_rate = scheduler.learning_rate(0.1, 25); // 0.025
+    /// ```
+    pub fn learning_rate(&self, base_learning_rate: f64, epoch: usize) -> f64 {
+        match *self {
+            Scheduler::Constant => base_learning_rate,
+            Scheduler::StepDecay { step_size, gamma } => {
+                if step_size == 0 {
+                    panic!("The step size of the scheduler must be positive.");
+                }
+                base_learning_rate * gamma.powi((epoch / step_size) as i32)
+            }
+            Scheduler::ExponentialDecay { gamma } => base_learning_rate * gamma.powi(epoch as i32),
+        }
+    }
+}
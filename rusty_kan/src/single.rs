@@ -0,0 +1,671 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::{self, Edge, BaseFn, InputMode, Residual}, node::{Node, Aggregation}, layer::Layer, spline::{self, BSpline, BasisMemo, Extrapolation, Shape}};
+use crate::error::KanError;
+use crate::kan::{KAN, connect};
+use crate::loss::Loss;
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+/// A B-spline in single precision, the counterpart of a polynomial `BSpline` for the edges of a `SingleKAN`.
+/// It is represented as a list of control points, a list of knots, a degree, a domain of inputs that is mapped affinely onto the parameter range [0, 1] of the knots, and an extrapolation policy, as in `BSpline`, whose evaluation it shares.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingleSpline {
+    pub control_points: Vector<f32>, // Coefficients to be trained
+    pub knots: Vector<f32>,
+    pub degree: usize,
+    pub domain: (f32, f32),
+    pub extrapolation: Extrapolation,
+}
+
+impl SingleSpline {
+    /// Round a B-spline to single precision.
+    ///
+    /// # Arguments
+    ///
+    /// * `spline` - A polynomial B-spline without a shape constraint.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the B-spline in single precision, or an error if the B-spline is rational or has a shape constraint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let single = SingleSpline::from_spline(edge.spline()).unwrap();
+    /// ```
+    pub fn from_spline(spline: &BSpline) -> Result<SingleSpline, KanError> {
+        if spline.weights.is_some() || spline.shape != Shape::None {
+            return Err(KanError::Unsupported("Only polynomial B-splines without a shape constraint have a single-precision counterpart."));
+        }
+        Ok(SingleSpline {
+            control_points: spline.control_points.cast(),
+            knots: spline.knots.cast(),
+            degree: spline.degree,
+            domain: (spline.domain.0 as f32, spline.domain.1 as f32),
+            extrapolation: spline.extrapolation,
+        })
+    }
+
+    /// Widen the B-spline to double precision.
+    ///
+    /// # Returns
+    ///
+    /// * A polynomial B-spline with the control points, knots, degree, domain, and extrapolation policy of this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let spline: BSpline = single.to_spline();
+    /// ```
+    pub fn to_spline(&self) -> BSpline {
+        let domain: (f64, f64) = (self.domain.0 as f64, self.domain.1 as f64);
+        BSpline { control_points: self.control_points.cast(), knots: self.knots.cast(), degree: self.degree, memo: BasisMemo::default(), domain, extrapolation: self.extrapolation, shape: Shape::None, weights: None }
+    }
+
+    /// Calculate the values of all basis functions and their derivatives with respect to t at a given parameter value, following the extrapolation policy like `BSpline::basis_matrix`.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - A parameter value in the domain of the B-spline.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the values of the basis functions and their derivatives, whose products with the control points are the value and the derivative of the B-spline at t, or an error if t lies outside the knots and the extrapolation policy is `Extrapolation::Panic`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (basis, slopes) = single.basis(0.5).unwrap();
+    /// let value = basis.dot(&single.control_points);
+    /// ```
+    pub fn basis(&self, t: f32) -> Result<(Vector<f32>, Vector<f32>), KanError> {
+        spline::basis_at(&self.knots, self.degree, None, self.domain, self.extrapolation, t)
+    }
+
+    /// Evaluate the B-spline at a given parameter value.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - A parameter value in the domain of the B-spline.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the value of the B-spline at t, or an error as in `basis`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let value = single.value(0.5).unwrap();
+    /// ```
+    pub fn value(&self, t: f32) -> Result<f32, KanError> {
+        Ok(self.basis(t)?.0.dot(&self.control_points))
+    }
+
+    /// Evaluate the first derivative of the B-spline with respect to t at a given parameter value.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - A parameter value in the domain of the B-spline.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the derivative of the B-spline at t, or an error as in `basis`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let slope = single.derivative(0.5).unwrap();
+    /// ```
+    pub fn derivative(&self, t: f32) -> Result<f32, KanError> {
+        Ok(self.basis(t)?.1.dot(&self.control_points))
+    }
+}
+
+/// An edge in single precision, the counterpart of an `Edge` with a polynomial B-spline for a `SingleKAN`.
+/// It is represented as the index of the node it starts at, a B-spline, a base function with its scale w_b and the scale w_s of the B-spline, a learning rate multiplier, whether it is frozen, and how inputs outside the domain of the B-spline are treated, as in `Edge`.
+///
+/// The value of the edge is w_b * base(t) + w_s * spline(t), and its weights are the control points followed by w_b and w_s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingleEdge {
+    pub start: usize,
+    pub spline: SingleSpline,
+    pub base: BaseFn,
+    pub base_weight: f32, // Scale w_b of the base function
+    pub spline_weight: f32, // Scale w_s of the B-spline
+    pub gradient: Vector<f32>, // Gradients of the weights, summed over the backward passes since the last `zero_grad`
+    pub lr_scale: f32, // Multiplier of the learning rate for the weights
+    pub frozen: bool, // Whether the weights are excluded from updates
+    pub input_mode: InputMode, // How inputs outside the domain of the B-spline are treated
+}
+
+impl SingleEdge {
+    /// Round an edge to single precision.
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - An edge with a polynomial B-spline, without dropout, and not tied to other edges.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the edge in single precision and a zero gradient, or an error if the edge has another activation, dropout, or a tie, or as in `SingleSpline::from_spline`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let single = SingleEdge::from_edge(&Edge::standard(0, 0, 1)).unwrap();
+    /// ```
+    pub fn from_edge(edge: &Edge) -> Result<SingleEdge, KanError> {
+        let spline: &BSpline = match edge.activation.downcast_ref::<BSpline>() {
+            Some(spline) if edge.dropout == 0.0 && edge.tie.is_none() => spline,
+            _ => return Err(KanError::Unsupported("Only edges with a B-spline and without dropout or ties have a single-precision counterpart.")),
+        };
+        let spline: SingleSpline = SingleSpline::from_spline(spline)?;
+        let gradient: Vector<f32> = Vector::zeros(spline.control_points.len() + 2);
+        Ok(SingleEdge { start: edge.start, spline, base: edge.base, base_weight: edge.base_weight as f32, spline_weight: edge.spline_weight as f32, gradient, lr_scale: edge.lr_scale as f32, frozen: edge.frozen, input_mode: edge.input_mode })
+    }
+
+    /// Widen the edge to double precision.
+    ///
+    /// # Arguments
+    ///
+    /// * `end` - The index of the node the edge ends at.
+    ///
+    /// * `layer` - A layer index corresponding to the origin layer.
+    ///
+    /// # Returns
+    ///
+    /// * An edge with the B-spline, base function, weights, learning rate multiplier, and input mode of this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let edge: Edge = single.to_edge(0, 1);
+    /// ```
+    pub fn to_edge(&self, end: usize, layer: usize) -> Edge {
+        let mut edge: Edge = Edge::new(self.start, end, self.spline.to_spline(), layer);
+        edge.base = self.base;
+        edge.base_weight = self.base_weight as f64;
+        edge.spline_weight = self.spline_weight as f64;
+        edge.lr_scale = self.lr_scale as f64;
+        edge.frozen = self.frozen;
+        edge.input_mode = self.input_mode;
+        edge
+    }
+
+    /// The number of trainable weights of the edge: the control points, w_b, and w_s.
+    pub fn num_weights(&self) -> usize {
+        self.spline.control_points.len() + 2
+    }
+
+    /// Compute the value of the edge at a given input.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - An input value, mapped into the domain of the B-spline according to the input mode.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the value of the edge at t, or an error as in `SingleSpline::basis`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let value = single.value(0.5).unwrap();
+    /// ```
+    pub fn value(&self, t: f32) -> Result<f32, KanError> {
+        let residual: Residual<f32> = self.residual();
+        Ok(residual.value(t, self.spline.value(residual.spline_input(t))?))
+    }
+
+    /// The backward pass adds the gradient of the edge with respect to its weights to the stored gradient, so the gradients of several samples are summed.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - An input value, mapped into the domain of the B-spline according to the input mode.
+    ///
+    /// * `upstream_gradient` - A scalar representing the gradient of the loss with respect to the value of the edge at t.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the backward pass was successful, or an error if the gradient does not have one entry per weight or as in `SingleSpline::basis`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// single.backward(0.5, 0.25).unwrap();
+    /// ```
+    pub fn backward(&mut self, t: f32, upstream_gradient: f32) -> Result<(), KanError> {
+        if self.gradient.len() != self.num_weights() {
+            return Err(KanError::mismatch("gradient entries, one per weight", self.num_weights(), self.gradient.len()));
+        }
+        let residual: Residual<f32> = self.residual();
+        let (basis, _) = self.spline.basis(residual.spline_input(t))?;
+        let activation: f32 = basis.dot(&self.spline.control_points);
+        self.gradient += &residual.gradient(t, activation, &basis, upstream_gradient);
+        Ok(())
+    }
+
+    /// Compute the derivative of the value of the edge with respect to its input.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - An input value, mapped into the domain of the B-spline according to the input mode.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the derivative of the value of the edge at t, or an error as in `SingleSpline::basis`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let slope = single.input_gradient(0.5).unwrap();
+    /// ```
+    pub fn input_gradient(&self, t: f32) -> Result<f32, KanError> {
+        let residual: Residual<f32> = self.residual();
+        Ok(residual.input_gradient(t, self.spline.derivative(residual.spline_input(t))?))
+    }
+
+    /// Take a step of gradient descent with the stored gradient, unless the edge is frozen, and reset the gradient.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - A scalar representing the learning rate, scaled by the learning rate multiplier of the edge.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the update was successful, or an error if the gradient does not have one entry per weight.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// single.update_weights(0.01).unwrap();
+    /// ```
+    pub fn update_weights(&mut self, learning_rate: f32) -> Result<(), KanError> {
+        if !self.frozen {
+            edge::descend(&mut self.spline.control_points, &mut self.base_weight, &mut self.spline_weight, &self.gradient, -learning_rate * self.lr_scale)?;
+        }
+        self.zero_grad();
+        Ok(())
+    }
+
+    /// Reset the stored gradient to zeros with one entry per weight.
+    pub fn zero_grad(&mut self) {
+        self.gradient = Vector::zeros(self.num_weights());
+    }
+
+    /// The base function, weights, input mode, and domain of the B-spline of the edge, which compute its value and gradients as for an `Edge`.
+    fn residual(&self) -> Residual<f32> {
+        Residual { base: self.base, base_weight: self.base_weight, spline_weight: self.spline_weight, input_mode: self.input_mode, domain: self.spline.domain }
+    }
+}
+
+/// A layer in single precision, the counterpart of a `Layer` of sum nodes without a normalization for a `SingleKAN`.
+/// It is represented as the incoming edges of every node, and the value of a node is the sum of the values of its incoming edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingleLayer {
+    pub nodes: Vec<Vec<SingleEdge>>, // The incoming edges of every node
+}
+
+impl SingleLayer {
+    /// The forward pass computes the values of the nodes of the layer.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector with the values of the nodes of the previous layer, or the input values for the first layer.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a vector where the j-th entry is the value of the j-th node, or an error if an edge starts at a node that does not exist or as in `SingleEdge::value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let values = layer.forward(&Vector::new(vec![0.2, 0.7])).unwrap();
+    /// ```
+    pub fn forward(&self, input: &Vector<f32>) -> Result<Vector<f32>, KanError> {
+        let mut values: Vec<f32> = Vec::with_capacity(self.nodes.len());
+        for edges in self.nodes.iter() {
+            let mut value: f32 = 0.0;
+            for edge in edges.iter() {
+                value += edge.value(source(input, edge)?)?;
+            }
+            values.push(value);
+        }
+        Ok(Vector::new(values))
+    }
+
+    /// The backward pass adds the gradients of the incoming edges of every node to their stored gradients and propagates the gradient to the previous layer.
+    /// Each incoming edge is treated as having unit slope with respect to its input, as in `Layer::propagate`, so the single-precision model is trained like the KAN it was rounded from.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector with the values of the nodes of the previous layer at the forward pass.
+    ///
+    /// * `upstream_gradients` - A vector where the j-th entry is the gradient of the loss with respect to the value of the j-th node.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a vector where the i-th entry is the gradient of the loss with respect to the i-th input value, or an error if there is not one upstream gradient per node or as in `forward`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let gradients = layer.backward(&input, &Vector::new(vec![0.5])).unwrap();
+    /// ```
+    pub fn backward(&mut self, input: &Vector<f32>, upstream_gradients: &Vector<f32>) -> Result<Vector<f32>, KanError> {
+        if upstream_gradients.len() != self.nodes.len() {
+            return Err(KanError::mismatch("upstream gradients, one per node", self.nodes.len(), upstream_gradients.len()));
+        }
+        let mut gradients: Vector<f32> = Vector::zeros(input.len());
+        for (edges, &upstream_gradient) in self.nodes.iter_mut().zip(upstream_gradients.iter()) {
+            for edge in edges.iter_mut() {
+                let t: f32 = source(input, edge)?;
+                edge.backward(t, upstream_gradient)?;
+                gradients[edge.start] += upstream_gradient;
+            }
+        }
+        Ok(gradients)
+    }
+}
+
+/// The value an edge receives from the values of the nodes it starts at.
+fn source(input: &Vector<f32>, edge: &SingleEdge) -> Result<f32, KanError> {
+    input.elements.get(edge.start).copied().ok_or(KanError::Format("An edge starts at a node that does not exist in the previous layer."))
+}
+
+/// A SingleKAN is a copy of a KAN in single precision, for training and inference where memory and throughput matter more than the last digits, e.g. on small devices or with SIMD.
+/// Its control points, weights, gradients, inputs, and outputs are `f32`, which halves the memory of the model, and the forward and backward passes run in single precision.
+///
+/// It covers the KAN of the paper: layers of sum nodes whose edges start in the previous layer and have a polynomial B-spline.
+/// It is created with `KAN::to_single` and converted back with `to_kan`, e.g. to save, compile, or symbolify the trained model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingleKAN {
+    pub layers: Vec<SingleLayer>,
+    pub inputs: usize, // Number of input values, which the edges of the first layer start at
+}
+
+impl SingleKAN {
+    /// Round a KAN to single precision.
+    ///
+    /// # Arguments
+    ///
+    /// * `kan` - A KAN without a scaler, normalizations, product nodes, or skip edges.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the KAN in single precision, or an error if the KAN has a scaler, normalizations, product nodes, or skip edges, or as in `SingleEdge::from_edge`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let single = SingleKAN::from_kan(&KANBuilder::new(2).add_layer(3).add_layer(1).build()).unwrap();
+    /// ```
+    pub fn from_kan(kan: &KAN) -> Result<SingleKAN, KanError> {
+        let unsupported: KanError = KanError::Unsupported("A KAN with a scaler, normalizations, product nodes, or skip edges has no single-precision counterpart.");
+        if kan.scaler.is_some() {
+            return Err(unsupported);
+        }
+        let mut layers: Vec<SingleLayer> = Vec::with_capacity(kan.layers.len());
+        for layer in kan.layers.iter() {
+            let layer: Ref<Layer> = layer.borrow();
+            if layer.norm.is_some() {
+                return Err(unsupported);
+            }
+            let mut nodes: Vec<Vec<SingleEdge>> = Vec::with_capacity(layer.nodes.len());
+            for node in layer.nodes.iter() {
+                let node: Ref<Node> = node.borrow();
+                if node.aggregation != Aggregation::Sum || node.incoming.iter().any(|edge| edge.borrow().skip) {
+                    return Err(unsupported);
+                }
+                nodes.push(node.incoming.iter().map(|edge| SingleEdge::from_edge(&edge.borrow())).collect::<Result<Vec<SingleEdge>, KanError>>()?);
+            }
+            layers.push(SingleLayer { nodes });
+        }
+        Ok(SingleKAN { layers, inputs: kan.inputs })
+    }
+
+    /// Widen the KAN to double precision.
+    /// The layers, nodes, and edges are numbered as by `KANBuilder`, with the inputs at 0.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a KAN with the layers and edges of this one, or an error if an edge starts at a node that does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let kan: KAN = single.to_kan().unwrap();
+    /// kan.save("kan.json").unwrap();
+    /// ```
+    pub fn to_kan(&self) -> Result<KAN, KanError> {
+        let layers: Vec<Rc<RefCell<Layer>>> = self.layers.iter().enumerate().map(|(l, layer)| {
+            let nodes: Vec<Rc<RefCell<Node>>> = layer.nodes.iter().enumerate().map(|(j, edges)| {
+                let incoming: Vec<Rc<RefCell<Edge>>> = edges.iter().map(|edge| Rc::new(RefCell::new(edge.to_edge(j, l + 1)))).collect();
+                Rc::new(RefCell::new(Node::new(incoming, vec![], l + 1)))
+            }).collect();
+            Rc::new(RefCell::new(Layer::new(nodes)))
+        }).collect();
+        connect(&layers)?;
+        let mut kan: KAN = KAN::new(layers);
+        kan.inputs = self.inputs;
+        Ok(kan)
+    }
+
+    /// The number of trainable weights of all edges.
+    pub fn num_params(&self) -> usize {
+        self.edges().map(|edge| edge.num_weights()).sum()
+    }
+
+    /// The forward pass computes the values of all output nodes for the given input values.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector with one value per input of the KAN.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a vector where the i-th entry is the value of the i-th node in the last layer, or an error if the input does not have one value per input of the KAN or as in `SingleLayer::forward`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let output = single.forward(&Vector::new(vec![0.2, 0.7])).unwrap();
+    /// ```
+    pub fn forward(&self, input: &Vector<f32>) -> Result<Vector<f32>, KanError> {
+        Ok(self.trace(input)?.pop().unwrap_or_else(|| input.clone()))
+    }
+
+    /// The batched forward pass computes the values of all output nodes for many samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row holds the input values of the ith sample.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a matrix where the ith row holds the values of the nodes in the last layer for the ith sample, or an error as in `forward`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let outputs = single.forward_batch(&inputs).unwrap();
+    /// ```
+    pub fn forward_batch(&self, inputs: &Matrix<f32>) -> Result<Matrix<f32>, KanError> {
+        let outputs: Vec<Vector<f32>> = (0..inputs.rows).map(|i| self.forward(&inputs.row(i))).collect::<Result<Vec<Vector<f32>>, KanError>>()?;
+        Ok(Matrix::new(outputs))
+    }
+
+    /// Compute the mean loss of the KAN over a dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row holds the input values of the ith sample.
+    ///
+    /// * `targets` - A matrix where the ith row holds the target values of the ith sample.
+    ///
+    /// * `loss` - The loss function.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the mean loss, or an error if there are no samples, the numbers of inputs and targets differ, a target does not have one value per output node, or as in `forward`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let loss = single.loss(&inputs, &targets, Loss::MeanSquaredError).unwrap();
+    /// ```
+    pub fn loss(&self, inputs: &Matrix<f32>, targets: &Matrix<f32>, loss: Loss) -> Result<f32, KanError> {
+        check_batch(inputs, targets)?;
+        let mut total: f32 = 0.0;
+        for i in 0..inputs.rows {
+            let output: Vector<f32> = self.forward(&inputs.row(i))?;
+            total += sample_loss(&output, &targets.row(i), loss)?.0;
+        }
+        Ok(total / inputs.rows as f32)
+    }
+
+    /// The backward pass adds the gradient of the loss of one sample with respect to the weights of every edge to their stored gradients.
+    /// The gradients of several samples are summed until `update_weights` or `zero_grad` resets them.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A vector with one value per input of the KAN.
+    ///
+    /// * `target` - A vector of target values for the output nodes (a one-hot vector for cross-entropy).
+    ///
+    /// * `loss` - The loss function.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the loss of the sample at the forward pass, or an error if the target does not have one value per output node or as in `forward`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let loss = single.backward(&input, &target, Loss::MeanSquaredError).unwrap();
+    /// ```
+    pub fn backward(&mut self, input: &Vector<f32>, target: &Vector<f32>, loss: Loss) -> Result<f32, KanError> {
+        let mut layer_inputs: Vec<Vector<f32>> = self.trace(input)?;
+        let output: Vector<f32> = layer_inputs.pop().unwrap_or_else(|| input.clone());
+        layer_inputs.insert(0, input.clone());
+        let (value, mut upstream_gradient) = sample_loss(&output, target, loss)?;
+        for (l, layer) in self.layers.iter_mut().enumerate().rev() {
+            upstream_gradient = layer.backward(&layer_inputs[l], &upstream_gradient).map_err(|err| err.at_layer(l))?;
+        }
+        Ok(value)
+    }
+
+    /// Take a step of gradient descent on every edge with its stored gradient and reset the gradients.
+    ///
+    /// # Arguments
+    ///
+    /// * `learning_rate` - A scalar representing the learning rate.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the update was successful, or an error as in `SingleEdge::update_weights`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// single.update_weights(0.01).unwrap();
+    /// ```
+    pub fn update_weights(&mut self, learning_rate: f32) -> Result<(), KanError> {
+        for edge in self.edges_mut() {
+            edge.update_weights(learning_rate)?;
+        }
+        Ok(())
+    }
+
+    /// Reset the stored gradients of all edges to zeros.
+    pub fn zero_grad(&mut self) {
+        for edge in self.edges_mut() {
+            edge.zero_grad();
+        }
+    }
+
+    /// Train the KAN on a batch of samples with one step of gradient descent on the mean loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row holds the input values of the ith sample.
+    ///
+    /// * `targets` - A matrix where the ith row holds the target values of the ith sample.
+    ///
+    /// * `learning_rate` - A positive scalar representing the learning rate.
+    ///
+    /// * `loss` - The loss function.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the mean loss of the batch before the step, or an error if the learning rate is not positive or as in `loss` and `backward`, in which case the weights are not changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// for _ in 0..100 {
+    ///     let loss = single.train_batch(&inputs, &targets, 0.05, Loss::MeanSquaredError).unwrap();
+    /// }
+    /// ```
+    pub fn train_batch(&mut self, inputs: &Matrix<f32>, targets: &Matrix<f32>, learning_rate: f32, loss: Loss) -> Result<f32, KanError> {
+        if learning_rate.is_nan() || learning_rate <= 0.0 {
+            return Err(KanError::InvalidConfig("The learning rate must be greater than 0."));
+        }
+        check_batch(inputs, targets)?;
+        self.zero_grad();
+        let mut total: f32 = 0.0;
+        for i in 0..inputs.rows {
+            match self.backward(&inputs.row(i), &targets.row(i), loss) {
+                Ok(value) => total += value,
+                Err(err) => {
+                    self.zero_grad();
+                    return Err(err);
+                }
+            }
+        }
+        // The summed gradients with the learning rate divided by the number of samples are a step on the mean loss
+        let n: f32 = inputs.rows as f32;
+        self.update_weights(learning_rate / n)?;
+        Ok(total / n)
+    }
+
+    /// The values of the nodes of every layer for the given input values, from the first to the last layer.
+    fn trace(&self, input: &Vector<f32>) -> Result<Vec<Vector<f32>>, KanError> {
+        if input.len() != self.inputs {
+            return Err(KanError::mismatch("input values, one per input of the KAN", self.inputs, input.len()));
+        }
+        let mut values: Vec<Vector<f32>> = Vec::with_capacity(self.layers.len());
+        for (l, layer) in self.layers.iter().enumerate() {
+            let next: Vector<f32> = layer.forward(values.last().unwrap_or(input)).map_err(|err| err.at_layer(l))?;
+            values.push(next);
+        }
+        Ok(values)
+    }
+
+    /// The edges of all layers, node by node.
+    fn edges(&self) -> impl Iterator<Item = &SingleEdge> {
+        self.layers.iter().flat_map(|layer| layer.nodes.iter().flatten())
+    }
+
+    /// The edges of all layers, node by node, for updates.
+    fn edges_mut(&mut self) -> impl Iterator<Item = &mut SingleEdge> {
+        self.layers.iter_mut().flat_map(|layer| layer.nodes.iter_mut().flatten())
+    }
+}
+
+/// Check that a batch has at least one sample and one target per input.
+fn check_batch(inputs: &Matrix<f32>, targets: &Matrix<f32>) -> Result<(), KanError> {
+    if inputs.rows == 0 {
+        return Err(KanError::InvalidConfig("The loss of a batch requires at least one sample."));
+    }
+    if inputs.rows != targets.rows {
+        return Err(KanError::mismatch("targets, one per input", inputs.rows, targets.rows));
+    }
+    Ok(())
+}
+
+/// The loss of one sample and its gradient with respect to the output values.
+/// They are computed in double precision from the few output values, which leaves the passes through the edges in single precision.
+fn sample_loss(output: &Vector<f32>, target: &Vector<f32>, loss: Loss) -> Result<(f32, Vector<f32>), KanError> {
+    let (output, target): (Vector, Vector) = (output.cast(), target.cast());
+    Ok((loss.compute(&output, &target)? as f32, loss.gradient(&output, &target)?.cast()))
+}
@@ -0,0 +1,132 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, spline::BSpline, activation::ActivationFn, edge::Edge, node::Node, layer::Layer};
+use crate::kan::KAN;
+use crate::loss::Loss;
+use crate::trainer::Trainer;
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// A linear activation a * t, to check that edges work with activations defined outside the crate's data structures.
+#[derive(Debug, Clone)]
+struct Linear {
+    params: Vector,
+}
+
+impl ActivationFn for Linear {
+    fn eval(&mut self, t: f64) -> f64 {
+        self.params[0] * t
+    }
+
+    fn grad_wrt_params(&mut self, t: f64) -> Vector {
+        Vector::new(vec![t])
+    }
+
+    fn grad_wrt_input(&self, _t: f64) -> f64 {
+        self.params[0]
+    }
+
+    fn params(&self) -> &Vector {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut Vector {
+        &mut self.params
+    }
+
+    fn domain(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActivationFn> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl std::fmt::Display for Linear {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Linear {{ slope: {} }}", self.params[0])
+    }
+}
+
+fn linear_kan(slope: f64) -> KAN {
+    let edge: Rc<RefCell<Edge>> = Rc::new(RefCell::new(Edge::with_activation(0, 0, Box::new(Linear { params: Vector::new(vec![slope]) }), 1)));
+    let node: Rc<RefCell<Node>> = Rc::new(RefCell::new(Node::new(vec![edge], vec![], 1)));
+    KAN::new(vec![Rc::new(RefCell::new(Layer::new(vec![node])))])
+}
+
+#[test]
+fn activation_custom_edge_pass() {
+    let mut edge: Edge = Edge::with_activation(0, 1, Box::new(Linear { params: Vector::new(vec![2.0]) }), 0);
+    let silu: f64 = 0.5 / (1.0 + (-0.5f64).exp());
+
+    assert_is_close!(edge.forward(0.5), 1.0 + silu, 1e-12);
+    edge.backward(0.5, 2.0).unwrap();
+    assert_eq!(edge.gradient, Vector::new(vec![1.0, 2.0 * silu, 2.0]));
+    edge.update_weights(0.1).unwrap();
+    assert_is_close!(edge.activation.params()[0], 1.9, 1e-12);
+    assert_eq!(edge.to_string(), "Edge(start: 0, end: 1, layer: 0, activation: Linear { slope: 1.9 })");
+}
+
+#[test]
+fn activation_value_pass() {
+    let linear: Linear = Linear { params: Vector::new(vec![3.0]) };
+    let mut spline: BSpline = BSpline::rational(Vector::new(vec![0.0, 1.0, -0.5, 2.0]), Vector::new(vec![1.0, 2.0, 0.5, 1.0]), 2);
+
+    assert_is_close!(linear.value(0.5), 1.5, 1e-12);
+    for &t in [0.0, 0.3, 0.7, 1.0].iter() {
+        assert_is_close!(ActivationFn::value(&spline, t), spline.eval(t), 1e-12);
+    }
+}
+
+#[test]
+fn activation_custom_kan_pass() {
+    let kan: KAN = linear_kan(2.0);
+    let snapshot: Vec<Vector> = kan.snapshot();
+    let cloned: KAN = kan.clone();
+
+    kan.edges()[0].borrow_mut().activation.params_mut()[0] = 5.0;
+    kan.refine_grid(10);
+    assert_eq!(kan.edges()[0].borrow().activation.params().len(), 1);
+    kan.restore(&snapshot).unwrap();
+    assert_eq!(kan.snapshot(), vec![Vector::new(vec![2.0, 1.0, 1.0])]);
+    assert_eq!(cloned.snapshot(), snapshot);
+}
+
+#[test]
+fn activation_downcast_pass() {
+    let mut activation: Box<dyn ActivationFn> = Box::new(BSpline::new(Vector::new(vec![1.0, 2.0, 3.0]), 2));
+
+    assert!(activation.downcast_ref::<Linear>().is_none());
+    activation.downcast_mut::<BSpline>().unwrap().degree = 1;
+    assert_eq!(activation.downcast_ref::<BSpline>().unwrap().degree, 1);
+    assert_eq!(activation.grad_wrt_params(0.5).len(), 3);
+}
+
+#[test]
+fn activation_checkpoint_fail() {
+    let trainer: Trainer = Trainer::new(linear_kan(1.0), 0.1, Loss::MeanSquaredError, 1);
+    let path: std::path::PathBuf = std::env::temp_dir().join("rusty_kan_activation_checkpoint_fail.json");
+
+    assert!(trainer.save_checkpoint(&path).is_err());
+}
@@ -0,0 +1,174 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::attribution::EdgeScores;
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::Node};
+use crate::error::KanError;
+use crate::kan::KAN;
+use crate::loss::Loss;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.2, 0.4]), Vector::new(vec![0.6, 0.9]), Vector::new(vec![0.1, 0.7])])
+}
+
+#[test]
+fn kan_update_edge_scores_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    assert!(kan.edge_scores().is_empty());
+
+    kan.update_edge_scores(&inputs()).unwrap();
+    let scores: EdgeScores = kan.edge_scores();
+
+    assert_eq!(scores.len(), kan.edges().len());
+    // The edge from the second input to the third hidden node, scored as its mean absolute value
+    let edge = kan.layers[0].borrow().nodes[2].borrow().incoming[1].clone();
+    let expected: f64 = (0..3).map(|i| edge.borrow().value(inputs()[i][1]).abs()).sum::<f64>() / 3.0;
+    assert_is_close!(scores.get(0, 1, 2).unwrap(), expected, 1e-12);
+    assert_eq!(edge.borrow().score, scores.get(0, 1, 2));
+    assert!(scores.get(1, 2, 0).is_some());
+    assert_eq!(scores.get(1, 3, 0), None);
+}
+
+#[test]
+fn kan_update_edge_scores_fail() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    assert_eq!(kan.update_edge_scores(&Matrix::zeros(0, 2)), Err(KanError::InvalidConfig("The scores of the edges require at least one sample.")));
+    assert!(kan.update_edge_scores(&Matrix::zeros(3, 1)).is_err());
+    assert!(kan.edge_scores().is_empty());
+}
+
+#[test]
+fn edge_scores_below_pass() {
+    let mut scores: EdgeScores = EdgeScores::default();
+    scores.scores.insert((1, 0, 0), 0.5);
+    scores.scores.insert((0, 1, 0), 0.01);
+    scores.scores.insert((0, 0, 0), 0.2);
+
+    assert_eq!(scores.below(0.3), vec![(0, 0, 0), (0, 1, 0)]);
+    assert_eq!(scores.to_string(), "layer start end score\n0 0 0 0.2\n0 1 0 0.01\n1 0 0 0.5\n");
+}
+
+#[test]
+fn kan_prune_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    kan.update_edge_scores(&inputs()).unwrap();
+    for edge in kan.edges().iter() {
+        edge.borrow_mut().score = Some(1.0);
+    }
+    // The second hidden node loses its incoming edges, so it only adds the value of its outgoing edge at 0
+    let hidden: Rc<RefCell<Node>> = kan.layers[0].borrow().nodes[1].clone();
+    for edge in hidden.borrow().incoming.iter() {
+        let zeros: Vector = Vector::zeros(edge.borrow().num_weights());
+        edge.borrow_mut().set_weights(&zeros);
+        edge.borrow_mut().score = Some(0.0);
+    }
+    let outgoing: Rc<RefCell<Edge>> = kan.layers[1].borrow().nodes[0].borrow().incoming[1].clone();
+    let expected: Vec<f64> = (0..3).map(|i| kan.forward(&inputs().row(i)).unwrap() - outgoing.borrow().value(0.0)).collect();
+
+    assert_eq!(kan.prune(0.5).unwrap(), 3);
+
+    assert_eq!(kan.layers[0].borrow().nodes.len(), 2);
+    assert_eq!(kan.edges().len(), 6);
+    let output: Rc<RefCell<Node>> = kan.layers[1].borrow().nodes[0].clone();
+    assert_eq!(output.borrow().incoming.iter().map(|edge| edge.borrow().start).collect::<Vec<usize>>(), vec![0, 1]);
+    assert!(kan.layers[0].borrow().nodes[1].borrow().incoming.iter().all(|edge| edge.borrow().end == 1));
+    for (i, expected) in expected.iter().enumerate() {
+        assert_is_close!(kan.forward(&inputs().row(i)).unwrap(), expected, 1e-12);
+    }
+}
+
+#[test]
+fn kan_prune_dead_end_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    kan.update_edge_scores(&inputs()).unwrap();
+    for edge in kan.edges().iter() {
+        edge.borrow_mut().score = Some(1.0);
+    }
+    // Without its outgoing edge the first hidden node no longer affects the output, so its incoming edges go too
+    kan.layers[1].borrow().nodes[0].borrow().incoming[0].borrow_mut().score = Some(0.0);
+
+    assert_eq!(kan.prune(0.5).unwrap(), 3);
+    assert_eq!(kan.layers[0].borrow().nodes.len(), 2);
+    assert_eq!(kan.prune(0.5).unwrap(), 0);
+    assert_eq!(kan.forward_batch(&inputs()).unwrap().shape(), (3, 1));
+}
+
+#[test]
+fn kan_prune_uneven_fan_in_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    let first: Rc<RefCell<Edge>> = kan.edges()[0].clone();
+    let weak: f64 = first.borrow().value(0.2);
+    for (i, edge) in kan.edges().iter().enumerate() {
+        edge.borrow_mut().score = Some(if i == 0 { 0.0 } else { 1.0 });
+    }
+
+    assert_eq!(kan.prune(0.5).unwrap(), 1);
+
+    // The first hidden node keeps one incoming edge while the others keep two, and the KAN is still evaluated and trained
+    let input: Matrix = kan.input_matrix(&Vector::new(vec![0.2, 0.4]));
+    assert_eq!(input.shape(), (3, 2));
+    let hidden: f64 = kan.layers[0].borrow().activations(input.clone()).unwrap()[0];
+    assert_is_close!(hidden, kan.edges()[0].borrow().value(0.4), 1e-12);
+    assert_ne!(hidden, weak + kan.edges()[0].borrow().value(0.4));
+    assert!(kan.backward_features(&Vector::new(vec![0.2, 0.4]), &Vector::new(vec![1.0]), Loss::MeanSquaredError).is_ok());
+    kan.update_edge_scores(&inputs()).unwrap();
+    assert!(kan.edges().iter().all(|edge| edge.borrow().score.is_some()));
+}
+
+#[test]
+fn kan_prune_fail() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    assert_eq!(kan.prune(0.5), Err(KanError::InvalidConfig("Every edge must be scored with update_edge_scores before pruning.")));
+    assert_eq!(kan.edges().len(), 9);
+}
+
+#[test]
+fn kan_sparsity_gradients_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    let gradients: Vec<Vector> = kan.sparsity_gradients(&inputs(), 0.5, 2.0).unwrap();
+    let epsilon: f64 = 1e-6;
+
+    // The edges of the last layer do not change the inputs of other edges, so their gradients match finite differences
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    for e in 6..9 {
+        let weights: Vector = edges[e].borrow().weights();
+        for k in [0, 3, weights.len() - 1] {
+            let mut shifted: Vector = weights.clone();
+            shifted[k] += epsilon;
+            edges[e].borrow_mut().set_weights(&shifted);
+            let plus: f64 = kan.sparsity(&inputs(), 0.5, 2.0).unwrap();
+            shifted[k] -= 2.0 * epsilon;
+            edges[e].borrow_mut().set_weights(&shifted);
+            let minus: f64 = kan.sparsity(&inputs(), 0.5, 2.0).unwrap();
+            edges[e].borrow_mut().set_weights(&weights);
+            assert_is_close!(gradients[e][k], (plus - minus) / (2.0 * epsilon), 1e-6);
+        }
+    }
+}
+
+#[test]
+fn kan_sparsity_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    kan.update_edge_scores(&inputs()).unwrap();
+    let scores: Vec<f64> = kan.edges().iter().map(|edge| edge.borrow().score.unwrap()).collect();
+    let entropy = |magnitudes: &[f64]| -> f64 {
+        let total: f64 = magnitudes.iter().sum();
+        -magnitudes.iter().map(|m| (m / total) * (m / total).ln()).sum::<f64>()
+    };
+
+    // The L1 term of every layer is the sum of the scores of its edges
+    assert_is_close!(kan.sparsity(&inputs(), 1.0, 0.0).unwrap(), scores.iter().sum::<f64>(), 1e-12);
+    assert_is_close!(kan.sparsity(&inputs(), 0.0, 1.0).unwrap(), entropy(&scores[..6]) + entropy(&scores[6..]), 1e-12);
+}
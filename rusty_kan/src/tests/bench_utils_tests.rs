@@ -0,0 +1,26 @@
+use crate::bench_utils;
+use crate::data_structures::vector::Vector;
+use crate::datasets::dataset::Dataset;
+use crate::kan::KAN;
+
+#[test]
+fn bench_utils_workloads_pass() {
+    assert_eq!(bench_utils::grid(5), Vector::new(vec![0.0, 0.25, 0.5, 0.75, 1.0]));
+    assert_eq!(bench_utils::spline(16, 3, 1).control_points, bench_utils::spline(16, 3, 1).control_points);
+
+    let kan: KAN = bench_utils::kan(&[4, 8, 1], 1);
+    assert_eq!(kan.layers.len(), 2);
+    assert_eq!(kan.edges().len(), 4 * 8 + 8);
+
+    let dataset: Dataset = bench_utils::regression(32, 1);
+    assert_eq!((dataset.inputs.rows, dataset.inputs.cols, dataset.targets.cols), (32, 4, 1));
+    assert!(dataset.inputs.elements.iter().all(|x| (0.0..=1.0).contains(x)));
+    assert_eq!(bench_utils::layer_input(&kan, &dataset.inputs.row(0)).rows, 8);
+    assert!(kan.predict(&dataset.inputs).unwrap().iter().all(|y| y.is_finite()));
+}
+
+#[test]
+#[should_panic]
+fn bench_utils_kan_fail() {
+    bench_utils::kan(&[4], 1);
+}
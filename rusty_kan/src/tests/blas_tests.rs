@@ -0,0 +1,70 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::error::KanError;
+use rand::SeedableRng;
+
+// The product of two matrices by the definition.
+fn naive_product(a: &Matrix, b: &Matrix) -> Matrix {
+    Matrix::from_elements(a.rows, b.cols, (0..a.rows * b.cols).map(|k| (0..a.cols).map(|j| a[k / b.cols][j] * b[j][k % b.cols]).sum()).collect())
+}
+
+#[test]
+fn blas_matmul_pass() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+    // Rectangular shapes catch swapped dimensions of the column-major call
+    let a: Matrix = Matrix::random_with_rng(70, 45, &mut rng);
+    let b: Matrix = Matrix::random_with_rng(45, 90, &mut rng);
+
+    let product: Matrix = &a * &b;
+    let expected: Matrix = naive_product(&a, &b);
+
+    assert_eq!(product.shape(), (70, 90));
+    for (x, y) in product.elements.iter().zip(expected.elements.iter()) {
+        assert_is_close!(x, y, 1e-10);
+    }
+    assert_eq!((&Matrix::<f64>::zeros(0, 3) * &Matrix::zeros(3, 2)).shape(), (0, 2));
+    assert_eq!(&Matrix::<f64>::zeros(2, 0) * &Matrix::zeros(0, 2), Matrix::zeros(2, 2));
+
+    let single: Matrix<f32> = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
+    assert_eq!(&single * &single, Matrix::new(vec![Vector::new(vec![7.0, 10.0]), Vector::new(vec![15.0, 22.0])]));
+}
+
+#[test]
+fn blas_solve_pass() {
+    // An asymmetric system catches a missing transpose to column-major order
+    let a: Matrix = Matrix::new(vec![Vector::new(vec![2.0, 1.0, 0.0]), Vector::new(vec![0.0, 3.0, 1.0]), Vector::new(vec![4.0, 0.0, 1.0])]);
+    let x: Vector = Vector::new(vec![1.0, -2.0, 0.5]);
+
+    let solution: Vector = a.solve(&(&a * &x)).unwrap();
+    for i in 0..3 {
+        assert_is_close!(solution[i], x[i], 1e-10);
+    }
+
+    let spd: Matrix<f32> = Matrix::new(vec![Vector::new(vec![4.0, 2.0]), Vector::new(vec![2.0, 3.0])]);
+    let y: Vector<f32> = spd.solve_cholesky(&Vector::new(vec![6.0, 5.0])).unwrap();
+    assert_is_close!(y[0], 1.0, 1e-5);
+    assert_is_close!(y[1], 1.0, 1e-5);
+}
+
+#[test]
+fn blas_solve_fail() {
+    let singular: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![2.0, 4.0])]);
+    let indefinite: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![2.0, 1.0])]);
+    let asymmetric: Matrix = Matrix::new(vec![Vector::new(vec![2.0, 1.0]), Vector::new(vec![0.0, 2.0])]);
+
+    assert_eq!(singular.solve(&Vector::new(vec![1.0, 1.0])), Err(KanError::SingularMatrix));
+    assert_eq!(indefinite.solve_cholesky(&Vector::new(vec![1.0, 1.0])), Err(KanError::NotPositiveDefinite));
+    assert_eq!(asymmetric.solve_cholesky(&Vector::new(vec![1.0, 1.0])), Err(KanError::DomainError("The matrix is not symmetric.")));
+}
@@ -0,0 +1,138 @@
+use crate::builder::KANBuilder;
+use crate::data_structures::{node::Aggregation, vector::Vector, layer::Layer, edge::BaseFn, spline::BSpline, rbf::GaussianRbf, activation::Basis};
+use crate::init::Init;
+use crate::kan::KAN;
+use std::cell::Ref;
+
+#[test]
+fn builder_new_pass() {
+    let builder: KANBuilder = KANBuilder::new(2).add_layer(3).add_layer(1).init(Init::Xavier).seed(42);
+
+    assert_eq!(builder.widths, vec![2, 3, 1]);
+    assert_eq!(builder.init, Init::Xavier);
+    assert_eq!(builder.seed, Some(42));
+}
+
+#[test]
+fn builder_build_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(4).add_layer(3).add_layer(2).build();
+
+    assert_eq!(kan.layers.len(), 3);
+    assert_eq!(kan.edges().len(), 2 * 4 + 4 * 3 + 3 * 2);
+    for (l, width) in [(0, 4), (1, 3), (2, 2)] {
+        let layer: Ref<Layer> = kan.layers[l].borrow();
+        assert_eq!(layer.nodes.len(), width);
+        for (j, node) in layer.nodes.iter().enumerate() {
+            for (i, edge) in node.borrow().incoming.iter().enumerate() {
+                assert_eq!(edge.borrow().start, i);
+                assert_eq!(edge.borrow().end, j);
+            }
+        }
+    }
+    // The outgoing edges of a layer are the incoming edges of the next layer
+    let hidden: Ref<Layer> = kan.layers[1].borrow();
+    let output: Ref<Layer> = kan.layers[2].borrow();
+    assert_eq!(hidden.nodes[2].borrow().outgoing.len(), 2);
+    assert!(std::rc::Rc::ptr_eq(&hidden.nodes[2].borrow().outgoing[1], &output.nodes[1].borrow().incoming[2]));
+    assert!(output.nodes[0].borrow().outgoing.is_empty());
+}
+
+#[test]
+fn builder_forward_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).add_layer(2).seed(1).build();
+    assert_eq!(kan.inputs, 2);
+    assert_eq!(kan.forward_logits(&Vector::new(vec![0.2, 0.7])).unwrap().len(), 2);
+}
+
+#[test]
+fn builder_seed_pass() {
+    let builder: KANBuilder = KANBuilder::new(2).add_layer(3).add_layer(1).seed(42);
+
+    assert_eq!(builder.build().snapshot(), builder.build().snapshot());
+    assert_ne!(builder.build().snapshot(), builder.clone().seed(43).build().snapshot());
+}
+
+#[test]
+fn builder_init_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(1).init(Init::Zeros).build();
+
+    // Zero control points with unit base and spline weights
+    assert_eq!(kan.snapshot(), vec![Vector::new(vec![0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0]); 2]);
+}
+
+#[test]
+fn builder_basis_pass() {
+    let builder: KANBuilder = KANBuilder::new(2).add_layer_with_basis(3, Basis::Rbf { trainable_centers: true }).add_layer(1).seed(7);
+    let kan: KAN = builder.build();
+
+    assert_eq!(builder.bases, vec![Basis::Rbf { trainable_centers: true }, Basis::BSpline]);
+    let edges = kan.edges();
+    assert!(edges[..6].iter().all(|edge| edge.borrow().activation.downcast_ref::<GaussianRbf>().is_some_and(|rbf| rbf.trainable_centers)));
+    assert!(edges[6..].iter().all(|edge| edge.borrow().activation.downcast_ref::<BSpline>().is_some()));
+    assert_eq!(kan.snapshot()[0].len(), 17);
+
+    assert!(kan.forward_logits(&Vector::new(vec![0.2, 0.7])).unwrap()[0].is_finite());
+}
+
+#[test]
+fn builder_grid_pass() {
+    let builder: KANBuilder = KANBuilder::new(2).add_layer(3).layer_grid(3, 8).add_layer_with_basis(1, Basis::Rbf { trainable_centers: false }).degree(1).grid_size(4).seed(5);
+    let kan: KAN = builder.build();
+
+    assert_eq!(builder.grids, vec![Some((3, 8)), None]);
+    assert_eq!((builder.grid_of(0), builder.grid_of(1)), ((3, 8), (1, 4)));
+    let edges = kan.edges();
+    assert!(edges[..6].iter().all(|edge| edge.borrow().activation.downcast_ref::<BSpline>().is_some_and(|spline| spline.degree == 3 && spline.control_points.len() == 8)));
+    assert!(edges[6..].iter().all(|edge| edge.borrow().activation.downcast_ref::<GaussianRbf>().is_some_and(|rbf| rbf.len() == 4)));
+    assert!(kan.forward(&Vector::new(vec![0.2, 0.7])).unwrap().is_finite());
+
+    // The default grid draws the same control points as before grids were configurable
+    assert!(KANBuilder::new(2).add_layer(1).degree(2).grid_size(5).seed(9).build().approx_eq(&KANBuilder::new(2).add_layer(1).seed(9).build(), 0.0));
+}
+
+#[test]
+#[should_panic(expected = "A B-spline must have more control points than its degree.")]
+fn builder_grid_fail() {
+    KANBuilder::new(2).add_layer(1).layer_grid(4, 4).build();
+}
+
+#[test]
+fn builder_base_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(2).add_layer(1).base(BaseFn::Identity).build();
+
+    assert!(kan.edges().iter().all(|edge| edge.borrow().base == BaseFn::Identity));
+    assert_eq!(KANBuilder::new(2).base, BaseFn::Silu);
+}
+
+#[test]
+fn builder_mult_layer_pass() {
+    let builder: KANBuilder = KANBuilder::new(2).add_mult_layer(2, 1).add_layer(1).seed(3);
+    let kan: KAN = builder.build();
+
+    assert_eq!(builder.widths, vec![2, 3, 1]);
+    assert_eq!(builder.products, vec![1, 0]);
+    let layer: Ref<Layer> = kan.layers[0].borrow();
+    let aggregations: Vec<Aggregation> = layer.nodes.iter().map(|node| node.borrow().aggregation).collect();
+    assert_eq!(aggregations, vec![Aggregation::Sum, Aggregation::Sum, Aggregation::Product]);
+    assert_eq!(kan.layers[1].borrow().nodes[0].borrow().aggregation, Aggregation::Sum);
+}
+
+#[test]
+#[should_panic]
+fn builder_mult_layer_fail() {
+    let mut builder: KANBuilder = KANBuilder::new(2).add_layer(1);
+    builder.products[0] = 2;
+    builder.build();
+}
+
+#[test]
+#[should_panic]
+fn builder_build_fail() {
+    KANBuilder::new(2).build();
+}
+
+#[test]
+#[should_panic]
+fn builder_zero_width_fail() {
+    KANBuilder::new(2).add_layer(0).build();
+}
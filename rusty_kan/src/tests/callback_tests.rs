@@ -0,0 +1,241 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::Node, layer::Layer};
+use crate::callback::{Callback, TrainingState, TrainingLogger, LogFormat, ProgressBar};
+use crate::kan::KAN;
+use crate::loss::Loss;
+use crate::trainer::{Trainer, History, EarlyStopping};
+use crate::error::KanError;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+fn single_layer_kan() -> KAN {
+    let edge: Rc<RefCell<Edge>> = Rc::new(RefCell::new(Edge::standard(0, 0, 0)));
+    let node: Rc<RefCell<Node>> = Rc::new(RefCell::new(Node::new(vec![edge], vec![], 0)));
+    KAN::new(vec![Rc::new(RefCell::new(Layer::new(vec![node])))])
+}
+
+fn dataset() -> (Matrix, Matrix) {
+    let inputs: Matrix = Matrix::new((0..4).map(|i| Vector::new(vec![0.1 + 0.2 * i as f64])).collect());
+    let targets: Matrix = Matrix::new((0..4).map(|i| Vector::new(vec![0.2 * i as f64])).collect());
+    (inputs, targets)
+}
+
+#[derive(Default)]
+struct Record {
+    epoch_starts: Vec<usize>,
+    epoch_ends: Vec<(usize, Option<f64>)>,
+    batches: usize,
+    learning_rates: Vec<f64>,
+}
+
+struct Recorder(Rc<RefCell<Record>>);
+
+impl Callback for Recorder {
+    fn on_epoch_start(&mut self, _kan: &KAN, state: &mut TrainingState) {
+        self.0.borrow_mut().epoch_starts.push(state.epoch);
+    }
+
+    fn on_epoch_end(&mut self, _kan: &KAN, state: &mut TrainingState) {
+        self.0.borrow_mut().epoch_ends.push((state.epoch, state.train_loss));
+    }
+
+    fn on_batch_end(&mut self, _kan: &KAN, _batch: usize, _loss: f64, state: &mut TrainingState) {
+        let mut record = self.0.borrow_mut();
+        record.batches += 1;
+        record.learning_rates.push(state.learning_rate);
+    }
+}
+
+struct HalveLearningRate;
+
+impl Callback for HalveLearningRate {
+    fn on_epoch_start(&mut self, _kan: &KAN, state: &mut TrainingState) {
+        state.learning_rate /= 2.0_f64.powi(state.epoch as i32);
+    }
+}
+
+struct StopAfter(usize);
+
+impl Callback for StopAfter {
+    fn on_epoch_end(&mut self, _kan: &KAN, state: &mut TrainingState) {
+        state.stop = state.epoch + 1 >= self.0;
+    }
+}
+
+#[test]
+fn training_state_new_pass() {
+    let state: TrainingState = TrainingState::new(0.1);
+
+    assert_eq!(state.epoch, 0);
+    assert_eq!(state.learning_rate, 0.1);
+    assert_eq!(state.train_loss, None);
+    assert_eq!(state.validation_loss, None);
+    assert_eq!(state.gradient_norm, None);
+    assert!(!state.stop);
+}
+
+#[test]
+fn callback_events_pass() {
+    let (inputs, targets) = dataset();
+    let record: Rc<RefCell<Record>> = Rc::new(RefCell::new(Record::default()));
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(), 0.1, Loss::MeanSquaredError, 0);
+    trainer.add_callback(Box::new(Recorder(record.clone())));
+
+    let history: History = trainer.fit(&inputs, &targets, 3).unwrap();
+
+    let record = record.borrow();
+    assert_eq!(record.epoch_starts, vec![0, 1, 2]);
+    assert_eq!(record.epoch_ends.len(), 3);
+    assert_eq!(record.batches, 12);
+    for (i, (epoch, train_loss)) in record.epoch_ends.iter().enumerate() {
+        assert_eq!(*epoch, i);
+        assert_eq!(*train_loss, Some(history.train_loss[i]));
+    }
+    assert_eq!(trainer.callbacks.len(), 1);
+}
+
+#[test]
+fn callback_learning_rate_pass() {
+    let (inputs, targets) = dataset();
+    let record: Rc<RefCell<Record>> = Rc::new(RefCell::new(Record::default()));
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(), 0.4, Loss::MeanSquaredError, 0);
+    trainer.add_callback(Box::new(HalveLearningRate));
+    trainer.add_callback(Box::new(Recorder(record.clone())));
+
+    trainer.fit(&inputs, &targets, 3).unwrap();
+
+    let record = record.borrow();
+    assert_eq!(record.learning_rates[0], 0.4);
+    assert_eq!(record.learning_rates[4], 0.2);
+    assert_eq!(record.learning_rates[8], 0.1);
+}
+
+#[test]
+fn callback_stop_pass() {
+    let (inputs, targets) = dataset();
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(), 0.1, Loss::MeanSquaredError, 0);
+    trainer.add_callback(Box::new(StopAfter(2)));
+
+    let history: History = trainer.fit(&inputs, &targets, 10).unwrap();
+
+    assert_eq!(history.train_loss.len(), 2);
+    assert_eq!(trainer.epoch, 2);
+}
+
+#[test]
+fn training_logger_csv_pass() {
+    let (inputs, targets) = dataset();
+    let path: std::path::PathBuf = std::env::temp_dir().join(format!("rusty_kan_training_log_{}.csv", std::process::id()));
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(), 0.1, Loss::MeanSquaredError, 0);
+    trainer.early_stopping = Some(EarlyStopping { validation_split: 0.25, patience: 10 });
+    trainer.add_callback(Box::new(TrainingLogger::create(&path, LogFormat::Csv).unwrap()));
+
+    let history: History = trainer.fit(&inputs, &targets, 3).unwrap();
+    let log: String = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "epoch,train_loss,validation_loss,learning_rate,gradient_norm,wall_time");
+    for (i, line) in lines[1..].iter().enumerate() {
+        let fields: Vec<f64> = line.split(',').map(|field| field.parse().unwrap()).collect();
+        assert_eq!(fields[0], i as f64);
+        assert_eq!(fields[1], history.train_loss[i]);
+        assert_eq!(fields[2], history.validation_loss[i]);
+        assert_eq!(fields[3], 0.1);
+        assert!(fields[4] > 0.0);
+        assert!(fields[5] >= 0.0);
+    }
+}
+
+#[test]
+fn training_logger_jsonl_pass() {
+    let (inputs, targets) = dataset();
+    let path: std::path::PathBuf = std::env::temp_dir().join(format!("rusty_kan_training_log_{}.jsonl", std::process::id()));
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(), 0.1, Loss::MeanSquaredError, 0);
+    trainer.add_callback(Box::new(TrainingLogger::create(&path, LogFormat::Jsonl).unwrap()));
+
+    let history: History = trainer.fit(&inputs, &targets, 2).unwrap();
+    let log: String = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let records: Vec<serde_json::Value> = log.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1]["epoch"], 1);
+    assert_eq!(records[1]["train_loss"], history.train_loss[1]);
+    // Without a validation set there is no validation loss
+    assert!(records[1]["validation_loss"].is_null());
+    assert!(records[1]["gradient_norm"].as_f64().unwrap() > 0.0);
+    assert!(records[1]["wall_time"].as_f64().unwrap() >= records[0]["wall_time"].as_f64().unwrap());
+}
+
+struct FailingWriter;
+
+impl std::io::Write for FailingWriter {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("closed"))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn training_logger_fail() {
+    assert!(TrainingLogger::create(std::env::temp_dir().join("rusty_kan_missing_directory").join("log.csv"), LogFormat::Csv).is_err());
+
+    let mut logger: TrainingLogger = TrainingLogger::new(Box::new(FailingWriter), LogFormat::Jsonl);
+    assert_eq!(logger.error(), None);
+    logger.on_epoch_end(&single_layer_kan(), &mut TrainingState::new(0.1));
+    assert_eq!(logger.error(), Some(&KanError::Io("Failed to write the training log.")));
+}
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl std::io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn progress_bar_pass() {
+    let (inputs, targets) = dataset();
+    let buffer: SharedBuffer = SharedBuffer::default();
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(), 0.1, Loss::MeanSquaredError, 0);
+    trainer.add_callback(Box::new(ProgressBar::with_writer(2, Box::new(buffer.clone()))));
+
+    trainer.fit(&inputs, &targets, 2).unwrap();
+    let output: String = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+
+    // One redraw per batch and per epoch, and the line ends after the last epoch
+    let lines: Vec<&str> = output.split('\r').filter(|line| !line.is_empty()).collect();
+    assert_eq!(lines.len(), 10);
+    assert!(lines[0].starts_with("epoch 1/2 [------------------------------]   0% batch 1 loss "));
+    assert!(lines[5].starts_with("epoch 2/2 [###################-----------]  62% batch 1/4 loss "));
+    assert!(lines[5].contains(" eta "));
+    assert!(lines[9].starts_with("epoch 2/2 [##############################] 100% batch 4/4 loss "));
+    assert!(output.ends_with('\n'));
+    assert_eq!(output.matches('\n').count(), 1);
+}
+
+#[test]
+fn progress_bar_stop_pass() {
+    let (inputs, targets) = dataset();
+    let buffer: SharedBuffer = SharedBuffer::default();
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(), 0.1, Loss::MeanSquaredError, 0);
+    trainer.add_callback(Box::new(StopAfter(1)));
+    trainer.add_callback(Box::new(ProgressBar::with_writer(10, Box::new(buffer.clone()))));
+
+    trainer.fit(&inputs, &targets, 10).unwrap();
+    let output: String = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+
+    assert!(output.ends_with('\n'));
+    assert!(output.trim_end().rsplit('\r').next().unwrap().starts_with("epoch 1/10 [###---------------------------]  10% batch 4/4"));
+}
@@ -0,0 +1,46 @@
+use crate::builder::KANBuilder;
+use crate::data_structures::{edge::Edge, spline::BSpline};
+use crate::codegen;
+use crate::kan::KAN;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn codegen_rust_pass() {
+    let kan: KAN = KAN::classifier(2, 3, 2);
+
+    let source: String = codegen::rust(&kan).unwrap();
+
+    assert!(source.contains("pub const INPUTS: usize = 2;"));
+    assert!(source.contains("pub const OUTPUTS: usize = 2;"));
+    assert!(source.contains("pub fn predict(input: &[f64]) -> f64"));
+    assert!(source.contains("pub fn forward(input: &[f64]) -> [f64; OUTPUTS]"));
+    assert!(!source.contains("{LOG_FLOOR}"));
+    assert!(!source.contains("{RECIPROCAL_FLOOR}"));
+    assert!(!source.contains("use "));
+    // The control points are written so that they parse to exactly the same numbers
+    let edge: Rc<RefCell<Edge>> = kan.edges()[0].clone();
+    let control_points: String = edge.borrow().activation.downcast_ref::<BSpline>().unwrap().control_points.elements.iter().map(|x| format!("{:?}", x)).collect::<Vec<String>>().join(", ");
+    assert!(source.contains(&format!("control_points: &[{}]", control_points)));
+}
+
+#[test]
+fn codegen_rust_product_pass() {
+    let kan: KAN = KANBuilder::new(2).add_mult_layer(1, 2).add_layer(1).seed(6).build();
+
+    let source: String = codegen::rust(&kan).unwrap();
+
+    assert!(source.contains("static PRODUCTS: &[&[bool]] = &[\n    &[false, true, true],\n    &[false],\n];"));
+}
+
+#[test]
+fn codegen_rust_fail() {
+    assert!(codegen::rust(&KAN::new(vec![])).is_err());
+}
+
+#[test]
+fn kan_codegen_rust_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 5);
+
+    assert_eq!(kan.codegen_rust().unwrap(), codegen::rust(&kan).unwrap());
+}
@@ -0,0 +1,131 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge};
+use crate::compiled::{CompiledKAN, Lut};
+use crate::kan::KAN;
+use crate::error::KanError;
+use std::thread;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn compiled_forward_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 5);
+    let compiled: CompiledKAN = kan.compile();
+    let input: Vector = Vector::new(vec![0.2, 0.7]);
+
+    let expected: Vector = kan.forward_logits(&input).unwrap();
+    let output: Vector = compiled.forward(&input).unwrap();
+
+    assert_eq!(compiled.layers.len(), 2);
+    assert_eq!(compiled.layers[0].len(), 3);
+    assert_eq!(output.len(), 1);
+    assert_is_close!(output[0], expected[0], 1e-12);
+}
+
+#[test]
+fn compiled_forward_batch_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 5);
+    let compiled: CompiledKAN = kan.compile();
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.7]), Vector::new(vec![0.9, 0.1])]);
+
+    let outputs: Matrix = compiled.forward_batch(&inputs).unwrap();
+
+    assert_eq!(outputs.shape(), (2, 1));
+    for i in 0..2 {
+        assert_is_close!(outputs[i][0], compiled.forward(&inputs.row(i)).unwrap()[0], 1e-12);
+    }
+}
+
+#[test]
+fn compiled_frozen_pass() {
+    let kan: KAN = KAN::standard_seeded(1, 1, 5);
+    let compiled: CompiledKAN = kan.compile();
+    let input: Vector = Vector::new(vec![0.4]);
+    let before: f64 = compiled.forward(&input).unwrap()[0];
+
+    kan.edges()[0].borrow_mut().spline_weight += 1.0;
+
+    assert_eq!(compiled.forward(&input).unwrap()[0], before);
+    assert_ne!(kan.compile().forward(&input).unwrap()[0], before);
+}
+
+#[test]
+fn compiled_threads_pass() {
+    assert_send_sync::<CompiledKAN>();
+    assert_send_sync::<Edge>();
+
+    let kan: KAN = KAN::standard_seeded(2, 3, 5);
+    let compiled: CompiledKAN = kan.compile();
+    let inputs: Vec<Vector> = (0..8).map(|i| Vector::new(vec![i as f64 / 8.0, 1.0 - i as f64 / 8.0])).collect();
+    let expected: Vec<f64> = inputs.iter().map(|input| compiled.forward(input).unwrap()[0]).collect();
+
+    let outputs: Vec<f64> = thread::scope(|scope| {
+        let handles: Vec<thread::ScopedJoinHandle<f64>> = inputs.iter().map(|input| scope.spawn(|| compiled.forward(input).unwrap()[0])).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    assert_eq!(outputs, expected);
+}
+
+#[test]
+fn compiled_predict_class_pass() {
+    let kan: KAN = KAN::classifier(2, 3, 4);
+    let compiled: CompiledKAN = kan.compile();
+    let input: Vector = Vector::new(vec![0.2, 0.7]);
+
+    let probabilities: Vector = compiled.predict_proba(&input).unwrap();
+
+    assert_is_close!(probabilities.elements.iter().sum::<f64>(), 1.0, 1e-9);
+    assert_eq!(compiled.predict_class(&input).unwrap(), kan.predict_class(input).unwrap());
+}
+
+#[test]
+fn compiled_forward_fail() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 5);
+    let compiled: CompiledKAN = kan.compile();
+    assert!(matches!(compiled.forward(&Vector::new(vec![0.2])), Err(KanError::DimensionMismatch { .. })));
+    assert!(matches!(compiled.forward_batch(&Matrix::new(vec![Vector::new(vec![0.2])])), Err(KanError::DimensionMismatch { .. })));
+    assert!(compiled.predict_class(&Vector::new(vec![])).is_err());
+}
+
+#[test]
+fn compiled_with_luts_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 5);
+    let exact: CompiledKAN = kan.compile();
+    let compiled: CompiledKAN = kan.compile().with_luts(2048);
+
+    assert_eq!(compiled.luts.len(), 2);
+    assert_eq!(compiled.luts[0][1].len(), 2);
+    for i in 0..10 {
+        let input: Vector = Vector::new(vec![0.1 * i as f64, 0.95 - 0.1 * i as f64]);
+        assert_is_close!(compiled.forward(&input).unwrap()[0], exact.forward(&input).unwrap()[0], 1e-4);
+    }
+
+    // The table interpolates inside the domain of the activation, and the edge is evaluated exactly outside of it
+    let edge: Edge = Edge::standard(0, 0, 0);
+    let lut: Lut = Lut::new(&edge, 3);
+    assert_eq!(lut.value(0.0), Some(edge.value(0.0)));
+    assert_is_close!(lut.value(0.25).unwrap(), 0.5 * (edge.value(0.0) + edge.value(0.5)), 1e-12);
+    assert_eq!(lut.value(1.0), Some(edge.value(1.0)));
+    assert_eq!(lut.value(1.5), None);
+    let input: Vector = Vector::new(vec![1.5, -0.5]);
+    assert_eq!(compiled.forward(&input).unwrap(), exact.forward(&input).unwrap());
+}
+
+#[test]
+#[should_panic]
+fn compiled_with_luts_fail() {
+    KAN::standard(2, 3).compile().with_luts(1);
+}
@@ -0,0 +1,160 @@
+use crate::builder::KANBuilder;
+use crate::config::{Config, TrainingConfig};
+use crate::data_structures::{activation::Basis, edge::BaseFn};
+use crate::error::KanError;
+use crate::init::Init;
+use crate::kan::KAN;
+use crate::loss::Loss;
+use crate::optimizer::Optimizer;
+use crate::scheduler::Scheduler;
+use crate::trainer::{Trainer, EarlyStopping, Regularization};
+use std::path::PathBuf;
+
+const TOML: &str = r#"
+# A small regression experiment
+[model]
+widths = [2, 3, 1]
+basis = "rbf"
+trainable_centers = true
+base = "tanh"
+seed = 7
+
+[model.init]
+kind = "uniform"
+low = -0.5
+high = 0.5
+
+[training]
+learning_rate = 0.05
+loss = "cross_entropy"
+epochs = 20
+batch_size = 4
+shuffle = false
+seed = 3
+
+[training.optimizer]
+kind = "momentum"
+beta = 0.8
+
+[training.scheduler]
+kind = "step_decay"
+step_size = 10
+gamma = 0.5
+
+[training.early_stopping]
+validation_split = 0.25
+patience = 2
+
+[training.regularization]
+l1 = 1e-3
+"#;
+
+#[test]
+fn config_toml_pass() {
+    let config: Config = Config::from_toml(TOML).unwrap();
+    let builder: KANBuilder = KANBuilder::new(2).add_layer_with_basis(3, Basis::Rbf { trainable_centers: true }).add_layer_with_basis(1, Basis::Rbf { trainable_centers: true })
+        .base(BaseFn::Tanh).init(Init::Uniform { low: -0.5, high: 0.5 }).seed(7);
+    assert_eq!(config.builder, builder);
+    assert!(config.build().approx_eq(&builder.build(), 0.0));
+    assert_eq!(config.training, TrainingConfig {
+        learning_rate: 0.05,
+        loss: Loss::CrossEntropy,
+        optimizer: Optimizer::Momentum { beta: 0.8 },
+        scheduler: Scheduler::StepDecay { step_size: 10, gamma: 0.5 },
+        epochs: 20,
+        batch_size: 4,
+        shuffle: false,
+        drop_last: false,
+        seed: 3,
+        early_stopping: Some(EarlyStopping { validation_split: 0.25, patience: 2 }),
+        regularization: Regularization { smoothness: 0.0, l1: 1e-3, entropy: 0.0 },
+    });
+
+    let trainer: Trainer = config.trainer();
+    assert_eq!(trainer.learning_rate, 0.05);
+    assert_eq!(trainer.optimizer, Optimizer::Momentum { beta: 0.8 });
+    assert_eq!(trainer.loader.batch_size, 4);
+    assert!(!trainer.loader.shuffle);
+    assert_eq!(trainer.early_stopping, config.training.early_stopping);
+    assert!(trainer.kan.approx_eq(&builder.build(), 0.0));
+}
+
+#[test]
+fn config_json_pass() {
+    let config: Config = Config::from_json(r#"{"model": {"widths": [3, 2], "seed": 1}, "training": {"optimizer": {"kind": "adam", "beta1": 0.5}}}"#).unwrap();
+    assert_eq!(config.builder, KANBuilder::new(3).add_layer(2).seed(1));
+    let Optimizer::Adam { beta2, epsilon, .. } = Optimizer::adam() else { unreachable!() };
+    assert_eq!(config.training.optimizer, Optimizer::Adam { beta1: 0.5, beta2, epsilon });
+    assert_eq!(config.training.scheduler, TrainingConfig::default().scheduler);
+
+    // Without a training section the defaults of the trainer are used
+    let config: Config = Config::from_json(r#"{"model": {"widths": [1, 1]}}"#).unwrap();
+    assert_eq!(config.builder, KANBuilder::new(1).add_layer(1));
+    assert_eq!(config.training, TrainingConfig::default());
+}
+
+#[test]
+fn config_grid_pass() {
+    let config: Config = Config::from_toml("[model]\nwidths = [2, 3, 1]\ndegree = 3\ngrid_size = 8").unwrap();
+    assert_eq!(config.builder, KANBuilder::new(2).add_layer(3).add_layer(1).degree(3).grid_size(8));
+
+    // Arrays give every layer its own grid
+    let config: Config = Config::from_toml("[model]\nwidths = [2, 3, 1]\ndegree = 3\ngrid_size = [8, 6]").unwrap();
+    assert_eq!(config.builder, KANBuilder::new(2).add_layer(3).layer_grid(3, 8).add_layer(1).layer_grid(3, 6).degree(3));
+}
+
+#[test]
+fn kan_from_config_pass() {
+    let toml: PathBuf = std::env::temp_dir().join(format!("rusty_kan_config_{}.toml", std::process::id()));
+    let json: PathBuf = std::env::temp_dir().join(format!("rusty_kan_config_{}.json", std::process::id()));
+    std::fs::write(&toml, "[model]\nwidths = [2, 4, 1]  # inputs first\nseed = 5\n").unwrap();
+    std::fs::write(&json, r#"{"model": {"widths": [2, 4, 1], "seed": 5}}"#).unwrap();
+    let from_toml: KAN = KAN::from_config(&toml).unwrap();
+    let from_json: KAN = KAN::from_config(&json).unwrap();
+    std::fs::remove_file(&toml).unwrap();
+    std::fs::remove_file(&json).unwrap();
+
+    let expected: KAN = KANBuilder::new(2).add_layer(4).add_layer(1).seed(5).build();
+    assert!(from_toml.approx_eq(&expected, 0.0));
+    assert!(from_json.approx_eq(&expected, 0.0));
+    assert_eq!(from_toml.inputs, 2);
+    assert!(matches!(KAN::from_config(std::env::temp_dir().join("rusty_kan_missing_config.toml")), Err(KanError::Io(_))));
+}
+
+#[test]
+fn config_toml_syntax_pass() {
+    // Multiline arrays, inline tables, literal strings, and dotted keys are read as in any TOML document
+    let config: Config = Config::from_toml(r#"
+model.widths = [
+    2,
+    3, # hidden
+    1,
+]
+model.base = 'identity'
+model.init = { kind = "normal", std = 0.5 }
+training = { optimizer = { kind = "adam" }, scheduler.kind = "exponential_decay", scheduler.gamma = 0.9 }
+"#).unwrap();
+    assert_eq!(config.builder, KANBuilder::new(2).add_layer(3).add_layer(1).base(BaseFn::Identity).init(Init::Normal { mean: 0.0, std: 0.5 }));
+    assert_eq!(config.training.optimizer, Optimizer::adam());
+    assert_eq!(config.training.scheduler, Scheduler::ExponentialDecay { gamma: 0.9 });
+}
+
+#[test]
+fn config_fail() {
+    let schema: KanError = KanError::InvalidConfig("The configuration has an unknown key, lacks a required key, or has a value of the wrong type.");
+    assert!(matches!(Config::from_json("{"), Err(KanError::Format(_))));
+    assert!(matches!(Config::from_toml("[model\nwidths = [2, 1]"), Err(KanError::Format(_))));
+    assert!(matches!(Config::from_toml("[model]\nwidths = [2, 1]\nwidths = [3, 1]"), Err(KanError::Format(_))));
+    assert_eq!(Config::from_toml("[training]\nepochs = 3"), Err(KanError::InvalidConfig("The configuration must have a model section.")));
+    assert_eq!(Config::from_toml("[model]\nwidths = [2]"), Err(KanError::InvalidConfig("The widths of the model must have the number of inputs and the width of at least one layer.")));
+    assert_eq!(Config::from_toml("[model]\nwidths = [2, 0]"), Err(KanError::InvalidConfig("The number of inputs and the width of every layer must be greater than 0.")));
+    assert_eq!(Config::from_toml("[model]\nwidths = [2, 1]\nlearning_rate = 0.1"), Err(schema.clone()));
+    assert_eq!(Config::from_toml("[model]\nwidths = [2, 1]\nbasis = \"fourier\""), Err(schema.clone()));
+    assert_eq!(Config::from_toml("[model]\nwidths = [2, 1]\nbase = 1"), Err(schema.clone()));
+    assert_eq!(Config::from_json(r#"{"model": {"seed": 1}}"#), Err(schema.clone()));
+    assert_eq!(Config::from_toml("[model]\nwidths = [2, 1]\ndegree = 5"), Err(KanError::InvalidConfig("A B-spline must have more control points than its degree.")));
+    assert!(matches!(Config::from_toml("[model]\nwidths = [2, 3, 1]\ngrid_size = [8]"), Err(KanError::InvalidConfig(_))));
+    assert_eq!(Config::from_toml("[model]\nwidths = [2, 1]\n[training]\nbatch_size = 0"), Err(KanError::InvalidConfig("The batch size must be greater than 0.")));
+    assert_eq!(Config::from_toml("[model]\nwidths = [2, 1]\n[training.optimizer]\nkind = \"sgd\"\nbeta = 0.9"), Err(schema.clone()));
+    assert_eq!(Config::from_toml("[model]\nwidths = [2, 1]\n[training.scheduler]\nkind = \"exponential_decay\""), Err(schema));
+}
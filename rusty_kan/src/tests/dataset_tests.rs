@@ -0,0 +1,118 @@
+use crate::builder::KANBuilder;
+use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::datasets::dataset::{Dataset, DataLoader, cross_validate};
+use crate::loss::Loss;
+use crate::trainer::Trainer;
+
+fn dataset(samples: usize) -> Dataset {
+    let inputs: Matrix = Matrix::new((0..samples).map(|i| Vector::new(vec![i as f64 / samples as f64, 0.5])).collect());
+    let targets: Matrix = Matrix::new((0..samples).map(|i| Vector::new(vec![i as f64])).collect());
+    Dataset::new(inputs, targets)
+}
+
+#[test]
+fn dataset_select_pass() {
+    let dataset: Dataset = dataset(5);
+    let selected: Dataset = dataset.select(&[3, 0]);
+
+    assert_eq!(selected.len(), 2);
+    assert_eq!(selected.inputs.row(0), dataset.inputs.row(3));
+    assert_eq!(selected.targets.get_col(0), Vector::new(vec![3.0, 0.0]));
+}
+
+#[test]
+#[should_panic]
+fn dataset_new_fail() {
+    Dataset::new(Matrix::zeros(3, 2), Matrix::zeros(2, 1));
+}
+
+#[test]
+fn dataset_split_pass() {
+    let dataset: Dataset = dataset(10);
+    let parts: Vec<Dataset> = dataset.split(&[0.6, 0.2, 0.2], 42);
+
+    assert_eq!(parts.iter().map(|part| part.len()).collect::<Vec<usize>>(), vec![6, 2, 2]);
+    // Every sample is in exactly one part, with its inputs and targets kept together
+    let mut targets: Vec<f64> = parts.iter().flat_map(|part| part.targets.elements.clone()).collect();
+    for part in parts.iter() {
+        for i in 0..part.len() {
+            assert_eq!(part.inputs[i][0] * 10.0, part.targets[i][0]);
+        }
+    }
+    targets.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(targets, (0..10).map(|i| i as f64).collect::<Vec<f64>>());
+
+    // The same seed gives the same split
+    assert_eq!(dataset.split(&[0.6, 0.2, 0.2], 42), parts);
+}
+
+#[test]
+#[should_panic]
+fn dataset_split_fail() {
+    dataset(10).split(&[0.5, 0.4], 42);
+}
+
+#[test]
+fn dataset_kfold_pass() {
+    let folds: Vec<(Dataset, Dataset)> = dataset(7).kfold(3);
+
+    assert_eq!(folds.len(), 3);
+    assert_eq!(folds.iter().map(|(_, validation)| validation.len()).collect::<Vec<usize>>(), vec![2, 2, 3]);
+    assert_eq!(folds[1].1.targets.get_col(0), Vector::new(vec![2.0, 3.0]));
+    assert_eq!(folds[1].0.targets.get_col(0), Vector::new(vec![0.0, 1.0, 4.0, 5.0, 6.0]));
+}
+
+#[test]
+#[should_panic]
+fn dataset_kfold_fail() {
+    dataset(3).kfold(4);
+}
+
+#[test]
+fn data_loader_batches_pass() {
+    let dataset: Dataset = dataset(7);
+    let mut loader: DataLoader = DataLoader::new(3, 42);
+    let batches: Vec<Dataset> = loader.batches(&dataset);
+
+    assert_eq!(batches.iter().map(|batch| batch.len()).collect::<Vec<usize>>(), vec![3, 3, 1]);
+    let mut targets: Vec<f64> = batches.iter().flat_map(|batch| batch.targets.elements.clone()).collect();
+    targets.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(targets, (0..7).map(|i| i as f64).collect::<Vec<f64>>());
+
+    // A new order every epoch, reproducible from the seed
+    let second: Vec<Vec<usize>> = loader.indices(7);
+    let mut replay: DataLoader = DataLoader::new(3, 42);
+    replay.indices(7);
+    assert_eq!(replay.indices(7), second);
+}
+
+#[test]
+fn data_loader_drop_last_pass() {
+    let mut loader: DataLoader = DataLoader::new(3, 0);
+    loader.shuffle = false;
+    loader.drop_last = true;
+
+    assert_eq!(loader.indices(7), vec![vec![0, 1, 2], vec![3, 4, 5]]);
+    assert!(loader.indices(2).is_empty());
+}
+
+#[test]
+#[should_panic]
+fn data_loader_indices_fail() {
+    DataLoader::new(0, 0).indices(4);
+}
+
+#[test]
+fn cross_validate_pass() {
+    let builder: KANBuilder = KANBuilder::new(2).add_layer(1).seed(7);
+    let dataset: Dataset = dataset(6);
+    let losses: Vector = cross_validate(&builder, &dataset, 3, |kan| Trainer::new(kan, 0.01, Loss::MeanSquaredError, 7), 2).unwrap();
+
+    assert_eq!(losses.len(), 3);
+    assert!(losses.iter().all(|loss| loss.is_finite() && *loss >= 0.0));
+    // The first fold is evaluated on the first two samples after training on the rest
+    let (train, validation) = &dataset.kfold(3)[0];
+    let mut trainer: Trainer = Trainer::new(builder.build(), 0.01, Loss::MeanSquaredError, 7);
+    trainer.fit(&train.inputs, &train.targets, 2).unwrap();
+    assert_eq!(losses[0], trainer.evaluate(&validation.inputs, &validation.targets).unwrap());
+}
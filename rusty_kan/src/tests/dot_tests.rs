@@ -0,0 +1,65 @@
+use crate::builder::{KANBuilder, Skip};
+use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::kan::KAN;
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.1, 0.8]), Vector::new(vec![0.4, 0.3]), Vector::new(vec![0.9, 0.5])])
+}
+
+fn edge_lines(dot: &str) -> Vec<&str> {
+    dot.lines().filter(|line| line.contains("->")).collect()
+}
+
+#[test]
+fn kan_to_dot_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).add_layer(1).seed(1).build();
+
+    let dot: String = kan.to_dot();
+
+    assert!(dot.starts_with("digraph KAN {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains("{ rank=same; x0 [label=\"x0\", shape=plaintext]; x1 [label=\"x1\", shape=plaintext]; }"));
+    assert!(dot.contains("{ rank=same; n0_0; n0_1; n0_2; }"));
+    assert!(dot.contains("{ rank=same; n1_0; }"));
+    assert_eq!(edge_lines(&dot).len(), 2 * 3 + 3);
+    assert!(dot.contains("    x1 -> n0_2;\n"));
+    assert!(dot.contains("    n0_2 -> n1_0;\n"));
+}
+
+#[test]
+fn kan_to_dot_scores_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).add_layer(1).seed(1).build();
+    kan.update_edge_scores(&inputs()).unwrap();
+
+    let dot: String = kan.to_dot();
+    let widths: Vec<f64> = edge_lines(&dot).iter().map(|line| line.split("penwidth=").nth(1).unwrap().split(',').next().unwrap().parse().unwrap()).collect();
+
+    // The edge with the largest score has the largest width
+    assert_eq!(widths.len(), 9);
+    assert_eq!(widths.iter().copied().fold(0.0, f64::max), 5.0);
+    assert!(widths.iter().all(|&width| width >= 0.1));
+    assert!(dot.contains("tooltip=\"score "));
+
+    // Pruned edges are left out of the graph
+    let scores: Vec<f64> = kan.edges().iter().map(|edge| edge.borrow().score.unwrap()).collect();
+    let median: f64 = {
+        let mut sorted: Vec<f64> = scores.clone();
+        sorted.sort_by(f64::total_cmp);
+        sorted[4]
+    };
+    let removed: usize = kan.prune(median).unwrap();
+    assert_eq!(edge_lines(&kan.to_dot()).len(), 9 - removed);
+}
+
+#[test]
+fn kan_to_dot_layers_pass() {
+    let kan: KAN = KANBuilder::new(2).add_mult_layer(1, 1).add_layer(2).add_layer(1).skip(Skip::Spline).seed(3).build();
+    kan.edges()[0].borrow_mut().frozen = true;
+
+    let dot: String = kan.to_dot();
+
+    assert!(dot.contains("n0_1 [shape=box, label=\"×\"]"));
+    // The skip edges of the third layer start at the nodes of the first layer
+    assert!(dot.contains("    n0_0 -> n2_0 [style=dashed];\n"));
+    assert!(edge_lines(&dot).iter().any(|line| line.ends_with("[color=gray];")));
+}
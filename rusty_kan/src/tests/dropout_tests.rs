@@ -0,0 +1,175 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::builder::KANBuilder;
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::{Node, Aggregation}, layer::Layer};
+use crate::error::KanError;
+use crate::kan::{KAN, grad_check};
+use crate::loss::Loss;
+use crate::trainer::Trainer;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.1, 0.8]), Vector::new(vec![0.4, 0.3]), Vector::new(vec![0.9, 0.5]), Vector::new(vec![0.6, 0.2])])
+}
+
+#[test]
+fn edge_dropout_scale_pass() {
+    let mut edge: Edge = Edge::standard(0, 0, 0);
+    assert_eq!(edge.dropout_scale(), 1.0);
+
+    edge.set_dropout(0.25);
+    assert_eq!(edge.dropout_scale(), 0.75);
+    edge.masked = Some(true);
+    assert_eq!(edge.dropout_scale(), 0.0);
+    edge.masked = Some(false);
+    assert_eq!(edge.dropout_scale(), 1.0);
+}
+
+#[test]
+#[should_panic]
+fn edge_set_dropout_fail() {
+    Edge::standard(0, 0, 0).set_dropout(1.0);
+}
+
+#[test]
+fn node_dropout_pass() {
+    let incoming_edges: Vec<Rc<RefCell<Edge>>> = (0..3).map(|i| Rc::new(RefCell::new(Edge::standard(i, 0, 0)))).collect();
+    incoming_edges[1].borrow_mut().masked = Some(true);
+    let t: Vector = Vector::new(vec![0.2, 0.5, 0.7]);
+    let values: Vec<f64> = (0..3).map(|i| incoming_edges[i].borrow().value(t[i])).collect();
+    let mut node: Node = Node::new(incoming_edges, vec![], 0);
+
+    // The masked edge is left out of the sum and receives no gradient
+    assert_is_close!(node.forward(&t).unwrap(), values[0] + values[2], 1e-12);
+    assert_eq!(node.edge_gradients(&t, 2.0), Vector::new(vec![2.0, 0.0, 2.0]));
+
+    node.aggregation = Aggregation::Product;
+    node.incoming[1].borrow_mut().masked = Some(false);
+    node.incoming[2].borrow_mut().set_dropout(0.5);
+    assert_is_close!(node.forward(&t).unwrap(), values[0] * values[1] * 0.5 * values[2], 1e-12);
+    assert_is_close!(node.edge_gradients(&t, 1.0)[0], values[1] * 0.5 * values[2], 1e-12);
+    assert_is_close!(node.edge_gradients(&t, 1.0)[2], values[0] * values[1] * 0.5, 1e-12);
+}
+
+#[test]
+fn kan_sample_dropout_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).dropout(0.5).add_layer(1).seed(4).build();
+    let input: Vector = Vector::new(vec![0.3, 0.6]);
+    let expected: f64 = kan.forward(&input).unwrap();
+
+    kan.sample_dropout(&mut ChaCha8Rng::seed_from_u64(1));
+
+    // Only the edges of the first layer are masked, and the masked edges are left out of the hidden nodes
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    assert!(edges[..6].iter().all(|edge| edge.borrow().masked.is_some()));
+    assert!(edges[6..].iter().all(|edge| edge.borrow().masked.is_none()));
+    let hidden: Vector = kan.layers[0].borrow().activations(kan.input_matrix(&input)).unwrap();
+    for (j, node) in kan.layers[0].borrow().nodes.iter().enumerate() {
+        let kept: f64 = node.borrow().incoming.iter().filter(|edge| edge.borrow().masked == Some(false)).map(|edge| edge.borrow().value(input[edge.borrow().start])).sum();
+        assert_is_close!(hidden[j], kept, 1e-12);
+    }
+
+    // Outside of training steps the edges are scaled by the keep probability, as in the compiled model
+    kan.clear_dropout();
+    assert_eq!(kan.forward(&input).unwrap(), expected);
+    let scaled: f64 = kan.layers[0].borrow().nodes[0].borrow().incoming.iter().map(|edge| 0.5 * edge.borrow().value(input[edge.borrow().start])).sum();
+    assert_is_close!(kan.layers[0].borrow().activations(kan.input_matrix(&input)).unwrap()[0], scaled, 1e-12);
+    assert_is_close!(kan.compile().forward(&input).unwrap()[0], expected, 1e-12);
+}
+
+#[test]
+fn kan_loss_dropout_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).dropout(0.5).add_layer(1).seed(4).build();
+    let targets: Matrix = Matrix::new((0..4).map(|_| Vector::new(vec![0.5])).collect());
+
+    // The loss of a training step is that of the masked edges, not of the compiled model
+    kan.sample_dropout(&mut ChaCha8Rng::seed_from_u64(1));
+    let masked: Vec<f64> = (0..4).map(|i| Loss::MeanSquaredError.compute(&kan.forward_features(&inputs().row(i)).unwrap(), &targets.row(i)).unwrap()).collect();
+    assert_is_close!(kan.loss_with(inputs(), targets.clone(), Loss::MeanSquaredError).unwrap(), masked.iter().sum::<f64>() / 4.0, 1e-12);
+    assert_ne!(kan.compile().forward(&inputs().row(0)).unwrap(), kan.forward_features(&inputs().row(0)).unwrap());
+}
+
+#[test]
+fn kan_dropout_grad_check_pass() {
+    let incoming_edges: Vec<Rc<RefCell<Edge>>> = (0..3).map(|i| Rc::new(RefCell::new(Edge::standard(i, 0, 0)))).collect();
+    incoming_edges[0].borrow_mut().set_dropout(0.3);
+    incoming_edges[1].borrow_mut().masked = Some(true);
+    let layer: Layer = Layer::new(vec![Rc::new(RefCell::new(Node::new(incoming_edges, vec![], 0)))]);
+    let kan: KAN = KAN::new(vec![Rc::new(RefCell::new(layer))]);
+
+    let errors: Vector = grad_check(&kan, &Vector::new(vec![0.3, 0.6, 0.9]), &Vector::new(vec![2.0]), Loss::MeanSquaredError, 1e-5).unwrap();
+
+    for i in 0..errors.len() {
+        assert!(errors[i] < 1e-6);
+    }
+}
+
+#[test]
+fn kan_predict_with_uncertainty_pass() {
+    let input: Vector = Vector::new(vec![0.3, 0.6]);
+    let plain: KAN = KANBuilder::new(2).add_layer(3).add_layer(2).seed(4).build();
+    let (mean, std) = plain.predict_with_uncertainty(&input, 5).unwrap();
+    assert_eq!(mean, plain.forward_logits(&input).unwrap());
+    assert_eq!(std, Vector::zeros(2));
+
+    // The masks are drawn in evaluation mode too, and a single-layer KAN averages to the output scaled by the keep probability
+    let mut kan: KAN = KANBuilder::new(2).add_layer(1).dropout(0.5).seed(4).build();
+    kan.eval_mode();
+    let values: Vec<f64> = kan.edges().iter().map(|edge| edge.borrow().value(input[edge.borrow().start])).collect();
+    let (mean, std) = kan.predict_with_uncertainty_with_rng(&input, 4000, &mut ChaCha8Rng::seed_from_u64(3)).unwrap();
+    assert_is_close!(mean[0], kan.forward(&input).unwrap(), 0.05 * (values[0].abs() + values[1].abs()));
+    assert_is_close!(std[0], 0.5 * (values[0] * values[0] + values[1] * values[1]).sqrt(), 0.05 * (values[0].abs() + values[1].abs()));
+    assert!(kan.edges().iter().all(|edge| edge.borrow().masked.is_none()));
+    assert_eq!(kan.predict_with_uncertainty_with_rng(&input, 10, &mut ChaCha8Rng::seed_from_u64(1)), kan.predict_with_uncertainty_with_rng(&input, 10, &mut ChaCha8Rng::seed_from_u64(1)));
+}
+
+#[test]
+fn kan_predict_with_uncertainty_fail() {
+    assert_eq!(KAN::standard(2, 3).predict_with_uncertainty(&Vector::new(vec![0.3, 0.6]), 0), Err(KanError::InvalidConfig("The number of samples must be greater than 0.")));
+    assert!(matches!(KAN::standard(2, 3).predict_with_uncertainty(&Vector::new(vec![0.3]), 4), Err(KanError::DimensionMismatch { .. })));
+}
+
+#[test]
+#[should_panic]
+fn builder_dropout_fail() {
+    KANBuilder::new(2).dropout(0.1);
+}
+
+#[test]
+fn trainer_dropout_pass() {
+    let builder: KANBuilder = KANBuilder::new(2).add_layer(3).dropout(0.2).add_layer(1).seed(8);
+    let targets: Matrix = Matrix::new((0..4).map(|i| Vector::new(vec![inputs()[i][0] * inputs()[i][1]])).collect());
+    let mut trainer: Trainer = Trainer::new(builder.build(), 0.01, Loss::MeanSquaredError, 8);
+    let mut eval: Trainer = Trainer::new(builder.build(), 0.01, Loss::MeanSquaredError, 8);
+    eval.kan.eval_mode();
+
+    trainer.fit(&inputs(), &targets, 3).unwrap();
+    eval.fit(&inputs(), &targets, 3).unwrap();
+
+    // The masks are cleared after every batch, and only the trainer in training mode masks edges
+    assert!(trainer.kan.edges().iter().all(|edge| edge.borrow().masked.is_none()));
+    assert_ne!(trainer.kan.edges()[0].borrow().weights(), eval.kan.edges()[0].borrow().weights());
+
+    let path: PathBuf = std::env::temp_dir().join(format!("rusty_kan_dropout_{}.json", std::process::id()));
+    trainer.save_checkpoint(&path).unwrap();
+    let loaded: Trainer = Trainer::load_checkpoint(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.kan.edges()[0].borrow().dropout, 0.2);
+    assert_eq!(loaded.evaluate(&inputs(), &targets), trainer.evaluate(&inputs(), &targets));
+}
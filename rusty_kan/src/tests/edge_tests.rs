@@ -11,8 +11,9 @@ macro_rules! assert_is_close {
     }};
 }
 
-use std::collections::HashMap;
-use crate::data_structures::{vector::Vector, spline::BSpline, edge::Edge};
+use crate::data_structures::{vector::Vector, spline::{BSpline, BasisMemo, Extrapolation, Shape}, rbf::GaussianRbf, activation::ActivationFn, edge::{Edge, InputMode, BaseFn, BatchCache}};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
 #[test]
 fn edge_new_pass() {
@@ -23,11 +24,11 @@ fn edge_new_pass() {
 
     assert_eq!(edge.start, 0);
     assert_eq!(edge.end, 1);
-    assert_eq!(edge.spline.control_points, control_points);
-    assert_eq!(edge.spline.knots.len(), edge.spline.control_points.len() + edge.spline.degree + 1);
-    assert_eq!(edge.spline.degree, 2);
+    assert_eq!(edge.spline().control_points, control_points);
+    assert_eq!(edge.spline().knots.len(), edge.spline().control_points.len() + edge.spline().degree + 1);
+    assert_eq!(edge.spline().degree, 2);
 
-    assert_eq!(edge.gradient.elements.len(), edge.spline.control_points.len());
+    assert_eq!(edge.gradient.elements.len(), edge.spline().control_points.len() + 2);
     assert_eq!(edge.layer, 0);
 }
 
@@ -43,9 +44,21 @@ fn edge_standard_pass() {
     assert_eq!(edge.end, end);
     assert_eq!(edge.layer, layer);
 
-    assert_eq!(edge.spline.control_points.len(), 5);
-    assert_eq!(edge.spline.knots.len(), edge.spline.control_points.len() + edge.spline.degree + 1);
-    assert_eq!(edge.spline.degree, 2);
+    assert_eq!(edge.spline().control_points.len(), 5);
+    assert_eq!(edge.spline().knots.len(), edge.spline().control_points.len() + edge.spline().degree + 1);
+    assert_eq!(edge.spline().degree, 2);
+}
+
+#[test]
+fn edge_standard_with_rng_pass() {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(7);
+    let a: Edge = Edge::standard_with_rng(0, 1, 0, &mut rng);
+    let b: Edge = Edge::standard_with_rng(0, 1, 0, &mut rng);
+    let c: Edge = Edge::standard_with_rng(0, 1, 0, &mut ChaCha8Rng::seed_from_u64(7));
+
+    assert_eq!(a.spline().control_points.len(), 5);
+    assert_eq!(a.spline().control_points, c.spline().control_points);
+    assert_ne!(a.spline().control_points, b.spline().control_points);
 }
 
 #[test]
@@ -53,7 +66,7 @@ fn edge_forward_pass() {
     let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
     let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
     let degree: usize = 2;
-    let spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: HashMap::new() };
+    let spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
     let mut edge: Edge = Edge::new(0, 1, spline.clone(), 0);
 
     println!("{:?}", spline);
@@ -87,11 +100,11 @@ fn edge_forward_batch_pass() {
     let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
     let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
     let degree: usize = 2;
-    let spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: HashMap::new() };
+    let spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
     let mut edge: Edge = Edge::new(0, 1, spline.clone(), 0);
     
     let inputs: Vector = Vector::new(vec![0.3, 0.5, 0.7]);
-    let result: Vector = edge.forward_batch(inputs.clone());
+    let (result, cache): (Vector, BatchCache) = edge.forward_batch(inputs.clone());
 
     let silu = |x: f64| x/(1.0 + (-x).exp());
 
@@ -111,6 +124,12 @@ fn edge_forward_batch_pass() {
     for i in 0..result.len() {
         assert_is_close!(result[i], expected_result[i], 1e-3);
     }
+
+    // The inputs are not consumed, and the cache holds the basis of the spline at every input
+    assert_eq!(inputs, Vector::new(vec![0.3, 0.5, 0.7]));
+    assert_eq!(cache.inputs, inputs);
+    assert_eq!(cache.basis.as_ref().map(|basis| basis.shape()), Some((3, 3)));
+    assert_is_close!(cache.activations[1], 2.0, 1e-12);
 }
 
 #[test]
@@ -118,7 +137,7 @@ fn edge_backward_pass() {
     let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
     let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
     let degree: usize = 2;
-    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: HashMap::new() };
+    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
     let mut edge: Edge = Edge::new(0, 1, spline.clone(), 0);
 
     let t: f64 = 0.1;
@@ -131,7 +150,8 @@ fn edge_backward_pass() {
     let result_gradient: Vector = edge.gradient.clone();
     print!("Result Gradient: {}\n", result_gradient);
     let expected_gradient: Vec<f64> = vec![1.0 * spline.basis(0, spline.degree, t), 1.0 * spline.basis(1, spline.degree, t), 1.0 * spline.basis(2, spline.degree, t)];
-    for i in 0..result_gradient.elements.len() {
+    // The gradient ends with those of the base and spline weights, which are not compared here
+    for i in 0..expected_gradient.len() {
         assert_is_close!(result_gradient.elements[i], expected_gradient[i], 1e-3);
     }
 }
@@ -141,7 +161,7 @@ fn edge_weight_update_pass() {
     let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
     let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
     let degree: usize = 2;
-    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: HashMap::new() };
+    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
     let mut edge: Edge = Edge::new(0, 1, spline.clone(), 0);
 
     let learning_rate: f64 = 0.1;
@@ -149,7 +169,7 @@ fn edge_weight_update_pass() {
     edge.backward(0.1, 1.0).unwrap();
     edge.update_weights(learning_rate).unwrap();
 
-    let result_control_points: Vector = edge.spline.control_points.clone();
+    let result_control_points: Vector = edge.spline().control_points.clone();
     print!("Result Control Points: {}\n", result_control_points);
     let expected_control_points: Vec<f64> = vec![1.0 - learning_rate * 1.0 * spline.basis(0, spline.degree, 0.1), 2.0 - learning_rate * 1.0 * spline.basis(1, spline.degree, 0.1), 3.0 - learning_rate * 1.0 * spline.basis(2, spline.degree, 0.1)];
     for i in 0..result_control_points.elements.len() {
@@ -157,14 +177,308 @@ fn edge_weight_update_pass() {
     }
 }
 
+#[test]
+fn edge_rbf_pass() {
+    let rbf: GaussianRbf = GaussianRbf::uniform(Vector::new(vec![1.0, -1.0, 0.5]), (0.0, 1.0));
+    let mut edge: Edge = Edge::with_activation(0, 1, Box::new(rbf.clone()), 0);
+    let silu: f64 = 0.25 / (1.0 + (-0.25f64).exp());
+
+    assert_eq!(edge.gradient.len(), 11);
+    assert_is_close!(edge.forward(0.25), rbf.eval(0.25) + silu, 1e-12);
+
+    edge.backward(0.25, 2.0).unwrap();
+    edge.update_weights(0.1).unwrap();
+
+    let expected: Vector = &rbf.params - &(&rbf.grad_wrt_params(0.25) * 0.2);
+    assert_eq!(edge.activation.params(), &expected);
+    // Fixed centers and widths are unchanged
+    assert_eq!(&edge.activation.params().elements[3..], &rbf.params.elements[3..]);
+}
+
+#[test]
+#[should_panic]
+fn edge_spline_fail() {
+    let rbf: GaussianRbf = GaussianRbf::uniform(Vector::new(vec![1.0, -1.0, 0.5]), (0.0, 1.0));
+    let edge: Edge = Edge::with_activation(0, 1, Box::new(rbf), 0);
+
+    let _ = edge.spline();
+}
+
 #[test]
 #[should_panic]
 fn edge_weight_update_fail() {
     let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
     let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
     let degree: usize = 2;
-    let spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: HashMap::new() };
+    let spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
     let mut edge: Edge = Edge::new(0, 1, spline.clone(), 0);
 
     edge.update_weights(-0.1).unwrap();
-}
\ No newline at end of file
+}
+#[test]
+fn edge_lr_scale_pass() {
+    let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let mut edge: Edge = Edge::new(0, 1, BSpline::new(control_points, 2), 0);
+    assert_eq!(edge.lr_scale, 1.0);
+
+    edge.set_lr_scale(0.5);
+    edge.gradient = Vector::new(vec![1.0, 1.0, -2.0, 0.0, 0.0]);
+    edge.update_weights(0.1).unwrap();
+
+    assert_eq!(edge.lr_scale, 0.5);
+    assert_is_close!(edge.spline().control_points[0], 0.95, 1e-12);
+    assert_is_close!(edge.spline().control_points[1], 1.95, 1e-12);
+    assert_is_close!(edge.spline().control_points[2], 3.1, 1e-12);
+}
+
+#[test]
+#[should_panic]
+fn edge_lr_scale_fail() {
+    let mut edge: Edge = Edge::standard(0, 1, 0);
+
+    edge.set_lr_scale(-1.0);
+}
+
+#[test]
+fn edge_freeze_pass() {
+    let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let mut edge: Edge = Edge::new(0, 1, BSpline::new(control_points.clone(), 2), 0);
+    assert!(!edge.frozen);
+
+    edge.freeze();
+    edge.gradient = Vector::new(vec![1.0, 1.0, 1.0, 1.0, 1.0]);
+    edge.update_weights(0.1).unwrap();
+
+    assert!(edge.frozen);
+    assert_eq!(edge.spline().control_points, control_points);
+    assert_eq!(edge.base_weight, 1.0);
+    assert_eq!(edge.gradient, Vector::zeros(5));
+
+    edge.unfreeze();
+    edge.gradient = Vector::new(vec![1.0, 1.0, 1.0, 1.0, 1.0]);
+    edge.update_weights(0.1).unwrap();
+
+    assert!(!edge.frozen);
+    assert_is_close!(edge.spline().control_points[0], 0.9, 1e-12);
+    assert_is_close!(edge.base_weight, 0.9, 1e-12);
+}
+
+#[test]
+fn edge_base_fn_pass() {
+    assert_is_close!(BaseFn::Silu.eval(1.0), 1.0 / (1.0 + (-1.0f64).exp()), 1e-12);
+    assert_eq!(BaseFn::Identity.eval(-2.5), -2.5);
+    assert_is_close!(BaseFn::Tanh.eval(0.5), 0.5f64.tanh(), 1e-12);
+    assert_eq!(BaseFn::None.eval(3.0), 0.0);
+
+    let epsilon: f64 = 1e-6;
+    for base in [BaseFn::Silu, BaseFn::Identity, BaseFn::Tanh, BaseFn::None] {
+        for x in [-2.0, 0.0, 0.7] {
+            let numerical: f64 = (base.eval(x + epsilon) - base.eval(x - epsilon)) / (2.0 * epsilon);
+            assert_is_close!(base.derivative(x), numerical, 1e-6);
+        }
+    }
+}
+
+#[test]
+fn edge_scales_pass() {
+    let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let mut spline: BSpline = BSpline::new(control_points, 2);
+    let mut edge: Edge = Edge::new(0, 1, spline.clone(), 0);
+    edge.base = BaseFn::Tanh;
+    edge.base_weight = 0.5;
+    edge.spline_weight = 2.0;
+    let t: f64 = 0.4;
+
+    assert_is_close!(edge.forward(t), 0.5 * t.tanh() + 2.0 * spline.eval(t), 1e-12);
+
+    edge.backward(t, 3.0).unwrap();
+    for i in 0..3 {
+        assert_is_close!(edge.gradient[i], 2.0 * 3.0 * spline.basis(i, 2, t), 1e-12);
+    }
+    assert_is_close!(edge.gradient[3], 3.0 * t.tanh(), 1e-12);
+    assert_is_close!(edge.gradient[4], 3.0 * spline.eval(t), 1e-12);
+
+    edge.update_weights(0.1).unwrap();
+    assert_is_close!(edge.base_weight, 0.5 - 0.3 * t.tanh(), 1e-12);
+    assert_is_close!(edge.spline_weight, 2.0 - 0.3 * spline.eval(t), 1e-12);
+    assert_eq!(edge.weights().len(), edge.num_weights());
+}
+
+#[test]
+#[should_panic]
+fn edge_set_weights_fail() {
+    let mut edge: Edge = Edge::new(0, 1, BSpline::new(Vector::new(vec![1.0, 2.0, 3.0]), 2), 0);
+
+    edge.set_weights(&Vector::new(vec![1.0, 2.0, 3.0]));
+}
+
+#[test]
+fn edge_shape_pass() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.0, 1.0, 2.0, 3.0]), 2);
+    spline.shape = Shape::Increasing;
+    let mut edge: Edge = Edge::new(0, 1, spline, 0);
+
+    // A step that would break monotonicity is projected back
+    edge.gradient = Vector::new(vec![0.0, 20.0, 0.0, 0.0, 0.0, 0.0]);
+    edge.update_weights(0.1).unwrap();
+
+    assert_eq!(edge.spline().control_points, Vector::new(vec![-0.5, -0.5, 2.0, 3.0]));
+}
+
+#[test]
+fn edge_input_mode_pass() {
+    assert_eq!(InputMode::Raw.apply(1.5, (0.0, 1.0)), 1.5);
+    assert_eq!(InputMode::Clamp.apply(1.5, (0.0, 1.0)), 1.0);
+    assert_eq!(InputMode::Clamp.apply(-4.0, (-2.0, 2.0)), -2.0);
+    assert_is_close!(InputMode::Squash.apply(0.0f64, (-2.0, 2.0)), 0.0, 1e-12);
+    assert!(InputMode::Squash.apply(100.0, (0.0, 1.0)) <= 1.0);
+}
+
+#[test]
+fn edge_forward_clamp_pass() {
+    let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let mut edge: Edge = Edge::new(0, 1, BSpline::clamped(control_points, 2), 0);
+
+    // Outside the knots the raw spline vanishes, leaving only the SiLU term
+    let silu: f64 = 5.0 / (1.0 + (-5.0_f64).exp());
+    assert_is_close!(edge.forward(5.0), silu, 1e-12);
+
+    edge.input_mode = InputMode::Clamp;
+    assert_is_close!(edge.forward(5.0), 3.0 + silu, 1e-12);
+
+    edge.backward(5.0, 1.0).unwrap();
+    assert_eq!(edge.gradient, Vector::new(vec![0.0, 0.0, 1.0, silu, 3.0]));
+}
+
+#[test]
+fn edge_backward_batch_pass() {
+    let inputs: Vector = Vector::new(vec![0.1, 0.45, 0.8, 1.3]);
+    let upstream_gradients: Vector = Vector::new(vec![0.5, -1.0, 2.0, 0.25]);
+    let activations: Vec<Box<dyn ActivationFn>> = vec![
+        Box::new(BSpline::new(Vector::new(vec![1.0, -0.5, 2.0, 0.0, 1.5]), 2)),
+        Box::new(GaussianRbf::new(Vector::new(vec![1.0, -0.5, 2.0]), Vector::new(vec![0.0, 0.5, 1.0]), Vector::new(vec![0.4, 0.4, 0.4]), true)),
+    ];
+    for activation in activations {
+        let mut edge: Edge = Edge::with_activation(0, 1, activation, 0);
+        edge.input_mode = InputMode::Clamp;
+        edge.spline_weight = 0.7;
+
+        let mut expected: Vector = Vector::zeros(edge.num_weights());
+        for k in 0..inputs.len() {
+            edge.backward(inputs[k], upstream_gradients[k]).unwrap();
+            expected = &expected + &edge.gradient;
+        }
+        let values: Vector = Vector::new(inputs.elements.iter().map(|&t| edge.forward(t)).collect());
+
+        edge.zero_grad();
+        edge.backward_batch(&inputs, &upstream_gradients).unwrap();
+        assert_eq!(edge.gradient.len(), expected.len());
+        for i in 0..expected.len() {
+            assert_is_close!(edge.gradient[i], expected[i], 1e-12);
+        }
+        let (batch, cache): (Vector, BatchCache) = edge.forward_batch(inputs.clone());
+        for k in 0..inputs.len() {
+            assert_is_close!(batch[k], values[k], 1e-12);
+        }
+        edge.zero_grad();
+        edge.backward_batch_cached(&cache, &upstream_gradients).unwrap();
+        for i in 0..expected.len() {
+            assert_is_close!(edge.gradient[i], expected[i], 1e-12);
+        }
+    }
+}
+
+#[test]
+fn edge_backward_batch_fail() {
+    let mut edge: Edge = Edge::new(0, 1, BSpline::new(Vector::new(vec![1.0, 2.0, 3.0]), 2), 0);
+    let result = edge.backward_batch(&Vector::new(vec![0.1, 0.2]), &Vector::new(vec![1.0]));
+    assert!(result.is_err());
+}
+
+#[test]
+fn edge_backward_batch_accumulate_pass() {
+    let mut edge: Edge = Edge::new(0, 1, BSpline::new(Vector::new(vec![1.0, -0.5, 2.0, 0.0]), 2), 0);
+    let inputs: Vector = Vector::new(vec![0.1, 0.45, 0.8]);
+    let upstream_gradients: Vector = Vector::new(vec![0.5, -1.0, 2.0]);
+
+    edge.backward_batch(&inputs, &upstream_gradients).unwrap();
+    let once: Vector = edge.gradient.clone();
+    edge.backward_batch(&inputs, &upstream_gradients).unwrap();
+    for i in 0..once.len() {
+        assert_is_close!(edge.gradient[i], 2.0 * once[i], 1e-12);
+    }
+
+    edge.zero_grad();
+    assert_eq!(edge.gradient, Vector::zeros(6));
+
+    // A stored gradient of the wrong length is not accumulated into
+    edge.spline_mut().insert_knot(0.5);
+    assert!(edge.backward_batch(&inputs, &upstream_gradients).is_err());
+    edge.zero_grad();
+    assert_eq!(edge.gradient.len(), 7);
+    assert!(edge.backward_batch(&inputs, &upstream_gradients).is_ok());
+}
+
+#[test]
+fn edge_input_gradient_pass() {
+    let spline: BSpline = BSpline::new(Vector::new(vec![1.0, -0.5, 2.0, 0.0, 1.5]), 3);
+    let epsilon: f64 = 1e-6;
+    for input_mode in [InputMode::Raw, InputMode::Clamp, InputMode::Squash] {
+        for base in [BaseFn::Silu, BaseFn::Tanh, BaseFn::None] {
+            let mut edge: Edge = Edge::new(0, 1, spline.clone(), 0);
+            edge.input_mode = input_mode;
+            edge.base = base;
+            edge.base_weight = 0.8;
+            edge.spline_weight = 1.3;
+            for &t in &[-0.7, 0.15, 0.42, 0.9, 1.6] {
+                let numerical: f64 = (edge.forward(t + epsilon) - edge.forward(t - epsilon)) / (2.0 * epsilon);
+                assert_is_close!(edge.input_gradient(t), numerical, 1e-5);
+            }
+        }
+    }
+
+    // Only the base function contributes where the input is clamped
+    let mut edge: Edge = Edge::new(0, 1, spline, 0);
+    edge.input_mode = InputMode::Clamp;
+    assert_is_close!(edge.input_gradient(2.0), BaseFn::Silu.derivative(2.0), 1e-12);
+}
+
+#[test]
+fn edge_value_pass() {
+    let spline: BSpline = BSpline { control_points: Vector::new(vec![1.0, 2.0, 3.0]), knots: Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]), degree: 2, memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Linear, shape: Shape::None, weights: None };
+    let mut edge: Edge = Edge::new(0, 1, spline, 0);
+
+    let ts: [f64; 5] = [-0.5, 0.3, 0.5, 0.7, 1.5];
+    let values: Vec<f64> = ts.iter().map(|&t| edge.value(t)).collect();
+
+    // The shared evaluation leaves the memo of the spline untouched
+    assert!(edge.activation.downcast_ref::<BSpline>().unwrap().memo.is_empty());
+    for (i, &t) in ts.iter().enumerate() {
+        assert_is_close!(values[i], edge.forward(t), 1e-12);
+    }
+}
+
+#[test]
+fn edge_update_weights_gradient_fail() {
+    let mut edge: Edge = Edge::standard(0, 1, 0);
+    let weights: Vector = edge.weights();
+    edge.gradient = Vector::new(vec![1.0]);
+
+    assert!(edge.update_weights(0.1).is_err());
+    assert_eq!(edge.weights(), weights);
+}
+
+#[test]
+fn edge_serde_pass() {
+    let mut edge: Edge = Edge::with_activation(0, 1, Box::new(GaussianRbf::uniform(Vector::new(vec![0.5, -1.0, 2.0]), (0.0, 1.0))), 0);
+    edge.base = BaseFn::Tanh;
+    edge.input_mode = InputMode::Clamp;
+
+    let json: String = serde_json::to_string(&edge).unwrap();
+    let loaded: Edge = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded.activation.downcast_ref::<GaussianRbf>(), edge.activation.downcast_ref::<GaussianRbf>());
+    assert_eq!(loaded.base, BaseFn::Tanh);
+    assert_eq!(loaded.input_mode, InputMode::Clamp);
+    assert_eq!(loaded.weights(), edge.weights());
+}
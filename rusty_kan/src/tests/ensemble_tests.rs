@@ -0,0 +1,98 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::builder::KANBuilder;
+use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::ensemble::{KANEnsemble, Consensus};
+use crate::kan::KAN;
+use crate::error::KanError;
+use crate::loss::Loss;
+use crate::trainer::{Trainer, History};
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.1, 0.8]), Vector::new(vec![0.4, 0.3]), Vector::new(vec![0.9, 0.5]), Vector::new(vec![0.6, 0.2])])
+}
+
+fn builder() -> KANBuilder {
+    KANBuilder::new(2).add_layer(3).add_layer(2)
+}
+
+#[test]
+fn ensemble_from_builder_pass() {
+    let ensemble: KANEnsemble = KANEnsemble::from_builder(&builder(), 3, 10);
+
+    assert_eq!(ensemble.len(), 3);
+    assert_eq!(ensemble.members[1].get_flat_params(), builder().seed(11).build().get_flat_params());
+    assert_ne!(ensemble.members[0].get_flat_params(), ensemble.members[1].get_flat_params());
+}
+
+#[test]
+fn ensemble_predict_pass() {
+    let ensemble: KANEnsemble = KANEnsemble::from_builder(&builder(), 3, 0);
+    let outputs: Vec<Matrix> = ensemble.members.iter().map(|member| member.forward_batch(&inputs()).unwrap()).collect();
+
+    let mean: Matrix = ensemble.predict(&inputs(), Consensus::Mean).unwrap();
+    let median: Matrix = ensemble.predict(&inputs(), Consensus::Median).unwrap();
+    let (mean_with_variance, variance) = ensemble.predict_with_variance(&inputs()).unwrap();
+
+    assert_eq!((mean.rows, mean.cols), (4, 2));
+    assert_eq!(mean_with_variance, mean);
+    for i in 0..4 {
+        for j in 0..2 {
+            let mut values: Vec<f64> = outputs.iter().map(|output| output[i][j]).collect();
+            let average: f64 = values.iter().sum::<f64>() / 3.0;
+            assert_is_close!(mean[i][j], average, 1e-12);
+            assert_is_close!(variance[i][j], values.iter().map(|value| (value - average).powi(2)).sum::<f64>() / 3.0, 1e-12);
+            values.sort_by(f64::total_cmp);
+            assert_eq!(median[i][j], values[1]);
+        }
+    }
+}
+
+#[test]
+fn ensemble_median_even_pass() {
+    // Identical members agree everywhere, and the median of an even number of members is the mean of the middle two
+    let ensemble: KANEnsemble = KANEnsemble::new(vec![builder().seed(1).build(), builder().seed(1).build()]);
+
+    let (mean, variance) = ensemble.predict_with_variance(&inputs()).unwrap();
+
+    assert_eq!(ensemble.predict(&inputs(), Consensus::Median).unwrap(), mean);
+    assert!(variance.elements.iter().all(|&v| v == 0.0));
+}
+
+#[test]
+fn ensemble_fit_pass() {
+    let targets: Matrix = Matrix::new((0..4).map(|i| Vector::new(vec![inputs()[i][0] * inputs()[i][1], inputs()[i][0]])).collect());
+    let ensemble: KANEnsemble = KANEnsemble::from_builder(&builder(), 2, 3);
+    let initial: Vec<f64> = ensemble.members.iter().map(|member| member.loss_with(inputs(), targets.clone(), Loss::MeanSquaredError).unwrap()).collect();
+
+    let histories: Vec<History> = ensemble.fit(&inputs(), &targets, 5, |kan, i| Trainer::new(kan, 0.01, Loss::MeanSquaredError, i as u64)).unwrap();
+
+    // The trainers train the members in place
+    assert_eq!(histories.len(), 2);
+    for (member, initial) in ensemble.members.iter().zip(initial) {
+        assert!(member.loss_with(inputs(), targets.clone(), Loss::MeanSquaredError).unwrap() < initial);
+    }
+}
+
+#[test]
+#[should_panic]
+fn ensemble_new_fail() {
+    KANEnsemble::new(vec![]);
+}
+
+#[test]
+fn ensemble_predict_fail() {
+    let ensemble: KANEnsemble = KANEnsemble::new(vec![builder().seed(1).build(), KAN::standard_seeded(2, 3, 1)]);
+    assert!(matches!(ensemble.predict(&inputs(), Consensus::Mean), Err(KanError::DimensionMismatch { .. })));
+}
@@ -0,0 +1,80 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::Node, layer::Layer};
+use crate::error::{KanError, Location, Shape};
+use crate::kan::KAN;
+use crate::loss::Loss;
+use crate::trainer::Trainer;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn kan_error_display_pass() {
+    assert_eq!(KanError::SingularMatrix.to_string(), "The matrix is singular.");
+    assert_eq!(KanError::NotPositiveDefinite.to_string(), "The matrix is not positive definite.");
+    assert_eq!(KanError::mismatch("inputs, one per incoming edge", 2, 3).to_string(), "Expected 2 inputs, one per incoming edge, got 3.");
+    assert_eq!(KanError::shape_mismatch("the second matrix of the addition", (2, 3), (3, 2)).to_string(), "Expected the second matrix of the addition to have shape 2x3, got 3x2.");
+    assert_eq!(KanError::mismatch("inputs, one per incoming edge", 2, 3).at_edge(0).at_node(3).at_layer(1).to_string(), "Layer 1, node 3, edge 0: expected 2 inputs, one per incoming edge, got 3.");
+    let err: Box<dyn std::error::Error> = Box::new(KanError::Io("Failed to write the JSON file."));
+    assert_eq!(err.to_string(), "Failed to write the JSON file.");
+}
+
+#[test]
+#[allow(deprecated)]
+fn forward_dimension_mismatch_fail() {
+    let mut node: Node = Node::new(vec![Rc::new(RefCell::new(Edge::standard(0, 0, 0))), Rc::new(RefCell::new(Edge::standard(1, 0, 0)))], vec![], 0);
+    assert_eq!(node.forward(&Vector::new(vec![0.5])), Err(KanError::mismatch("inputs, one per incoming edge", 2, 1)));
+    assert!(matches!(node.backward(Vector::new(vec![0.5]), 1.0), Err(KanError::DimensionMismatch { .. })));
+
+    let layer: Layer = Layer::new(vec![Rc::new(RefCell::new(node))]);
+    assert_eq!(layer.forward(Matrix::new(vec![Vector::new(vec![0.1, 0.2]), Vector::new(vec![0.3, 0.4])])), Err(KanError::mismatch("input rows, one per node", 1, 2)));
+
+    // One row more than the first layer has nodes, which only the raw input matrix can have
+    let kan: KAN = KAN::standard_seeded(2, 3, 0);
+    let input: Matrix = Matrix::zeros(kan.layers[0].borrow().nodes.len() + 1, 2);
+    assert!(matches!(kan.forward_matrix(input.clone()), Err(KanError::DimensionMismatch { location: Location { layer: Some(0), .. }, .. })));
+    assert!(matches!(kan.backward(input, 0.5), Err(KanError::DimensionMismatch { location: Location { layer: Some(0), .. }, .. })));
+}
+
+#[test]
+fn solve_singular_fail() {
+    let singular: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![2.0, 4.0])]);
+    assert_eq!(singular.solve(&Vector::new(vec![1.0, 1.0])), Err(KanError::SingularMatrix));
+    assert_eq!(singular.cholesky(), Err(KanError::NotPositiveDefinite));
+}
+
+#[test]
+fn trainer_dimension_mismatch_fail() {
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.1, 0.2])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![0.5]), Vector::new(vec![0.6])]);
+    let mut trainer: Trainer = Trainer::new(KAN::standard_seeded(2, 3, 0), 0.01, Loss::MeanSquaredError, 0);
+    assert_eq!(trainer.fit(&inputs, &targets, 1).unwrap_err(), KanError::mismatch("targets, one per input", 1, 2));
+}
+
+#[test]
+fn location_pass() {
+    let err: KanError = KanError::mismatch("inputs, one per incoming edge", 2, 3).at_node(2).at_layer(0);
+    assert_eq!(err, KanError::DimensionMismatch { what: "inputs, one per incoming edge", expected: Shape::Length(2), found: Shape::Length(3), location: Location { layer: Some(0), node: Some(2), edge: None } });
+    assert_eq!(err.to_string(), "Layer 0, node 2: expected 2 inputs, one per incoming edge, got 3.");
+
+    // The innermost part that knows its index wins
+    assert_eq!(err.clone().at_node(5).at_layer(4), err);
+    assert_eq!(KanError::SingularMatrix.at_layer(1), KanError::SingularMatrix);
+    assert!(Location::default().is_empty());
+}
+
+#[test]
+#[allow(deprecated)]
+fn kan_forward_location_fail() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 0);
+    let width: usize = kan.layers[0].borrow().nodes[0].borrow().incoming.len();
+    // One column more than any node of the first layer has incoming edges
+    let input: Matrix = Matrix::zeros(kan.layers[0].borrow().nodes.len(), width + 1);
+    let err: KanError = kan.forward_matrix(input).unwrap_err();
+    assert_eq!(err.to_string(), format!("Layer 0, node 0: expected {} inputs, one per incoming edge, got {}.", width, width + 1));
+}
+
+#[test]
+fn kan_forward_inputs_fail() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 0);
+    assert_eq!(kan.forward(&Vector::new(vec![0.5])), Err(KanError::mismatch("input values, one per input of the KAN", 2, 1)));
+    assert_eq!(kan.predict_proba(&Vector::new(vec![0.1, 0.2, 0.3])).unwrap_err().to_string(), "Expected 2 input values, one per input of the KAN, got 3.");
+}
@@ -0,0 +1,175 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::builder::{KANBuilder, Skip};
+use crate::data_structures::{vector::Vector, matrix::Matrix, symbolic::{Symbolic, SymbolicFn}, edge::{Edge, InputMode}, norm::NormKind};
+use crate::expr::Expr;
+use crate::kan::KAN;
+use crate::scaler::Scaler;
+
+fn var(i: usize) -> Box<Expr> {
+    Box::new(Expr::Var(i))
+}
+
+// 2 * sin(x0) - 0.5 * x1^2 + 1
+fn formula() -> Expr {
+    Expr::Sum(vec![
+        Expr::Product(vec![Expr::Const(2.0), Expr::Apply(SymbolicFn::Sin, var(0))]),
+        Expr::Product(vec![Expr::Const(-0.5), Expr::Apply(SymbolicFn::Square, var(1))]),
+        Expr::Const(1.0),
+    ])
+}
+
+// Snap every edge of a KAN to a symbolic activation, cycling through the candidate functions.
+fn snap(kan: &KAN) {
+    for (i, edge) in kan.edges().iter().enumerate() {
+        let mut edge = edge.borrow_mut();
+        let domain: (f64, f64) = edge.activation.domain();
+        let params: Vector = Vector::new(vec![0.5 + 0.1 * i as f64, 0.1, 0.8, -0.05]);
+        edge.activation = Box::new(Symbolic::new(SymbolicFn::ALL[i % SymbolicFn::ALL.len()], params, domain));
+    }
+}
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.1, 0.8]), Vector::new(vec![0.4, 0.3]), Vector::new(vec![0.9, 0.5]), Vector::new(vec![0.6, 0.2])])
+}
+
+#[test]
+fn expr_eval_pass() {
+    let x: Vector = Vector::new(vec![0.3, 1.5]);
+
+    assert_is_close!(formula().eval(&x), 2.0 * 0.3_f64.sin() - 0.5 * 1.5 * 1.5 + 1.0, 1e-12);
+    assert_eq!(Expr::Sum(vec![]).eval(&x), 0.0);
+    assert_eq!(Expr::Product(vec![]).eval(&x), 1.0);
+}
+
+#[test]
+#[should_panic]
+fn expr_eval_fail() {
+    formula().eval(&Vector::new(vec![0.3]));
+}
+
+#[test]
+fn expr_simplify_pass() {
+    // x0 + (2 * x0 + 1) + 0 * x1 + identity(3) * 1
+    let expr: Expr = Expr::Sum(vec![
+        Expr::Var(0),
+        Expr::Sum(vec![Expr::Product(vec![Expr::Const(2.0), Expr::Var(0)]), Expr::Const(1.0)]),
+        Expr::Product(vec![Expr::Const(0.0), Expr::Var(1)]),
+        Expr::Product(vec![Expr::Apply(SymbolicFn::Identity, Box::new(Expr::Const(3.0))), Expr::Const(1.0)]),
+    ]);
+
+    assert_eq!(expr.simplify(), Expr::Sum(vec![Expr::Product(vec![Expr::Const(3.0), Expr::Var(0)]), Expr::Const(4.0)]));
+
+    // Nested products are flattened with their constants folded, and functions of constants are evaluated
+    let product: Expr = Expr::Product(vec![Expr::Const(2.0), Expr::Product(vec![Expr::Const(3.0), Expr::Var(1)]), Expr::Apply(SymbolicFn::Exp, Box::new(Expr::Const(0.0)))]);
+    assert_eq!(product.simplify(), Expr::Product(vec![Expr::Const(6.0), Expr::Var(1)]));
+    let cancelled: Expr = Expr::Sum(vec![Expr::Apply(SymbolicFn::Sin, var(0)), Expr::Product(vec![Expr::Const(-1.0), Expr::Apply(SymbolicFn::Sin, var(0))])]);
+    assert_eq!(cancelled.simplify(), Expr::Const(0.0));
+    assert_eq!(formula().simplify(), formula());
+}
+
+#[test]
+fn expr_display_pass() {
+    assert_eq!(formula().to_string(), "2 * sin(x0) - 0.5 * x1^2 + 1");
+    assert_eq!(format!("{:.2}", formula()), "2.00 * sin(x0) - 0.50 * x1^2 + 1.00");
+
+    // Sums are parenthesized inside products and powers
+    let nested: Expr = Expr::Product(vec![
+        Expr::Const(-1.0),
+        Expr::Sum(vec![Expr::Var(0), Expr::Const(-2.0)]),
+        Expr::Apply(SymbolicFn::Cube, Box::new(Expr::Sum(vec![Expr::Var(1), Expr::Const(1.0)]))),
+        Expr::Apply(SymbolicFn::Reciprocal, var(0)),
+    ]);
+    assert_eq!(nested.to_string(), "-(x0 - 2) * (x1 + 1)^3 * (1 / x0)");
+    assert_eq!(Expr::Apply(SymbolicFn::Gaussian, var(2)).to_string(), "exp(-x2^2)");
+}
+
+#[test]
+fn expr_to_latex_pass() {
+    assert_eq!(formula().to_latex(), "2 \\cdot \\sin\\left(x_{0}\\right) - 0.5 \\cdot x_{1}^{2} + 1");
+
+    let nested: Expr = Expr::Product(vec![
+        Expr::Apply(SymbolicFn::Reciprocal, Box::new(Expr::Sum(vec![Expr::Var(0), Expr::Const(1.0)]))),
+        Expr::Apply(SymbolicFn::Square, Box::new(Expr::Apply(SymbolicFn::Exp, var(1)))),
+        Expr::Apply(SymbolicFn::Sigmoid, Box::new(Expr::Sum(vec![Expr::Var(0), Expr::Var(1)]))),
+    ]);
+    assert_eq!(nested.to_latex(), "\\frac{1}{x_{0} + 1} \\cdot \\left(e^{x_{1}}\\right)^{2} \\cdot \\frac{1}{1 + e^{-\\left(x_{0} + x_{1}\\right)}}");
+}
+
+#[test]
+fn expr_to_sympy_string_pass() {
+    assert_eq!(formula().to_sympy_string(), "2*sin(x0) - 0.5*x1**2 + 1");
+
+    let nested: Expr = Expr::Product(vec![
+        Expr::Const(-1.0),
+        Expr::Apply(SymbolicFn::Reciprocal, Box::new(Expr::Sum(vec![Expr::Var(0), Expr::Const(1.0)]))),
+        Expr::Apply(SymbolicFn::Abs, var(1)),
+        Expr::Apply(SymbolicFn::Sigmoid, Box::new(Expr::Const(-2.0))),
+        Expr::Apply(SymbolicFn::Gaussian, Box::new(Expr::Product(vec![Expr::Const(3.0), Expr::Var(0)]))),
+    ]);
+    assert_eq!(nested.to_sympy_string(), "-(1/(x0 + 1))*Abs(x1)*(1/(1 + exp(-(-2))))*exp(-(3*x0)**2)");
+
+    // Constants that are not finite use the names of SymPy and LaTeX
+    let infinite: Expr = Expr::Sum(vec![Expr::Var(0), Expr::Const(f64::NEG_INFINITY)]);
+    assert_eq!(infinite.to_sympy_string(), "x0 - oo");
+    assert_eq!(infinite.to_latex(), "x_{0} - \\infty");
+    assert_eq!(Expr::Const(f64::NAN).to_sympy_string(), "nan");
+}
+
+#[test]
+fn kan_extract_formula_pass() {
+    let mut kan: KAN = KANBuilder::new(2).add_layer(3).add_layer(1).seed(6).build();
+    snap(&kan);
+    kan.edges()[1].borrow_mut().input_mode = InputMode::Squash;
+    kan.scaler = Some(Scaler::min_max(&inputs()));
+
+    let formulas: Vec<Expr> = kan.extract_formula().unwrap();
+
+    assert_eq!(formulas.len(), 1);
+    for i in 0..4 {
+        assert_is_close!(formulas[0].eval(&inputs().row(i)), kan.forward(&inputs().row(i)).unwrap(), 1e-9);
+    }
+}
+
+#[test]
+fn kan_extract_formula_layers_pass() {
+    // Product nodes, skip edges, dropout, and batch normalization are all part of the formula
+    let kan: KAN = KANBuilder::new(2).add_mult_layer(2, 1).norm(NormKind::Batch).dropout(0.25).add_layer(2).add_layer(1).skip(Skip::Spline).seed(2).build();
+    snap(&kan);
+    kan.update_norm_statistics(&inputs()).unwrap();
+
+    let formulas: Vec<Expr> = kan.extract_formula().unwrap();
+
+    for i in 0..4 {
+        assert_is_close!(formulas[0].eval(&inputs().row(i)), kan.forward(&inputs().row(i)).unwrap(), 1e-9);
+    }
+}
+
+#[test]
+fn kan_extract_formula_fail() {
+    // B-spline activations have no formula
+    assert!(KANBuilder::new(2).add_layer(1).seed(1).build().extract_formula().is_err());
+
+    let kan: KAN = KANBuilder::new(2).add_layer(2).norm(NormKind::Layer).add_layer(1).seed(1).build();
+    snap(&kan);
+    assert!(kan.extract_formula().is_err());
+
+    let mut edge: Edge = Edge::standard(0, 0, 0);
+    edge.spline_weight = 0.0;
+    assert!(edge.expr(Expr::Var(0)).is_ok());
+    edge.spline_weight = 1.0;
+    edge.symbolify(&[SymbolicFn::Identity]);
+    edge.input_mode = InputMode::Clamp;
+    assert!(edge.expr(Expr::Var(0)).is_err());
+}
@@ -0,0 +1,62 @@
+use crate::data_structures::vector::Vector;
+use crate::ffi::{KanModel, kan_load, kan_num_inputs, kan_num_outputs, kan_forward, kan_free};
+use crate::kan::KAN;
+use std::ffi::CString;
+use std::path::PathBuf;
+use std::ptr;
+
+fn model_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rusty_kan_ffi_{}_{}.json", name, std::process::id()))
+}
+
+#[test]
+fn ffi_forward_pass() {
+    let path: PathBuf = model_path("forward");
+    let kan: KAN = KAN::classifier_with_rng(2, 3, 2, &mut rand::thread_rng());
+    kan.save_json(&path).unwrap();
+    let c_path: CString = CString::new(path.to_str().unwrap()).unwrap();
+
+    unsafe {
+        let model: *mut KanModel = kan_load(c_path.as_ptr());
+        std::fs::remove_file(&path).unwrap();
+        assert!(!model.is_null());
+        assert_eq!(kan_num_inputs(model), 2);
+        assert_eq!(kan_num_outputs(model), 2);
+
+        let input: [f64; 2] = [0.2, 0.7];
+        let mut output: [f64; 2] = [0.0; 2];
+        assert_eq!(kan_forward(model, input.as_ptr(), 2, output.as_mut_ptr(), 2), 0);
+        assert_eq!(output.to_vec(), kan.compile().forward(&Vector::new(input.to_vec())).unwrap().elements);
+        kan_free(model);
+    }
+}
+
+#[test]
+fn ffi_forward_fail() {
+    let path: PathBuf = model_path("forward_fail");
+    KAN::standard_seeded(2, 3, 5).save_json(&path).unwrap();
+    let c_path: CString = CString::new(path.to_str().unwrap()).unwrap();
+
+    unsafe {
+        let model: *mut KanModel = kan_load(c_path.as_ptr());
+        std::fs::remove_file(&path).unwrap();
+        let input: [f64; 3] = [0.2, 0.7, 0.1];
+        let mut output: [f64; 1] = [0.0];
+        assert_eq!(kan_forward(model, input.as_ptr(), 3, output.as_mut_ptr(), 1), -1);
+        assert_eq!(kan_forward(model, input.as_ptr(), 2, ptr::null_mut(), 1), -1);
+        assert_eq!(kan_forward(ptr::null(), input.as_ptr(), 2, output.as_mut_ptr(), 1), -1);
+        kan_free(model);
+    }
+}
+
+#[test]
+fn ffi_load_fail() {
+    let c_path: CString = CString::new(model_path("missing").to_str().unwrap()).unwrap();
+
+    unsafe {
+        assert!(kan_load(c_path.as_ptr()).is_null());
+        assert!(kan_load(ptr::null()).is_null());
+        assert_eq!(kan_num_inputs(ptr::null()), 0);
+        kan_free(ptr::null_mut());
+    }
+}
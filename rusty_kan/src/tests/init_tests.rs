@@ -0,0 +1,73 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::vector::Vector;
+use crate::init::Init;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn mean_and_std(values: &Vector) -> (f64, f64) {
+    let mean: f64 = values.elements.iter().sum::<f64>() / values.len() as f64;
+    let variance: f64 = values.elements.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
+#[test]
+fn init_zeros_pass() {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(0);
+
+    assert_eq!(Init::Zeros.sample(5, 3, &mut rng), Vector::zeros(5));
+}
+
+#[test]
+fn init_uniform_pass() {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(0);
+    let values: Vector = Init::Uniform { low: -0.5, high: 0.25 }.sample(1000, 1, &mut rng);
+
+    assert_eq!(values.len(), 1000);
+    assert!(values.elements.iter().all(|x| (-0.5..0.25).contains(x)));
+}
+
+#[test]
+#[should_panic]
+fn init_uniform_fail() {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(0);
+
+    Init::Uniform { low: 1.0, high: 0.0 }.sample(5, 1, &mut rng);
+}
+
+#[test]
+fn init_normal_pass() {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(0);
+    let (mean, std) = mean_and_std(&Init::Normal { mean: 2.0, std: 0.5 }.sample(10000, 1, &mut rng));
+
+    assert_is_close!(mean, 2.0, 0.05);
+    assert_is_close!(std, 0.5, 0.05);
+}
+
+#[test]
+fn init_xavier_pass() {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(0);
+    let (mean, std) = mean_and_std(&Init::Xavier.sample(10000, 16, &mut rng));
+
+    assert_is_close!(mean, 0.0, 0.05);
+    assert_is_close!(std, 0.25, 0.02);
+}
+
+#[test]
+#[should_panic]
+fn init_xavier_fail() {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(0);
+
+    Init::Xavier.sample(5, 0, &mut rng);
+}
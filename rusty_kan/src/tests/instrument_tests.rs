@@ -0,0 +1,52 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::instrument::{self, Phase, PhaseTiming};
+use crate::kan::KAN;
+use crate::loss::Loss;
+use crate::trainer::Trainer;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+fn count(timings: &[PhaseTiming], phase: Phase) -> usize {
+    timings.iter().find(|timing| timing.phase == phase).unwrap().count
+}
+
+#[test]
+fn instrument_timings_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 1);
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.1, 0.2]), Vector::new(vec![0.7, 0.4]), Vector::new(vec![0.5, 0.9])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![0.3]), Vector::new(vec![1.1]), Vector::new(vec![1.4])]);
+    instrument::reset();
+
+    kan.forward(&inputs.row(0)).unwrap();
+    let timings: Vec<PhaseTiming> = instrument::timings();
+    assert_eq!((count(&timings, Phase::Forward), count(&timings, Phase::Backward), count(&timings, Phase::Update)), (1, 0, 0));
+
+    // Every sample of the epoch is a forward and a backward pass, and the single batch is one update
+    let mut trainer: Trainer = Trainer::new(kan, 0.01, Loss::MeanSquaredError, 0);
+    trainer.loader.batch_size = 3;
+    trainer.fit(&inputs, &targets, 1).unwrap();
+    let timings: Vec<PhaseTiming> = instrument::timings();
+    assert_eq!(count(&timings, Phase::Backward), 3);
+    assert_eq!(count(&timings, Phase::Update), 1);
+    assert!(count(&timings, Phase::Forward) >= 4);
+    assert_eq!(timings.iter().map(|timing| timing.phase).collect::<Vec<Phase>>(), Phase::ALL.to_vec());
+
+    instrument::reset();
+    assert!(instrument::timings().iter().all(|timing| timing.count == 0 && timing.total == Duration::ZERO));
+}
+
+#[test]
+fn instrument_subscriber_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 1);
+    let spans: Rc<RefCell<Vec<Phase>>> = Rc::new(RefCell::new(Vec::new()));
+    let recorded: Rc<RefCell<Vec<Phase>>> = spans.clone();
+    instrument::set_subscriber(Some(Box::new(move |phase, _| recorded.borrow_mut().push(phase))));
+
+    kan.backward_features(&Vector::new(vec![0.2, 0.8]), &Vector::new(vec![1.0]), Loss::MeanSquaredError).unwrap();
+    instrument::set_subscriber(None);
+    kan.forward(&Vector::new(vec![0.2, 0.8])).unwrap();
+
+    assert_eq!(*spans.borrow(), vec![Phase::Forward, Phase::Backward]);
+    assert_eq!(Phase::Update.name(), "update");
+}
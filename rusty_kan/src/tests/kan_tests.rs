@@ -11,10 +11,14 @@ macro_rules! assert_is_close {
     }};
 }
 
-use crate::data_structures::{vector::Vector, spline::BSpline, edge::Edge, node::Node, layer::Layer, matrix::Matrix};
-use crate::kan::KAN;
+use crate::builder::{KANBuilder, Skip};
+use crate::data_structures::{vector::Vector, edge::Edge, node::{Node, Aggregation}, layer::Layer, matrix::Matrix, norm::NormKind, spline::BSpline};
+use crate::kan::{KAN, grad_check};
+use crate::loss::{Loss, Reduction, one_hot};
+use crate::scaler::Scaler;
+use crate::error::KanError;
 use std::rc::Rc;
-use std::cell::{RefCell, RefMut};
+use std::cell::RefCell;
 
 #[test]
 fn kan_new_pass() {
@@ -36,6 +40,7 @@ fn kan_new_pass() {
 
     // Check that the layers are correct.
     assert_eq!(kan.layers.len(), layers.len());
+    assert_eq!(kan.inputs, 1);
 }
 
 #[test]
@@ -51,6 +56,627 @@ fn kan_standard_pass() {
 fn kan_forward_pass() {
     let kan: KAN = KAN::standard(1, 1);
 
-    let input: Matrix = Vector::new(vec![1.0]).to_matrix();
-    let output: f64 = kan.forward(input.clone());
+    let _output: f64 = kan.forward(&Vector::new(vec![1.0])).unwrap();
+}
+
+#[test]
+fn kan_forward_multi_input_pass() {
+    let kan: KAN = KAN::standard(2, 3);
+
+    let features: Vector = Vector::new(vec![0.2, 0.7]);
+    let input: Matrix = kan.layers[0].borrow().route(&features);
+    assert_eq!(input.shape(), (3, 2));
+
+    let output: f64 = kan.forward(&features).unwrap();
+    assert_is_close!(output, kan.forward_logits(&features).unwrap()[0], 1e-9);
+    assert_eq!(kan.forward_features(&features).unwrap()[0], output);
+    #[allow(deprecated)]
+    let routed: f64 = kan.forward_matrix(input).unwrap()[0];
+    assert_eq!(routed, output);
+}
+
+#[test]
+fn kan_inputs_pass() {
+    // The inputs are routed to the edges of the hidden layer, which start at them
+    let kan: KAN = KAN::classifier(2, 3, 4);
+    assert_eq!(kan.inputs, 2);
+    assert!(kan.layers[0].borrow().nodes.iter().all(|node| node.borrow().incoming.iter().enumerate().all(|(i, edge)| edge.borrow().start == i)));
+
+    // An input that no edge starts at still counts if a later one does
+    let mut kan: KAN = KAN::new(vec![]);
+    assert_eq!(kan.inputs, 0);
+    kan.add_layer(Layer::new(vec![Rc::new(RefCell::new(Node::new(vec![Rc::new(RefCell::new(Edge::standard(2, 0, 0)))], vec![], 0)))]));
+    assert_eq!(kan.inputs, 3);
+    assert!(kan.forward(&Vector::new(vec![0.1, 0.2, 0.3])).is_ok());
+
+    // Serialized KANs keep the number of inputs, and older files without it infer it from the edges
+    let kan: KAN = KANBuilder::new(3).add_layer(1).seed(1).build();
+    let first: Rc<RefCell<Node>> = kan.layers[0].borrow().nodes[0].clone();
+    first.borrow_mut().incoming.pop();
+    let json: String = serde_json::to_string(&kan).unwrap();
+    assert_eq!(serde_json::from_str::<KAN>(&json).unwrap().inputs, 3);
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value.as_object_mut().unwrap().remove("inputs");
+    assert_eq!(serde_json::from_value::<KAN>(value).unwrap().inputs, 2);
+}
+
+#[test]
+fn kan_forward_features_pass() {
+    // Every feature reaches the edges of the first layer and the skip edges of the second layer that start at it
+    let kan: KAN = KANBuilder::new(3).add_layer(2).add_layer(2).skip(Skip::Spline).add_layer(1).seed(4).build();
+    let features: Vector = Vector::new(vec![0.1, 0.5, 0.8]);
+
+    assert_eq!(kan.forward_features(&features).unwrap(), kan.compile().forward(&features).unwrap());
+    assert_eq!(kan.forward_features(&Vector::new(vec![0.1, 0.5])), Err(KanError::mismatch("input values, one per input of the KAN", 3, 2)));
+
+    let target: Vector = Vector::new(vec![0.5]);
+    let loss: f64 = kan.backward_features(&features, &target, Loss::MeanSquaredError).unwrap();
+    assert_is_close!(loss, kan.loss_single_with(features, &target, Loss::MeanSquaredError).unwrap(), 1e-12);
+    assert!(kan.edges().iter().any(|edge| edge.borrow().skip && edge.borrow().gradient.iter().any(|&g| g != 0.0)));
+}
+
+#[test]
+fn kan_classifier_pass() {
+    let kan: KAN = KAN::classifier(2, 3, 4);
+
+    assert_eq!(kan.layers.len(), 2);
+    assert_eq!(kan.layers[0].borrow().nodes.len(), 3);
+    assert_eq!(kan.layers[1].borrow().nodes.len(), 4);
+    assert_eq!(kan.layers[1].borrow().nodes[0].borrow().incoming.len(), 3);
+}
+
+#[test]
+fn kan_predict_proba_pass() {
+    let kan: KAN = KAN::classifier(2, 3, 4);
+
+    let input: Vector = Vector::new(vec![0.2, 0.7]);
+    let logits: Vector = kan.forward_logits(&input).unwrap();
+    let probabilities: Vector = kan.predict_proba(&input).unwrap();
+
+    assert_eq!(logits.len(), 4);
+    assert_is_close!(probabilities.elements.iter().sum::<f64>(), 1.0, 1e-9);
+    assert_eq!(kan.predict_class(Vector::new(vec![0.2, 0.7])).unwrap(), probabilities.elements.iter().enumerate().fold(0, |best, (i, p)| if *p > probabilities[best] { i } else { best }));
+}
+
+// A single layer of output nodes, each connected to every input, so the inputs stay within the spline domain.
+fn single_layer_kan(inputs: usize, outputs: usize) -> KAN {
+    let mut nodes: Vec<Rc<RefCell<Node>>> = Vec::new();
+    for j in 0..outputs {
+        let incoming_edges: Vec<Rc<RefCell<Edge>>> = (0..inputs).map(|i| Rc::new(RefCell::new(Edge::standard(i, j, 0)))).collect();
+        nodes.push(Rc::new(RefCell::new(Node::new(incoming_edges, vec![], 0))));
+    }
+    KAN::new(vec![Rc::new(RefCell::new(Layer::new(nodes)))])
+}
+
+#[test]
+fn kan_backward_loss_pass() {
+    let kan: KAN = single_layer_kan(2, 3);
+    let input: Vector = Vector::new(vec![0.3, 0.6]);
+    let target: Vector = one_hot(1, 3).unwrap();
+
+    let expected_loss: f64 = kan.loss_single_with(input.clone(), &target, Loss::CrossEntropy).unwrap();
+    let loss: f64 = kan.backward_features(&input, &target, Loss::CrossEntropy).unwrap();
+    assert_is_close!(loss, expected_loss, 1e-12);
+
+    // The gradient of the first output node is seeded with its softmax probability.
+    let probabilities: Vector = kan.predict_proba(&input).unwrap();
+    let edge = kan.layers[0].borrow().nodes[0].borrow().incoming[0].clone();
+    let mut spline = edge.borrow().spline().clone();
+    for i in 0..spline.control_points.len() {
+        let degree: usize = spline.degree;
+        assert_is_close!(edge.borrow().gradient[i], spline.basis(i, degree, 0.3) * probabilities[0], 1e-9);
+    }
+}
+
+#[test]
+fn kan_backward_loss_wrong_target_fail() {
+    let kan: KAN = KAN::classifier(2, 2, 3);
+    let input: Vector = Vector::new(vec![0.3, 0.6]);
+
+    assert!(kan.backward_features(&input, &one_hot(0, 2).unwrap(), Loss::CrossEntropy).is_err());
+    assert!(kan.backward_features(&Vector::new(vec![0.3]), &one_hot(0, 3).unwrap(), Loss::CrossEntropy).is_err());
+}
+
+#[test]
+fn kan_snapshot_restore_pass() {
+    let kan: KAN = KAN::standard(2, 3);
+    let snapshot: Vec<Vector> = kan.snapshot();
+    assert_eq!(snapshot.len(), kan.edges().len());
+    assert_eq!(snapshot.len(), 2 * 3 + 3);
+
+    kan.edges()[0].borrow_mut().spline_mut().control_points = Vector::zeros(5);
+    assert_ne!(kan.snapshot(), snapshot);
+
+    kan.restore(&snapshot).unwrap();
+    assert_eq!(kan.snapshot(), snapshot);
+}
+
+#[test]
+fn kan_restore_fail() {
+    let kan: KAN = KAN::standard(2, 3);
+    let mut snapshot: Vec<Vector> = kan.snapshot();
+    assert_eq!(kan.restore(&snapshot[1..]), Err(KanError::mismatch("weight vectors in the snapshot, one per edge", 9, 8)));
+
+    // The edges before the faulty one keep their weights
+    snapshot[0] = Vector::zeros(5);
+    snapshot[8] = Vector::zeros(2);
+    assert!(kan.restore(&snapshot).is_err());
+    assert_ne!(kan.snapshot()[0], snapshot[0]);
+}
+
+#[test]
+fn kan_flat_params_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 1);
+    let params: Vector = kan.get_flat_params();
+    assert_eq!(params.len(), kan.num_params());
+    assert_eq!(params.elements, kan.snapshot().into_iter().flat_map(|weights| weights.elements).collect::<Vec<f64>>());
+
+    // Averaging with a second KAN of the same architecture averages every weight
+    let other: KAN = KAN::standard_seeded(2, 3, 2);
+    let average: Vector = &(&params + &other.get_flat_params()) * 0.5;
+    kan.set_flat_params(&average).unwrap();
+    assert_eq!(kan.get_flat_params(), average);
+    let weights: Vector = kan.edges()[4].borrow().weights();
+    let expected: Vector = &(&other.edges()[4].borrow().weights() + &KAN::standard_seeded(2, 3, 1).edges()[4].borrow().weights()) * 0.5;
+    assert_eq!(weights, expected);
+}
+
+#[test]
+fn kan_set_flat_params_fail() {
+    let kan: KAN = KAN::standard(2, 3);
+    let params: Vector = kan.get_flat_params();
+    assert_eq!(kan.set_flat_params(&Vector::zeros(kan.num_params() - 1)), Err(KanError::mismatch("parameters, one per weight of the KAN", kan.num_params(), kan.num_params() - 1)));
+    assert_eq!(kan.get_flat_params(), params);
+}
+
+#[test]
+fn kan_hvp_pass() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let input: Vector = Vector::new(vec![0.3, 0.6]);
+    let target: Vector = Vector::new(vec![0.4]);
+    let params: Vector = kan.get_flat_params();
+    let n: usize = kan.num_params();
+    let basis = |i: usize| Vector::new((0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect());
+
+    // The output is linear in the base weight w_b of the last edge, so the loss (y - t)^2 has curvature 2 b(x)^2 along it
+    let base: f64 = kan.edges()[1].borrow().base.eval(0.6);
+    let hv: Vector = kan.hvp(&input, &target, &basis(n - 2), Loss::MeanSquaredError).unwrap();
+    assert_is_close!(hv[n - 2], 2.0 * base * base, 1e-6);
+
+    // The Hessian is symmetric, and the product is linear in the direction
+    let u: Vector = Vector::new((0..n).map(|i| (i as f64 * 0.7).sin()).collect());
+    let w: Vector = Vector::new((0..n).map(|i| (i as f64 * 0.3).cos()).collect());
+    let hu: Vector = kan.hvp(&input, &target, &u, Loss::MeanSquaredError).unwrap();
+    let hw: Vector = kan.hvp(&input, &target, &w, Loss::MeanSquaredError).unwrap();
+    assert_is_close!(w.dot(&hu), u.dot(&hw), 1e-5);
+    let hsum: Vector = kan.hvp(&input, &target, &(&u + &w), Loss::MeanSquaredError).unwrap();
+    for i in 0..n {
+        assert_is_close!(hsum[i], hu[i] + hw[i], 1e-5);
+    }
+
+    // The weights and the gradients are unchanged
+    assert_eq!(kan.get_flat_params(), params);
+    assert!(kan.edges().iter().all(|edge| edge.borrow().gradient.iter().all(|&g| g == 0.0)));
+    assert_eq!(kan.hvp(&input, &target, &Vector::zeros(n), Loss::MeanSquaredError).unwrap(), Vector::zeros(n));
+}
+
+#[test]
+fn kan_hvp_fail() {
+    let kan: KAN = KAN::standard(2, 3);
+    let input: Vector = Vector::new(vec![0.3, 0.6]);
+    let params: Vector = kan.get_flat_params();
+
+    assert!(kan.hvp(&Vector::new(vec![0.3]), &Vector::new(vec![0.4]), &Vector::ones(kan.num_params()), Loss::MeanSquaredError).is_err());
+    assert!(kan.hvp(&input, &Vector::new(vec![0.4]), &Vector::zeros(kan.num_params() + 1), Loss::MeanSquaredError).is_err());
+    // A target with the wrong length fails in the backward pass, which leaves the weights unchanged
+    assert!(kan.hvp(&input, &Vector::new(vec![0.4, 0.2]), &Vector::ones(kan.num_params()), Loss::MeanSquaredError).is_err());
+    assert_eq!(kan.get_flat_params(), params);
+}
+
+#[test]
+fn kan_grad_check_pass() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let snapshot: Vec<Vector> = kan.snapshot();
+
+    let errors: Vector = grad_check(&kan, &Vector::new(vec![0.3, 0.6]), &Vector::new(vec![2.0]), Loss::MeanSquaredError, 1e-5).unwrap();
+
+    assert_eq!(errors.len(), 2);
+    for i in 0..errors.len() {
+        assert!(errors[i] < 1e-6);
+    }
+    // The control points and the gradients are unchanged
+    assert_eq!(kan.snapshot(), snapshot);
+    for edge in kan.edges().iter() {
+        assert_eq!(edge.borrow().gradient, Vector::zeros(7));
+    }
+}
+
+#[test]
+fn kan_grad_check_product_pass() {
+    let kan: KAN = KANBuilder::new(2).add_mult_layer(0, 1).seed(9).build();
+
+    let errors: Vector = grad_check(&kan, &Vector::new(vec![0.3, 0.6]), &Vector::new(vec![2.0]), Loss::MeanSquaredError, 1e-5).unwrap();
+
+    assert_eq!(errors.len(), 2);
+    for i in 0..errors.len() {
+        assert!(errors[i] < 1e-6);
+    }
+}
+
+#[test]
+fn kan_product_nodes_pass() {
+    let kan: KAN = KANBuilder::new(2).add_mult_layer(1, 1).add_layer(1).seed(4).build();
+    let input: Vector = Vector::new(vec![0.4, 0.8]);
+    let expected: f64 = kan.forward(&input).unwrap();
+
+    // The compiled model and a JSON round trip keep the product node
+    assert_is_close!(kan.compile().forward(&input).unwrap()[0], expected, 1e-12);
+    let loaded: KAN = serde_json::from_str(&serde_json::to_string(&kan).unwrap()).unwrap();
+    assert_eq!(loaded.layers[0].borrow().nodes[1].borrow().aggregation, Aggregation::Product);
+    assert_eq!(loaded.forward(&input).unwrap(), expected);
+
+    // The input gradients include the values of the other factor
+    let epsilon: f64 = 1e-6;
+    let gradients: Vector = kan.input_gradients(&input).unwrap();
+    for i in 0..2 {
+        let (mut plus, mut minus): (Vector, Vector) = (input.clone(), input.clone());
+        plus[i] += epsilon;
+        minus[i] -= epsilon;
+        let numerical: f64 = (kan.forward(&plus).unwrap() - kan.forward(&minus).unwrap()) / (2.0 * epsilon);
+        assert_is_close!(gradients[i], numerical, 1e-6);
+    }
+}
+
+#[test]
+fn kan_grad_check_multi_output_pass() {
+    let kan: KAN = single_layer_kan(2, 3);
+
+    let errors: Vector = grad_check(&kan, &Vector::new(vec![0.3, 0.6]), &one_hot(1, 3).unwrap(), Loss::CrossEntropy, 1e-5).unwrap();
+
+    assert_eq!(errors.len(), 6);
+    for i in 0..errors.len() {
+        assert!(errors[i] < 1e-6);
+    }
+}
+
+#[test]
+fn kan_grad_check_fail() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let features: Vector = Vector::new(vec![0.3, 0.6]);
+
+    assert_eq!(grad_check(&kan, &features, &Vector::new(vec![2.0]), Loss::MeanSquaredError, 0.0), Err(KanError::InvalidConfig("The perturbation must be greater than 0.")));
+    assert!(matches!(grad_check(&kan, &Vector::new(vec![0.3]), &Vector::new(vec![2.0]), Loss::MeanSquaredError, 1e-5), Err(KanError::DimensionMismatch { .. })));
+    assert!(matches!(grad_check(&kan, &features, &Vector::new(vec![2.0, 1.0]), Loss::MeanSquaredError, 1e-5), Err(KanError::DimensionMismatch { .. })));
+    // A failed check leaves the gradients unchanged
+    for edge in kan.edges().iter() {
+        assert_eq!(edge.borrow().gradient, Vector::zeros(7));
+    }
+}
+
+#[test]
+fn kan_standard_seeded_pass() {
+    let a: KAN = KAN::standard_seeded(2, 3, 42);
+    let b: KAN = KAN::standard_seeded(2, 3, 42);
+    let c: KAN = KAN::standard_seeded(2, 3, 43);
+
+    assert_eq!(a.edges().len(), 9);
+    assert_eq!(a.snapshot(), b.snapshot());
+    assert_ne!(a.snapshot(), c.snapshot());
+}
+
+#[test]
+fn kan_standard_with_grid_pass() {
+    let kan: KAN = KAN::standard_with_grid(2, 3, 3, 9, 42);
+
+    assert_eq!(kan.edges().len(), 9);
+    assert!(kan.edges().iter().all(|edge| edge.borrow().activation.downcast_ref::<BSpline>().is_some_and(|spline| spline.degree == 3 && spline.control_points.len() == 9)));
+    assert!(kan.forward(&Vector::new(vec![0.3, 0.6])).unwrap().is_finite());
+    assert_eq!(KAN::standard_with_grid(2, 3, 2, 5, 42).snapshot(), KAN::standard_seeded(2, 3, 42).snapshot());
+}
+
+#[test]
+fn kan_loss_reduced_pass() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.4]), Vector::new(vec![0.6, 0.9]), Vector::new(vec![0.1, 0.3])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![1.0]), Vector::new(vec![-1.0]), Vector::new(vec![0.5])]);
+
+    let losses: Vector = kan.loss_reduced(inputs.clone(), targets.clone(), Loss::MeanSquaredError, Reduction::None).unwrap();
+    let sum: Vector = kan.loss_reduced(inputs.clone(), targets.clone(), Loss::MeanSquaredError, Reduction::Sum).unwrap();
+    let mean: f64 = kan.loss_with(inputs.clone(), targets.clone(), Loss::MeanSquaredError).unwrap();
+
+    assert_eq!(losses.len(), 3);
+    for i in 0..3 {
+        assert_is_close!(losses[i], kan.loss_single_with(inputs.row(i), &targets.row(i), Loss::MeanSquaredError).unwrap(), 1e-12);
+    }
+    assert_is_close!(sum[0], losses[0] + losses[1] + losses[2], 1e-12);
+    assert_is_close!(mean, sum[0] / 3.0, 1e-12);
+}
+
+#[test]
+fn kan_loss_weighted_pass() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.4]), Vector::new(vec![0.6, 0.9]), Vector::new(vec![0.1, 0.3])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![1.0]), Vector::new(vec![-1.0]), Vector::new(vec![0.5])]);
+    let losses: Vector = kan.loss_reduced(inputs.clone(), targets.clone(), Loss::MeanSquaredError, Reduction::None).unwrap();
+
+    let weighted: f64 = kan.loss_weighted(inputs.clone(), targets.clone(), &Vector::new(vec![3.0, 1.0, 0.0]), Loss::MeanSquaredError).unwrap();
+    let equal: f64 = kan.loss_weighted(inputs.clone(), targets.clone(), &Vector::new(vec![2.0, 2.0, 2.0]), Loss::MeanSquaredError).unwrap();
+
+    assert_is_close!(weighted, (3.0 * losses[0] + losses[1]) / 4.0, 1e-12);
+    assert_is_close!(equal, kan.loss_with(inputs, targets, Loss::MeanSquaredError).unwrap(), 1e-12);
+}
+
+#[test]
+fn kan_loss_weighted_fail() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.4]), Vector::new(vec![0.6, 0.9])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![1.0]), Vector::new(vec![-1.0])]);
+
+    assert_eq!(kan.loss_weighted(inputs.clone(), targets.clone(), &Vector::new(vec![0.0, 0.0]), Loss::MeanSquaredError), Err(KanError::DomainError("The weights must be finite and non-negative with a positive sum.")));
+    assert_eq!(kan.loss_weighted(inputs.clone(), targets.clone(), &Vector::new(vec![1.0]), Loss::MeanSquaredError), Err(KanError::mismatch("weights, one per input", 2, 1)));
+    assert!(kan.loss_reduced(inputs, Matrix::new(vec![Vector::new(vec![1.0])]), Loss::MeanSquaredError, Reduction::Mean).is_err());
+}
+
+#[test]
+fn kan_input_gradients_pass() {
+    let mut kan: KAN = KAN::standard_seeded(2, 3, 11);
+    kan.scaler = Some(Scaler::min_max(&Matrix::new(vec![Vector::new(vec![-2.0, 0.0]), Vector::new(vec![2.0, 4.0])])));
+    let input: Vector = Vector::new(vec![0.4, 1.3]);
+    let epsilon: f64 = 1e-6;
+
+    let gradients: Vector = kan.input_gradients(&input).unwrap();
+
+    assert_eq!(gradients.len(), 2);
+    for i in 0..2 {
+        let (mut plus, mut minus): (Vector, Vector) = (input.clone(), input.clone());
+        plus[i] += epsilon;
+        minus[i] -= epsilon;
+        let numerical: f64 = (kan.forward(&plus).unwrap() - kan.forward(&minus).unwrap()) / (2.0 * epsilon);
+        assert_is_close!(gradients[i], numerical, 1e-6);
+    }
+}
+
+#[test]
+fn kan_feature_importance_pass() {
+    let kan: KAN = single_layer_kan(2, 1);
+    // The output does not depend on the second input once its edge is zeroed
+    let edge: Rc<RefCell<Edge>> = kan.edges()[1].clone();
+    let zeros: Vector = Vector::zeros(edge.borrow().num_weights());
+    edge.borrow_mut().set_weights(&zeros);
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.4]), Vector::new(vec![0.6, 0.9])]);
+
+    let importance: Vector = kan.feature_importance(&inputs).unwrap();
+
+    assert_eq!(importance[1], 0.0);
+    let expected: f64 = (kan.input_gradients(&inputs.row(0)).unwrap()[0].abs() + kan.input_gradients(&inputs.row(1)).unwrap()[0].abs()) / 2.0;
+    assert_is_close!(importance[0], expected, 1e-12);
+    assert!(importance[0] > 0.0);
+}
+
+#[test]
+fn kan_feature_importance_fail() {
+    assert_eq!(single_layer_kan(2, 1).feature_importance(&Matrix::zeros(0, 2)), Err(KanError::InvalidConfig("The importance of the inputs requires at least one sample.")));
+    assert!(single_layer_kan(2, 1).input_gradients(&Vector::new(vec![0.3])).is_err());
+}
+
+#[test]
+fn kan_forward_batch_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 11);
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.4]), Vector::new(vec![0.6, 0.9]), Vector::new(vec![-0.5, 1.5])]);
+
+    let outputs: Matrix = kan.forward_batch(&inputs).unwrap();
+
+    assert_eq!(outputs.shape(), (3, 1));
+    for i in 0..3 {
+        let expected: Vector = kan.forward_logits(&inputs.row(i)).unwrap();
+        assert_is_close!(outputs[i][0], expected[0], 1e-12);
+    }
+    assert_eq!(kan.forward_batch(&Matrix::new(vec![])).unwrap().rows, 0);
+}
+
+#[test]
+fn kan_forward_batch_fail() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 11);
+    let short: Matrix = Matrix::new(vec![Vector::new(vec![0.2])]);
+    let wide: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.4, 0.6])]);
+
+    assert_eq!(kan.forward_batch(&short), Err(KanError::mismatch("input values, one per input of the KAN", 2, 1)));
+    assert_eq!(kan.forward_batch(&wide), Err(KanError::mismatch("input values, one per input of the KAN", 2, 3)));
+    assert!(matches!(kan.predict_batch(&short), Err(KanError::DimensionMismatch { .. })));
+}
+
+#[test]
+fn kan_predict_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.1, 0.2]), Vector::new(vec![0.5, 0.9]), Vector::new(vec![0.7, 0.3])]);
+
+    let predictions: Vector = kan.predict(&inputs).unwrap();
+    let batch: Vector = kan.predict_batch(&inputs).unwrap();
+
+    assert_eq!(predictions.len(), 3);
+    for i in 0..inputs.rows {
+        assert_is_close!(predictions[i], kan.forward(&inputs.row(i)).unwrap(), 1e-12);
+        assert_is_close!(batch[i], predictions[i], 1e-12);
+    }
+}
+
+#[test]
+fn kan_loss_pass() {
+    let kan: KAN = KAN::standard_seeded(2, 3, 11);
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.4]), Vector::new(vec![0.6, 0.9]), Vector::new(vec![0.1, 0.3])]);
+    let targets: Vector = Vector::new(vec![1.0, -1.0, 0.5]);
+
+    let expected: f64 = (0..3).map(|i| kan.loss_single(inputs.row(i), targets[i]).unwrap()).sum::<f64>() / 3.0;
+    assert_is_close!(kan.loss(inputs, targets).unwrap(), expected, 1e-12);
+}
+
+#[test]
+fn kan_refine_grid_pass() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let inputs: Vec<Vector> = (1..10).map(|i| Vector::new(vec![0.1 * i as f64, 1.0 - 0.1 * i as f64])).collect();
+    let before: Vec<f64> = inputs.iter().map(|input| kan.forward(input).unwrap()).collect();
+
+    kan.refine_grid(10);
+
+    for edge in kan.edges().iter() {
+        assert_eq!(edge.borrow().spline().control_points.len(), 10);
+        assert_eq!(edge.borrow().gradient.len(), 12);
+    }
+    for (input, expected) in inputs.iter().zip(before.iter()) {
+        assert_is_close!(kan.forward(input).unwrap(), *expected, 0.1);
+    }
+}
+
+#[test]
+fn kan_update_grids_pass() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let inputs: Matrix = Matrix::new((0..20).map(|i| Vector::new(vec![0.2 + 0.02 * i as f64, 0.5 + 0.01 * i as f64])).collect());
+
+    kan.update_grids(&inputs).unwrap();
+
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    assert_is_close!(edges[0].borrow().spline().knots[2], 0.2, 1e-2);
+    assert_is_close!(edges[0].borrow().spline().knots[5], 0.58, 1e-2);
+    assert_is_close!(edges[1].borrow().spline().knots[2], 0.5, 1e-2);
+    assert_is_close!(edges[1].borrow().spline().knots[5], 0.69, 1e-2);
+}
+
+#[test]
+fn kan_update_grids_fail() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let knots: Vector = kan.edges()[0].borrow().spline().knots.clone();
+
+    // The second input is constant, so the knots of neither edge change
+    let constant: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.5]), Vector::new(vec![0.6, 0.5])]);
+    assert_eq!(kan.update_grids(&constant), Err(KanError::DomainError("Every B-spline edge must receive at least two distinct values to update its knots.")));
+    assert_eq!(kan.edges()[0].borrow().spline().knots, knots);
+    assert!(kan.update_grids(&Matrix::new(vec![])).is_err());
+    assert!(kan.update_grids(&Matrix::zeros(2, 3)).is_err());
+}
+
+#[test]
+fn kan_partial_fit_pass() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let target = |input: &Vector| Vector::new(vec![input[0] - input[1]]);
+    let stream: Vec<Vector> = (0..200).map(|i| Vector::new(vec![i as f64 / 100.0, 0.5])).collect();
+
+    let mut losses: Vec<f64> = Vec::new();
+    for _ in 0..5 {
+        for input in stream.iter() {
+            losses.push(kan.partial_fit(input, &target(input), 0.1).unwrap());
+        }
+    }
+
+    // The grid of the first edge followed the input beyond the initial range [0, 1]
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    let knots: Vector = edges[0].borrow().spline().knots.clone();
+    assert!(knots[knots.len() - 1] > 1.99);
+    assert_eq!(edges[0].borrow().gradient.len(), edges[0].borrow().num_weights());
+    assert_eq!(edges[1].borrow().spline().knots[0], 0.0);
+    let early: f64 = losses[..200].iter().sum::<f64>();
+    let late: f64 = losses[losses.len() - 200..].iter().sum::<f64>();
+    assert!(late < early, "{} is not below {}", late, early);
+}
+
+#[test]
+fn kan_partial_fit_fail() {
+    let kan: KAN = single_layer_kan(2, 1);
+    let knots: Vector = kan.edges()[0].borrow().spline().knots.clone();
+
+    assert!(kan.partial_fit(&Vector::new(vec![0.1, 0.2]), &Vector::new(vec![1.0, 2.0]), 0.1).is_err());
+    assert_eq!(kan.partial_fit(&Vector::new(vec![0.1, 0.2]), &Vector::new(vec![1.0]), 0.0), Err(KanError::InvalidConfig("The learning rate must be greater than 0.")));
+    assert_eq!(kan.partial_fit(&Vector::new(vec![0.1, 0.2, 0.3]), &Vector::new(vec![1.0]), 0.1), Err(KanError::mismatch("input values, one per input of the KAN", 2, 3)));
+    assert!(matches!(kan.partial_fit(&Vector::new(vec![f64::NAN, 0.2]), &Vector::new(vec![1.0]), 0.1), Err(KanError::DomainError(_))));
+
+    // A failed step does not widen the grid for an input outside of it
+    assert!(kan.partial_fit(&Vector::new(vec![3.0, 0.2]), &Vector::new(vec![1.0, 2.0]), 0.1).is_err());
+    assert_eq!(kan.edges()[0].borrow().spline().knots, knots);
+}
+
+#[test]
+fn kan_save_json_pass() {
+    let path: std::path::PathBuf = std::env::temp_dir().join(format!("rusty_kan_save_json_{}.json", std::process::id()));
+    let kan: KAN = KAN::standard_seeded(2, 3, 7);
+    let input: Vector = Vector::new(vec![0.2, 0.7]);
+
+    kan.save_json(&path).unwrap();
+    let loaded: KAN = KAN::load_json(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.snapshot(), kan.snapshot());
+    assert!(loaded.approx_eq(&kan, 0.0));
+    assert_is_close!(loaded.forward_batch(&input.to_matrix()).unwrap()[0][0], kan.forward_batch(&input.to_matrix()).unwrap()[0][0], 1e-12);
+    // The outgoing edges of the hidden layer are the incoming edges of the output layer again
+    let hidden_edge: Rc<RefCell<Edge>> = loaded.layers[0].borrow().nodes[1].borrow().outgoing[0].clone();
+    let output_edge: Rc<RefCell<Edge>> = loaded.layers[1].borrow().nodes[0].borrow().incoming[1].clone();
+    assert!(Rc::ptr_eq(&hidden_edge, &output_edge));
+}
+
+#[test]
+fn kan_deep_clone_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Layer).add_layer(2).add_layer(1).skip(Skip::Spline).seed(5).build();
+    let input: Vector = Vector::new(vec![0.2, 0.7]);
+    let copy: KAN = kan.deep_clone();
+
+    assert!(copy.approx_eq(&kan, 0.0));
+    assert_eq!(copy.validate(), Ok(()));
+    assert_is_close!(copy.forward_batch(&input.to_matrix()).unwrap()[0][0], kan.forward_batch(&input.to_matrix()).unwrap()[0][0], 1e-12);
+    // No edge is shared with the original, but the outgoing edges of every node are the incoming edges of the later layers again
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    assert!(copy.edges().iter().all(|edge| !edges.iter().any(|original| Rc::ptr_eq(original, edge))));
+    let hidden_edge: Rc<RefCell<Edge>> = copy.layers[1].borrow().nodes[1].borrow().outgoing[0].clone();
+    let output_edge: Rc<RefCell<Edge>> = copy.layers[2].borrow().nodes[0].borrow().incoming[1].clone();
+    assert!(Rc::ptr_eq(&hidden_edge, &output_edge));
+    let skip_edge: Rc<RefCell<Edge>> = copy.layers[0].borrow().nodes[2].borrow().outgoing[2].clone();
+    assert!(skip_edge.borrow().skip && copy.layers[2].borrow().nodes[0].borrow().incoming.iter().any(|edge| Rc::ptr_eq(edge, &skip_edge)));
+
+    // Changing the copy leaves the original unchanged, unlike a clone
+    let snapshot: Vec<Vector> = kan.snapshot();
+    copy.edges()[0].borrow_mut().base_weight += 1.0;
+    copy.layers[0].borrow_mut().norm.as_mut().unwrap().shift[0] += 1.0;
+    assert_eq!(kan.snapshot(), snapshot);
+    assert_ne!(kan.layers[0].borrow().norm, copy.layers[0].borrow().norm);
+    kan.clone().edges()[0].borrow_mut().base_weight += 1.0;
+    assert_ne!(kan.snapshot(), snapshot);
+}
+
+#[test]
+fn kan_load_json_fail() {
+    let path: std::path::PathBuf = std::env::temp_dir().join(format!("rusty_kan_load_json_{}.json", std::process::id()));
+    std::fs::write(&path, "{\"layers\": []").unwrap();
+
+    let result: Result<KAN, KanError> = KAN::load_json(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(result.unwrap_err(), KanError::Format("The JSON file does not describe a KAN."));
+    assert!(matches!(KAN::load_json(&path), Err(KanError::Io(_))));
+}
+
+#[test]
+fn kan_eval_mode_pass() {
+    let mut kan: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Batch).dropout(0.5).add_layer(1).seed(2).build();
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.9]), Vector::new(vec![0.7, 0.1])]);
+    kan.sample_dropout(&mut rand::thread_rng());
+
+    kan.eval_mode();
+
+    // The mode reaches the edges and normalizations, so no edge is masked and the running statistics are kept
+    assert!(!kan.training);
+    assert!(kan.edges().iter().all(|edge| !edge.borrow().training && edge.borrow().masked.is_none()));
+    kan.sample_dropout(&mut rand::thread_rng());
+    assert!(kan.edges().iter().all(|edge| edge.borrow().masked.is_none()));
+    let norm = kan.layers[0].borrow().norm.clone();
+    kan.update_norm_statistics(&inputs).unwrap();
+    assert_eq!(kan.layers[0].borrow().norm, norm);
+
+    kan.train_mode();
+    kan.update_norm_statistics(&inputs).unwrap();
+    assert_ne!(kan.layers[0].borrow().norm, norm);
+    kan.sample_dropout(&mut rand::thread_rng());
+    assert!(kan.edges()[..6].iter().all(|edge| edge.borrow().masked.is_some()));
+
+    // A loaded KAN is in training mode
+    kan.eval_mode();
+    let loaded: KAN = serde_json::from_str(&serde_json::to_string(&kan).unwrap()).unwrap();
+    assert!(loaded.training && loaded.layers[0].borrow().norm.as_ref().unwrap().training);
 }
\ No newline at end of file
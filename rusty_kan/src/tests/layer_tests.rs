@@ -11,7 +11,8 @@ macro_rules! assert_is_close {
     }};
 }
 
-use crate::data_structures::{node::Node, vector::Vector, matrix::Matrix, edge::Edge, spline::BSpline, layer::Layer};
+use crate::data_structures::{node::{Node, Aggregation}, vector::Vector, matrix::Matrix, edge::Edge, spline::BSpline, layer::Layer, sparse::SparseMatrix};
+use crate::error::KanError;
 use std::rc::Rc;
 use std::cell::{RefCell, RefMut};
 
@@ -95,13 +96,11 @@ fn layer_forward_pass() {
 
     let input: Matrix = Matrix::new(vec![Vector::from(vec![0.1, 0.2]), Vector::from(vec![0.3, 0.4])]);
     
-    let value: Matrix = layer.forward(input.clone());
+    let value: Matrix = layer.forward(input.clone()).unwrap();
 
-    assert_eq!(value.rows.len(), 2);
-    assert_eq!(value.rows[0].elements.len(), 1);
-    assert_eq!(value.rows[1].elements.len(), 1);
-    assert_is_close!(value[0][0], layer.nodes[0].borrow_mut().forward(&input[0]), 1e-6);
-    assert_is_close!(value[1][0], layer.nodes[1].borrow_mut().forward(&input[1]), 1e-6);
+    assert_eq!(value.shape(), (2, 1));
+    assert_is_close!(value[0][0], layer.nodes[0].borrow_mut().forward(&input.row(0)).unwrap(), 1e-6);
+    assert_is_close!(value[1][0], layer.nodes[1].borrow_mut().forward(&input.row(1)).unwrap(), 1e-6);
 }
 
 #[test]
@@ -130,7 +129,7 @@ fn layer_forward_less_rows_fail() {
     // Input dimensions should be 2 x 2
     let input: Matrix = Matrix::new(vec![Vector::from(vec![0.1, 0.2])]);
 
-    layer.forward(input);
+    layer.forward(input).unwrap();
 }
 
 #[test]
@@ -159,7 +158,7 @@ fn layer_forward_more_rows_fail() {
     // Input dimensions should be 2 x 2
     let input: Matrix = Matrix::new(vec![Vector::from(vec![0.1, 0.2]), Vector::from(vec![0.3, 0.4]), Vector::from(vec![0.5, 0.6])]);
 
-    layer.forward(input);
+    layer.forward(input).unwrap();
 }
 
 #[test]
@@ -188,7 +187,7 @@ fn layer_forward_less_cols_fail() {
     // Input dimensions should be 2 x 2
     let input: Matrix = Matrix::new(vec![Vector::from(vec![0.1]), Vector::from(vec![0.3])]);
 
-    layer.forward(input);
+    layer.forward(input).unwrap();
 }
 
 #[test]
@@ -217,7 +216,7 @@ fn layer_forward_more_cols_fail() {
     // Input dimensions should be 2 x 2
     let input: Matrix = Matrix::new(vec![Vector::from(vec![0.1, 0.2, 0.3]), Vector::from(vec![0.3, 0.4, 0.5])]);
 
-    layer.forward(input);
+    layer.forward(input).unwrap();
 }
 
 #[test]
@@ -250,28 +249,28 @@ fn layer_backward_pass() {
     // Node 1:
     // Incoming edge 1
     let incoming_edge_1: RefMut<Edge> = node_1.incoming[0].borrow_mut();
-    assert_is_close!(incoming_edge_1.gradient[0], incoming_edge_1.clone().spline.basis(0, incoming_edge_1.spline.degree, inputs[0][0]) * upstream_gradient[0], 1e-3);
-    assert_is_close!(incoming_edge_1.gradient[1], incoming_edge_1.clone().spline.basis(1, incoming_edge_1.spline.degree, inputs[0][0]) * upstream_gradient[0], 1e-3);
-    assert_is_close!(incoming_edge_1.gradient[2], incoming_edge_1.clone().spline.basis(2, incoming_edge_1.spline.degree, inputs[0][0]) * upstream_gradient[0], 1e-3);
+    assert_is_close!(incoming_edge_1.gradient[0], incoming_edge_1.clone().spline_mut().basis(0, incoming_edge_1.spline().degree, inputs[0][0]) * upstream_gradient[0], 1e-3);
+    assert_is_close!(incoming_edge_1.gradient[1], incoming_edge_1.clone().spline_mut().basis(1, incoming_edge_1.spline().degree, inputs[0][0]) * upstream_gradient[0], 1e-3);
+    assert_is_close!(incoming_edge_1.gradient[2], incoming_edge_1.clone().spline_mut().basis(2, incoming_edge_1.spline().degree, inputs[0][0]) * upstream_gradient[0], 1e-3);
 
     // Incoming edge 2
     let incoming_edge_2: RefMut<Edge> = node_1.incoming[1].borrow_mut();
-    assert_is_close!(incoming_edge_2.gradient[0], incoming_edge_2.clone().spline.basis(0, incoming_edge_2.spline.degree, inputs[0][1]) * upstream_gradient[0], 1e-3);
-    assert_is_close!(incoming_edge_2.gradient[1], incoming_edge_2.clone().spline.basis(1, incoming_edge_2.spline.degree, inputs[0][1]) * upstream_gradient[0], 1e-3);
-    assert_is_close!(incoming_edge_2.gradient[2], incoming_edge_2.clone().spline.basis(2, incoming_edge_2.spline.degree, inputs[0][1]) * upstream_gradient[0], 1e-3);
+    assert_is_close!(incoming_edge_2.gradient[0], incoming_edge_2.clone().spline_mut().basis(0, incoming_edge_2.spline().degree, inputs[0][1]) * upstream_gradient[0], 1e-3);
+    assert_is_close!(incoming_edge_2.gradient[1], incoming_edge_2.clone().spline_mut().basis(1, incoming_edge_2.spline().degree, inputs[0][1]) * upstream_gradient[0], 1e-3);
+    assert_is_close!(incoming_edge_2.gradient[2], incoming_edge_2.clone().spline_mut().basis(2, incoming_edge_2.spline().degree, inputs[0][1]) * upstream_gradient[0], 1e-3);
 
     // Node 2:
     // Incoming edge 1
     let incoming_edge_1: RefMut<Edge> = node_2.incoming[0].borrow_mut();
-    assert_is_close!(incoming_edge_1.gradient[0], incoming_edge_1.clone().spline.basis(0, incoming_edge_1.spline.degree, inputs[1][0]) * upstream_gradient[1], 1e-3);
-    assert_is_close!(incoming_edge_1.gradient[1], incoming_edge_1.clone().spline.basis(1, incoming_edge_1.spline.degree, inputs[1][0]) * upstream_gradient[1], 1e-3);
-    assert_is_close!(incoming_edge_1.gradient[2], incoming_edge_1.clone().spline.basis(2, incoming_edge_1.spline.degree, inputs[1][0]) * upstream_gradient[1], 1e-3);
+    assert_is_close!(incoming_edge_1.gradient[0], incoming_edge_1.clone().spline_mut().basis(0, incoming_edge_1.spline().degree, inputs[1][0]) * upstream_gradient[1], 1e-3);
+    assert_is_close!(incoming_edge_1.gradient[1], incoming_edge_1.clone().spline_mut().basis(1, incoming_edge_1.spline().degree, inputs[1][0]) * upstream_gradient[1], 1e-3);
+    assert_is_close!(incoming_edge_1.gradient[2], incoming_edge_1.clone().spline_mut().basis(2, incoming_edge_1.spline().degree, inputs[1][0]) * upstream_gradient[1], 1e-3);
 
     // Incoming edge 2
     let incoming_edge_2: RefMut<Edge> = node_2.incoming[1].borrow_mut();
-    assert_is_close!(incoming_edge_2.gradient[0], incoming_edge_2.clone().spline.basis(0, incoming_edge_2.spline.degree, inputs[1][1]) * upstream_gradient[1], 1e-3);
-    assert_is_close!(incoming_edge_2.gradient[1], incoming_edge_2.clone().spline.basis(1, incoming_edge_2.spline.degree, inputs[1][1]) * upstream_gradient[1], 1e-3);
-    assert_is_close!(incoming_edge_2.gradient[2], incoming_edge_2.clone().spline.basis(2, incoming_edge_2.spline.degree, inputs[1][1]) * upstream_gradient[1], 1e-3);
+    assert_is_close!(incoming_edge_2.gradient[0], incoming_edge_2.clone().spline_mut().basis(0, incoming_edge_2.spline().degree, inputs[1][1]) * upstream_gradient[1], 1e-3);
+    assert_is_close!(incoming_edge_2.gradient[1], incoming_edge_2.clone().spline_mut().basis(1, incoming_edge_2.spline().degree, inputs[1][1]) * upstream_gradient[1], 1e-3);
+    assert_is_close!(incoming_edge_2.gradient[2], incoming_edge_2.clone().spline_mut().basis(2, incoming_edge_2.spline().degree, inputs[1][1]) * upstream_gradient[1], 1e-3);
 }
 
 #[test]
@@ -330,4 +329,91 @@ fn layer_backward_wrong_gradient_dims_fail() {
     let upstream_gradient: Vector = Vector::from(vec![0.4]);
 
     layer.backward(inputs.clone(), &upstream_gradient).unwrap();
-}
\ No newline at end of file
+}
+#[test]
+fn layer_set_lr_scale_pass() {
+    let nodes: Vec<Rc<RefCell<Node>>> = (0..2).map(|j| {
+        let incoming: Vec<Rc<RefCell<Edge>>> = (0..3).map(|i| Rc::new(RefCell::new(Edge::standard(i, j, 0)))).collect();
+        Rc::new(RefCell::new(Node::new(incoming, vec![], 0)))
+    }).collect();
+    let layer: Layer = Layer::new(nodes);
+
+    layer.set_lr_scale(0.1);
+
+    for node in layer.nodes.iter() {
+        for edge in node.borrow().incoming.iter() {
+            assert_eq!(edge.borrow().lr_scale, 0.1);
+        }
+    }
+}
+
+#[test]
+fn layer_freeze_pass() {
+    let nodes: Vec<Rc<RefCell<Node>>> = (0..2).map(|j| {
+        let incoming: Vec<Rc<RefCell<Edge>>> = (0..3).map(|i| Rc::new(RefCell::new(Edge::standard(i, j, 0)))).collect();
+        Rc::new(RefCell::new(Node::new(incoming, vec![], 0)))
+    }).collect();
+    let layer: Layer = Layer::new(nodes);
+
+    layer.freeze();
+    assert!(layer.nodes.iter().all(|node| node.borrow().incoming.iter().all(|edge| edge.borrow().frozen)));
+
+    layer.unfreeze();
+    assert!(layer.nodes.iter().all(|node| node.borrow().incoming.iter().all(|edge| !edge.borrow().frozen)));
+}
+
+#[test]
+fn layer_adjacency_pass() {
+    // Node 0 reads previous nodes 0 and 2, node 1 reads previous node 2 twice
+    let starts: Vec<Vec<usize>> = vec![vec![0, 2], vec![2, 2]];
+    let nodes: Vec<Rc<RefCell<Node>>> = starts.iter().enumerate().map(|(j, starts)| {
+        let incoming: Vec<Rc<RefCell<Edge>>> = starts.iter().map(|&i| Rc::new(RefCell::new(Edge::standard(i, j, 0)))).collect();
+        Rc::new(RefCell::new(Node::new(incoming, vec![], 0)))
+    }).collect();
+    let layer: Layer = Layer::new(nodes);
+
+    let adjacency: SparseMatrix = layer.adjacency(4).unwrap();
+
+    assert_eq!(adjacency.shape(), (2, 4));
+    assert_eq!(adjacency.nnz(), 3);
+    assert_eq!(adjacency.to_dense(), Matrix::new(vec![Vector::new(vec![1.0, 0.0, 1.0, 0.0]), Vector::new(vec![0.0, 0.0, 2.0, 0.0])]));
+    assert_eq!(layer.propagate(&Vector::new(vec![0.5, 0.25]), 4).unwrap(), Vector::new(vec![0.5, 0.0, 1.0, 0.0]));
+}
+
+#[test]
+fn layer_propagate_through_pass() {
+    // Same wiring as above, with node 1 multiplying its two edges
+    let starts: Vec<Vec<usize>> = vec![vec![0, 2], vec![2, 2]];
+    let nodes: Vec<Rc<RefCell<Node>>> = starts.iter().enumerate().map(|(j, starts)| {
+        let incoming: Vec<Rc<RefCell<Edge>>> = starts.iter().map(|&i| Rc::new(RefCell::new(Edge::standard(i, j, 0)))).collect();
+        Rc::new(RefCell::new(Node::new(incoming, vec![], 0)))
+    }).collect();
+    let layer: Layer = Layer::new(nodes);
+    let input: Matrix = Matrix::new(vec![Vector::new(vec![0.1, 0.4]), Vector::new(vec![0.4, 0.4])]);
+    let upstream: Vector = Vector::new(vec![0.5, 0.25]);
+
+    // With only sum nodes it matches the unit-slope adjacency
+    assert_eq!(layer.propagate_through(&input, &upstream, 4).unwrap(), layer.propagate(&upstream, 4).unwrap());
+
+    layer.nodes[1].borrow_mut().aggregation = Aggregation::Product;
+    let values: Vec<f64> = layer.nodes[1].borrow().incoming.iter().map(|edge| edge.borrow().value(0.4)).collect();
+    let gradient: Vector = layer.propagate_through(&input, &upstream, 4).unwrap();
+
+    // Each edge of the product node receives the upstream gradient times the value of the other edge
+    assert_eq!(gradient[0], 0.5);
+    assert_is_close!(gradient[2], 0.5 + 0.25 * (values[1] + values[0]), 1e-12);
+}
+
+#[test]
+fn layer_adjacency_fail() {
+    let incoming: Vec<Rc<RefCell<Edge>>> = vec![Rc::new(RefCell::new(Edge::standard(3, 0, 0)))];
+    let layer: Layer = Layer::new(vec![Rc::new(RefCell::new(Node::new(incoming, vec![], 0)))]);
+    let input: Matrix = Matrix::new(vec![Vector::new(vec![0.5])]);
+
+    assert!(matches!(layer.adjacency(2), Err(KanError::DimensionMismatch { .. })));
+    assert!(matches!(layer.propagate(&Vector::new(vec![1.0]), 2), Err(KanError::DimensionMismatch { .. })));
+    assert_eq!(layer.propagate(&Vector::new(vec![1.0, 2.0]), 4), Err(KanError::mismatch("upstream gradients, one per node", 1, 2)));
+    layer.nodes[0].borrow_mut().aggregation = Aggregation::Product;
+    assert!(matches!(layer.propagate_through(&input, &Vector::new(vec![1.0]), 2), Err(KanError::DimensionMismatch { .. })));
+    assert!(matches!(layer.propagate_through(&Matrix::new(vec![Vector::new(vec![0.5, 0.5, 0.5])]), &Vector::new(vec![1.0]), 4), Err(KanError::DimensionMismatch { .. })));
+}
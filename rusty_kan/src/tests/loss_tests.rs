@@ -0,0 +1,115 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::vector::Vector;
+use crate::loss::{Loss, Reduction, softmax, one_hot};
+use crate::error::KanError;
+
+#[test]
+fn softmax_pass() {
+    let logits: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let probabilities: Vector = softmax(&logits);
+
+    let denominator: f64 = 1.0_f64.exp() + 2.0_f64.exp() + 3.0_f64.exp();
+    assert_is_close!(probabilities[0], 1.0_f64.exp() / denominator, 1e-9);
+    assert_is_close!(probabilities[1], 2.0_f64.exp() / denominator, 1e-9);
+    assert_is_close!(probabilities[2], 3.0_f64.exp() / denominator, 1e-9);
+}
+
+#[test]
+fn softmax_large_logits_pass() {
+    let logits: Vector = Vector::new(vec![1000.0, 1000.0]);
+    let probabilities: Vector = softmax(&logits);
+
+    assert_is_close!(probabilities[0], 0.5, 1e-9);
+    assert_is_close!(probabilities[1], 0.5, 1e-9);
+}
+
+#[test]
+fn one_hot_pass() {
+    let target: Vector = one_hot(1, 3).unwrap();
+
+    assert_eq!(target.elements, vec![0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn one_hot_fail() {
+    assert_eq!(one_hot(3, 3), Err(KanError::DomainError("The class index must be smaller than the number of classes.")));
+}
+
+#[test]
+fn mean_squared_error_pass() {
+    let output: Vector = Vector::new(vec![1.0, 2.0]);
+    let target: Vector = Vector::new(vec![0.0, 4.0]);
+
+    assert_is_close!(Loss::MeanSquaredError.compute(&output, &target).unwrap(), 2.5, 1e-9);
+
+    let gradient: Vector = Loss::MeanSquaredError.gradient(&output, &target).unwrap();
+    assert_is_close!(gradient[0], 1.0, 1e-9);
+    assert_is_close!(gradient[1], -2.0, 1e-9);
+}
+
+#[test]
+fn cross_entropy_pass() {
+    let output: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let target: Vector = one_hot(2, 3).unwrap();
+    let probabilities: Vector = softmax(&output);
+
+    assert_is_close!(Loss::CrossEntropy.compute(&output, &target).unwrap(), -probabilities[2].ln(), 1e-9);
+
+    let gradient: Vector = Loss::CrossEntropy.gradient(&output, &target).unwrap();
+    assert_is_close!(gradient[0], probabilities[0], 1e-9);
+    assert_is_close!(gradient[1], probabilities[1], 1e-9);
+    assert_is_close!(gradient[2], probabilities[2] - 1.0, 1e-9);
+}
+
+#[test]
+fn cross_entropy_gradient_numerical_pass() {
+    let output: Vector = Vector::new(vec![0.3, -1.2, 0.8]);
+    let target: Vector = one_hot(0, 3).unwrap();
+    let gradient: Vector = Loss::CrossEntropy.gradient(&output, &target).unwrap();
+
+    let epsilon: f64 = 1e-6;
+    for i in 0..output.len() {
+        let mut plus: Vector = output.clone();
+        let mut minus: Vector = output.clone();
+        plus[i] += epsilon;
+        minus[i] -= epsilon;
+        let numerical: f64 = (Loss::CrossEntropy.compute(&plus, &target).unwrap() - Loss::CrossEntropy.compute(&minus, &target).unwrap()) / (2.0 * epsilon);
+        assert_is_close!(gradient[i], numerical, 1e-6);
+    }
+}
+
+#[test]
+fn loss_length_mismatch_fail() {
+    let (output, target): (Vector, Vector) = (Vector::new(vec![1.0]), Vector::new(vec![1.0, 2.0]));
+
+    assert_eq!(Loss::MeanSquaredError.compute(&output, &target), Err(KanError::mismatch("target values, one per output value", 1, 2)));
+    assert!(matches!(Loss::CrossEntropy.gradient(&output, &target), Err(KanError::DimensionMismatch { .. })));
+}
+
+#[test]
+fn reduction_reduce_pass() {
+    let losses: Vector = Vector::new(vec![0.5, 1.5, 4.0]);
+
+    assert_is_close!(Reduction::Mean.reduce(losses.clone()).unwrap()[0], 2.0, 1e-12);
+    assert_is_close!(Reduction::Sum.reduce(losses.clone()).unwrap()[0], 6.0, 1e-12);
+    assert_eq!(Reduction::Mean.reduce(losses.clone()).unwrap().len(), 1);
+    assert_eq!(Reduction::None.reduce(losses.clone()).unwrap(), losses);
+}
+
+#[test]
+fn reduction_mean_empty_fail() {
+    assert_eq!(Reduction::Mean.reduce(Vector::new(vec![])), Err(KanError::DomainError("The mean of an empty batch of losses is undefined.")));
+    assert_eq!(Reduction::Sum.reduce(Vector::new(vec![])).unwrap()[0], 0.0);
+}
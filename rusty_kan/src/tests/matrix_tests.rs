@@ -1,54 +1,70 @@
-use crate::data_structures::{vector::Vector, matrix::Matrix};
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, matrix::{Matrix, Axis}};
+use crate::error::KanError;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
 #[test]
 fn matrix_add_pass() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
-    let b = Matrix { rows: vec![Vector { elements: vec![7.0, 8.0, 9.0] }, Vector { elements: vec![10.0, 11.0, 12.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
+    let b: Matrix = Matrix::new(vec![Vector { elements: vec![7.0, 8.0, 9.0] }, Vector { elements: vec![10.0, 11.0, 12.0] }]);
 
     let c = a + b;
 
-    assert_eq!(c.rows, vec![Vector { elements: vec![8.0, 10.0, 12.0] }, Vector { elements: vec![14.0, 16.0, 18.0] }]);
+    assert_eq!(c, Matrix::new(vec![Vector { elements: vec![8.0, 10.0, 12.0] }, Vector { elements: vec![14.0, 16.0, 18.0] }]));
 }
 
 #[test]
 #[should_panic]
 fn matrix_add_fail() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
-    let b = Matrix { rows: vec![Vector { elements: vec![7.0, 8.0, 9.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
+    let b: Matrix = Matrix::new(vec![Vector { elements: vec![7.0, 8.0, 9.0] }]);
     let _ = a + b;
 }
 
 #[test]
 fn matrix_sub_pass() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
-    let b = Matrix { rows: vec![Vector { elements: vec![7.0, 8.0, 9.0] }, Vector { elements: vec![10.0, 11.0, 12.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
+    let b: Matrix = Matrix::new(vec![Vector { elements: vec![7.0, 8.0, 9.0] }, Vector { elements: vec![10.0, 11.0, 12.0] }]);
 
     let c = a - b;
 
-    assert_eq!(c.rows, vec![Vector { elements: vec![-6.0, -6.0, -6.0] }, Vector { elements: vec![-6.0, -6.0, -6.0] }]);
+    assert_eq!(c, Matrix::new(vec![Vector { elements: vec![-6.0, -6.0, -6.0] }, Vector { elements: vec![-6.0, -6.0, -6.0] }]));
 }
 
 #[test]
 #[should_panic]
 fn matrix_sub_fail() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
-    let b = Matrix { rows: vec![Vector { elements: vec![7.0, 8.0, 9.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
+    let b: Matrix = Matrix::new(vec![Vector { elements: vec![7.0, 8.0, 9.0] }]);
     let _ = a - b;
 }
 
 #[test]
 fn matrix_mul_scalar_pass() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
     let scalar = 2.0;
 
     let b = a * scalar;
 
-    assert_eq!(b.rows, vec![Vector { elements: vec![2.0, 4.0, 6.0] }, Vector { elements: vec![8.0, 10.0, 12.0] }]);
+    assert_eq!(b, Matrix::new(vec![Vector { elements: vec![2.0, 4.0, 6.0] }, Vector { elements: vec![8.0, 10.0, 12.0] }]));
 }
 
 #[test]
 fn matrix_mul_vector_pass() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
     let b = Vector { elements: vec![7.0, 8.0, 9.0] };
     
     let c = a * b;
@@ -58,25 +74,49 @@ fn matrix_mul_vector_pass() {
 
 #[test]
 fn matrix_mul_matrix_pass() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0] }, Vector { elements: vec![3.0, 4.0] }] };
-    let b = Matrix { rows: vec![Vector { elements: vec![5.0, 6.0] }, Vector { elements: vec![7.0, 8.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0] }, Vector { elements: vec![3.0, 4.0] }]);
+    let b: Matrix = Matrix::new(vec![Vector { elements: vec![5.0, 6.0] }, Vector { elements: vec![7.0, 8.0] }]);
 
     let c = a * b;
 
-    assert_eq!(c.rows, vec![Vector { elements: vec![19.0, 22.0] }, Vector { elements: vec![43.0, 50.0] }]);
+    assert_eq!(c, Matrix::new(vec![Vector { elements: vec![19.0, 22.0] }, Vector { elements: vec![43.0, 50.0] }]));
 }
 
 #[test]
 #[should_panic]
 fn matrix_mul_matrix_fail() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
-    let b = Matrix { rows: vec![Vector { elements: vec![4.0, 5.0] }, Vector { elements: vec![7.0, 8.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
+    let b: Matrix = Matrix::new(vec![Vector { elements: vec![4.0, 5.0] }, Vector { elements: vec![7.0, 8.0] }]);
     let _ = a * b;
 }
 
+#[test]
+fn matrix_try_pass() {
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0] }, Vector { elements: vec![3.0, 4.0] }]);
+    let b: Matrix = Matrix::new(vec![Vector { elements: vec![5.0, 6.0] }, Vector { elements: vec![7.0, 8.0] }]);
+
+    assert_eq!(a.try_add(&b), Ok(&a + &b));
+    assert_eq!(a.try_sub(&b), Ok(&a - &b));
+    assert_eq!(a.try_mul(&b), Ok(&a * &b));
+    assert_eq!(a.try_mul_vector(&Vector { elements: vec![1.0, -1.0] }), Ok(Vector { elements: vec![-1.0, -1.0] }));
+}
+
+#[test]
+fn matrix_try_fail() {
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
+    let b: Matrix = Matrix::new(vec![Vector { elements: vec![4.0, 5.0] }, Vector { elements: vec![7.0, 8.0] }]);
+
+    assert_eq!(a.try_add(&b), Err(KanError::shape_mismatch("the second matrix of the addition", (2, 3), (2, 2))));
+    assert_eq!(a.try_sub(&b), Err(KanError::shape_mismatch("the second matrix of the subtraction", (2, 3), (2, 2))));
+    assert_eq!(a.try_mul(&b), Err(KanError::mismatch("rows in the second matrix of the product, one per column of the first", 3, 2)));
+    assert_eq!(a.try_mul_vector(&Vector { elements: vec![1.0, 2.0] }), Err(KanError::mismatch("elements in the vector of the product, one per column of the matrix", 3, 2)));
+    // The checked product is defined in the other order
+    assert!(b.try_mul(&a).is_ok());
+}
+
 #[test]
 fn matrix_index_pass() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
 
     assert_eq!(a[0][0], 1.0);
     assert_eq!(a[0][1], 2.0);
@@ -89,36 +129,85 @@ fn matrix_index_pass() {
 #[test]
 #[should_panic]
 fn matrix_index_fail() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
     let _ = a[0][3];
 }
 
 #[test]
 fn matrix_zeros_pass() {
-    let a = Matrix::zeros(2, 3);
+    let a: Matrix = Matrix::zeros(2, 3);
 
-    assert_eq!(a.rows, vec![Vector { elements: vec![0.0, 0.0, 0.0] }, Vector { elements: vec![0.0, 0.0, 0.0] }]);
+    assert_eq!(a, Matrix::new(vec![Vector { elements: vec![0.0, 0.0, 0.0] }, Vector { elements: vec![0.0, 0.0, 0.0] }]));
 }
 
 #[test]
 fn matrix_ones_pass() {
-    let a = Matrix::ones(2, 3);
+    let a: Matrix = Matrix::ones(2, 3);
 
-    assert_eq!(a.rows, vec![Vector { elements: vec![1.0, 1.0, 1.0] }, Vector { elements: vec![1.0, 1.0, 1.0] }]);
+    assert_eq!(a, Matrix::new(vec![Vector { elements: vec![1.0, 1.0, 1.0] }, Vector { elements: vec![1.0, 1.0, 1.0] }]));
 }
 
 #[test]
 fn matrix_random_pass() {
-    let a = Matrix::random(2, 3);
+    let a: Matrix = Matrix::random(2, 3);
+
+    assert_eq!(a.shape(), (2, 3));
+    assert_eq!(a.elements.len(), 6);
+}
+
+#[test]
+fn matrix_random_with_rng_pass() {
+    let a: Matrix = Matrix::random_with_rng(2, 3, &mut ChaCha8Rng::seed_from_u64(7));
+    let b: Matrix = Matrix::random_with_rng(2, 3, &mut ChaCha8Rng::seed_from_u64(7));
+
+    assert_eq!(a.rows, 2);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn matrix_random_uniform_pass() {
+    let a: Matrix = Matrix::random_uniform_with_rng(4, 5, -1.0, 1.0, &mut ChaCha8Rng::seed_from_u64(7));
+    let b: Matrix = Matrix::random_uniform_with_rng(4, 5, -1.0, 1.0, &mut ChaCha8Rng::seed_from_u64(7));
+
+    assert_eq!(a.shape(), (4, 5));
+    assert_eq!(a, b);
+    assert!(a.elements.iter().all(|&x| (-1.0..1.0).contains(&x)));
+    assert_ne!(a.row(0), a.row(1));
+}
+
+#[test]
+fn matrix_random_normal_pass() {
+    let a: Matrix = Matrix::random_normal_with_rng(100, 100, 0.0, 1.0, &mut ChaCha8Rng::seed_from_u64(7));
 
-    assert_eq!(a.rows.len(), 2);
-    assert_eq!(a.rows[0].elements.len(), 3);
-    assert_eq!(a.rows[1].elements.len(), 3);
+    assert_eq!(a.shape(), (100, 100));
+    assert_is_close!(Vector::new(a.elements.clone()).mean(), 0.0, 0.05);
+    assert_is_close!(Vector::new(a.elements.clone()).std(), 1.0, 0.05);
+}
+
+#[test]
+fn matrix_rank1_update_pass() {
+    let mut a: Matrix = Matrix::identity(2);
+    let u: Vector = Vector::new(vec![1.0, 2.0]);
+    let v: Vector = Vector::new(vec![3.0, 4.0]);
+
+    a.rank1_update(&u, &v, 0.5);
+
+    assert_eq!(a, Matrix::new(vec![Vector::new(vec![2.5, 2.0]), Vector::new(vec![3.0, 5.0])]));
+    let mut b: Matrix = Matrix::identity(2);
+    b += &(u.outer(&v) * 0.5);
+    assert_eq!(a, b);
+}
+
+#[test]
+#[should_panic]
+fn matrix_rank1_update_fail() {
+    let mut a: Matrix = Matrix::zeros(2, 3);
+    a.rank1_update(&Vector::new(vec![1.0, 2.0]), &Vector::new(vec![1.0, 2.0]), 1.0);
 }
 
 #[test]
 fn matrix_identity_pass() {
-    let a = Matrix::identity(3);
+    let a: Matrix = Matrix::identity(3);
     for i in 0..3 {
         for j in 0..3 {
             if i == j {
@@ -132,14 +221,14 @@ fn matrix_identity_pass() {
 
 #[test]
 fn matrix_shape_pass() {
-    let a = Matrix::zeros(2, 3);
+    let a: Matrix = Matrix::zeros(2, 3);
 
     assert_eq!(a.shape(), (2, 3));
 }
 
 #[test]
 fn matrix_get_col_pass() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
 
     let b = a.get_col(1);
 
@@ -149,7 +238,7 @@ fn matrix_get_col_pass() {
 #[test]
 #[should_panic]
 fn matrix_get_col_fail() {
-    let a = Matrix::zeros(2, 3);
+    let a: Matrix = Matrix::zeros(2, 3);
     let _ = a.get_col(3);
 }
 
@@ -160,7 +249,7 @@ fn matrix_set_row_pass() {
 
     a.set_row(1, b);
 
-    assert_eq!(a.rows, vec![Vector { elements: vec![0.0, 0.0, 0.0] }, Vector { elements: vec![1.0, 2.0, 3.0] }]);
+    assert_eq!(a, Matrix::new(vec![Vector { elements: vec![0.0, 0.0, 0.0] }, Vector { elements: vec![1.0, 2.0, 3.0] }]));
 }
 
 #[test]
@@ -178,7 +267,7 @@ fn matrix_set_col_pass() {
 
     a.set_col(1, b);
 
-    assert_eq!(a.rows, vec![Vector { elements: vec![0.0, 1.0, 0.0] }, Vector { elements: vec![0.0, 2.0, 0.0] }]);
+    assert_eq!(a, Matrix::new(vec![Vector { elements: vec![0.0, 1.0, 0.0] }, Vector { elements: vec![0.0, 2.0, 0.0] }]));
 }
 
 #[test]
@@ -191,9 +280,304 @@ fn matrix_set_col_fail() {
 
 #[test]
 fn matrix_transpose_pass() {
-    let a = Matrix { rows: vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }] };
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]);
 
     let b = a.transpose();
 
-    assert_eq!(b.rows, vec![Vector { elements: vec![1.0, 4.0] }, Vector { elements: vec![2.0, 5.0] }, Vector { elements: vec![3.0, 6.0] }]);
-}
\ No newline at end of file
+    assert_eq!(b, Matrix::new(vec![Vector { elements: vec![1.0, 4.0] }, Vector { elements: vec![2.0, 5.0] }, Vector { elements: vec![3.0, 6.0] }]));
+}
+#[test]
+fn matrix_lu_decompose_pass() {
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![0.0, 2.0, 1.0] }, Vector { elements: vec![1.0, 1.0, 0.0] }, Vector { elements: vec![3.0, 0.0, 1.0] }]);
+
+    let lu = a.lu_decompose().unwrap();
+
+    // P A = L U, with L unit lower triangular and U upper triangular
+    let product = &lu.lower * &lu.upper;
+    for i in 0..3 {
+        assert_eq!(lu.lower[i][i], 1.0);
+        for j in 0..3 {
+            if j > i {
+                assert_eq!(lu.lower[i][j], 0.0);
+            }
+            if j < i {
+                assert_eq!(lu.upper[i][j], 0.0);
+            }
+            assert_is_close!(product[i][j], a[lu.permutation[i]][j], 1e-12);
+        }
+    }
+}
+
+#[test]
+fn matrix_lu_decompose_fail() {
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0] }, Vector { elements: vec![2.0, 4.0] }]);
+
+    assert!(a.lu_decompose().is_err());
+    assert!(a.solve(&Vector { elements: vec![1.0, 2.0] }).is_err());
+    assert!(a.inverse().is_err());
+    assert!(Matrix::<f64>::zeros(2, 2).inverse().is_err());
+}
+
+#[test]
+fn matrix_solve_pass() {
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![0.0, 2.0, 1.0] }, Vector { elements: vec![1.0, 1.0, 0.0] }, Vector { elements: vec![3.0, 0.0, 1.0] }]);
+    let x = Vector { elements: vec![1.0, -2.0, 0.5] };
+    let b = &a * &x;
+
+    let solution = a.solve(&b).unwrap();
+
+    for i in 0..3 {
+        assert_is_close!(solution[i], x[i], 1e-12);
+    }
+}
+
+#[test]
+#[should_panic]
+fn matrix_solve_fail() {
+    let a: Matrix = Matrix::identity(2);
+    let _ = a.solve(&Vector { elements: vec![1.0, 2.0, 3.0] });
+}
+
+#[test]
+fn matrix_inverse_pass() {
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![4.0, 7.0] }, Vector { elements: vec![2.0, 6.0] }]);
+
+    let inverse = a.inverse().unwrap();
+
+    assert_is_close!(inverse[0][0], 0.6, 1e-12);
+    assert_is_close!(inverse[0][1], -0.7, 1e-12);
+    assert_is_close!(inverse[1][0], -0.2, 1e-12);
+    assert_is_close!(inverse[1][1], 0.4, 1e-12);
+    let product = &a * &inverse;
+    for i in 0..2 {
+        for j in 0..2 {
+            assert_is_close!(product[i][j], if i == j { 1.0 } else { 0.0 }, 1e-12);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn matrix_inverse_fail() {
+    let a: Matrix = Matrix::zeros(2, 3);
+    let _ = a.inverse();
+}
+
+#[test]
+fn matrix_cholesky_pass() {
+    let a: Matrix = Matrix::new(vec![Vector { elements: vec![4.0, 12.0, -16.0] }, Vector { elements: vec![12.0, 37.0, -43.0] }, Vector { elements: vec![-16.0, -43.0, 98.0] }]);
+
+    let lower = a.cholesky().unwrap();
+
+    assert_eq!(lower, Matrix::new(vec![Vector { elements: vec![2.0, 0.0, 0.0] }, Vector { elements: vec![6.0, 1.0, 0.0] }, Vector { elements: vec![-8.0, 5.0, 3.0] }]));
+
+    let x = Vector { elements: vec![1.0, -2.0, 0.5] };
+    let solution = a.solve_cholesky(&(&a * &x)).unwrap();
+    for i in 0..3 {
+        assert_is_close!(solution[i], x[i], 1e-10);
+    }
+}
+
+#[test]
+fn matrix_cholesky_fail() {
+    let indefinite: Matrix = Matrix::new(vec![Vector { elements: vec![1.0, 2.0] }, Vector { elements: vec![2.0, 1.0] }]);
+    let asymmetric: Matrix = Matrix::new(vec![Vector { elements: vec![2.0, 1.0] }, Vector { elements: vec![0.0, 2.0] }]);
+
+    assert!(indefinite.cholesky().is_err());
+    assert!(indefinite.solve_cholesky(&Vector { elements: vec![1.0, 1.0] }).is_err());
+    assert!(asymmetric.cholesky().is_err());
+    assert!(Matrix::<f64>::zeros(2, 2).cholesky().is_err());
+}
+
+#[test]
+fn matrix_from_elements_pass() {
+    let a = Matrix::from_elements(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    assert_eq!(a, Matrix::new(vec![Vector { elements: vec![1.0, 2.0, 3.0] }, Vector { elements: vec![4.0, 5.0, 6.0] }]));
+    assert_eq!(a[1], [4.0, 5.0, 6.0]);
+    assert_eq!(a.row(0), Vector { elements: vec![1.0, 2.0, 3.0] });
+    assert_eq!(a.iter_rows().map(|row| row.iter().sum::<f64>()).collect::<Vec<f64>>(), vec![6.0, 15.0]);
+}
+
+#[test]
+#[should_panic]
+fn matrix_from_elements_fail() {
+    Matrix::from_elements(2, 3, vec![1.0, 2.0]);
+}
+
+#[test]
+fn matrix_slice_rows_pass() {
+    let mut a = Matrix::new(vec![]);
+    for i in 0..4 {
+        a.push(Vector { elements: vec![i as f64, -(i as f64)] });
+    }
+
+    let b = a.slice_rows(1..3);
+
+    assert_eq!(a.shape(), (4, 2));
+    assert_eq!(b, Matrix::new(vec![Vector { elements: vec![1.0, -1.0] }, Vector { elements: vec![2.0, -2.0] }]));
+}
+
+#[test]
+#[should_panic]
+fn matrix_new_ragged_fail() {
+    Matrix::new(vec![Vector { elements: vec![1.0, 2.0] }, Vector { elements: vec![3.0] }]);
+}
+
+#[test]
+fn matrix_mul_blocked_pass() {
+    // Sizes that are not multiples of the tile size
+    let a = Matrix::from_elements(70, 130, (0..70 * 130).map(|x| ((x * 37) % 101) as f64 / 101.0 - 0.5).collect());
+    let b = Matrix::from_elements(130, 65, (0..130 * 65).map(|x| ((x * 53) % 97) as f64 / 97.0).collect());
+
+    let c = &a * &b;
+
+    assert_eq!(c.shape(), (70, 65));
+    for i in 0..70 {
+        for j in 0..65 {
+            let expected: f64 = (0..130).map(|k| a[i][k] * b[k][j]).sum();
+            assert_is_close!(c[i][j], expected, 1e-10);
+        }
+    }
+}
+
+#[test]
+fn matrix_f32_pass() {
+    let a: Matrix<f32> = Matrix::new(vec![Vector::new(vec![4.0, 1.0]), Vector::new(vec![1.0, 3.0])]);
+    let b: Vector<f32> = Vector::new(vec![1.0, 2.0]);
+
+    let x: Vector<f32> = a.solve(&b).unwrap();
+    let y: Vector<f32> = a.solve_cholesky(&b).unwrap();
+    let product: Vector<f32> = &a * &x;
+
+    for i in 0..2 {
+        assert!((product[i] - b[i]).abs() < 1e-5);
+        assert!((x[i] - y[i]).abs() < 1e-5);
+    }
+    assert_eq!((&a * &Matrix::identity(2)), a);
+    assert_eq!(a.cast::<f64>().cast::<f32>(), a);
+}
+
+#[test]
+fn matrix_reductions_pass() {
+    let a: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 5.0, 3.0]), Vector::new(vec![4.0, 2.0, 6.0])]);
+
+    assert_eq!(a.sum(Axis::Rows), Vector::new(vec![9.0, 12.0]));
+    assert_eq!(a.sum(Axis::Cols), Vector::new(vec![5.0, 7.0, 9.0]));
+    assert_eq!(a.mean(Axis::Rows), Vector::new(vec![3.0, 4.0]));
+    assert_eq!(a.mean(Axis::Cols), Vector::new(vec![2.5, 3.5, 4.5]));
+    assert_eq!(a.std(Axis::Cols), Vector::new(vec![1.5, 1.5, 1.5]));
+    assert_eq!(a.min(Axis::Rows), Vector::new(vec![1.0, 2.0]));
+    assert_eq!(a.max(Axis::Cols), Vector::new(vec![4.0, 5.0, 6.0]));
+    assert_eq!(a.argmin(Axis::Cols), vec![0, 1, 0]);
+    assert_eq!(a.argmax(Axis::Rows), vec![1, 2]);
+    assert_eq!(a.norm(Axis::Rows, 1.0), Vector::new(vec![9.0, 12.0]));
+    assert_eq!(a.norm(Axis::Cols, f64::INFINITY), Vector::new(vec![4.0, 5.0, 6.0]));
+}
+
+#[test]
+fn matrix_broadcast_row_pass() {
+    let a: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 2.0, 3.0]), Vector::new(vec![4.0, 5.0, 6.0])]);
+    let v: Vector = Vector::new(vec![1.0, 2.0, 4.0]);
+
+    assert_eq!(a.add_row_vector(&v), Matrix::new(vec![Vector::new(vec![2.0, 4.0, 7.0]), Vector::new(vec![5.0, 7.0, 10.0])]));
+    assert_eq!(a.sub_row_vector(&v), Matrix::new(vec![Vector::new(vec![0.0, 0.0, -1.0]), Vector::new(vec![3.0, 3.0, 2.0])]));
+    assert_eq!(a.mul_row_vector(&v), Matrix::new(vec![Vector::new(vec![1.0, 4.0, 12.0]), Vector::new(vec![4.0, 10.0, 24.0])]));
+    assert_eq!(a.div_row_vector(&v), Matrix::new(vec![Vector::new(vec![1.0, 1.0, 0.75]), Vector::new(vec![4.0, 2.5, 1.5])]));
+}
+
+#[test]
+fn matrix_broadcast_col_pass() {
+    let a: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 2.0, 3.0]), Vector::new(vec![4.0, 5.0, 6.0])]);
+    let v: Vector = Vector::new(vec![2.0, -1.0]);
+
+    assert_eq!(a.add_col_vector(&v), Matrix::new(vec![Vector::new(vec![3.0, 4.0, 5.0]), Vector::new(vec![3.0, 4.0, 5.0])]));
+    assert_eq!(a.sub_col_vector(&v), Matrix::new(vec![Vector::new(vec![-1.0, 0.0, 1.0]), Vector::new(vec![5.0, 6.0, 7.0])]));
+    assert_eq!(a.mul_col_vector(&v), Matrix::new(vec![Vector::new(vec![2.0, 4.0, 6.0]), Vector::new(vec![-4.0, -5.0, -6.0])]));
+    assert_eq!(a.div_col_vector(&v), Matrix::new(vec![Vector::new(vec![0.5, 1.0, 1.5]), Vector::new(vec![-4.0, -5.0, -6.0])]));
+}
+
+#[test]
+fn matrix_standardize_columns_pass() {
+    let a: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 10.0]), Vector::new(vec![3.0, 30.0])]);
+
+    let standardized: Matrix = a.sub_row_vector(&a.mean(Axis::Cols)).div_row_vector(&a.std(Axis::Cols));
+
+    assert_eq!(standardized, Matrix::new(vec![Vector::new(vec![-1.0, -1.0]), Vector::new(vec![1.0, 1.0])]));
+}
+
+#[test]
+#[should_panic]
+fn matrix_broadcast_row_fail() {
+    let a: Matrix = Matrix::zeros(2, 3);
+    a.add_row_vector(&Vector::new(vec![1.0, 2.0]));
+}
+
+#[test]
+#[should_panic]
+fn matrix_broadcast_col_fail() {
+    let a: Matrix = Matrix::zeros(2, 3);
+    a.mul_col_vector(&Vector::new(vec![1.0, 2.0, 3.0]));
+}
+
+#[test]
+fn matrix_vstack_pass() {
+    let a: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 2.0])]);
+    let b: Matrix = Matrix::new(vec![Vector::new(vec![3.0, 4.0]), Vector::new(vec![5.0, 6.0])]);
+
+    let stacked: Matrix = Matrix::vstack(&[a, b]);
+
+    assert_eq!(stacked, Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0]), Vector::new(vec![5.0, 6.0])]));
+    assert_eq!(Matrix::<f64>::vstack(&[]).shape(), (0, 0));
+}
+
+#[test]
+fn matrix_hstack_pass() {
+    let a: Matrix = Matrix::new(vec![Vector::new(vec![1.0]), Vector::new(vec![2.0])]);
+    let b: Matrix = Matrix::new(vec![Vector::new(vec![3.0, 4.0]), Vector::new(vec![5.0, 6.0])]);
+
+    let stacked: Matrix = Matrix::hstack(&[a, b]);
+
+    assert_eq!(stacked, Matrix::new(vec![Vector::new(vec![1.0, 3.0, 4.0]), Vector::new(vec![2.0, 5.0, 6.0])]));
+}
+
+#[test]
+#[should_panic]
+fn matrix_vstack_fail() {
+    Matrix::vstack(&[Matrix::<f64>::zeros(1, 2), Matrix::zeros(1, 3)]);
+}
+
+#[test]
+#[should_panic]
+fn matrix_hstack_fail() {
+    Matrix::hstack(&[Matrix::<f64>::zeros(1, 2), Matrix::zeros(2, 2)]);
+}
+
+#[test]
+fn matrix_sum_stable_pass() {
+    let a: Matrix = Matrix::new(vec![Vector::new(vec![1e100, 1.0, -1e100]), Vector::new(vec![1.0, 2.0, 3.0])]);
+
+    assert_eq!(a.sum_stable(Axis::Rows), Vector::new(vec![1.0, 6.0]));
+    assert_eq!(a.sum_stable(Axis::Cols), a.sum(Axis::Cols));
+}
+
+#[test]
+fn matrix_assign_ops_pass() {
+    let mut a: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 2.0]), Vector::new(vec![3.0, 4.0])]);
+    let b: Matrix = Matrix::ones(2, 2);
+
+    a += &b;
+    assert_eq!(a.elements, vec![2.0, 3.0, 4.0, 5.0]);
+    a -= &b;
+    a *= 3.0;
+    assert_eq!(a.elements, vec![3.0, 6.0, 9.0, 12.0]);
+    a.axpy(0.5, &b);
+    assert_eq!(a.elements, vec![3.5, 6.5, 9.5, 12.5]);
+}
+
+#[test]
+#[should_panic]
+fn matrix_add_assign_fail() {
+    let mut a: Matrix = Matrix::zeros(2, 2);
+    a += &Matrix::zeros(2, 3);
+}
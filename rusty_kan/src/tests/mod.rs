@@ -1,7 +1,55 @@
 mod vector_tests;
 mod matrix_tests;
+// The tests of the original data structures are kept as they were written
+#[allow(clippy::clone_on_copy)]
 mod spline_tests;
+#[allow(clippy::clone_on_copy, clippy::let_unit_value, clippy::print_with_newline, clippy::needless_range_loop)]
 mod edge_tests;
+#[allow(clippy::useless_vec)]
 mod node_tests;
+#[allow(clippy::useless_vec)]
 mod layer_tests;
-mod kan_tests;
\ No newline at end of file
+mod kan_tests;
+mod loss_tests;
+mod callback_tests;
+mod trainer_tests;
+mod optimizer_tests;
+mod scheduler_tests;
+mod init_tests;
+mod builder_tests;
+mod rbf_tests;
+mod activation_tests;
+mod symbolic_tests;
+mod compiled_tests;
+mod sparse_tests;
+mod onnx_tests;
+mod pykan_tests;
+mod codegen_tests;
+mod dataset_tests;
+mod toy_tests;
+mod scaler_tests;
+mod attribution_tests;
+mod norm_tests;
+mod skip_tests;
+mod dropout_tests;
+mod expr_tests;
+mod tie_tests;
+mod ensemble_tests;
+mod quantized_tests;
+mod single_tests;
+mod bench_utils_tests;
+mod dot_tests;
+mod plot_tests;
+mod topology_tests;
+mod error_tests;
+mod config_tests;
+#[cfg(feature = "ffi")]
+mod ffi_tests;
+#[cfg(feature = "wasm")]
+mod wasm_tests;
+#[cfg(feature = "blas")]
+mod blas_tests;
+#[cfg(feature = "instrument")]
+mod instrument_tests;
+#[cfg(feature = "parallel")]
+mod parallel_tests;
@@ -11,7 +11,7 @@ macro_rules! assert_is_close {
     }};
 }
 
-use crate::data_structures::{node::Node, vector::Vector, edge::Edge, spline::BSpline};
+use crate::data_structures::{node::{Node, Aggregation}, vector::Vector, edge::Edge, spline::BSpline};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -85,7 +85,7 @@ fn node_forward_pass() {
     let mut node: Node = Node::new(incoming_edges, outgoing_edges, 0);
 
     let input: Vector = Vector::new(vec![0.0, 1.0]);
-    let value: f64 = node.forward(&input);
+    let value: f64 = node.forward(&input).unwrap();
 
     // Expected value
     let expected_value: f64 = incoming_edge_1.forward(0.0) + incoming_edge_2.forward(1.0);
@@ -93,6 +93,44 @@ fn node_forward_pass() {
     assert_is_close!(value, expected_value, 1e-3);
 }
 
+#[test]
+fn node_forward_product_pass() {
+    let incoming_edge_1: Edge = Edge::new(0, 0, BSpline::new(Vector::new(vec![1.0, 2.0, 3.0]), 2), 0);
+    let incoming_edge_2: Edge = Edge::new(1, 0, BSpline::new(Vector::new(vec![4.0, 5.0, 6.0]), 2), 0);
+
+    let incoming_edges: Vec<Rc<RefCell<Edge>>> = [incoming_edge_1.clone(), incoming_edge_2.clone()].iter().map(|edge| Rc::new(RefCell::new(edge.clone()))).collect();
+
+    let mut node: Node = Node::new(incoming_edges, vec![], 0);
+    node.aggregation = Aggregation::Product;
+
+    let value: f64 = node.forward(&Vector::new(vec![0.25, 0.75])).unwrap();
+
+    assert_is_close!(value, incoming_edge_1.value(0.25) * incoming_edge_2.value(0.75), 1e-12);
+}
+
+#[test]
+fn node_edge_gradients_pass() {
+    let edges: Vec<Edge> = vec![
+        Edge::new(0, 0, BSpline::new(Vector::new(vec![1.0, 2.0, 3.0]), 2), 0),
+        Edge::new(1, 0, BSpline::new(Vector::new(vec![4.0, 5.0, 6.0]), 2), 0),
+        Edge::new(2, 0, BSpline::new(Vector::new(vec![-1.0, 0.5, 2.0]), 2), 0),
+    ];
+    let incoming_edges: Vec<Rc<RefCell<Edge>>> = edges.iter().map(|edge| Rc::new(RefCell::new(edge.clone()))).collect();
+    let mut node: Node = Node::new(incoming_edges, vec![], 0);
+    let input: Vector = Vector::new(vec![0.2, 0.5, 0.9]);
+
+    // A sum node passes the upstream gradient to every edge
+    assert_eq!(node.edge_gradients(&input, 0.5), Vector::new(vec![0.5; 3]));
+
+    // A product node scales it by the product of the values of the other edges
+    node.aggregation = Aggregation::Product;
+    let gradients: Vector = node.edge_gradients(&input, 0.5);
+    let values: Vec<f64> = edges.iter().enumerate().map(|(i, edge)| edge.value(input[i])).collect();
+    assert_is_close!(gradients[0], 0.5 * values[1] * values[2], 1e-12);
+    assert_is_close!(gradients[1], 0.5 * values[0] * values[2], 1e-12);
+    assert_is_close!(gradients[2], 0.5 * values[0] * values[1], 1e-12);
+}
+
 #[test]
 #[should_panic]
 fn node_forward_fail() {
@@ -107,7 +145,7 @@ fn node_forward_fail() {
     let mut node: Node = Node::new(incoming_edges, outgoing_edges, 0);
 
     let input: Vector = Vector::new(vec![0.0, 1.0, 2.0]);
-    let _ = node.forward(&input);
+    let _ = node.forward(&input).unwrap();
 }
 
 #[test]
@@ -126,13 +164,13 @@ fn node_backward_pass() {
     let inputs: Vector = Vector::new(vec![0.5, 1.0]);
 
     node.backward(inputs.clone(), upstream_gradient).unwrap();
-    assert_is_close!(node.incoming[0].borrow().gradient[0], incoming_edge_1.spline.basis(0, incoming_edge_1.spline.degree, inputs[0]) * upstream_gradient, 1e-3);
-    assert_is_close!(node.incoming[0].borrow().gradient[1], incoming_edge_1.spline.basis(1, incoming_edge_1.spline.degree, inputs[0]) * upstream_gradient, 1e-3);
-    assert_is_close!(node.incoming[0].borrow().gradient[2], incoming_edge_1.spline.basis(2, incoming_edge_1.spline.degree, inputs[0]) * upstream_gradient, 1e-3);
+    assert_is_close!(node.incoming[0].borrow().gradient[0], incoming_edge_1.spline_mut().basis(0, 2, inputs[0]) * upstream_gradient, 1e-3);
+    assert_is_close!(node.incoming[0].borrow().gradient[1], incoming_edge_1.spline_mut().basis(1, 2, inputs[0]) * upstream_gradient, 1e-3);
+    assert_is_close!(node.incoming[0].borrow().gradient[2], incoming_edge_1.spline_mut().basis(2, 2, inputs[0]) * upstream_gradient, 1e-3);
 
-    assert_is_close!(node.incoming[1].borrow().gradient[0], incoming_edge_2.spline.basis(0, incoming_edge_2.spline.degree, inputs[1]) * upstream_gradient, 1e-3);
-    assert_is_close!(node.incoming[1].borrow().gradient[1], incoming_edge_2.spline.basis(1, incoming_edge_2.spline.degree, inputs[1]) * upstream_gradient, 1e-3);
-    assert_is_close!(node.incoming[1].borrow().gradient[2], incoming_edge_2.spline.basis(2, incoming_edge_2.spline.degree, inputs[1]) * upstream_gradient, 1e-3);
+    assert_is_close!(node.incoming[1].borrow().gradient[0], incoming_edge_2.spline_mut().basis(0, 2, inputs[1]) * upstream_gradient, 1e-3);
+    assert_is_close!(node.incoming[1].borrow().gradient[1], incoming_edge_2.spline_mut().basis(1, 2, inputs[1]) * upstream_gradient, 1e-3);
+    assert_is_close!(node.incoming[1].borrow().gradient[2], incoming_edge_2.spline_mut().basis(2, 2, inputs[1]) * upstream_gradient, 1e-3);
 }
 
 #[test]
@@ -172,14 +210,14 @@ fn node_weight_update_pass() {
     node.update_weights(learning_rate).unwrap();
 
     // Expected values
-    let expected_gradient_1: Vector = Vector::from(vec![incoming_edge_1.spline.basis(0, incoming_edge_1.spline.degree, inputs[0]), incoming_edge_1.spline.basis(1, incoming_edge_1.spline.degree, inputs[0]), incoming_edge_1.spline.basis(2, incoming_edge_1.spline.degree, inputs[0])]) * upstream_gradient;
-    let expected_gradient_2: Vector = Vector::from(vec![incoming_edge_2.spline.basis(0, incoming_edge_2.spline.degree, inputs[1]), incoming_edge_2.spline.basis(1, incoming_edge_2.spline.degree, inputs[1]), incoming_edge_2.spline.basis(2, incoming_edge_2.spline.degree, inputs[1])]) * upstream_gradient;
+    let expected_gradient_1: Vector = Vector::from(vec![incoming_edge_1.spline_mut().basis(0, 2, inputs[0]), incoming_edge_1.spline_mut().basis(1, 2, inputs[0]), incoming_edge_1.spline_mut().basis(2, 2, inputs[0])]) * upstream_gradient;
+    let expected_gradient_2: Vector = Vector::from(vec![incoming_edge_2.spline_mut().basis(0, 2, inputs[1]), incoming_edge_2.spline_mut().basis(1, 2, inputs[1]), incoming_edge_2.spline_mut().basis(2, 2, inputs[1])]) * upstream_gradient;
 
-    let result_control_points_1: Vector = incoming_edge_1.spline.control_points.clone() - expected_gradient_1 * learning_rate;
-    let result_control_points_2: Vector = incoming_edge_2.spline.control_points.clone() - expected_gradient_2 * learning_rate;
+    let result_control_points_1: Vector = incoming_edge_1.spline().control_points.clone() - expected_gradient_1 * learning_rate;
+    let result_control_points_2: Vector = incoming_edge_2.spline().control_points.clone() - expected_gradient_2 * learning_rate;
 
-    assert_eq!(node.incoming[0].borrow().spline.control_points, result_control_points_1);
-    assert_eq!(node.incoming[1].borrow().spline.control_points, result_control_points_2);
+    assert_eq!(node.incoming[0].borrow().spline().control_points, result_control_points_1);
+    assert_eq!(node.incoming[1].borrow().spline().control_points, result_control_points_2);
 }
 
 #[test]
@@ -195,7 +233,7 @@ fn node_train_pass() {
     let mut node: Node = Node::new(incoming_edges, outgoing_edges, 0);
 
     let inputs: Vector = Vector::new(vec![0.0, 1.0]);
-    let forward_result: f64 = node.forward(&inputs);
+    let forward_result: f64 = node.forward(&inputs).unwrap();
     let actual_value: f64 = 0.5;
 
     let mse_gradient: f64 = forward_result - actual_value;
@@ -207,8 +245,8 @@ fn node_train_pass() {
     node.backward(inputs.clone(), mse_gradient).unwrap();
 
     // Expected gradients
-    let expected_gradient_1: Vector = Vector::from(vec![incoming_edge_1.spline.basis(0, incoming_edge_1.spline.degree, inputs[0]), incoming_edge_1.spline.basis(1, incoming_edge_1.spline.degree, inputs[0]), incoming_edge_1.spline.basis(2, incoming_edge_1.spline.degree, inputs[0])]) * mse_gradient;
-    let expected_gradient_2: Vector = Vector::from(vec![incoming_edge_2.spline.basis(0, incoming_edge_2.spline.degree, inputs[1]), incoming_edge_2.spline.basis(1, incoming_edge_2.spline.degree, inputs[1]), incoming_edge_2.spline.basis(2, incoming_edge_2.spline.degree, inputs[1])]) * mse_gradient;
+    let expected_gradient_1: Vector = Vector::from(vec![incoming_edge_1.spline_mut().basis(0, 2, inputs[0]), incoming_edge_1.spline_mut().basis(1, 2, inputs[0]), incoming_edge_1.spline_mut().basis(2, 2, inputs[0])]) * mse_gradient;
+    let expected_gradient_2: Vector = Vector::from(vec![incoming_edge_2.spline_mut().basis(0, 2, inputs[1]), incoming_edge_2.spline_mut().basis(1, 2, inputs[1]), incoming_edge_2.spline_mut().basis(2, 2, inputs[1])]) * mse_gradient;
     
     assert_is_close!(node.incoming[0].borrow().gradient[0], expected_gradient_1[0], 1e-3);
     assert_is_close!(node.incoming[0].borrow().gradient[1], expected_gradient_1[1], 1e-3);
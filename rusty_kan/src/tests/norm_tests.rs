@@ -0,0 +1,213 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::builder::KANBuilder;
+use crate::codegen;
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::Node, layer::Layer, norm::{Norm, NormKind}};
+use crate::kan::{KAN, grad_check};
+use crate::loss::Loss;
+use crate::onnx::OnnxModel;
+use crate::trainer::Trainer;
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+fn trained_norm(kind: NormKind) -> Norm {
+    let mut norm: Norm = Norm::new(kind, 3);
+    norm.scale = Vector::new(vec![1.5, -0.5, 2.0]);
+    norm.shift = Vector::new(vec![0.1, 0.2, -0.3]);
+    norm.running_mean = Vector::new(vec![0.5, -1.0, 0.0]);
+    norm.running_var = Vector::new(vec![4.0, 0.25, 1.0]);
+    norm
+}
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.1, 0.8]), Vector::new(vec![0.4, 0.3]), Vector::new(vec![0.9, 0.5]), Vector::new(vec![0.6, 0.2])])
+}
+
+#[test]
+fn norm_forward_pass() {
+    let x: Vector = Vector::new(vec![1.0, 2.0, 6.0]);
+
+    // Layer normalization standardizes over the nodes of every sample
+    let layer: Vector = Norm::new(NormKind::Layer, 3).forward(&x);
+    assert_is_close!(layer.mean(), 0.0, 1e-12);
+    assert_is_close!(layer.std(), 1.0, 1e-5);
+
+    // Batch normalization uses the running statistics of every node
+    let batch: Vector = trained_norm(NormKind::Batch).forward(&x);
+    assert_is_close!(batch[0], 1.5 * 0.5 / (4.0 + 1e-5_f64).sqrt() + 0.1, 1e-12);
+    assert_is_close!(batch[1], -0.5 * 3.0 / (0.25 + 1e-5_f64).sqrt() + 0.2, 1e-12);
+}
+
+#[test]
+#[should_panic]
+fn norm_forward_fail() {
+    Norm::new(NormKind::Layer, 3).forward(&Vector::new(vec![1.0, 2.0]));
+}
+
+#[test]
+#[should_panic]
+fn norm_new_fail() {
+    Norm::new(NormKind::Batch, 0);
+}
+
+#[test]
+fn norm_backward_pass() {
+    let x: Vector = Vector::new(vec![0.3, -1.2, 2.5]);
+    let upstream: Vector = Vector::new(vec![0.7, -0.4, 1.1]);
+    let epsilon: f64 = 1e-6;
+    for kind in [NormKind::Batch, NormKind::Layer] {
+        let mut norm: Norm = trained_norm(kind);
+        let loss = |norm: &Norm, x: &Vector| -> f64 { norm.forward(x).dot(&upstream) };
+
+        let gradient: Vector = norm.backward(&x, &upstream);
+
+        for i in 0..3 {
+            let (mut plus, mut minus): (Vector, Vector) = (x.clone(), x.clone());
+            plus[i] += epsilon;
+            minus[i] -= epsilon;
+            assert_is_close!(gradient[i], (loss(&norm, &plus) - loss(&norm, &minus)) / (2.0 * epsilon), 1e-6);
+
+            let mut shifted: Norm = norm.clone();
+            shifted.scale[i] += epsilon;
+            assert_is_close!(norm.scale_gradient[i], (loss(&shifted, &x) - loss(&norm, &x)) / epsilon, 1e-5);
+        }
+        assert_eq!(norm.shift_gradient, upstream);
+        assert_eq!(norm.input_gradient(&x, &upstream), gradient);
+    }
+}
+
+#[test]
+fn norm_update_statistics_pass() {
+    let values: Matrix = Matrix::new(vec![Vector::new(vec![1.0, 0.0, 2.0]), Vector::new(vec![3.0, 0.0, 2.0])]);
+
+    let mut batch: Norm = Norm::new(NormKind::Batch, 3);
+    batch.update_statistics(&values);
+    assert_eq!(batch.running_mean, Vector::new(vec![0.2, 0.0, 0.2]));
+    assert_is_close!(batch.running_var[0], 0.9 + 0.1, 1e-12);
+    assert_is_close!(batch.running_var[1], 0.9, 1e-12);
+
+    let mut layer: Norm = Norm::new(NormKind::Layer, 3);
+    layer.update_statistics(&values);
+    assert_eq!(layer, Norm::new(NormKind::Layer, 3));
+}
+
+#[test]
+#[should_panic]
+fn norm_update_statistics_fail() {
+    Norm::new(NormKind::Batch, 3).update_statistics(&Matrix::zeros(2, 2));
+}
+
+#[test]
+fn kan_norm_grad_check_pass() {
+    // A batch normalization of the single output is an affine map, so the gradients of the edges are exact
+    let incoming_edges: Vec<Rc<RefCell<Edge>>> = (0..2).map(|i| Rc::new(RefCell::new(Edge::standard(i, 0, 0)))).collect();
+    let mut layer: Layer = Layer::new(vec![Rc::new(RefCell::new(Node::new(incoming_edges, vec![], 0)))]);
+    let mut norm: Norm = Norm::new(NormKind::Batch, 1);
+    norm.scale[0] = 2.0;
+    norm.running_mean[0] = 0.5;
+    norm.running_var[0] = 0.25;
+    layer.norm = Some(norm);
+    let kan: KAN = KAN::new(vec![Rc::new(RefCell::new(layer))]);
+
+    let errors: Vector = grad_check(&kan, &Vector::new(vec![0.3, 0.6]), &Vector::new(vec![2.0]), Loss::MeanSquaredError, 1e-5).unwrap();
+
+    for i in 0..errors.len() {
+        assert!(errors[i] < 1e-6);
+    }
+}
+
+#[test]
+fn kan_norm_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Layer).add_layer(1).seed(5).build();
+    let input: Vector = Vector::new(vec![0.2, 0.9]);
+    let expected: f64 = kan.forward(&input).unwrap();
+
+    // The normalized values of the hidden layer are passed to the output layer
+    let hidden: Vector = kan.layers[0].borrow().activations(kan.input_matrix(&input)).unwrap();
+    let normalized: Vector = kan.layers[0].borrow().normalize(hidden);
+    assert_eq!(kan.layers[1].borrow().activations(kan.layers[1].borrow().route(&normalized)).unwrap()[0], expected);
+
+    // The compiled model and a JSON round trip keep the normalization
+    assert_is_close!(kan.compile().forward(&input).unwrap()[0], expected, 1e-12);
+    let loaded: KAN = serde_json::from_str(&serde_json::to_string(&kan).unwrap()).unwrap();
+    assert_eq!(loaded.layers[0].borrow().norm, kan.layers[0].borrow().norm);
+    assert_eq!(loaded.forward(&input).unwrap(), expected);
+
+    // The input gradients pass through the normalization
+    let epsilon: f64 = 1e-6;
+    let gradients: Vector = kan.input_gradients(&input).unwrap();
+    for i in 0..2 {
+        let (mut plus, mut minus): (Vector, Vector) = (input.clone(), input.clone());
+        plus[i] += epsilon;
+        minus[i] -= epsilon;
+        let numerical: f64 = (kan.forward(&plus).unwrap() - kan.forward(&minus).unwrap()) / (2.0 * epsilon);
+        assert_is_close!(gradients[i], numerical, 1e-6);
+    }
+}
+
+#[test]
+fn kan_update_norm_statistics_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Batch).add_layer(1).seed(5).build();
+    let hidden: Matrix = Matrix::new((0..4).map(|i| kan.layers[0].borrow().activations(kan.input_matrix(&inputs().row(i))).unwrap()).collect());
+
+    kan.update_norm_statistics(&inputs()).unwrap();
+
+    let mut expected: Norm = Norm::new(NormKind::Batch, 3);
+    expected.update_statistics(&hidden);
+    assert_eq!(kan.layers[0].borrow().norm, Some(expected));
+}
+
+#[test]
+fn trainer_norm_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Batch).add_layer(1).seed(8).build();
+    let targets: Matrix = Matrix::new((0..4).map(|i| Vector::new(vec![inputs()[i][0] * inputs()[i][1]])).collect());
+    let mut trainer: Trainer = Trainer::new(kan, 0.1, Loss::MeanSquaredError, 8);
+
+    trainer.fit(&inputs(), &targets, 3).unwrap();
+
+    // The running statistics and the scale and shift are trained along with the edges
+    let norm: Norm = trainer.kan.layers[0].borrow().norm.clone().unwrap();
+    assert_ne!(norm.running_mean, Vector::zeros(3));
+    assert_ne!(norm.scale, Vector::new(vec![1.0; 3]));
+    assert_ne!(norm.shift, Vector::zeros(3));
+
+    let path: PathBuf = std::env::temp_dir().join(format!("rusty_kan_norm_{}.json", std::process::id()));
+    trainer.save_checkpoint(&path).unwrap();
+    let loaded: Trainer = Trainer::load_checkpoint(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.kan.layers[0].borrow().norm, Some(norm));
+    assert_eq!(loaded.kan.layers[1].borrow().norm, None);
+}
+
+#[test]
+#[should_panic]
+fn builder_norm_fail() {
+    KANBuilder::new(2).add_layer(3).norm(NormKind::Layer).build();
+}
+
+#[test]
+fn codegen_norm_fail() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Layer).add_layer(1).seed(5).build();
+
+    assert!(codegen::rust(&kan).is_err());
+}
+
+#[test]
+#[should_panic]
+fn onnx_norm_fail() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Batch).add_layer(1).seed(5).build();
+    OnnxModel::from_kan(&kan, 16);
+}
@@ -0,0 +1,104 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, edge::Edge, node::Node, layer::Layer};
+use crate::builder::KANBuilder;
+use crate::onnx::{OnnxLayer, OnnxModel};
+use crate::kan::KAN;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+/// A KAN with one layer of two nodes, each with an incoming edge from both of the two inputs.
+fn single_layer_kan() -> KAN {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(3);
+    let nodes: Vec<Rc<RefCell<Node>>> = (0..2).map(|j| {
+        let incoming: Vec<Rc<RefCell<Edge>>> = (0..2).map(|i| Rc::new(RefCell::new(Edge::standard_with_rng(i, j, 0, &mut rng)))).collect();
+        Rc::new(RefCell::new(Node::new(incoming, vec![], 0)))
+    }).collect();
+    KAN::new(vec![Rc::new(RefCell::new(Layer::new(nodes)))])
+}
+
+#[test]
+fn onnx_from_kan_pass() {
+    let kan: KAN = single_layer_kan();
+    let model: OnnxModel = OnnxModel::from_kan(&kan, 256);
+    let input: Vector = Vector::new(vec![0.2, 0.7]);
+
+    let expected: Vector = kan.compile().forward(&input).unwrap();
+    let output: Vector = model.forward(&input);
+
+    assert_eq!(model.inputs, 2);
+    assert_eq!(model.layers.len(), 1);
+    assert_eq!(output.len(), 2);
+    for j in 0..2 {
+        assert_is_close!(output[j], expected[j], 1e-3);
+    }
+}
+
+#[test]
+fn onnx_from_kan_clamp_pass() {
+    let kan: KAN = single_layer_kan();
+    let model: OnnxModel = OnnxModel::from_kan(&kan, 16);
+
+    // Inputs beyond the domain of the edges give the value at the end of the domain
+    let below: Vector = model.forward(&Vector::new(vec![-5.0, 3.0]));
+    let boundary: Vector = model.forward(&Vector::new(vec![0.0, 1.0]));
+
+    for j in 0..2 {
+        assert_is_close!(below[j], boundary[j], 1e-12);
+    }
+}
+
+#[test]
+#[should_panic]
+fn onnx_from_edges_fail() {
+    let kan: KAN = single_layer_kan();
+    OnnxLayer::from_edges(&kan.compile().layers[0], 2, 0);
+}
+
+#[test]
+#[should_panic]
+fn onnx_from_kan_product_fail() {
+    let kan: KAN = KANBuilder::new(2).add_mult_layer(0, 1).seed(2).build();
+    OnnxModel::from_kan(&kan, 16);
+}
+
+#[test]
+fn onnx_to_bytes_pass() {
+    let model: OnnxModel = OnnxModel::from_kan(&KAN::standard_seeded(2, 3, 5), 8);
+
+    let bytes: Vec<u8> = model.to_bytes();
+    let contains = |pattern: &[u8]| bytes.windows(pattern.len()).any(|window| window == pattern);
+
+    // The first field is the IR version 7
+    assert_eq!(&bytes[..2], &[0x08, 0x07]);
+    assert!(contains(b"MatMul"));
+    assert!(contains(b"Relu"));
+    assert!(contains(b"output"));
+    assert!(contains(b"weights_1"));
+}
+
+#[test]
+fn kan_export_onnx_pass() {
+    let path: PathBuf = std::env::temp_dir().join(format!("rusty_kan_export_{}.onnx", std::process::id()));
+    let kan: KAN = KAN::standard_seeded(2, 3, 5);
+
+    kan.export_onnx_with_segments(&path, 8).unwrap();
+    let bytes: Vec<u8> = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(bytes, OnnxModel::from_kan(&kan, 8).to_bytes());
+}
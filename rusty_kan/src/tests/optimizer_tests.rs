@@ -0,0 +1,111 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, spline::BSpline, edge::Edge};
+use crate::optimizer::{Optimizer, OptimizerState, Lbfgs, LbfgsState};
+use crate::error::KanError;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+fn edges() -> Vec<Rc<RefCell<Edge>>> {
+    vec![Rc::new(RefCell::new(Edge::new(0, 0, BSpline::new(Vector::new(vec![1.0, 2.0, 3.0]), 2), 0)))]
+}
+
+#[test]
+fn optimizer_sgd_pass() {
+    let edges: Vec<Rc<RefCell<Edge>>> = edges();
+    let mut state: OptimizerState = OptimizerState::default();
+    let gradients: Vec<Vector> = vec![Vector::new(vec![1.0, -2.0, 0.5, 0.0, 0.0])];
+
+    Optimizer::Sgd.step(&mut state, &edges, &gradients, 0.1).unwrap();
+
+    assert_eq!(state.step, 1);
+    let control_points: Vector = edges[0].borrow().spline().control_points.clone();
+    assert_is_close!(control_points[0], 0.9, 1e-12);
+    assert_is_close!(control_points[1], 2.2, 1e-12);
+    assert_is_close!(control_points[2], 2.95, 1e-12);
+    assert_eq!(edges[0].borrow().gradient, Vector::zeros(5));
+}
+
+#[test]
+fn optimizer_momentum_pass() {
+    let edges: Vec<Rc<RefCell<Edge>>> = edges();
+    let mut state: OptimizerState = OptimizerState::default();
+    let gradients: Vec<Vector> = vec![Vector::new(vec![1.0, 0.0, 0.0, 0.0, 0.0])];
+    let optimizer: Optimizer = Optimizer::Momentum { beta: 0.5 };
+
+    optimizer.step(&mut state, &edges, &gradients, 0.1).unwrap();
+    optimizer.step(&mut state, &edges, &gradients, 0.1).unwrap();
+
+    // Velocities 1.0 and 1.5
+    assert_is_close!(edges[0].borrow().spline().control_points[0], 1.0 - 0.1 * (1.0 + 1.5), 1e-12);
+    assert_is_close!(state.first_moments[0][0], 1.5, 1e-12);
+}
+
+#[test]
+fn optimizer_adam_pass() {
+    let edges: Vec<Rc<RefCell<Edge>>> = edges();
+    let mut state: OptimizerState = OptimizerState::default();
+    let gradients: Vec<Vector> = vec![Vector::new(vec![4.0, -0.01, 0.0, 0.0, 0.0])];
+
+    Optimizer::adam().step(&mut state, &edges, &gradients, 0.1).unwrap();
+
+    // The first bias-corrected Adam step has the size of the learning rate in the direction of the gradient sign.
+    let control_points: Vector = edges[0].borrow().spline().control_points.clone();
+    assert_is_close!(control_points[0], 0.9, 1e-6);
+    assert_is_close!(control_points[1], 2.1, 1e-5);
+    assert_is_close!(control_points[2], 3.0, 1e-12);
+}
+
+#[test]
+fn optimizer_gradient_count_fail() {
+    let edges: Vec<Rc<RefCell<Edge>>> = edges();
+    let mut state: OptimizerState = OptimizerState::default();
+
+    assert!(Optimizer::Sgd.step(&mut state, &edges, &[], 0.1).is_err());
+}
+
+// The Rosenbrock function (1 - x)^2 + 100 (y - x^2)^2, with its minimum 0 at (1, 1) in a curved valley.
+fn rosenbrock(p: &Vector) -> Result<(f64, Vector), KanError> {
+    let (x, y) = (p[0], p[1]);
+    let loss: f64 = (1.0 - x).powi(2) + 100.0 * (y - x * x).powi(2);
+    Ok((loss, Vector::new(vec![-2.0 * (1.0 - x) - 400.0 * x * (y - x * x), 200.0 * (y - x * x)])))
+}
+
+#[test]
+fn optimizer_lbfgs_pass() {
+    let lbfgs: Lbfgs = Lbfgs::default();
+    let mut state: LbfgsState = LbfgsState::default();
+    let mut params: Vector = Vector::new(vec![-1.2, 1.0]);
+
+    let mut losses: Vec<f64> = vec![rosenbrock(&params).unwrap().0];
+    for _ in 0..100 {
+        losses.push(lbfgs.step(&mut state, &mut params, rosenbrock).unwrap());
+    }
+
+    // The line search never increases the loss, and the curvature pairs carry the iterates through the valley
+    assert!(losses.windows(2).all(|pair| pair[1] <= pair[0]));
+    assert_is_close!(params[0], 1.0, 1e-4);
+    assert_is_close!(params[1], 1.0, 1e-4);
+    assert!(state.steps.len() <= lbfgs.history_size);
+}
+
+#[test]
+fn optimizer_lbfgs_fail() {
+    let mut state: LbfgsState = LbfgsState::default();
+    let mut params: Vector = Vector::new(vec![0.5, 0.5]);
+
+    assert!(Lbfgs::default().step(&mut state, &mut params, |_| Err(KanError::DomainError("The objective failed."))).is_err());
+    assert!(Lbfgs::default().step(&mut state, &mut params, |_| Ok((1.0, Vector::zeros(3)))).is_err());
+    assert_eq!(params, Vector::new(vec![0.5, 0.5]));
+}
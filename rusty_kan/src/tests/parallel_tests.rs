@@ -0,0 +1,58 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::builder::KANBuilder;
+use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::kan::KAN;
+use crate::loss::{Loss, Reduction};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn inputs() -> Matrix {
+    Matrix::new((0..64).map(|i| Vector::new(vec![i as f64 / 64.0, 1.0 - i as f64 / 128.0])).collect())
+}
+
+fn targets() -> Matrix {
+    Matrix::new((0..64).map(|i| Vector::new(vec![(i as f64 / 32.0).sin()])).collect())
+}
+
+/// The losses of every sample, evaluated one after the other on the layers of the KAN.
+fn sequential_losses(kan: &KAN, loss: Loss) -> Vec<f64> {
+    (0..64).map(|i| loss.compute(&kan.forward_features(&inputs().row(i)).unwrap(), &targets().row(i)).unwrap()).collect()
+}
+
+#[test]
+fn parallel_loss_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(4).add_layer(1).seed(3).build();
+
+    let sequential: Vec<f64> = sequential_losses(&kan, Loss::MeanSquaredError);
+    let losses: Vector = kan.loss_reduced(inputs(), targets(), Loss::MeanSquaredError, Reduction::None).unwrap();
+    for (&parallel, &sequential) in losses.iter().zip(sequential.iter()) {
+        assert_is_close!(parallel, sequential, 1e-12);
+    }
+    let mean: f64 = sequential.iter().sum::<f64>() / 64.0;
+    assert_is_close!(kan.loss_with(inputs(), targets(), Loss::MeanSquaredError).unwrap(), mean, 1e-12);
+    assert_is_close!(kan.loss(inputs(), targets().get_col(0)).unwrap(), mean, 1e-12);
+}
+
+#[test]
+fn parallel_loss_dropout_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(4).dropout(0.5).add_layer(1).seed(3).build();
+
+    // The compiled copy keeps the masks of the training step
+    kan.sample_dropout(&mut ChaCha8Rng::seed_from_u64(2));
+    let losses: Vector = kan.loss_reduced(inputs(), targets(), Loss::MeanSquaredError, Reduction::None).unwrap();
+    for (&parallel, sequential) in losses.iter().zip(sequential_losses(&kan, Loss::MeanSquaredError)) {
+        assert_is_close!(parallel, sequential, 1e-12);
+    }
+}
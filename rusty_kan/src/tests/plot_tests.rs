@@ -0,0 +1,100 @@
+use crate::builder::{KANBuilder, Skip};
+use crate::data_structures::edge::Edge;
+use crate::kan::KAN;
+use crate::plot::{self, Svg};
+use crate::trainer::History;
+use std::path::PathBuf;
+
+// The coordinates of the points of the nth polyline of a chart.
+fn polyline(svg: &str, n: usize) -> Vec<(f64, f64)> {
+    let points: &str = svg.split("<polyline points=\"").nth(n + 1).unwrap().split('"').next().unwrap();
+    points.split(' ').map(|point| {
+        let (x, y) = point.split_once(',').unwrap();
+        (x.parse().unwrap(), y.parse().unwrap())
+    }).collect()
+}
+
+#[test]
+fn plot_edge_svg_pass() {
+    let mut edge: Edge = Edge::standard(1, 2, 0);
+    edge.score = Some(0.25);
+
+    let svg: String = plot::edge_svg(&edge, 50).source;
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(svg.contains("edge 1 \u{2192} 2 (score 0.250)"));
+    assert_eq!(svg.matches("<polyline").count(), 3);
+    let values: Vec<(f64, f64)> = polyline(&svg, 0);
+    assert_eq!(values.len(), 50);
+    // The lines span the plot area from left to right
+    assert_eq!(values[0].0, 40.0);
+    assert_eq!(values[49].0, 300.0);
+    assert!(values.iter().all(|&(_, y)| (40.0..=200.0).contains(&y)));
+}
+
+#[test]
+#[should_panic]
+fn plot_edge_svg_fail() {
+    plot::edge_svg(&Edge::standard(0, 0, 0), 1);
+}
+
+#[test]
+fn kan_plot_all_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(2).add_layer(2).add_layer(1).skip(Skip::Spline).seed(4).build();
+    let dir: PathBuf = std::env::temp_dir().join(format!("rusty_kan_plot_all_{}", std::process::id()));
+
+    let written: usize = kan.plot_all(&dir).unwrap();
+    let mut names: Vec<String> = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name().into_string().unwrap()).collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+    names.sort();
+
+    assert_eq!(written, kan.edges().len());
+    assert_eq!(names.len(), written);
+    assert!(names.contains(&String::from("layer0_1_0.svg")));
+    assert!(names.contains(&String::from("layer2_skip1_0.svg")));
+}
+
+#[test]
+fn kan_plot_all_fail() {
+    let file: PathBuf = std::env::temp_dir().join(format!("rusty_kan_plot_file_{}", std::process::id()));
+    std::fs::write(&file, "").unwrap();
+
+    let result = KAN::standard_seeded(2, 1, 0).plot_all(&file);
+    std::fs::remove_file(&file).unwrap();
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn plot_loss_curve_pass() {
+    let history: History = History { train_loss: vec![1.0, 0.1, 0.01], validation_loss: vec![2.0, 0.2, 0.02] };
+
+    let svg: Svg = plot::loss_curve(&history);
+
+    assert!(svg.source.contains("loss (log scale)"));
+    assert!(svg.source.contains(">train<") && svg.source.contains(">validation<"));
+    // A loss falling by a constant factor is a straight line on the logarithmic axis
+    let train: Vec<(f64, f64)> = polyline(&svg.source, 0);
+    assert_eq!(train.len(), 3);
+    assert!((train[1].1 - train[0].1 - (train[2].1 - train[1].1)).abs() < 0.02);
+    assert!(train[0].1 < train[2].1);
+    assert_eq!(svg.to_string(), svg.source);
+}
+
+#[test]
+fn plot_loss_curve_linear_pass() {
+    // Without a validation set there is one line, and a loss of 0 has no logarithm
+    let history: History = History { train_loss: vec![0.5, 0.0, f64::NAN], validation_loss: vec![] };
+
+    let svg: Svg = plot::loss_curve(&history);
+
+    assert!(!svg.source.contains("log scale"));
+    assert!(!svg.source.contains(">validation<"));
+    assert_eq!(polyline(&svg.source, 0).len(), 2);
+
+    let path: PathBuf = std::env::temp_dir().join(format!("rusty_kan_loss_curve_{}.svg", std::process::id()));
+    svg.save(&path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), svg.source);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,83 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::vector::Vector;
+use crate::pykan::{Tensor, parse_state_dict, from_state_dict};
+use crate::kan::KAN;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// The state dict of a pykan model of width [2, 1] with degree 1 and one grid interval on [-1, 1].
+const STATE: &str = r#"{
+    "act_fun.0.grid": [[-3.0, -1.0, 1.0, 3.0], [-3.0, -1.0, 1.0, 3.0]],
+    "act_fun.0.coef": [[[1.0, 3.0]], [[-2.0, 0.0]]],
+    "act_fun.0.scale_base": [[0.5], [0.0]],
+    "act_fun.0.scale_sp": [[1.0], [2.0]],
+    "act_fun.0.mask": [[1.0], [1.0]],
+    "node_scale.0": [2.0],
+    "node_bias.0": [0.0],
+    "subnode_scale.0": [1.0],
+    "subnode_bias.0": [0.0]
+}"#;
+
+#[test]
+fn pykan_parse_state_dict_pass() {
+    let state: HashMap<String, Tensor> = parse_state_dict(STATE).unwrap();
+
+    assert_eq!(state["act_fun.0.coef"].shape, vec![2, 1, 2]);
+    assert_eq!(state["act_fun.0.coef"].values, vec![1.0, 3.0, -2.0, 0.0]);
+    assert_eq!(state["node_scale.0"].shape, vec![1]);
+}
+
+#[test]
+fn pykan_parse_state_dict_fail() {
+    assert!(parse_state_dict(r#"{"act_fun.0.grid": [[1.0, 2.0], [3.0]]}"#).is_err());
+    assert!(parse_state_dict("[1.0]").is_err());
+}
+
+#[test]
+fn pykan_from_state_dict_pass() {
+    let kan: KAN = from_state_dict(&parse_state_dict(STATE).unwrap()).unwrap();
+
+    let output: Vector = kan.compile().forward(&Vector::new(vec![0.5, 0.0])).unwrap();
+
+    // Edge 0: 0.5 * silu(0.5) + (0.25 * 1 + 0.75 * 3), edge 1: 2 * (0.5 * -2 + 0.5 * 0), scaled by the node scale 2
+    let silu: f64 = 0.5 / (1.0 + (-0.5f64).exp());
+    assert_eq!(kan.layers.len(), 1);
+    assert_is_close!(output[0], 2.0 * (0.5 * silu + 2.5 - 2.0), 1e-12);
+}
+
+#[test]
+fn pykan_from_state_dict_fail() {
+    let mut state: HashMap<String, Tensor> = parse_state_dict(STATE).unwrap();
+    state.insert("node_bias.0".to_string(), Tensor { shape: vec![1], values: vec![0.1] });
+    assert!(from_state_dict(&state).is_err());
+
+    let mut state: HashMap<String, Tensor> = parse_state_dict(STATE).unwrap();
+    state.remove("act_fun.0.scale_sp");
+    assert!(from_state_dict(&state).is_err());
+
+    assert!(from_state_dict(&HashMap::new()).is_err());
+}
+
+#[test]
+fn kan_load_pykan_json_pass() {
+    let path: PathBuf = std::env::temp_dir().join(format!("rusty_kan_pykan_{}.json", std::process::id()));
+    std::fs::write(&path, STATE).unwrap();
+
+    let kan: KAN = KAN::load_pykan_json(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(kan.edges().len(), 2);
+    assert!(KAN::load_pykan_json(&path).is_err());
+}
@@ -0,0 +1,100 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::builder::KANBuilder;
+use crate::compiled::CompiledKAN;
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge};
+use crate::error::KanError;
+use crate::kan::KAN;
+use crate::quantized::{QuantizedKAN, QuantizedEdge, QuantizationError, Precision, Table};
+use crate::scaler::Scaler;
+
+fn grid() -> Matrix {
+    Matrix::new((0..25).map(|i| Vector::new(vec![0.25 * (i / 5) as f64, 0.25 * (i % 5) as f64])).collect())
+}
+
+// Samples between the points of the grid
+fn interior() -> Matrix {
+    Matrix::new((0..16).map(|i| Vector::new(vec![0.1 + 0.2 * (i / 4) as f64, 0.15 + 0.2 * (i % 4) as f64])).collect())
+}
+
+#[test]
+fn quantized_edge_pass() {
+    let edge: Edge = Edge::standard(0, 0, 0);
+    let quantized: QuantizedEdge = QuantizedEdge::new(&edge, 0.0, 1.0, 1025, Precision::Int16);
+
+    assert_eq!(quantized.len(), 1025);
+    for i in 0..=20 {
+        let t: f64 = 0.05 * i as f64;
+        assert_is_close!(quantized.value(t), edge.value(t), 1e-3);
+    }
+    // Inputs outside of the range are clamped
+    assert_eq!(quantized.value(-1.0), quantized.value(0.0));
+    assert_eq!(quantized.value(2.0), quantized.value(1.0));
+
+    // The entries span the full range of the integers
+    let coarse: QuantizedEdge = QuantizedEdge::new(&edge, 0.0, 1.0, 16, Precision::Int8);
+    match &coarse.table {
+        Table::Int8(table) => {
+            assert_eq!(*table.iter().min().unwrap(), i8::MIN);
+            assert_eq!(*table.iter().max().unwrap(), i8::MAX);
+        }
+        Table::Int16(_) => panic!("The table must have the requested precision."),
+    }
+
+    // A constant edge and an empty range are represented exactly
+    let mut constant: Edge = Edge::standard(0, 0, 0);
+    constant.base_weight = 0.0;
+    constant.spline_weight = 0.0;
+    assert_eq!(QuantizedEdge::new(&constant, 0.0, 1.0, 4, Precision::Int8).value(0.3), 0.0);
+    assert_is_close!(QuantizedEdge::new(&edge, 0.4, 0.4, 2, Precision::Int8).value(0.9), edge.value(0.4), 1e-12);
+}
+
+#[test]
+#[should_panic]
+fn quantized_edge_fail() {
+    QuantizedEdge::new(&Edge::standard(0, 0, 0), 0.0, 1.0, 1, Precision::Int8);
+}
+
+#[test]
+fn kan_quantize_pass() {
+    let mut kan: KAN = KANBuilder::new(2).add_layer(3).add_layer(2).dropout(0.1).seed(6).build();
+    kan.scaler = Some(Scaler::min_max(&grid()));
+    let compiled: CompiledKAN = kan.compile();
+
+    let fine: QuantizedKAN = kan.quantize(&interior(), 1024, Precision::Int16).unwrap();
+    let coarse: QuantizedKAN = kan.quantize(&interior(), 64, Precision::Int8).unwrap();
+
+    let fine_error: QuantizationError = fine.compare(&compiled, &interior()).unwrap();
+    let coarse_error: QuantizationError = coarse.compare(&compiled, &interior()).unwrap();
+    assert!(fine_error.max_error < 1e-3);
+    assert!(fine_error.mean_error <= fine_error.max_error);
+    assert!(coarse_error.max_error < 0.1);
+    assert!(fine_error.max_error < coarse_error.max_error);
+    // The two outputs are equal for some samples, where rounding can flip the predicted class
+    assert!(fine_error.agreement > 0.9);
+    assert_eq!(fine.forward_batch(&interior()).row(3), fine.forward(&interior().row(3)));
+
+    // One and two bytes per entry against eight bytes per control point
+    let edges: usize = kan.edges().len();
+    assert_eq!(fine.table_bytes(), edges * 1024 * 2);
+    assert_eq!(coarse.table_bytes(), edges * 64);
+}
+
+#[test]
+fn kan_quantize_fail() {
+    let kan: KAN = KAN::standard(2, 3);
+    assert_eq!(kan.quantize(&Matrix::new(vec![]), 16, Precision::Int8).err(), Some(KanError::InvalidConfig("The quantization requires at least one calibration sample.")));
+    assert_eq!(kan.quantize(&interior(), 1, Precision::Int8).err(), Some(KanError::InvalidConfig("The table of a quantized edge must have at least 2 entries.")));
+    assert!(kan.quantize(&Matrix::zeros(2, 3), 16, Precision::Int8).is_err());
+}
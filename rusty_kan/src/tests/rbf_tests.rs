@@ -0,0 +1,103 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, rbf::GaussianRbf, activation::{ActivationFn, Basis}};
+use crate::init::Init;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn rbf_uniform_pass() {
+    let rbf: GaussianRbf = GaussianRbf::uniform(Vector::new(vec![1.0, 2.0, 3.0]), (-2.0, 2.0));
+
+    assert_eq!(rbf.len(), 3);
+    assert_eq!(rbf.amplitudes(), &[1.0, 2.0, 3.0]);
+    assert_eq!(rbf.centers(), &[-2.0, 0.0, 2.0]);
+    assert_eq!(rbf.widths(), &[2.0, 2.0, 2.0]);
+    assert_eq!(rbf.domain, (-2.0, 2.0));
+    assert!(!rbf.trainable_centers);
+}
+
+#[test]
+fn rbf_eval_pass() {
+    let rbf: GaussianRbf = GaussianRbf::new(Vector::new(vec![1.0, -2.0]), Vector::new(vec![0.0, 1.0]), Vector::new(vec![0.5, 1.0]), false);
+
+    // 1 * exp(-(0.5 / 0.5)^2) - 2 * exp(-(0.5 / 1)^2)
+    assert_is_close!(rbf.eval(0.5), (-1.0f64).exp() - 2.0 * (-0.25f64).exp(), 1e-12);
+    assert_is_close!(rbf.eval(0.0), 1.0 - 2.0 * (-1.0f64).exp(), 1e-12);
+}
+
+#[test]
+fn rbf_grad_wrt_params_pass() {
+    let mut rbf: GaussianRbf = GaussianRbf::new(Vector::new(vec![0.5, -1.0, 2.0]), Vector::new(vec![0.0, 0.4, 1.0]), Vector::new(vec![0.3, 0.5, 0.4]), true);
+    let t: f64 = 0.3;
+    let gradient: Vector = rbf.grad_wrt_params(t);
+
+    let epsilon: f64 = 1e-6;
+    for j in 0..rbf.params.len() {
+        let original: f64 = rbf.params[j];
+        rbf.params[j] = original + epsilon;
+        let plus: f64 = rbf.eval(t);
+        rbf.params[j] = original - epsilon;
+        let minus: f64 = rbf.eval(t);
+        rbf.params[j] = original;
+        assert_is_close!(gradient[j], (plus - minus) / (2.0 * epsilon), 1e-6);
+    }
+}
+
+#[test]
+fn rbf_fixed_centers_pass() {
+    let rbf: GaussianRbf = GaussianRbf::uniform(Vector::new(vec![0.5, -1.0, 2.0]), (0.0, 1.0));
+    let gradient: Vector = rbf.grad_wrt_params(0.3);
+
+    // Only the amplitudes receive a gradient
+    assert!(gradient.elements[..3].iter().all(|g| *g > 0.0));
+    assert_eq!(&gradient.elements[3..], &[0.0; 6]);
+}
+
+#[test]
+fn rbf_grad_wrt_input_pass() {
+    let rbf: GaussianRbf = GaussianRbf::uniform(Vector::new(vec![0.5, -1.0, 2.0, 0.3]), (-1.0, 1.0));
+    let epsilon: f64 = 1e-6;
+
+    for t in [-1.5, -0.2, 0.4, 1.2] {
+        let numerical: f64 = (rbf.eval(t + epsilon) - rbf.eval(t - epsilon)) / (2.0 * epsilon);
+        assert_is_close!(rbf.grad_wrt_input(t), numerical, 1e-6);
+    }
+}
+
+#[test]
+fn rbf_activation_pass() {
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(42);
+    let mut activation: Box<dyn ActivationFn> = Basis::Rbf { trainable_centers: true }.sample(Init::Uniform { low: -1.0, high: 1.0 }, 2, &mut rng);
+
+    assert_eq!(activation.params().len(), 15);
+    assert_eq!(activation.domain(), (0.0, 1.0));
+    assert_eq!(activation.grad_wrt_params(0.5).len(), 15);
+    activation.params_mut()[0] = 3.0;
+    let rbf: &GaussianRbf = activation.downcast_ref::<GaussianRbf>().unwrap();
+    assert_eq!(rbf.amplitudes()[0], 3.0);
+    assert!(rbf.trainable_centers);
+}
+
+#[test]
+#[should_panic]
+fn rbf_new_fail() {
+    let _ = GaussianRbf::new(Vector::new(vec![1.0, 2.0]), Vector::new(vec![0.0, 1.0]), Vector::new(vec![0.5, 0.0]), false);
+}
+
+#[test]
+#[should_panic]
+fn rbf_uniform_fail() {
+    let _ = GaussianRbf::uniform(Vector::new(vec![1.0]), (0.0, 1.0));
+}
@@ -0,0 +1,83 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::kan::KAN;
+use crate::onnx::OnnxModel;
+use crate::scaler::Scaler;
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![10.0, -1.0, 3.0]), Vector::new(vec![20.0, 1.0, 3.0]), Vector::new(vec![30.0, 3.0, 3.0])])
+}
+
+#[test]
+fn scaler_min_max_pass() {
+    let scaler: Scaler = Scaler::min_max(&inputs());
+
+    assert_eq!(scaler, Scaler::MinMax { min: Vector::new(vec![10.0, -1.0, 3.0]), max: Vector::new(vec![30.0, 3.0, 3.0]) });
+    // A constant feature is only shifted
+    assert_eq!(scaler.transform(&Vector::new(vec![20.0, 3.0, 3.5])), Vector::new(vec![0.5, 1.0, 0.5]));
+    assert_eq!(scaler.transform_batch(&inputs()).get_col(0), Vector::new(vec![0.0, 0.5, 1.0]));
+}
+
+#[test]
+fn scaler_z_score_pass() {
+    let scaler: Scaler = Scaler::z_score(&inputs());
+    let scaled: Matrix = scaler.transform_batch(&inputs());
+
+    assert_is_close!(scaled.get_col(0).mean(), 0.0, 1e-12);
+    assert_is_close!(scaled.get_col(1).std(), 1.0, 1e-12);
+    assert_is_close!(scaled[2][1], 1.5_f64.sqrt(), 1e-12);
+}
+
+#[test]
+fn scaler_inverse_transform_pass() {
+    for scaler in [Scaler::min_max(&inputs()), Scaler::z_score(&inputs())] {
+        let restored: Matrix = scaler.inverse_transform_batch(&scaler.transform_batch(&inputs()));
+        for (a, b) in restored.elements.iter().zip(inputs().elements.iter()) {
+            assert_is_close!(a, b, 1e-12);
+        }
+    }
+}
+
+#[test]
+fn scaler_fit_pass() {
+    let mut scaler: Scaler = Scaler::z_score(&inputs());
+    scaler.fit(&Matrix::new(vec![Vector::new(vec![1.0]), Vector::new(vec![3.0])]));
+
+    assert_eq!(scaler, Scaler::ZScore { mean: Vector::new(vec![2.0]), std: Vector::new(vec![1.0]) });
+}
+
+#[test]
+#[should_panic]
+fn scaler_transform_fail() {
+    Scaler::min_max(&inputs()).transform(&Vector::new(vec![1.0, 2.0]));
+}
+
+#[test]
+fn kan_scaler_pass() {
+    let raw: Matrix = Matrix::new(vec![Vector::new(vec![-40.0, 200.0]), Vector::new(vec![60.0, 400.0])]);
+    let scaler: Scaler = Scaler::min_max(&raw);
+    let mut kan: KAN = KAN::standard_seeded(2, 3, 7);
+    let expected: Matrix = kan.forward_batch(&scaler.transform_batch(&raw)).unwrap();
+    kan.scaler = Some(scaler.clone());
+
+    // The raw inputs are mapped into the domain of the splines before every forward pass
+    assert_eq!(kan.forward_batch(&raw).unwrap(), expected);
+    assert_is_close!(kan.forward_logits(&raw.row(1)).unwrap()[0], expected[1][0], 1e-12);
+    let model: OnnxModel = OnnxModel::from_kan(&kan, 256);
+    assert_is_close!(model.forward(&raw.row(0))[0], expected[0][0], 1e-3);
+
+    let loaded: KAN = serde_json::from_str(&serde_json::to_string(&kan).unwrap()).unwrap();
+    assert_eq!(loaded.scaler, Some(scaler));
+}
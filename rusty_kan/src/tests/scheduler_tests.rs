@@ -0,0 +1,42 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::scheduler::Scheduler;
+
+#[test]
+fn scheduler_constant_pass() {
+    assert_eq!(Scheduler::Constant.learning_rate(0.1, 0), 0.1);
+    assert_eq!(Scheduler::Constant.learning_rate(0.1, 100), 0.1);
+}
+
+#[test]
+fn scheduler_step_decay_pass() {
+    let scheduler: Scheduler = Scheduler::StepDecay { step_size: 10, gamma: 0.5 };
+
+    assert_is_close!(scheduler.learning_rate(0.1, 9), 0.1, 1e-12);
+    assert_is_close!(scheduler.learning_rate(0.1, 10), 0.05, 1e-12);
+    assert_is_close!(scheduler.learning_rate(0.1, 25), 0.025, 1e-12);
+}
+
+#[test]
+#[should_panic]
+fn scheduler_step_decay_fail() {
+    let _ = Scheduler::StepDecay { step_size: 0, gamma: 0.5 }.learning_rate(0.1, 1);
+}
+
+#[test]
+fn scheduler_exponential_decay_pass() {
+    let scheduler: Scheduler = Scheduler::ExponentialDecay { gamma: 0.9 };
+
+    assert_is_close!(scheduler.learning_rate(1.0, 2), 0.81, 1e-12);
+}
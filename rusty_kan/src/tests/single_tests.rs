@@ -0,0 +1,174 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::builder::KANBuilder;
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::{Edge, BaseFn}, spline::{BSpline, BasisMemo, Extrapolation, Shape}};
+use crate::error::KanError;
+use crate::kan::KAN;
+use crate::loss::Loss;
+use crate::scaler::Scaler;
+use crate::single::{SingleSpline, SingleEdge, SingleKAN};
+
+fn kan() -> KAN {
+    KANBuilder::new(2).add_layer(3).add_layer(1).seed(4).build()
+}
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.1, 0.8]), Vector::new(vec![0.4, 0.3]), Vector::new(vec![0.9, 0.5]), Vector::new(vec![0.6, 0.2])])
+}
+
+fn targets() -> Matrix {
+    Matrix::new((0..4).map(|i| Vector::new(vec![inputs()[i][0] * inputs()[i][1]])).collect())
+}
+
+#[test]
+fn single_spline_pass() {
+    let mut spline: BSpline = BSpline { control_points: Vector::new(vec![1.0, -2.0, 3.0, 0.5]), knots: Vector::new(vec![0.0, 0.1, 0.3, 0.4, 0.6, 0.8, 1.0]), degree: 2, memo: BasisMemo::default(), domain: (-1.0, 2.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
+
+    for extrapolation in [Extrapolation::Zero, Extrapolation::Clamp, Extrapolation::Linear] {
+        spline.extrapolation = extrapolation;
+        let single: SingleSpline = SingleSpline::from_spline(&spline).unwrap();
+        for &t in [-1.5, -1.0, -0.3, 0.4, 1.1, 2.0, 2.5].iter() {
+            assert_is_close!(single.value(t as f32).unwrap() as f64, spline.eval(t), 1e-5);
+            assert_is_close!(single.derivative(t as f32).unwrap() as f64, spline.derivative(t), 1e-4);
+        }
+    }
+
+    let round_trip: BSpline = SingleSpline::from_spline(&spline).unwrap().to_spline();
+    assert_eq!(round_trip.knots, spline.knots.cast::<f32>().cast());
+    assert_eq!(round_trip.control_points, spline.control_points.cast::<f32>().cast());
+    assert_eq!((round_trip.degree, round_trip.domain, round_trip.extrapolation), (spline.degree, spline.domain, spline.extrapolation));
+}
+
+#[test]
+fn single_spline_fail() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![1.0, 2.0, 3.0]), 2);
+    spline.extrapolation = Extrapolation::Panic;
+    let single: SingleSpline = SingleSpline::from_spline(&spline).unwrap();
+    assert_eq!(single.value(1.5), Err(KanError::DomainError("The parameter value lies outside the range of the knots.")));
+
+    spline.weights = Some(Vector::new(vec![1.0, 2.0, 1.0]));
+    assert!(matches!(SingleSpline::from_spline(&spline), Err(KanError::Unsupported(_))));
+}
+
+#[test]
+fn single_edge_pass() {
+    let mut edge: Edge = Edge::standard(1, 0, 1);
+    edge.base = BaseFn::Tanh;
+    edge.base_weight = 0.7;
+    edge.spline_weight = -1.3;
+    let mut single: SingleEdge = SingleEdge::from_edge(&edge).unwrap();
+
+    for &t in [0.2, 0.55, 0.9].iter() {
+        assert_is_close!(single.value(t as f32).unwrap() as f64, edge.value(t), 1e-5);
+    }
+
+    // The gradients of two samples are summed until the update
+    single.backward(0.3, 1.0).unwrap();
+    single.backward(0.6, 0.5).unwrap();
+    assert_is_close!(single.input_gradient(0.3).unwrap() as f64, edge.input_gradient(0.3), 1e-4);
+    let n: usize = single.num_weights();
+    assert_is_close!(single.gradient[n - 2] as f64, 0.3f64.tanh() + 0.5 * 0.6f64.tanh(), 1e-5);
+
+    let gradient: Vector<f32> = single.gradient.clone();
+    let before: Vector<f32> = single.spline.control_points.clone();
+    single.update_weights(0.1).unwrap();
+    assert_is_close!(single.spline.control_points[0], before[0] - 0.1 * gradient[0], 1e-6);
+    assert_eq!(single.gradient, Vector::zeros(n));
+
+    let round_trip: Edge = single.to_edge(0, 1);
+    assert_eq!(round_trip.start, 1);
+    assert_eq!(round_trip.base, BaseFn::Tanh);
+    assert_is_close!(round_trip.spline_weight, single.spline_weight as f64, 1e-12);
+}
+
+#[test]
+fn single_edge_fail() {
+    let mut edge: Edge = Edge::standard(0, 0, 1);
+    edge.set_dropout(0.2);
+    assert!(matches!(SingleEdge::from_edge(&edge), Err(KanError::Unsupported(_))));
+
+    let mut single: SingleEdge = SingleEdge::from_edge(&Edge::standard(0, 0, 1)).unwrap();
+    single.gradient = Vector::zeros(2);
+    assert!(matches!(single.backward(0.5, 1.0), Err(KanError::DimensionMismatch { .. })));
+}
+
+#[test]
+fn single_kan_forward_pass() {
+    let kan: KAN = kan();
+    let single: SingleKAN = kan.to_single().unwrap();
+    assert_eq!(single.num_params(), kan.num_params());
+
+    let outputs: Matrix<f32> = single.forward_batch(&inputs().cast()).unwrap();
+    for i in 0..4 {
+        assert_is_close!(outputs[i][0] as f64, kan.forward(&inputs().row(i)).unwrap(), 1e-5);
+    }
+    assert_is_close!(single.loss(&inputs().cast(), &targets().cast(), Loss::MeanSquaredError).unwrap() as f64, kan.loss_with(inputs(), targets(), Loss::MeanSquaredError).unwrap(), 1e-5);
+}
+
+#[test]
+fn single_kan_backward_pass() {
+    let kan: KAN = kan();
+    let mut single: SingleKAN = kan.to_single().unwrap();
+    let input: Vector = inputs().row(2);
+    let target: Vector = targets().row(2);
+
+    let loss: f64 = kan.backward_features(&input, &target, Loss::MeanSquaredError).unwrap();
+    assert_is_close!(single.backward(&input.cast(), &target.cast(), Loss::MeanSquaredError).unwrap() as f64, loss, 1e-5);
+
+    for (l, layer) in single.layers.iter().enumerate() {
+        for (j, edges) in layer.nodes.iter().enumerate() {
+            for (k, edge) in edges.iter().enumerate() {
+                let expected: Vector = kan.layers[l].borrow().nodes[j].borrow().incoming[k].borrow().gradient.clone();
+                for i in 0..expected.len() {
+                    assert_is_close!(edge.gradient[i] as f64, expected[i], 1e-5);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn single_kan_train_batch_pass() {
+    let mut single: SingleKAN = kan().to_single().unwrap();
+    let (inputs, targets): (Matrix<f32>, Matrix<f32>) = (inputs().cast(), targets().cast());
+
+    let first: f32 = single.train_batch(&inputs, &targets, 0.05, Loss::MeanSquaredError).unwrap();
+    for _ in 0..20 {
+        single.train_batch(&inputs, &targets, 0.05, Loss::MeanSquaredError).unwrap();
+    }
+    let last: f32 = single.loss(&inputs, &targets, Loss::MeanSquaredError).unwrap();
+    assert!(last < first);
+
+    // The trained model is widened with its layers and edges
+    let kan: KAN = single.to_kan().unwrap();
+    for i in 0..4 {
+        assert_is_close!(kan.forward(&inputs.row(i).cast()).unwrap(), single.forward(&inputs.row(i)).unwrap()[0] as f64, 1e-5);
+    }
+    assert_eq!(kan.to_single().unwrap(), single);
+}
+
+#[test]
+fn single_kan_fail() {
+    let mut kan: KAN = kan();
+    kan.scaler = Some(Scaler::min_max(&inputs()));
+    assert!(matches!(kan.to_single(), Err(KanError::Unsupported(_))));
+    assert!(matches!(KANBuilder::new(2).add_mult_layer(2, 1).add_layer(1).build().to_single(), Err(KanError::Unsupported(_))));
+
+    let mut single: SingleKAN = self::kan().to_single().unwrap();
+    assert!(matches!(single.forward(&Vector::new(vec![0.5])), Err(KanError::DimensionMismatch { .. })));
+    let empty: Matrix<f32> = Matrix::new(vec![]);
+    assert_eq!(single.train_batch(&empty, &empty, 0.05, Loss::MeanSquaredError), Err(KanError::InvalidConfig("The loss of a batch requires at least one sample.")));
+    assert_eq!(single.train_batch(&inputs().cast(), &targets().cast(), 0.0, Loss::MeanSquaredError), Err(KanError::InvalidConfig("The learning rate must be greater than 0.")));
+}
+
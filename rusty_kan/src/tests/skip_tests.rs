@@ -0,0 +1,193 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::builder::{KANBuilder, Skip};
+use crate::codegen;
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::Node, layer::Layer};
+use crate::kan::{KAN, grad_check};
+use crate::loss::Loss;
+use crate::onnx::OnnxModel;
+use crate::trainer::Trainer;
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+fn skip_kan() -> KAN {
+    // Identity skip edges from the inputs into the second layer, and spline skip edges from the first layer into the third
+    KANBuilder::new(2).add_layer(3).add_layer(2).skip(Skip::Identity).add_layer(1).skip(Skip::Spline).seed(3).build()
+}
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.1, 0.8]), Vector::new(vec![0.4, 0.3]), Vector::new(vec![0.9, 0.5]), Vector::new(vec![0.6, 0.2])])
+}
+
+#[test]
+fn edge_identity_pass() {
+    let mut edge: Edge = Edge::identity(1, 0, 2);
+
+    assert_eq!(edge.value(-3.5), -3.5);
+    assert_eq!(edge.input_gradient(0.7), 1.0);
+    assert!(edge.frozen);
+    assert!(!edge.skip);
+    edge.backward(0.5, 1.0).unwrap();
+    edge.update_weights(0.1).unwrap();
+    assert_eq!(edge.value(2.0), 2.0);
+}
+
+#[test]
+fn layer_route_with_skip_pass() {
+    let mut skip: Edge = Edge::identity(1, 0, 1);
+    skip.skip = true;
+    let incoming_edges: Vec<Rc<RefCell<Edge>>> = vec![Rc::new(RefCell::new(Edge::standard(0, 0, 1))), Rc::new(RefCell::new(skip))];
+    let layer: Layer = Layer::new(vec![Rc::new(RefCell::new(Node::new(incoming_edges, vec![], 1)))]);
+
+    let input: Matrix = layer.route_with_skip(&Vector::new(vec![0.25]), &Vector::new(vec![0.5, 0.75]));
+
+    assert_eq!(input, Matrix::new(vec![Vector::new(vec![0.25, 0.75])]));
+    // Only the skip edge reaches two layers back
+    assert_eq!(layer.propagate_through(&input, &Vector::new(vec![2.0]), 1).unwrap(), Vector::new(vec![2.0]));
+    assert_eq!(layer.propagate_skip(&input, &Vector::new(vec![2.0]), 2).unwrap(), Vector::new(vec![0.0, 2.0]));
+}
+
+#[test]
+#[should_panic]
+fn layer_route_with_skip_fail() {
+    let mut skip: Edge = Edge::identity(0, 0, 1);
+    skip.skip = true;
+    let layer: Layer = Layer::new(vec![Rc::new(RefCell::new(Node::new(vec![Rc::new(RefCell::new(skip))], vec![], 1)))]);
+    layer.route(&Vector::new(vec![0.25]));
+}
+
+#[test]
+fn builder_skip_pass() {
+    let kan: KAN = skip_kan();
+
+    let second: Vec<usize> = kan.layers[1].borrow().nodes.iter().map(|node| node.borrow().incoming.len()).collect();
+    let third: Vec<usize> = kan.layers[2].borrow().nodes.iter().map(|node| node.borrow().incoming.len()).collect();
+    assert_eq!(second, vec![3 + 1, 3 + 1]);
+    assert_eq!(third, vec![2 + 3]);
+    assert!(kan.layers[1].borrow().nodes[1].borrow().incoming[3].borrow().skip);
+    assert_eq!(kan.layers[1].borrow().nodes[1].borrow().incoming[3].borrow().start, 1);
+    // The nodes of the first layer feed the second layer and the skip edges of the third
+    assert!(kan.layers[0].borrow().nodes.iter().all(|node| node.borrow().outgoing.len() == 2 + 1));
+}
+
+#[test]
+#[should_panic]
+fn builder_skip_fail() {
+    KANBuilder::new(2).add_layer(3).skip(Skip::Spline);
+}
+
+#[test]
+#[should_panic]
+fn builder_identity_skip_fail() {
+    KANBuilder::new(2).add_layer(3).add_layer(1).skip(Skip::Identity).build();
+}
+
+#[test]
+fn kan_skip_pass() {
+    let kan: KAN = skip_kan();
+    let input: Vector = Vector::new(vec![0.2, 0.9]);
+    let expected: f64 = kan.forward(&input).unwrap();
+
+    // The skip edges receive the values two layers back
+    let first: Vector = kan.layers[0].borrow().activations(kan.input_matrix(&input)).unwrap();
+    let second: Vector = kan.layers[1].borrow().activations(kan.layers[1].borrow().route_with_skip(&first, &input)).unwrap();
+    let regular: f64 = (0..3).map(|j| kan.layers[1].borrow().nodes[0].borrow().incoming[j].borrow().value(first[j])).sum();
+    assert_is_close!(second[0], regular + input[0], 1e-12);
+    let third: Vector = kan.layers[2].borrow().activations(kan.layers[2].borrow().route_with_skip(&second, &first)).unwrap();
+    assert_eq!(third[0], expected);
+
+    // The compiled model and a JSON round trip keep the skip edges
+    assert_is_close!(kan.compile().forward(&input).unwrap()[0], expected, 1e-12);
+    assert_eq!(kan.compile().num_inputs(), 2);
+    let loaded: KAN = serde_json::from_str(&serde_json::to_string(&kan).unwrap()).unwrap();
+    assert_eq!(loaded.forward(&input).unwrap(), expected);
+    assert!(loaded.layers[0].borrow().nodes.iter().all(|node| node.borrow().outgoing.len() == 3));
+
+    // The input gradients flow through the skip edges
+    let epsilon: f64 = 1e-6;
+    let gradients: Vector = kan.input_gradients(&input).unwrap();
+    for i in 0..2 {
+        let (mut plus, mut minus): (Vector, Vector) = (input.clone(), input.clone());
+        plus[i] += epsilon;
+        minus[i] -= epsilon;
+        let numerical: f64 = (kan.forward(&plus).unwrap() - kan.forward(&minus).unwrap()) / (2.0 * epsilon);
+        assert_is_close!(gradients[i], numerical, 1e-6);
+    }
+}
+
+#[test]
+fn kan_skip_grad_check_pass() {
+    let kan: KAN = skip_kan();
+
+    let errors: Vector = grad_check(&kan, &Vector::new(vec![0.3, 0.6]), &Vector::new(vec![2.0]), Loss::MeanSquaredError, 1e-5).unwrap();
+
+    // The gradients of the edges of the last layer, including its skip edges, are exact
+    let last: usize = kan.layers[2].borrow().nodes[0].borrow().incoming.len();
+    for i in errors.len() - last..errors.len() {
+        assert!(errors[i] < 1e-6);
+    }
+}
+
+#[test]
+fn kan_skip_prune_pass() {
+    let kan: KAN = skip_kan();
+    // Keep the skip edges of the last layer and one edge into it from the second layer
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    let last: usize = edges.len() - 5;
+    for (i, edge) in edges.iter().enumerate() {
+        edge.borrow_mut().score = Some(if i <= last || edge.borrow().skip { 1.0 } else { 0.0 });
+    }
+
+    assert_eq!(kan.prune(0.5).unwrap(), 1 + 4);
+
+    // The second node of the second layer fed only the pruned edge, so it is removed with its incoming edges
+    assert_eq!(kan.layers[1].borrow().nodes.len(), 1);
+    let input: Vector = Vector::new(vec![0.2, 0.9]);
+    assert_is_close!(kan.compile().forward(&input).unwrap()[0], kan.forward(&input).unwrap(), 1e-12);
+    assert!(kan.backward_features(&input, &Vector::new(vec![1.0]), Loss::MeanSquaredError).is_ok());
+}
+
+#[test]
+fn trainer_skip_pass() {
+    let kan: KAN = skip_kan();
+    let targets: Matrix = Matrix::new((0..4).map(|i| Vector::new(vec![inputs()[i][0] * inputs()[i][1]])).collect());
+    let mut trainer: Trainer = Trainer::new(kan, 0.01, Loss::MeanSquaredError, 8);
+    let before: Vector = trainer.kan.layers[2].borrow().nodes[0].borrow().incoming[4].borrow().weights();
+
+    trainer.fit(&inputs(), &targets, 5).unwrap();
+
+    // The identity skip edges are frozen while the spline skip edges are trained
+    assert_ne!(trainer.kan.layers[2].borrow().nodes[0].borrow().incoming[4].borrow().weights(), before);
+    assert_eq!(trainer.kan.layers[1].borrow().nodes[0].borrow().incoming[3].borrow().value(0.3), 0.3);
+
+    let path: PathBuf = std::env::temp_dir().join(format!("rusty_kan_skip_{}.json", std::process::id()));
+    trainer.save_checkpoint(&path).unwrap();
+    let loaded: Trainer = Trainer::load_checkpoint(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(loaded.kan.layers[2].borrow().nodes[0].borrow().incoming[4].borrow().skip);
+    assert_eq!(loaded.evaluate(&inputs(), &targets), trainer.evaluate(&inputs(), &targets));
+}
+
+#[test]
+fn codegen_skip_fail() {
+    assert!(codegen::rust(&skip_kan()).is_err());
+}
+
+#[test]
+#[should_panic]
+fn onnx_skip_fail() {
+    OnnxModel::from_kan(&skip_kan(), 16);
+}
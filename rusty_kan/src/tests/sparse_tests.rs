@@ -0,0 +1,71 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, matrix::Matrix, sparse::SparseMatrix};
+
+fn dense() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.0, 2.0, 0.0]), Vector::new(vec![0.0, 0.0, 0.0]), Vector::new(vec![1.0, 0.0, -3.0])])
+}
+
+#[test]
+fn sparse_from_dense_pass() {
+    let a: SparseMatrix = SparseMatrix::from_dense(&dense());
+
+    assert_eq!(a.nnz(), 3);
+    assert_eq!(a.row_offsets, vec![0, 1, 1, 3]);
+    assert_eq!(a.col_indices, vec![1, 0, 2]);
+    assert_eq!(a.values, vec![2.0, 1.0, -3.0]);
+    assert_eq!(a.to_dense(), dense());
+}
+
+#[test]
+fn sparse_from_triplets_pass() {
+    let a: SparseMatrix = SparseMatrix::from_triplets(3, 3, &[(2, 2, -1.0), (0, 1, 2.0), (2, 0, 1.0), (2, 2, -2.0)]);
+
+    assert_eq!(a, SparseMatrix::from_dense(&dense()));
+    assert_eq!(a.get(2, 2), -3.0);
+    assert_eq!(a.get(1, 1), 0.0);
+    assert_eq!(a.row_entries(2).collect::<Vec<(usize, f64)>>(), vec![(0, 1.0), (2, -3.0)]);
+}
+
+#[test]
+#[should_panic]
+fn sparse_from_triplets_fail() {
+    SparseMatrix::from_triplets(2, 2, &[(0, 2, 1.0)]);
+}
+
+#[test]
+fn sparse_mul_pass() {
+    let a: SparseMatrix = SparseMatrix::from_dense(&dense());
+    let v: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+
+    assert_eq!(&a * &v, &dense() * &v);
+    assert_eq!(a.mul_transpose(&v), &dense().transpose() * &v);
+    assert_eq!(a.transpose().to_dense(), dense().transpose());
+}
+
+#[test]
+#[should_panic]
+fn sparse_mul_fail() {
+    let a: SparseMatrix = SparseMatrix::from_dense(&dense());
+    let _ = &a * &Vector::new(vec![1.0, 2.0]);
+}
+
+#[test]
+fn sparse_zeros_pass() {
+    let a: SparseMatrix = SparseMatrix::zeros(2, 3);
+
+    assert_eq!(a.nnz(), 0);
+    assert_eq!(a.to_dense(), Matrix::zeros(2, 3));
+    assert_is_close!((&a * &Vector::ones(3)).sum(), 0.0, 1e-12);
+}
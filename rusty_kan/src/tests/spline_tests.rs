@@ -12,9 +12,8 @@ macro_rules! assert_is_close {
 }
 
 use std::vec;
-use std::collections::HashMap;
 
-use crate::data_structures::{vector::Vector, spline::BSpline};
+use crate::data_structures::{vector::Vector, matrix::Matrix, spline::{BSpline, BasisMemo, Extrapolation, Shape}, activation::ActivationFn};
 
 #[test]
 fn spline_new_pass() {
@@ -34,7 +33,7 @@ fn spline_eval_pass() {
     let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
     let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
     let degree: usize = 2;
-    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: HashMap::new()};
+    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
 
     println!("{:?}", spline);
 
@@ -64,7 +63,7 @@ fn spline_basis_pass() {
     let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
     let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
     let degree: usize = 2;
-    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: HashMap::new()};
+    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
 
     println!("{:?}", spline);
 
@@ -95,8 +94,588 @@ fn spline_basis_fail() {
     let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
     let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
     let degree: usize = 2;
-    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: HashMap::new()};
+    let mut spline: BSpline = BSpline { control_points: control_points.clone(), knots: knots.clone(), degree: degree.clone(), memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
 
     // i > degree -> should fail
     let _ = spline.basis(3, 2, 0.5);
-}
\ No newline at end of file
+}
+#[test]
+fn spline_refine_pass() {
+    let control_points: Vector = Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2]);
+    let mut coarse: BSpline = BSpline::new(control_points, 2);
+    let mut fine: BSpline = coarse.clone();
+
+    fine.refine(12);
+
+    assert_eq!(fine.control_points.len(), 12);
+    assert_eq!(fine.knots.len(), 12 + 2 + 1);
+    assert_eq!(fine.knots[0], 0.0);
+    assert_eq!(fine.knots[fine.knots.len() - 1], 1.0);
+    // The refined spline closely matches the original on the domain of the knots
+    for i in 0..50 {
+        let t: f64 = 0.01 + 0.98 * i as f64 / 49.0;
+        assert_is_close!(fine.eval(t), coarse.eval(t), 0.05);
+    }
+}
+
+#[test]
+fn spline_refine_same_size_pass() {
+    let control_points: Vector = Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2]);
+    let mut spline: BSpline = BSpline::new(control_points.clone(), 2);
+
+    spline.refine(5);
+
+    for i in 0..5 {
+        assert_is_close!(spline.control_points[i], control_points[i], 1e-6);
+    }
+}
+
+#[test]
+#[should_panic]
+fn spline_refine_fail() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2]), 2);
+
+    spline.refine(4);
+}
+
+#[test]
+fn spline_extend_to_pass() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2, -0.4, 0.8, 1.0]), 2);
+    spline.extrapolation = Extrapolation::Linear;
+    let original: BSpline = spline.clone();
+
+    assert!(!spline.extend_to(0.7));
+    assert!(spline.extend_to(1.8));
+
+    assert_eq!(spline.control_points.len(), 18);
+    assert_eq!(spline.knots[0], 0.0);
+    assert_is_close!(spline.knots[spline.knots.len() - 1], 2.0, 1e-12);
+    // The function is preserved on the old range of the knots and extended linearly beyond it
+    for i in 0..20 {
+        let t: f64 = 0.3 + 0.8 * i as f64 / 19.0;
+        assert_is_close!(spline.eval(t), original.clone().eval(t), 1e-6);
+    }
+}
+
+#[test]
+#[should_panic]
+fn spline_extend_to_fail() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2]), 2);
+
+    spline.extend_to(f64::NAN);
+}
+
+#[test]
+fn spline_update_knots_from_samples_pass() {
+    let control_points: Vector = Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2]);
+    let mut original: BSpline = BSpline::new(control_points, 2);
+    let mut updated: BSpline = original.clone();
+    // Most samples lie in [0.3, 0.4]
+    let samples: Vector = Vector::new((0..100).map(|i| if i < 90 { 0.3 + 0.1 * i as f64 / 89.0 } else { 0.4 + 0.3 * (i - 90) as f64 / 9.0 }).collect());
+
+    updated.update_knots_from_samples(&samples);
+
+    assert_eq!(updated.control_points.len(), 5);
+    assert_eq!(updated.knots.len(), 8);
+    for i in 1..updated.knots.len() {
+        assert!(updated.knots[i] > updated.knots[i - 1]);
+    }
+    // The grid spans the samples and concentrates where they lie
+    assert_is_close!(updated.knots[2], 0.3, 1e-2);
+    assert_is_close!(updated.knots[5], 0.7, 1e-2);
+    assert!(updated.knots[4] < 0.45);
+    // The function is approximately preserved on the observed range
+    for i in 0..20 {
+        let t: f64 = 0.3 + 0.4 * (i as f64 + 0.5) / 20.0;
+        assert_is_close!(updated.eval(t), original.eval(t), 0.5);
+    }
+}
+
+#[test]
+#[should_panic]
+fn spline_update_knots_from_samples_fail() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2]), 2);
+
+    spline.update_knots_from_samples(&Vector::new(vec![0.5, 0.5, 0.5]));
+}
+
+#[test]
+fn spline_derivative_pass() {
+    let control_points: Vector = Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2]);
+    let mut spline: BSpline = BSpline::new(control_points, 2);
+    let h: f64 = 1e-6;
+
+    for t in [0.2, 0.35, 0.5, 0.65, 0.8] {
+        let numerical: f64 = (spline.eval(t + h) - spline.eval(t - h)) / (2.0 * h);
+        assert_is_close!(spline.derivative(t), numerical, 1e-5);
+    }
+}
+
+#[test]
+fn spline_nth_derivative_pass() {
+    let control_points: Vector = Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2, -0.7]);
+    let spline: BSpline = BSpline::new(control_points, 3);
+    let h: f64 = 1e-5;
+
+    for t in [0.4, 0.55, 0.6] {
+        let numerical: f64 = (spline.derivative(t + h) - spline.derivative(t - h)) / (2.0 * h);
+        assert_is_close!(spline.nth_derivative(t, 2), numerical, 1e-4);
+    }
+    assert_eq!(spline.nth_derivative(0.5, 4), 0.0);
+}
+
+#[test]
+fn spline_derivative_linear_pass() {
+    // Control points on a line at the Greville abscissae give a spline with constant slope on its full-support domain
+    let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
+    let control_points: Vector = Vector::new(vec![0.3, 0.5, 0.7]);
+    let spline: BSpline = BSpline { control_points, knots, degree: 2, memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
+
+    assert_is_close!(spline.derivative(0.45), 1.0, 1e-12);
+    assert_is_close!(spline.nth_derivative(0.45, 2), 0.0, 1e-12);
+}
+
+#[test]
+fn spline_memo_pass() {
+    let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let knots: Vector = Vector::new(vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
+    let mut spline: BSpline = BSpline { control_points, knots, degree: 2, memo: BasisMemo::default(), domain: (0.0, 1.0), extrapolation: Extrapolation::Zero, shape: Shape::None, weights: None };
+
+    let first: f64 = spline.eval(0.5);
+    let cached: usize = spline.memo.len();
+    let second: f64 = spline.eval(0.5);
+
+    assert!(cached > 0);
+    assert_eq!(spline.memo.len(), cached);
+    assert_eq!(spline.memo.get(&(1, 2, 0.5_f64.to_bits())), Some(&0.75));
+    assert_eq!(first, second);
+}
+
+#[test]
+fn spline_clear_memo_pass() {
+    let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let mut spline: BSpline = BSpline::new(control_points, 2);
+    let before: f64 = spline.eval(0.5);
+
+    // Stale cached basis values are discarded after the knots change
+    spline.knots = Vector::new(vec![0.0, 0.1, 0.3, 0.6, 0.8, 1.0]);
+    spline.clear_memo();
+
+    assert!(spline.memo.is_empty());
+    assert_ne!(spline.eval(0.5), before);
+}
+
+#[test]
+fn spline_with_knots_pass() {
+    let control_points: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let knots: Vector = Vector::new(vec![0.0, 0.1, 0.3, 0.6, 0.8, 1.0]);
+
+    let spline: BSpline = BSpline::with_knots(control_points.clone(), knots.clone(), 2);
+
+    assert_eq!(spline.control_points, control_points);
+    assert_eq!(spline.knots, knots);
+    assert_eq!(spline.degree, 2);
+}
+
+#[test]
+#[should_panic]
+fn spline_with_knots_length_fail() {
+    BSpline::with_knots(Vector::new(vec![1.0, 2.0, 3.0]), Vector::new(vec![0.0, 0.25, 0.5, 0.75, 1.0]), 2);
+}
+
+#[test]
+#[should_panic]
+fn spline_with_knots_order_fail() {
+    BSpline::with_knots(Vector::new(vec![1.0, 2.0, 3.0]), Vector::new(vec![0.0, 0.2, 0.6, 0.4, 0.8, 1.0]), 2);
+}
+
+#[test]
+fn spline_clamped_pass() {
+    let control_points: Vector = Vector::new(vec![1.5, -2.0, 3.0, 0.5]);
+    let mut spline: BSpline = BSpline::clamped(control_points, 2);
+
+    assert_eq!(spline.knots, Vector::new(vec![0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0]));
+    // The clamped spline interpolates its endpoints
+    assert_is_close!(spline.eval(0.0), 1.5, 1e-12);
+    assert_is_close!(spline.eval(1.0), 0.5, 1e-12);
+    // The basis functions form a partition of unity on the whole domain
+    for t in [0.0, 0.2, 0.5, 0.9, 1.0] {
+        let sum: f64 = (0..4).map(|i| spline.basis(i, 2, t)).sum();
+        assert_is_close!(sum, 1.0, 1e-12);
+    }
+}
+
+#[test]
+#[should_panic]
+fn spline_clamped_fail() {
+    BSpline::clamped(Vector::new(vec![1.0, 2.0]), 2);
+}
+
+#[test]
+fn spline_domain_pass() {
+    let control_points: Vector = Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2]);
+    let mut unit: BSpline = BSpline::new(control_points.clone(), 2);
+    let mut scaled: BSpline = BSpline::new(control_points, 2);
+
+    scaled.set_domain((-10.0, 30.0));
+
+    assert_eq!(scaled.domain, (-10.0, 30.0));
+    for t in [0.1, 0.35, 0.5, 0.9] {
+        let x: f64 = -10.0 + 40.0 * t;
+        assert_is_close!(scaled.eval(x), unit.eval(t), 1e-12);
+        assert_is_close!(scaled.basis(2, 2, x), unit.basis(2, 2, t), 1e-12);
+        assert_is_close!(scaled.derivative(x), unit.derivative(t) / 40.0, 1e-12);
+        assert_is_close!(scaled.nth_derivative(x, 2), unit.nth_derivative(t, 2) / 1600.0, 1e-12);
+    }
+}
+
+#[test]
+fn spline_domain_update_knots_pass() {
+    let control_points: Vector = Vector::new(vec![0.5, -1.0, 2.0, 0.3, 1.2]);
+    let mut spline: BSpline = BSpline::new(control_points, 2);
+    spline.set_domain((0.0, 100.0));
+
+    spline.update_knots_from_samples(&Vector::new((0..=20).map(|i| 20.0 + 2.0 * i as f64).collect()));
+
+    // The knots stay in the parameter range, covering the samples in [20, 60]
+    assert_is_close!(spline.knots[2], 0.2, 1e-2);
+    assert_is_close!(spline.knots[5], 0.6, 1e-2);
+}
+
+#[test]
+#[should_panic]
+fn spline_domain_fail() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![1.0, 2.0, 3.0]), 2);
+
+    spline.set_domain((1.0, -1.0));
+}
+
+#[test]
+fn spline_extrapolation_zero_pass() {
+    let mut spline: BSpline = BSpline::clamped(Vector::new(vec![1.0, 2.0, 4.0]), 2);
+
+    assert_eq!(spline.extrapolation, Extrapolation::Zero);
+    assert_eq!(spline.eval(1.5), 0.0);
+    assert_eq!(spline.eval(-0.5), 0.0);
+}
+
+#[test]
+#[should_panic]
+fn spline_extrapolation_panic_fail() {
+    let mut spline: BSpline = BSpline::clamped(Vector::new(vec![1.0, 2.0, 4.0]), 2);
+    spline.extrapolation = Extrapolation::Panic;
+
+    spline.eval(1.5);
+}
+
+#[test]
+fn spline_extrapolation_clamp_pass() {
+    let mut spline: BSpline = BSpline::clamped(Vector::new(vec![1.0, 2.0, 4.0]), 2);
+    spline.extrapolation = Extrapolation::Clamp;
+
+    assert_is_close!(spline.eval(1.5), 4.0, 1e-12);
+    assert_is_close!(spline.eval(-0.5), 1.0, 1e-12);
+    assert_eq!(spline.derivative(1.5), 0.0);
+    assert_is_close!(spline.basis(2, 2, 1.5), 1.0, 1e-12);
+}
+
+#[test]
+fn spline_extrapolation_linear_pass() {
+    // On clamped knots [0, 0, 0, 1, 1, 1] the spline is the quadratic Bezier curve with slope 2 * (c2 - c1) = 4 at t = 1
+    let mut spline: BSpline = BSpline::clamped(Vector::new(vec![1.0, 2.0, 4.0]), 2);
+    spline.extrapolation = Extrapolation::Linear;
+
+    assert_is_close!(spline.eval(1.5), 4.0 + 4.0 * 0.5, 1e-12);
+    assert_is_close!(spline.eval(-0.5), 1.0 - 2.0 * 0.5, 1e-12);
+    assert_is_close!(spline.derivative(1.5), 4.0, 1e-12);
+    assert_eq!(spline.nth_derivative(1.5, 2), 0.0);
+    // The extrapolated basis functions still reproduce the spline
+    let from_basis: f64 = (0..3).map(|i| spline.control_points[i] * spline.basis(i, 2, 1.5)).sum();
+    assert_is_close!(from_basis, spline.eval(1.5), 1e-12);
+    // Inside the knots nothing changes
+    assert_is_close!(spline.eval(0.5), 0.25 + 2.0 * 0.5 + 4.0 * 0.25, 1e-12);
+}
+
+#[test]
+fn spline_fit_pass() {
+    let xs: Vector = Vector::new((0..60).map(|i| -2.0 + 5.0 * i as f64 / 59.0).collect());
+    let ys: Vector = Vector::new(xs.elements.iter().map(|x| x.sin()).collect());
+
+    let mut spline: BSpline = BSpline::fit(&xs, &ys, 3, 12);
+
+    assert_eq!(spline.control_points.len(), 12);
+    assert_eq!(spline.domain, (-2.0, 3.0));
+    for x in [-2.0, -1.3, 0.0, 0.7, 2.2, 3.0] {
+        assert_is_close!(spline.eval(x), f64::sin(x), 1e-3);
+    }
+}
+
+#[test]
+fn spline_fit_exact_pass() {
+    // A quadratic is represented exactly by a quadratic B-spline
+    let xs: Vector = Vector::new((0..20).map(|i| i as f64 / 19.0).collect());
+    let ys: Vector = Vector::new(xs.elements.iter().map(|x| 3.0 * x * x - x + 0.5).collect());
+
+    let mut spline: BSpline = BSpline::fit(&xs, &ys, 2, 5);
+
+    for x in [0.1, 0.45, 0.8] {
+        assert_is_close!(spline.eval(x), 3.0 * x * x - x + 0.5, 1e-8);
+    }
+}
+
+#[test]
+#[should_panic]
+fn spline_fit_length_fail() {
+    BSpline::fit(&Vector::new(vec![0.0, 1.0]), &Vector::new(vec![0.0]), 2, 5);
+}
+
+#[test]
+#[should_panic]
+fn spline_fit_degenerate_fail() {
+    BSpline::fit(&Vector::new(vec![0.5, 0.5]), &Vector::new(vec![0.0, 1.0]), 2, 5);
+}
+
+#[test]
+fn spline_roughness_pass() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.0, 1.0, 4.0, 2.0]), 2);
+
+    // Second differences 2 and -5
+    assert_is_close!(spline.roughness(), 29.0, 1e-12);
+    assert_eq!(BSpline::new(Vector::new(vec![1.0, 2.0, 3.0, 4.0]), 2).roughness(), 0.0);
+
+    let gradient: Vector = spline.roughness_gradient();
+    let epsilon: f64 = 1e-6;
+    for i in 0..4 {
+        let original: f64 = spline.control_points[i];
+        spline.control_points[i] = original + epsilon;
+        let plus: f64 = spline.roughness();
+        spline.control_points[i] = original - epsilon;
+        let minus: f64 = spline.roughness();
+        spline.control_points[i] = original;
+        assert_is_close!(gradient[i], (plus - minus) / (2.0 * epsilon), 1e-5);
+    }
+}
+
+#[test]
+fn spline_project_monotone_pass() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.0, 2.0, 1.0, 3.0]), 2);
+
+    spline.project();
+    assert_eq!(spline.control_points, Vector::new(vec![0.0, 2.0, 1.0, 3.0]));
+
+    spline.shape = Shape::Increasing;
+    spline.project();
+    assert_eq!(spline.control_points, Vector::new(vec![0.0, 1.5, 1.5, 3.0]));
+
+    spline.control_points = Vector::new(vec![3.0, 1.0, 2.0, 0.0]);
+    spline.shape = Shape::Decreasing;
+    spline.project();
+    assert_eq!(spline.control_points, Vector::new(vec![3.0, 1.5, 1.5, 0.0]));
+}
+
+#[test]
+fn spline_project_convex_pass() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![1.0, 0.0, 1.0, 0.0, 2.0]), 2);
+    spline.shape = Shape::Convex;
+
+    spline.project();
+    let c: Vector = spline.control_points.clone();
+    for i in 2..c.len() {
+        assert!(c[i - 2] - 2.0 * c[i - 1] + c[i] >= -1e-12);
+    }
+    assert_is_close!(c.elements.iter().sum::<f64>(), 4.0, 1e-12);
+    // Projecting a convex sequence leaves it unchanged
+    spline.project();
+    assert_eq!(spline.control_points, c);
+
+    spline.shape = Shape::Concave;
+    spline.project();
+    let c: Vector = spline.control_points.clone();
+    for i in 2..c.len() {
+        assert!(c[i - 2] - 2.0 * c[i - 1] + c[i] <= 1e-12);
+    }
+}
+
+#[test]
+fn spline_insert_knot_pass() {
+    for &t in &[0.05, 0.3, 0.5, 0.55, 0.95] {
+        let original: BSpline = BSpline::new(Vector::new(vec![0.5, -1.0, 2.0, 0.0, 1.5, -0.5]), 3);
+        let mut spline: BSpline = original.clone();
+        spline.insert_knot(t);
+        assert_eq!(spline.control_points.len(), 7);
+        assert_eq!(spline.knots.len(), 11);
+        assert!(spline.knots.elements.windows(2).all(|w| w[0] <= w[1]));
+        let mut original: BSpline = original;
+        for i in 0..=100 {
+            let x: f64 = i as f64 / 100.0;
+            assert_is_close!(spline.eval(x), original.eval(x), 1e-12);
+        }
+    }
+
+    // The new knot is given in the domain of the B-spline
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.0, 1.0, 0.0, 1.0]), 2);
+    spline.set_domain((-2.0, 2.0));
+    let before: f64 = spline.eval(0.3);
+    spline.insert_knot(0.0);
+    assert!(spline.knots.elements.contains(&0.5));
+    assert_is_close!(spline.eval(0.3), before, 1e-12);
+}
+
+#[test]
+#[should_panic]
+fn spline_insert_knot_fail() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.0, 1.0, 2.0, 3.0]), 2);
+    spline.insert_knot(1.0);
+}
+
+#[test]
+fn spline_remove_knot_pass() {
+    let original: BSpline = BSpline::new(Vector::new(vec![0.5, -1.0, 2.0, 0.0, 1.5]), 2);
+    let mut spline: BSpline = original.clone();
+    spline.insert_knot(0.5);
+    let index: usize = spline.knots.elements.iter().position(|&k| k == 0.5).unwrap();
+    assert!(spline.remove_knot(index, 1e-8));
+    assert_eq!(spline.control_points.len(), 5);
+    for i in 0..5 {
+        assert_is_close!(spline.control_points[i], original.control_points[i], 1e-6);
+    }
+
+    // A knot that shapes the B-spline is kept, leaving the B-spline unchanged
+    let mut spline: BSpline = original.clone();
+    assert!(!spline.remove_knot(3, 1e-3));
+    assert_eq!(spline.control_points, original.control_points);
+    assert_eq!(spline.knots, original.knots);
+}
+
+#[test]
+#[should_panic]
+fn spline_remove_knot_fail() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.0, 1.0, 2.0, 3.0]), 2);
+    spline.remove_knot(2, 1.0);
+}
+
+#[test]
+fn spline_basis_matrix_pass() {
+    let knots: Vector = Vector::new(vec![0.0, 0.1, 0.3, 0.35, 0.6, 0.8, 0.9, 1.0]);
+    let ts: Vector = Vector::new(vec![-0.3, 0.0, 0.05, 0.33, 0.5, 0.99, 1.0, 1.4]);
+    for extrapolation in [Extrapolation::Zero, Extrapolation::Clamp, Extrapolation::Linear] {
+        let mut spline: BSpline = BSpline::with_knots(Vector::new(vec![1.0, -2.0, 0.5, 3.0, 1.5]), knots.clone(), 2);
+        spline.extrapolation = extrapolation;
+        let basis: Matrix = spline.basis_matrix(&ts);
+        assert_eq!(basis.shape(), (ts.len(), 5));
+        for (row, &t) in ts.elements.iter().enumerate() {
+            for i in 0..5 {
+                assert_is_close!(basis[row][i], spline.basis(i, 2, t), 1e-12);
+            }
+            let values: Vector = &basis * &spline.control_points;
+            assert_is_close!(values[row], spline.eval(t), 1e-12);
+        }
+    }
+}
+
+#[test]
+fn spline_sample_pass() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![1.0, -0.5, 2.0, 0.0, 1.5]), 2);
+    spline.set_domain((-2.0, 3.0));
+    let (xs, ys) = spline.sample(11);
+    assert_eq!(xs.len(), 11);
+    assert_eq!(ys.len(), 11);
+    assert_eq!(xs[0], -2.0);
+    assert_eq!(xs[10], 3.0);
+    for i in 0..11 {
+        assert_is_close!(xs[i], -2.0 + 0.5 * i as f64, 1e-12);
+        assert_is_close!(ys[i], spline.eval(xs[i]), 1e-12);
+    }
+}
+
+#[test]
+#[should_panic]
+fn spline_sample_fail() {
+    let spline: BSpline = BSpline::new(Vector::new(vec![1.0, 2.0, 3.0]), 2);
+    spline.sample(1);
+}
+
+#[test]
+fn spline_rational_pass() {
+    let control_points: Vector = Vector::new(vec![1.0, -0.5, 2.0, 0.0, 1.5]);
+    let weights: Vector = Vector::new(vec![1.0, 3.0, 0.5, 2.0, 1.0]);
+    let mut spline: BSpline = BSpline::rational(control_points.clone(), weights.clone(), 2);
+    let mut polynomial: BSpline = BSpline::new(control_points.clone(), 2);
+
+    // The value is the weighted average of the control points
+    let t: f64 = 0.45;
+    let numerator: f64 = (0..5).map(|i| weights[i] * control_points[i] * polynomial.basis(i, 2, t)).sum();
+    let denominator: f64 = (0..5).map(|i| weights[i] * polynomial.basis(i, 2, t)).sum();
+    assert_is_close!(spline.eval(t), numerator / denominator, 1e-12);
+
+    // Unit weights give the polynomial B-spline
+    let mut unit: BSpline = BSpline::rational(control_points.clone(), Vector::new(vec![1.0; 5]), 2);
+    assert_is_close!(unit.eval(t), polynomial.eval(t), 1e-12);
+
+    // Derivatives match finite differences, including orders above the degree
+    let epsilon: f64 = 1e-4;
+    for &t in &[0.35, 0.5, 0.62] {
+        let numerical: f64 = (spline.eval(t + epsilon) - spline.eval(t - epsilon)) / (2.0 * epsilon);
+        assert_is_close!(spline.derivative(t), numerical, 1e-4 * numerical.abs().max(1.0));
+        for k in 1..4 {
+            let numerical: f64 = (spline.nth_derivative(t + epsilon, k - 1) - spline.nth_derivative(t - epsilon, k - 1)) / (2.0 * epsilon);
+            assert_is_close!(spline.nth_derivative(t, k), numerical, 1e-3 * numerical.abs().max(1.0));
+        }
+    }
+    assert!(spline.nth_derivative(0.5, 3) != 0.0);
+
+    // The basis matrix holds the rational basis, and its rows are the gradient w.r.t. the control points
+    let basis: Matrix = spline.basis_matrix(&Vector::new(vec![0.45]));
+    assert_is_close!((&basis * &control_points)[0], spline.eval(0.45), 1e-12);
+    let gradient: Vector = ActivationFn::grad_wrt_params(&mut spline, 0.45);
+    for i in 0..5 {
+        assert_is_close!(gradient[i], basis[0][i], 1e-12);
+    }
+
+    // The gradient w.r.t. the weights matches finite differences
+    let gradient: Vector = spline.grad_wrt_weights(0.45);
+    for i in 0..5 {
+        let mut plus: BSpline = spline.clone();
+        plus.weights.as_mut().unwrap()[i] += epsilon;
+        let mut minus: BSpline = spline.clone();
+        minus.weights.as_mut().unwrap()[i] -= epsilon;
+        assert_is_close!(gradient[i], (plus.eval(0.45) - minus.eval(0.45)) / (2.0 * epsilon), 1e-7);
+    }
+    assert_eq!(polynomial.grad_wrt_weights(0.45), Vector::zeros(5));
+}
+
+#[test]
+fn spline_rational_insert_knot_pass() {
+    let mut original: BSpline = BSpline::rational(Vector::new(vec![1.0, -0.5, 2.0, 0.0, 1.5]), Vector::new(vec![1.0, 3.0, 0.5, 2.0, 1.0]), 3);
+    let mut spline: BSpline = original.clone();
+    spline.insert_knot(0.55);
+    assert_eq!(spline.weights.as_ref().unwrap().len(), 6);
+    for i in 0..=100 {
+        let x: f64 = i as f64 / 100.0;
+        assert_is_close!(spline.eval(x), original.eval(x), 1e-12);
+    }
+}
+
+#[test]
+#[should_panic]
+fn spline_rational_fail() {
+    BSpline::rational(Vector::new(vec![1.0, 2.0, 3.0]), Vector::new(vec![1.0, 0.0, 1.0]), 2);
+}
+
+#[test]
+fn spline_serde_pass() {
+    let mut spline: BSpline = BSpline::new(Vector::new(vec![0.0, 1.0, 4.0, 2.0]), 2);
+    spline.extrapolation = Extrapolation::Clamp;
+    spline.weights = Some(Vector::new(vec![1.0, 2.0, 1.0, 0.5]));
+    let value: f64 = spline.eval(0.4);
+
+    let json: String = serde_json::to_string(&spline).unwrap();
+    let mut loaded: BSpline = serde_json::from_str(&json).unwrap();
+
+    assert!(loaded.memo.is_empty());
+    assert_eq!(loaded.control_points, spline.control_points);
+    assert_eq!(loaded.knots, spline.knots);
+    assert_eq!(loaded.weights, spline.weights);
+    assert_eq!(loaded.extrapolation, Extrapolation::Clamp);
+    assert_is_close!(loaded.eval(0.4), value, 1e-12);
+}
@@ -0,0 +1,137 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::{vector::Vector, spline::BSpline, activation::ActivationFn, symbolic::{Symbolic, SymbolicFn, suggest}, edge::Edge};
+
+fn samples(f: impl Fn(f64) -> f64) -> (Vector, Vector) {
+    let xs: Vector = Vector::new((0..41).map(|i| i as f64 / 40.0).collect());
+    let ys: Vector = Vector::new(xs.elements.iter().map(|x| f(*x)).collect());
+    (xs, ys)
+}
+
+#[test]
+fn symbolic_fn_derivative_pass() {
+    let epsilon: f64 = 1e-6;
+    for function in SymbolicFn::ALL {
+        for x in [-0.7, 0.3, 1.2] {
+            let numerical: f64 = (function.eval(x + epsilon) - function.eval(x - epsilon)) / (2.0 * epsilon);
+            assert_is_close!(function.derivative(x), numerical, 1e-5);
+        }
+    }
+    // Clamped outside the domain of the logarithm, square root, and reciprocal
+    assert_eq!(SymbolicFn::Sqrt.eval(-1.0), 0.0);
+    assert_eq!(SymbolicFn::Log.derivative(-1.0), 0.0);
+    assert!(SymbolicFn::Log.eval(-1.0).is_finite());
+    assert_eq!(SymbolicFn::Reciprocal.eval(0.0), 100.0);
+    assert_eq!(SymbolicFn::Reciprocal.eval(-1e-3), -100.0);
+    assert_eq!(SymbolicFn::Reciprocal.derivative(1e-3), 0.0);
+}
+
+#[test]
+fn symbolic_fit_pass() {
+    let (xs, ys) = samples(|x| 0.5 * (3.0 * x - 1.0).sin() + 2.0);
+    let (symbolic, r2) = Symbolic::fit(SymbolicFn::Sin, &xs, &ys, (0.0, 1.0));
+
+    assert!(r2 > 0.9999);
+    for (x, y) in xs.elements.iter().zip(ys.elements.iter()) {
+        assert_is_close!(symbolic.clone().eval(*x), *y, 1e-2);
+    }
+    assert_eq!(symbolic.domain, (0.0, 1.0));
+}
+
+#[test]
+fn symbolic_suggest_pass() {
+    let (xs, ys) = samples(|x| 3.0 / (2.0 * x + 1.0) - 1.0);
+
+    let ranking: Vec<(Symbolic, f64)> = suggest(&SymbolicFn::ALL, &xs, &ys, (0.0, 1.0));
+
+    // Every candidate is fitted, and the fits are ranked by R²
+    assert_eq!(ranking.len(), SymbolicFn::ALL.len());
+    assert_eq!(ranking[0].0.function, SymbolicFn::Reciprocal);
+    assert!(ranking[0].1 > 0.9999);
+    assert!(ranking.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    for (x, y) in xs.elements.iter().zip(ys.elements.iter()) {
+        assert_is_close!(ranking[0].0.value(*x), *y, 1e-2);
+    }
+    assert!(suggest(&[], &xs, &ys, (0.0, 1.0)).is_empty());
+}
+
+#[test]
+fn symbolic_grad_pass() {
+    let mut symbolic: Symbolic = Symbolic::new(SymbolicFn::Tanh, Vector::new(vec![1.5, -0.2, 2.0, 0.3]), (0.0, 1.0));
+    let t: f64 = 0.4;
+    let gradient: Vector = symbolic.grad_wrt_params(t);
+
+    let epsilon: f64 = 1e-6;
+    for j in 0..4 {
+        let original: f64 = symbolic.params[j];
+        symbolic.params[j] = original + epsilon;
+        let plus: f64 = symbolic.eval(t);
+        symbolic.params[j] = original - epsilon;
+        let minus: f64 = symbolic.eval(t);
+        symbolic.params[j] = original;
+        assert_is_close!(gradient[j], (plus - minus) / (2.0 * epsilon), 1e-6);
+    }
+    let numerical: f64 = (symbolic.eval(t + epsilon) - symbolic.eval(t - epsilon)) / (2.0 * epsilon);
+    assert_is_close!(symbolic.grad_wrt_input(t), numerical, 1e-6);
+}
+
+#[test]
+fn symbolic_symbolify_pass() {
+    let (xs, ys) = samples(|x| 2.0 * x * x - 1.0);
+    let mut edge: Edge = Edge::new(0, 0, BSpline::fit(&xs, &ys, 2, 6), 1);
+    let before: f64 = edge.forward(0.3);
+
+    let (function, r2) = edge.symbolify(&SymbolicFn::ALL);
+
+    assert_eq!(function, SymbolicFn::Square);
+    assert!(r2 > 0.9999);
+    assert!(edge.is_symbolic());
+    assert_eq!(edge.gradient.len(), 6);
+    assert_is_close!(edge.forward(0.3), before, 1e-3);
+
+    // The affine parameters remain trainable
+    edge.backward(0.3, 1.0).unwrap();
+    edge.update_weights(0.1).unwrap();
+    assert_ne!(edge.activation.params(), &Symbolic::fit(SymbolicFn::Square, &xs, &ys, (0.0, 1.0)).0.params);
+}
+
+#[test]
+fn symbolic_suggest_symbolic_pass() {
+    let (xs, ys) = samples(|x| (4.0 * x - 2.0).atan());
+    let mut edge: Edge = Edge::new(0, 0, BSpline::fit(&xs, &ys, 3, 12), 1);
+    let before: Vector = edge.weights();
+
+    let ranking: Vec<(Symbolic, f64)> = edge.suggest_symbolic(&[SymbolicFn::Square, SymbolicFn::Arctan, SymbolicFn::Exp]);
+
+    assert_eq!(ranking[0].0.function, SymbolicFn::Arctan);
+    assert!(ranking[0].1 > 0.999);
+    // The edge is left unchanged, and symbolify snaps it to the best suggestion
+    assert_eq!(edge.weights(), before);
+    assert_eq!(edge.symbolify(&[SymbolicFn::Square, SymbolicFn::Arctan, SymbolicFn::Exp]), (SymbolicFn::Arctan, ranking[0].1));
+    assert_eq!(edge.activation.params(), &ranking[0].0.params);
+}
+
+#[test]
+#[should_panic]
+fn symbolic_symbolify_fail() {
+    let mut edge: Edge = Edge::standard(0, 0, 1);
+
+    edge.symbolify(&[]);
+}
+
+#[test]
+#[should_panic]
+fn symbolic_new_fail() {
+    let _ = Symbolic::new(SymbolicFn::Sin, Vector::new(vec![1.0, 0.0]), (0.0, 1.0));
+}
@@ -0,0 +1,97 @@
+use crate::builder::KANBuilder;
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, symbolic::SymbolicFn};
+use crate::error::KanError;
+use crate::kan::{KAN, share_tied_gradients};
+use crate::loss::Loss;
+use crate::optimizer::Optimizer;
+use crate::trainer::Trainer;
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.1, 0.8]), Vector::new(vec![0.4, 0.3]), Vector::new(vec![0.9, 0.5]), Vector::new(vec![0.6, 0.2])])
+}
+
+fn params(edge: &Rc<RefCell<Edge>>) -> Vector {
+    edge.borrow().activation.params().clone()
+}
+
+#[test]
+fn kan_tie_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(2).add_layer(1).seed(4).build();
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    assert_ne!(params(&edges[0]), params(&edges[1]));
+
+    kan.tie(&[edges[0].clone(), edges[1].clone()]).unwrap();
+    kan.tie(&[edges[2].clone(), edges[3].clone()]).unwrap();
+
+    assert_eq!(params(&edges[1]), params(&edges[0]));
+    assert_eq!((edges[0].borrow().tie, edges[1].borrow().tie, edges[2].borrow().tie), (Some(0), Some(0), Some(1)));
+
+    // Tying edges of two groups merges the groups, with the activation of the first edge
+    kan.tie(&[edges[3].clone(), edges[0].clone()]).unwrap();
+    for edge in edges[..4].iter() {
+        assert_eq!(edge.borrow().tie, Some(2));
+        assert_eq!(params(edge), params(&edges[3]));
+    }
+    assert_eq!(edges[4].borrow().tie, None);
+}
+
+#[test]
+fn kan_tie_fail() {
+    let kan: KAN = KANBuilder::new(2).add_layer(1).seed(4).build();
+    let edges: Vec<Rc<RefCell<Edge>>> = kan.edges();
+    edges[1].borrow_mut().symbolify(&[SymbolicFn::Identity]);
+
+    assert_eq!(kan.tie(&[edges[0].clone(), edges[1].clone()]), Err(KanError::InvalidConfig("Tied edges must have activations of the same kind with the same number of parameters.")));
+    assert_eq!(kan.tie(&[edges[0].clone()]), Err(KanError::InvalidConfig("At least two edges are needed to tie their activations.")));
+    assert!(edges.iter().all(|edge| edge.borrow().tie.is_none()));
+}
+
+#[test]
+fn share_tied_gradients_pass() {
+    let edges: Vec<Rc<RefCell<Edge>>> = (0..3).map(|i| Rc::new(RefCell::new(Edge::standard(i, 0, 0)))).collect();
+    edges[0].borrow_mut().tie = Some(5);
+    edges[2].borrow_mut().tie = Some(5);
+    let n: usize = edges[0].borrow().gradient.len();
+    let mut gradients: Vec<Vector> = (0..3).map(|i| Vector::new((0..n).map(|j| (i * n + j) as f64).collect())).collect();
+    let expected: Vector = Vector::new((0..n).map(|j| if j < n - 2 { (j + 2 * n + j) as f64 } else { j as f64 }).collect());
+
+    share_tied_gradients(&edges, &mut gradients);
+
+    // The gradients of the control points are summed, while the base and spline weights keep their own
+    assert_eq!(gradients[0], expected);
+    assert_eq!(gradients[2].elements[..n - 2], expected.elements[..n - 2]);
+    assert_eq!(gradients[2][n - 1], (3 * n - 1) as f64);
+    assert_eq!(gradients[1], Vector::new((0..n).map(|j| (n + j) as f64).collect()));
+}
+
+#[test]
+fn trainer_tie_pass() {
+    // A symmetric target f(x0) + f(x1)
+    let targets: Matrix = Matrix::new((0..4).map(|i| Vector::new(vec![inputs()[i][0].powi(2) + inputs()[i][1].powi(2)])).collect());
+    let mut trainer: Trainer = Trainer::new(KANBuilder::new(2).add_layer(1).seed(2).build(), 0.05, Loss::MeanSquaredError, 3);
+    trainer.optimizer = Optimizer::adam();
+    let edges: Vec<Rc<RefCell<Edge>>> = trainer.kan.edges();
+    trainer.kan.tie(&edges).unwrap();
+    let before: Vector = params(&edges[0]);
+
+    trainer.fit(&inputs(), &targets, 5).unwrap();
+    trainer.kan.update_grids(&inputs()).unwrap();
+    trainer.kan.partial_fit(&Vector::new(vec![1.5, 0.2]), &Vector::new(vec![2.29]), 0.01).unwrap();
+
+    // The tied edges are trained, and keep sharing their control points and knots
+    assert_ne!(params(&edges[0]), before);
+    assert_eq!(params(&edges[1]), params(&edges[0]));
+    assert_eq!(edges[1].borrow().spline().knots, edges[0].borrow().spline().knots);
+
+    let path: PathBuf = std::env::temp_dir().join(format!("rusty_kan_tie_{}.json", std::process::id()));
+    trainer.save_checkpoint(&path).unwrap();
+    let loaded: Trainer = Trainer::load_checkpoint(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(loaded.kan.edges().iter().all(|edge| edge.borrow().tie == Some(0)));
+    let json: KAN = serde_json::from_str(&serde_json::to_string(&trainer.kan).unwrap()).unwrap();
+    assert_eq!(json.edges()[1].borrow().tie, Some(0));
+}
@@ -0,0 +1,146 @@
+use crate::builder::{KANBuilder, Skip};
+use crate::data_structures::{vector::Vector, matrix::Matrix, edge::Edge, node::Node, layer::Layer, norm::{Norm, NormKind}};
+use crate::kan::KAN;
+use crate::scaler::Scaler;
+use crate::topology::TopologyError;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn inputs() -> Matrix {
+    Matrix::new(vec![Vector::new(vec![0.1, 0.8]), Vector::new(vec![0.4, 0.3]), Vector::new(vec![0.9, 0.5])])
+}
+
+fn edge(start: usize, end: usize, layer: usize) -> Rc<RefCell<Edge>> {
+    Rc::new(RefCell::new(Edge::standard(start, end, layer)))
+}
+
+#[test]
+fn kan_validate_pass() {
+    assert_eq!(KAN::standard_seeded(2, 3, 0).validate(), Ok(()));
+    assert_eq!(KAN::classifier_with_rng(3, 4, 2, &mut ChaCha8Rng::seed_from_u64(0)).validate(), Ok(()));
+    let mut kan: KAN = KANBuilder::new(2).add_mult_layer(2, 1).norm(NormKind::Layer).add_layer(3).add_layer(3).skip(Skip::Spline).add_layer(3).skip(Skip::Identity).seed(1).build();
+    kan.scaler = Some(Scaler::min_max(&inputs()));
+    assert_eq!(kan.validate(), Ok(()));
+
+    // Pruning and serialization keep the graph consistent
+    kan.update_edge_scores(&inputs()).unwrap();
+    let scores: Vec<f64> = kan.edges().iter().map(|edge| edge.borrow().score.unwrap()).collect();
+    kan.prune(scores.iter().copied().fold(f64::INFINITY, f64::min) + 1e-12).unwrap();
+    assert_eq!(kan.validate(), Ok(()));
+    let json: String = serde_json::to_string(&kan).unwrap();
+    assert_eq!(serde_json::from_str::<KAN>(&json).unwrap().validate(), Ok(()));
+}
+
+// A valid KAN with 2 inputs, 3 hidden nodes, and 1 output.
+fn valid() -> KAN {
+    let mut kan: KAN = KANBuilder::new(2).add_layer(3).add_layer(1).seed(2).build();
+    kan.scaler = Some(Scaler::min_max(&inputs()));
+    kan
+}
+
+fn incoming(kan: &KAN, layer: usize, node: usize, edge: usize) -> Rc<RefCell<Edge>> {
+    kan.layers[layer].borrow().nodes[node].borrow().incoming[edge].clone()
+}
+
+#[test]
+fn kan_validate_edges_fail() {
+    let kan: KAN = valid();
+    incoming(&kan, 1, 0, 1).borrow_mut().start = 7;
+    // The hidden node still lists the edge as outgoing, but the edge no longer starts there
+    assert_eq!(kan.validate(), Err(vec![TopologyError::DanglingOutgoing { layer: 0, node: 1, edge: 0 }, TopologyError::StartOutOfRange { layer: 1, node: 0, edge: 1, start: 7, width: 3 }]));
+
+    let kan: KAN = valid();
+    incoming(&kan, 0, 0, 1).borrow_mut().start = 4;
+    incoming(&kan, 0, 2, 0).borrow_mut().end = 0;
+    incoming(&kan, 0, 1, 0).borrow_mut().layer = 5;
+    assert_eq!(kan.validate(), Err(vec![
+        TopologyError::StartOutOfRange { layer: 0, node: 0, edge: 1, start: 4, width: 2 },
+        TopologyError::EdgeLayer { layer: 0, node: 1, edge: 0, expected: 1, found: 5 },
+        TopologyError::EdgeEnd { layer: 0, node: 2, edge: 0, found: 0 },
+    ]));
+
+    let kan: KAN = valid();
+    incoming(&kan, 0, 0, 0).borrow_mut().skip = true;
+    let output: Rc<RefCell<Node>> = kan.layers[1].borrow().nodes[0].clone();
+    let first: Rc<RefCell<Edge>> = incoming(&kan, 1, 0, 0);
+    output.borrow_mut().incoming.push(first);
+    assert_eq!(kan.validate(), Err(vec![TopologyError::SkipInFirstLayer { node: 0, edge: 0 }, TopologyError::SharedEdge { layer: 1, node: 0, edge: 3 }]));
+}
+
+#[test]
+fn kan_validate_outgoing_fail() {
+    let kan: KAN = valid();
+    kan.layers[0].borrow().nodes[1].borrow_mut().outgoing.clear();
+    kan.layers[0].borrow().nodes[0].borrow_mut().outgoing.push(edge(0, 0, 2));
+
+    assert_eq!(kan.validate(), Err(vec![TopologyError::DanglingOutgoing { layer: 0, node: 0, edge: 1 }, TopologyError::MissingOutgoing { layer: 1, node: 0, edge: 1 }]));
+}
+
+#[test]
+fn kan_validate_layers_fail() {
+    let kan: KAN = valid();
+    kan.layers[0].borrow_mut().norm = Some(Norm::new(NormKind::Layer, 2));
+    kan.layers[1].borrow().nodes[0].borrow_mut().layer = 7;
+    let errors: Vec<TopologyError> = kan.validate().unwrap_err();
+    assert_eq!(errors[0], TopologyError::NormWidth { layer: 0, expected: 3, found: 2 });
+    assert_eq!(errors[1], TopologyError::NodeLayer { layer: 1, node: 0, expected: 2, found: 7 });
+    assert_eq!(errors[2..], [0, 1, 2].map(|edge| TopologyError::EdgeLayer { layer: 1, node: 0, edge, expected: 7, found: 2 }));
+
+    let mut kan: KAN = valid();
+    kan.inputs = 1;
+    assert_eq!(kan.validate(), Err(vec![
+        TopologyError::ScalerWidth { expected: 1, found: 2 },
+        TopologyError::StartOutOfRange { layer: 0, node: 0, edge: 1, start: 1, width: 1 },
+        TopologyError::StartOutOfRange { layer: 0, node: 1, edge: 1, start: 1, width: 1 },
+        TopologyError::StartOutOfRange { layer: 0, node: 2, edge: 1, start: 1, width: 1 },
+    ]));
+
+    assert_eq!(KAN::new(vec![]).validate(), Err(vec![TopologyError::NoLayers]));
+    assert_eq!(KAN::new(vec![Rc::new(RefCell::new(Layer::new(vec![])))]).validate(), Err(vec![TopologyError::EmptyLayer { layer: 0 }]));
+}
+
+#[test]
+fn topology_error_display_pass() {
+    assert_eq!(TopologyError::StartOutOfRange { layer: 1, node: 0, edge: 2, start: 7, width: 3 }.to_string(), "Incoming edge 2 of node 0 of layer 1 starts at node 7, but the level it starts at has 3 nodes.");
+    assert_eq!(TopologyError::NoLayers.to_string(), "The KAN has no layers.");
+    assert_eq!(TopologyError::ScalerWidth { expected: 2, found: 3 }.to_string(), "The scaler has 3 features, expected 2.");
+}
+
+#[test]
+fn kan_same_topology_pass() {
+    let kan: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Layer).add_layer(2).add_layer(1).skip(Skip::Spline).seed(1).build();
+    let reseeded: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Layer).add_layer(2).add_layer(1).skip(Skip::Spline).seed(2).build();
+    assert!(kan.same_topology(&kan.deep_clone()));
+    assert!(kan.same_topology(&reseeded));
+    assert!(!kan.approx_eq(&reseeded, 1e-6));
+
+    assert!(!kan.same_topology(&KANBuilder::new(2).add_layer(3).add_layer(2).add_layer(1).skip(Skip::Spline).seed(1).build()));
+    assert!(!kan.same_topology(&KANBuilder::new(2).add_layer(3).norm(NormKind::Layer).add_layer(2).add_layer(1).seed(1).build()));
+    assert!(!kan.same_topology(&KANBuilder::new(2).add_layer(3).norm(NormKind::Batch).add_layer(2).add_layer(1).skip(Skip::Spline).seed(1).build()));
+    assert!(!KAN::standard_seeded(2, 3, 0).same_topology(&KAN::standard_seeded(3, 3, 0)));
+}
+
+#[test]
+fn kan_approx_eq_pass() {
+    let mut kan: KAN = KANBuilder::new(2).add_layer(3).norm(NormKind::Batch).add_layer(1).seed(3).build();
+    kan.scaler = Some(Scaler::min_max(&inputs()));
+    kan.update_norm_statistics(&inputs()).unwrap();
+    let copy: KAN = kan.deep_clone();
+    assert!(kan.approx_eq(&copy, 0.0));
+    let loaded: KAN = serde_json::from_str(&serde_json::to_string(&kan).unwrap()).unwrap();
+    assert!(kan.approx_eq(&loaded, 1e-12));
+
+    // Every kind of parameter counts, up to the tolerance
+    copy.edges()[2].borrow_mut().spline_weight += 1e-3;
+    assert!(kan.approx_eq(&copy, 1e-2) && !kan.approx_eq(&copy, 1e-4));
+    let copy: KAN = kan.deep_clone();
+    copy.layers[0].borrow_mut().norm.as_mut().unwrap().running_mean[1] += 1e-3;
+    assert!(!kan.approx_eq(&copy, 1e-4));
+    let mut copy: KAN = kan.deep_clone();
+    copy.scaler = Some(Scaler::z_score(&inputs()));
+    assert!(!kan.approx_eq(&copy, 1e-4));
+    copy.scaler = None;
+    assert!(!kan.approx_eq(&copy, 1e-4));
+}
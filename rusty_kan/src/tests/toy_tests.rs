@@ -0,0 +1,57 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::data_structures::vector::Vector;
+use crate::datasets::{dataset::Dataset, toy::Toy};
+
+#[test]
+fn toy_eval_pass() {
+    assert_is_close!(Toy::ExpSinSquare.eval(&Vector::new(vec![0.5, 0.0])), std::f64::consts::E, 1e-12);
+    assert_eq!(Toy::Product.eval(&Vector::new(vec![0.5, -0.5])), -0.25);
+    assert_is_close!(Toy::ExpSinFour.eval(&Vector::new(vec![0.0, 0.0, 0.0, 0.0])), 1.0, 1e-12);
+    assert_is_close!(Toy::GaussianDensity.eval(&Vector::new(vec![0.0])), 0.3989422804014327, 1e-12);
+    assert_is_close!(Toy::RelativisticMass.eval(&Vector::new(vec![2.0, 0.6])), 2.5, 1e-12);
+    assert_is_close!(Toy::Coulomb.eval(&Vector::new(vec![2.0, 3.0, 1.0])), 6.0 / (4.0 * std::f64::consts::PI), 1e-12);
+}
+
+#[test]
+#[should_panic]
+fn toy_eval_fail() {
+    Toy::Product.eval(&Vector::new(vec![1.0]));
+}
+
+#[test]
+fn toy_generate_pass() {
+    let dataset: Dataset = Toy::RelativisticMass.generate(50, 0.0, 42);
+
+    assert_eq!(dataset.inputs.shape(), (50, 2));
+    assert_eq!(dataset.targets.shape(), (50, 1));
+    for i in 0..dataset.len() {
+        let input: Vector = dataset.inputs.row(i);
+        assert!((1.0..=5.0).contains(&input[0]) && (0.0..=0.9).contains(&input[1]));
+        assert_eq!(dataset.targets[i][0], Toy::RelativisticMass.eval(&input));
+    }
+    assert_eq!(Toy::RelativisticMass.generate(50, 0.0, 42), dataset);
+}
+
+#[test]
+fn toy_generate_noise_pass() {
+    let exact: Dataset = Toy::ExpSinSquare.generate(2000, 0.0, 7);
+    let noisy: Dataset = Toy::ExpSinSquare.generate(2000, 0.1, 7);
+
+    // The inputs are the same and only the targets are perturbed
+    assert_eq!(noisy.inputs, exact.inputs);
+    let residuals: Vector = &noisy.targets.get_col(0) - &exact.targets.get_col(0);
+    assert_is_close!(residuals.mean(), 0.0, 0.01);
+    assert_is_close!(residuals.std(), 0.1, 0.01);
+}
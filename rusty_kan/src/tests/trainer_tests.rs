@@ -0,0 +1,606 @@
+macro_rules! assert_is_close {
+    ($a:expr, $b:expr, $c:expr) => {{
+        let a = $a;
+        let b = $b;
+        let c = $c;
+        assert!(
+            (a - b).abs() < c,
+            "{} and {} are not within {} precision of each other",
+            a, b, c
+        );
+    }};
+}
+
+use crate::builder::KANBuilder;
+use crate::data_structures::{spline::{Extrapolation, Shape}, rbf::GaussianRbf, symbolic::SymbolicFn, activation::Basis, vector::Vector, matrix::Matrix, edge::{Edge, InputMode, BaseFn}, node::{Node, Aggregation}, layer::Layer};
+use crate::error::KanError;
+use crate::kan::KAN;
+use crate::loss::{Loss, one_hot};
+use crate::optimizer::{Optimizer, Lbfgs};
+use crate::scheduler::Scheduler;
+use crate::scaler::Scaler;
+use crate::trainer::{Trainer, History, EarlyStopping, Regularization, PruningConfig, GridExtension};
+use std::cell::{Ref, RefCell};
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+// A single layer of output nodes, each connected to every input, so the inputs stay within the spline domain.
+fn single_layer_kan(inputs: usize, outputs: usize) -> KAN {
+    // Seeded, so training with large learning rates cannot diverge from an unlucky initialisation
+    let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(7);
+    let mut nodes: Vec<Rc<RefCell<Node>>> = Vec::new();
+    for j in 0..outputs {
+        let incoming_edges: Vec<Rc<RefCell<Edge>>> = (0..inputs).map(|i| Rc::new(RefCell::new(Edge::standard_with_rng(i, j, 0, &mut rng)))).collect();
+        nodes.push(Rc::new(RefCell::new(Node::new(incoming_edges, vec![], 0))));
+    }
+    KAN::new(vec![Rc::new(RefCell::new(Layer::new(nodes)))])
+}
+
+fn dataset() -> (Matrix, Matrix) {
+    let inputs: Matrix = Matrix::new((0..6).map(|i| Vector::new(vec![0.1 * i as f64, 0.9 - 0.1 * i as f64])).collect());
+    let targets: Matrix = Matrix::new((0..6).map(|i| Vector::new(vec![0.5 * i as f64])).collect());
+    (inputs, targets)
+}
+
+fn checkpoint_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rusty_kan_{}_{}.json", name, std::process::id()))
+}
+
+#[test]
+fn trainer_new_pass() {
+    let trainer: Trainer = Trainer::new(KAN::standard(2, 3), 0.1, Loss::MeanSquaredError, 7);
+
+    assert_eq!(trainer.epoch, 0);
+    assert_eq!(trainer.learning_rate, 0.1);
+    assert_eq!(trainer.loss, Loss::MeanSquaredError);
+    assert_eq!(trainer.optimizer, Optimizer::Sgd);
+    assert_eq!(trainer.scheduler, Scheduler::Constant);
+    assert_eq!(trainer.loader.batch_size, 1);
+    assert!(trainer.loader.shuffle);
+    assert_eq!(trainer.early_stopping, None);
+    assert_eq!(trainer.regularization, Regularization::default());
+}
+
+#[test]
+fn trainer_fit_pass() {
+    let (inputs, targets) = dataset();
+    let mut trainer: Trainer = Trainer::new(KAN::standard(2, 3), 0.01, Loss::MeanSquaredError, 7);
+
+    let history: History = trainer.fit(&inputs, &targets, 3).unwrap();
+
+    assert_eq!(history.train_loss.len(), 3);
+    assert_eq!(trainer.epoch, 3);
+}
+
+#[test]
+fn trainer_fit_fail() {
+    let (inputs, _) = dataset();
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![0.5])]);
+    let mut trainer: Trainer = Trainer::new(KAN::standard(2, 3), 0.01, Loss::MeanSquaredError, 7);
+
+    assert!(trainer.fit(&inputs, &targets, 3).is_err());
+}
+
+#[test]
+fn trainer_checkpoint_round_trip_pass() {
+    let path: PathBuf = checkpoint_path("round_trip");
+    let mut trainer: Trainer = Trainer::new(KAN::classifier(2, 3, 2), 0.05, Loss::CrossEntropy, 11);
+    trainer.regularization = Regularization { smoothness: 0.125, l1: 0.5, entropy: 0.25 };
+    trainer.kan.layers[0].borrow().set_lr_scale(0.25);
+    trainer.kan.layers[1].borrow().freeze();
+    trainer.kan.edges()[3].borrow_mut().spline_mut().set_domain((-2.5, 4.0));
+    trainer.kan.edges()[3].borrow_mut().input_mode = InputMode::Squash;
+    trainer.kan.edges()[3].borrow_mut().spline_mut().extrapolation = Extrapolation::Linear;
+    trainer.kan.edges()[3].borrow_mut().spline_mut().shape = Shape::Convex;
+    trainer.kan.edges()[4].borrow_mut().spline_mut().weights = Some(Vector::new(vec![1.0, 0.5, 2.0, 1.5, 0.75]));
+    trainer.kan.edges()[5].borrow_mut().base = BaseFn::Tanh;
+    trainer.kan.edges()[5].borrow_mut().base_weight = 0.75;
+    trainer.kan.edges()[5].borrow_mut().spline_weight = -1.5;
+    trainer.kan.edges()[6].borrow_mut().symbolify(&[SymbolicFn::Sin, SymbolicFn::Exp]);
+    trainer.kan.scaler = Some(Scaler::z_score(&dataset().0));
+    trainer.kan.edges()[2].borrow_mut().score = Some(0.375);
+
+    trainer.save_checkpoint(&path).unwrap();
+    let loaded: Trainer = Trainer::load_checkpoint(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.epoch, trainer.epoch);
+    assert_eq!(loaded.learning_rate, trainer.learning_rate);
+    assert_eq!(loaded.loss, trainer.loss);
+    assert_eq!(loaded.regularization, trainer.regularization);
+    assert_eq!(loaded.kan.snapshot(), trainer.kan.snapshot());
+    assert_eq!(loaded.kan.scaler, trainer.kan.scaler);
+    assert_eq!(loaded.kan.layers.len(), 2);
+    assert_eq!(loaded.kan.layers[0].borrow().nodes[1].borrow().outgoing.len(), 2);
+    let lr_scales: Vec<f64> = loaded.kan.edges().iter().map(|edge| edge.borrow().lr_scale).collect();
+    assert_eq!(lr_scales, vec![0.25, 0.25, 0.25, 0.25, 0.25, 0.25, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0]);
+    let frozen: Vec<bool> = loaded.kan.edges().iter().map(|edge| edge.borrow().frozen).collect();
+    assert_eq!(frozen, vec![false, false, false, false, false, false, true, true, true, true, true, true]);
+    assert_eq!(loaded.kan.edges()[3].borrow().spline().domain, (-2.5, 4.0));
+    assert_eq!(loaded.kan.edges()[3].borrow().input_mode, InputMode::Squash);
+    assert_eq!(loaded.kan.edges()[4].borrow().input_mode, InputMode::Raw);
+    assert_eq!(loaded.kan.edges()[3].borrow().spline().extrapolation, Extrapolation::Linear);
+    assert_eq!(loaded.kan.edges()[4].borrow().spline().extrapolation, Extrapolation::Zero);
+    assert_eq!(loaded.kan.edges()[3].borrow().spline().shape, Shape::Convex);
+    assert_eq!(loaded.kan.edges()[4].borrow().spline().shape, Shape::None);
+    assert_eq!(loaded.kan.edges()[4].borrow().spline().weights, Some(Vector::new(vec![1.0, 0.5, 2.0, 1.5, 0.75])));
+    assert_eq!(loaded.kan.edges()[3].borrow().spline().weights, None);
+    assert_eq!(loaded.kan.edges()[5].borrow().base, BaseFn::Tanh);
+    assert_eq!(loaded.kan.edges()[4].borrow().base, BaseFn::Silu);
+    assert!(loaded.kan.edges()[6].borrow().is_symbolic());
+    assert!(!loaded.kan.edges()[7].borrow().is_symbolic());
+    assert_eq!(loaded.kan.edges()[2].borrow().score, Some(0.375));
+    assert_eq!(loaded.kan.edges()[3].borrow().score, None);
+
+    // The loaded network computes the same function.
+    let features: Vector = Vector::new(vec![0.25, 0.5]);
+    assert_eq!(loaded.kan.predict_proba(&features).unwrap(), trainer.kan.predict_proba(&features).unwrap());
+}
+
+#[test]
+fn trainer_checkpoint_product_pass() {
+    let path: PathBuf = checkpoint_path("product");
+    let kan: KAN = KANBuilder::new(2).add_mult_layer(1, 1).add_layer(1).seed(3).build();
+    let trainer: Trainer = Trainer::new(kan, 0.05, Loss::MeanSquaredError, 5);
+
+    trainer.save_checkpoint(&path).unwrap();
+    let loaded: Trainer = Trainer::load_checkpoint(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.kan.layers[0].borrow().nodes[1].borrow().aggregation, Aggregation::Product);
+    let input: Vector = Vector::new(vec![0.3, 0.9]);
+    assert_eq!(loaded.kan.forward(&input).unwrap(), trainer.kan.forward(&input).unwrap());
+}
+
+#[test]
+fn trainer_checkpoint_rbf_pass() {
+    let path: PathBuf = checkpoint_path("rbf");
+    let kan: KAN = KANBuilder::new(2).add_layer_with_basis(3, Basis::Rbf { trainable_centers: true }).add_layer(1).seed(3).build();
+    let trainer: Trainer = Trainer::new(kan, 0.05, Loss::MeanSquaredError, 5);
+
+    trainer.save_checkpoint(&path).unwrap();
+    let loaded: Trainer = Trainer::load_checkpoint(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.kan.snapshot(), trainer.kan.snapshot());
+    let edges: Vec<Rc<RefCell<Edge>>> = loaded.kan.edges();
+    let edge: Ref<Edge> = edges[0].borrow();
+    let rbf: &GaussianRbf = edge.activation.downcast_ref::<GaussianRbf>().unwrap();
+    assert!(rbf.trainable_centers);
+    assert_eq!(rbf.domain, (0.0, 1.0));
+    assert_eq!(loaded.kan.edges()[6].borrow().spline().control_points, trainer.kan.edges()[6].borrow().spline().control_points);
+}
+
+#[test]
+fn trainer_checkpoint_resume_pass() {
+    let path: PathBuf = checkpoint_path("resume");
+    let (inputs, targets) = dataset();
+    let configure = |trainer: &mut Trainer| {
+        trainer.optimizer = Optimizer::adam();
+        trainer.scheduler = Scheduler::ExponentialDecay { gamma: 0.9 };
+        trainer.loader.batch_size = 4;
+    };
+
+    // Uninterrupted run
+    let kan: KAN = KAN::standard(2, 3);
+    let initial: Vec<Vector> = kan.snapshot();
+    let mut uninterrupted: Trainer = Trainer::new(kan, 0.01, Loss::MeanSquaredError, 3);
+    configure(&mut uninterrupted);
+    uninterrupted.fit(&inputs, &targets, 4).unwrap();
+
+    // Interrupted run from the same initial control points
+    let kan: KAN = KAN::standard(2, 3);
+    kan.restore(&initial).unwrap();
+    let mut interrupted: Trainer = Trainer::new(kan, 0.01, Loss::MeanSquaredError, 3);
+    configure(&mut interrupted);
+    interrupted.fit(&inputs, &targets, 2).unwrap();
+    interrupted.save_checkpoint(&path).unwrap();
+
+    let mut resumed: Trainer = Trainer::load_checkpoint(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(resumed.epoch, 2);
+    assert_eq!(resumed.optimizer, Optimizer::adam());
+    assert_eq!(resumed.scheduler, Scheduler::ExponentialDecay { gamma: 0.9 });
+    assert_eq!(resumed.loader.batch_size, 4);
+    assert_eq!(resumed.optimizer_state(), interrupted.optimizer_state());
+    resumed.fit(&inputs, &targets, 2).unwrap();
+
+    assert_eq!(resumed.epoch, 4);
+    assert_eq!(resumed.kan.snapshot(), uninterrupted.kan.snapshot());
+}
+
+#[test]
+fn trainer_load_checkpoint_fail() {
+    let path: PathBuf = checkpoint_path("malformed");
+    fs::write(&path, "not a checkpoint").unwrap();
+
+    let result = Trainer::load_checkpoint(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert!(result.is_err());
+    assert!(Trainer::load_checkpoint(checkpoint_path("missing")).is_err());
+
+    // A checkpoint of another version of the format is rejected
+    let path: PathBuf = checkpoint_path("version");
+    Trainer::new(KAN::standard(2, 3), 0.1, Loss::MeanSquaredError, 7).save_checkpoint(&path).unwrap();
+    fs::write(&path, fs::read_to_string(&path).unwrap().replace("\"version\":1", "\"version\":2")).unwrap();
+    let result = Trainer::load_checkpoint(&path);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(result.unwrap_err(), KanError::Format("The checkpoint was written in an unsupported version of the format."));
+}
+
+#[test]
+fn trainer_cross_entropy_pass() {
+    let input: Vector = Vector::new(vec![0.3, 0.6]);
+    let inputs: Matrix = Matrix::new(vec![input.clone()]);
+    let targets: Matrix = Matrix::new(vec![one_hot(1, 3).unwrap()]);
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(2, 3), 0.5, Loss::CrossEntropy, 0);
+
+    let initial_loss: f64 = trainer.evaluate(&inputs, &targets).unwrap();
+    trainer.fit(&inputs, &targets, 100).unwrap();
+
+    assert!(trainer.evaluate(&inputs, &targets).unwrap() < initial_loss);
+    assert_eq!(trainer.kan.predict_class(input).unwrap(), 1);
+}
+
+#[test]
+fn trainer_batch_pass() {
+    let inputs: Matrix = Matrix::new((0..5).map(|i| Vector::new(vec![0.1 + 0.15 * i as f64])).collect());
+    let targets: Matrix = Matrix::new((0..5).map(|i| Vector::new(vec![0.5 + 0.1 * i as f64])).collect());
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(1, 1), 0.5, Loss::MeanSquaredError, 0);
+    trainer.loader.batch_size = 2;
+
+    let history: History = trainer.fit(&inputs, &targets, 10).unwrap();
+
+    assert_eq!(history.train_loss.len(), 10);
+    assert!(history.validation_loss.is_empty());
+    assert!(history.train_loss[9] < history.train_loss[0]);
+
+    trainer.loader.batch_size = 0;
+    assert!(trainer.fit(&inputs, &targets, 1).is_err());
+}
+
+#[test]
+fn trainer_drop_last_pass() {
+    let (inputs, targets) = dataset();
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(2, 1), 0.01, Loss::MeanSquaredError, 0);
+    trainer.loader.batch_size = 4;
+    trainer.loader.drop_last = true;
+
+    // The two samples of the last batch are skipped, so the loss is the mean over the first batch
+    let history: History = trainer.fit(&inputs, &targets, 1).unwrap();
+    assert_eq!(history.train_loss.len(), 1);
+
+    trainer.loader.batch_size = 8;
+    assert!(trainer.fit(&inputs, &targets, 1).is_err());
+}
+
+#[test]
+fn trainer_full_batch_pass() {
+    // With one batch and no shuffling, an epoch is a single gradient descent step on the mean loss.
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.3]), Vector::new(vec![0.5])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![1.0]), Vector::new(vec![2.0])]);
+    let kan: KAN = single_layer_kan(1, 1);
+    let edge: Rc<RefCell<Edge>> = kan.edges()[0].clone();
+    let initial: Vector = edge.borrow().weights();
+
+    let mut expected: Vector = Vector::zeros(initial.len());
+    for i in 0..inputs.rows {
+        kan.backward_features(&inputs.row(i), &targets.row(i), Loss::MeanSquaredError).unwrap();
+        expected = &expected + &(&edge.borrow().gradient * 0.5);
+    }
+    let expected: Vector = &initial - &(&expected * 0.1);
+
+    let mut trainer: Trainer = Trainer::new(kan, 0.1, Loss::MeanSquaredError, 0);
+    trainer.loader.batch_size = 2;
+    trainer.loader.shuffle = false;
+    trainer.fit(&inputs, &targets, 1).unwrap();
+
+    for i in 0..expected.len() {
+        assert_is_close!(edge.borrow().weights()[i], expected[i], 1e-12);
+    }
+}
+
+#[test]
+fn trainer_fit_weighted_pass() {
+    // A sample with weight 2 counts as much as two copies of it
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.3]), Vector::new(vec![0.5])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![1.0]), Vector::new(vec![2.0])]);
+    let duplicated_inputs: Matrix = Matrix::new(vec![inputs.row(0), inputs.row(0), inputs.row(1)]);
+    let duplicated_targets: Matrix = Matrix::new(vec![targets.row(0), targets.row(0), targets.row(1)]);
+
+    let mut weighted: Trainer = Trainer::new(single_layer_kan(1, 1), 0.1, Loss::MeanSquaredError, 0);
+    weighted.loader.batch_size = 3;
+    let weighted_history: History = weighted.fit_weighted(&inputs, &targets, &Vector::new(vec![2.0, 1.0]), 3).unwrap();
+    let mut duplicated: Trainer = Trainer::new(single_layer_kan(1, 1), 0.1, Loss::MeanSquaredError, 0);
+    duplicated.loader.batch_size = 3;
+    let duplicated_history: History = duplicated.fit(&duplicated_inputs, &duplicated_targets, 3).unwrap();
+
+    for (a, b) in weighted_history.train_loss.iter().zip(duplicated_history.train_loss.iter()) {
+        assert_is_close!(a, b, 1e-12);
+    }
+    let (a, b): (Vector, Vector) = (weighted.kan.edges()[0].borrow().weights(), duplicated.kan.edges()[0].borrow().weights());
+    for i in 0..a.len() {
+        assert_is_close!(a[i], b[i], 1e-12);
+    }
+}
+
+#[test]
+fn trainer_fit_weighted_zero_pass() {
+    // Batches of samples with weight 0 are skipped
+    let (inputs, targets) = dataset();
+    let mut weighted: Trainer = Trainer::new(single_layer_kan(2, 1), 0.1, Loss::MeanSquaredError, 0);
+    weighted.loader.shuffle = false;
+    weighted.fit_weighted(&inputs, &targets, &Vector::new(vec![1.0, 1.0, 0.0, 0.0, 0.0, 0.0]), 2).unwrap();
+    let mut truncated: Trainer = Trainer::new(single_layer_kan(2, 1), 0.1, Loss::MeanSquaredError, 0);
+    truncated.loader.shuffle = false;
+    truncated.fit(&inputs.slice_rows(0..2), &targets.slice_rows(0..2), 2).unwrap();
+
+    assert_eq!(weighted.kan.edges()[1].borrow().weights(), truncated.kan.edges()[1].borrow().weights());
+}
+
+#[test]
+fn trainer_fit_weighted_fail() {
+    let (inputs, targets) = dataset();
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(2, 1), 0.1, Loss::MeanSquaredError, 0);
+
+    assert!(trainer.fit_weighted(&inputs, &targets, &Vector::new(vec![1.0; 5]), 1).is_err());
+    assert!(trainer.fit_weighted(&inputs, &targets, &Vector::new(vec![1.0, -1.0, 1.0, 1.0, 1.0, 1.0]), 1).is_err());
+    assert!(trainer.fit_weighted(&inputs, &targets, &Vector::zeros(6), 1).is_err());
+}
+
+#[test]
+fn trainer_sparsity_pass() {
+    // Only the first input matters, so the sparsity regularization shrinks the edges of the second
+    let inputs: Matrix = Matrix::new((0..8).map(|i| Vector::new(vec![0.1 * i as f64 + 0.1, 0.9 - 0.1 * i as f64])).collect());
+    let targets: Matrix = Matrix::new((0..8).map(|i| Vector::new(vec![inputs[i][0] * inputs[i][0]])).collect());
+    let train = |l1: f64| -> f64 {
+        let mut trainer: Trainer = Trainer::new(KAN::standard_seeded(2, 3, 7), 0.05, Loss::MeanSquaredError, 0);
+        trainer.regularization = Regularization { l1, entropy: l1, ..Regularization::default() };
+        trainer.fit(&inputs, &targets, 50).unwrap();
+        trainer.kan.sparsity(&inputs, 1.0, 0.0).unwrap()
+    };
+
+    assert!(train(0.05) < train(0.0));
+}
+
+#[test]
+fn trainer_fit_with_pruning_pass() {
+    let inputs: Matrix = Matrix::new((0..8).map(|i| Vector::new(vec![0.1 * i as f64 + 0.1, 0.9 - 0.1 * i as f64])).collect());
+    let targets: Matrix = Matrix::new((0..8).map(|i| Vector::new(vec![inputs[i][0] * inputs[i][0]])).collect());
+    let mut trainer: Trainer = Trainer::new(KAN::standard_seeded(2, 3, 7), 0.05, Loss::MeanSquaredError, 0);
+    trainer.optimizer = Optimizer::adam();
+    let config: PruningConfig = PruningConfig { sparsify_epochs: 20, l1: 0.05, entropy: 0.05, threshold: 0.2, grid: Some(8), finetune_epochs: 10 };
+
+    let history: History = trainer.fit_with_pruning(&inputs, &targets, config).unwrap();
+
+    assert_eq!(history.train_loss.len(), 30);
+    assert!(history.train_loss.iter().all(|loss| loss.is_finite()));
+    assert_eq!(trainer.epoch, 30);
+    assert!(trainer.kan.edges().len() < 9);
+    assert!(trainer.kan.edges().iter().all(|edge| edge.borrow().spline().control_points.len() == 8));
+    // The regularization of the trainer is restored for the fine-tuning
+    assert_eq!(trainer.regularization, Regularization::default());
+}
+
+#[test]
+fn trainer_fit_with_grid_extension_pass() {
+    let inputs: Matrix = Matrix::new((0..8).map(|i| Vector::new(vec![0.1 * i as f64 + 0.1, 0.9 - 0.1 * i as f64])).collect());
+    let targets: Matrix = Matrix::new((0..8).map(|i| Vector::new(vec![inputs[i][0] * inputs[i][1]])).collect());
+    let train = |schedule: &[GridExtension]| -> Trainer {
+        let mut trainer: Trainer = Trainer::new(KAN::standard_seeded(2, 3, 3), 0.05, Loss::MeanSquaredError, 0);
+        trainer.optimizer = Optimizer::adam();
+        let history: History = trainer.fit_with_grid_extension(&inputs, &targets, 20, schedule).unwrap();
+        assert_eq!(history.train_loss.len(), 20);
+        trainer
+    };
+
+    // Extending the grids after the last epoch preserves the learned functions
+    let coarse: Trainer = train(&[]);
+    let extended: Trainer = train(&[GridExtension { epoch: 20, grid: 10 }]);
+    assert!(extended.kan.edges().iter().all(|edge| edge.borrow().spline().control_points.len() == 10));
+    for i in 0..8 {
+        assert_is_close!(extended.kan.forward(&inputs.row(i)).unwrap(), coarse.kan.forward(&inputs.row(i)).unwrap(), 0.1);
+    }
+
+    // The optimizer continues with the moments mapped onto the refined grids
+    let trainer: Trainer = train(&[GridExtension { epoch: 5, grid: 10 }, GridExtension { epoch: 10, grid: 20 }]);
+    assert_eq!(trainer.epoch, 20);
+    assert_eq!(trainer.optimizer_state().step, 20 * 8);
+    assert!(trainer.kan.edges().iter().all(|edge| edge.borrow().spline().control_points.len() == 20));
+    assert!(trainer.optimizer_state().first_moments.iter().all(|moments| moments.len() == 20 + 2));
+    assert!(trainer.optimizer_state().second_moments.iter().all(|moments| moments.iter().all(|&v| v >= 0.0)));
+    assert!(trainer.evaluate(&inputs, &targets).unwrap() < coarse.evaluate(&inputs, &targets).unwrap());
+}
+
+#[test]
+fn trainer_fit_with_grid_extension_fail() {
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.2, 0.4])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![0.1])]);
+    let mut trainer: Trainer = Trainer::new(KAN::standard_seeded(2, 3, 7), 0.05, Loss::MeanSquaredError, 0);
+    let num_points: usize = trainer.kan.edges()[0].borrow().spline().control_points.len();
+
+    assert!(trainer.fit_with_grid_extension(&inputs, &targets, 10, &[GridExtension { epoch: 5, grid: 10 }, GridExtension { epoch: 5, grid: 20 }]).is_err());
+    assert!(trainer.fit_with_grid_extension(&inputs, &targets, 10, &[GridExtension { epoch: 20, grid: 10 }]).is_err());
+    assert!(trainer.fit_with_grid_extension(&inputs, &targets, 10, &[GridExtension { epoch: 5, grid: num_points - 1 }]).is_err());
+    assert_eq!(trainer.epoch, 0);
+}
+
+#[test]
+fn trainer_fit_lbfgs_pass() {
+    let (inputs, targets) = dataset();
+    let mut trainer: Trainer = Trainer::new(KAN::standard_seeded(2, 3, 7), 0.01, Loss::MeanSquaredError, 0);
+    let mut sgd: Trainer = Trainer::new(KAN::standard_seeded(2, 3, 7), 0.01, Loss::MeanSquaredError, 0);
+    trainer.regularization.smoothness = 1e-4;
+    trainer.kan.edges()[0].borrow_mut().freeze();
+    let frozen: Vector = trainer.kan.edges()[0].borrow().weights();
+    let initial_loss: f64 = trainer.evaluate(&inputs, &targets).unwrap();
+
+    let history: History = trainer.fit_lbfgs(&inputs, &targets, 20, Lbfgs::default()).unwrap();
+    sgd.fit(&inputs, &targets, 20).unwrap();
+
+    assert_eq!(history.train_loss.len(), 20);
+    assert_eq!(trainer.epoch, 20);
+    assert!(history.train_loss.windows(2).all(|pair| pair[1] <= pair[0]));
+    assert!(history.train_loss[19] < initial_loss);
+    assert!(trainer.evaluate(&inputs, &targets).unwrap() < sgd.evaluate(&inputs, &targets).unwrap());
+    assert_eq!(trainer.kan.edges()[0].borrow().weights(), frozen);
+}
+
+#[test]
+fn trainer_fit_lbfgs_fail() {
+    let (inputs, _) = dataset();
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![0.5])]);
+    let mut trainer: Trainer = Trainer::new(KAN::standard_seeded(2, 3, 7), 0.01, Loss::MeanSquaredError, 0);
+
+    assert!(trainer.fit_lbfgs(&inputs, &targets, 5, Lbfgs::default()).is_err());
+
+    let (inputs, targets) = dataset();
+    trainer.kan.edges()[0].borrow_mut().spline_mut().shape = Shape::Increasing;
+    assert!(trainer.fit_lbfgs(&inputs, &targets, 5, Lbfgs::default()).is_err());
+    assert_eq!(trainer.epoch, 0);
+}
+
+#[test]
+fn trainer_adam_pass() {
+    let inputs: Matrix = Matrix::new((0..5).map(|i| Vector::new(vec![0.1 + 0.15 * i as f64])).collect());
+    let targets: Matrix = Matrix::new((0..5).map(|i| Vector::new(vec![0.5 + 0.1 * i as f64])).collect());
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(1, 1), 0.05, Loss::MeanSquaredError, 0);
+    trainer.optimizer = Optimizer::adam();
+
+    let initial_loss: f64 = trainer.evaluate(&inputs, &targets).unwrap();
+    trainer.fit(&inputs, &targets, 20).unwrap();
+
+    assert!(trainer.evaluate(&inputs, &targets).unwrap() < initial_loss);
+    assert_eq!(trainer.optimizer_state().step, 100);
+}
+
+#[test]
+fn trainer_smoothness_pass() {
+    // Sparse noisy samples of a line
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.1]), Vector::new(vec![0.4]), Vector::new(vec![0.9])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![0.3]), Vector::new(vec![-0.2]), Vector::new(vec![0.8])]);
+    let train = |smoothness: f64| -> f64 {
+        let mut trainer: Trainer = Trainer::new(single_layer_kan(1, 1), 0.1, Loss::MeanSquaredError, 0);
+        trainer.regularization = Regularization { smoothness, ..Regularization::default() };
+        trainer.fit(&inputs, &targets, 100).unwrap();
+        trainer.kan.roughness()
+    };
+
+    assert!(train(0.5) < 0.5 * train(0.0));
+}
+
+#[test]
+fn trainer_early_stopping_pass() {
+    // The validation inputs lie outside the knots and there is no base function, so training cannot improve the validation loss.
+    let inputs: Matrix = Matrix::new((0..10).map(|i| Vector::new(vec![if i < 8 { 0.05 + 0.1 * i as f64 } else { 1.5 }])).collect());
+    let targets: Matrix = Matrix::new((0..10).map(|_| Vector::new(vec![1.0])).collect());
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(1, 1), 0.5, Loss::MeanSquaredError, 0);
+    trainer.early_stopping = Some(EarlyStopping { validation_split: 0.2, patience: 3 });
+    trainer.kan.edges()[0].borrow_mut().base = BaseFn::None;
+
+    let history: History = trainer.fit(&inputs, &targets, 200).unwrap();
+
+    assert_eq!(history.train_loss.len(), 4);
+    assert_eq!(history.validation_loss.len(), 4);
+}
+
+#[test]
+fn trainer_early_stopping_restore_pass() {
+    let inputs: Matrix = Matrix::new((0..10).map(|i| Vector::new(vec![0.05 + 0.09 * i as f64])).collect());
+    let targets: Matrix = Matrix::new((0..10).map(|i| Vector::new(vec![if i < 8 { 1.0 } else { -1.0 }])).collect());
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(1, 1), 0.5, Loss::MeanSquaredError, 0);
+    trainer.early_stopping = Some(EarlyStopping { validation_split: 0.2, patience: 3 });
+
+    let history: History = trainer.fit(&inputs, &targets, 20).unwrap();
+
+    // The best-seen control points are restored.
+    let best_loss: f64 = history.validation_loss.iter().cloned().fold(f64::INFINITY, f64::min);
+    let validation_inputs: Matrix = inputs.slice_rows(8..10);
+    let validation_targets: Matrix = targets.slice_rows(8..10);
+    assert_is_close!(trainer.evaluate(&validation_inputs, &validation_targets).unwrap(), best_loss, 1e-9);
+}
+
+#[test]
+fn trainer_invalid_split_fail() {
+    let inputs: Matrix = Matrix::new(vec![Vector::new(vec![0.5])]);
+    let targets: Matrix = Matrix::new(vec![Vector::new(vec![0.5])]);
+    let mut trainer: Trainer = Trainer::new(single_layer_kan(1, 1), 0.5, Loss::MeanSquaredError, 0);
+    trainer.early_stopping = Some(EarlyStopping { validation_split: 1.0, patience: 3 });
+
+    assert!(trainer.fit(&inputs, &targets, 10).is_err());
+}
+
+#[test]
+fn trainer_same_model_different_regimes_pass() {
+    let inputs: Matrix = Matrix::new((0..5).map(|i| Vector::new(vec![0.1 + 0.15 * i as f64])).collect());
+    let targets: Matrix = Matrix::new((0..5).map(|i| Vector::new(vec![0.5 + 0.1 * i as f64])).collect());
+    let kan: KAN = single_layer_kan(1, 1);
+
+    let mut warmup: Trainer = Trainer::new(kan.clone(), 0.5, Loss::MeanSquaredError, 0);
+    warmup.fit(&inputs, &targets, 5).unwrap();
+    let mut fine_tune: Trainer = Trainer::new(kan.clone(), 0.01, Loss::MeanSquaredError, 0);
+    fine_tune.optimizer = Optimizer::Momentum { beta: 0.9 };
+    fine_tune.fit(&inputs, &targets, 5).unwrap();
+
+    // Both trainers update the same model.
+    assert_eq!(warmup.kan.snapshot(), kan.snapshot());
+    assert_eq!(fine_tune.kan.snapshot(), kan.snapshot());
+}
+
+#[test]
+fn trainer_reproducible_pass() {
+    let (inputs, targets) = dataset();
+    let train = || {
+        let mut trainer: Trainer = Trainer::new(KAN::standard_seeded(2, 3, 42), 0.01, Loss::MeanSquaredError, 7);
+        let history: History = trainer.fit(&inputs, &targets, 3).unwrap();
+        (trainer.kan.snapshot(), history)
+    };
+
+    let (first_snapshot, first_history) = train();
+    let (second_snapshot, second_history) = train();
+
+    assert_eq!(first_snapshot, second_snapshot);
+    assert_eq!(first_history.train_loss, second_history.train_loss);
+}
+
+#[test]
+fn trainer_lr_scale_pass() {
+    // A layer with a zero learning rate scale keeps its pretrained control points.
+    let (inputs, targets) = dataset();
+    let kan: KAN = KAN::standard_seeded(2, 3, 5);
+    kan.layers[0].borrow().set_lr_scale(0.0);
+    let initial: Vec<Vector> = kan.snapshot();
+
+    let mut trainer: Trainer = Trainer::new(kan, 0.01, Loss::MeanSquaredError, 7);
+    trainer.fit(&inputs, &targets, 2).unwrap();
+
+    let snapshot: Vec<Vector> = trainer.kan.snapshot();
+    assert_eq!(snapshot[..6], initial[..6]);
+    assert_ne!(snapshot[6..], initial[6..]);
+}
+
+#[test]
+fn trainer_freeze_pass() {
+    // Only the output layer is retrained.
+    let (inputs, targets) = dataset();
+    let kan: KAN = KAN::standard_seeded(2, 3, 5);
+    kan.layers[0].borrow().freeze();
+    let initial: Vec<Vector> = kan.snapshot();
+
+    let mut trainer: Trainer = Trainer::new(kan, 0.01, Loss::MeanSquaredError, 7);
+    trainer.optimizer = Optimizer::adam();
+    trainer.fit(&inputs, &targets, 2).unwrap();
+
+    let snapshot: Vec<Vector> = trainer.kan.snapshot();
+    assert_eq!(snapshot[..6], initial[..6]);
+    assert_ne!(snapshot[6..], initial[6..]);
+
+    // Unfrozen layers are trained again.
+    trainer.kan.layers[0].borrow().unfreeze();
+    trainer.fit(&inputs, &targets, 1).unwrap();
+    assert_ne!(trainer.kan.snapshot()[..6], initial[..6]);
+}
@@ -1,4 +1,7 @@
 use crate::data_structures::{vector::Vector, matrix::Matrix};
+use crate::error::KanError;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
 #[test]
 fn vector_add_pass() {
@@ -36,6 +39,26 @@ fn vector_sub_fail() {
     let _ = a - b;
 }
 
+#[test]
+fn vector_try_pass() {
+    let a = Vector { elements: vec![1.0, 2.0, 3.0] };
+    let b = Vector { elements: vec![4.0, 5.0, 6.0] };
+
+    assert_eq!(a.try_add(&b), Ok(&a + &b));
+    assert_eq!(a.try_sub(&b), Ok(&a - &b));
+    assert_eq!(a.try_dot(&b), Ok(32.0));
+}
+
+#[test]
+fn vector_try_fail() {
+    let a = Vector { elements: vec![1.0, 2.0, 3.0] };
+    let b = Vector { elements: vec![4.0, 5.0] };
+
+    assert_eq!(a.try_add(&b), Err(KanError::mismatch("elements in the second vector of the addition", 3, 2)));
+    assert_eq!(a.try_sub(&b), Err(KanError::mismatch("elements in the second vector of the subtraction", 3, 2)));
+    assert_eq!(a.try_dot(&b), Err(KanError::mismatch("elements in the second vector of the dot product", 3, 2)));
+}
+
 #[test]
 fn vector_mul_scalar_pass() {
     let a = Vector { elements: vec![1.0, 2.0, 3.0] };
@@ -128,25 +151,76 @@ fn vector_len_pass() {
 
 #[test]
 fn vector_zeros_pass() {
-    let a = Vector::zeros(3);
+    let a: Vector = Vector::zeros(3);
 
     assert_eq!(a.elements, vec![0.0, 0.0, 0.0]);
 }
 
 #[test]
 fn vector_ones_pass() {
-    let a = Vector::ones(3);
+    let a: Vector = Vector::ones(3);
 
     assert_eq!(a.elements, vec![1.0, 1.0, 1.0]);
 }
 
 #[test]
 fn vector_random_pass() {
-    let a = Vector::random(3);
+    let a: Vector = Vector::random(3);
 
     assert_eq!(a.elements.len(), 3);
 }
 
+#[test]
+fn vector_random_with_rng_pass() {
+    let a: Vector = Vector::random_with_rng(3, &mut ChaCha8Rng::seed_from_u64(7));
+    let b: Vector = Vector::random_with_rng(3, &mut ChaCha8Rng::seed_from_u64(7));
+    let c: Vector = Vector::random_with_rng(3, &mut ChaCha8Rng::seed_from_u64(8));
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn vector_random_uniform_pass() {
+    let a: Vector = Vector::random_uniform_with_rng(100, -2.0, 3.0, &mut ChaCha8Rng::seed_from_u64(7));
+    let b: Vector = Vector::random_uniform_with_rng(100, -2.0, 3.0, &mut ChaCha8Rng::seed_from_u64(7));
+
+    assert_eq!(a, b);
+    assert!(a.iter().all(|&x| (-2.0..3.0).contains(&x)));
+}
+
+#[test]
+#[should_panic]
+fn vector_random_uniform_fail() {
+    let _: Vector = Vector::random_uniform(3, 1.0, 1.0);
+}
+
+#[test]
+fn vector_random_normal_pass() {
+    let a: Vector = Vector::random_normal_with_rng(10000, 5.0, 2.0, &mut ChaCha8Rng::seed_from_u64(7));
+    let b: Vector = Vector::random_normal_with_rng(10000, 5.0, 2.0, &mut ChaCha8Rng::seed_from_u64(7));
+
+    assert_eq!(a, b);
+    assert!((a.mean() - 5.0).abs() < 0.1);
+    assert!((a.std() - 2.0).abs() < 0.1);
+}
+
+#[test]
+#[should_panic]
+fn vector_random_normal_fail() {
+    let _: Vector = Vector::random_normal(3, 0.0, -1.0);
+}
+
+#[test]
+fn vector_outer_pass() {
+    let a: Vector = Vector::new(vec![1.0, 2.0]);
+    let b: Vector = Vector::new(vec![3.0, 4.0, 5.0]);
+
+    let c: Matrix = a.outer(&b);
+
+    assert_eq!(c, Matrix::new(vec![Vector::new(vec![3.0, 4.0, 5.0]), Vector::new(vec![6.0, 8.0, 10.0])]));
+}
+
 #[test]
 fn vector_to_matrix_pass() {
     let a = Vector { elements: vec![1.0, 2.0, 3.0] };
@@ -160,4 +234,168 @@ fn vector_push_pass() {
     let mut a = Vector { elements: vec![1.0, 2.0, 3.0] };
     a.push(4.0);
     assert_eq!(a.elements, vec![1.0, 2.0, 3.0, 4.0]);
-}
\ No newline at end of file
+}
+#[test]
+fn vector_f32_pass() {
+    let a: Vector<f32> = Vector::new(vec![1.0, 2.0, 3.0]);
+    let b: Vector<f32> = Vector::ones(3);
+
+    assert_eq!((&a + &b).elements, vec![2.0f32, 3.0, 4.0]);
+    assert_eq!((&a * 2.0).elements, vec![2.0f32, 4.0, 6.0]);
+    assert_eq!(a.dot(&b), 6.0f32);
+    assert_eq!(a.to_matrix().shape(), (1, 3));
+}
+
+#[test]
+fn vector_cast_pass() {
+    let a: Vector = Vector::new(vec![0.1, 1.5, -2.0]);
+    let single: Vector<f32> = a.cast();
+
+    assert_eq!(single.elements, vec![0.1f32, 1.5, -2.0]);
+    assert!((single.cast::<f64>()[0] - 0.1).abs() < 1e-7);
+    assert_eq!(single.cast::<f64>()[1], 1.5);
+}
+
+#[test]
+fn vector_reductions_pass() {
+    let a: Vector = Vector::new(vec![2.0, -1.0, 4.0, -1.0]);
+
+    assert_eq!(a.sum(), 4.0);
+    assert_eq!(a.mean(), 1.0);
+    assert!((a.std() - 4.5f64.sqrt()).abs() < 1e-12);
+    assert_eq!(a.min(), -1.0);
+    assert_eq!(a.max(), 4.0);
+    assert_eq!(a.argmin(), 1);
+    assert_eq!(a.argmax(), 2);
+    assert_eq!(a.norm(1.0), 8.0);
+    assert!((a.norm(2.0) - 22.0f64.sqrt()).abs() < 1e-12);
+    assert_eq!(a.norm(f64::INFINITY), 4.0);
+    assert_eq!(Vector::<f64>::new(vec![]).sum(), 0.0);
+}
+
+#[test]
+fn vector_reductions_nan_pass() {
+    let a: Vector = Vector::new(vec![f64::NAN, 3.0, f64::NAN, -2.0]);
+
+    assert_eq!(a.argmax(), 1);
+    assert_eq!(a.argmin(), 3);
+    assert_eq!(a.max(), 3.0);
+}
+
+#[test]
+#[should_panic]
+fn vector_mean_fail() {
+    Vector::<f64>::new(vec![]).mean();
+}
+
+#[test]
+#[should_panic]
+fn vector_norm_fail() {
+    Vector::new(vec![1.0, 2.0]).norm(0.5);
+}
+
+#[test]
+fn vector_into_iter_pass() {
+    let a: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+
+    assert_eq!((&a).into_iter().copied().collect::<Vec<f64>>(), vec![1.0, 2.0, 3.0]);
+    assert_eq!(a.into_iter().collect::<Vec<f64>>(), vec![1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn vector_concat_pass() {
+    let a: Vector = Vector::new(vec![1.0, 2.0]);
+    let b: Vector = Vector::new(vec![]);
+    let c: Vector = Vector::new(vec![3.0]);
+
+    assert_eq!(Vector::concat(&[a, b, c]), Vector::new(vec![1.0, 2.0, 3.0]));
+}
+
+#[test]
+fn vector_iter_pass() {
+    let mut a: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+
+    assert_eq!(a.iter().copied().collect::<Vec<f64>>(), vec![1.0, 2.0, 3.0]);
+    for x in a.iter_mut() {
+        *x *= 2.0;
+    }
+    for x in &mut a {
+        *x += 1.0;
+    }
+    let mut sum: f64 = 0.0;
+    for x in &a {
+        sum += x;
+    }
+
+    assert_eq!(a.elements, vec![3.0, 5.0, 7.0]);
+    assert_eq!(sum, 15.0);
+}
+
+#[test]
+fn vector_map_pass() {
+    let a: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+
+    let squared: Vector = a.map(|x| x * x);
+
+    assert_eq!(squared.elements, vec![1.0, 4.0, 9.0]);
+    assert_eq!(a.elements, vec![1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn vector_sum_stable_pass() {
+    // 1 followed by many values below half an ulp of 1, which a naive sum drops
+    let mut elements: Vec<f64> = vec![1.0];
+    elements.extend(vec![1e-16; 10000]);
+    let a: Vector = Vector::new(elements);
+
+    assert_eq!(a.sum(), 1.0);
+    assert!((a.sum_stable() - (1.0 + 1e-12)).abs() < 1e-15);
+    assert_eq!(Vector::new(vec![1e100, 1.0, -1e100]).sum_stable(), 1.0);
+}
+
+#[test]
+fn vector_dot_stable_pass() {
+    let a: Vector = Vector::new(vec![1e100, 1.0, -1e100]);
+    let b: Vector = Vector::ones(3);
+
+    assert_eq!(a.dot(&b), 0.0);
+    assert_eq!(a.dot_stable(&b), 1.0);
+    assert_eq!(Vector::new(vec![1.0, 2.0]).dot_stable(&Vector::new(vec![3.0, 4.0])), 11.0);
+}
+
+#[test]
+#[should_panic]
+fn vector_dot_stable_fail() {
+    Vector::new(vec![1.0, 2.0]).dot_stable(&Vector::new(vec![1.0]));
+}
+
+#[test]
+fn vector_assign_ops_pass() {
+    let mut a: Vector = Vector::new(vec![1.0, 2.0, 3.0]);
+    let b: Vector = Vector::new(vec![0.5, 0.5, 1.0]);
+
+    a += &b;
+    assert_eq!(a.elements, vec![1.5, 2.5, 4.0]);
+    a -= &b;
+    assert_eq!(a.elements, vec![1.0, 2.0, 3.0]);
+    a *= 2.0;
+    assert_eq!(a.elements, vec![2.0, 4.0, 6.0]);
+    a /= 4.0;
+    assert_eq!(a.elements, vec![0.5, 1.0, 1.5]);
+    a.axpy(-2.0, &b);
+    assert_eq!(a.elements, vec![-0.5, 0.0, -0.5]);
+}
+
+#[test]
+#[should_panic]
+fn vector_axpy_fail() {
+    let mut a: Vector = Vector::new(vec![1.0, 2.0]);
+    a.axpy(1.0, &Vector::new(vec![1.0]));
+}
+
+#[test]
+#[should_panic]
+fn vector_div_assign_fail() {
+    let mut a: Vector = Vector::new(vec![1.0, 2.0]);
+    a /= 0.0;
+}
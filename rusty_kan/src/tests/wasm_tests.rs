@@ -0,0 +1,15 @@
+use crate::data_structures::vector::Vector;
+use crate::kan::KAN;
+use crate::wasm::WasmKAN;
+
+#[test]
+fn wasm_forward_pass() {
+    let kan: KAN = KAN::classifier_with_rng(2, 3, 2, &mut rand::thread_rng());
+    let model: WasmKAN = WasmKAN::from_json(&serde_json::to_string(&kan).unwrap()).unwrap();
+    assert_eq!(model.num_inputs(), 2);
+    assert_eq!(model.num_outputs(), 2);
+
+    let input: Vec<f64> = vec![0.2, 0.7];
+    assert_eq!(model.forward(&input).unwrap(), kan.compile().forward(&Vector::new(input.clone())).unwrap().elements);
+    assert_eq!(model.predict_class(&input).unwrap(), kan.compile().predict_class(&Vector::new(input)).unwrap());
+}
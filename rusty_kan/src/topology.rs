@@ -0,0 +1,229 @@
+use crate::data_structures::{vector::Vector, edge::Edge, node::Node};
+use crate::kan::KAN;
+use crate::scaler::Scaler;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A structural error of the graph of a KAN, found by `KAN::validate`.
+/// Layers are indexed as in `KAN::layers`, nodes by their position in their layer, and edges by their position in the incoming or outgoing edges of their node.
+///
+/// `Display` prints a sentence that locates the error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopologyError {
+    /// The KAN has no layers.
+    NoLayers,
+    /// A layer has no nodes.
+    EmptyLayer { layer: usize },
+    /// A node has a `layer` field that differs from the other nodes of its layer, or does not increase by one from the previous layer.
+    NodeLayer { layer: usize, node: usize, expected: usize, found: usize },
+    /// An incoming edge has a `layer` field that differs from the `layer` field of its node.
+    EdgeLayer { layer: usize, node: usize, edge: usize, expected: usize, found: usize },
+    /// An incoming edge has an `end` that is not the index of its node.
+    EdgeEnd { layer: usize, node: usize, edge: usize, found: usize },
+    /// An incoming edge starts at a node that does not exist in the level it starts at, whose width is known.
+    StartOutOfRange { layer: usize, node: usize, edge: usize, start: usize, width: usize },
+    /// A skip edge in the first layer, which has no level two layers back.
+    SkipInFirstLayer { node: usize, edge: usize },
+    /// The same edge is an incoming edge of more than one node, or more than once of the same node.
+    SharedEdge { layer: usize, node: usize, edge: usize },
+    /// An incoming edge is missing from the outgoing edges of the node it starts at.
+    MissingOutgoing { layer: usize, node: usize, edge: usize },
+    /// An outgoing edge of a node is not an incoming edge of the next layer, or a skip edge of the layer after it, that starts at the node.
+    DanglingOutgoing { layer: usize, node: usize, edge: usize },
+    /// A normalization has a different number of nodes than its layer.
+    NormWidth { layer: usize, expected: usize, found: usize },
+    /// The scaler has a different number of features than the KAN has inputs.
+    ScalerWidth { expected: usize, found: usize },
+}
+
+impl fmt::Display for TopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TopologyError::NoLayers => write!(f, "The KAN has no layers."),
+            TopologyError::EmptyLayer { layer } => write!(f, "Layer {} has no nodes.", layer),
+            TopologyError::NodeLayer { layer, node, expected, found } => write!(f, "Node {} of layer {} has layer index {}, expected {}.", node, layer, found, expected),
+            TopologyError::EdgeLayer { layer, node, edge, expected, found } => write!(f, "Incoming edge {} of node {} of layer {} has layer index {}, expected {}.", edge, node, layer, found, expected),
+            TopologyError::EdgeEnd { layer, node, edge, found } => write!(f, "Incoming edge {} of node {} of layer {} ends at node {}.", edge, node, layer, found),
+            TopologyError::StartOutOfRange { layer, node, edge, start, width } => write!(f, "Incoming edge {} of node {} of layer {} starts at node {}, but the level it starts at has {} nodes.", edge, node, layer, start, width),
+            TopologyError::SkipInFirstLayer { node, edge } => write!(f, "Incoming edge {} of node {} of layer 0 is a skip edge, but there is no level two layers back.", edge, node),
+            TopologyError::SharedEdge { layer, node, edge } => write!(f, "Incoming edge {} of node {} of layer {} is also an incoming edge of another node.", edge, node, layer),
+            TopologyError::MissingOutgoing { layer, node, edge } => write!(f, "Incoming edge {} of node {} of layer {} is not an outgoing edge of the node it starts at.", edge, node, layer),
+            TopologyError::DanglingOutgoing { layer, node, edge } => write!(f, "Outgoing edge {} of node {} of layer {} is not an incoming edge of a later layer that starts at the node.", edge, node, layer),
+            TopologyError::NormWidth { layer, expected, found } => write!(f, "The normalization of layer {} has {} nodes, expected {}.", layer, found, expected),
+            TopologyError::ScalerWidth { expected, found } => write!(f, "The scaler has {} features, expected {}.", found, expected),
+        }
+    }
+}
+
+/// Check the graph of a KAN for structural errors that would otherwise only surface as a panic or a wrong result in a forward pass.
+/// The starts of the edges of the first layer, and of the skip edges of the second, are checked against the number of inputs of the KAN.
+///
+/// # Arguments
+///
+/// * `kan` - The KAN.
+///
+/// # Returns
+///
+/// * Every structural error, layer by layer, which is empty for a valid KAN.
+///
+/// # Example
+///
+/// ```
+/// for error in topology::validate(&kan) {
+///     println!("{}", error);
+/// }
+/// ```
+pub fn validate(kan: &KAN) -> Vec<TopologyError> {
+    let mut errors: Vec<TopologyError> = Vec::new();
+    if kan.layers.is_empty() {
+        errors.push(TopologyError::NoLayers);
+        return errors;
+    }
+    let levels: Vec<Vec<Rc<RefCell<Node>>>> = kan.layers.iter().map(|layer| layer.borrow().nodes.clone()).collect();
+    if let Some(scaler) = &kan.scaler {
+        if scaler.len() != kan.inputs {
+            errors.push(TopologyError::ScalerWidth { expected: kan.inputs, found: scaler.len() });
+        }
+    }
+    // The layer index of the nodes of every layer is the index of the first node of the first layer plus the layer
+    let first_index: Option<usize> = levels.iter().find_map(|nodes| nodes.first()).map(|node| node.borrow().layer);
+    let offset: usize = match (first_index, levels.iter().position(|nodes| !nodes.is_empty())) {
+        (Some(index), Some(l)) => index.saturating_sub(l),
+        _ => 0,
+    };
+    let mut seen: Vec<*const RefCell<Edge>> = Vec::new();
+
+    for (l, layer) in kan.layers.iter().enumerate() {
+        let layer = layer.borrow();
+        if layer.nodes.is_empty() {
+            errors.push(TopologyError::EmptyLayer { layer: l });
+        }
+        if let Some(norm) = &layer.norm {
+            if norm.len() != layer.nodes.len() {
+                errors.push(TopologyError::NormWidth { layer: l, expected: layer.nodes.len(), found: norm.len() });
+            }
+        }
+        for (j, node) in layer.nodes.iter().enumerate() {
+            let node = node.borrow();
+            if node.layer != offset + l {
+                errors.push(TopologyError::NodeLayer { layer: l, node: j, expected: offset + l, found: node.layer });
+            }
+            for (k, edge_rc) in node.incoming.iter().enumerate() {
+                let edge = edge_rc.borrow();
+                if edge.layer != node.layer {
+                    errors.push(TopologyError::EdgeLayer { layer: l, node: j, edge: k, expected: node.layer, found: edge.layer });
+                }
+                if edge.end != j {
+                    errors.push(TopologyError::EdgeEnd { layer: l, node: j, edge: k, found: edge.end });
+                }
+                if seen.contains(&Rc::as_ptr(edge_rc)) {
+                    errors.push(TopologyError::SharedEdge { layer: l, node: j, edge: k });
+                }
+                seen.push(Rc::as_ptr(edge_rc));
+                if edge.skip && l == 0 {
+                    errors.push(TopologyError::SkipInFirstLayer { node: j, edge: k });
+                    continue;
+                }
+                // The level the edge starts at, where level 0 holds the inputs and level m + 1 the nodes of layer m
+                let level: usize = if edge.skip { l - 1 } else { l };
+                let width: usize = if level == 0 { kan.inputs } else { levels[level - 1].len() };
+                if edge.start >= width {
+                    errors.push(TopologyError::StartOutOfRange { layer: l, node: j, edge: k, start: edge.start, width });
+                } else if level > 0 && !levels[level - 1][edge.start].borrow().outgoing.iter().any(|outgoing| Rc::ptr_eq(outgoing, edge_rc)) {
+                    errors.push(TopologyError::MissingOutgoing { layer: l, node: j, edge: k });
+                }
+            }
+            for (k, outgoing) in node.outgoing.iter().enumerate() {
+                let is_incoming = |later: usize, skip: bool| {
+                    levels.get(later).is_some_and(|nodes| nodes.iter().any(|next| next.borrow().incoming.iter().any(|edge| Rc::ptr_eq(edge, outgoing) && edge.borrow().skip == skip && edge.borrow().start == j)))
+                };
+                if !is_incoming(l + 1, false) && !is_incoming(l + 2, true) {
+                    errors.push(TopologyError::DanglingOutgoing { layer: l, node: j, edge: k });
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Whether two KANs have the same graph: the same number of nodes in every layer, the same aggregation and layer index of every node, and incoming edges with the same start, end, and skip flag in the same order.
+/// Both KANs must also have normalizations of the same kind in the same layers, and activations of the same type with the same number of parameters on every edge; the values of the parameters are not compared.
+///
+/// # Arguments
+///
+/// * `kan` - The first KAN.
+///
+/// * `other` - The second KAN.
+///
+/// # Returns
+///
+/// * Whether the KANs have the same topology.
+///
+/// # Example
+///
+/// ```
+/// assert!(topology::same_topology(&kan, &KAN::load_json("kan.json").unwrap()));
+/// ```
+pub fn same_topology(kan: &KAN, other: &KAN) -> bool {
+    kan.layers.len() == other.layers.len() && kan.layers.iter().zip(other.layers.iter()).all(|(layer, other)| {
+        let (layer, other) = (layer.borrow(), other.borrow());
+        layer.norm.as_ref().map(|norm| norm.kind) == other.norm.as_ref().map(|norm| norm.kind)
+            && layer.nodes.len() == other.nodes.len()
+            && layer.nodes.iter().zip(other.nodes.iter()).all(|(node, other)| {
+                let (node, other) = (node.borrow(), other.borrow());
+                node.layer == other.layer && node.aggregation == other.aggregation && node.incoming.len() == other.incoming.len()
+                    && node.incoming.iter().zip(other.incoming.iter()).all(|(edge, other)| {
+                        let (edge, other) = (edge.borrow(), other.borrow());
+                        edge.start == other.start && edge.end == other.end && edge.skip == other.skip
+                            && edge.activation.as_any().type_id() == other.activation.as_any().type_id()
+                            && edge.num_weights() == other.num_weights()
+                    })
+            })
+    })
+}
+
+/// Whether two KANs have the same topology, see `same_topology`, and parameters that differ by at most a tolerance.
+/// The parameters are the weights of every edge, the domains of the activations, the scales, shifts, and running statistics of the normalizations, and the scaler, which must be of the same kind in both KANs if either has one.
+/// The gradients, the settings of training such as dropout, and the mode are not compared.
+///
+/// # Arguments
+///
+/// * `kan` - The first KAN.
+///
+/// * `other` - The second KAN.
+///
+/// * `tol` - The largest absolute difference between two parameters that counts as equal.
+///
+/// # Returns
+///
+/// * Whether the KANs are equal up to the tolerance.
+///
+/// # Example
+///
+/// ```
+/// kan.save_json("kan.json").unwrap();
+/// assert!(topology::approx_eq(&kan, &KAN::load_json("kan.json").unwrap(), 1e-12));
+/// ```
+pub fn approx_eq(kan: &KAN, other: &KAN, tol: f64) -> bool {
+    let close = |a: &Vector, b: &Vector| a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() <= tol);
+    if !same_topology(kan, other) {
+        return false;
+    }
+    let scalers: bool = match (&kan.scaler, &other.scaler) {
+        (None, None) => true,
+        (Some(Scaler::MinMax { min, max }), Some(Scaler::MinMax { min: other_min, max: other_max })) => close(min, other_min) && close(max, other_max),
+        (Some(Scaler::ZScore { mean, std }), Some(Scaler::ZScore { mean: other_mean, std: other_std })) => close(mean, other_mean) && close(std, other_std),
+        _ => false,
+    };
+    let norms: bool = kan.layers.iter().zip(other.layers.iter()).all(|(layer, other)| match (&layer.borrow().norm, &other.borrow().norm) {
+        (Some(norm), Some(other)) => close(&norm.scale, &other.scale) && close(&norm.shift, &other.shift) && close(&norm.running_mean, &other.running_mean) && close(&norm.running_var, &other.running_var),
+        _ => true,
+    });
+    let edges: bool = kan.edges().iter().zip(other.edges().iter()).all(|(edge, other)| {
+        let (edge, other) = (edge.borrow(), other.borrow());
+        let (domain, other_domain): ((f64, f64), (f64, f64)) = (edge.activation.domain(), other.activation.domain());
+        close(&edge.weights(), &other.weights()) && (domain.0 - other_domain.0).abs() <= tol && (domain.1 - other_domain.1).abs() <= tol
+    });
+    scalers && norms && edges
+}
@@ -0,0 +1,762 @@
+use crate::data_structures::{vector::Vector, matrix::Matrix, spline::{BSpline, Shape}, edge::Edge, layer::Layer};
+use crate::error::KanError;
+use crate::kan::{KAN, share_tied_gradients};
+use crate::loss::Loss;
+use crate::optimizer::{Optimizer, OptimizerState, Lbfgs, LbfgsState};
+use crate::scheduler::Scheduler;
+use crate::callback::{Callback, TrainingState};
+use crate::datasets::dataset::DataLoader;
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Deserialize};
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Early stopping holds out the last part of the training data as a validation set and stops training when the validation loss stops improving.
+/// After training, the control points with the lowest validation loss are restored.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EarlyStopping {
+    pub validation_split: f64, // Fraction of the samples used for validation
+    pub patience: usize, // Number of epochs without improvement before stopping
+}
+
+/// Regularization adds penalty terms to the loss that is minimised by `Trainer::fit`.
+/// A coefficient of 0 disables the corresponding penalty. The reported losses do not include the penalties.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Regularization {
+    pub smoothness: f64, // Coefficient of the roughness penalty of the B-spline edges, see `BSpline::roughness`
+    pub l1: f64, // Coefficient of the L1 norm of the edge magnitudes of every layer, see `KAN::sparsity`
+    pub entropy: f64, // Coefficient of the entropy of the edge magnitudes of every layer, see `KAN::sparsity`
+}
+
+/// A pruning schedule runs the sparsification workflow of the KAN paper as one pipeline: train with sparsity regularization, prune the weak edges, optionally refine the grids, and continue training the smaller network.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PruningConfig {
+    pub sparsify_epochs: usize, // Number of epochs trained with the sparsity regularization before pruning
+    pub l1: f64, // Coefficient of the L1 term while sparsifying, see `Regularization::l1`
+    pub entropy: f64, // Coefficient of the entropy term while sparsifying, see `Regularization::entropy`
+    pub threshold: f64, // Edges with a smaller attribution score are pruned, see `KAN::prune`
+    pub grid: Option<usize>, // Number of control points of every B-spline edge after pruning, at least the current number, or None to keep the grids
+    pub finetune_epochs: usize, // Number of epochs trained after pruning, with the regularization of the trainer
+}
+
+/// A grid extension refines the grid of every B-spline edge at a given epoch of a training run, so a KAN can be trained coarse-to-fine (e.g. 5, then 10, then 20 control points).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridExtension {
+    pub epoch: usize, // Number of epochs of the run trained before the grids are refined
+    pub grid: usize, // Number of control points of every B-spline edge after the extension, see `KAN::refine_grid`
+}
+
+/// The history of a training run, with one entry per completed epoch.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct History {
+    pub train_loss: Vec<f64>,
+    pub validation_loss: Vec<f64>,
+}
+
+/// A trainer holds a KAN together with the configuration and state of its training run.
+/// It is represented as the network, the optimizer, the loss function, the learning rate schedule, the data loader that batches and shuffles the samples, the callbacks, and the number of completed epochs.
+/// The trainer struct provides methods to train the network and to save and restore the full training state, so that an interrupted run resumes exactly where it left off.
+/// Because the network is a plain model, the same KAN can be trained with different trainers.
+pub struct Trainer {
+    pub kan: KAN,
+    pub optimizer: Optimizer,
+    pub loss: Loss,
+    pub learning_rate: f64, // Base learning rate, adjusted per epoch by the scheduler
+    pub scheduler: Scheduler,
+    pub loader: DataLoader, // Splits the training samples into the batches whose gradients are averaged per update
+    pub early_stopping: Option<EarlyStopping>,
+    pub regularization: Regularization,
+    pub callbacks: Vec<Box<dyn Callback>>,
+    pub epoch: usize, // Number of completed epochs
+    optimizer_state: OptimizerState,
+}
+
+impl Trainer {
+    /// Create a new trainer for a given KAN, using stochastic gradient descent on single samples with a constant learning rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `kan` - The KAN to train.
+    ///
+    /// * `learning_rate` - A scalar representing the learning rate.
+    ///
+    /// * `loss` - The loss function.
+    ///
+    /// * `seed` - A seed for the random number generator of the data loader that shuffles the samples.
+    ///
+    /// # Returns
+    ///
+    /// * A trainer at epoch 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut trainer = Trainer::new(KAN::standard(2, 3), 0.01, Loss::MeanSquaredError, 42);
+    /// trainer.optimizer = Optimizer::adam();
+    /// trainer.loader.batch_size = 16;
+    /// ```
+    pub fn new(kan: KAN, learning_rate: f64, loss: Loss, seed: u64) -> Trainer {
+        Trainer {
+            kan,
+            optimizer: Optimizer::Sgd,
+            loss,
+            learning_rate,
+            scheduler: Scheduler::Constant,
+            loader: DataLoader::new(1, seed),
+            early_stopping: None,
+            regularization: Regularization::default(),
+            callbacks: Vec::new(),
+            epoch: 0,
+            optimizer_state: OptimizerState::default(),
+        }
+    }
+
+    /// Add a callback to the trainer.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A callback, invoked after the callbacks added before it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// trainer.add_callback(Box::new(Logger));
+    /// ```
+    pub fn add_callback(&mut self, callback: Box<dyn Callback>) {
+        self.callbacks.push(callback);
+    }
+
+    /// Get the state of the optimizer.
+    ///
+    /// # Returns
+    ///
+    /// * The step count and moment estimates of the optimizer.
+    pub fn optimizer_state(&self) -> &OptimizerState {
+        &self.optimizer_state
+    }
+
+    /// Train the KAN for a number of epochs.
+    /// In every epoch the samples are split into batches by the data loader, and the averaged gradient of every batch is applied by the optimizer with the learning rate given by the scheduler.
+    /// If early stopping is configured, the last part of the samples is held out for validation, training stops once the validation loss has not improved for `patience` epochs, and the best-seen control points are restored.
+    /// The running statistics of every batch normalization are updated from the samples of a batch before its gradient is computed, and the scale and shift of every normalization are updated with plain gradient descent, since the optimizer only tracks the edges.
+    /// In training mode, a new dropout mask is drawn for every sample with `KAN::sample_dropout` and cleared after the batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    ///
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    ///
+    /// * `epochs` - The maximum number of epochs to train for.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the training was successful, with the training and validation loss of every epoch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let history = trainer.fit(&inputs, &targets, 10).unwrap();
+    /// ```
+    pub fn fit(&mut self, inputs: &Matrix, targets: &Matrix, epochs: usize) -> Result<History, KanError> {
+        self.fit_weighted(inputs, targets, &Vector::ones(inputs.rows), epochs)
+    }
+
+    /// Train the KAN for a number of epochs like `fit`, with a weight per sample.
+    /// The gradient of every batch is the weighted mean of the gradients of its samples, and the training and validation losses are weighted means, so a sample with weight 2 counts as much as two copies of it.
+    /// A batch whose samples all have weight 0 is skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    ///
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    ///
+    /// * `weights` - A vector of finite, non-negative weights, where the ith element is the weight of the ith input-target pair.
+    ///
+    /// * `epochs` - The maximum number of epochs to train for.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the training was successful, with the training and validation loss of every epoch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // Count the samples of the rare class ten times
+    /// let weights = Vector::new(labels.iter().map(|&label| if label == 1 { 10.0 } else { 1.0 }).collect());
+    /// let history = trainer.fit_weighted(&inputs, &targets, &weights, 10).unwrap();
+    /// ```
+    pub fn fit_weighted(&mut self, inputs: &Matrix, targets: &Matrix, weights: &Vector, epochs: usize) -> Result<History, KanError> {
+        if inputs.rows != targets.rows {
+            return Err(KanError::mismatch("targets, one per input", inputs.rows, targets.rows));
+        }
+        if weights.len() != inputs.rows {
+            return Err(KanError::mismatch("weights, one per input", inputs.rows, weights.len()));
+        }
+        if weights.iter().any(|&weight| !(weight >= 0.0 && weight.is_finite())) {
+            return Err(KanError::DomainError("The weights must be finite and non-negative."));
+        }
+        if self.loader.batch_size == 0 {
+            return Err(KanError::InvalidConfig("The batch size must be positive."));
+        }
+
+        // Hold out the last samples for validation
+        let validation_size: usize = match self.early_stopping {
+            Some(config) => {
+                if !(0.0..1.0).contains(&config.validation_split) {
+                    return Err(KanError::DomainError("The validation split must be in [0, 1)."));
+                }
+                (inputs.rows as f64 * config.validation_split).ceil() as usize
+            }
+            None => 0,
+        };
+        let train_size: usize = inputs.rows - validation_size;
+        if train_size == 0 {
+            return Err(KanError::InvalidConfig("There must be at least one training sample."));
+        }
+        if self.loader.drop_last && train_size < self.loader.batch_size {
+            return Err(KanError::InvalidConfig("There must be at least one full batch of training samples when the last batch is dropped."));
+        }
+        let validation_inputs: Matrix = inputs.slice_rows(train_size..inputs.rows);
+        let validation_targets: Matrix = targets.slice_rows(train_size..targets.rows);
+        let validation_weights: Vector = Vector::new(weights.elements[train_size..].to_vec());
+        if validation_size > 0 && validation_weights.sum_stable() <= 0.0 {
+            return Err(KanError::DomainError("The validation samples must have a positive total weight."));
+        }
+        if Vector::new(weights.elements[..train_size].to_vec()).sum_stable() <= 0.0 {
+            return Err(KanError::DomainError("The training samples must have a positive total weight."));
+        }
+
+        let edges: Vec<Rc<RefCell<Edge>>> = self.kan.edges();
+        let mut callbacks: Vec<Box<dyn Callback>> = std::mem::take(&mut self.callbacks);
+        let mut history: History = History::default();
+        let mut best_loss: f64 = f64::INFINITY;
+        let mut best_snapshot: Vec<Vector> = self.kan.snapshot();
+        let mut epochs_without_improvement: usize = 0;
+        let mut result: Result<(), KanError> = Ok(());
+
+        for _ in 0..epochs {
+            let mut state: TrainingState = TrainingState::new(self.scheduler.learning_rate(self.learning_rate, self.epoch));
+            state.epoch = self.epoch;
+            for callback in callbacks.iter_mut() {
+                callback.on_epoch_start(&self.kan, &mut state);
+            }
+
+            let mut epoch_loss: f64 = 0.0;
+            let mut epoch_weight: f64 = 0.0;
+            for (batch, indices) in self.loader.indices(train_size).iter().enumerate() {
+                let batch_weight: f64 = indices.iter().map(|&i| weights[i]).sum();
+                if batch_weight == 0.0 {
+                    continue;
+                }
+                let batch_loss: f64 = match self.train_batch(&edges, inputs, targets, weights, indices, state.learning_rate) {
+                    Ok((batch_loss, gradient_norm)) => {
+                        state.gradient_norm = Some(gradient_norm);
+                        batch_loss
+                    }
+                    Err(err) => {
+                        result = Err(err);
+                        break;
+                    }
+                };
+                epoch_loss += batch_loss * batch_weight;
+                epoch_weight += batch_weight;
+                for callback in callbacks.iter_mut() {
+                    callback.on_batch_end(&self.kan, batch, batch_loss, &mut state);
+                }
+            }
+            if result.is_err() {
+                break;
+            }
+            state.train_loss = Some(epoch_loss / epoch_weight);
+            history.train_loss.push(epoch_loss / epoch_weight);
+
+            if let Some(config) = self.early_stopping {
+                if validation_size > 0 {
+                    let validation_loss: f64 = match self.kan.loss_weighted(validation_inputs.clone(), validation_targets.clone(), &validation_weights, self.loss) {
+                        Ok(validation_loss) => validation_loss,
+                        Err(err) => {
+                            result = Err(err);
+                            break;
+                        }
+                    };
+                    state.validation_loss = Some(validation_loss);
+                    history.validation_loss.push(validation_loss);
+                    if validation_loss < best_loss {
+                        best_loss = validation_loss;
+                        best_snapshot = self.kan.snapshot();
+                        epochs_without_improvement = 0;
+                    } else {
+                        epochs_without_improvement += 1;
+                        if epochs_without_improvement >= config.patience {
+                            state.stop = true;
+                        }
+                    }
+                }
+            }
+
+            for callback in callbacks.iter_mut() {
+                callback.on_epoch_end(&self.kan, &mut state);
+            }
+            self.epoch += 1;
+            if state.stop {
+                break;
+            }
+        }
+
+        self.callbacks = callbacks;
+        result?;
+
+        if validation_size > 0 {
+            self.kan.restore(&best_snapshot)?;
+        }
+
+        Ok(history)
+    }
+
+    /// Run the sparsification workflow of the KAN paper as one pipeline.
+    /// The KAN is trained with the L1 and entropy regularization of the schedule, then every edge is scored on the inputs and the edges below the threshold are pruned with `KAN::prune`.
+    /// Optionally the grids are refined with `KAN::refine_grid`, and the smaller KAN is trained further with the regularization of the trainer and a fresh optimizer state.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    ///
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    ///
+    /// * `config` - The pruning schedule.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the training was successful, with the training and validation loss of every epoch of both phases.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let config = PruningConfig { sparsify_epochs: 50, l1: 1e-2, entropy: 2e-2, threshold: 1e-2, grid: Some(10), finetune_epochs: 50 };
+    /// let history = trainer.fit_with_pruning(&inputs, &targets, config).unwrap();
+    /// ```
+    pub fn fit_with_pruning(&mut self, inputs: &Matrix, targets: &Matrix, config: PruningConfig) -> Result<History, KanError> {
+        let regularization: Regularization = self.regularization;
+        self.regularization.l1 = config.l1;
+        self.regularization.entropy = config.entropy;
+        let sparsified: Result<History, KanError> = self.fit(inputs, targets, config.sparsify_epochs);
+        self.regularization = regularization;
+        let mut history: History = sparsified?;
+
+        self.kan.update_edge_scores(inputs)?;
+        self.kan.prune(config.threshold)?;
+        if let Some(k) = config.grid {
+            self.kan.refine_grid(k);
+        }
+        // The moments of the optimizer belong to the edges and grids before pruning
+        self.optimizer_state = OptimizerState::default();
+
+        let finetuned: History = self.fit(inputs, targets, config.finetune_epochs)?;
+        history.train_loss.extend(finetuned.train_loss);
+        history.validation_loss.extend(finetuned.validation_loss);
+        Ok(history)
+    }
+
+    /// Train the KAN for a number of epochs while extending the grids of its B-spline edges at the epochs of a schedule.
+    /// At every extension the grids are refined with `KAN::refine_grid`, which preserves the learned functions, and the moments of the optimizer are carried over to the refined grids so training continues without a restart.
+    /// Early stopping applies to every stage between two extensions separately.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    ///
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    ///
+    /// * `epochs` - The total number of epochs to train for.
+    ///
+    /// * `schedule` - The grid extensions in order of increasing epoch, at most `epochs`, and increasing number of control points, at least the number of every B-spline edge.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the training was successful, with the training and validation loss of every epoch of all stages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let schedule = [GridExtension { epoch: 50, grid: 10 }, GridExtension { epoch: 100, grid: 20 }];
+    /// let history = trainer.fit_with_grid_extension(&inputs, &targets, 150, &schedule).unwrap();
+    /// ```
+    pub fn fit_with_grid_extension(&mut self, inputs: &Matrix, targets: &Matrix, epochs: usize, schedule: &[GridExtension]) -> Result<History, KanError> {
+        let num_points: usize = self.kan.edges().iter().filter_map(|edge| edge.borrow().activation.downcast_ref::<BSpline>().map(|spline| spline.control_points.len())).max().unwrap_or(0);
+        let mut previous: Option<&GridExtension> = None;
+        for extension in schedule.iter() {
+            if extension.epoch > epochs || previous.is_some_and(|previous| extension.epoch <= previous.epoch) {
+                return Err(KanError::InvalidConfig("The epochs of the grid extensions must be increasing and at most the number of epochs."));
+            }
+            if extension.grid < num_points || previous.is_some_and(|previous| extension.grid <= previous.grid) {
+                return Err(KanError::InvalidConfig("The grids of the extensions must be increasing and at least the number of control points of every B-spline edge."));
+            }
+            previous = Some(extension);
+        }
+
+        let mut history: History = History::default();
+        let mut trained: usize = 0;
+        for extension in schedule.iter() {
+            let stage: History = self.fit(inputs, targets, extension.epoch - trained)?;
+            history.train_loss.extend(stage.train_loss);
+            history.validation_loss.extend(stage.validation_loss);
+            self.extend_grids(extension.grid);
+            trained = extension.epoch;
+        }
+        let stage: History = self.fit(inputs, targets, epochs - trained)?;
+        history.train_loss.extend(stage.train_loss);
+        history.validation_loss.extend(stage.validation_loss);
+        Ok(history)
+    }
+
+    /// Train the KAN with L-BFGS on the full set of samples for a number of iterations, as an alternative to `fit` for small networks.
+    /// The weights of all edges are optimized as one flat vector, in the layout of `KAN::get_flat_params`, and every iteration minimises the mean loss plus the regularization of the trainer.
+    /// Frozen edges are kept fixed and tied edges share their gradients, while the learning rate, scheduler, data loader, early stopping, and the per-edge learning rate multipliers are not used.
+    /// The scale and shift and the running statistics of every normalization are kept fixed, and no dropout masks are drawn, so the objective is the same in every iteration.
+    /// Every iteration counts as an epoch for the callbacks and the epoch counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    ///
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    ///
+    /// * `iterations` - The maximum number of iterations.
+    ///
+    /// * `lbfgs` - The configuration of L-BFGS.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the training was successful, with the training loss after every iteration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let history = trainer.fit_lbfgs(&inputs, &targets, 50, Lbfgs::default()).unwrap();
+    /// ```
+    pub fn fit_lbfgs(&mut self, inputs: &Matrix, targets: &Matrix, iterations: usize, lbfgs: Lbfgs) -> Result<History, KanError> {
+        if inputs.rows != targets.rows {
+            return Err(KanError::mismatch("targets, one per input", inputs.rows, targets.rows));
+        }
+        if inputs.rows == 0 {
+            return Err(KanError::InvalidConfig("There must be at least one training sample."));
+        }
+        let edges: Vec<Rc<RefCell<Edge>>> = self.kan.edges();
+        if edges.iter().any(|edge| edge.borrow().activation.downcast_ref::<BSpline>().is_some_and(|spline| spline.shape != Shape::None)) {
+            return Err(KanError::Unsupported("L-BFGS does not support shape-constrained B-splines."));
+        }
+        self.kan.clear_dropout();
+
+        let mut callbacks: Vec<Box<dyn Callback>> = std::mem::take(&mut self.callbacks);
+        let mut history: History = History::default();
+        let mut lbfgs_state: LbfgsState = LbfgsState::default();
+        let mut params: Vector = self.kan.get_flat_params();
+        let mut result: Result<(), KanError> = Ok(());
+
+        for _ in 0..iterations {
+            let mut state: TrainingState = TrainingState::new(self.learning_rate);
+            state.epoch = self.epoch;
+            for callback in callbacks.iter_mut() {
+                callback.on_epoch_start(&self.kan, &mut state);
+            }
+
+            let loss: Result<f64, KanError> = lbfgs.step(&mut lbfgs_state, &mut params, |params| self.objective(&edges, inputs, targets, params));
+            // The line search leaves the weights of its last trial in the KAN
+            let restored: Result<(), KanError> = self.kan.set_flat_params(&params);
+            let loss: f64 = match loss.and_then(|loss| restored.map(|_| loss)) {
+                Ok(loss) => loss,
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            };
+            state.train_loss = Some(loss);
+            state.gradient_norm = Some(lbfgs_state.gradient.norm(2.0));
+            history.train_loss.push(loss);
+
+            for callback in callbacks.iter_mut() {
+                callback.on_epoch_end(&self.kan, &mut state);
+            }
+            self.epoch += 1;
+            if state.stop {
+                break;
+            }
+        }
+
+        self.callbacks = callbacks;
+        result?;
+        Ok(history)
+    }
+
+    /// Compute the mean loss of the KAN on a list of input-target pairs with the loss function of the trainer.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - A matrix where the ith row represents the input values to the first layer for the ith input-target pair.
+    ///
+    /// * `targets` - A matrix where the ith row represents the target values for the ith input-target pair.
+    ///
+    /// # Returns
+    ///
+    /// * A result with a scalar representing the mean loss, or an error as in `KAN::loss_with`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let loss = trainer.evaluate(&inputs, &targets).unwrap();
+    /// ```
+    pub fn evaluate(&self, inputs: &Matrix, targets: &Matrix) -> Result<f64, KanError> {
+        self.kan.loss_with(inputs.clone(), targets.clone(), self.loss)
+    }
+
+    /// Run the backward pass for every sample of a batch, take the weighted mean of the gradients, and apply one optimizer step, returning the loss and the norm of the gradient.
+    fn train_batch(&mut self, edges: &[Rc<RefCell<Edge>>], inputs: &Matrix, targets: &Matrix, weights: &Vector, indices: &[usize], learning_rate: f64) -> Result<(f64, f64), KanError> {
+        let batch: Matrix = Matrix::new(indices.iter().map(|&i| inputs.row(i)).collect());
+        self.kan.update_norm_statistics(&batch)?;
+        let norm_layers: Vec<Rc<RefCell<Layer>>> = self.kan.layers.iter().filter(|layer| layer.borrow().norm.is_some()).cloned().collect();
+        let mut norm_gradients: Vec<(Vector, Vector)> = norm_layers.iter().map(|layer| {
+            let width: usize = layer.borrow().nodes.len();
+            (Vector::zeros(width), Vector::zeros(width))
+        }).collect();
+
+        let mut gradients: Vec<Vector> = edges.iter().map(|edge| Vector::zeros(edge.borrow().gradient.len())).collect();
+        let mut batch_loss: f64 = 0.0;
+        let mut batch_weight: f64 = 0.0;
+        for &i in indices.iter() {
+            if self.kan.training {
+                self.kan.sample_dropout(&mut self.loader.rng);
+            }
+            batch_loss += weights[i] * self.kan.backward_features(&inputs.row(i), &targets.row(i), self.loss)?;
+            batch_weight += weights[i];
+            for (gradient, edge) in gradients.iter_mut().zip(edges.iter()) {
+                *gradient = &*gradient + &(&edge.borrow().gradient * weights[i]);
+            }
+            for ((scale_gradient, shift_gradient), layer) in norm_gradients.iter_mut().zip(norm_layers.iter()) {
+                if let Some(norm) = &layer.borrow().norm {
+                    scale_gradient.axpy(weights[i], &norm.scale_gradient);
+                    shift_gradient.axpy(weights[i], &norm.shift_gradient);
+                }
+            }
+        }
+        self.kan.clear_dropout();
+        let scale: f64 = 1.0 / batch_weight;
+        let mut gradients: Vec<Vector> = gradients.iter().map(|gradient| gradient * scale).collect();
+        self.add_regularization_gradients(edges, &batch, &mut gradients)?;
+        share_tied_gradients(edges, &mut gradients);
+        let gradient_norm: f64 = gradients.iter().map(|gradient| gradient.dot(gradient)).sum::<f64>().sqrt();
+        #[cfg(feature = "instrument")]
+        let _span = crate::instrument::span(crate::instrument::Phase::Update);
+        self.optimizer.step(&mut self.optimizer_state, edges, &gradients, learning_rate)?;
+        for ((scale_gradient, shift_gradient), layer) in norm_gradients.iter().zip(norm_layers.iter()) {
+            if let Some(norm) = &mut layer.borrow_mut().norm {
+                norm.step(&(scale_gradient * scale), &(shift_gradient * scale), learning_rate);
+            }
+        }
+        Ok((batch_loss * scale, gradient_norm))
+    }
+
+    /// Add the gradients of the regularization penalties over a batch of samples to the gradients of the edges.
+    fn add_regularization_gradients(&self, edges: &[Rc<RefCell<Edge>>], batch: &Matrix, gradients: &mut [Vector]) -> Result<(), KanError> {
+        if self.regularization.l1 != 0.0 || self.regularization.entropy != 0.0 {
+            let penalties: Vec<Vector> = self.kan.sparsity_gradients(batch, self.regularization.l1, self.regularization.entropy)?;
+            for (gradient, penalty) in gradients.iter_mut().zip(penalties.iter()) {
+                *gradient = &*gradient + penalty;
+            }
+        }
+        if self.regularization.smoothness != 0.0 {
+            for (gradient, edge) in gradients.iter_mut().zip(edges.iter()) {
+                if let Some(spline) = edge.borrow().activation.downcast_ref::<BSpline>() {
+                    let mut penalty: Vector = &spline.roughness_gradient() * self.regularization.smoothness;
+                    // The base and spline weights are not penalised
+                    penalty.push(0.0);
+                    penalty.push(0.0);
+                    *gradient = &*gradient + &penalty;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the weights of the edges to a flat vector of parameters, and compute the regularized mean loss over all samples and its gradient in the same layout.
+    fn objective(&self, edges: &[Rc<RefCell<Edge>>], inputs: &Matrix, targets: &Matrix, params: &Vector) -> Result<(f64, Vector), KanError> {
+        self.kan.set_flat_params(params)?;
+        let mut gradients: Vec<Vector> = edges.iter().map(|edge| Vector::zeros(edge.borrow().num_weights())).collect();
+        let mut loss: f64 = 0.0;
+        for i in 0..inputs.rows {
+            loss += self.kan.backward_features(&inputs.row(i), &targets.row(i), self.loss)?;
+            for (gradient, edge) in gradients.iter_mut().zip(edges.iter()) {
+                gradient.axpy(1.0, &edge.borrow().gradient);
+            }
+        }
+        for edge in edges.iter() {
+            edge.borrow_mut().zero_grad();
+        }
+        let scale: f64 = 1.0 / inputs.rows as f64;
+        loss *= scale;
+        for gradient in gradients.iter_mut() {
+            *gradient *= scale;
+        }
+        if self.regularization.l1 != 0.0 || self.regularization.entropy != 0.0 {
+            loss += self.kan.sparsity(inputs, self.regularization.l1, self.regularization.entropy)?;
+        }
+        loss += self.regularization.smoothness * self.kan.roughness();
+        self.add_regularization_gradients(edges, inputs, &mut gradients)?;
+        share_tied_gradients(edges, &mut gradients);
+        for (gradient, edge) in gradients.iter_mut().zip(edges.iter()) {
+            if edge.borrow().frozen {
+                *gradient = Vector::zeros(gradient.len());
+            }
+        }
+        Ok((loss, Vector::new(gradients.into_iter().flat_map(|gradient| gradient.elements).collect())))
+    }
+
+    /// Refine the grid of every B-spline edge to k control points and map the moments of the optimizer of its control points onto the refined grid.
+    fn extend_grids(&mut self, k: usize) {
+        let edges: Vec<Rc<RefCell<Edge>>> = self.kan.edges();
+        let state: &mut OptimizerState = &mut self.optimizer_state;
+        if state.first_moments.len() == edges.len() {
+            for (i, edge) in edges.iter().enumerate() {
+                if let Some(spline) = edge.borrow().activation.downcast_ref::<BSpline>() {
+                    if state.first_moments[i].len() == spline.control_points.len() + 2 {
+                        state.first_moments[i] = refine_moments(spline, &state.first_moments[i], k, false);
+                        state.second_moments[i] = refine_moments(spline, &state.second_moments[i], k, true);
+                    }
+                }
+            }
+        }
+        self.kan.refine_grid(k);
+    }
+
+    /// Save the full training state (network, optimizer and its moments, loss function, learning rate schedule, data loader and its random number generator state, regularization, and epoch counter) to a file.
+    /// Callbacks are not saved and must be added again after loading.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the checkpoint file.
+    ///
+    /// # Returns
+    ///
+    /// * A result indicating whether the checkpoint was written successfully.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// trainer.save_checkpoint("checkpoint.json").unwrap();
+    /// ```
+    pub fn save_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<(), KanError> {
+        let checkpoint: CheckpointData = CheckpointData {
+            version: CHECKPOINT_VERSION,
+            epoch: self.epoch,
+            learning_rate: self.learning_rate,
+            loss: self.loss,
+            optimizer: self.optimizer,
+            scheduler: self.scheduler,
+            batch_size: self.loader.batch_size,
+            shuffle: self.loader.shuffle,
+            drop_last: self.loader.drop_last,
+            rng: self.loader.rng.clone(),
+            early_stopping: self.early_stopping,
+            regularization: self.regularization,
+            optimizer_state: self.optimizer_state.clone(),
+            kan: self.kan.clone(),
+        };
+        let json: String = serde_json::to_string(&checkpoint).map_err(|_| KanError::Unsupported("The activation of an edge cannot be written to a checkpoint."))?;
+        fs::write(path, json).map_err(|_| KanError::Io("Failed to write the checkpoint file."))
+    }
+
+    /// Load a trainer from a checkpoint file written by `save_checkpoint`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the checkpoint file.
+    ///
+    /// # Returns
+    ///
+    /// * A result with the restored trainer, or an error if the file cannot be read, is malformed, or has another version.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut trainer = Trainer::load_checkpoint("checkpoint.json").unwrap();
+    /// trainer.fit(&inputs, &targets, 10).unwrap();
+    /// ```
+    pub fn load_checkpoint<P: AsRef<Path>>(path: P) -> Result<Trainer, KanError> {
+        let json: String = fs::read_to_string(path).map_err(|_| KanError::Io("Failed to read the checkpoint file."))?;
+        let checkpoint: CheckpointData = serde_json::from_str(&json).map_err(|_| KanError::Format("The file is not a checkpoint."))?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(KanError::Format("The checkpoint was written in an unsupported version of the format."));
+        }
+
+        let loader: DataLoader = DataLoader { batch_size: checkpoint.batch_size, shuffle: checkpoint.shuffle, drop_last: checkpoint.drop_last, rng: checkpoint.rng };
+        Ok(Trainer {
+            kan: checkpoint.kan,
+            optimizer: checkpoint.optimizer,
+            loss: checkpoint.loss,
+            learning_rate: checkpoint.learning_rate,
+            scheduler: checkpoint.scheduler,
+            loader,
+            early_stopping: checkpoint.early_stopping,
+            regularization: checkpoint.regularization,
+            callbacks: Vec::new(),
+            epoch: checkpoint.epoch,
+            optimizer_state: checkpoint.optimizer_state,
+        })
+    }
+}
+
+impl std::fmt::Debug for Trainer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Trainer")
+            .field("kan", &self.kan)
+            .field("optimizer", &self.optimizer)
+            .field("loss", &self.loss)
+            .field("learning_rate", &self.learning_rate)
+            .field("scheduler", &self.scheduler)
+            .field("loader", &self.loader)
+            .field("early_stopping", &self.early_stopping)
+            .field("regularization", &self.regularization)
+            .field("callbacks", &self.callbacks.len())
+            .field("epoch", &self.epoch)
+            .field("optimizer_state", &self.optimizer_state)
+            .finish()
+    }
+}
+
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// The serialized form of a trainer: the version of the format, the training configuration and state, and the KAN, serialized as in `KAN::save`.
+#[derive(Serialize, Deserialize)]
+struct CheckpointData {
+    version: u32,
+    epoch: usize,
+    learning_rate: f64,
+    loss: Loss,
+    optimizer: Optimizer,
+    scheduler: Scheduler,
+    batch_size: usize,
+    shuffle: bool,
+    drop_last: bool,
+    rng: ChaCha8Rng, // State of the shuffle of the data loader, so a resumed run visits the samples in the same order
+    early_stopping: Option<EarlyStopping>,
+    regularization: Regularization,
+    optimizer_state: OptimizerState,
+    kan: KAN,
+}
+
+/// Map the moments of the weights of a B-spline edge onto its grid refined to k control points.
+/// The refinement is linear in the control points, so the moments of the control points are refined like the control points of a copy of the spline.
+/// Second moments are refined as their square roots so they stay non-negative, and the moments of the base and spline weights are kept.
+fn refine_moments(spline: &BSpline, moments: &Vector, k: usize, second: bool) -> Vector {
+    let n: usize = spline.control_points.len();
+    let control_points: Vector = Vector::new(moments.elements[..n].iter().map(|&m| if second { m.sqrt() } else { m }).collect());
+    let mut copy: BSpline = BSpline { control_points, memo: Default::default(), shape: Shape::None, weights: None, ..spline.clone() };
+    copy.refine(k);
+    let mut refined: Vector = if second { copy.control_points.map(|m| m * m) } else { copy.control_points };
+    refined.push(moments[n]);
+    refined.push(moments[n + 1]);
+    refined
+}
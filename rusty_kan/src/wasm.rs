@@ -0,0 +1,89 @@
+use crate::compiled::CompiledKAN;
+use crate::data_structures::vector::Vector;
+use crate::kan::KAN;
+use wasm_bindgen::prelude::*;
+
+/// A KAN loaded for inference in the browser through the JavaScript interface of the `wasm` feature, built with `wasm-pack build --target web -- --features wasm`.
+#[wasm_bindgen]
+pub struct WasmKAN {
+    compiled: CompiledKAN,
+}
+
+#[wasm_bindgen]
+impl WasmKAN {
+    /// Load a KAN from the contents of a JSON file written by `KAN::save_json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The contents of the JSON file.
+    ///
+    /// # Returns
+    ///
+    /// * The model, or an error if the JSON does not describe a KAN.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// const model = WasmKAN.fromJson(await (await fetch("model.json")).text());
+    /// ```
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<WasmKAN, JsError> {
+        let kan: KAN = serde_json::from_str(json).map_err(|_| JsError::new("The JSON does not describe a KAN."))?;
+        Ok(WasmKAN { compiled: kan.compile() })
+    }
+
+    /// The number of input values of the model.
+    #[wasm_bindgen(getter, js_name = numInputs)]
+    pub fn num_inputs(&self) -> usize {
+        self.compiled.num_inputs()
+    }
+
+    /// The number of output values of the model.
+    #[wasm_bindgen(getter, js_name = numOutputs)]
+    pub fn num_outputs(&self) -> usize {
+        self.compiled.num_outputs()
+    }
+
+    /// Compute the output values of the model for one sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input values, as a `Float64Array` or an array of numbers.
+    ///
+    /// # Returns
+    ///
+    /// * The output values as a `Float64Array`, or an error if the number of input values does not match the model.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// const output = model.forward(new Float64Array([0.2, 0.7]));
+    /// ```
+    pub fn forward(&self, input: &[f64]) -> Result<Vec<f64>, JsError> {
+        Ok(self.compiled.forward(&self.input(input)?).map_err(|err| JsError::new(&err.to_string()))?.elements)
+    }
+
+    /// Compute the index of the most likely class of the model for one sample.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input values, as a `Float64Array` or an array of numbers.
+    ///
+    /// # Returns
+    ///
+    /// * The index of the output with the largest value, or an error if the number of input values does not match the model.
+    #[wasm_bindgen(js_name = predictClass)]
+    pub fn predict_class(&self, input: &[f64]) -> Result<usize, JsError> {
+        self.compiled.predict_class(&self.input(input)?).map_err(|err| JsError::new(&err.to_string()))
+    }
+}
+
+impl WasmKAN {
+    /// Check the number of input values before the evaluation, as a panic aborts the WebAssembly module.
+    fn input(&self, input: &[f64]) -> Result<Vector, JsError> {
+        if input.len() != self.compiled.num_inputs() {
+            return Err(JsError::new("The number of input values does not match the model."));
+        }
+        Ok(Vector::new(input.to_vec()))
+    }
+}